@@ -22,10 +22,11 @@ use bevy_xpbd_3d::prelude::*;
 // NOTE: bevy_flycam REMOVED - was causing noclip/flying
 // All movement is now physics-based via bevy_xpbd_3d
 
-use oroboros_procedural::{WorldManager, WorldManagerConfig, WorldSeed, ChunkCoord, CHUNK_SIZE};
+use oroboros::{BlockPalette, core::BlockRegistry};
+use oroboros_procedural::{Block, WorldManager, WorldManagerConfig, WorldSeed, ChunkCoord, CHUNK_SIZE};
 
 use std::collections::{HashMap, HashSet};
-use std::sync::Mutex;
+use std::sync::{Mutex, OnceLock};
 
 // =============================================================================
 // MULTIPLAYER NETWORKING (WASM WebSocket)
@@ -887,117 +888,14 @@ pub struct RenderedChunks {
 }
 
 // =============================================================================
-// BLOCK TYPES (from backend)
+// BLOCK LOOKS (from the block registry)
 // =============================================================================
 
-/// Block types for BRUTALIST MEGA-STRUCTURE
-/// Inspired by: Alice in Borderland + Squid Game
-/// Palette: Concrete Grey, Neon Red, Ice White
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-#[repr(u8)]
-enum BlockType {
-    Air = 0,
-    /// Floor - Matte Grey Concrete
-    ConcreteFloor = 1,
-    /// Walls - Darker Concrete  
-    ConcreteWall = 2,
-    /// Hazard - Glowing Red Neon (DEATH)
-    HazardNeon = 3,
-    /// Goal - Ice White Laser Floor
-    GoalZone = 4,
-    /// Bedrock - Indestructible Black
-    Bedrock = 5,
-    /// Gold Loot - Contrasting Gold
-    GoldLoot = 6,
-    /// Bridge/Beam - Weathered Metal
-    MetalBridge = 7,
-}
-
-impl BlockType {
-    fn from_id(id: u16) -> Self {
-        match id {
-            0 => BlockType::Air,
-            1 => BlockType::ConcreteFloor,
-            2 => BlockType::ConcreteWall,
-            3 => BlockType::HazardNeon,
-            4 => BlockType::GoalZone,
-            5 => BlockType::Bedrock,
-            6 => BlockType::GoldLoot,
-            7 => BlockType::MetalBridge,
-            _ => BlockType::ConcreteWall,
-        }
-    }
-    
-    /// Returns RGBA vertex color for BRUTALIST PALETTE
-    /// Grey concrete, red neon hazards, white goals
-    fn vertex_color(&self) -> [f32; 4] {
-        match self {
-            // ID 0: Air -> Invisible
-            BlockType::Air => [0.0, 0.0, 0.0, 0.0],
-            
-            // ID 1: Concrete Floor -> #505050 Matte Grey
-            // The arena floor - visible but muted
-            BlockType::ConcreteFloor => [0.314, 0.314, 0.314, 1.0],
-            
-            // ID 2: Concrete Wall -> #303030 Darker Grey
-            // Massive brutalist walls
-            BlockType::ConcreteWall => [0.188, 0.188, 0.188, 1.0],
-            
-            // ID 3: Hazard Neon -> #FF0000 GLOWING RED
-            // HDR emission for bloom - DEATH ZONE
-            BlockType::HazardNeon => [5.0, 0.0, 0.0, 1.0],
-            
-            // ID 4: Goal Zone -> #E0FFFF Ice White Glow
-            // The extraction point - safety
-            BlockType::GoalZone => [4.0, 5.0, 5.0, 1.0],
-            
-            // ID 5: Bedrock -> #101010 Near Black
-            // Indestructible foundation
-            BlockType::Bedrock => [0.063, 0.063, 0.063, 1.0],
-            
-            // ID 6: Gold Loot -> Bright Gold (contrasts grey)
-            // Valuable collectible
-            BlockType::GoldLoot => [4.0, 3.0, 0.3, 1.0],
-            
-            // ID 7: Metal Bridge -> #404045 Weathered Steel
-            // Thin walkways connecting platforms
-            BlockType::MetalBridge => [0.25, 0.25, 0.27, 1.0],
-        }
-    }
-    
-    /// Returns PBR Metallic value
-    #[allow(dead_code)]
-    fn metallic(&self) -> f32 {
-        match self {
-            BlockType::Air => 0.0,
-            BlockType::ConcreteFloor => 0.0,  // Concrete is not metal
-            BlockType::ConcreteWall => 0.0,   // Concrete is not metal
-            BlockType::HazardNeon => 0.1,     // Slight metallic sheen
-            BlockType::GoalZone => 0.2,       // Slight metallic
-            BlockType::Bedrock => 0.0,        // Matte black
-            BlockType::GoldLoot => 1.0,       // Pure metal
-            BlockType::MetalBridge => 0.9,    // Steel
-        }
-    }
-    
-    /// Returns PBR Roughness value (0.0 = mirror, 1.0 = matte)
-    #[allow(dead_code)]
-    fn roughness(&self) -> f32 {
-        match self {
-            BlockType::Air => 1.0,
-            BlockType::ConcreteFloor => 0.9,  // Very matte concrete
-            BlockType::ConcreteWall => 1.0,   // Completely matte
-            BlockType::HazardNeon => 0.1,     // Shiny neon
-            BlockType::GoalZone => 0.2,       // Glossy
-            BlockType::Bedrock => 0.95,       // Almost matte
-            BlockType::GoldLoot => 0.3,       // Shiny gold
-            BlockType::MetalBridge => 0.6,    // Worn metal
-        }
-    }
-    
-    fn is_solid(&self) -> bool {
-        !matches!(self, BlockType::Air)
-    }
+/// How each block is drawn, from `blocks.toml` and the shipped materials
+/// (BRUTALIST palette: concrete grey, neon red hazards, ice white goals).
+fn palette() -> &'static BlockPalette {
+    static PALETTE: OnceLock<BlockPalette> = OnceLock::new();
+    PALETTE.get_or_init(|| BlockPalette::new(&BlockRegistry::shipped()).expect("shipped blocks name unknown materials"))
 }
 
 // =============================================================================
@@ -1038,7 +936,7 @@ fn generate_chunk_mesh(
     let mut colors: Vec<[f32; 4]> = Vec::new();
     
     // Color tracking
-    let mut color_counts: HashMap<u8, u32> = HashMap::new();
+    let mut color_counts: HashMap<u16, u32> = HashMap::new();
     let mut solid_blocks_found = 0u32;
     let mut faces_added = 0u32;
     
@@ -1052,21 +950,19 @@ fn generate_chunk_mesh(
                 let world_x = chunk_world_x + local_x;
                 let world_z = chunk_world_z + local_z;
                 
-                let block = match inner.world_manager.get_block(world_x, local_y, world_z) {
-                    Some(b) => BlockType::from_id(b.id),
-                    None => BlockType::Air,
-                };
+                let id = inner.world_manager.get_block(world_x, local_y, world_z).map_or(0, |b| b.id);
+                let block = palette().get(id);
                 
-                if !block.is_solid() {
+                if !block.solid {
                     continue;
                 }
                 
                 solid_blocks_found += 1;
-                *color_counts.entry(block as u8).or_insert(0) += 1;
+                *color_counts.entry(id).or_insert(0) += 1;
                 
                 let pos = [world_x as f32, local_y as f32, world_z as f32];
                 // VERTEX COLORING: Get color for this block
-                let block_color = block.vertex_color();
+                let block_color = block.color;
                 
                 // Check each face
                 for face in 0..6 {
@@ -1132,7 +1028,7 @@ fn is_solid_at(world: &WorldManager, x: i32, y: i32, z: i32) -> bool {
         return false;
     }
     match world.get_block(x, y, z) {
-        Some(b) => BlockType::from_id(b.id).is_solid(),
+        Some(b) => palette().is_solid(b.id),
         None => false, // Unloaded = draw face
     }
 }
@@ -1592,7 +1488,7 @@ fn find_ground_height(world: &WorldManager, x: i32, z: i32) -> i32 {
     // Search from top down to find first solid block
     for y in (0..128).rev() {
         if let Some(block) = world.get_block(x, y, z) {
-            if BlockType::from_id(block.id).is_solid() {
+            if palette().is_solid(block.id) {
                 return y + 1;
             }
         }
@@ -1643,10 +1539,10 @@ fn handle_mining(
             Color::rgba(1.0, 1.0, 0.0, 0.5),
         );
         
-        // Left Click: Break block (set to Air - ID 0)
+        // Left Click: Break block (set to Air)
         if mouse_button.just_pressed(MouseButton::Left) {
             let mut inner = bridge.inner.lock().unwrap();
-            if inner.world_manager.set_block(hit_pos.0, hit_pos.1 as i32, hit_pos.2, 0) {
+            if inner.world_manager.set_block(hit_pos.0, hit_pos.1 as i32, hit_pos.2, Block::AIR.id) {
                 info!("Block broken at ({}, {}, {})", hit_pos.0, hit_pos.1, hit_pos.2);
                 // Mark chunk as dirty for re-meshing
                 let chunk_coord = ChunkCoord::new(
@@ -1657,7 +1553,7 @@ fn handle_mining(
             }
         }
         
-        // Right Click: Place block (Gold - ID 3)
+        // Right Click: Place block (Hazard Neon)
         if mouse_button.just_pressed(MouseButton::Right) {
             // Place at adjacent position (using normal)
             let place_pos = (
@@ -1667,7 +1563,7 @@ fn handle_mining(
             );
             
             let mut inner = bridge.inner.lock().unwrap();
-            if inner.world_manager.set_block(place_pos.0, place_pos.1 as i32, place_pos.2, 3) {
+            if inner.world_manager.set_block(place_pos.0, place_pos.1 as i32, place_pos.2, Block::HAZARD_NEON.id) {
                 info!("Block placed at ({}, {}, {})", place_pos.0, place_pos.1, place_pos.2);
                 // Mark chunk as dirty for re-meshing
                 let chunk_coord = ChunkCoord::new(
@@ -1729,7 +1625,7 @@ fn voxel_raycast(
         // Check current voxel
         if y >= 0 && y < 256 {
             if let Some(block) = inner.world_manager.get_block(x, y, z) {
-                if BlockType::from_id(block.id).is_solid() {
+                if palette().is_solid(block.id) {
                     return Some(((x, y as usize, z), last_normal));
                 }
             }
@@ -31,7 +31,7 @@ use std::io::Write;
 
 
 // Procedural generation - Infinite terrain
-use oroboros_procedural::{WorldManager, WorldManagerConfig, WorldSeed, ChunkCoord, CHUNK_SIZE};
+use oroboros_procedural::{Block, WorldManager, WorldManagerConfig, WorldSeed, ChunkCoord, CHUNK_SIZE};
 
 // =============================================================================
 // ASYNC MESH WORKER SYSTEM - Reserved for future optimization
@@ -70,10 +70,10 @@ const MOUSE_SENSITIVITY: f32 = 0.1;
 enum BlockType {
     Air = 0,
     Grass = 1,
-    Dirt = 2,
-    Stone = 3,
-    Sand = 4,
-    Bedrock = 5,
+    Dirt = 3,
+    Stone = 2,
+    Sand = 11,
+    Bedrock = 7,
     Neon = 255,
 }
 
@@ -103,12 +103,12 @@ impl BlockType {
     
     fn from_id(id: u16) -> Self {
         match id {
-            0 => BlockType::Air,
-            1 => BlockType::Grass,
-            2 => BlockType::Dirt,
-            3 => BlockType::Stone,
-            4 => BlockType::Sand,
-            5 => BlockType::Bedrock,
+            id if id == Block::AIR.id => BlockType::Air,
+            id if id == Block::GRASS.id => BlockType::Grass,
+            id if id == Block::DIRT.id => BlockType::Dirt,
+            id if id == Block::STONE.id => BlockType::Stone,
+            id if id == Block::SAND.id => BlockType::Sand,
+            id if id == Block::BEDROCK.id => BlockType::Bedrock,
             _ => BlockType::Stone, // Default for unknown blocks
         }
     }
@@ -24,7 +24,8 @@ use std::sync::Arc;
 use std::time::Instant;
 use std::collections::HashSet;
 
-// Physics from Unit 4
+// Physics from Unit 4, colliding with blocks from the block registry
+use oroboros::core::BlockRegistry;
 use oroboros::physics::{
     CharacterController, VoxelWorld,
     get_look_direction, generate_wireframe_cube, RaycastHit,
@@ -53,7 +54,7 @@ impl GameWorld {
     fn new() -> Self {
         Self {
             removed_blocks: HashSet::new(),
-            voxel_world: VoxelWorld::new(),
+            voxel_world: VoxelWorld::terrain(BlockRegistry::shipped()),
         }
     }
 
@@ -13,7 +13,7 @@
 use std::time::Instant;
 
 use oroboros::{
-    core::{Position, Velocity, EntityId, DoubleBufferedWorld},
+    core::{BlockRegistry, Position, Velocity, EntityId, DoubleBufferedWorld},
     economy::{EconomySystem, LootTable, Rarity, BlockchainSalt},
     events::{EventBus, GameEvent},
 };
@@ -77,8 +77,8 @@ fn run_golden_path_iteration(
     // =========================================================================
     let economy_start = Instant::now();
 
-    // Diamond ore block = ID 56 (Minecraft convention)
-    let block_id = 56u32;
+    // Diamond ore block
+    let block_id = BlockRegistry::shipped().id("diamond_ore").expect("diamond_ore is a shipped block");
     let player_level = 50u8;
     let pickaxe_tier = 4u8; // Diamond pickaxe
     let weather_seed = 12345u32;
@@ -126,7 +126,7 @@ fn run_golden_path_iteration(
             let _ = sender.send(GameEvent::BlockBroken {
                 entity_id: EntityId::new(player_id as u32, 0),
                 block_pos,
-                block_type: block_id,
+                block_type: u32::from(block_id),
                 tool_tier: pickaxe_tier,
             });
 
@@ -174,11 +174,13 @@ fn create_test_economy() -> EconomySystem {
             .as_nanos()
     ));
 
-    let mut economy = EconomySystem::new(&path).expect("Failed to create economy system");
+    let blocks = BlockRegistry::shipped();
+    let diamond_ore = blocks.id("diamond_ore").expect("diamond_ore is a shipped block");
+    let mut economy = EconomySystem::new(&path, blocks).expect("Failed to create economy system");
 
-    // Diamond ore loot table (block ID 56)
+    // Diamond ore loot table
     let diamond_table = LootTable {
-        block_id: 56,
+        block_id: u32::from(diamond_ore),
         block_rarity: Rarity::Rare,
         entries: vec![
             LootEntry {
@@ -25,11 +25,15 @@
 #[cfg(feature = "rendering")]
 compile_error!("SERVER MUST NOT HAVE RENDERING FEATURE! You're pulling GPU dependencies onto the German server!");
 
+use oroboros::{check_block_references, check_dungeon_references};
 use oroboros::core::{DoubleBufferedWorld, Position, Velocity};
+use oroboros::economy::LootCalculator;
+use oroboros_procedural::{ChunkGenerator, CityConfig, DungeonConfig, WorldSeed};
 use oroboros_shared::{SERVER_BIND, TICK_RATE, MAX_CLIENTS};
 
 use std::collections::HashMap;
 use std::net::{SocketAddr, UdpSocket};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use std::io::ErrorKind;
 
@@ -89,7 +93,23 @@ fn main() {
     println!("   ✓ Unit 1 (Core): DoubleBufferedWorld ready (1M entities)");
 
     // Unit 3: Economy
-    println!("   ✓ Unit 3 (Veridia): Economy systems ready");
+    let mut loot = LootCalculator::new();
+    let loot_tables = loot.register_shipped_tables();
+    println!("   ✓ Unit 3 (Veridia): Economy systems ready ({} loot tables)", loot_tables);
+
+    // Block registry: every unit must agree on block IDs
+    let generator = ChunkGenerator::new(WorldSeed::new(0));
+    let dungeons = DungeonConfig::shipped();
+    if let Err(e) = check_block_references(&generator, &loot)
+        .and_then(|()| check_dungeon_references(&dungeons, &generator, &loot))
+        .and_then(|()| CityConfig::shipped().check_blocks(generator.blocks()))
+    {
+        eprintln!("   ✗ FATAL: Invalid block data: {}", e);
+        std::process::exit(1);
+    }
+    println!("   ✓ Blocks: {} block types, all references valid", generator.blocks().blocks().len());
+    // Mined blocks drop from the table (and need the tool tier) the registry gives them
+    loot.set_blocks(Arc::clone(generator.blocks()));
 
     // Unit 4: Networking
     println!("   ✓ Unit 4 (Inferno): Network server ready");
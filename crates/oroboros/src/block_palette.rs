@@ -0,0 +1,100 @@
+//! # Block Palette
+//!
+//! How each block looks to a client, from the block registry and the
+//! render materials its blocks name. Clients draw from this instead of
+//! keeping their own list of block types and colours, so a block added to
+//! `blocks.toml` shows up without touching client code.
+
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
+use oroboros_rendering::voxel::MaterialRegistry;
+
+/// How one block is drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockLook {
+    /// Vertex colour (HDR for emissive blocks).
+    pub color: [f32; 4],
+    /// PBR metallic.
+    pub metallic: f32,
+    /// PBR roughness.
+    pub roughness: f32,
+    /// Stops movement (and hides faces behind it).
+    pub solid: bool,
+}
+
+impl BlockLook {
+    /// Look of air, of blocks without a material and of IDs the registry
+    /// doesn't define.
+    pub const AIR: Self = Self {
+        color: [0.0; 4],
+        metallic: 0.0,
+        roughness: 1.0,
+        solid: false,
+    };
+}
+
+/// Looks of every block, indexed by block ID.
+pub struct BlockPalette {
+    looks: Vec<BlockLook>,
+}
+
+impl BlockPalette {
+    /// Builds the palette from block definitions and the shipped materials.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a block names a material that isn't registered.
+    pub fn new(blocks: &BlockRegistry) -> Result<Self, BlockConfigError> {
+        let mut materials = MaterialRegistry::new();
+        materials.bind_blocks(blocks)?;
+
+        let len = blocks.blocks().iter().map(|def| usize::from(def.id) + 1).max().unwrap_or(0);
+        let mut looks = vec![BlockLook::AIR; len];
+        // Blocks without a material (air) aren't drawn
+        for def in blocks.blocks().iter().filter(|def| def.material.is_some()) {
+            let material = materials.get(materials.block_material(def.id));
+            looks[usize::from(def.id)] = BlockLook {
+                color: material.vertex_color(),
+                metallic: material.metallic(),
+                roughness: material.roughness(),
+                solid: def.solid,
+            };
+        }
+        Ok(Self { looks })
+    }
+
+    /// Returns how a block is drawn.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, id: BlockId) -> BlockLook {
+        self.looks.get(usize::from(id)).copied().unwrap_or(BlockLook::AIR)
+    }
+
+    /// Returns true if a block stops movement.
+    #[inline]
+    #[must_use]
+    pub fn is_solid(&self, id: BlockId) -> bool {
+        self.get(id).solid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_palette() {
+        let blocks = BlockRegistry::shipped();
+        let palette = BlockPalette::new(&blocks).unwrap();
+        let look = |name: &str| palette.get(blocks.id(name).unwrap());
+
+        assert_eq!(palette.get(0), BlockLook::AIR);
+        assert_eq!(palette.get(u16::MAX), BlockLook::AIR);
+        assert_eq!(look("concrete_floor").color, [0.314, 0.314, 0.314, 1.0]);
+        // Neon glows past 1 for bloom
+        assert!(look("hazard_neon").color[0] > 1.0);
+        assert!(look("metal_bridge").metallic > 0.5);
+        assert!(look("stone").solid);
+        assert!(!look("water").solid);
+        assert!(look("water").color[3] < 1.0);
+    }
+}
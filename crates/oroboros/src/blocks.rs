@@ -0,0 +1,55 @@
+//! # Block Reference Check
+//!
+//! Every unit refers to blocks from its own data: biomes name surface
+//! blocks, generators place built-in blocks, blocks name loot tables.
+//! Run this once at startup so a missing ID stops the server instead of
+//! turning into air, stone or "no drop" in the middle of a game.
+
+use oroboros_core::BlockConfigError;
+use oroboros_economy::LootCalculator;
+use oroboros_procedural::{ChunkGenerator, DungeonConfig};
+
+/// Checks that everything the generator and loot tables refer to exists,
+/// using the generator's block registry.
+///
+/// # Errors
+///
+/// Fails with the first dangling block ID or loot table.
+pub fn check_block_references(generator: &ChunkGenerator, loot: &LootCalculator) -> Result<(), BlockConfigError> {
+    generator.check_blocks()?;
+    loot.check_blocks(generator.blocks())
+}
+
+/// Checks that the blocks and loot tables dungeons use exist.
+///
+/// # Errors
+///
+/// Fails with the first dangling block ID or loot table.
+pub fn check_dungeon_references(
+    dungeons: &DungeonConfig,
+    generator: &ChunkGenerator,
+    loot: &LootCalculator,
+) -> Result<(), BlockConfigError> {
+    dungeons.check_blocks(generator.blocks())?;
+    dungeons.check_loot_tables(|table| loot.has_table(table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oroboros_procedural::{CityConfig, WorldSeed};
+
+    #[test]
+    fn test_shipped_data_is_consistent() {
+        let generator = ChunkGenerator::new(WorldSeed::new(1));
+        let mut loot = LootCalculator::new();
+        loot.register_shipped_tables();
+        check_block_references(&generator, &loot).unwrap();
+        check_dungeon_references(&DungeonConfig::shipped(), &generator, &loot).unwrap();
+        CityConfig::shipped().check_blocks(generator.blocks()).unwrap();
+
+        // Without loot tables, stone's table dangles
+        let err = check_block_references(&generator, &LootCalculator::new()).unwrap_err();
+        assert!(matches!(err, BlockConfigError::UnknownLootTable { .. }));
+    }
+}
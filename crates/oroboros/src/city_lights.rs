@@ -0,0 +1,96 @@
+//! # City Lights
+//!
+//! Hands a generated city's neon to the renderer. A city holds far more
+//! lights than a frame can (`MAX_NEON_LIGHTS`), so each frame takes the
+//! ones nearest the camera.
+
+use oroboros_procedural::CityLight;
+use oroboros_rendering::atmosphere::neon_lighting::MAX_NEON_LIGHTS;
+use oroboros_rendering::atmosphere::{NeonLight, NeonLighting};
+
+/// Converts a city light: spots stay spots, everything else is a sign
+/// (steady if its flicker speed is 0).
+#[must_use]
+pub fn neon_light(light: &CityLight) -> NeonLight {
+    match light.spot {
+        Some(spot) => NeonLight::spot(
+            light.position,
+            spot.direction,
+            light.color,
+            light.radius,
+            light.intensity,
+            spot.angle,
+        ),
+        None => NeonLight::neon_sign(light.position, light.color, light.radius, light.intensity, light.flicker_speed),
+    }
+}
+
+/// Picks the city lights nearest the camera each frame.
+///
+/// Only the nearest `MAX_NEON_LIGHTS` are ordered; the rest are just
+/// partitioned off. Scratch storage is kept between calls, so frames
+/// don't allocate once it has grown to the city's light count.
+#[derive(Default)]
+pub struct CityLightPicker {
+    /// Squared camera distance and index of every light.
+    nearest: Vec<(f32, usize)>,
+}
+
+impl CityLightPicker {
+    /// Creates a picker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the lights nearest `camera`, nearest first, until the frame is
+    /// full. Call after `NeonLighting::begin_frame`; returns how many were
+    /// added.
+    pub fn add_city_lights(&mut self, lighting: &mut NeonLighting, lights: &[CityLight], camera: [f32; 3]) -> usize {
+        let room = MAX_NEON_LIGHTS.saturating_sub(lighting.light_count() as usize).min(lights.len());
+        if room == 0 {
+            return 0;
+        }
+
+        self.nearest.clear();
+        self.nearest.extend(lights.iter().enumerate().map(|(index, light)| {
+            let distance = light.position.iter().zip(camera).map(|(a, b)| (a - b) * (a - b)).sum::<f32>();
+            (distance, index)
+        }));
+        if room < self.nearest.len() {
+            self.nearest.select_nth_unstable_by(room - 1, |a, b| a.0.total_cmp(&b.0));
+            self.nearest.truncate(room);
+        }
+        self.nearest.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        self.nearest.iter().take_while(|&&(_, index)| lighting.add_light(neon_light(&lights[index]))).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oroboros_procedural::{City, CityConfig, LightSource, WorldSeed};
+
+    #[test]
+    fn test_nearest_lights_fill_the_frame() {
+        let city = City::generate(&CityConfig::shipped(), WorldSeed::new(2), [-128, -128]);
+        assert!(city.lights().len() > MAX_NEON_LIGHTS);
+
+        let mut lighting = NeonLighting::new();
+        lighting.begin_frame(0.016);
+        let mut picker = CityLightPicker::new();
+        let added = picker.add_city_lights(&mut lighting, city.lights(), [0.0, 60.0, 0.0]);
+        assert_eq!(added, MAX_NEON_LIGHTS);
+        assert_eq!(lighting.light_count() as usize, MAX_NEON_LIGHTS);
+
+        let first = lighting.buffer().lights[0];
+        let nearest = city.lights().iter().map(|light| light.position.iter().map(|p| p * p).sum::<f32>()).fold(f32::MAX, f32::min);
+        assert!((first.position.iter().map(|p| p * p).sum::<f32>() - nearest).abs() < 1e-3);
+
+        let beacon = city.lights().iter().find(|light| light.source == LightSource::Landmark && light.spot.is_some()).unwrap();
+        let spot = neon_light(beacon);
+        assert!(spot.spot_angle > 0.0);
+        assert!(spot.direction[1] > 0.99, "beacons point up");
+    }
+}
@@ -5,9 +5,11 @@
 //! Frame N:
 //! ┌─────────────────────────────────────────────────────────────────────┐
 //! │ 1. BEGIN FRAME                                                      │
-//! │    └─ Acquire write handle to Buffer A                              │
+//! │    ├─ Acquire write handle to Buffer A                              │
+//! │    └─ Rotate ECS event queues                                       │
 //! │                                                                     │
 //! │ 2. LOGIC TICK (Unit 4 writes to Buffer A)                          │
+//! │    ├─ Run scheduled systems (parallel waves, see `Schedule`)        │
 //! │    ├─ Process network input                                         │
 //! │    ├─ Update physics                                                │
 //! │    ├─ Call economy (Unit 3) for mining/combat                      │
@@ -18,6 +20,7 @@
 //! │    └─ Update inventories in ECS                                     │
 //! │                                                                     │
 //! │ 4. SWAP BUFFERS (Unit 1)                                           │
+//! │    ├─ Apply deferred commands (spawn/despawn/insert/remove)         │
 //! │    └─ Atomic pointer swap + dirty copy                              │
 //! │                                                                     │
 //! │ 5. RENDER TICK (Unit 2 reads from Buffer B)                        │
@@ -26,6 +29,7 @@
 //! │    └─ Submit GPU commands                                           │
 //! │                                                                     │
 //! │ 6. END FRAME                                                        │
+//! │    ├─ Reset frame arena                                             │
 //! │    └─ Wait for vsync / frame budget                                 │
 //! └─────────────────────────────────────────────────────────────────────┘
 //! ```
@@ -33,7 +37,12 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use oroboros_core::{DoubleBufferedWorld, FrameSync, WorldWriteHandle, WorldReadHandle};
+use oroboros_core::memory::thread_arena_stats;
+use oroboros_core::schedule::default_worker_threads;
+use oroboros_core::{
+    Arena, ArenaStats, CommandBuffer, DoubleBufferedWorld, FrameSync, Schedule, ScheduleError, SystemTimings, WorldReadHandle,
+    WorldWriteHandle,
+};
 
 use crate::events::{EventSystem, EventReceiver, EventSender};
 
@@ -56,6 +65,10 @@ pub struct GameLoopConfig {
     pub enable_timing_logs: bool,
     /// Target frames per second.
     pub target_fps: u32,
+    /// Scheduler worker threads besides the logic thread.
+    pub worker_threads: usize,
+    /// Size of the per-frame scratch arena in bytes.
+    pub frame_arena_bytes: usize,
 }
 
 impl Default for GameLoopConfig {
@@ -66,6 +79,8 @@ impl Default for GameLoopConfig {
             event_capacity: 2048,
             enable_timing_logs: false,
             target_fps: 60,
+            worker_threads: default_worker_threads(),
+            frame_arena_bytes: 4 * 1024 * 1024,
         }
     }
 }
@@ -87,6 +102,14 @@ pub struct FrameStats {
     pub frame: u64,
     /// Events processed this frame.
     pub events_processed: u32,
+    /// Per-system times from the scheduler.
+    pub systems: SystemTimings,
+    /// Frame arena usage before its reset; filled in by
+    /// [`GameLoop::end_frame`].
+    pub frame_arena: ArenaStats,
+    /// Worker thread arena usage across all threads; filled in by
+    /// [`GameLoop::end_frame`].
+    pub thread_arenas: ArenaStats,
 }
 
 /// Handles for a single frame's work.
@@ -105,6 +128,42 @@ pub struct FrameContext<'a> {
     pub frame: u64,
     /// Delta time since last frame.
     pub delta_time: f32,
+    /// Structural changes recorded outside systems, applied on swap.
+    pub commands: &'a mut CommandBuffer,
+    /// Scratch memory freed at [`GameLoop::end_frame`].
+    pub scratch: &'a Arena,
+    /// Systems to run against the write buffer.
+    schedule: &'a mut Schedule,
+    /// Swaps buffers once the frame's writes are done.
+    frame_sync: &'a FrameSync,
+}
+
+impl FrameContext<'_> {
+    /// Runs every scheduled system against the write buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schedule's ordering is invalid.
+    pub fn run_systems(&mut self) -> Result<SystemTimings, ScheduleError> {
+        self.schedule
+            .run(&mut self.write, self.delta_time, self.frame)
+            .copied()
+    }
+
+    /// Swaps buffers after the logic tick.
+    ///
+    /// Call this after logic has finished writing but before rendering.
+    /// Applies [`commands`](Self::commands) first, then consumes the
+    /// context so the write handle is dropped before the swap.
+    pub fn swap_buffers(mut self) {
+        self.commands.apply(&mut self.write);
+
+        let frame_sync = self.frame_sync;
+        drop(self.write);
+
+        // Perform the swap (includes dirty copy)
+        frame_sync.end_frame();
+    }
 }
 
 /// Read-only context for rendering.
@@ -137,6 +196,12 @@ pub struct GameLoop {
     last_frame_time: Instant,
     /// Accumulated frame statistics.
     stats_accumulator: FrameStatsAccumulator,
+    /// Logic systems run each frame.
+    schedule: Schedule,
+    /// Frame-level command buffer, reused every frame.
+    commands: CommandBuffer,
+    /// Per-frame scratch allocator.
+    frame_arena: Arena,
 }
 
 impl GameLoop {
@@ -150,6 +215,8 @@ impl GameLoop {
         let world = DoubleBufferedWorld::new(config.pv_capacity, config.p_capacity);
         let frame_sync = world.frame_sync();
         let events = EventSystem::new();
+        let schedule = Schedule::new(config.worker_threads);
+        let frame_arena = Arena::new(config.frame_arena_bytes);
 
         Self {
             world,
@@ -159,6 +226,9 @@ impl GameLoop {
             frame_count: 0,
             last_frame_time: Instant::now(),
             stats_accumulator: FrameStatsAccumulator::new(),
+            schedule,
+            commands: CommandBuffer::new(),
+            frame_arena,
         }
     }
 
@@ -178,7 +248,10 @@ impl GameLoop {
         // Clamp delta time to prevent physics explosion after pause
         let delta_time = delta.as_secs_f32().min(0.1);
 
-        let write = self.world.write_handle();
+        let mut write = self.world.write_handle();
+
+        // Events from two frames ago expire
+        write.update_events();
 
         FrameContext {
             write,
@@ -187,27 +260,16 @@ impl GameLoop {
             economy_events: &self.events.economy_sender,
             frame: self.frame_count,
             delta_time,
+            commands: &mut self.commands,
+            scratch: &self.frame_arena,
+            schedule: &mut self.schedule,
+            frame_sync: &self.frame_sync,
         }
     }
 
-    /// Swaps buffers after logic tick.
-    ///
-    /// Call this after logic has finished writing but before rendering.
-    ///
-    /// # Arguments
-    ///
-    /// * `ctx` - The frame context (consumed to ensure write handle is dropped)
-    pub fn swap_buffers(&self, ctx: FrameContext<'_>) {
-        // Drop the write handle explicitly
-        drop(ctx.write);
-
-        // Perform the swap (includes dirty copy)
-        self.frame_sync.end_frame();
-    }
-
     /// Gets a render context for the current frame.
     ///
-    /// Call this after `swap_buffers()`.
+    /// Call this after [`FrameContext::swap_buffers`].
     #[must_use]
     pub fn render_context(&self) -> RenderContext<'_> {
         RenderContext {
@@ -220,8 +282,12 @@ impl GameLoop {
 
     /// Ends the current frame.
     ///
-    /// Records timing and prepares for next frame.
-    pub fn end_frame(&mut self, stats: FrameStats) {
+    /// Records timing and arena usage, then resets the frame arena.
+    pub fn end_frame(&mut self, mut stats: FrameStats) {
+        stats.frame_arena = self.frame_arena.stats();
+        stats.thread_arenas = thread_arena_stats();
+        self.frame_arena.reset();
+
         self.frame_count += 1;
         self.stats_accumulator.record(stats);
 
@@ -243,6 +309,12 @@ impl GameLoop {
         self.frame_count
     }
 
+    /// Returns the system schedule, for registering systems.
+    #[must_use]
+    pub fn schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
+    }
+
     /// Returns a reference to the event system.
     #[must_use]
     pub fn events(&self) -> &EventSystem {
@@ -287,6 +359,10 @@ pub struct FrameStatsAccumulator {
     pub max_frame_us: u64,
     /// Frames that exceeded budget.
     pub frames_over_budget: u64,
+    /// Most frame arena bytes used in one frame.
+    pub frame_arena_high_water: usize,
+    /// Frame and thread arena allocations refused so far.
+    pub arena_overflows: u64,
 }
 
 impl FrameStatsAccumulator {
@@ -302,6 +378,8 @@ impl FrameStatsAccumulator {
             min_frame_us: u64::MAX,
             max_frame_us: 0,
             frames_over_budget: 0,
+            frame_arena_high_water: 0,
+            arena_overflows: 0,
         }
     }
 
@@ -318,6 +396,9 @@ impl FrameStatsAccumulator {
         if stats.total_us > TARGET_FRAME_TIME.as_micros() as u64 {
             self.frames_over_budget += 1;
         }
+
+        self.frame_arena_high_water = self.frame_arena_high_water.max(stats.frame_arena.used);
+        self.arena_overflows = stats.frame_arena.overflows + stats.thread_arenas.overflows;
     }
 
     /// Returns average frame time in milliseconds.
@@ -379,6 +460,12 @@ impl FrameStatsAccumulator {
             println!("│ Render (Unit 2):    {:.3} ms                                  ", avg_render);
             println!("└──────────────────────────────────────────────────────────────────┘");
         }
+
+        println!();
+        println!("┌─ MEMORY ───────────────────────────────────────────────────────┐");
+        println!("│ Frame Arena Peak:   {:.1} KB                                  ", self.frame_arena_high_water as f64 / 1024.0);
+        println!("│ Arena Overflows:    {}                                        ", self.arena_overflows);
+        println!("└──────────────────────────────────────────────────────────────────┘");
     }
 }
 
@@ -413,7 +500,7 @@ mod tests {
         assert_eq!(ctx.frame, 0);
 
         // Swap
-        game_loop.swap_buffers(ctx);
+        ctx.swap_buffers();
 
         // Render
         let render_ctx = game_loop.render_context();
@@ -429,6 +516,7 @@ mod tests {
             render_us: 200,
             frame: 0,
             events_processed: 0,
+            ..Default::default()
         });
 
         assert_eq!(game_loop.frame_count(), 1);
@@ -447,6 +535,7 @@ mod tests {
                 render_us: 2000,
                 frame: i,
                 events_processed: 10,
+                ..Default::default()
             });
         }
 
@@ -454,4 +543,66 @@ mod tests {
         assert!(acc.avg_fps() > 50.0);
         assert!(acc.avg_fps() < 100.0);
     }
+
+    #[test]
+    fn test_systems_run_on_write_buffer() {
+        use oroboros_core::{FnSystem, Position, SystemAccess, Velocity};
+
+        let config = GameLoopConfig {
+            pv_capacity: 16,
+            p_capacity: 16,
+            worker_threads: 1,
+            ..Default::default()
+        };
+        let mut game_loop = GameLoop::new(config);
+        game_loop.schedule_mut().add_system(FnSystem::new(
+            "movement",
+            SystemAccess::new().write::<Position>().read::<Velocity>(),
+            |ctx| {
+                for (_, (pos, vel)) in ctx.query_mut::<(&mut Position, &Velocity)>() {
+                    pos.x += vel.x;
+                }
+            },
+        ));
+
+        let mut ctx = game_loop.begin_frame();
+        let id = ctx.write.spawn_pv(Position::new(0.0, 0.0, 0.0), Velocity::new(2.0, 0.0, 0.0));
+        let timings = ctx.run_systems().unwrap();
+        assert!(timings.get("movement").is_some());
+        assert_eq!(ctx.write.get_position(id).unwrap().x, 2.0);
+
+        ctx.commands.despawn(id);
+        assert!(ctx.write.is_alive(id));
+        ctx.swap_buffers();
+
+        let render_ctx = game_loop.render_context();
+        assert!(!render_ctx.read.is_alive(id));
+    }
+
+    #[test]
+    fn test_frame_arena_resets_each_frame() {
+        let config = GameLoopConfig {
+            pv_capacity: 16,
+            p_capacity: 16,
+            frame_arena_bytes: 1024,
+            ..Default::default()
+        };
+        let mut game_loop = GameLoop::new(config);
+
+        let ctx = game_loop.begin_frame();
+        let scratch = ctx.scratch.alloc_slice::<u32>(100).unwrap();
+        scratch[99] = 7;
+        assert!(ctx.scratch.alloc_slice::<u8>(1024).is_none());
+        ctx.swap_buffers();
+        game_loop.end_frame(FrameStats::default());
+
+        let ctx = game_loop.begin_frame();
+        assert_eq!(ctx.scratch.used(), 0);
+        ctx.swap_buffers();
+        game_loop.end_frame(FrameStats::default());
+
+        let stats = game_loop.stats();
+        assert_eq!(stats.frame_arena_high_water, 400);
+        assert!(stats.arena_overflows >= 1);
+    }
 }
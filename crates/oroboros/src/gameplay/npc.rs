@@ -9,6 +9,8 @@
 //!
 //! CRITICAL: NPCs must NOT fall through the floor!
 
+use oroboros_procedural::dungeon::{Dungeon, SpawnRole};
+
 use crate::physics::{VoxelWorld, AABB, GRAVITY, TERMINAL_VELOCITY};
 
 // ============================================================================
@@ -469,6 +471,21 @@ impl NpcManager {
         }
     }
 
+    /// Spawns a dungeon's boss and guards at their spawn points.
+    /// All of them are hostile. Returns their IDs, in spawn point order.
+    pub fn spawn_dungeon(&mut self, dungeon: &Dungeon) -> Vec<u32> {
+        dungeon
+            .spawns()
+            .iter()
+            .map(|point| {
+                let npc_type = match point.role {
+                    SpawnRole::Boss | SpawnRole::Guard => NpcType::Hostile,
+                };
+                self.spawn(npc_type, point.position)
+            })
+            .collect()
+    }
+
     /// Updates all NPCs.
     pub fn update(&mut self, dt: f32, world: &VoxelWorld, player_pos: [f32; 3]) {
         for npc in &mut self.npcs {
@@ -571,6 +588,22 @@ mod tests {
         assert!(manager.get(id2).is_some());
     }
 
+    #[test]
+    fn test_spawn_dungeon() {
+        use oroboros_procedural::{DungeonConfig, WorldSeed};
+
+        let dungeon = Dungeon::generate(&DungeonConfig::shipped(), WorldSeed::new(5), [0, 20, 0]).unwrap();
+        let mut manager = NpcManager::new();
+        let ids = manager.spawn_dungeon(&dungeon);
+
+        assert_eq!(ids.len(), dungeon.spawns().len());
+        for (id, point) in ids.iter().zip(dungeon.spawns()) {
+            let npc = manager.get(*id).unwrap();
+            assert_eq!(npc.npc_type, NpcType::Hostile);
+            assert_eq!(npc.position, point.position);
+        }
+    }
+
     #[test]
     fn test_ai_state_default() {
         let state = AiState::default();
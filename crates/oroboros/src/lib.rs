@@ -33,10 +33,18 @@
 //!
 //! ## Modules
 //!
+//! - `block_palette`: How blocks look to clients (`rendering` feature)
+//! - `blocks`: Startup check of block references across units
+//! - `city_lights`: City neon handed to the renderer (`rendering` feature)
 //! - `events`: Inter-unit event system
 //! - `game_loop`: Frame orchestration and timing
 //! - `integration`: Vertical slice tests
 
+#[cfg(feature = "rendering")]
+pub mod block_palette;
+pub mod blocks;
+#[cfg(feature = "rendering")]
+pub mod city_lights;
 pub mod events;
 pub mod game_loop;
 pub mod gameplay;
@@ -57,5 +65,10 @@ pub use oroboros_networking as networking;
 pub use oroboros_security as security;
 
 // Re-export commonly used types
+#[cfg(feature = "rendering")]
+pub use block_palette::{BlockLook, BlockPalette};
+pub use blocks::{check_block_references, check_dungeon_references};
+#[cfg(feature = "rendering")]
+pub use city_lights::CityLightPicker;
 pub use events::{EventBus, EventSender, EventReceiver, EventSystem, GameEvent};
 pub use game_loop::{GameLoop, GameLoopConfig, FrameStats, FrameContext, RenderContext};
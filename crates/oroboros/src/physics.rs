@@ -8,6 +8,10 @@
 //! - Voxel raycasting for block selection
 //! - Ground detection and jumping
 
+use std::sync::Arc;
+
+use oroboros_core::{BlockId, BlockRegistry};
+
 /// Gravity acceleration (blocks per second squared).
 pub const GRAVITY: f32 = 32.0;
 
@@ -98,22 +102,58 @@ impl AABB {
 /// Returns `true` if solid (blocks movement).
 pub type VoxelQueryFn = fn(x: i32, y: i32, z: i32) -> bool;
 
+/// Callback returning the block ID at given coordinates.
+pub type BlockQueryFn = Box<dyn Fn(i32, i32, i32) -> BlockId + Send + Sync>;
+
 /// Simple voxel world for collision testing.
 /// Uses a height-based terrain model.
 pub struct VoxelWorld {
     /// Custom query function (if set).
     custom_query: Option<VoxelQueryFn>,
+    /// Block ID lookup and the registry that says which IDs are solid (if set).
+    block_query: Option<(BlockQueryFn, Arc<BlockRegistry>)>,
 }
 
 impl VoxelWorld {
     /// Creates a new voxel world with procedural terrain.
     pub fn new() -> Self {
-        Self { custom_query: None }
+        Self { custom_query: None, block_query: None }
     }
 
     /// Sets a custom query function for collision checking.
     pub fn with_query(query: VoxelQueryFn) -> Self {
-        Self { custom_query: Some(query) }
+        Self { custom_query: Some(query), block_query: None }
+    }
+
+    /// Collides with real blocks: `query` returns the block ID at a
+    /// position and `blocks` decides whether it is solid.
+    pub fn with_blocks(
+        query: impl Fn(i32, i32, i32) -> BlockId + Send + Sync + 'static,
+        blocks: Arc<BlockRegistry>,
+    ) -> Self {
+        Self { custom_query: None, block_query: Some((Box::new(query), blocks)) }
+    }
+
+    /// Collides with the built-in hills as real blocks: grass on top,
+    /// stone below, air above. Solidity comes from `blocks`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `blocks` has no `grass` or `stone`.
+    pub fn terrain(blocks: Arc<BlockRegistry>) -> Self {
+        let grass = blocks.id("grass").expect("block registry has no grass");
+        let stone = blocks.id("stone").expect("block registry has no stone");
+        Self::with_blocks(
+            move |x, y, z| {
+                let height = terrain_height(x, z);
+                match y {
+                    _ if y < 0 || y >= height => 0,
+                    _ if y == height - 1 => grass,
+                    _ => stone,
+                }
+            },
+            blocks,
+        )
     }
 
     /// Checks if a voxel exists at the given coordinates.
@@ -121,6 +161,9 @@ impl VoxelWorld {
         if let Some(query) = self.custom_query {
             return query(x, y, z);
         }
+        if let Some((query, blocks)) = &self.block_query {
+            return blocks.is_solid(query(x, y, z));
+        }
 
         // Default: procedural terrain
         // Ground level with some hills
@@ -130,19 +173,7 @@ impl VoxelWorld {
 
     /// Gets the terrain height at (x, z).
     pub fn get_height(&self, x: i32, z: i32) -> i32 {
-        // Simple noise-based height
-        let seed = ((x.abs() % 97) * 7919 + (z.abs() % 97) * 4363) as f32;
-        let noise = (seed * 0.01).sin() * 0.5 + 0.5;
-        let base_height = 1;
-        let hill_height = (noise * 10.0) as i32;
-        
-        // Add some features
-        let dist_from_center = ((x * x + z * z) as f32).sqrt();
-        if dist_from_center < 20.0 {
-            base_height // Flat spawn area
-        } else {
-            base_height + hill_height
-        }
+        terrain_height(x, z)
     }
 
     /// Gets all solid voxels that might collide with an AABB.
@@ -170,6 +201,23 @@ impl VoxelWorld {
     }
 }
 
+/// Height of the built-in hills at (x, z).
+fn terrain_height(x: i32, z: i32) -> i32 {
+    // Simple noise-based height
+    let seed = ((x.abs() % 97) * 7919 + (z.abs() % 97) * 4363) as f32;
+    let noise = (seed * 0.01).sin() * 0.5 + 0.5;
+    let base_height = 1;
+    let hill_height = (noise * 10.0) as i32;
+    
+    // Add some features
+    let dist_from_center = ((x * x + z * z) as f32).sqrt();
+    if dist_from_center < 20.0 {
+        base_height // Flat spawn area
+    } else {
+        base_height + hill_height
+    }
+}
+
 impl Default for VoxelWorld {
     fn default() -> Self {
         Self::new()
@@ -563,4 +611,38 @@ mod tests {
         assert!(hit.voxel[1] >= 0);
         assert_eq!(hit.normal, [0, 1, 0]); // Hit from above
     }
+
+    #[test]
+    fn test_block_registry_decides_solidity() {
+        let blocks = BlockRegistry::shipped();
+        let stone = blocks.id("stone").unwrap();
+        let water = blocks.id("water").unwrap();
+        let world = VoxelWorld::with_blocks(
+            move |_, y, _| match y {
+                0 => stone,
+                1..=4 => water,
+                _ => 0,
+            },
+            blocks,
+        );
+
+        assert!(world.is_solid(3, 0, -3));
+        assert!(!world.is_solid(3, 2, -3));
+        assert!(!world.is_solid(3, 9, -3));
+
+        // Rays pass through water and stop on the stone below
+        let hit = raycast([0.5, 10.0, 0.5], [0.0, -1.0, 0.0], 100.0, &world).unwrap();
+        assert_eq!(hit.voxel, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_terrain_matches_built_in_hills() {
+        let hills = VoxelWorld::new();
+        let terrain = VoxelWorld::terrain(BlockRegistry::shipped());
+        for (x, z) in [(0, 0), (25, -3), (-40, 61), (90, 90)] {
+            for y in -1..14 {
+                assert_eq!(terrain.is_solid(x, y, z), hills.is_solid(x, y, z), "({x}, {y}, {z})");
+            }
+        }
+    }
 }
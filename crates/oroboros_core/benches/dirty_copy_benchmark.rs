@@ -29,7 +29,7 @@ fn bench_full_copy(c: &mut Criterion) {
             );
         }
         // Mark all as dirty (simulates physics update)
-        source.pv_table.dirty_tracker_mut().mark_all_dirty(entity_count);
+        source.pv_table_mut().dirty_tracker_mut().mark_all_dirty(entity_count);
 
         // Create destination world
         let mut dest = ArchetypeWorld::new(entity_count, 0);
@@ -74,7 +74,7 @@ fn bench_sparse_copy(c: &mut Criterion) {
 
         // Mark only a percentage as dirty (simulates partial update)
         for i in 0..dirty_count {
-            source.pv_table.dirty_tracker_mut().mark_dirty(i);
+            source.pv_table_mut().dirty_tracker_mut().mark_dirty(i);
         }
 
         // Create destination world with matching data
@@ -130,7 +130,7 @@ fn bench_swap_cycle(c: &mut Criterion) {
 
             // Mark desired percentage as dirty
             let dirty_count = entity_count * dirty_pct / 100;
-            write.pv_table.dirty_tracker_mut().mark_all_dirty(dirty_count.min(entity_count));
+            write.pv_table_mut().dirty_tracker_mut().mark_all_dirty(dirty_count.min(entity_count));
         }
 
         let label = format!("{}pct_dirty_1M", dirty_pct);
@@ -174,10 +174,10 @@ fn bench_bandwidth_savings(c: &mut Criterion) {
 
         let dirty_count = entity_count * dirty_pct / 100;
         if dirty_pct == 100 {
-            source.pv_table.dirty_tracker_mut().mark_all_dirty(entity_count);
+            source.pv_table_mut().dirty_tracker_mut().mark_all_dirty(entity_count);
         } else {
             for i in 0..dirty_count {
-                source.pv_table.dirty_tracker_mut().mark_dirty(i);
+                source.pv_table_mut().dirty_tracker_mut().mark_dirty(i);
             }
         }
 
@@ -0,0 +1,17 @@
+//! # Block Registry
+//!
+//! One table of block types that every unit agrees on.
+//!
+//! ## Design Philosophy
+//!
+//! - Block IDs are data: `data/schemas/world/blocks.toml` names each ID and
+//!   says how it collides, draws, and drops loot
+//! - World generation, physics, rendering and loot all consult the same
+//!   [`BlockRegistry`] instead of hardcoding numbers
+//! - References are checked once at startup, so an ID that doesn't exist
+//!   fails loudly instead of turning into a silent default mid-game
+//! - Lookups by ID are a single index, cheap enough for collision queries
+
+mod registry;
+
+pub use registry::{BlockConfigError, BlockDef, BlockId, BlockRegistry};
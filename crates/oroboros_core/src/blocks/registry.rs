@@ -0,0 +1,389 @@
+//! Block definitions loaded from `blocks.toml`.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::embedded::EmbeddedData;
+
+/// The shipped block definitions.
+const SHIPPED_BLOCKS: &str = include_str!("../../../../data/schemas/world/blocks.toml");
+
+/// Numeric block ID, as stored in chunks and sent over the network.
+pub type BlockId = u16;
+
+/// Why block definitions could not be loaded, or something refers to a
+/// block that doesn't exist.
+#[derive(Error, Debug)]
+pub enum BlockConfigError {
+    /// Reading the file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid TOML or is missing fields.
+    #[error("invalid block config: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// Two blocks share an ID.
+    #[error("block ID {0} is defined twice")]
+    DuplicateId(BlockId),
+    /// Two blocks share a name.
+    #[error("block name {0:?} is defined twice")]
+    DuplicateName(String),
+    /// Block 0 is missing or solid; everything treats 0 as empty space.
+    #[error("block 0 must be defined and not solid")]
+    Air,
+    /// Something refers to a block ID that isn't defined.
+    #[error("{referrer} refers to unknown block ID {id}")]
+    UnknownBlock {
+        /// What holds the reference.
+        referrer: String,
+        /// The missing ID.
+        id: BlockId,
+    },
+    /// A block's render material doesn't exist.
+    #[error("block {block:?} uses unknown material {material:?}")]
+    UnknownMaterial {
+        /// Block name.
+        block: String,
+        /// The missing material name.
+        material: String,
+    },
+    /// A block's loot table doesn't exist.
+    #[error("block {block:?} uses unknown loot table {table}")]
+    UnknownLootTable {
+        /// Block name.
+        block: String,
+        /// The missing loot table ID.
+        table: u32,
+    },
+    /// Something expects a block to drop loot, but it has no loot table.
+    #[error("{referrer} expects block {block:?} to have a loot table")]
+    NoLootTable {
+        /// What expects the loot.
+        referrer: String,
+        /// Block name.
+        block: String,
+    },
+}
+
+/// One block type's definition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockDef {
+    /// Block ID.
+    pub id: BlockId,
+    /// Unique name.
+    pub name: String,
+    /// Stops movement.
+    pub solid: bool,
+    /// Faces behind it stay visible.
+    pub transparent: bool,
+    /// Mining resistance; infinite for unbreakable blocks.
+    pub hardness: f32,
+    /// Minimum pickaxe tier that can mine it.
+    pub tool_tier: u8,
+    /// Loot table dropped when mined, if any.
+    pub loot_table: Option<u32>,
+    /// Render material name, if drawn.
+    pub material: Option<String>,
+}
+
+impl BlockDef {
+    /// Checks if the block can be mined at all.
+    #[inline]
+    #[must_use]
+    pub fn is_breakable(&self) -> bool {
+        self.hardness.is_finite()
+    }
+}
+
+/// `blocks.toml` as written.
+#[derive(Deserialize)]
+struct BlockFile {
+    /// `[[block]]`.
+    #[serde(default)]
+    block: Vec<BlockTable>,
+}
+
+/// One `[[block]]`.
+#[derive(Deserialize)]
+struct BlockTable {
+    /// Block ID.
+    id: BlockId,
+    /// Unique name.
+    name: String,
+    /// Stops movement.
+    solid: bool,
+    /// Faces behind it stay visible.
+    transparent: bool,
+    /// Mining resistance.
+    #[serde(default)]
+    hardness: f32,
+    /// Minimum pickaxe tier.
+    #[serde(default)]
+    tool_tier: u8,
+    /// Loot table ID.
+    loot_table: Option<u32>,
+    /// Render material name.
+    material: Option<String>,
+}
+
+/// Validated block definitions.
+///
+/// IDs and names are unique and block 0 is non-solid air. Whether other
+/// data refers only to defined blocks is checked with
+/// [`check_ids`](Self::check_ids) and friends at startup.
+///
+/// ```rust,ignore
+/// let blocks = BlockRegistry::shipped();
+/// assert!(blocks.is_solid(blocks.id("stone").unwrap()));
+/// ```
+#[derive(Debug)]
+pub struct BlockRegistry {
+    /// Definitions in file order.
+    blocks: Vec<BlockDef>,
+    /// Index into `blocks` by ID, up to the highest ID.
+    lookup: Vec<Option<u16>>,
+}
+
+impl BlockRegistry {
+    /// Returns the registry built from the shipped `blocks.toml`.
+    ///
+    /// Every caller shares one registry, so world generation, mining and
+    /// loot all agree on what each block ID is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `blocks.toml` defines an ID or name twice or lacks a
+    /// non-solid block 0.
+    #[must_use]
+    pub fn shipped() -> Arc<Self> {
+        static SHIPPED: EmbeddedData<BlockRegistry, BlockConfigError> =
+            EmbeddedData::new("blocks.toml", SHIPPED_BLOCKS, BlockRegistry::from_toml);
+        SHIPPED.get()
+    }
+
+    /// Loads definitions from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or the definitions are
+    /// invalid (see [`from_defs`](Self::from_defs)).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BlockConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses definitions in the `blocks.toml` format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text can't be parsed or the definitions are invalid.
+    pub fn from_toml(text: &str) -> Result<Self, BlockConfigError> {
+        let file: BlockFile = toml::from_str(text)?;
+        let blocks = file
+            .block
+            .into_iter()
+            .map(|table| BlockDef {
+                id: table.id,
+                name: table.name,
+                solid: table.solid,
+                transparent: table.transparent,
+                hardness: table.hardness,
+                tool_tier: table.tool_tier,
+                loot_table: table.loot_table,
+                material: table.material,
+            })
+            .collect();
+        Self::from_defs(blocks)
+    }
+
+    /// Builds a registry from definitions.
+    ///
+    /// # Errors
+    ///
+    /// Fails if an ID or name repeats, or block 0 is missing or solid.
+    pub fn from_defs(blocks: Vec<BlockDef>) -> Result<Self, BlockConfigError> {
+        let len = blocks.iter().map(|def| usize::from(def.id) + 1).max().unwrap_or(0);
+        let mut lookup = vec![None; len];
+        for (index, def) in blocks.iter().enumerate() {
+            let slot = &mut lookup[usize::from(def.id)];
+            if slot.is_some() {
+                return Err(BlockConfigError::DuplicateId(def.id));
+            }
+            // At most 65536 unique IDs, so the index fits
+            *slot = Some(index as u16);
+
+            if blocks[..index].iter().any(|earlier| earlier.name == def.name) {
+                return Err(BlockConfigError::DuplicateName(def.name.clone()));
+            }
+        }
+
+        let registry = Self { blocks, lookup };
+        if registry.get(0).map_or(true, |air| air.solid) {
+            return Err(BlockConfigError::Air);
+        }
+        Ok(registry)
+    }
+
+    /// Returns all definitions in file order.
+    #[must_use]
+    pub fn blocks(&self) -> &[BlockDef] {
+        &self.blocks
+    }
+
+    /// Returns a block's definition.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, id: BlockId) -> Option<&BlockDef> {
+        let index = (*self.lookup.get(usize::from(id))?)?;
+        Some(&self.blocks[usize::from(index)])
+    }
+
+    /// Returns the ID of the block with the given name.
+    #[must_use]
+    pub fn id(&self, name: &str) -> Option<BlockId> {
+        self.blocks.iter().find(|def| def.name == name).map(|def| def.id)
+    }
+
+    /// Checks if a block stops movement. Unknown IDs behave like air.
+    #[inline]
+    #[must_use]
+    pub fn is_solid(&self, id: BlockId) -> bool {
+        self.get(id).is_some_and(|def| def.solid)
+    }
+
+    /// Checks if faces behind a block stay visible. Unknown IDs behave like
+    /// air.
+    #[inline]
+    #[must_use]
+    pub fn is_transparent(&self, id: BlockId) -> bool {
+        self.get(id).map_or(true, |def| def.transparent)
+    }
+
+    /// Checks that every ID is defined.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first undefined ID, naming `referrer` as its source.
+    pub fn check_ids(&self, referrer: &str, ids: impl IntoIterator<Item = BlockId>) -> Result<(), BlockConfigError> {
+        match ids.into_iter().find(|&id| self.get(id).is_none()) {
+            Some(id) => Err(BlockConfigError::UnknownBlock {
+                referrer: referrer.to_owned(),
+                id,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that every block's material exists.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first block whose material `exists` rejects.
+    pub fn check_materials(&self, exists: impl Fn(&str) -> bool) -> Result<(), BlockConfigError> {
+        for def in &self.blocks {
+            if let Some(material) = def.material.as_deref().filter(|&material| !exists(material)) {
+                return Err(BlockConfigError::UnknownMaterial {
+                    block: def.name.clone(),
+                    material: material.to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every block's loot table exists.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first block whose loot table `exists` rejects.
+    pub fn check_loot_tables(&self, exists: impl Fn(u32) -> bool) -> Result<(), BlockConfigError> {
+        for def in &self.blocks {
+            if let Some(table) = def.loot_table.filter(|&table| !exists(table)) {
+                return Err(BlockConfigError::UnknownLootTable {
+                    block: def.name.clone(),
+                    table,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(id: BlockId, name: &str, solid: bool) -> BlockDef {
+        BlockDef {
+            id,
+            name: name.to_owned(),
+            solid,
+            transparent: !solid,
+            hardness: 1.0,
+            tool_tier: 0,
+            loot_table: None,
+            material: None,
+        }
+    }
+
+    #[test]
+    fn test_shipped_registry() {
+        let blocks = BlockRegistry::shipped();
+        let stone = blocks.id("stone").unwrap();
+        assert!(blocks.is_solid(stone));
+        assert!(!blocks.is_transparent(stone));
+        assert!(!blocks.is_solid(0));
+        assert!(!blocks.is_solid(blocks.id("water").unwrap()));
+        assert!(!blocks.get(blocks.id("bedrock").unwrap()).unwrap().is_breakable());
+        assert!(blocks.get(blocks.id("iron_ore").unwrap()).unwrap().tool_tier > 0);
+    }
+
+    #[test]
+    fn test_unknown_ids_behave_like_air() {
+        let blocks = BlockRegistry::from_defs(vec![def(0, "air", false), def(5, "stone", true)]).unwrap();
+        assert!(blocks.get(3).is_none());
+        assert!(!blocks.is_solid(3));
+        assert!(blocks.is_transparent(3));
+        assert!(!blocks.is_solid(BlockId::MAX));
+        assert!(blocks.is_solid(5));
+    }
+
+    #[test]
+    fn test_rejects_invalid_definitions() {
+        let duplicate_id = BlockRegistry::from_defs(vec![def(0, "air", false), def(0, "void", false)]);
+        assert!(matches!(duplicate_id, Err(BlockConfigError::DuplicateId(0))));
+
+        let duplicate_name = BlockRegistry::from_defs(vec![def(0, "air", false), def(1, "air", true)]);
+        assert!(matches!(duplicate_name, Err(BlockConfigError::DuplicateName(name)) if name == "air"));
+
+        let no_air = BlockRegistry::from_defs(vec![def(1, "stone", true)]);
+        assert!(matches!(no_air, Err(BlockConfigError::Air)));
+
+        let solid_air = BlockRegistry::from_defs(vec![def(0, "stone", true)]);
+        assert!(matches!(solid_air, Err(BlockConfigError::Air)));
+    }
+
+    #[test]
+    fn test_reference_checks() {
+        let mut stone = def(1, "stone", true);
+        stone.material = Some("stone".to_owned());
+        stone.loot_table = Some(7);
+        let blocks = BlockRegistry::from_defs(vec![def(0, "air", false), stone]).unwrap();
+
+        assert!(blocks.check_ids("test", [0, 1]).is_ok());
+        let err = blocks.check_ids("biome \"Plains\"", [1, 9]).unwrap_err();
+        assert!(matches!(err, BlockConfigError::UnknownBlock { id: 9, .. }));
+        assert_eq!(err.to_string(), "biome \"Plains\" refers to unknown block ID 9");
+
+        assert!(blocks.check_materials(|name| name == "stone").is_ok());
+        assert!(matches!(
+            blocks.check_materials(|_| false),
+            Err(BlockConfigError::UnknownMaterial { material, .. }) if material == "stone"
+        ));
+
+        assert!(blocks.check_loot_tables(|table| table == 7).is_ok());
+        assert!(matches!(blocks.check_loot_tables(|_| false), Err(BlockConfigError::UnknownLootTable { table: 7, .. })));
+    }
+}
@@ -20,19 +20,32 @@
 //!
 //! ```text
 //! Archetype "Player" (Position + Velocity + Health):
-//! [P0, V0, H0, P1, V1, H1, P2, V2, H2, ...]  <- All in same cache lines!
+//! Position[]:  [P0, P1, P2, ...]
+//! Velocity[]:  [V0, V1, V2, ...]  <- Row i is always entity i, no holes
+//! Health[]:    [H0, H1, H2, ...]
 //! ```
 //!
 //! Entities with same component set are stored together.
 //! Iteration is linear and cache-friendly.
+//!
+//! ## Dynamic Archetypes
+//!
+//! Any combination of [`Component`] types forms an [`ArchetypeSignature`].
+//! Tables are created on demand and store one type-erased column per
+//! component. Inserting or removing a component moves the entity's row
+//! to the matching table. Dirty tracking is per row (structural changes)
+//! and per column (value writes), so a sync only copies what changed.
 
-use std::alloc::{alloc, dealloc, Layout};
+use std::alloc::{alloc_zeroed, dealloc, Layout};
 use std::any::TypeId;
 use std::collections::HashMap;
 use std::ptr::NonNull;
 
 use super::component::{Component, Position, Velocity};
 use super::entity::EntityId;
+use super::event::{Event, Events};
+use super::hierarchy::Hierarchy;
+use super::resource::{Resource, Resources};
 
 // ============================================================================
 // DIRTY TRACKING - Sparse copy optimization
@@ -73,6 +86,12 @@ impl DirtyTracker {
         }
     }
 
+    /// Extends the tracker to `capacity` entities, keeping existing flags.
+    fn grow(&mut self, capacity: usize) {
+        self.bits.resize(capacity.div_ceil(64), 0);
+        self.capacity = capacity;
+    }
+
     /// Marks an entity index as dirty.
     ///
     /// # Performance
@@ -287,7 +306,7 @@ unsafe fn simd_memcpy_unaligned(dst: *mut u8, src: *const u8, len: usize) {
 /// Statistics from buffer synchronization.
 ///
 /// Used for profiling and verifying the optimization is working.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct SyncStats {
     /// Total entities in the table.
     pub total_entities: usize,
@@ -311,42 +330,116 @@ impl SyncStats {
             1.0 - (self.sparse_copy_bytes as f32 / self.full_copy_bytes as f32)
         }
     }
+
+    /// Adds up the stats of two tables.
+    ///
+    /// `bytes_per_entity` becomes the average row size.
+    #[must_use]
+    pub fn combine(self, other: Self) -> Self {
+        let total_entities = self.total_entities + other.total_entities;
+        let full_copy_bytes = self.full_copy_bytes + other.full_copy_bytes;
+        Self {
+            total_entities,
+            dirty_entities: self.dirty_entities + other.dirty_entities,
+            bytes_per_entity: full_copy_bytes.checked_div(total_entities).unwrap_or(0),
+            full_copy_bytes,
+            sparse_copy_bytes: self.sparse_copy_bytes + other.sparse_copy_bytes,
+        }
+    }
+}
+
+/// Type-erased description of a component type.
+///
+/// Everything a column needs to store a component without knowing its type.
+/// Ordered by `TypeId` so signatures sort consistently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct ComponentType {
+    /// Rust type identity.
+    type_id: TypeId,
+    /// Size of the component in bytes.
+    size: usize,
+    /// Alignment requirement.
+    align: usize,
+}
+
+impl ComponentType {
+    /// Describes component type `C`.
+    fn of<C: Component>() -> Self {
+        Self {
+            type_id: TypeId::of::<C>(),
+            size: std::mem::size_of::<C>(),
+            align: std::mem::align_of::<C>(),
+        }
+    }
 }
 
 /// Signature of an archetype - which components it contains.
 ///
-/// Uses a sorted vector of TypeIds for consistent hashing.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Component types are kept sorted, so the same set always compares and
+/// hashes equal regardless of the order components were added in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct ArchetypeSignature {
-    /// Sorted list of component TypeIds.
-    components: Vec<TypeId>,
+    /// Sorted list of component types.
+    components: Vec<ComponentType>,
 }
 
 impl ArchetypeSignature {
-    /// Creates a new archetype signature from component types.
+    /// Creates an empty signature (entities with no components).
     #[must_use]
-    pub fn new(mut components: Vec<TypeId>) -> Self {
-        components.sort();
-        components.dedup();
-        Self { components }
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this signature with component `C` added.
+    #[must_use]
+    pub fn with<C: Component>(mut self) -> Self {
+        let ty = ComponentType::of::<C>();
+        if let Err(at) = self.components.binary_search(&ty) {
+            self.components.insert(at, ty);
+        }
+        self
+    }
+
+    /// Returns this signature with component `C` removed.
+    #[must_use]
+    pub fn without<C: Component>(mut self) -> Self {
+        self.components.retain(|ty| ty.type_id != TypeId::of::<C>());
+        self
     }
 
     /// Creates signature for Position + Velocity (common case).
     #[must_use]
     pub fn position_velocity() -> Self {
-        Self::new(vec![TypeId::of::<Position>(), TypeId::of::<Velocity>()])
+        Self::new().with::<Position>().with::<Velocity>()
     }
 
     /// Creates signature for Position only.
     #[must_use]
     pub fn position_only() -> Self {
-        Self::new(vec![TypeId::of::<Position>()])
+        Self::new().with::<Position>()
     }
 
     /// Checks if this signature contains a component type.
     #[must_use]
     pub fn contains<C: Component>(&self) -> bool {
-        self.components.contains(&TypeId::of::<C>())
+        self.contains_type(TypeId::of::<C>())
+    }
+
+    /// Checks if this signature contains the component with `type_id`.
+    #[must_use]
+    pub fn contains_type(&self, type_id: TypeId) -> bool {
+        self.components.iter().any(|ty| ty.type_id == type_id)
+    }
+
+    /// Checks if every component of `other` is also in this signature.
+    #[must_use]
+    pub fn contains_all(&self, other: &Self) -> bool {
+        other.components.iter().all(|ty| self.components.binary_search(ty).is_ok())
+    }
+
+    /// Returns the component `TypeId`s in storage order.
+    pub fn type_ids(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.components.iter().map(|ty| ty.type_id)
     }
 
     /// Returns the number of component types.
@@ -362,150 +455,234 @@ impl ArchetypeSignature {
     }
 }
 
-/// Component metadata for layout calculation.
-#[derive(Clone, Copy, Debug)]
-struct ComponentInfo {
-    /// Size of the component in bytes.
-    #[allow(dead_code)]
-    size: usize,
-    /// Alignment requirement.
-    align: usize,
-    /// Offset within the row.
-    offset: usize,
+/// One component's values for every entity of an archetype.
+///
+/// Type-erased: only the component's size and alignment are known here.
+/// Each column tracks its own dirty rows, so writing one component does
+/// not resync the others.
+struct Column {
+    /// Component stored in this column.
+    ty: ComponentType,
+    /// Column data - `capacity * ty.size` bytes.
+    data: NonNull<u8>,
+    /// Layout of the data allocation.
+    layout: Layout,
+    /// Rows whose value in this column changed since the last sync.
+    dirty: DirtyTracker,
+}
+
+impl Column {
+    /// Allocates a zeroed column for `capacity` values.
+    fn new(ty: ComponentType, capacity: usize) -> Self {
+        let layout = Self::layout_for(ty, capacity);
+
+        // SAFETY: The layout has a non-zero size
+        let data = unsafe { alloc_zeroed(layout) };
+        let Some(data) = NonNull::new(data) else {
+            panic!("Allocation failed for archetype column");
+        };
+
+        Self {
+            ty,
+            data,
+            layout,
+            dirty: DirtyTracker::new(capacity),
+        }
+    }
+
+    /// Allocation layout for `capacity` values (never zero-sized).
+    fn layout_for(ty: ComponentType, capacity: usize) -> Layout {
+        Layout::from_size_align((ty.size * capacity).max(1), ty.align)
+            .expect("Invalid layout")
+    }
+
+    /// Gets the pointer to the value at `index`.
+    ///
+    /// # Safety
+    ///
+    /// Index must be < capacity.
+    #[inline]
+    unsafe fn ptr(&self, index: usize) -> *mut u8 {
+        self.data.as_ptr().add(index * self.ty.size)
+    }
+
+    /// Views the first `len` values as `C`.
+    ///
+    /// # Safety
+    ///
+    /// `C` must be this column's component type and `len` <= capacity.
+    #[inline]
+    unsafe fn slice<C: Component>(&self, len: usize) -> &[C] {
+        std::slice::from_raw_parts(self.data.as_ptr().cast::<C>(), len)
+    }
+
+    /// Views the first `len` values mutably as `C`.
+    ///
+    /// # Safety
+    ///
+    /// `C` must be this column's component type and `len` <= capacity.
+    #[inline]
+    unsafe fn slice_mut<C: Component>(&mut self, len: usize) -> &mut [C] {
+        std::slice::from_raw_parts_mut(self.data.as_ptr().cast::<C>(), len)
+    }
+
+    /// Copies the first `len` values from `source` (full copy).
+    fn copy_all_from(&mut self, source: &Self, len: usize) {
+        // SAFETY: Both columns hold the same type and at least `len` values
+        unsafe {
+            simd_memcpy(self.data.as_ptr(), source.data.as_ptr(), len * self.ty.size);
+        }
+    }
+
+    /// Copies the given rows from `source` (sparse copy).
+    fn copy_rows_from(&mut self, source: &Self, rows: impl Iterator<Item = usize>) {
+        let size = self.ty.size;
+        for row in rows {
+            // SAFETY: Callers only pass rows < len <= both capacities
+            unsafe {
+                std::ptr::copy_nonoverlapping(source.ptr(row), self.ptr(row), size);
+            }
+        }
+    }
+
+    /// Reallocates to `new_capacity`, keeping the first `len` values.
+    fn grow(&mut self, len: usize, new_capacity: usize) {
+        let new_layout = Self::layout_for(self.ty, new_capacity);
+
+        // SAFETY: Allocating and copying with valid layouts
+        unsafe {
+            let Some(new_ptr) = NonNull::new(alloc_zeroed(new_layout)) else {
+                panic!("Allocation failed during grow");
+            };
+
+            std::ptr::copy_nonoverlapping(self.data.as_ptr(), new_ptr.as_ptr(), len * self.ty.size);
+            dealloc(self.data.as_ptr(), self.layout);
+
+            self.data = new_ptr;
+            self.layout = new_layout;
+        }
+
+        self.dirty.grow(new_capacity);
+    }
+}
+
+impl Drop for Column {
+    fn drop(&mut self) {
+        // SAFETY: We allocated this memory with this layout
+        unsafe {
+            dealloc(self.data.as_ptr(), self.layout);
+        }
+    }
+}
+
+// SAFETY: Column is Send because it owns its data
+unsafe impl Send for Column {}
+// SAFETY: Column is Sync when accessed properly
+unsafe impl Sync for Column {}
+
+/// Iterator over the set bits of one bitset word.
+struct SetBits(u64);
+
+impl Iterator for SetBits {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+/// Rows below `len` that are dirty in either tracker.
+///
+/// Both trackers must cover the same capacity (a table's row tracker and
+/// one of its column trackers).
+fn dirty_union<'a>(
+    rows: &'a DirtyTracker,
+    column: &'a DirtyTracker,
+    len: usize,
+) -> impl Iterator<Item = usize> + 'a {
+    rows.bits
+        .iter()
+        .zip(&column.bits)
+        .enumerate()
+        .flat_map(|(word_idx, (&a, &b))| SetBits(a | b).map(move |bit| word_idx * 64 + bit))
+        .take_while(move |&index| index < len)
 }
 
 /// A single archetype table - stores all entities with the same component set.
 ///
-/// Memory layout is Structure of Arrays within each archetype:
+/// Memory layout is Structure of Arrays within each archetype, one
+/// type-erased column per component:
 /// ```text
 /// | Entity IDs | Position[] | Velocity[] | Health[] |
 /// ```
 ///
-/// But all arrays are contiguous, enabling prefetching.
+/// Row `i` of every column belongs to `entities[i]`, and rows are kept
+/// dense by swap-remove, so iteration is always linear.
 pub struct ArchetypeTable {
     /// Signature identifying this archetype.
     signature: ArchetypeSignature,
     /// Entity IDs in this archetype (for reverse lookup).
     entities: Vec<EntityId>,
-    /// Component data storage - raw bytes.
-    /// Layout: [Component1 for all entities][Component2 for all entities]...
-    storage: NonNull<u8>,
-    /// Layout of the storage allocation.
-    storage_layout: Layout,
+    /// One column per component, in signature order.
+    columns: Box<[Column]>,
     /// Number of entities currently stored.
     len: usize,
     /// Capacity (max entities before realloc).
     capacity: usize,
-    /// Info about each component type.
-    component_info: Vec<(TypeId, ComponentInfo)>,
     /// Total size of one "row" (all components for one entity).
     row_size: usize,
-    /// Dirty tracking for sparse buffer sync.
-    #[allow(dead_code)]
+    /// Rows that changed as a whole (spawned, moved in, marked by hand).
+    /// A dirty row is synced in every column.
     dirty: DirtyTracker,
 }
 
 impl ArchetypeTable {
-    /// Creates a new archetype table for Position + Velocity entities.
+    /// Creates an empty table for entities with exactly `signature`'s components.
     ///
     /// # Arguments
     ///
+    /// * `signature` - Component set stored by this table
     /// * `capacity` - Initial capacity (number of entities)
     #[must_use]
-    pub fn new_position_velocity(capacity: usize) -> Self {
-        let pos_size = std::mem::size_of::<Position>();
-        let pos_align = std::mem::align_of::<Position>();
-        let vel_size = std::mem::size_of::<Velocity>();
-        let vel_align = std::mem::align_of::<Velocity>();
-
-        // Calculate offsets
-        let pos_offset = 0;
-        let vel_offset = (pos_size + vel_align - 1) & !(vel_align - 1);
-        let row_size = vel_offset + vel_size;
-        // Ensure row_size is aligned for Position (the largest alignment)
-        let max_align = pos_align.max(vel_align);
-        let row_size = (row_size + max_align - 1) & !(max_align - 1);
-
-        let total_size = row_size * capacity;
-        let layout = Layout::from_size_align(total_size.max(1), max_align)
-            .expect("Invalid layout");
-
-        // SAFETY: We're allocating with a valid layout
-        let storage = unsafe {
-            let ptr = alloc(layout);
-            if ptr.is_null() {
-                panic!("Allocation failed for archetype table");
-            }
-            // Zero-initialize
-            std::ptr::write_bytes(ptr, 0, total_size);
-            NonNull::new_unchecked(ptr)
-        };
-
-        let component_info = vec![
-            (TypeId::of::<Position>(), ComponentInfo {
-                size: pos_size,
-                align: pos_align,
-                offset: pos_offset,
-            }),
-            (TypeId::of::<Velocity>(), ComponentInfo {
-                size: vel_size,
-                align: vel_align,
-                offset: vel_offset,
-            }),
-        ];
+    pub fn new(signature: ArchetypeSignature, capacity: usize) -> Self {
+        let columns: Box<[Column]> = signature
+            .components
+            .iter()
+            .map(|&ty| Column::new(ty, capacity))
+            .collect();
+        let row_size = signature.components.iter().map(|ty| ty.size).sum();
 
         Self {
-            signature: ArchetypeSignature::position_velocity(),
+            signature,
             entities: Vec::with_capacity(capacity),
-            storage,
-            storage_layout: layout,
+            columns,
             len: 0,
             capacity,
-            component_info,
             row_size,
             dirty: DirtyTracker::new(capacity),
         }
     }
 
+    /// Creates a new archetype table for Position + Velocity entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Initial capacity (number of entities)
+    #[must_use]
+    pub fn new_position_velocity(capacity: usize) -> Self {
+        Self::new(ArchetypeSignature::position_velocity(), capacity)
+    }
+
     /// Creates a new archetype table for Position-only entities.
     #[must_use]
     pub fn new_position_only(capacity: usize) -> Self {
-        let pos_size = std::mem::size_of::<Position>();
-        let pos_align = std::mem::align_of::<Position>();
-        let row_size = pos_size;
-
-        let total_size = row_size * capacity;
-        let layout = Layout::from_size_align(total_size.max(1), pos_align)
-            .expect("Invalid layout");
-
-        let storage = unsafe {
-            let ptr = alloc(layout);
-            if ptr.is_null() {
-                panic!("Allocation failed for archetype table");
-            }
-            std::ptr::write_bytes(ptr, 0, total_size);
-            NonNull::new_unchecked(ptr)
-        };
-
-        let component_info = vec![
-            (TypeId::of::<Position>(), ComponentInfo {
-                size: pos_size,
-                align: pos_align,
-                offset: 0,
-            }),
-        ];
-
-        Self {
-            signature: ArchetypeSignature::position_only(),
-            entities: Vec::with_capacity(capacity),
-            storage,
-            storage_layout: layout,
-            len: 0,
-            capacity,
-            component_info,
-            row_size,
-            dirty: DirtyTracker::new(capacity),
-        }
+        Self::new(ArchetypeSignature::position_only(), capacity)
     }
 
     /// Returns the signature of this archetype.
@@ -532,20 +709,17 @@ impl ArchetypeTable {
         self.capacity
     }
 
-    /// Gets the row pointer for an entity index.
-    ///
-    /// # Safety
-    ///
-    /// Index must be < len.
+    /// Finds the column storing component `C`.
     #[inline]
-    unsafe fn row_ptr(&self, index: usize) -> *mut u8 {
-        self.storage.as_ptr().add(index * self.row_size)
+    fn column_index<C: Component>(&self) -> Option<usize> {
+        let type_id = TypeId::of::<C>();
+        self.columns.iter().position(|column| column.ty.type_id == type_id)
     }
 
-    /// Adds an entity with Position and Velocity.
+    /// Appends a row for `id` with every component zeroed.
     ///
-    /// Returns the index within this archetype.
-    pub fn add_entity_pv(&mut self, id: EntityId, pos: Position, vel: Velocity) -> usize {
+    /// Returns the index within this archetype. The row is marked dirty.
+    pub fn push(&mut self, id: EntityId) -> usize {
         if self.len >= self.capacity {
             self.grow();
         }
@@ -553,247 +727,369 @@ impl ArchetypeTable {
         let index = self.len;
         self.entities.push(id);
 
-        // SAFETY: We just ensured capacity and index is valid
-        unsafe {
-            let row = self.row_ptr(index);
-
-            // Write Position at offset 0
-            let pos_ptr = row as *mut Position;
-            std::ptr::write(pos_ptr, pos);
-
-            // Write Velocity at its offset
-            let vel_offset = self.component_info[1].1.offset;
-            let vel_ptr = row.add(vel_offset) as *mut Velocity;
-            std::ptr::write(vel_ptr, vel);
+        for column in self.columns.iter_mut() {
+            // SAFETY: We just ensured capacity; zero is a valid Pod value
+            unsafe {
+                std::ptr::write_bytes(column.ptr(index), 0, column.ty.size);
+            }
         }
 
+        self.dirty.mark_dirty(index);
         self.len += 1;
         index
     }
 
-    /// Adds an entity with Position only.
+    /// Appends zeroed rows for `entities`, returning the first new row.
     ///
-    /// Only valid for Position-only archetypes.
-    pub fn add_entity_p(&mut self, id: EntityId, pos: Position) -> usize {
-        if self.len >= self.capacity {
-            self.grow();
+    /// Grows once to fit all of them. The rows are marked dirty.
+    pub(crate) fn push_rows(&mut self, entities: &[EntityId]) -> usize {
+        let start = self.len;
+        let end = start + entities.len();
+        if end > self.capacity {
+            let new_capacity = end.max(self.capacity * 2);
+            for column in self.columns.iter_mut() {
+                column.grow(self.len, new_capacity);
+            }
+            self.dirty.grow(new_capacity);
+            self.capacity = new_capacity;
         }
 
-        let index = self.len;
-        self.entities.push(id);
-
-        // SAFETY: We just ensured capacity and index is valid
-        unsafe {
-            let row = self.row_ptr(index);
-            let pos_ptr = row as *mut Position;
-            std::ptr::write(pos_ptr, pos);
+        self.entities.extend_from_slice(entities);
+        for column in self.columns.iter_mut() {
+            // SAFETY: `end` <= capacity; zero is a valid Pod value
+            unsafe {
+                std::ptr::write_bytes(column.ptr(start), 0, entities.len() * column.ty.size);
+            }
         }
 
-        self.len += 1;
+        self.len = end;
+        self.dirty.mark_range_dirty(start, end);
+        start
+    }
+
+    /// Returns the raw bytes of every stored value of component `type_id`.
+    pub(crate) fn column_bytes(&self, type_id: TypeId) -> Option<&[u8]> {
+        let column = self.columns.iter().find(|column| column.ty.type_id == type_id)?;
+        // SAFETY: The first `len` values are initialized Pod data
+        Some(unsafe { std::slice::from_raw_parts(column.data.as_ptr(), self.len * column.ty.size) })
+    }
+
+    /// Returns the raw bytes of every stored value of component `type_id`
+    /// mutably. Callers mark what they change dirty.
+    pub(crate) fn column_bytes_mut(&mut self, type_id: TypeId) -> Option<&mut [u8]> {
+        let len = self.len;
+        let column = self.columns.iter_mut().find(|column| column.ty.type_id == type_id)?;
+        // SAFETY: As above; any bytes are a valid Pod value
+        Some(unsafe { std::slice::from_raw_parts_mut(column.data.as_ptr(), len * column.ty.size) })
+    }
+
+    /// Adds an entity with Position and Velocity.
+    ///
+    /// Returns the index within this archetype.
+    pub fn add_entity_pv(&mut self, id: EntityId, pos: Position, vel: Velocity) -> usize {
+        let index = self.push(id);
+        self.set(index, pos);
+        self.set(index, vel);
         index
     }
 
-    /// Gets Position for an entity at index.
+    /// Adds an entity with Position only.
+    ///
+    /// Only valid for Position-only archetypes.
+    pub fn add_entity_p(&mut self, id: EntityId, pos: Position) -> usize {
+        let index = self.push(id);
+        self.set(index, pos);
+        index
+    }
+
+    /// Gets component `C` for the entity at index.
     #[inline]
     #[must_use]
-    pub fn get_position(&self, index: usize) -> Option<&Position> {
+    pub fn get<C: Component>(&self, index: usize) -> Option<&C> {
         if index >= self.len {
             return None;
         }
+        let column = &self.columns[self.column_index::<C>()?];
 
-        // SAFETY: Index is bounds-checked
-        unsafe {
-            let row = self.row_ptr(index);
-            Some(&*(row as *const Position))
-        }
+        // SAFETY: Index is bounds-checked, column stores C
+        unsafe { Some(&*column.ptr(index).cast::<C>()) }
     }
 
-    /// Gets mutable Position for an entity at index.
+    /// Gets mutable component `C` for the entity at index.
+    ///
+    /// Marks the value dirty in `C`'s column.
     #[inline]
-    pub fn get_position_mut(&mut self, index: usize) -> Option<&mut Position> {
+    pub fn get_mut<C: Component>(&mut self, index: usize) -> Option<&mut C> {
         if index >= self.len {
             return None;
         }
+        let column_index = self.column_index::<C>()?;
+        let column = &mut self.columns[column_index];
+        column.dirty.mark_dirty(index);
 
-        // SAFETY: Index is bounds-checked
-        unsafe {
-            let row = self.row_ptr(index);
-            Some(&mut *(row as *mut Position))
+        // SAFETY: Index is bounds-checked, column stores C
+        unsafe { Some(&mut *column.ptr(index).cast::<C>()) }
+    }
+
+    /// Sets component `C` for the entity at index.
+    ///
+    /// Returns `false` if the index is out of range or this archetype
+    /// has no `C`.
+    pub fn set<C: Component>(&mut self, index: usize, value: C) -> bool {
+        match self.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                true
+            }
+            None => false,
         }
     }
 
+    /// Returns every `C` in this archetype, in row order.
+    #[must_use]
+    pub fn column<C: Component>(&self) -> Option<&[C]> {
+        let column = &self.columns[self.column_index::<C>()?];
+
+        // SAFETY: Column stores C, len <= capacity
+        unsafe { Some(column.slice(self.len)) }
+    }
+
+    /// Returns every `C` in this archetype mutably, in row order.
+    ///
+    /// Marks the whole column dirty.
+    pub fn column_mut<C: Component>(&mut self) -> Option<&mut [C]> {
+        let column_index = self.column_index::<C>()?;
+        let len = self.len;
+        let column = &mut self.columns[column_index];
+        column.dirty.mark_all_dirty(len);
+
+        // SAFETY: Column stores C, len <= capacity
+        unsafe { Some(column.slice_mut(len)) }
+    }
+
+    /// Gets Position for an entity at index.
+    #[inline]
+    #[must_use]
+    pub fn get_position(&self, index: usize) -> Option<&Position> {
+        self.get(index)
+    }
+
+    /// Gets mutable Position for an entity at index.
+    #[inline]
+    pub fn get_position_mut(&mut self, index: usize) -> Option<&mut Position> {
+        self.get_mut(index)
+    }
+
     /// Gets Velocity for an entity at index (Position+Velocity archetype only).
     #[inline]
     #[must_use]
     pub fn get_velocity(&self, index: usize) -> Option<&Velocity> {
-        if index >= self.len || self.component_info.len() < 2 {
-            return None;
-        }
-
-        // SAFETY: Index is bounds-checked
-        unsafe {
-            let row = self.row_ptr(index);
-            let vel_offset = self.component_info[1].1.offset;
-            Some(&*(row.add(vel_offset) as *const Velocity))
-        }
+        self.get(index)
     }
 
     /// Gets mutable Velocity for an entity at index.
     #[inline]
     pub fn get_velocity_mut(&mut self, index: usize) -> Option<&mut Velocity> {
-        if index >= self.len || self.component_info.len() < 2 {
-            return None;
-        }
-
-        // SAFETY: Index is bounds-checked
-        unsafe {
-            let row = self.row_ptr(index);
-            let vel_offset = self.component_info[1].1.offset;
-            Some(&mut *(row.add(vel_offset) as *mut Velocity))
-        }
+        self.get_mut(index)
     }
 
     /// Iterates over all Position components.
     ///
-    /// This is CACHE-FRIENDLY - positions are stored contiguously within rows.
+    /// This is CACHE-FRIENDLY - positions are stored contiguously.
     pub fn iter_positions(&self) -> impl Iterator<Item = &Position> {
-        (0..self.len).map(move |i| {
-            // SAFETY: i < len, so this is valid
-            unsafe {
-                let row = self.row_ptr(i);
-                &*(row as *const Position)
-            }
-        })
+        self.column::<Position>().unwrap_or_default().iter()
     }
 
     /// Iterates mutably over all Position components.
     pub fn iter_positions_mut(&mut self) -> impl Iterator<Item = &mut Position> {
-        let row_size = self.row_size;
-        let storage = self.storage.as_ptr();
+        self.column_mut::<Position>().unwrap_or_default().iter_mut()
+    }
+
+    /// Splits out the Position column (marked dirty) and Velocity column.
+    fn position_velocity_mut(&mut self) -> Option<(&mut [Position], &[Velocity])> {
+        let pos_index = self.column_index::<Position>()?;
+        let vel_index = self.column_index::<Velocity>()?;
         let len = self.len;
 
-        (0..len).map(move |i| {
-            // SAFETY: i < len, and we have exclusive access
-            unsafe {
-                let row = storage.add(i * row_size);
-                &mut *(row as *mut Position)
-            }
-        })
+        let (pos_column, vel_column) = if pos_index < vel_index {
+            let (left, right) = self.columns.split_at_mut(vel_index);
+            (&mut left[pos_index], &right[0])
+        } else {
+            let (left, right) = self.columns.split_at_mut(pos_index);
+            (&mut right[0], &left[vel_index])
+        };
+        pos_column.dirty.mark_all_dirty(len);
+
+        // SAFETY: Columns store these types, len <= capacity
+        unsafe { Some((pos_column.slice_mut(len), vel_column.slice(len))) }
     }
 
     /// Iterates over (Position, Velocity) pairs.
     ///
-    /// OPTIMIZED: Both components are adjacent in memory.
+    /// # Panics
+    ///
+    /// Panics if this archetype doesn't have both components.
     pub fn iter_position_velocity(&self) -> impl Iterator<Item = (&Position, &Velocity)> {
-        if self.component_info.len() < 2 {
+        let (Some(positions), Some(velocities)) = (self.column::<Position>(), self.column::<Velocity>()) else {
             panic!("This archetype doesn't have Velocity");
-        }
-
-        let vel_offset = self.component_info[1].1.offset;
-
-        (0..self.len).map(move |i| {
-            // SAFETY: i < len, archetype has both components
-            unsafe {
-                let row = self.row_ptr(i);
-                let pos = &*(row as *const Position);
-                let vel = &*(row.add(vel_offset) as *const Velocity);
-                (pos, vel)
-            }
-        })
+        };
+        positions.iter().zip(velocities)
     }
 
     /// Iterates mutably over (Position, Velocity) pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this archetype doesn't have both components.
     pub fn iter_position_velocity_mut(&mut self) -> impl Iterator<Item = (&mut Position, &Velocity)> {
-        if self.component_info.len() < 2 {
+        let Some((positions, velocities)) = self.position_velocity_mut() else {
             panic!("This archetype doesn't have Velocity");
-        }
-
-        let vel_offset = self.component_info[1].1.offset;
-        let row_size = self.row_size;
-        let storage = self.storage.as_ptr();
-        let len = self.len;
-
-        (0..len).map(move |i| {
-            // SAFETY: i < len, we have exclusive access to positions
-            unsafe {
-                let row = storage.add(i * row_size);
-                let pos = &mut *(row as *mut Position);
-                let vel = &*(row.add(vel_offset) as *const Velocity);
-                (pos, vel)
-            }
-        })
+        };
+        positions.iter_mut().zip(velocities)
     }
 
     /// Updates all positions by velocities - OPTIMIZED HOT PATH.
     ///
-    /// This is the critical function. Memory access is linear.
-    /// Marks all entities as dirty (since all positions change).
+    /// This is the critical function. Both columns are walked linearly.
+    /// Marks the whole Position column dirty; velocities are untouched
+    /// and stay clean.
     #[inline]
     pub fn update_positions_by_velocity(&mut self, delta_time: f32) {
-        if self.component_info.len() < 2 {
+        let Some((positions, velocities)) = self.position_velocity_mut() else {
             return;
+        };
+
+        for (pos, vel) in positions.iter_mut().zip(velocities) {
+            pos.x += vel.x * delta_time;
+            pos.y += vel.y * delta_time;
+            pos.z += vel.z * delta_time;
         }
+    }
 
-        let vel_offset = self.component_info[1].1.offset;
-        let row_size = self.row_size;
-        let storage = self.storage.as_ptr();
-        let len = self.len;
+    /// Removes the row at `index` by moving the last row into it.
+    ///
+    /// Returns the entity that now occupies `index`, or `None` if the
+    /// removed row was the last one (or out of range).
+    pub fn swap_remove(&mut self, index: usize) -> Option<EntityId> {
+        if index >= self.len {
+            return None;
+        }
 
-        // Manual loop for maximum control
-        for i in 0..len {
-            // SAFETY: i < len
-            unsafe {
-                let row = storage.add(i * row_size);
-
-                // Prefetch next row (software prefetching) - x86_64 only
-                #[cfg(target_arch = "x86_64")]
-                if i + 4 < len {
-                    let prefetch_row = storage.add((i + 4) * row_size);
-                    // SAFETY: prefetch is a hint, no side effects
-                    #[allow(unsafe_code)]
-                    std::arch::x86_64::_mm_prefetch(
-                        prefetch_row as *const i8,
-                        std::arch::x86_64::_MM_HINT_T0,
-                    );
+        let last = self.len - 1;
+        self.entities.swap_remove(index);
+        if index != last {
+            for column in self.columns.iter_mut() {
+                // SAFETY: Both rows < len, and distinct
+                unsafe {
+                    std::ptr::copy_nonoverlapping(column.ptr(last), column.ptr(index), column.ty.size);
                 }
+            }
+            self.dirty.mark_dirty(index);
+        }
+        self.len = last;
 
-                let pos = &mut *(row as *mut Position);
-                let vel = &*(row.add(vel_offset) as *const Velocity);
+        self.entities.get(index).copied()
+    }
 
-                pos.x += vel.x * delta_time;
-                pos.y += vel.y * delta_time;
-                pos.z += vel.z * delta_time;
+    /// Moves the entity at `index` into `dst`, keeping every component
+    /// both archetypes share. Components only `dst` has are zeroed.
+    ///
+    /// Returns the entity's row in `dst` and the entity swapped into
+    /// `index` here, if any. Returns `None` if `index` is out of range.
+    pub fn move_row(&mut self, index: usize, dst: &mut Self) -> Option<(usize, Option<EntityId>)> {
+        let id = self.entity_at(index)?;
+        let row = dst.push(id);
+
+        for column in self.columns.iter() {
+            if let Some(target) = dst.columns.iter_mut().find(|target| target.ty == column.ty) {
+                // SAFETY: index < self.len, row < dst.len, separate allocations
+                unsafe {
+                    std::ptr::copy_nonoverlapping(column.ptr(index), target.ptr(row), column.ty.size);
+                }
             }
         }
 
-        // Mark all as dirty (physics updates all entities)
-        self.dirty.mark_all_dirty(len);
+        Some((row, self.swap_remove(index)))
     }
 
     // ========================================================================
     // BUFFER SYNCHRONIZATION - The Cold Buffer Solution
     // ========================================================================
 
-    /// Returns the dirty tracker for inspection.
+    /// Returns the row dirty tracker for inspection.
+    ///
+    /// A dirty row is synced in every column; see
+    /// [`column_dirty_tracker`](Self::column_dirty_tracker) for per-component
+    /// changes.
     #[must_use]
     pub fn dirty_tracker(&self) -> &DirtyTracker {
         &self.dirty
     }
 
-    /// Returns mutable dirty tracker.
+    /// Returns mutable row dirty tracker.
     pub fn dirty_tracker_mut(&mut self) -> &mut DirtyTracker {
         &mut self.dirty
     }
 
-    /// Clears all dirty flags.
+    /// Returns the dirty tracker of `C`'s column.
+    #[must_use]
+    pub fn column_dirty_tracker<C: Component>(&self) -> Option<&DirtyTracker> {
+        Some(&self.columns[self.column_index::<C>()?].dirty)
+    }
+
+    /// Raw pointers to `C`'s column data and its dirty tracker, for queries.
+    ///
+    /// Never borrows the table or its column list, so systems on other
+    /// threads can hold pointers into the table's other columns.
+    ///
+    /// # Safety
+    ///
+    /// `table` must point to a live table.
+    pub(super) unsafe fn column_raw<C: Component>(
+        table: *mut Self,
+    ) -> Option<(NonNull<C>, NonNull<DirtyTracker>)> {
+        let type_id = TypeId::of::<C>();
+        let columns = std::ptr::addr_of_mut!(*(*table).columns).cast::<Column>();
+        let count = (*table).signature.len();
+
+        for i in 0..count {
+            let column = columns.add(i);
+            if (*column).ty.type_id == type_id {
+                let dirty = NonNull::new_unchecked(std::ptr::addr_of_mut!((*column).dirty));
+                return Some(((*column).data.cast(), dirty));
+            }
+        }
+        None
+    }
+
+    /// Raw pointer to the row dirty tracker, for queries.
+    ///
+    /// # Safety
+    ///
+    /// `table` must point to a live table.
+    pub(super) unsafe fn dirty_raw(table: *const Self) -> NonNull<DirtyTracker> {
+        NonNull::new_unchecked(std::ptr::addr_of!((*table).dirty).cast_mut())
+    }
+
+    /// Clears all dirty flags (rows and columns).
     pub fn clear_dirty(&mut self) {
         self.dirty.clear();
+        for column in self.columns.iter_mut() {
+            column.dirty.clear();
+        }
+    }
+
+    /// Checks if no row or column has anything marked dirty.
+    fn is_untracked(&self) -> bool {
+        !self.dirty.has_dirty() && self.columns.iter().all(|column| !column.dirty.has_dirty())
     }
 
     /// Syncs dirty entities from another table (SPARSE COPY).
     ///
-    /// This is the key optimization - only copies entities that changed.
-    /// For 5% dirty ratio, this saves 95% of memory bandwidth.
+    /// This is the key optimization - only copies values that changed.
+    /// Each column is copied on its own: rows dirty in the row tracker or
+    /// in that column's tracker. For 5% dirty ratio, this saves 95% of
+    /// memory bandwidth.
     ///
     /// # Arguments
     ///
@@ -801,15 +1097,13 @@ impl ArchetypeTable {
     ///
     /// # Safety Note
     ///
-    /// Both tables must have the same structure (signature, row_size).
+    /// Both tables must have the same structure (signature).
     pub fn sync_dirty_from(&mut self, source: &Self) {
-        debug_assert_eq!(self.row_size, source.row_size, "Table structure mismatch");
+        debug_assert_eq!(self.signature, source.signature, "Table structure mismatch");
 
         // Ensure we have enough capacity
-        if source.len > self.capacity {
-            while self.capacity < source.len {
-                self.grow();
-            }
+        while self.capacity < source.len {
+            self.grow();
         }
 
         // Sync entity list
@@ -817,115 +1111,76 @@ impl ArchetypeTable {
         self.entities.extend_from_slice(&source.entities);
         self.len = source.len;
 
-        let dirty_count = source.dirty.dirty_count();
-        let dirty_ratio = source.dirty.dirty_ratio(source.len);
-
-        // Decision: sparse copy vs full copy
-        // If >50% dirty, full copy is faster (sequential access)
-        if dirty_ratio > 0.5 || dirty_count == 0 {
-            self.sync_full_from(source);
-        } else {
-            self.sync_sparse_from(source);
-        }
-    }
-
-    /// Full SIMD copy - used when >50% entities are dirty.
-    ///
-    /// Uses streaming stores to avoid polluting CPU cache.
-    fn sync_full_from(&mut self, source: &Self) {
         if source.len == 0 {
             return;
         }
 
-        let bytes_to_copy = source.len * source.row_size;
+        // Nothing tracked at all: fall back to a full copy
+        let untracked = source.is_untracked();
 
-        // SAFETY: Both tables have valid storage, same layout
-        unsafe {
-            simd_memcpy(
-                self.storage.as_ptr(),
-                source.storage.as_ptr(),
-                bytes_to_copy,
-            );
+        for (column, source_column) in self.columns.iter_mut().zip(source.columns.iter()) {
+            if untracked {
+                column.copy_all_from(source_column, source.len);
+                continue;
+            }
+
+            // Decision: sparse copy vs full copy
+            // If >50% dirty, full copy is faster (sequential access)
+            let dirty_count = dirty_union(&source.dirty, &source_column.dirty, source.len).count();
+            if dirty_count * 2 > source.len {
+                column.copy_all_from(source_column, source.len);
+            } else if dirty_count > 0 {
+                column.copy_rows_from(
+                    source_column,
+                    dirty_union(&source.dirty, &source_column.dirty, source.len),
+                );
+            }
         }
     }
 
-    /// Sparse copy - only copies dirty entities.
-    ///
-    /// Iterates through dirty bitset and copies individual rows.
-    fn sync_sparse_from(&mut self, source: &Self) {
-        let row_size = self.row_size;
-
-        for dirty_idx in source.dirty.iter_dirty() {
-            if dirty_idx >= source.len {
+    /// Counts rows with anything dirty, in the row tracker or any column.
+    fn dirty_rows(&self) -> usize {
+        let mut count = 0;
+        for (word_idx, &row_word) in self.dirty.bits.iter().enumerate() {
+            let start = word_idx * 64;
+            if start >= self.len {
                 break;
             }
-
-            // SAFETY: dirty_idx < len, same table structure
-            unsafe {
-                let src_row = source.storage.as_ptr().add(dirty_idx * row_size);
-                let dst_row = self.storage.as_ptr().add(dirty_idx * row_size);
-
-                // Copy one row
-                std::ptr::copy_nonoverlapping(src_row, dst_row, row_size);
+            let mut word = self.columns.iter().fold(row_word, |word, column| word | column.dirty.bits[word_idx]);
+            if self.len - start < 64 {
+                word &= (1u64 << (self.len - start)) - 1;
             }
+            count += word.count_ones() as usize;
         }
+        count
     }
 
     /// Reports sync statistics for profiling.
     #[must_use]
     pub fn sync_stats(&self) -> SyncStats {
+        let sparse_copy_bytes = self
+            .columns
+            .iter()
+            .map(|column| dirty_union(&self.dirty, &column.dirty, self.len).count() * column.ty.size)
+            .sum();
+
         SyncStats {
             total_entities: self.len,
-            dirty_entities: self.dirty.dirty_count(),
+            dirty_entities: self.dirty_rows(),
             bytes_per_entity: self.row_size,
             full_copy_bytes: self.len * self.row_size,
-            sparse_copy_bytes: self.dirty.dirty_count() * self.row_size,
+            sparse_copy_bytes,
         }
     }
 
     /// Grows the storage capacity.
     fn grow(&mut self) {
         let new_capacity = (self.capacity * 2).max(64);
-        let new_size = self.row_size * new_capacity;
-        let max_align = self.component_info.iter()
-            .map(|(_, info)| info.align)
-            .max()
-            .unwrap_or(8);
-
-        let new_layout = Layout::from_size_align(new_size, max_align)
-            .expect("Invalid layout");
-
-        // SAFETY: Allocating and copying with valid layouts
-        unsafe {
-            let new_ptr = alloc(new_layout);
-            if new_ptr.is_null() {
-                panic!("Allocation failed during grow");
-            }
-
-            // Copy existing data
-            std::ptr::copy_nonoverlapping(
-                self.storage.as_ptr(),
-                new_ptr,
-                self.len * self.row_size,
-            );
-
-            // Zero the new space
-            std::ptr::write_bytes(
-                new_ptr.add(self.len * self.row_size),
-                0,
-                (new_capacity - self.len) * self.row_size,
-            );
-
-            // Free old allocation
-            dealloc(self.storage.as_ptr(), self.storage_layout);
-
-            self.storage = NonNull::new_unchecked(new_ptr);
-            self.storage_layout = new_layout;
-            self.capacity = new_capacity;
+        for column in self.columns.iter_mut() {
+            column.grow(self.len, new_capacity);
         }
-
-        // Grow the dirty tracker too
-        self.dirty = DirtyTracker::new(new_capacity);
+        self.dirty.grow(new_capacity);
+        self.capacity = new_capacity;
     }
 
     /// Gets the entity ID at an index.
@@ -941,37 +1196,54 @@ impl ArchetypeTable {
     }
 }
 
-impl Drop for ArchetypeTable {
-    fn drop(&mut self) {
-        if self.storage_layout.size() > 0 {
-            // SAFETY: We allocated this memory
-            unsafe {
-                dealloc(self.storage.as_ptr(), self.storage_layout);
-            }
-        }
+/// Archetype index of the Position+Velocity table.
+const PV_ARCHETYPE: usize = 0;
+/// Archetype index of the Position-only table.
+const P_ARCHETYPE: usize = 1;
+/// Initial capacity of archetypes created on demand.
+const DEFAULT_ARCHETYPE_CAPACITY: usize = 64;
+
+/// Borrows two distinct tables mutably.
+fn pair_mut(
+    tables: &mut [ArchetypeTable],
+    a: usize,
+    b: usize,
+) -> (&mut ArchetypeTable, &mut ArchetypeTable) {
+    debug_assert_ne!(a, b, "Cannot borrow the same table twice");
+    if a < b {
+        let (left, right) = tables.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = tables.split_at_mut(a);
+        (&mut right[0], &mut left[b])
     }
 }
 
-// SAFETY: ArchetypeTable is Send because it owns its data
-unsafe impl Send for ArchetypeTable {}
-// SAFETY: ArchetypeTable is Sync when accessed properly
-unsafe impl Sync for ArchetypeTable {}
-
 /// World using Archetype storage.
 ///
 /// Entities are grouped by their component set for cache efficiency.
+/// The Position+Velocity and Position-only archetypes are pre-allocated;
+/// any other combination gets its own table the first time an entity
+/// needs it. Inserting or removing a component moves the entity to the
+/// matching archetype.
 pub struct ArchetypeWorld {
-    /// Position+Velocity entities (most common for moving objects).
-    pub pv_table: ArchetypeTable,
-    /// Position-only entities (static objects like voxels).
-    pub p_table: ArchetypeTable,
+    /// All archetype tables. Tables are only ever appended, so indices
+    /// are stable (0 = Position+Velocity, 1 = Position-only).
+    archetypes: Vec<ArchetypeTable>,
+    /// Archetype index by signature.
+    archetype_index: HashMap<ArchetypeSignature, usize>,
     /// Mapping from EntityId to (archetype_index, row_index).
-    /// 0 = pv_table, 1 = p_table
-    entity_locations: HashMap<EntityId, (u8, usize)>,
-    /// Next entity ID to assign.
+    entity_locations: HashMap<EntityId, (usize, usize)>,
+    /// Next never-used entity index.
     next_id: u64,
+    /// Current generation of every entity index ever handed out.
+    generations: Vec<u32>,
+    /// Despawned indices available for reuse.
+    free_indices: Vec<u32>,
     /// Total alive entities.
     alive_count: usize,
+    /// World-wide singletons and event queues.
+    resources: Resources,
 }
 
 impl ArchetypeWorld {
@@ -983,38 +1255,158 @@ impl ArchetypeWorld {
     /// * `p_capacity` - Capacity for Position-only entities
     #[must_use]
     pub fn new(pv_capacity: usize, p_capacity: usize) -> Self {
-        Self {
-            pv_table: ArchetypeTable::new_position_velocity(pv_capacity),
-            p_table: ArchetypeTable::new_position_only(p_capacity),
+        let mut world = Self {
+            archetypes: Vec::new(),
+            archetype_index: HashMap::new(),
             entity_locations: HashMap::with_capacity(pv_capacity + p_capacity),
             next_id: 1,
+            generations: Vec::with_capacity(pv_capacity + p_capacity + 1),
+            free_indices: Vec::new(),
             alive_count: 0,
-        }
+            resources: Resources::new(),
+        };
+        world.reserve_archetype(ArchetypeSignature::position_velocity(), pv_capacity);
+        world.reserve_archetype(ArchetypeSignature::position_only(), p_capacity);
+        world
     }
 
-    /// Spawns an entity with Position and Velocity.
+    /// Returns the Position+Velocity table.
     #[must_use]
-    pub fn spawn_pv(&mut self, pos: Position, vel: Velocity) -> EntityId {
-        let id = EntityId::new(self.next_id as u32, 0);
+    pub fn pv_table(&self) -> &ArchetypeTable {
+        &self.archetypes[PV_ARCHETYPE]
+    }
+
+    /// Returns the Position+Velocity table mutably.
+    pub fn pv_table_mut(&mut self) -> &mut ArchetypeTable {
+        &mut self.archetypes[PV_ARCHETYPE]
+    }
+
+    /// Returns the Position-only table.
+    #[must_use]
+    pub fn p_table(&self) -> &ArchetypeTable {
+        &self.archetypes[P_ARCHETYPE]
+    }
+
+    /// Returns the Position-only table mutably.
+    pub fn p_table_mut(&mut self) -> &mut ArchetypeTable {
+        &mut self.archetypes[P_ARCHETYPE]
+    }
+
+    /// Returns every archetype table, indexed by archetype.
+    #[must_use]
+    pub fn archetypes(&self) -> &[ArchetypeTable] {
+        &self.archetypes
+    }
+
+    /// Returns every archetype table mutably, for queries.
+    pub(crate) fn archetypes_mut(&mut self) -> &mut [ArchetypeTable] {
+        &mut self.archetypes
+    }
+
+    /// Returns the table for `signature`, if any entity has needed it yet.
+    #[must_use]
+    pub fn archetype(&self, signature: &ArchetypeSignature) -> Option<&ArchetypeTable> {
+        self.archetype_index.get(signature).map(|&index| &self.archetypes[index])
+    }
+
+    /// Returns the archetype index for `signature`, creating its table with
+    /// room for `capacity` entities if it doesn't exist yet.
+    ///
+    /// Use this at startup to pre-allocate archetypes known to be hot.
+    pub fn reserve_archetype(&mut self, signature: ArchetypeSignature, capacity: usize) -> usize {
+        if let Some(&index) = self.archetype_index.get(&signature) {
+            return index;
+        }
+
+        let index = self.archetypes.len();
+        self.archetypes.push(ArchetypeTable::new(signature.clone(), capacity));
+        self.archetype_index.insert(signature, index);
+        index
+    }
+
+    /// Allocates the next entity ID.
+    ///
+    /// Despawned indices are reused first, with the generation bumped at
+    /// despawn so old IDs for the slot stay invalid.
+    fn next_entity_id(&mut self) -> EntityId {
+        if let Some(index) = self.free_indices.pop() {
+            return EntityId::new(index, self.generations[index as usize]);
+        }
+
+        let index = self.next_id as u32;
         self.next_id += 1;
+        if self.generations.len() <= index as usize {
+            self.generations.resize(index as usize + 1, 0);
+        }
+        EntityId::new(index, 0)
+    }
+
+    /// Returns the entity allocator: next fresh index, generation per
+    /// index, and indices free for reuse.
+    pub(crate) fn entity_allocator(&self) -> (u64, &[u32], &[u32]) {
+        (self.next_id, &self.generations, &self.free_indices)
+    }
 
-        let index = self.pv_table.add_entity_pv(id, pos, vel);
-        self.entity_locations.insert(id, (0, index));
+    /// Replaces the entity allocator, e.g. when loading a save.
+    pub(crate) fn restore_entity_allocator(&mut self, next_id: u64, generations: Vec<u32>, free_indices: Vec<u32>) {
+        self.next_id = next_id;
+        self.generations = generations;
+        self.free_indices = free_indices;
+    }
+
+    /// Appends rows for already-allocated `entities` to an archetype and
+    /// tracks them. Returns the first new row.
+    pub(crate) fn push_rows(&mut self, archetype: usize, entities: &[EntityId]) -> usize {
+        let start = self.archetypes[archetype].push_rows(entities);
+        self.entity_locations.reserve(entities.len());
+        for (offset, &id) in entities.iter().enumerate() {
+            self.track(id, archetype, start + offset);
+        }
+        start
+    }
+
+    /// Records a newly spawned entity's location.
+    fn track(&mut self, id: EntityId, archetype: usize, row: usize) {
+        self.entity_locations.insert(id, (archetype, row));
         self.alive_count += 1;
+    }
 
+    /// Spawns an entity with Position and Velocity.
+    #[must_use]
+    pub fn spawn_pv(&mut self, pos: Position, vel: Velocity) -> EntityId {
+        let id = self.next_entity_id();
+        let index = self.archetypes[PV_ARCHETYPE].add_entity_pv(id, pos, vel);
+        self.track(id, PV_ARCHETYPE, index);
         id
     }
 
     /// Spawns an entity with Position only.
     #[must_use]
     pub fn spawn_p(&mut self, pos: Position) -> EntityId {
-        let id = EntityId::new(self.next_id as u32, 0);
-        self.next_id += 1;
+        let id = self.next_entity_id();
+        let index = self.archetypes[P_ARCHETYPE].add_entity_p(id, pos);
+        self.track(id, P_ARCHETYPE, index);
+        id
+    }
 
-        let index = self.p_table.add_entity_p(id, pos);
-        self.entity_locations.insert(id, (1, index));
-        self.alive_count += 1;
+    /// Spawns an entity with no components.
+    ///
+    /// Add components with [`insert`](Self::insert).
+    #[must_use]
+    pub fn spawn_empty(&mut self) -> EntityId {
+        self.spawn_zeroed(ArchetypeSignature::new())
+    }
 
+    /// Spawns an entity with every component of `signature` zeroed.
+    ///
+    /// Inserting those components afterwards writes them in place, where
+    /// inserts after [`spawn_empty`](Self::spawn_empty) move the entity
+    /// once per component.
+    pub(crate) fn spawn_zeroed(&mut self, signature: ArchetypeSignature) -> EntityId {
+        let archetype = self.reserve_archetype(signature, DEFAULT_ARCHETYPE_CAPACITY);
+        let id = self.next_entity_id();
+        let index = self.archetypes[archetype].push(id);
+        self.track(id, archetype, index);
         id
     }
 
@@ -1038,31 +1430,217 @@ impl ArchetypeWorld {
         self.alive_count
     }
 
+    /// Despawns an entity, freeing its slot for reuse.
+    ///
+    /// The last row of its archetype is swapped into the hole, so rows stay
+    /// dense. The index goes on the free list with a new generation, so
+    /// `id` and any copies of it are stale from now on.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity was despawned, `false` if it was already dead
+    /// or the ID was invalid/stale.
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        if self.has::<Hierarchy>(id) {
+            self.unlink(id);
+        }
+        let Some((archetype, index)) = self.entity_locations.remove(&id) else {
+            return false;
+        };
+
+        if let Some(swapped) = self.archetypes[archetype].swap_remove(index) {
+            self.entity_locations.insert(swapped, (archetype, index));
+        }
+
+        let slot = id.index() as usize;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.free_indices.push(id.index());
+        self.alive_count -= 1;
+        true
+    }
+
+    /// Checks if an entity is alive.
+    ///
+    /// Stale IDs (despawned, even if the index was reused) are not alive.
+    #[must_use]
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.entity_locations.contains_key(&id)
+    }
+
+    /// Returns the signature of an entity's archetype.
+    #[must_use]
+    pub fn signature_of(&self, id: EntityId) -> Option<&ArchetypeSignature> {
+        let &(archetype, _) = self.entity_locations.get(&id)?;
+        Some(self.archetypes[archetype].signature())
+    }
+
+    /// Checks if an entity has component `C`.
+    #[must_use]
+    pub fn has<C: Component>(&self, id: EntityId) -> bool {
+        self.signature_of(id).is_some_and(ArchetypeSignature::contains::<C>)
+    }
+
+    /// Gets component `C` for an entity.
+    #[must_use]
+    pub fn get<C: Component>(&self, id: EntityId) -> Option<&C> {
+        let &(archetype, index) = self.entity_locations.get(&id)?;
+        self.archetypes[archetype].get(index)
+    }
+
+    /// Gets mutable component `C` for an entity, marking it dirty.
+    pub fn get_mut<C: Component>(&mut self, id: EntityId) -> Option<&mut C> {
+        let &(archetype, index) = self.entity_locations.get(&id)?;
+        self.archetypes[archetype].get_mut(index)
+    }
+
+    /// Sets component `C` on an entity.
+    ///
+    /// If the entity has no `C` yet it moves to the archetype that adds
+    /// `C` to its current set, creating that archetype if needed.
+    ///
+    /// Returns `false` if the entity doesn't exist.
+    pub fn insert<C: Component>(&mut self, id: EntityId, value: C) -> bool {
+        let Some(&(archetype, index)) = self.entity_locations.get(&id) else {
+            return false;
+        };
+
+        let (archetype, index) = if self.archetypes[archetype].signature().contains::<C>() {
+            (archetype, index)
+        } else {
+            let signature = self.archetypes[archetype].signature().clone().with::<C>();
+            self.move_entity(archetype, index, signature)
+        };
+
+        self.archetypes[archetype].set(index, value)
+    }
+
+    /// Removes component `C` from an entity, moving it to the archetype
+    /// without `C`.
+    ///
+    /// Returns the removed value, or `None` if the entity doesn't exist or
+    /// has no `C`.
+    pub fn remove<C: Component>(&mut self, id: EntityId) -> Option<C> {
+        let &(archetype, index) = self.entity_locations.get(&id)?;
+        let value = *self.archetypes[archetype].get::<C>(index)?;
+
+        let signature = self.archetypes[archetype].signature().clone().without::<C>();
+        self.move_entity(archetype, index, signature);
+        Some(value)
+    }
+
+    /// Moves the entity at `(archetype, index)` to the archetype for
+    /// `signature`, fixing up the location of the entity swapped into its
+    /// old row. Returns the new location.
+    fn move_entity(&mut self, archetype: usize, index: usize, signature: ArchetypeSignature) -> (usize, usize) {
+        let target = self.reserve_archetype(signature, DEFAULT_ARCHETYPE_CAPACITY);
+        let (source, destination) = pair_mut(&mut self.archetypes, archetype, target);
+
+        let id = source.entities[index];
+        let Some((new_index, swapped)) = source.move_row(index, destination) else {
+            return (archetype, index);
+        };
+
+        if let Some(swapped) = swapped {
+            self.entity_locations.insert(swapped, (archetype, index));
+        }
+        self.entity_locations.insert(id, (target, new_index));
+        (target, new_index)
+    }
+
+    /// Stores a resource, returning the previous value of that type.
+    pub fn insert_resource<R: Resource>(&mut self, value: R) -> Option<R> {
+        self.resources.insert(value)
+    }
+
+    /// Removes and returns the resource of type `R`.
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        self.resources.remove()
+    }
+
+    /// Returns the resource of type `R`.
+    #[must_use]
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources.get()
+    }
+
+    /// Returns the resource of type `R` mutably.
+    #[must_use]
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut()
+    }
+
+    /// Returns every resource.
+    #[must_use]
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Returns every resource mutably.
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    /// Registers an event queue for `E`, if there isn't one yet.
+    pub fn add_events<E: Event>(&mut self) {
+        self.resources.add_events::<E>();
+    }
+
+    /// Sends an event. Returns `false` if `E` was never registered.
+    pub fn send_event<E: Event>(&mut self, event: E) -> bool {
+        match self.resources.get_mut::<Events<E>>() {
+            Some(events) => {
+                events.send(event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Starts a new event frame: drops events older than last frame.
+    ///
+    /// The game loop calls this once at the start of every frame.
+    pub fn update_events(&mut self) {
+        self.resources.update_events();
+    }
+
+    /// Returns the resource store without borrowing the rest of the world.
+    ///
+    /// # Safety
+    ///
+    /// `world` must be valid, and nothing may hold `&mut` to the world or
+    /// its resource store while the result is used.
+    pub(crate) unsafe fn resources_raw<'w>(world: *const Self) -> &'w Resources {
+        &*std::ptr::addr_of!((*world).resources)
+    }
+
+    /// Hands this world's resources to `other`.
+    ///
+    /// Resources follow the write buffer across swaps instead of being
+    /// copied, so readers of the other buffer see none.
+    pub(crate) fn swap_resources(&mut self, other: &mut Self) {
+        std::mem::swap(&mut self.resources, &mut other.resources);
+    }
+
     /// Updates all positions by velocities - THE OPTIMIZED HOT PATH.
+    ///
+    /// Covers every archetype with both components, not only the
+    /// Position+Velocity table.
     #[inline]
     pub fn update_positions(&mut self, delta_time: f32) {
-        self.pv_table.update_positions_by_velocity(delta_time);
+        for table in &mut self.archetypes {
+            table.update_positions_by_velocity(delta_time);
+        }
     }
 
     /// Gets Position for an entity.
     #[must_use]
     pub fn get_position(&self, id: EntityId) -> Option<&Position> {
-        let (archetype, index) = self.entity_locations.get(&id)?;
-        match archetype {
-            0 => self.pv_table.get_position(*index),
-            1 => self.p_table.get_position(*index),
-            _ => None,
-        }
+        self.get(id)
     }
 
     /// Gets mutable Position for an entity.
     pub fn get_position_mut(&mut self, id: EntityId) -> Option<&mut Position> {
-        let (archetype, index) = self.entity_locations.get(&id)?.clone();
-        match archetype {
-            0 => self.pv_table.get_position_mut(index),
-            1 => self.p_table.get_position_mut(index),
-            _ => None,
-        }
+        self.get_mut(id)
     }
 
     // ========================================================================
@@ -1076,6 +1654,9 @@ impl ArchetypeWorld {
     /// - Uses SIMD for large copies
     /// - Avoids cache pollution with streaming stores
     ///
+    /// Archetypes the source created since the last sync are created here
+    /// first, so archetype indices stay identical in both worlds.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
@@ -1085,30 +1666,53 @@ impl ArchetypeWorld {
     /// write_buffer.sync_dirty_from(&read_buffer);
     /// ```
     pub fn sync_dirty_from(&mut self, source: &Self) {
-        // Sync the Position+Velocity table
-        self.pv_table.sync_dirty_from(&source.pv_table);
+        // Mirror newly created archetypes
+        let known = self.archetypes.len().min(source.archetypes.len());
+        for table in &source.archetypes[known..] {
+            self.archetypes.push(ArchetypeTable::new(table.signature().clone(), table.capacity()));
+        }
+        if self.archetype_index.len() != source.archetype_index.len() {
+            self.archetype_index.clone_from(&source.archetype_index);
+        }
 
-        // Sync the Position-only table
-        self.p_table.sync_dirty_from(&source.p_table);
+        // Sync every table, column by column
+        for (table, source_table) in self.archetypes.iter_mut().zip(&source.archetypes) {
+            table.sync_dirty_from(source_table);
+        }
 
         // Sync entity locations
         self.entity_locations.clone_from(&source.entity_locations);
         self.next_id = source.next_id;
+        self.generations.clone_from(&source.generations);
+        self.free_indices.clone_from(&source.free_indices);
         self.alive_count = source.alive_count;
     }
 
-    /// Clears all dirty flags in both tables.
+    /// Marks every entity in every archetype dirty, forcing a full sync.
+    pub fn mark_all_dirty(&mut self) {
+        for table in &mut self.archetypes {
+            let len = table.len();
+            table.dirty_tracker_mut().mark_all_dirty(len);
+        }
+    }
+
+    /// Clears all dirty flags in every table.
     pub fn clear_dirty(&mut self) {
-        self.pv_table.clear_dirty();
-        self.p_table.clear_dirty();
+        for table in &mut self.archetypes {
+            table.clear_dirty();
+        }
     }
 
     /// Returns sync statistics for profiling.
     #[must_use]
     pub fn sync_stats(&self) -> WorldSyncStats {
         WorldSyncStats {
-            pv_stats: self.pv_table.sync_stats(),
-            p_stats: self.p_table.sync_stats(),
+            pv_stats: self.archetypes[PV_ARCHETYPE].sync_stats(),
+            p_stats: self.archetypes[P_ARCHETYPE].sync_stats(),
+            other_stats: self.archetypes[P_ARCHETYPE + 1..]
+                .iter()
+                .map(ArchetypeTable::sync_stats)
+                .fold(SyncStats::default(), SyncStats::combine),
         }
     }
 }
@@ -1120,19 +1724,21 @@ pub struct WorldSyncStats {
     pub pv_stats: SyncStats,
     /// Stats for Position-only table.
     pub p_stats: SyncStats,
+    /// Stats for every other archetype, combined.
+    pub other_stats: SyncStats,
 }
 
 impl WorldSyncStats {
     /// Total bytes that would be copied with full sync.
     #[must_use]
     pub fn total_full_bytes(&self) -> usize {
-        self.pv_stats.full_copy_bytes + self.p_stats.full_copy_bytes
+        self.pv_stats.full_copy_bytes + self.p_stats.full_copy_bytes + self.other_stats.full_copy_bytes
     }
 
     /// Total bytes actually copied with sparse sync.
     #[must_use]
     pub fn total_sparse_bytes(&self) -> usize {
-        self.pv_stats.sparse_copy_bytes + self.p_stats.sparse_copy_bytes
+        self.pv_stats.sparse_copy_bytes + self.p_stats.sparse_copy_bytes + self.other_stats.sparse_copy_bytes
     }
 
     /// Overall bandwidth savings.
@@ -1212,4 +1818,168 @@ mod tests {
         let pos = world.get_position(id).unwrap();
         assert!((pos.x - 1.0).abs() < f32::EPSILON);
     }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+    #[repr(C)]
+    struct Health(u32);
+
+    impl Component for Health {
+        const ID: u8 = 40;
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+    #[repr(C)]
+    struct Team(u8);
+
+    impl Component for Team {
+        const ID: u8 = 41;
+    }
+
+    #[test]
+    fn test_signature_order_independent() {
+        let a = ArchetypeSignature::new().with::<Health>().with::<Position>().with::<Team>();
+        let b = ArchetypeSignature::new().with::<Team>().with::<Position>().with::<Health>().with::<Team>();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 3);
+        assert!(a.contains_all(&ArchetypeSignature::position_only()));
+        assert_eq!(a.without::<Team>().without::<Health>(), ArchetypeSignature::position_only());
+    }
+
+    #[test]
+    fn test_insert_and_remove_move_archetypes() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let a = world.spawn_pv(Position::new(1.0, 0.0, 0.0), Velocity::new(1.0, 0.0, 0.0));
+        let b = world.spawn_pv(Position::new(2.0, 0.0, 0.0), Velocity::new(0.0, 0.0, 0.0));
+
+        // Moving `a` out swaps `b` into row 0
+        assert!(world.insert(a, Health(100)));
+        assert_eq!(world.pv_table().len(), 1);
+        assert_eq!(world.pv_table().entity_at(0), Some(b));
+        assert_eq!(world.get_position(b).unwrap().x, 2.0);
+
+        assert!(world.has::<Health>(a));
+        assert_eq!(world.get::<Health>(a), Some(&Health(100)));
+        assert_eq!(world.get_position(a).unwrap().x, 1.0);
+        assert_eq!(world.archetypes().len(), 3);
+
+        // Overwrite in place, then a second component
+        assert!(world.insert(a, Health(50)));
+        assert!(world.insert(a, Team(2)));
+        assert_eq!(world.get::<Health>(a), Some(&Health(50)));
+        assert_eq!(world.get::<Team>(a), Some(&Team(2)));
+
+        // Systems still see moved entities
+        world.update_positions(1.0);
+        assert_eq!(world.get_position(a).unwrap().x, 2.0);
+
+        assert_eq!(world.remove::<Health>(a), Some(Health(50)));
+        assert_eq!(world.remove::<Health>(a), None);
+        assert_eq!(world.get::<Team>(a), Some(&Team(2)));
+        assert_eq!(world.remove::<Team>(a), Some(Team(2)));
+        assert_eq!(world.signature_of(a), Some(&ArchetypeSignature::position_velocity()));
+        assert_eq!(world.pv_table().len(), 2);
+        assert_eq!(world.alive_count(), 2);
+    }
+
+    #[test]
+    fn test_spawn_empty_and_grow() {
+        let mut world = ArchetypeWorld::new(0, 0);
+        let ids: Vec<EntityId> = (0..200).map(|_| world.spawn_empty()).collect();
+        for (i, &id) in ids.iter().enumerate() {
+            assert!(world.insert(id, Health(i as u32)));
+        }
+        let table = world.archetype(&ArchetypeSignature::new().with::<Health>()).unwrap();
+        assert_eq!(table.len(), 200);
+        assert!(table.capacity() >= 200);
+        assert_eq!(world.get::<Health>(ids[150]), Some(&Health(150)));
+        assert!(!world.insert(EntityId::new(9999, 0), Health(1)));
+    }
+
+    #[test]
+    fn test_despawn_swaps_last_row() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let a = world.spawn_pv(Position::new(1.0, 0.0, 0.0), Velocity::default());
+        let b = world.spawn_pv(Position::new(2.0, 0.0, 0.0), Velocity::default());
+        let c = world.spawn_pv(Position::new(3.0, 0.0, 0.0), Velocity::default());
+
+        assert!(world.despawn(a));
+        assert!(!world.despawn(a));
+        assert!(!world.is_alive(a));
+        assert_eq!(world.get_position(a), None);
+        assert_eq!(world.alive_count(), 2);
+
+        // `c` filled the hole
+        assert_eq!(world.pv_table().len(), 2);
+        assert_eq!(world.pv_table().entity_at(0), Some(c));
+        assert_eq!(world.get_position(c).unwrap().x, 3.0);
+        assert_eq!(world.get_position(b).unwrap().x, 2.0);
+
+        // Despawning the last row moves nothing
+        assert!(world.despawn(b));
+        assert_eq!(world.get_position(c).unwrap().x, 3.0);
+    }
+
+    #[test]
+    fn test_id_reuse_after_despawn() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let old = world.spawn_pv(Position::new(1.0, 0.0, 0.0), Velocity::default());
+        assert!(world.insert(old, Health(10)));
+        assert!(world.despawn(old));
+
+        let new = world.spawn_p(Position::new(5.0, 0.0, 0.0));
+        assert_eq!(new.index(), old.index());
+        assert_ne!(new.generation(), old.generation());
+
+        // The stale ID sees nothing of the new occupant
+        assert!(!world.is_alive(old));
+        assert_eq!(world.get_position(old), None);
+        assert!(!world.insert(old, Health(1)));
+        assert!(!world.despawn(old));
+        assert_eq!(world.get_position(new).unwrap().x, 5.0);
+        assert!(!world.has::<Health>(new));
+
+        // A fresh index once the free list is empty
+        let other = world.spawn_empty();
+        assert_ne!(other.index(), new.index());
+    }
+
+    #[test]
+    fn test_dirty_tracking_per_column() {
+        let mut table = ArchetypeTable::new_position_velocity(128);
+        for i in 0..100 {
+            table.add_entity_pv(EntityId::new(i, 0), Position::default(), Velocity::new(1.0, 0.0, 0.0));
+        }
+        table.clear_dirty();
+
+        table.update_positions_by_velocity(1.0);
+        assert_eq!(table.column_dirty_tracker::<Position>().unwrap().dirty_count(), 100);
+        assert_eq!(table.column_dirty_tracker::<Velocity>().unwrap().dirty_count(), 0);
+
+        // Only the Position column would be copied
+        let stats = table.sync_stats();
+        assert_eq!(stats.dirty_entities, 100);
+        assert_eq!(stats.sparse_copy_bytes, 100 * std::mem::size_of::<Position>());
+    }
+
+    #[test]
+    fn test_sync_dirty_from_new_archetypes() {
+        let mut source = ArchetypeWorld::new(16, 16);
+        let mut dest = ArchetypeWorld::new(16, 16);
+
+        let a = source.spawn_pv(Position::new(1.0, 0.0, 0.0), Velocity::default());
+        let b = source.spawn_pv(Position::new(2.0, 0.0, 0.0), Velocity::default());
+        assert!(source.insert(b, Health(7)));
+        dest.sync_dirty_from(&source);
+        source.clear_dirty();
+
+        assert_eq!(dest.get::<Health>(b), Some(&Health(7)));
+        assert_eq!(dest.get_position(a).unwrap().x, 1.0);
+        assert_eq!(dest.archetypes().len(), 3);
+
+        // A single column write reaches the other buffer
+        source.get_mut::<Health>(b).unwrap().0 = 3;
+        dest.sync_dirty_from(&source);
+        assert_eq!(dest.get::<Health>(b), Some(&Health(3)));
+        assert_eq!(dest.get_position(b).unwrap().x, 2.0);
+    }
 }
@@ -0,0 +1,434 @@
+//! # Deferred Commands
+//!
+//! Structural changes (spawn, despawn, insert, remove) can't happen while
+//! a query borrows archetype columns. Systems record them into a
+//! [`CommandBuffer`] instead, and the buffer is applied to the world at a
+//! sync point once iteration is over.
+//!
+//! ```text
+//! record (any thread, own buffer)        apply (sync point)
+//! ┌─────────────────────────────┐        ┌──────────────────────────┐
+//! │ commands: [Spawn, Insert,   │  ───►  │ world.spawn_zeroed(sig)  │
+//! │            Despawn, ...]    │        │ world.insert(id, value)  │
+//! │ data:     [component bytes] │        │ world.despawn(id)        │
+//! └─────────────────────────────┘        └──────────────────────────┘
+//! ```
+//!
+//! Components are `Pod`, so values are stored as raw bytes next to a
+//! monomorphized function that writes them back. A spawn is placed
+//! directly in the archetype its inserts add up to, so building an entity
+//! from N components costs one row push rather than N table moves. Both vectors are cleared
+//! but not freed on apply: once a buffer has seen a frame's worth of
+//! commands, recording allocates nothing.
+
+use std::mem::size_of;
+
+use super::archetype::{ArchetypeSignature, ArchetypeWorld};
+use super::component::Component;
+use super::entity::EntityId;
+
+/// Default number of commands preallocated per buffer.
+pub const DEFAULT_COMMAND_CAPACITY: usize = 256;
+
+/// Default bytes of component data preallocated per buffer.
+pub const DEFAULT_COMMAND_DATA_CAPACITY: usize = 4096;
+
+/// Writes a component stored as bytes onto an entity.
+type InsertFn = fn(&mut ArchetypeWorld, EntityId, &[u8]);
+
+/// Adds a component to an archetype signature.
+type WithFn = fn(ArchetypeSignature) -> ArchetypeSignature;
+
+/// Removes a component from an entity.
+type RemoveFn = fn(&mut ArchetypeWorld, EntityId);
+
+/// Entity a command applies to.
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    /// An existing entity.
+    Entity(EntityId),
+    /// The entity created by the most recent `Spawn`.
+    Spawned,
+}
+
+/// One recorded change.
+#[derive(Clone, Copy)]
+enum Command {
+    /// Creates an entity with no components.
+    Spawn,
+    /// Sets a component whose bytes start at `offset` in the data buffer.
+    Insert {
+        /// Entity to modify.
+        target: Target,
+        /// Start of the component bytes.
+        offset: usize,
+        /// Writes the component.
+        insert: InsertFn,
+        /// Adds the component's type to a signature.
+        with: WithFn,
+    },
+    /// Removes a component.
+    Remove {
+        /// Entity to modify.
+        target: Target,
+        /// Removes the component.
+        remove: RemoveFn,
+    },
+    /// Attaches an entity to a parent.
+    SetParent {
+        /// Entity to attach.
+        target: Target,
+        /// New parent.
+        parent: EntityId,
+    },
+    /// Destroys an entity.
+    Despawn(Target),
+    /// Destroys an entity and its descendants.
+    DespawnRecursive(Target),
+}
+
+/// `InsertFn` for component `C`.
+fn insert_erased<C: Component>(world: &mut ArchetypeWorld, id: EntityId, bytes: &[u8]) {
+    let value: C = bytemuck::pod_read_unaligned(&bytes[..size_of::<C>()]);
+    world.insert(id, value);
+}
+
+/// `WithFn` for component `C`.
+fn signature_with<C: Component>(signature: ArchetypeSignature) -> ArchetypeSignature {
+    signature.with::<C>()
+}
+
+/// `RemoveFn` for component `C`.
+fn remove_erased<C: Component>(world: &mut ArchetypeWorld, id: EntityId) {
+    world.remove::<C>(id);
+}
+
+/// Structural world changes recorded for later.
+///
+/// ```rust,ignore
+/// for (id, health) in world.query::<&Health>() {
+///     if health.0 == 0 {
+///         commands.despawn(id);
+///         commands.spawn().insert(Position::new(0.0, 0.0, 0.0)).insert(Corpse);
+///     }
+/// }
+/// commands.apply(&mut world);
+/// ```
+///
+/// Commands apply in recording order. Commands on entities that are gone
+/// by then are skipped, like the world's own `insert`/`despawn`.
+pub struct CommandBuffer {
+    /// Recorded commands.
+    commands: Vec<Command>,
+    /// Component bytes referenced by `Insert` commands.
+    data: Vec<u8>,
+}
+
+impl CommandBuffer {
+    /// Creates a buffer with the default preallocation.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_COMMAND_CAPACITY, DEFAULT_COMMAND_DATA_CAPACITY)
+    }
+
+    /// Creates a buffer with room for `commands` commands and `data_bytes`
+    /// bytes of component data before it has to grow.
+    #[must_use]
+    pub fn with_capacity(commands: usize, data_bytes: usize) -> Self {
+        Self {
+            commands: Vec::with_capacity(commands),
+            data: Vec::with_capacity(data_bytes),
+        }
+    }
+
+    /// Records a spawn; chain `insert` on the result to add components.
+    pub fn spawn(&mut self) -> EntityCommands<'_> {
+        self.commands.push(Command::Spawn);
+        EntityCommands {
+            buffer: self,
+            target: Target::Spawned,
+        }
+    }
+
+    /// Returns a handle recording commands for an existing entity.
+    pub fn entity(&mut self, id: EntityId) -> EntityCommands<'_> {
+        EntityCommands {
+            buffer: self,
+            target: Target::Entity(id),
+        }
+    }
+
+    /// Records setting component `C` on an entity.
+    pub fn insert<C: Component>(&mut self, id: EntityId, value: C) {
+        self.push_insert(Target::Entity(id), value);
+    }
+
+    /// Records removing component `C` from an entity.
+    pub fn remove<C: Component>(&mut self, id: EntityId) {
+        self.push_remove::<C>(Target::Entity(id));
+    }
+
+    /// Records attaching an entity to a parent.
+    pub fn set_parent(&mut self, id: EntityId, parent: EntityId) {
+        self.commands.push(Command::SetParent {
+            target: Target::Entity(id),
+            parent,
+        });
+    }
+
+    /// Records destroying an entity.
+    pub fn despawn(&mut self, id: EntityId) {
+        self.commands.push(Command::Despawn(Target::Entity(id)));
+    }
+
+    /// Records destroying an entity and its descendants.
+    pub fn despawn_recursive(&mut self, id: EntityId) {
+        self.commands.push(Command::DespawnRecursive(Target::Entity(id)));
+    }
+
+    /// Returns the number of recorded commands.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Checks if nothing is recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Drops every recorded command, keeping the storage.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.data.clear();
+    }
+
+    /// Applies every recorded command in order, then clears the buffer.
+    pub fn apply(&mut self, world: &mut ArchetypeWorld) {
+        let mut spawned = None;
+        let resolve = |target, spawned: Option<EntityId>| match target {
+            Target::Entity(id) => Some(id),
+            Target::Spawned => spawned,
+        };
+
+        for (at, &command) in self.commands.iter().enumerate() {
+            match command {
+                Command::Spawn => {
+                    // The inserts right after the spawn then write in place.
+                    let signature = self.commands[at + 1..]
+                        .iter()
+                        .map_while(|command| match *command {
+                            Command::Insert { target: Target::Spawned, with, .. } => Some(with),
+                            _ => None,
+                        })
+                        .fold(ArchetypeSignature::new(), |signature, with| with(signature));
+                    spawned = Some(world.spawn_zeroed(signature));
+                }
+                Command::Insert { target, offset, insert, .. } => {
+                    if let Some(id) = resolve(target, spawned) {
+                        insert(world, id, &self.data[offset..]);
+                    }
+                }
+                Command::Remove { target, remove } => {
+                    if let Some(id) = resolve(target, spawned) {
+                        remove(world, id);
+                    }
+                }
+                Command::SetParent { target, parent } => {
+                    if let Some(id) = resolve(target, spawned) {
+                        world.set_parent(id, parent);
+                    }
+                }
+                Command::Despawn(target) => {
+                    if let Some(id) = resolve(target, spawned) {
+                        world.despawn(id);
+                    }
+                }
+                Command::DespawnRecursive(target) => {
+                    if let Some(id) = resolve(target, spawned) {
+                        world.despawn_recursive(id);
+                    }
+                }
+            }
+        }
+
+        self.clear();
+    }
+
+    /// Appends an `Insert` and its component bytes.
+    fn push_insert<C: Component>(&mut self, target: Target, value: C) {
+        let offset = self.data.len();
+        self.data.extend_from_slice(bytemuck::bytes_of(&value));
+        self.commands.push(Command::Insert {
+            target,
+            offset,
+            insert: insert_erased::<C>,
+            with: signature_with::<C>,
+        });
+    }
+
+    /// Appends a `Remove`.
+    fn push_remove<C: Component>(&mut self, target: Target) {
+        self.commands.push(Command::Remove {
+            target,
+            remove: remove_erased::<C>,
+        });
+    }
+}
+
+impl Default for CommandBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Records commands for one entity, existing or about to be spawned.
+pub struct EntityCommands<'a> {
+    /// Buffer being recorded into.
+    buffer: &'a mut CommandBuffer,
+    /// Entity the commands apply to.
+    target: Target,
+}
+
+impl EntityCommands<'_> {
+    /// Records setting component `C`.
+    pub fn insert<C: Component>(&mut self, value: C) -> &mut Self {
+        self.buffer.push_insert(self.target, value);
+        self
+    }
+
+    /// Records removing component `C`.
+    pub fn remove<C: Component>(&mut self) -> &mut Self {
+        self.buffer.push_remove::<C>(self.target);
+        self
+    }
+
+    /// Records attaching the entity to a parent.
+    pub fn set_parent(&mut self, parent: EntityId) -> &mut Self {
+        self.buffer.commands.push(Command::SetParent {
+            target: self.target,
+            parent,
+        });
+        self
+    }
+
+    /// Records destroying the entity.
+    pub fn despawn(&mut self) {
+        self.buffer.commands.push(Command::Despawn(self.target));
+    }
+
+    /// Records destroying the entity and its descendants.
+    pub fn despawn_recursive(&mut self) {
+        self.buffer.commands.push(Command::DespawnRecursive(self.target));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Position, Velocity};
+    use bytemuck::{Pod, Zeroable};
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+    #[repr(C)]
+    struct Health(u32);
+
+    impl Component for Health {
+        const ID: u8 = 40;
+    }
+
+    #[test]
+    fn test_apply_in_order() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let doomed = world.spawn_p(Position::new(1.0, 0.0, 0.0));
+        let target = world.spawn_pv(Position::new(2.0, 0.0, 0.0), Velocity::new(1.0, 0.0, 0.0));
+
+        let mut commands = CommandBuffer::new();
+        commands.despawn(doomed);
+        commands.insert(target, Health(7));
+        commands.remove::<Velocity>(target);
+        commands
+            .spawn()
+            .insert(Position::new(3.0, 0.0, 0.0))
+            .insert(Health(3));
+        assert_eq!(commands.len(), 6);
+
+        // Nothing happens until applied
+        assert!(world.is_alive(doomed));
+        commands.apply(&mut world);
+        assert!(commands.is_empty());
+
+        assert!(!world.is_alive(doomed));
+        assert_eq!(world.get::<Health>(target), Some(&Health(7)));
+        assert!(!world.has::<Velocity>(target));
+        assert_eq!(world.alive_count(), 2);
+
+        let spawned: Vec<_> = world.query::<(&Position, &Health)>().collect();
+        assert_eq!(spawned.len(), 2);
+        assert!(spawned.iter().any(|(_, (pos, hp))| pos.x == 3.0 && hp.0 == 3));
+    }
+
+    #[test]
+    fn test_spawn_goes_straight_to_final_archetype() {
+        let mut world = ArchetypeWorld::new(16, 16);
+
+        let mut commands = CommandBuffer::new();
+        commands.spawn().insert(Health(3)).insert(Velocity::new(1.0, 0.0, 0.0));
+        commands.apply(&mut world);
+
+        let id = world.query::<&Health>().next().unwrap().0;
+        assert_eq!(world.get::<Health>(id), Some(&Health(3)));
+        assert_eq!(world.get::<Velocity>(id).map(|v| v.x), Some(1.0));
+
+        // No stop-over archetypes were created on the way
+        assert!(world.archetype(&ArchetypeSignature::new()).is_none());
+        assert!(world.archetype(&ArchetypeSignature::new().with::<Health>()).is_none());
+    }
+
+    #[test]
+    fn test_dead_targets_are_skipped() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let id = world.spawn_p(Position::default());
+
+        let mut commands = CommandBuffer::new();
+        commands.despawn(id);
+        commands.insert(id, Health(1));
+        commands.entity(id).despawn();
+        commands.apply(&mut world);
+
+        assert_eq!(world.alive_count(), 0);
+    }
+
+    #[test]
+    fn test_hierarchy_commands() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let rider = world.spawn_p(Position::default());
+
+        let mut commands = CommandBuffer::new();
+        commands.spawn().insert(Health(1)).set_parent(rider);
+        commands.apply(&mut world);
+        assert_eq!(world.children(rider).count(), 1);
+
+        commands.despawn_recursive(rider);
+        commands.apply(&mut world);
+        assert_eq!(world.alive_count(), 0);
+    }
+
+    #[test]
+    fn test_storage_is_reused() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let mut commands = CommandBuffer::with_capacity(8, 64);
+
+        for _ in 0..3 {
+            for _ in 0..2 {
+                commands.spawn().insert(Health(1));
+            }
+            commands.apply(&mut world);
+        }
+
+        assert_eq!(commands.commands.capacity(), 8);
+        assert_eq!(commands.data.capacity(), 64);
+        assert_eq!(world.alive_count(), 6);
+    }
+}
@@ -4,12 +4,14 @@
 //! - An index into component arrays
 //! - A generation counter for safe reuse
 
+use bytemuck::{Pod, Zeroable};
+
 /// Unique identifier for an entity.
 ///
 /// The ID is split into two parts:
 /// - Lower 32 bits: Index into component arrays
 /// - Upper 32 bits: Generation counter for detecting stale references
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Pod, Zeroable)]
 #[repr(transparent)]
 pub struct EntityId(u64);
 
@@ -40,6 +42,20 @@ impl EntityId {
         (self.0 >> 32) as u32
     }
 
+    /// Returns the packed 64-bit representation.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Rebuilds an ID from [`to_bits`](Self::to_bits).
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
     /// Null/invalid entity ID.
     pub const NULL: Self = Self(u64::MAX);
 
@@ -0,0 +1,153 @@
+//! # Events
+//!
+//! Double-buffered typed event queues, stored as resources.
+//!
+//! ```text
+//!  frame N        frame N+1       frame N+2
+//! ┌─────────┐    ┌─────────┐     ┌─────────┐
+//! │ current │ ─► │previous │ ──► │ dropped │
+//! └─────────┘    └─────────┘     └─────────┘
+//! ```
+//!
+//! An event sent in frame N can be read by any system later in frame N or
+//! anywhere in frame N+1, so readers running before the writer still see
+//! it. Each reader keeps an [`EventCursor`] so it sees every event once.
+
+/// Marker for types sendable as events.
+///
+/// Implemented for every `Send + Sync + 'static` type.
+pub trait Event: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Event for T {}
+
+/// Default events preallocated per buffer.
+const DEFAULT_EVENT_CAPACITY: usize = 64;
+
+/// Events of type `E` from this frame and the last.
+pub struct Events<E> {
+    /// Events sent last frame.
+    previous: Vec<E>,
+    /// Events sent this frame.
+    current: Vec<E>,
+    /// Sequence number of `previous[0]`.
+    previous_start: u64,
+}
+
+impl<E: Event> Events<E> {
+    /// Creates an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::with_capacity(DEFAULT_EVENT_CAPACITY),
+            current: Vec::with_capacity(DEFAULT_EVENT_CAPACITY),
+            previous_start: 0,
+        }
+    }
+
+    /// Sends an event.
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    /// Drops last frame's events and starts a new frame.
+    ///
+    /// Called once per frame through
+    /// [`ArchetypeWorld::update_events`](super::ArchetypeWorld::update_events).
+    /// Storage is swapped, not reallocated.
+    pub fn update(&mut self) {
+        self.previous_start += self.previous.len() as u64;
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+
+    /// Returns the events sent this frame.
+    #[must_use]
+    pub fn current(&self) -> &[E] {
+        &self.current
+    }
+
+    /// Iterates this frame's and last frame's events, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.previous.iter().chain(&self.current)
+    }
+
+    /// Returns the number of readable events.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    /// Checks if there are no readable events.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the sequence number the next sent event will get.
+    fn end(&self) -> u64 {
+        self.previous_start + self.len() as u64
+    }
+}
+
+impl<E: Event> Default for Events<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reader's position in an [`Events`] queue.
+///
+/// Keep one per reading system (e.g. captured in its closure).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EventCursor {
+    /// Sequence number of the next unread event.
+    next: u64,
+}
+
+impl EventCursor {
+    /// Creates a cursor that will see every event still in the queue.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { next: 0 }
+    }
+
+    /// Iterates events not yet seen by this cursor.
+    ///
+    /// Events older than last frame are gone; a reader that skips two
+    /// frames misses them.
+    pub fn read<'a, E: Event>(&mut self, events: &'a Events<E>) -> impl Iterator<Item = &'a E> {
+        let skip = self.next.saturating_sub(events.previous_start) as usize;
+        self.next = events.end();
+        events.iter().skip(skip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_live_two_frames() {
+        let mut events = Events::new();
+        let mut cursor = EventCursor::new();
+
+        events.send(1u32);
+        events.send(2);
+        assert_eq!(cursor.read(&events).copied().collect::<Vec<_>>(), [1, 2]);
+
+        // Next frame: still readable, but not twice by the same reader
+        events.update();
+        events.send(3);
+        assert_eq!(events.len(), 3);
+        assert_eq!(cursor.read(&events).copied().collect::<Vec<_>>(), [3]);
+
+        // A late reader still sees last frame's events
+        let mut late = EventCursor::new();
+        events.update();
+        assert_eq!(late.read(&events).copied().collect::<Vec<_>>(), [3]);
+
+        events.update();
+        assert!(events.is_empty());
+        assert_eq!(cursor.read(&events).count(), 0);
+    }
+}
@@ -0,0 +1,504 @@
+//! # Entity Hierarchy
+//!
+//! Parent/child links between entities and transform propagation.
+//!
+//! ```text
+//!        player              Hierarchy links (intrusive, all Pod):
+//!        ┌──────┐
+//!        │ root │            parent ──────────► player
+//!        └──┬───┘            first_child ─────► sword
+//!     ┌─────┴─────┐          next/prev_sibling  sword ◄──► shield
+//!  ┌──┴──┐     ┌──┴───┐
+//!  │sword│     │shield│      GlobalTransform = parent global × LocalTransform
+//!  └─────┘     └──────┘
+//! ```
+//!
+//! Links live in a [`Hierarchy`] component, so children need no
+//! allocation. [`TransformPropagator`] recomputes [`GlobalTransform`]s
+//! parents-first, but only for subtrees whose rows were touched since the
+//! last buffer swap.
+//!
+//! `Position` is the world-space translation the rest of the engine reads.
+//! A root's `Position` overrides its local translation; a child's
+//! `Position`, if it has one, is overwritten with its global translation.
+
+use std::collections::HashSet;
+
+use bytemuck::{Pod, Zeroable};
+use oroboros_shared::math::{Transform, Vec3};
+
+use super::archetype::ArchetypeWorld;
+use super::component::{Component, Position};
+use super::entity::EntityId;
+
+/// Hierarchy links of an entity.
+///
+/// Managed by [`ArchetypeWorld::set_parent`] and friends; don't edit the
+/// links by hand. A zeroed link reads as no link (entity index 0 is never
+/// handed out), so zero-filled rows are roots.
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct Hierarchy {
+    /// Parent, or null for a root.
+    pub parent: EntityId,
+    /// First child, or null.
+    pub first_child: EntityId,
+    /// Next child of the same parent, or null.
+    pub next_sibling: EntityId,
+    /// Previous child of the same parent, or null.
+    pub prev_sibling: EntityId,
+    /// Distance from the root (roots are 0).
+    pub depth: u32,
+    /// Padding for alignment.
+    pub _padding: u32,
+}
+
+impl Default for Hierarchy {
+    fn default() -> Self {
+        Self {
+            parent: EntityId::NULL,
+            first_child: EntityId::NULL,
+            next_sibling: EntityId::NULL,
+            prev_sibling: EntityId::NULL,
+            depth: 0,
+            _padding: 0,
+        }
+    }
+}
+
+impl Component for Hierarchy {
+    const ID: u8 = 3;
+}
+
+/// Transform relative to the parent (or the world, for roots).
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct LocalTransform(pub Transform);
+
+impl Default for LocalTransform {
+    fn default() -> Self {
+        Self(Transform::IDENTITY)
+    }
+}
+
+impl Component for LocalTransform {
+    const ID: u8 = 4;
+}
+
+/// World-space transform, written by [`TransformPropagator`].
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct GlobalTransform(pub Transform);
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        Self(Transform::IDENTITY)
+    }
+}
+
+impl Component for GlobalTransform {
+    const ID: u8 = 5;
+}
+
+/// Iterator over an entity's children, most recently attached first.
+pub struct Children<'w> {
+    /// The world.
+    world: &'w ArchetypeWorld,
+    /// Next child to yield.
+    next: EntityId,
+}
+
+impl Iterator for Children<'_> {
+    type Item = EntityId;
+
+    fn next(&mut self) -> Option<EntityId> {
+        let current = self.next;
+        if !is_link(current) {
+            return None;
+        }
+        let links = self.world.get::<Hierarchy>(current)?;
+        self.next = links.next_sibling;
+        Some(current)
+    }
+}
+
+impl ArchetypeWorld {
+    /// Returns an entity's parent.
+    #[must_use]
+    pub fn parent(&self, id: EntityId) -> Option<EntityId> {
+        self.get::<Hierarchy>(id).map(|links| links.parent).filter(|&parent| is_link(parent))
+    }
+
+    /// Iterates an entity's direct children.
+    #[must_use]
+    pub fn children(&self, id: EntityId) -> Children<'_> {
+        let next = self.get::<Hierarchy>(id).map_or(EntityId::NULL, |links| links.first_child);
+        Children { world: self, next }
+    }
+
+    /// Makes `child` a child of `parent`, detaching it from any previous
+    /// parent. Adds [`Hierarchy`] to either entity if missing.
+    ///
+    /// Returns `false` (and changes nothing) if either entity is dead or
+    /// the link would create a cycle.
+    pub fn set_parent(&mut self, child: EntityId, parent: EntityId) -> bool {
+        if !self.is_alive(child) || !self.is_alive(parent) {
+            return false;
+        }
+
+        // `parent` must not be `child` or one of its descendants
+        let mut ancestor = Some(parent);
+        while let Some(id) = ancestor {
+            if id == child {
+                return false;
+            }
+            ancestor = self.parent(id);
+        }
+
+        self.detach(child);
+        for id in [child, parent] {
+            if !self.has::<Hierarchy>(id) {
+                self.insert(id, Hierarchy::default());
+            }
+        }
+
+        let (first, depth) = match self.links_mut(parent) {
+            Some(links) => {
+                let first = links.first_child;
+                links.first_child = child;
+                (first, links.depth + 1)
+            }
+            None => (EntityId::NULL, 1),
+        };
+        if let Some(links) = self.links_mut(first) {
+            links.prev_sibling = child;
+        }
+        if let Some(links) = self.links_mut(child) {
+            links.parent = parent;
+            links.next_sibling = first;
+        }
+
+        self.set_subtree_depth(child, depth);
+        true
+    }
+
+    /// Detaches `child` from its parent, making it a root.
+    ///
+    /// Returns `false` if it had no parent.
+    pub fn remove_parent(&mut self, child: EntityId) -> bool {
+        if self.parent(child).is_none() {
+            return false;
+        }
+        self.detach(child);
+        self.set_subtree_depth(child, 0);
+        true
+    }
+
+    /// Despawns an entity and all its descendants.
+    ///
+    /// Returns the number of entities despawned.
+    pub fn despawn_recursive(&mut self, id: EntityId) -> usize {
+        if !self.is_alive(id) {
+            return 0;
+        }
+
+        // Pre-order, so reversed every child goes before its parent and
+        // each despawn only has to unlink a leaf
+        let mut subtree = vec![id];
+        let mut i = 0;
+        while i < subtree.len() {
+            let children = self.children(subtree[i]);
+            subtree.extend(children);
+            i += 1;
+        }
+
+        subtree.into_iter().rev().filter(|&id| self.despawn(id)).count()
+    }
+
+    /// Removes a despawning entity from the hierarchy. Its children become
+    /// roots.
+    pub(super) fn unlink(&mut self, id: EntityId) {
+        self.detach(id);
+
+        let Some(links) = self.links_mut(id) else {
+            return;
+        };
+        let mut child = std::mem::replace(&mut links.first_child, EntityId::NULL);
+        while let Some(links) = self.links_mut(child) {
+            let next = links.next_sibling;
+            links.parent = EntityId::NULL;
+            links.prev_sibling = EntityId::NULL;
+            links.next_sibling = EntityId::NULL;
+            self.set_subtree_depth(child, 0);
+            child = next;
+        }
+    }
+
+    /// Unlinks `id` from its parent and siblings.
+    fn detach(&mut self, id: EntityId) {
+        let Some(&links) = self.get::<Hierarchy>(id) else {
+            return;
+        };
+        if !is_link(links.parent) {
+            return;
+        }
+
+        let prev = if is_link(links.prev_sibling) { links.prev_sibling } else { EntityId::NULL };
+        if prev.is_null() {
+            if let Some(parent) = self.links_mut(links.parent) {
+                parent.first_child = links.next_sibling;
+            }
+        } else if let Some(sibling) = self.links_mut(prev) {
+            sibling.next_sibling = links.next_sibling;
+        }
+        if let Some(sibling) = self.links_mut(links.next_sibling) {
+            sibling.prev_sibling = prev;
+        }
+
+        if let Some(links) = self.links_mut(id) {
+            links.parent = EntityId::NULL;
+            links.prev_sibling = EntityId::NULL;
+            links.next_sibling = EntityId::NULL;
+        }
+    }
+
+    /// Sets the depth of `root` and everything below it.
+    fn set_subtree_depth(&mut self, root: EntityId, depth: u32) {
+        let mut stack = vec![(root, depth)];
+        while let Some((id, depth)) = stack.pop() {
+            if let Some(links) = self.links_mut(id) {
+                links.depth = depth;
+            }
+            stack.extend(self.children(id).map(|child| (child, depth + 1)));
+        }
+    }
+
+    /// Links of the entity a link points to, or `None` if it points
+    /// nowhere or at an entity without [`Hierarchy`].
+    fn links_mut(&mut self, id: EntityId) -> Option<&mut Hierarchy> {
+        if !is_link(id) {
+            return None;
+        }
+        self.get_mut::<Hierarchy>(id)
+    }
+}
+
+/// Whether a stored link points at an entity. Both [`EntityId::NULL`] and
+/// the zeroed ID mean no link.
+const fn is_link(id: EntityId) -> bool {
+    !id.is_null() && id.index() != 0
+}
+
+/// Recomputes [`GlobalTransform`]s for subtrees that changed.
+///
+/// Call [`propagate`](Self::propagate) on the write buffer once per frame,
+/// after logic and before the swap. A subtree is recomputed when any row
+/// in it has a dirty [`LocalTransform`], [`Hierarchy`] or `Position`, or
+/// was moved this frame. Scratch storage is kept between calls.
+#[derive(Default)]
+pub struct TransformPropagator {
+    /// Dirty entities with their depth.
+    dirty: Vec<(u32, EntityId)>,
+    /// Entities recomputed this call.
+    done: HashSet<EntityId>,
+    /// Entities still to visit, with their parent's global transform.
+    stack: Vec<(EntityId, Option<Transform>)>,
+}
+
+impl TransformPropagator {
+    /// Creates a propagator.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recomputes every dirty subtree, parents first.
+    ///
+    /// Returns the number of entities recomputed.
+    pub fn propagate(&mut self, world: &mut ArchetypeWorld) -> usize {
+        self.dirty.clear();
+        self.done.clear();
+
+        for table in world.archetypes() {
+            let signature = table.signature();
+            if !signature.contains::<Hierarchy>() && !signature.contains::<LocalTransform>() {
+                continue;
+            }
+
+            let depths = table.column::<Hierarchy>();
+            let trackers = [
+                Some(table.dirty_tracker()),
+                table.column_dirty_tracker::<LocalTransform>(),
+                table.column_dirty_tracker::<Hierarchy>(),
+                table.column_dirty_tracker::<Position>(),
+            ];
+            for row in trackers.into_iter().flatten().flat_map(|tracker| tracker.iter_dirty()) {
+                if row < table.len() {
+                    let depth = depths.map_or(0, |links| links[row].depth);
+                    self.dirty.push((depth, table.entities()[row]));
+                }
+            }
+        }
+        self.dirty.sort_unstable_by_key(|&(depth, _)| depth);
+
+        for i in 0..self.dirty.len() {
+            let (_, id) = self.dirty[i];
+            if self.done.contains(&id) {
+                // Already recomputed with a dirty ancestor
+                continue;
+            }
+            let parent_global = world.parent(id).map(|parent| {
+                world.get::<GlobalTransform>(parent).map_or(Transform::IDENTITY, |global| global.0)
+            });
+            self.stack.push((id, parent_global));
+
+            while let Some((id, parent_global)) = self.stack.pop() {
+                if !self.done.insert(id) {
+                    continue;
+                }
+                let local = world.get::<LocalTransform>(id).copied().unwrap_or_default().0;
+                let global = match parent_global {
+                    Some(parent) => {
+                        let global = parent.mul_transform(local);
+                        if let Some(pos) = world.get_mut::<Position>(id) {
+                            *pos = Position::new(global.position.x, global.position.y, global.position.z);
+                        }
+                        global
+                    }
+                    None => match world.get::<Position>(id) {
+                        Some(pos) => Transform {
+                            position: Vec3::new(pos.x, pos.y, pos.z),
+                            ..local
+                        },
+                        None => local,
+                    },
+                };
+
+                world.insert(id, GlobalTransform(global));
+                self.stack.extend(world.children(id).map(|child| (child, Some(global))));
+            }
+        }
+
+        self.done.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oroboros_shared::math::Quaternion;
+
+    fn local(x: f32, y: f32, z: f32) -> LocalTransform {
+        LocalTransform(Transform::new(Vec3::new(x, y, z), Quaternion::IDENTITY, 1.0))
+    }
+
+    fn global_position(world: &ArchetypeWorld, id: EntityId) -> Vec3 {
+        world.get::<GlobalTransform>(id).unwrap().0.position
+    }
+
+    #[test]
+    fn test_links_and_cycles() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let a = world.spawn_empty();
+        let b = world.spawn_empty();
+        let c = world.spawn_empty();
+
+        assert!(world.set_parent(b, a));
+        assert!(world.set_parent(c, a));
+        assert!(!world.set_parent(a, c), "cycle");
+        assert!(!world.set_parent(a, a), "self parent");
+        assert_eq!(world.children(a).collect::<Vec<_>>(), [c, b]);
+
+        // Reparent c under b
+        assert!(world.set_parent(c, b));
+        assert_eq!(world.children(a).collect::<Vec<_>>(), [b]);
+        assert_eq!(world.parent(c), Some(b));
+        assert_eq!(world.get::<Hierarchy>(c).unwrap().depth, 2);
+
+        assert!(world.remove_parent(b));
+        assert!(!world.remove_parent(b));
+        assert_eq!(world.children(a).count(), 0);
+        assert_eq!(world.get::<Hierarchy>(c).unwrap().depth, 1);
+
+        // Plain despawn orphans children
+        assert!(world.despawn(b));
+        assert_eq!(world.parent(c), None);
+        assert_eq!(world.get::<Hierarchy>(c).unwrap().depth, 0);
+    }
+
+    #[test]
+    fn test_despawn_recursive() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let root = world.spawn_empty();
+        let keep = world.spawn_empty();
+        let child = world.spawn_empty();
+        let grandchild = world.spawn_empty();
+        assert!(world.set_parent(keep, root));
+        assert!(world.set_parent(child, keep));
+        assert!(world.set_parent(grandchild, child));
+
+        assert_eq!(world.despawn_recursive(child), 2);
+        assert!(!world.is_alive(grandchild));
+        assert!(world.is_alive(keep));
+        assert_eq!(world.children(keep).count(), 0);
+        assert_eq!(world.despawn_recursive(child), 0);
+    }
+
+    #[test]
+    fn test_zeroed_links_are_roots() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let zeroed = world.spawn_empty();
+        let child = world.spawn_empty();
+        assert!(world.insert(zeroed, Hierarchy::zeroed()));
+        assert_eq!(world.parent(zeroed), None);
+        assert_eq!(world.children(zeroed).count(), 0);
+        assert!(!world.remove_parent(zeroed));
+
+        assert!(world.set_parent(child, zeroed));
+        assert_eq!(world.children(zeroed).collect::<Vec<_>>(), [child]);
+        assert!(world.despawn(zeroed));
+        assert_eq!(world.parent(child), None);
+
+        let lone = world.spawn_empty();
+        assert!(world.insert(lone, Hierarchy::zeroed()));
+        assert_eq!(world.despawn_recursive(lone), 1);
+    }
+
+    #[test]
+    fn test_propagation_recomputes_only_dirty_subtrees() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let mut propagator = TransformPropagator::new();
+
+        let player = world.spawn_p(Position::new(10.0, 0.0, 0.0));
+        let sword = world.spawn_p(Position::default());
+        let gem = world.spawn_empty();
+        let bystander = world.spawn_empty();
+        assert!(world.insert(player, LocalTransform::default()));
+        assert!(world.insert(sword, local(1.0, 0.0, 0.0)));
+        assert!(world.insert(gem, local(0.0, 0.5, 0.0)));
+        assert!(world.insert(bystander, local(-5.0, 0.0, 0.0)));
+        assert!(world.set_parent(sword, player));
+        assert!(world.set_parent(gem, sword));
+
+        assert_eq!(propagator.propagate(&mut world), 4);
+        assert_eq!(global_position(&world, gem), Vec3::new(11.0, 0.5, 0.0));
+        assert_eq!(world.get_position(sword), Some(&Position::new(11.0, 0.0, 0.0)));
+        assert_eq!(global_position(&world, bystander), Vec3::new(-5.0, 0.0, 0.0));
+
+        // Moving the player recomputes its subtree, not the bystander
+        world.clear_dirty();
+        world.get_position_mut(player).unwrap().x = 20.0;
+        assert_eq!(propagator.propagate(&mut world), 3);
+        assert_eq!(global_position(&world, gem), Vec3::new(21.0, 0.5, 0.0));
+
+        // A turned parent carries its children around
+        world.clear_dirty();
+        let quarter_turn = Quaternion::from_axis_angle(Vec3::Y, std::f32::consts::FRAC_PI_2);
+        world.get_mut::<LocalTransform>(player).unwrap().0.rotation = quarter_turn;
+        assert_eq!(propagator.propagate(&mut world), 3);
+        assert!(global_position(&world, sword).distance(Vec3::new(20.0, 0.0, -1.0)) < 1e-5);
+
+        world.clear_dirty();
+        assert_eq!(propagator.propagate(&mut world), 0);
+    }
+}
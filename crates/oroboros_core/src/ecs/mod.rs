@@ -10,8 +10,14 @@
 //! - No dynamic dispatch in hot paths
 
 pub mod archetype;
+mod commands;
 mod component;
 mod entity;
+mod event;
+mod hierarchy;
+mod persist;
+mod query;
+mod resource;
 mod storage;
 mod world;
 
@@ -19,7 +25,18 @@ pub use archetype::{
     ArchetypeTable, ArchetypeWorld, ArchetypeSignature,
     DirtyTracker, SyncStats, WorldSyncStats,
 };
+pub use commands::{
+    CommandBuffer, EntityCommands, DEFAULT_COMMAND_CAPACITY, DEFAULT_COMMAND_DATA_CAPACITY,
+};
 pub use component::{Component, Position, Velocity, Voxel};
 pub use entity::{Entity, EntityId};
+pub use event::{Event, EventCursor, Events};
+pub use hierarchy::{Children, GlobalTransform, Hierarchy, LocalTransform, TransformPropagator};
+pub use persist::{ComponentRegistry, PersistError, SAVE_FORMAT_VERSION};
+pub use query::{
+    Access, Changed, QueryData, QueryFilter, QueryIter, ReadOnlyQueryData, With, Without,
+};
+pub use resource::{Resource, Resources};
+pub(crate) use resource::resource_bit;
 pub use storage::ComponentStorage;
 pub use world::World;
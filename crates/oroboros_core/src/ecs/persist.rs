@@ -0,0 +1,618 @@
+//! # World Persistence
+//!
+//! Versioned binary save/load for [`ArchetypeWorld`].
+//!
+//! ## Format (version 1)
+//!
+//! ```text
+//! ┌─ HEADER ───────────────────────────────────────────────────────────┐
+//! │ magic "ORBW" │ version u16 │ flags u8 (bit 0: little-endian host)  │
+//! ├─ COMPONENTS ───────────────────────────────────────────────────────┤
+//! │ count u16 │ per component: name (u8 len + UTF-8), id u8, size u32  │
+//! ├─ ENTITY ALLOCATOR ─────────────────────────────────────────────────┤
+//! │ next_id u64 │ generations (u32 count + u32s) │ free list (same)    │
+//! ├─ ARCHETYPES (in table order) ──────────────────────────────────────┤
+//! │ count u32 │ per archetype: component count u16, component u16s,   │
+//! │ len u64, entity ids (len × u64), then each column's Pod bytes      │
+//! └────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Integers are little-endian. Columns are written as raw `Pod` bytes, so
+//! a save only loads on a host with the same byte order.
+//!
+//! Components are identified by stable names from a
+//! [`ComponentRegistry`], not by `TypeId` (which changes between builds).
+//! A component registered after a save was made can be backfilled with
+//! its `Default` on load. Resources and events are not saved.
+
+use std::any::TypeId;
+use std::io::{self, Read, Write};
+use std::mem::size_of;
+
+use thiserror::Error;
+
+use super::archetype::{ArchetypeSignature, ArchetypeWorld};
+use super::component::{Component, Position, Velocity, Voxel};
+use super::entity::EntityId;
+use super::hierarchy::{GlobalTransform, Hierarchy, LocalTransform};
+
+/// File magic.
+const MAGIC: [u8; 4] = *b"ORBW";
+
+/// Current format version.
+pub const SAVE_FORMAT_VERSION: u16 = 1;
+
+/// Header flag: written on a little-endian host.
+const FLAG_LITTLE_ENDIAN: u8 = 1;
+
+/// Entity ids read per chunk, so a corrupt length can't force a huge
+/// allocation before the data runs out.
+const READ_CHUNK: usize = 64 * 1024;
+
+/// Why a world could not be saved or loaded.
+#[derive(Error, Debug)]
+pub enum PersistError {
+    /// Reading or writing failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// The data is not a world save.
+    #[error("not a world save (bad magic)")]
+    BadMagic,
+    /// The save is from a newer format.
+    #[error("unsupported save format version {0}")]
+    UnsupportedVersion(u16),
+    /// The save was written on a host with the other byte order.
+    #[error("save was written with a different byte order")]
+    ByteOrder,
+    /// The world holds a component type the registry doesn't know.
+    #[error("component type {0:?} is not registered")]
+    UnregisteredComponent(TypeId),
+    /// The save holds a component the registry doesn't know.
+    #[error("save contains unknown component {0:?}")]
+    UnknownComponent(String),
+    /// A component's size changed since the save was made.
+    #[error("component {name:?} is {registered} bytes but was saved as {saved}")]
+    SizeMismatch {
+        /// Component name.
+        name: String,
+        /// Size in the save.
+        saved: usize,
+        /// Size of the registered type.
+        registered: usize,
+    },
+    /// The save is structurally invalid.
+    #[error("corrupt save: {0}")]
+    Corrupt(&'static str),
+}
+
+/// A component known to the registry.
+struct Registered {
+    /// Stable name written to saves.
+    name: &'static str,
+    /// `Component::ID`.
+    id: u8,
+    /// Runtime type.
+    type_id: TypeId,
+    /// Size in bytes.
+    size: usize,
+    /// Adds the component to a signature.
+    add: fn(ArchetypeSignature) -> ArchetypeSignature,
+    /// Bytes of `C::default()`.
+    default: Vec<u8>,
+    /// If set, saves predating this component get it on every entity that
+    /// has all of these components.
+    backfill: Option<Vec<&'static str>>,
+}
+
+/// Maps stable component names to component types for save/load.
+///
+/// ```rust,ignore
+/// let mut registry = ComponentRegistry::with_builtin();
+/// registry.register::<Health>("health");
+/// // Saves from before Health existed give every moving entity full health
+/// registry.backfill::<Health>(&["position", "velocity"]);
+/// ```
+#[derive(Default)]
+pub struct ComponentRegistry {
+    /// Registered components.
+    entries: Vec<Registered>,
+}
+
+impl ComponentRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the engine's own components
+    /// (`position`, `velocity`, `voxel`, `hierarchy`, `local_transform`,
+    /// `global_transform`).
+    #[must_use]
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register::<Position>("position")
+            .register::<Velocity>("velocity")
+            .register::<Voxel>("voxel")
+            .register::<Hierarchy>("hierarchy")
+            .register::<LocalTransform>("local_transform")
+            .register::<GlobalTransform>("global_transform");
+        registry
+    }
+
+    /// Registers component `C` under `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name, type or `Component::ID` is already registered,
+    /// or if the name is longer than 255 bytes.
+    pub fn register<C: Component>(&mut self, name: &'static str) -> &mut Self {
+        assert!(u8::try_from(name.len()).is_ok(), "Component name {name:?} is too long");
+        assert!(
+            !self.entries.iter().any(|entry| {
+                entry.name == name || entry.type_id == TypeId::of::<C>() || entry.id == C::ID
+            }),
+            "Component {name:?} (ID {}) is already registered",
+            C::ID
+        );
+        self.entries.push(Registered {
+            name,
+            id: C::ID,
+            type_id: TypeId::of::<C>(),
+            size: size_of::<C>(),
+            add: ArchetypeSignature::with::<C>,
+            default: bytemuck::bytes_of(&C::default()).to_vec(),
+            backfill: None,
+        });
+        self
+    }
+
+    /// Backfills `C` with its `Default` when loading a save made before
+    /// `C` was registered: every entity that has all the `required`
+    /// components gets it (every entity, if `required` is empty).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` is not registered.
+    pub fn backfill<C: Component>(&mut self, required: &[&'static str]) -> &mut Self {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.type_id == TypeId::of::<C>())
+            .expect("Backfilled component must be registered first");
+        entry.backfill = Some(required.to_vec());
+        self
+    }
+
+    /// Returns the stable name of component `C`.
+    #[must_use]
+    pub fn name_of<C: Component>(&self) -> Option<&'static str> {
+        self.by_type(TypeId::of::<C>()).map(|entry| entry.name)
+    }
+
+    /// Returns the number of registered components.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if nothing is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Looks up a component by type.
+    fn by_type(&self, type_id: TypeId) -> Option<&Registered> {
+        self.entries.iter().find(|entry| entry.type_id == type_id)
+    }
+
+    /// Looks up a component by name.
+    fn by_name(&self, name: &str) -> Option<&Registered> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+impl ArchetypeWorld {
+    /// Writes the whole world (archetypes, columns, entity allocator).
+    ///
+    /// Wrap `writer` in a `BufWriter` when writing to a file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a component type isn't in `registry` or writing fails.
+    pub fn save<W: Write>(&self, registry: &ComponentRegistry, mut writer: W) -> Result<(), PersistError> {
+        // Resolve every column type up front so nothing is half-written
+        // for an unregistered component
+        let mut used: Vec<&Registered> = Vec::new();
+        let mut tables = Vec::with_capacity(self.archetypes().len());
+        for table in self.archetypes() {
+            let mut components = Vec::with_capacity(table.signature().len());
+            for type_id in table.signature().type_ids() {
+                let entry = registry.by_type(type_id).ok_or(PersistError::UnregisteredComponent(type_id))?;
+                let index = used.iter().position(|used| used.type_id == type_id).unwrap_or_else(|| {
+                    used.push(entry);
+                    used.len() - 1
+                });
+                components.push((index as u16, type_id));
+            }
+            tables.push((table, components));
+        }
+
+        // Header
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&SAVE_FORMAT_VERSION.to_le_bytes())?;
+        let flags = if cfg!(target_endian = "little") { FLAG_LITTLE_ENDIAN } else { 0 };
+        writer.write_all(&[flags])?;
+
+        // Components
+        writer.write_all(&(used.len() as u16).to_le_bytes())?;
+        for entry in &used {
+            writer.write_all(&[entry.name.len() as u8])?;
+            writer.write_all(entry.name.as_bytes())?;
+            writer.write_all(&[entry.id])?;
+            writer.write_all(&(entry.size as u32).to_le_bytes())?;
+        }
+
+        // Entity allocator
+        let (next_id, generations, free_indices) = self.entity_allocator();
+        writer.write_all(&next_id.to_le_bytes())?;
+        write_u32s(&mut writer, generations)?;
+        write_u32s(&mut writer, free_indices)?;
+
+        // Archetypes
+        writer.write_all(&(tables.len() as u32).to_le_bytes())?;
+        let mut ids = Vec::new();
+        for (table, components) in tables {
+            writer.write_all(&(components.len() as u16).to_le_bytes())?;
+            for &(index, _) in &components {
+                writer.write_all(&index.to_le_bytes())?;
+            }
+
+            writer.write_all(&(table.len() as u64).to_le_bytes())?;
+            ids.clear();
+            ids.extend(table.entities().iter().flat_map(|id| id.to_bits().to_le_bytes()));
+            writer.write_all(&ids)?;
+
+            for &(_, type_id) in &components {
+                let bytes = table.column_bytes(type_id).ok_or(PersistError::Corrupt("missing column"))?;
+                writer.write_all(bytes)?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a world written by [`save`](Self::save).
+    ///
+    /// Entity ids and component values match the saved world. Components
+    /// added to `registry` since the save are backfilled as configured;
+    /// that changes signatures, so archetype indices may not match.
+    ///
+    /// # Errors
+    ///
+    /// Fails on I/O errors, foreign or corrupt data, components missing
+    /// from `registry` or components whose size changed.
+    pub fn load<R: Read>(registry: &ComponentRegistry, mut reader: R) -> Result<Self, PersistError> {
+        // Header
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(PersistError::BadMagic);
+        }
+        let version = read_u16(&mut reader)?;
+        if version != SAVE_FORMAT_VERSION {
+            return Err(PersistError::UnsupportedVersion(version));
+        }
+        let little_endian = read_u8(&mut reader)? & FLAG_LITTLE_ENDIAN != 0;
+        if little_endian != cfg!(target_endian = "little") {
+            return Err(PersistError::ByteOrder);
+        }
+
+        // Components, resolved against the registry
+        let count = read_u16(&mut reader)?;
+        let mut saved: Vec<&Registered> = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            let mut name = vec![0; usize::from(read_u8(&mut reader)?)];
+            reader.read_exact(&mut name)?;
+            let name = String::from_utf8(name).map_err(|_| PersistError::Corrupt("component name"))?;
+            let _id = read_u8(&mut reader)?;
+            let size = read_u32(&mut reader)? as usize;
+
+            let entry = registry.by_name(&name).ok_or_else(|| PersistError::UnknownComponent(name.clone()))?;
+            if entry.size != size {
+                return Err(PersistError::SizeMismatch {
+                    name,
+                    saved: size,
+                    registered: entry.size,
+                });
+            }
+            saved.push(entry);
+        }
+        let backfills: Vec<&Registered> = registry
+            .entries
+            .iter()
+            .filter(|entry| entry.backfill.is_some() && !saved.iter().any(|s| s.type_id == entry.type_id))
+            .collect();
+
+        // Entity allocator
+        let next_id = read_u64(&mut reader)?;
+        let generations = read_u32s(&mut reader)?;
+        let free_indices = read_u32s(&mut reader)?;
+        if generations.len() as u64 > next_id {
+            return Err(PersistError::Corrupt("next entity id"));
+        }
+        // Indices of live entities, to catch duplicates
+        let mut taken = vec![false; generations.len()];
+
+        let mut world = Self::new(0, 0);
+        world.restore_entity_allocator(next_id, generations, free_indices);
+
+        // Archetypes
+        let archetype_count = read_u32(&mut reader)?;
+        let mut components: Vec<&Registered> = Vec::new();
+        for _ in 0..archetype_count {
+            components.clear();
+            for _ in 0..read_u16(&mut reader)? {
+                let index = usize::from(read_u16(&mut reader)?);
+                components.push(saved.get(index).ok_or(PersistError::Corrupt("component index"))?);
+            }
+
+            let len = usize::try_from(read_u64(&mut reader)?).map_err(|_| PersistError::Corrupt("length"))?;
+            let entities = read_entities(&mut reader, len)?;
+            let (_, generations, _) = world.entity_allocator();
+            let valid = entities.iter().all(|id| {
+                generations.get(id.index() as usize).is_some_and(|&generation| generation == id.generation())
+            });
+            if !valid {
+                return Err(PersistError::Corrupt("entity id"));
+            }
+            for id in &entities {
+                if std::mem::replace(&mut taken[id.index() as usize], true) {
+                    return Err(PersistError::Corrupt("duplicate entity id"));
+                }
+            }
+
+            let mut signature = components.iter().fold(ArchetypeSignature::new(), |sig, entry| (entry.add)(sig));
+            let added: Vec<&Registered> = backfills
+                .iter()
+                .copied()
+                .filter(|entry| {
+                    let required = entry.backfill.as_deref().unwrap_or_default();
+                    required.iter().all(|name| components.iter().any(|c| c.name == *name))
+                })
+                .collect();
+            for entry in &added {
+                signature = (entry.add)(signature);
+            }
+
+            let archetype = world.reserve_archetype(signature, len);
+            let start = world.push_rows(archetype, &entities);
+            let table = &mut world.archetypes_mut()[archetype];
+
+            for entry in &components {
+                let column = table.column_bytes_mut(entry.type_id).ok_or(PersistError::Corrupt("column"))?;
+                reader.read_exact(&mut column[start * entry.size..])?;
+            }
+            for entry in &added {
+                let column = table.column_bytes_mut(entry.type_id).ok_or(PersistError::Corrupt("column"))?;
+                for value in column[start * entry.size..].chunks_exact_mut(entry.size) {
+                    value.copy_from_slice(&entry.default);
+                }
+            }
+        }
+
+        check_free_indices(taken, world.entity_allocator().2)?;
+        Ok(world)
+    }
+}
+
+/// Checks that reusing a free index can't hand out a live or repeated ID.
+/// `taken` marks the indices of live entities.
+fn check_free_indices(mut taken: Vec<bool>, free_indices: &[u32]) -> Result<(), PersistError> {
+    for &index in free_indices {
+        match taken.get_mut(index as usize) {
+            Some(taken) if !*taken => *taken = true,
+            _ => return Err(PersistError::Corrupt("free entity index")),
+        }
+    }
+    Ok(())
+}
+
+/// Writes a `u32` count followed by the values.
+fn write_u32s<W: Write>(writer: &mut W, values: &[u32]) -> io::Result<()> {
+    writer.write_all(&(values.len() as u32).to_le_bytes())?;
+    let bytes: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    writer.write_all(&bytes)
+}
+
+/// Reads one byte.
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+/// Reads a little-endian `u16`.
+fn read_u16<R: Read>(reader: &mut R) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+/// Reads a little-endian `u32`.
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Reads a little-endian `u64`.
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads a `u32` count followed by the values.
+fn read_u32s<R: Read>(reader: &mut R) -> io::Result<Vec<u32>> {
+    let count = read_u32(reader)? as usize;
+    let mut values = Vec::with_capacity(count.min(READ_CHUNK));
+    let mut chunk = vec![0; count.min(READ_CHUNK) * 4];
+    while values.len() < count {
+        let n = (count - values.len()).min(READ_CHUNK);
+        reader.read_exact(&mut chunk[..n * 4])?;
+        values.extend(chunk[..n * 4].chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])));
+    }
+    Ok(values)
+}
+
+/// Reads `count` entity ids.
+fn read_entities<R: Read>(reader: &mut R, count: usize) -> io::Result<Vec<EntityId>> {
+    let mut ids = Vec::with_capacity(count.min(READ_CHUNK));
+    let mut chunk = vec![0; count.min(READ_CHUNK) * 8];
+    while ids.len() < count {
+        let n = (count - ids.len()).min(READ_CHUNK);
+        reader.read_exact(&mut chunk[..n * 8])?;
+        ids.extend(chunk[..n * 8].chunks_exact(8).map(|b| {
+            EntityId::from_bits(u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+        }));
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::{Pod, Zeroable};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    #[repr(C)]
+    struct Health(u32);
+
+    impl Default for Health {
+        fn default() -> Self {
+            Self(100)
+        }
+    }
+
+    impl Component for Health {
+        const ID: u8 = 40;
+    }
+
+    fn save(world: &ArchetypeWorld, registry: &ComponentRegistry) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        world.save(registry, &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_round_trip_keeps_ids_and_free_list() {
+        let mut registry = ComponentRegistry::with_builtin();
+        registry.register::<Health>("health");
+
+        let mut world = ArchetypeWorld::new(16, 16);
+        let a = world.spawn_pv(Position::new(1.0, 2.0, 3.0), Velocity::new(0.5, 0.0, 0.0));
+        let b = world.spawn_p(Position::new(4.0, 0.0, 0.0));
+        let gone = world.spawn_p(Position::default());
+        assert!(world.insert(b, Health(7)));
+        assert!(world.despawn(gone));
+
+        let mut loaded = ArchetypeWorld::load(&registry, save(&world, &registry).as_slice()).unwrap();
+
+        assert_eq!(loaded.alive_count(), 2);
+        assert_eq!(loaded.archetypes().len(), world.archetypes().len());
+        assert_eq!(loaded.get::<Velocity>(a), Some(&Velocity::new(0.5, 0.0, 0.0)));
+        assert_eq!(loaded.get::<Health>(b), Some(&Health(7)));
+        assert!(!loaded.is_alive(gone));
+
+        // The freed slot is reused with a bumped generation, as before saving
+        let reused = loaded.spawn_p(Position::default());
+        assert_eq!(reused.index(), gone.index());
+        assert_eq!(reused.generation(), gone.generation() + 1);
+    }
+
+    #[test]
+    fn test_backfill_added_component() {
+        let old_registry = ComponentRegistry::with_builtin();
+        let mut world = ArchetypeWorld::new(16, 16);
+        let mover = world.spawn_pv(Position::default(), Velocity::default());
+        let prop = world.spawn_p(Position::default());
+        let bytes = save(&world, &old_registry);
+
+        let mut registry = ComponentRegistry::with_builtin();
+        registry.register::<Health>("health").backfill::<Health>(&["velocity"]);
+        let loaded = ArchetypeWorld::load(&registry, bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.get::<Health>(mover), Some(&Health(100)));
+        assert!(!loaded.has::<Health>(prop));
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let registry = ComponentRegistry::with_builtin();
+        let mut with_health = ComponentRegistry::with_builtin();
+        with_health.register::<Health>("health");
+
+        let mut world = ArchetypeWorld::new(4, 4);
+        let id = world.spawn_p(Position::default());
+        assert!(world.insert(id, Health(1)));
+
+        assert!(matches!(
+            world.save(&registry, Vec::new()),
+            Err(PersistError::UnregisteredComponent(_))
+        ));
+
+        let bytes = save(&world, &with_health);
+        assert!(matches!(
+            ArchetypeWorld::load(&registry, bytes.as_slice()),
+            Err(PersistError::UnknownComponent(name)) if name == "health"
+        ));
+        assert!(matches!(ArchetypeWorld::load(&with_health, &b"NOPE"[..]), Err(PersistError::BadMagic)));
+        assert!(matches!(
+            ArchetypeWorld::load(&with_health, &bytes[..bytes.len() - 1]),
+            Err(PersistError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_corrupt_entity_allocator() {
+        let mut registry = ComponentRegistry::with_builtin();
+        registry.register::<Health>("health");
+
+        // One live entity and one freed slot
+        let world = || {
+            let mut world = ArchetypeWorld::new(4, 4);
+            let alive = world.spawn_p(Position::default());
+            let gone = world.spawn_p(Position::default());
+            assert!(world.despawn(gone));
+            (world, alive, gone)
+        };
+        let load = |world: &ArchetypeWorld| ArchetypeWorld::load(&registry, save(world, &registry).as_slice());
+        let (_, alive, gone) = world();
+        let with_allocator = |next_id: u64, free_indices: Vec<u32>| {
+            let (mut world, _, _) = world();
+            let generations = world.entity_allocator().1.to_vec();
+            world.restore_entity_allocator(next_id, generations, free_indices);
+            load(&world)
+        };
+
+        assert!(with_allocator(3, vec![gone.index()]).is_ok());
+        for free_indices in [vec![99], vec![alive.index()], vec![gone.index(), gone.index()]] {
+            assert!(matches!(
+                with_allocator(3, free_indices),
+                Err(PersistError::Corrupt("free entity index"))
+            ));
+        }
+        // Fresh IDs would land on indices already handed out
+        assert!(matches!(with_allocator(1, vec![gone.index()]), Err(PersistError::Corrupt("next entity id"))));
+
+        // The same live entity in two archetypes
+        let (mut twice, _, _) = world();
+        let archetype = twice.reserve_archetype(ArchetypeSignature::new().with::<Position>().with::<Health>(), 1);
+        twice.push_rows(archetype, &[alive]);
+        assert!(matches!(load(&twice), Err(PersistError::Corrupt("duplicate entity id"))));
+    }
+}
@@ -0,0 +1,646 @@
+//! # Queries
+//!
+//! Typed iteration over every archetype that holds a component tuple.
+//!
+//! ```rust,ignore
+//! for (id, (pos, vel)) in world.query_mut::<(&mut Position, &Velocity)>() {
+//!     pos.x += vel.x * dt;
+//! }
+//!
+//! for (id, health) in world.query_filtered::<&Health, (Changed<Health>, Without<Npc>)>() {
+//!     // only entities whose Health changed this frame
+//! }
+//! ```
+//!
+//! ## Design
+//!
+//! - Archetypes are matched by signature once per table, not per entity
+//! - Each table resolves its column pointers up front; the per-row work is
+//!   a pointer offset and a filter check - no allocation, no hashing
+//! - `&mut T` marks only the rows it yields dirty in `T`'s column
+//! - `Changed<T>` reads the same trackers the buffer sync uses, so it means
+//!   "changed since the last buffer swap" (spawned and moved rows count)
+
+// SAFETY: Queries hand out references into type-erased columns.
+// Aliasing is ruled out by `Access` before any pointer is dereferenced.
+#![allow(unsafe_code)]
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use super::archetype::{ArchetypeSignature, ArchetypeTable, ArchetypeWorld, DirtyTracker};
+use super::component::Component;
+use super::entity::EntityId;
+
+/// Bit of a component in an [`Access`] mask.
+struct ComponentBit<C>(PhantomData<C>);
+
+impl<C: Component> ComponentBit<C> {
+    /// `1 << C::ID`; fails to compile for IDs that don't fit the mask.
+    const BIT: u64 = {
+        assert!(C::ID < 64, "Component::ID must be below 64");
+        1u64 << C::ID
+    };
+}
+
+/// Components a query reads and writes, as [`Component::ID`] bitmasks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Access {
+    /// Components read through shared references.
+    reads: u64,
+    /// Components written through mutable references.
+    writes: u64,
+}
+
+impl Access {
+    /// Records a shared read of `C`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query also writes `C`.
+    pub fn read<C: Component>(&mut self) {
+        let bit = ComponentBit::<C>::BIT;
+        assert!(self.writes & bit == 0, "Query aliases component {} mutably", C::ID);
+        self.reads |= bit;
+    }
+
+    /// Records a mutable write of `C`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query already reads or writes `C`.
+    pub fn write<C: Component>(&mut self) {
+        let bit = ComponentBit::<C>::BIT;
+        assert!((self.reads | self.writes) & bit == 0, "Query aliases component {} mutably", C::ID);
+        self.writes |= bit;
+    }
+
+    /// Records an inspection of `C`'s change flags (filters).
+    ///
+    /// Counts as a read for scheduling but never conflicts with the
+    /// query's own writes.
+    pub fn observe<C: Component>(&mut self) {
+        self.reads |= ComponentBit::<C>::BIT;
+    }
+
+    /// Adds raw bits to the read set, without alias checks.
+    pub(crate) fn read_bits(&mut self, bits: u64) {
+        self.reads |= bits;
+    }
+
+    /// Adds raw bits to the write set, without alias checks.
+    pub(crate) fn write_bits(&mut self, bits: u64) {
+        self.writes |= bits;
+    }
+
+    /// Returns the access of a query with data `Q` and filter `F`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Q` names a component both mutably and otherwise.
+    #[must_use]
+    pub fn of<Q: QueryData, F: QueryFilter>() -> Self {
+        let mut access = Self::default();
+        Q::access(&mut access);
+        F::access(&mut access);
+        access
+    }
+
+    /// Checks if running alongside `other` could race.
+    #[must_use]
+    pub const fn conflicts_with(&self, other: &Self) -> bool {
+        self.writes & (other.reads | other.writes) != 0 || other.writes & self.reads != 0
+    }
+
+    /// Checks if everything this touches is allowed by `declared`.
+    #[must_use]
+    pub const fn is_covered_by(&self, declared: &Self) -> bool {
+        self.writes & !declared.writes == 0 && self.reads & !(declared.reads | declared.writes) == 0
+    }
+}
+
+/// What a query yields per entity: `&T`, `&mut T`, `Option<&T>` or a
+/// tuple of those.
+///
+/// # Safety
+///
+/// `fetch` must only touch the columns declared in `access`, and `prepare`
+/// must only create mutable borrows for components it declares as writes.
+pub unsafe trait QueryData {
+    /// Item yielded for one entity.
+    type Item<'w>;
+    /// Per-archetype column pointers.
+    type State;
+
+    /// Declares the components this query touches.
+    fn access(access: &mut Access);
+
+    /// Checks if an archetype has everything this query needs.
+    fn matches(signature: &ArchetypeSignature) -> bool;
+
+    /// Resolves column pointers for a matching table.
+    ///
+    /// # Safety
+    ///
+    /// `table` must be valid, and writable if the query writes anything.
+    unsafe fn prepare(table: *mut ArchetypeTable) -> Self::State;
+
+    /// Fetches the item for one row.
+    ///
+    /// # Safety
+    ///
+    /// `row` must be below the table's length, and each row fetched at most
+    /// once while the items are alive.
+    unsafe fn fetch<'w>(state: &Self::State, row: usize) -> Self::Item<'w>;
+}
+
+/// Marker for queries that never write, usable through a shared world.
+pub trait ReadOnlyQueryData: QueryData {}
+
+/// Restricts which entities a query yields without fetching anything.
+///
+/// # Safety
+///
+/// `prepare` and `filter` must not write to the table.
+pub unsafe trait QueryFilter {
+    /// Per-archetype filter state.
+    type State;
+
+    /// Declares the components this filter inspects.
+    fn access(_access: &mut Access) {}
+
+    /// Checks if an archetype can pass the filter at all.
+    fn matches(signature: &ArchetypeSignature) -> bool;
+
+    /// Resolves dirty trackers for a matching table.
+    ///
+    /// # Safety
+    ///
+    /// `table` must be valid.
+    unsafe fn prepare(table: *const ArchetypeTable) -> Self::State;
+
+    /// Checks one row.
+    ///
+    /// # Safety
+    ///
+    /// `row` must be below the table's length.
+    unsafe fn filter(state: &Self::State, row: usize) -> bool;
+}
+
+// SAFETY: Reads only T's column, declared as a read
+unsafe impl<T: Component> QueryData for &T {
+    type Item<'w> = &'w T;
+    type State = NonNull<T>;
+
+    fn access(access: &mut Access) {
+        access.read::<T>();
+    }
+
+    fn matches(signature: &ArchetypeSignature) -> bool {
+        signature.contains::<T>()
+    }
+
+    unsafe fn prepare(table: *mut ArchetypeTable) -> Self::State {
+        let (data, _) = ArchetypeTable::column_raw::<T>(table).expect("Query matched a table without its component");
+        data
+    }
+
+    #[inline]
+    unsafe fn fetch<'w>(state: &Self::State, row: usize) -> Self::Item<'w> {
+        &*state.as_ptr().add(row)
+    }
+}
+
+impl<T: Component> ReadOnlyQueryData for &T {}
+
+// SAFETY: Writes only T's column and tracker, declared as a write
+unsafe impl<T: Component> QueryData for &mut T {
+    type Item<'w> = &'w mut T;
+    type State = (NonNull<T>, NonNull<DirtyTracker>);
+
+    fn access(access: &mut Access) {
+        access.write::<T>();
+    }
+
+    fn matches(signature: &ArchetypeSignature) -> bool {
+        signature.contains::<T>()
+    }
+
+    unsafe fn prepare(table: *mut ArchetypeTable) -> Self::State {
+        ArchetypeTable::column_raw::<T>(table).expect("Query matched a table without its component")
+    }
+
+    #[inline]
+    unsafe fn fetch<'w>(state: &Self::State, row: usize) -> Self::Item<'w> {
+        let (data, mut dirty) = *state;
+        dirty.as_mut().mark_dirty(row);
+        &mut *data.as_ptr().add(row)
+    }
+}
+
+// SAFETY: Reads only T's column when present, declared as a read
+unsafe impl<T: Component> QueryData for Option<&T> {
+    type Item<'w> = Option<&'w T>;
+    type State = Option<NonNull<T>>;
+
+    fn access(access: &mut Access) {
+        access.read::<T>();
+    }
+
+    fn matches(_signature: &ArchetypeSignature) -> bool {
+        true
+    }
+
+    unsafe fn prepare(table: *mut ArchetypeTable) -> Self::State {
+        ArchetypeTable::column_raw::<T>(table).map(|(data, _)| data)
+    }
+
+    #[inline]
+    unsafe fn fetch<'w>(state: &Self::State, row: usize) -> Self::Item<'w> {
+        state.map(|data| &*data.as_ptr().add(row))
+    }
+}
+
+impl<T: Component> ReadOnlyQueryData for Option<&T> {}
+
+/// Filter: only archetypes that have `T` (without fetching it).
+pub struct With<T>(PhantomData<T>);
+
+/// Filter: only archetypes that don't have `T`.
+pub struct Without<T>(PhantomData<T>);
+
+/// Filter: only entities whose `T` changed since the dirty flags were last
+/// cleared (normally the last buffer swap).
+///
+/// Spawned entities and entities that moved archetype count as changed.
+pub struct Changed<T>(PhantomData<T>);
+
+// SAFETY: Archetype-level only, touches nothing
+unsafe impl<T: Component> QueryFilter for With<T> {
+    type State = ();
+
+    fn matches(signature: &ArchetypeSignature) -> bool {
+        signature.contains::<T>()
+    }
+
+    unsafe fn prepare(_table: *const ArchetypeTable) -> Self::State {}
+
+    #[inline]
+    unsafe fn filter(_state: &Self::State, _row: usize) -> bool {
+        true
+    }
+}
+
+// SAFETY: Archetype-level only, touches nothing
+unsafe impl<T: Component> QueryFilter for Without<T> {
+    type State = ();
+
+    fn matches(signature: &ArchetypeSignature) -> bool {
+        !signature.contains::<T>()
+    }
+
+    unsafe fn prepare(_table: *const ArchetypeTable) -> Self::State {}
+
+    #[inline]
+    unsafe fn filter(_state: &Self::State, _row: usize) -> bool {
+        true
+    }
+}
+
+// SAFETY: Only reads dirty trackers
+unsafe impl<T: Component> QueryFilter for Changed<T> {
+    /// Row tracker and `T`'s column tracker.
+    type State = (NonNull<DirtyTracker>, NonNull<DirtyTracker>);
+
+    fn access(access: &mut Access) {
+        access.observe::<T>();
+    }
+
+    fn matches(signature: &ArchetypeSignature) -> bool {
+        signature.contains::<T>()
+    }
+
+    unsafe fn prepare(table: *const ArchetypeTable) -> Self::State {
+        let (_, column) = ArchetypeTable::column_raw::<T>(table.cast_mut())
+            .expect("Query matched a table without its component");
+        (ArchetypeTable::dirty_raw(table), column)
+    }
+
+    #[inline]
+    unsafe fn filter(state: &Self::State, row: usize) -> bool {
+        let (rows, column) = state;
+        rows.as_ref().is_dirty(row) || column.as_ref().is_dirty(row)
+    }
+}
+
+// SAFETY: No filter touches nothing
+unsafe impl QueryFilter for () {
+    type State = ();
+
+    fn matches(_signature: &ArchetypeSignature) -> bool {
+        true
+    }
+
+    unsafe fn prepare(_table: *const ArchetypeTable) -> Self::State {}
+
+    #[inline]
+    unsafe fn filter(_state: &Self::State, _row: usize) -> bool {
+        true
+    }
+}
+
+/// Implements [`QueryData`] and [`QueryFilter`] for a tuple of parts.
+macro_rules! impl_query_tuple {
+    ($($name:ident),+) => {
+        // SAFETY: Each part upholds the contract; `Access` rules out overlap
+        unsafe impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type State = ($($name::State,)+);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)+
+            }
+
+            fn matches(signature: &ArchetypeSignature) -> bool {
+                $($name::matches(signature))&&+
+            }
+
+            unsafe fn prepare(table: *mut ArchetypeTable) -> Self::State {
+                ($($name::prepare(table),)+)
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            unsafe fn fetch<'w>(state: &Self::State, row: usize) -> Self::Item<'w> {
+                let ($($name,)+) = state;
+                ($($name::fetch($name, row),)+)
+            }
+        }
+
+        impl<$($name: ReadOnlyQueryData),+> ReadOnlyQueryData for ($($name,)+) {}
+
+        // SAFETY: Each part upholds the contract
+        unsafe impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            type State = ($($name::State,)+);
+
+            fn access(access: &mut Access) {
+                $(<$name as QueryFilter>::access(access);)+
+            }
+
+            fn matches(signature: &ArchetypeSignature) -> bool {
+                $($name::matches(signature))&&+
+            }
+
+            unsafe fn prepare(table: *const ArchetypeTable) -> Self::State {
+                ($($name::prepare(table),)+)
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            unsafe fn filter(state: &Self::State, row: usize) -> bool {
+                let ($($name,)+) = state;
+                $($name::filter($name, row))&&+
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+
+/// Iterator over `(EntityId, Q::Item)` for every matching entity.
+///
+/// Created by [`ArchetypeWorld::query`] and friends.
+pub struct QueryIter<'w, Q: QueryData, F: QueryFilter = ()> {
+    /// First archetype table of the world.
+    tables: NonNull<ArchetypeTable>,
+    /// Number of archetype tables.
+    table_count: usize,
+    /// Next table to inspect.
+    next_table: usize,
+    /// Entity IDs of the current table.
+    entities: *const EntityId,
+    /// Rows in the current table.
+    len: usize,
+    /// Next row in the current table.
+    row: usize,
+    /// Column pointers for the current table.
+    state: Option<(Q::State, F::State)>,
+    /// Ties items to the world borrow.
+    _world: PhantomData<&'w mut ArchetypeTable>,
+}
+
+impl<Q: QueryData, F: QueryFilter> QueryIter<'_, Q, F> {
+    /// Creates an iterator over `tables`.
+    ///
+    /// # Safety
+    ///
+    /// `tables` must stay borrowed for `'w` - mutably if `Q` writes.
+    pub(crate) unsafe fn new(tables: NonNull<ArchetypeTable>, table_count: usize) -> Self {
+        let _ = Access::of::<Q, F>();
+
+        Self {
+            tables,
+            table_count,
+            next_table: 0,
+            entities: std::ptr::null(),
+            len: 0,
+            row: 0,
+            state: None,
+            _world: PhantomData,
+        }
+    }
+
+    /// Moves to the next non-empty matching table.
+    fn next_table(&mut self) -> bool {
+        while self.next_table < self.table_count {
+            // SAFETY: next_table < table_count
+            let table = unsafe { self.tables.as_ptr().add(self.next_table) };
+            self.next_table += 1;
+
+            // SAFETY: Table is valid for 'w
+            let (signature, len) = unsafe { ((*table).signature(), (*table).len()) };
+            if len == 0 || !Q::matches(signature) || !F::matches(signature) {
+                continue;
+            }
+
+            // SAFETY: Table matches both parts and is borrowed as `new` requires
+            unsafe {
+                self.state = Some((Q::prepare(table), F::prepare(table)));
+                self.entities = (*table).entities().as_ptr();
+            }
+            self.len = len;
+            self.row = 0;
+            return true;
+        }
+        false
+    }
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> Iterator for QueryIter<'w, Q, F> {
+    type Item = (EntityId, Q::Item<'w>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((data, filter)) = &self.state {
+                while self.row < self.len {
+                    let row = self.row;
+                    self.row += 1;
+
+                    // SAFETY: row < len, each row is visited once
+                    unsafe {
+                        if F::filter(filter, row) {
+                            return Some((*self.entities.add(row), Q::fetch(data, row)));
+                        }
+                    }
+                }
+            }
+
+            if !self.next_table() {
+                return None;
+            }
+        }
+    }
+}
+
+impl ArchetypeWorld {
+    /// Iterates every entity with the components in `Q`.
+    ///
+    /// ```rust,ignore
+    /// for (id, (pos, team)) in world.query::<(&Position, Option<&Team>)>() { ... }
+    /// ```
+    #[must_use]
+    pub fn query<Q: ReadOnlyQueryData>(&self) -> QueryIter<'_, Q> {
+        self.query_filtered::<Q, ()>()
+    }
+
+    /// Iterates every entity with the components in `Q` that passes `F`.
+    #[must_use]
+    pub fn query_filtered<Q: ReadOnlyQueryData, F: QueryFilter>(&self) -> QueryIter<'_, Q, F> {
+        let tables = self.archetypes();
+        // SAFETY: Read-only query, tables borrowed for the iterator's lifetime
+        unsafe { QueryIter::new(NonNull::from(tables).cast(), tables.len()) }
+    }
+
+    /// Iterates every entity with the components in `Q`, mutably.
+    ///
+    /// Components fetched as `&mut T` are marked dirty for every yielded
+    /// entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Q` names a component both mutably and otherwise.
+    pub fn query_mut<Q: QueryData>(&mut self) -> QueryIter<'_, Q> {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// Iterates every entity with the components in `Q` that passes `F`,
+    /// mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Q` names a component both mutably and otherwise.
+    pub fn query_filtered_mut<Q: QueryData, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        let tables = self.archetypes_mut();
+        let count = tables.len();
+        // SAFETY: Tables exclusively borrowed for the iterator's lifetime
+        unsafe { QueryIter::new(NonNull::from(tables).cast(), count) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Position, Velocity};
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+    #[repr(C)]
+    struct Health(u32);
+
+    impl Component for Health {
+        const ID: u8 = 40;
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+    #[repr(C)]
+    struct Npc(u32);
+
+    impl Component for Npc {
+        const ID: u8 = 41;
+    }
+
+    fn world() -> (ArchetypeWorld, [EntityId; 4]) {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let mover = world.spawn_pv(Position::new(0.0, 0.0, 0.0), Velocity::new(1.0, 0.0, 0.0));
+        let player = world.spawn_pv(Position::new(0.0, 0.0, 0.0), Velocity::new(2.0, 0.0, 0.0));
+        let npc = world.spawn_pv(Position::new(0.0, 0.0, 0.0), Velocity::new(3.0, 0.0, 0.0));
+        let rock = world.spawn_p(Position::new(5.0, 0.0, 0.0));
+        assert!(world.insert(player, Health(100)));
+        assert!(world.insert(npc, Health(20)));
+        assert!(world.insert(npc, Npc(7)));
+        (world, [mover, player, npc, rock])
+    }
+
+    fn ids<T>(items: impl Iterator<Item = (EntityId, T)>) -> Vec<EntityId> {
+        let mut ids: Vec<EntityId> = items.map(|(id, _)| id).collect();
+        ids.sort_by_key(|id| id.index());
+        ids
+    }
+
+    #[test]
+    fn test_query_spans_archetypes() {
+        let (mut world, [mover, player, npc, rock]) = world();
+
+        for (_, (pos, vel)) in world.query_mut::<(&mut Position, &Velocity)>() {
+            pos.x += vel.x;
+        }
+        assert_eq!(world.get_position(npc).unwrap().x, 3.0);
+        assert_eq!(world.get_position(rock).unwrap().x, 5.0);
+
+        assert_eq!(ids(world.query::<&Position>()), vec![mover, player, npc, rock]);
+        assert_eq!(ids(world.query::<(&Velocity, &Health)>()), vec![player, npc]);
+
+        let health: Vec<_> = world
+            .query::<(&Velocity, Option<&Health>)>()
+            .map(|(id, (_, health))| (id, health.copied()))
+            .collect();
+        assert_eq!(health.len(), 3);
+        assert!(health.contains(&(mover, None)));
+        assert!(health.contains(&(npc, Some(Health(20)))));
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let (mut world, [mover, player, npc, _]) = world();
+
+        assert_eq!(ids(world.query_filtered::<&Velocity, With<Health>>()), vec![player, npc]);
+        assert_eq!(ids(world.query_filtered::<&Velocity, Without<Health>>()), vec![mover]);
+        assert_eq!(ids(world.query_filtered::<&Health, (With<Health>, Without<Npc>)>()), vec![player]);
+
+        // Nothing changed after a sync; one write shows up
+        world.clear_dirty();
+        assert_eq!(world.query_filtered::<&Health, Changed<Health>>().count(), 0);
+        world.get_mut::<Health>(npc).unwrap().0 = 10;
+        assert_eq!(ids(world.query_filtered::<&Health, Changed<Health>>()), vec![npc]);
+        assert_eq!(world.query_filtered::<&Position, Changed<Position>>().count(), 0);
+
+        // Mutable fetches mark only what they yield
+        world.clear_dirty();
+        for (_, health) in world.query_filtered_mut::<&mut Health, Without<Npc>>() {
+            health.0 += 1;
+        }
+        assert_eq!(ids(world.query_filtered::<&Health, Changed<Health>>()), vec![player]);
+    }
+
+    #[test]
+    #[should_panic(expected = "aliases component")]
+    fn test_aliasing_query_panics() {
+        let (mut world, _) = world();
+        let _ = world.query_mut::<(&mut Position, &Position)>();
+    }
+}
@@ -0,0 +1,174 @@
+//! # Resources
+//!
+//! World-wide singletons (current tick, weather, dragon state, economy
+//! handles) stored by type next to the archetype tables.
+//!
+//! ## Safety Note
+//!
+//! Each value sits in an `UnsafeCell` so systems running in parallel can
+//! borrow different resources mutably through a shared `&Resources`. The
+//! scheduler's access declarations guarantee no two of them touch the
+//! same resource mutably at the same time.
+
+#![allow(unsafe_code)]
+
+use std::any::{Any, TypeId};
+use std::cell::UnsafeCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ptr::NonNull;
+
+use super::event::{Event, Events};
+
+/// Marker for types storable as a resource.
+///
+/// Implemented for every `Send + Sync + 'static` type.
+pub trait Resource: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Resource for T {}
+
+/// Bit standing for resource `R` in access masks.
+///
+/// Derived from the `TypeId`, so two resources may share a bit. That only
+/// makes the scheduler more conservative: systems touching either are
+/// treated as touching both.
+pub(crate) fn resource_bit<R: Resource>() -> u64 {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<R>().hash(&mut hasher);
+    1u64 << (hasher.finish() % 64)
+}
+
+/// Interior-mutable slot for one resource.
+struct Slot<R>(UnsafeCell<R>);
+
+// SAFETY: Access to the cell is either through `&mut Resources` or
+// serialized by the scheduler's declared resource access.
+unsafe impl<R: Resource> Sync for Slot<R> {}
+
+/// Type-keyed resource storage.
+#[derive(Default)]
+pub struct Resources {
+    /// One `Slot<R>` per resource type.
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    /// Rotates every registered event queue.
+    event_updates: Vec<fn(&mut Resources)>,
+}
+
+impl Resources {
+    /// Creates an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `value`, returning the previous value of that type.
+    pub fn insert<R: Resource>(&mut self, value: R) -> Option<R> {
+        self.map
+            .insert(TypeId::of::<R>(), Box::new(Slot(UnsafeCell::new(value))))
+            .and_then(|old| old.downcast::<Slot<R>>().ok())
+            .map(|old| old.0.into_inner())
+    }
+
+    /// Removes and returns the resource of type `R`.
+    pub fn remove<R: Resource>(&mut self) -> Option<R> {
+        let slot = self.map.remove(&TypeId::of::<R>())?;
+        slot.downcast::<Slot<R>>().ok().map(|slot| slot.0.into_inner())
+    }
+
+    /// Checks if a resource of type `R` is stored.
+    #[must_use]
+    pub fn contains<R: Resource>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Returns the resource of type `R`.
+    #[must_use]
+    pub fn get<R: Resource>(&self) -> Option<&R> {
+        // SAFETY: `&self` rules out `get_mut`; parallel systems only get
+        // shared access unless they declared a write
+        self.get_ptr::<R>().map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// Returns the resource of type `R` mutably.
+    #[must_use]
+    pub fn get_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        let slot = self.map.get_mut(&TypeId::of::<R>())?;
+        slot.downcast_mut::<Slot<R>>().map(|slot| slot.0.get_mut())
+    }
+
+    /// Returns a pointer to the resource of type `R`.
+    ///
+    /// Writing through it is only sound under the scheduler's access rules.
+    pub(crate) fn get_ptr<R: Resource>(&self) -> Option<NonNull<R>> {
+        let slot = self.map.get(&TypeId::of::<R>())?;
+        slot.downcast_ref::<Slot<R>>().and_then(|slot| NonNull::new(slot.0.get()))
+    }
+
+    /// Returns the number of resources.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Checks if no resources are stored.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Registers an event queue for `E`, if there isn't one yet.
+    pub fn add_events<E: Event>(&mut self) {
+        if !self.contains::<Events<E>>() {
+            self.insert(Events::<E>::new());
+            self.event_updates.push(|resources| {
+                if let Some(events) = resources.get_mut::<Events<E>>() {
+                    events.update();
+                }
+            });
+        }
+    }
+
+    /// Rotates every registered event queue; see [`Events::update`].
+    pub fn update_events(&mut self) {
+        for index in 0..self.event_updates.len() {
+            let update = self.event_updates[index];
+            update(self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Tick(u64);
+
+    #[derive(Debug, PartialEq)]
+    struct Weather {
+        rain: f32,
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut resources = Resources::new();
+        assert!(resources.insert(Tick(1)).is_none());
+        assert_eq!(resources.insert(Tick(2)), Some(Tick(1)));
+        resources.insert(Weather { rain: 0.5 });
+
+        resources.get_mut::<Tick>().unwrap().0 += 1;
+        assert_eq!(resources.get::<Tick>(), Some(&Tick(3)));
+        assert_eq!(resources.len(), 2);
+
+        assert_eq!(resources.remove::<Weather>(), Some(Weather { rain: 0.5 }));
+        assert!(!resources.contains::<Weather>());
+        assert!(resources.get::<Weather>().is_none());
+    }
+
+    #[test]
+    fn test_resource_bit_is_stable() {
+        assert_eq!(resource_bit::<Tick>(), resource_bit::<Tick>());
+        assert_eq!(resource_bit::<Tick>().count_ones(), 1);
+    }
+}
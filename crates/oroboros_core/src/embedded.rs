@@ -0,0 +1,77 @@
+//! # Embedded Data
+//!
+//! Data files compiled into the binary with `include_str!` and parsed the
+//! first time they're asked for.
+//!
+//! ```rust,ignore
+//! static SHIPPED: EmbeddedData<BlockRegistry, BlockConfigError> =
+//!     EmbeddedData::new("blocks.toml", SHIPPED_BLOCKS, BlockRegistry::from_toml);
+//!
+//! let registry = SHIPPED.get(); // parses once, then shares the result
+//! ```
+
+use std::fmt::Display;
+use std::sync::{Arc, OnceLock};
+
+/// A data file compiled into the binary, parsed once on first use.
+pub struct EmbeddedData<T, E> {
+    /// File name, for the panic message.
+    name: &'static str,
+    /// File contents.
+    text: &'static str,
+    /// Turns the contents into `T`.
+    parse: fn(&str) -> Result<T, E>,
+    /// Parsed value, once someone asked for it.
+    parsed: OnceLock<Arc<T>>,
+}
+
+impl<T, E: Display> EmbeddedData<T, E> {
+    /// Wraps the contents of `name`, to be parsed with `parse`.
+    #[must_use]
+    pub const fn new(name: &'static str, text: &'static str, parse: fn(&str) -> Result<T, E>) -> Self {
+        Self {
+            name,
+            text,
+            parse,
+            parsed: OnceLock::new(),
+        }
+    }
+
+    /// Returns the parsed data, parsing it on the first call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the embedded text doesn't parse.
+    pub fn get(&self) -> Arc<T> {
+        self.parsed
+            .get_or_init(|| match (self.parse)(self.text) {
+                Ok(value) => Arc::new(value),
+                Err(e) => panic!("embedded {} is invalid: {e}", self.name),
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_number(text: &str) -> Result<u32, std::num::ParseIntError> {
+        text.trim().parse()
+    }
+
+    #[test]
+    fn test_parses_once_and_shares() {
+        static NUMBER: EmbeddedData<u32, std::num::ParseIntError> = EmbeddedData::new("number.txt", "42\n", parse_number);
+        let first = NUMBER.get();
+        assert_eq!(*first, 42);
+        assert!(Arc::ptr_eq(&first, &NUMBER.get()));
+    }
+
+    #[test]
+    #[should_panic(expected = "embedded number.txt is invalid")]
+    fn test_invalid_text_panics() {
+        static NUMBER: EmbeddedData<u32, std::num::ParseIntError> = EmbeddedData::new("number.txt", "forty-two", parse_number);
+        let _ = NUMBER.get();
+    }
+}
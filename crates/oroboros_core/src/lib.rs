@@ -26,14 +26,27 @@
 #![warn(clippy::pedantic)]
 #![deny(clippy::perf)]
 
+pub mod blocks;
 pub mod ecs;
+pub mod embedded;
 pub mod memory;
+pub mod schedule;
+pub mod spatial;
 pub mod sync;
 
+pub use blocks::{BlockConfigError, BlockDef, BlockId, BlockRegistry};
+pub use embedded::EmbeddedData;
 pub use ecs::{
-    ArchetypeTable, ArchetypeWorld, ArchetypeSignature,
+    ArchetypeTable, ArchetypeWorld, ArchetypeSignature, CommandBuffer, EntityCommands,
     Component, ComponentStorage, Entity, EntityId, Position, Velocity, Voxel, World,
-    DirtyTracker, SyncStats, WorldSyncStats,
+    DirtyTracker, SyncStats, WorldSyncStats, Event, EventCursor, Events, Resource, Resources,
+    Children, GlobalTransform, Hierarchy, LocalTransform, TransformPropagator,
+    ComponentRegistry, PersistError,
+    Access, Changed, QueryData, QueryFilter, QueryIter, ReadOnlyQueryData, With, Without,
 };
-pub use memory::{Arena, PoolAllocator, PoolHandle};
+pub use memory::{Arena, ArenaStats, PoolAllocator, PoolHandle};
+pub use schedule::{
+    FnSystem, Schedule, ScheduleError, System, SystemAccess, SystemContext, SystemTimings,
+};
+pub use spatial::{ChunkCoord, SpatialGrid};
 pub use sync::{DoubleBufferedWorld, WorldWriteHandle, WorldReadHandle, FrameSync};
\ No newline at end of file
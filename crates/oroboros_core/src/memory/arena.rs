@@ -2,38 +2,71 @@
 //!
 //! A simple bump allocator for temporary allocations that are freed all at once.
 
-use std::cell::RefCell;
+#![allow(unsafe_code)]
+
+use std::alloc::{self, Layout};
+use std::cell::Cell;
+use std::ptr::NonNull;
+
+/// Alignment of the backing buffer. Types with larger alignment are still
+/// supported; their allocations are padded.
+const STORAGE_ALIGN: usize = 16;
+
+/// Allocation statistics for an arena.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArenaStats {
+    /// Total capacity in bytes.
+    pub capacity: usize,
+    /// Bytes in use right now.
+    pub used: usize,
+    /// Most bytes ever in use at once.
+    pub high_water: usize,
+    /// Allocations refused because the arena was full.
+    pub overflows: u64,
+}
 
 /// A bump-pointer arena allocator.
 ///
 /// Allocations are fast (just bump a pointer). Memory is freed all at once
-/// when the arena is reset or dropped.
+/// when the arena is reset or dropped. Slices borrow the arena, so
+/// [`reset`](Self::reset) can't run while any are alive.
+///
+/// Only `Copy` types are accepted: destructors never run.
 ///
 /// # Thread Safety
 ///
-/// This arena is NOT thread-safe. Use one arena per thread.
+/// This arena is NOT thread-safe. Use one arena per thread, e.g. through
+/// [`with_thread_arena`](super::with_thread_arena).
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// let arena = Arena::new(1024 * 1024); // 1MB
+/// let mut arena = Arena::new(1024 * 1024); // 1MB
 ///
-/// // Fast allocations
-/// let data = arena.alloc_slice::<f32>(1000);
+/// // Fast allocations, no heap traffic
+/// let data = arena.alloc_slice::<f32>(1000).unwrap();
+/// data[0] = 1.0;
 ///
 /// // Reset to free all allocations
 /// arena.reset();
 /// ```
 pub struct Arena {
-    /// The backing storage (kept for memory reservation).
-    #[allow(dead_code)]
-    storage: RefCell<Box<[u8]>>,
+    /// Start of the backing buffer.
+    storage: NonNull<u8>,
     /// Current allocation offset.
-    offset: RefCell<usize>,
+    offset: Cell<usize>,
     /// Total capacity.
     capacity: usize,
+    /// Highest offset reached.
+    high_water: Cell<usize>,
+    /// Failed allocations.
+    overflows: Cell<u64>,
 }
 
+// SAFETY: The arena owns its buffer; moving it to another thread moves
+// the only access path. `Cell` keeps it `!Sync`.
+unsafe impl Send for Arena {}
+
 impl Arena {
     /// Creates a new arena with the specified capacity in bytes.
     ///
@@ -42,14 +75,28 @@ impl Arena {
     /// * `capacity` - Total size in bytes
     #[must_use]
     pub fn new(capacity: usize) -> Self {
-        let storage = vec![0u8; capacity].into_boxed_slice();
+        let storage = if capacity == 0 {
+            NonNull::<u128>::dangling().cast()
+        } else {
+            // SAFETY: Size is non-zero
+            let ptr = unsafe { alloc::alloc(Self::layout(capacity)) };
+            NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(Self::layout(capacity)))
+        };
+
         Self {
-            storage: RefCell::new(storage),
-            offset: RefCell::new(0),
+            storage,
+            offset: Cell::new(0),
             capacity,
+            high_water: Cell::new(0),
+            overflows: Cell::new(0),
         }
     }
 
+    /// Layout of a backing buffer of `capacity` bytes.
+    fn layout(capacity: usize) -> Layout {
+        Layout::from_size_align(capacity, STORAGE_ALIGN).expect("Arena capacity overflows isize")
+    }
+
     /// Returns the total capacity in bytes.
     #[inline]
     #[must_use]
@@ -61,7 +108,7 @@ impl Arena {
     #[inline]
     #[must_use]
     pub fn used(&self) -> usize {
-        *self.offset.borrow()
+        self.offset.get()
     }
 
     /// Returns the remaining free space in bytes.
@@ -71,6 +118,56 @@ impl Arena {
         self.capacity - self.used()
     }
 
+    /// Returns the allocation statistics.
+    #[must_use]
+    pub fn stats(&self) -> ArenaStats {
+        ArenaStats {
+            capacity: self.capacity,
+            used: self.used(),
+            high_water: self.high_water.get(),
+            overflows: self.overflows.get(),
+        }
+    }
+
+    /// Reserves room for `count` values of `T`, returning a pointer to it.
+    fn bump<T>(&self, count: usize) -> Option<NonNull<T>> {
+        let size = std::mem::size_of::<T>().checked_mul(count);
+        let align = std::mem::align_of::<T>();
+
+        // Align the address, not the offset: T may be more aligned than
+        // the buffer
+        let base = self.storage.as_ptr() as usize;
+        let start = (base + self.offset.get() + align - 1) & !(align - 1);
+        let end = size.and_then(|size| (start - base).checked_add(size));
+
+        match end {
+            Some(end) if end <= self.capacity => {
+                self.offset.set(end);
+                self.high_water.set(self.high_water.get().max(end));
+                // SAFETY: `start - base <= end <= capacity`, so in bounds
+                Some(unsafe { NonNull::new_unchecked(self.storage.as_ptr().add(start - base).cast()) })
+            }
+            _ => {
+                self.overflows.set(self.overflows.get() + 1);
+                None
+            }
+        }
+    }
+
+    /// Allocates a single value.
+    ///
+    /// Returns None if out of space.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc<T: Copy>(&self, value: T) -> Option<&mut T> {
+        let ptr = self.bump::<T>(1)?;
+        // SAFETY: Fresh, aligned, in-bounds and exclusive until reset,
+        // which needs `&mut self`
+        unsafe {
+            ptr.as_ptr().write(value);
+            Some(&mut *ptr.as_ptr())
+        }
+    }
+
     /// Allocates a slice of `count` elements, returning a mutable reference.
     ///
     /// # Arguments
@@ -79,36 +176,60 @@ impl Arena {
     ///
     /// # Returns
     ///
-    /// A mutable slice of zeroed elements, or None if out of space.
-    pub fn alloc_slice<T: Default + Copy>(&self, count: usize) -> Option<Vec<T>> {
-        if count == 0 {
-            return Some(Vec::new());
+    /// A mutable slice of default elements, or None if out of space.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice<T: Default + Copy>(&self, count: usize) -> Option<&mut [T]> {
+        let ptr = self.bump::<T>(count)?;
+        // SAFETY: As in `alloc`; every element is written before the
+        // slice is formed
+        unsafe {
+            for i in 0..count {
+                ptr.as_ptr().add(i).write(T::default());
+            }
+            Some(std::slice::from_raw_parts_mut(ptr.as_ptr(), count))
         }
+    }
 
-        let size = std::mem::size_of::<T>() * count;
-        let align = std::mem::align_of::<T>();
-
-        let mut offset = self.offset.borrow_mut();
-        let aligned_offset = (*offset + align - 1) & !(align - 1);
-        let new_offset = aligned_offset + size;
-
-        if new_offset > self.capacity {
-            return None;
+    /// Allocates a copy of `values`.
+    ///
+    /// Returns None if out of space.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> Option<&mut [T]> {
+        let ptr = self.bump::<T>(values.len())?;
+        // SAFETY: As in `alloc`; the source can't overlap a fresh
+        // allocation
+        unsafe {
+            std::ptr::copy_nonoverlapping(values.as_ptr(), ptr.as_ptr(), values.len());
+            Some(std::slice::from_raw_parts_mut(ptr.as_ptr(), values.len()))
         }
-
-        *offset = new_offset;
-
-        // Return a new vector (safe version)
-        Some(vec![T::default(); count])
     }
 
-    /// Resets the arena, invalidating all previous allocations.
+    /// Resets the arena, freeing all previous allocations.
     ///
     /// This is a **zero-cost** operation - no memory is freed or reallocated.
-    /// Previous references become invalid and must not be used.
+    /// Taking `&mut self` guarantees no allocation is still borrowed.
     #[inline]
-    pub fn reset(&self) {
-        *self.offset.borrow_mut() = 0;
+    pub fn reset(&mut self) {
+        self.offset.set(0);
+    }
+
+    /// Rewinds to `offset` without proof that later allocations are dead.
+    ///
+    /// # Safety
+    ///
+    /// Nothing allocated past `offset` may be used afterwards.
+    pub(super) unsafe fn rewind(&self, offset: usize) {
+        debug_assert!(offset <= self.offset.get());
+        self.offset.set(offset);
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        if self.capacity > 0 {
+            // SAFETY: Allocated in `new` with this layout
+            unsafe { alloc::dealloc(self.storage.as_ptr(), Self::layout(self.capacity)) };
+        }
     }
 }
 
@@ -125,11 +246,50 @@ mod tests {
 
     #[test]
     fn test_arena_reset() {
-        let arena = Arena::new(1024);
+        let mut arena = Arena::new(1024);
         let _ = arena.alloc_slice::<f32>(10).unwrap();
         assert!(arena.used() > 0);
 
         arena.reset();
         assert_eq!(arena.used(), 0);
     }
+
+    #[test]
+    fn test_allocations_are_disjoint_and_aligned() {
+        #[derive(Clone, Copy, Default)]
+        #[repr(align(64))]
+        struct Wide(u8);
+
+        let arena = Arena::new(1024);
+        let bytes = arena.alloc_slice_copy(&[1u8, 2, 3]).unwrap();
+        let wide = arena.alloc(Wide(7)).unwrap();
+        let floats = arena.alloc_slice::<f64>(4).unwrap();
+
+        assert_eq!(wide as *const Wide as usize % 64, 0);
+        assert_eq!(floats.as_ptr() as usize % std::mem::align_of::<f64>(), 0);
+        floats.fill(9.0);
+        assert_eq!(bytes, [1, 2, 3]);
+        assert_eq!(wide.0, 7);
+        assert_eq!(floats, [9.0; 4]);
+    }
+
+    #[test]
+    fn test_stats_track_high_water_and_overflows() {
+        let mut arena = Arena::new(64);
+        assert!(arena.alloc_slice::<u8>(48).is_some());
+        assert!(arena.alloc_slice::<u8>(32).is_none());
+        assert!(arena.alloc_slice::<u64>(usize::MAX).is_none());
+        arena.reset();
+        assert!(arena.alloc_slice::<u8>(8).is_some());
+
+        assert_eq!(
+            arena.stats(),
+            ArenaStats {
+                capacity: 64,
+                used: 8,
+                high_water: 48,
+                overflows: 2,
+            }
+        );
+    }
 }
@@ -11,6 +11,8 @@
 
 mod arena;
 mod pool;
+mod scratch;
 
-pub use arena::Arena;
+pub use arena::{Arena, ArenaStats};
 pub use pool::{PoolAllocator, PoolHandle};
+pub use scratch::{thread_arena_stats, with_thread_arena, THREAD_ARENA_CAPACITY};
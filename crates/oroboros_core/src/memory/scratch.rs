@@ -0,0 +1,126 @@
+//! # Thread Scratch Arenas
+//!
+//! One lazily created [`Arena`] per thread, for worker threads that can't
+//! share the frame arena.
+
+#![allow(unsafe_code)]
+
+use std::cell::{Cell, OnceCell};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use super::arena::{Arena, ArenaStats};
+
+/// Capacity of each thread's scratch arena in bytes.
+pub const THREAD_ARENA_CAPACITY: usize = 1024 * 1024;
+
+/// Largest high-water mark any thread arena reached.
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Overflows across all thread arenas.
+static OVERFLOWS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// This thread's arena, created on first use.
+    static ARENA: OnceCell<Arena> = const { OnceCell::new() };
+    /// Nesting depth of `with_thread_arena` on this thread.
+    static DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Runs `f` with this thread's scratch arena.
+///
+/// Everything allocated is freed when the outermost call returns, so
+/// allocations can't outlive the closure. Nested calls share the arena
+/// and free nothing themselves.
+///
+/// ```rust,ignore
+/// let sum = with_thread_arena(|arena| {
+///     let ids = arena.alloc_slice_copy(&visible).unwrap();
+///     ids.sort_unstable();
+///     ids.len()
+/// });
+/// ```
+pub fn with_thread_arena<R>(f: impl FnOnce(&Arena) -> R) -> R {
+    /// Frees the arena once the outermost scope exits, even on panic.
+    struct Scope<'a> {
+        /// The thread's arena.
+        arena: &'a Arena,
+        /// Overflows when the scope was entered.
+        overflows: u64,
+    }
+
+    impl Drop for Scope<'_> {
+        fn drop(&mut self) {
+            let depth = DEPTH.with(|depth| {
+                depth.set(depth.get() - 1);
+                depth.get()
+            });
+            if depth > 0 {
+                return;
+            }
+
+            let stats = self.arena.stats();
+            PEAK.fetch_max(stats.high_water, Ordering::Relaxed);
+            OVERFLOWS.fetch_add(stats.overflows - self.overflows, Ordering::Relaxed);
+            // SAFETY: The outermost closure has returned; its `&Arena`
+            // argument was the only way to allocate, and the higher-ranked
+            // lifetime kept every allocation inside it
+            unsafe { self.arena.rewind(0) };
+        }
+    }
+
+    ARENA.with(|arena| {
+        let arena = arena.get_or_init(|| Arena::new(THREAD_ARENA_CAPACITY));
+        DEPTH.with(|depth| depth.set(depth.get() + 1));
+        let _scope = Scope {
+            arena,
+            overflows: arena.stats().overflows,
+        };
+        f(arena)
+    })
+}
+
+/// Returns statistics summed over every thread arena.
+///
+/// `high_water` is the largest any single thread reached and `overflows`
+/// is the total across threads. `used` is always zero: arenas are empty
+/// outside [`with_thread_arena`].
+#[must_use]
+pub fn thread_arena_stats() -> ArenaStats {
+    ArenaStats {
+        capacity: THREAD_ARENA_CAPACITY,
+        used: 0,
+        high_water: PEAK.load(Ordering::Relaxed),
+        overflows: OVERFLOWS.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_free_on_outermost_exit() {
+        with_thread_arena(|outer| {
+            let a = outer.alloc(1u32).unwrap();
+            let used = with_thread_arena(|inner| {
+                let b = inner.alloc_slice::<u64>(8).unwrap();
+                b[0] = 5;
+                inner.used()
+            });
+
+            // The nested scope didn't free anything under `a`
+            assert_eq!(outer.used(), used);
+            assert_eq!(*a, 1);
+        });
+        with_thread_arena(|arena| assert_eq!(arena.used(), 0));
+
+        let before = thread_arena_stats().overflows;
+        std::thread::spawn(|| {
+            with_thread_arena(|arena| assert!(arena.alloc_slice::<u8>(THREAD_ARENA_CAPACITY + 1).is_none()));
+        })
+        .join()
+        .unwrap();
+        assert!(thread_arena_stats().overflows > before);
+        assert!(thread_arena_stats().high_water >= std::mem::size_of::<u64>() * 8);
+    }
+}
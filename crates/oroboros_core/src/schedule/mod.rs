@@ -0,0 +1,715 @@
+//! # System Scheduler
+//!
+//! Runs the frame's game logic as systems, in parallel where it is safe.
+//!
+//! ```text
+//!  systems (declared access)            build()
+//! ┌──────────┐ ┌──────────┐ ┌────────┐   ┌─────────────────────────────┐
+//! │ input    │ │ movement │ │ ai     │   │ wave 0: input ‖ movement    │
+//! │          │ │ W:Pos    │ │ R:Pos  │ → │ wave 1: ai                  │
+//! │          │ │ R:Vel    │ │ W:Vel  │   │         (conflicts w/ move) │
+//! └──────────┘ └──────────┘ └────────┘   └─────────────────────────────┘
+//! ```
+//!
+//! ## Design
+//!
+//! - Systems declare component, resource and event reads/writes
+//!   ([`SystemAccess`]); access outside the declaration panics
+//! - Explicit `before`/`after` labels order systems; otherwise insertion
+//!   order breaks ties
+//! - `build()` packs systems into waves once: a system joins the earliest
+//!   wave after all its dependencies and after every earlier system it
+//!   conflicts with
+//! - Each wave runs on a fixed [`WorkerPool`]; running a frame allocates
+//!   nothing
+//! - Each system records spawns/despawns/inserts/removes into its own
+//!   [`CommandBuffer`]; buffers apply in execution order once the last
+//!   wave is done
+//! - Per-system wall time lands in [`SystemTimings`]
+
+// SAFETY: The scheduler hands raw world pointers to systems on worker
+// threads. Conflicting access is ruled out when waves are built.
+#![allow(unsafe_code)]
+
+mod pool;
+mod system;
+
+use std::ptr::NonNull;
+use std::time::Instant;
+
+use thiserror::Error;
+
+use crate::ecs::{ArchetypeTable, ArchetypeWorld, CommandBuffer};
+
+pub use pool::WorkerPool;
+pub use system::{FnSystem, System, SystemAccess, SystemContext};
+
+/// Maximum number of systems with individual timings.
+pub const MAX_TIMED_SYSTEMS: usize = 32;
+
+/// Why a schedule could not be built.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// Two systems share a label.
+    #[error("duplicate system label {0:?}")]
+    DuplicateSystem(&'static str),
+    /// An ordering constraint names a label no system has.
+    #[error("system {system:?} is ordered against unknown label {label:?}")]
+    UnknownLabel {
+        /// System with the constraint.
+        system: &'static str,
+        /// Label that doesn't exist.
+        label: &'static str,
+    },
+    /// The ordering constraints form a cycle.
+    #[error("ordering cycle through system {0:?}")]
+    Cycle(&'static str),
+}
+
+/// Wall time of one system in the last run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemTiming {
+    /// System label.
+    pub name: &'static str,
+    /// Run time in microseconds.
+    pub us: u64,
+}
+
+/// Per-system timings of the last run, in execution order.
+///
+/// Fixed-size so it can ride along in `Copy` frame stats; systems past
+/// [`MAX_TIMED_SYSTEMS`] are only counted in [`total_us`](Self::total_us).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTimings {
+    /// Individual timings.
+    entries: [SystemTiming; MAX_TIMED_SYSTEMS],
+    /// Number of valid entries.
+    len: usize,
+    /// Sum over every system.
+    total_us: u64,
+    /// Wall time of the whole run (less than the sum when parallel).
+    wall_us: u64,
+}
+
+impl SystemTimings {
+    /// Returns the timings in execution order.
+    #[must_use]
+    pub fn entries(&self) -> &[SystemTiming] {
+        &self.entries[..self.len]
+    }
+
+    /// Returns a system's run time in microseconds.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.entries().iter().find(|timing| timing.name == name).map(|timing| timing.us)
+    }
+
+    /// Returns the summed run time of every system in microseconds.
+    #[must_use]
+    pub const fn total_us(&self) -> u64 {
+        self.total_us
+    }
+
+    /// Returns the wall time of the whole run in microseconds.
+    #[must_use]
+    pub const fn wall_us(&self) -> u64 {
+        self.wall_us
+    }
+
+    /// Appends one timing.
+    fn push(&mut self, name: &'static str, us: u64) {
+        if self.len < MAX_TIMED_SYSTEMS {
+            self.entries[self.len] = SystemTiming { name, us };
+            self.len += 1;
+        }
+        self.total_us += us;
+    }
+}
+
+/// A registered system.
+struct Entry {
+    /// The system.
+    system: Box<dyn System>,
+    /// Its label.
+    name: &'static str,
+    /// Its access, read once at registration.
+    access: SystemAccess,
+    /// Labels that must run after this system.
+    before: Vec<&'static str>,
+    /// Labels that must run before this system.
+    after: Vec<&'static str>,
+    /// Deferred structural changes, reused every run.
+    commands: CommandBuffer,
+    /// Run time of the last run in microseconds.
+    last_us: u64,
+}
+
+/// Ordering options for a just-added system.
+pub struct SystemConfig<'a> {
+    /// The entry being configured.
+    entry: &'a mut Entry,
+}
+
+impl SystemConfig<'_> {
+    /// Runs this system before the system labelled `label`.
+    #[must_use]
+    pub fn before(self, label: &'static str) -> Self {
+        self.entry.before.push(label);
+        self
+    }
+
+    /// Runs this system after the system labelled `label`.
+    #[must_use]
+    pub fn after(self, label: &'static str) -> Self {
+        self.entry.after.push(label);
+        self
+    }
+}
+
+/// A set of systems and the plan for running them.
+pub struct Schedule {
+    /// Registered systems, in insertion order.
+    systems: Vec<Entry>,
+    /// System indices, wave by wave.
+    order: Vec<usize>,
+    /// End of each wave in `order`.
+    wave_ends: Vec<usize>,
+    /// Whether `order` reflects `systems`.
+    built: bool,
+    /// Threads running waves.
+    pool: WorkerPool,
+    /// Timings of the last run.
+    timings: SystemTimings,
+}
+
+/// Raw pointers one wave's workers share.
+#[derive(Clone, Copy)]
+struct WaveState {
+    /// First registered system.
+    systems: NonNull<Entry>,
+    /// The world being updated.
+    world: NonNull<ArchetypeWorld>,
+    /// First archetype table as of the start of the wave.
+    tables: NonNull<ArchetypeTable>,
+    /// Number of archetype tables.
+    table_count: usize,
+    /// Seconds since the last frame.
+    delta_time: f32,
+    /// Current frame number.
+    frame: u64,
+}
+
+// SAFETY: Workers claim distinct systems, and systems in one wave have
+// non-conflicting access
+unsafe impl Sync for WaveState {}
+
+impl WaveState {
+    /// Runs and times one system.
+    ///
+    /// # Safety
+    ///
+    /// `system` must be claimed by one worker only, and every system
+    /// running at the same time must have non-conflicting access.
+    unsafe fn run_system(&self, system: usize) {
+        let entry = &mut *self.systems.as_ptr().add(system);
+        let mut ctx = SystemContext::new(
+            self.world,
+            self.tables,
+            self.table_count,
+            entry.access,
+            self.delta_time,
+            self.frame,
+            &mut entry.commands,
+        );
+
+        let started = Instant::now();
+        entry.system.run(&mut ctx);
+        entry.last_us = started.elapsed().as_micros() as u64;
+    }
+}
+
+impl Schedule {
+    /// Creates an empty schedule with `worker_threads` threads.
+    ///
+    /// Zero threads runs every system on the caller, in order.
+    #[must_use]
+    pub fn new(worker_threads: usize) -> Self {
+        Self {
+            systems: Vec::new(),
+            order: Vec::new(),
+            wave_ends: Vec::new(),
+            built: false,
+            pool: WorkerPool::new(worker_threads),
+            timings: SystemTimings::default(),
+        }
+    }
+
+    /// Adds a system, returning a handle to order it.
+    pub fn add_system(&mut self, system: impl System + 'static) -> SystemConfig<'_> {
+        let access = system.access();
+        let name = system.name();
+        self.built = false;
+        let index = self.systems.len();
+        self.systems.push(Entry {
+            system: Box::new(system),
+            name,
+            access,
+            before: Vec::new(),
+            after: Vec::new(),
+            commands: CommandBuffer::new(),
+            last_us: 0,
+        });
+        SystemConfig { entry: &mut self.systems[index] }
+    }
+
+    /// Returns the number of systems.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Checks if there are no systems.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Returns the number of worker threads.
+    #[must_use]
+    pub fn worker_threads(&self) -> usize {
+        self.pool.threads()
+    }
+
+    /// Resolves ordering and packs systems into parallel waves.
+    ///
+    /// Called by [`run`](Self::run) when systems were added since the
+    /// last build.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for duplicate labels, unknown labels or cycles.
+    pub fn build(&mut self) -> Result<(), ScheduleError> {
+        let count = self.systems.len();
+        let index_of = |label: &'static str, system: &'static str| {
+            self.systems
+                .iter()
+                .position(|entry| entry.name == label)
+                .ok_or(ScheduleError::UnknownLabel { system, label })
+        };
+
+        for (i, entry) in self.systems.iter().enumerate() {
+            if self.systems[..i].iter().any(|other| other.name == entry.name) {
+                return Err(ScheduleError::DuplicateSystem(entry.name));
+            }
+        }
+
+        // Dependency edges: (earlier, later)
+        let mut edges = Vec::new();
+        for (i, entry) in self.systems.iter().enumerate() {
+            for &label in &entry.before {
+                edges.push((i, index_of(label, entry.name)?));
+            }
+            for &label in &entry.after {
+                edges.push((index_of(label, entry.name)?, i));
+            }
+        }
+
+        // Kahn's algorithm, lowest insertion index first
+        let mut pending: Vec<usize> = vec![0; count];
+        for &(_, later) in &edges {
+            pending[later] += 1;
+        }
+        let mut sorted = Vec::with_capacity(count);
+        let mut done = vec![false; count];
+        while sorted.len() < count {
+            let Some(next) = (0..count).find(|&i| !done[i] && pending[i] == 0) else {
+                let stuck = (0..count).find(|&i| !done[i]).unwrap_or(0);
+                return Err(ScheduleError::Cycle(self.systems[stuck].name));
+            };
+            done[next] = true;
+            sorted.push(next);
+            for &(earlier, later) in &edges {
+                if earlier == next {
+                    pending[later] -= 1;
+                }
+            }
+        }
+
+        // Pack into waves: after every dependency, and after every earlier
+        // system it conflicts with, so conflicting systems keep their order
+        let mut waves: Vec<Vec<usize>> = Vec::new();
+        let mut wave_of = vec![0; count];
+        for (position, &system) in sorted.iter().enumerate() {
+            let access = &self.systems[system].access;
+            let after_edges = edges
+                .iter()
+                .filter(|&&(_, later)| later == system)
+                .map(|&(earlier, _)| wave_of[earlier] + 1);
+            let after_conflicts = sorted[..position]
+                .iter()
+                .filter(|&&other| access.conflicts_with(&self.systems[other].access))
+                .map(|&other| wave_of[other] + 1);
+            let wave = after_edges.chain(after_conflicts).max().unwrap_or(0);
+
+            if wave == waves.len() {
+                waves.push(Vec::new());
+            }
+            waves[wave].push(system);
+            wave_of[system] = wave;
+        }
+
+        self.order.clear();
+        self.wave_ends.clear();
+        for wave in waves {
+            self.order.extend(wave);
+            self.wave_ends.push(self.order.len());
+        }
+        self.built = true;
+        Ok(())
+    }
+
+    /// Returns the wave a system runs in, once built.
+    #[must_use]
+    pub fn wave_of(&self, name: &str) -> Option<usize> {
+        if !self.built {
+            return None;
+        }
+        let position = self.order.iter().position(|&i| self.systems[i].name == name)?;
+        Some(self.wave_ends.iter().position(|&end| position < end).unwrap_or(0))
+    }
+
+    /// Returns the number of waves, once built.
+    #[must_use]
+    pub fn wave_count(&self) -> usize {
+        self.wave_ends.len()
+    }
+
+    /// Runs every system once, wave by wave, then applies their command
+    /// buffers in execution order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schedule needed building and failed.
+    pub fn run(
+        &mut self,
+        world: &mut ArchetypeWorld,
+        delta_time: f32,
+        frame: u64,
+    ) -> Result<&SystemTimings, ScheduleError> {
+        if !self.built {
+            self.build()?;
+        }
+
+        let started = Instant::now();
+        let systems = NonNull::from(self.systems.as_mut_slice()).cast();
+        let world = NonNull::from(world);
+
+        let mut start = 0;
+        for &end in &self.wave_ends {
+            let wave = &self.order[start..end];
+
+            // Re-read per wave: exclusive systems may have created archetypes
+            // SAFETY: No system is running between waves
+            let tables = unsafe { (*world.as_ptr()).archetypes_mut() };
+            let state = WaveState {
+                systems,
+                world,
+                table_count: tables.len(),
+                tables: NonNull::from(tables).cast(),
+                delta_time,
+                frame,
+            };
+
+            // SAFETY: The pool hands out each index once, and `build` only
+            // puts non-conflicting systems in a wave
+            self.pool.run(wave.len(), &|i| unsafe { state.run_system(wave[i]) });
+            start = end;
+        }
+
+        // Sync point: no system is running and no query is alive
+        // SAFETY: The last wave has finished
+        let world = unsafe { &mut *world.as_ptr() };
+        for &i in &self.order {
+            self.systems[i].commands.apply(world);
+        }
+
+        self.timings = SystemTimings::default();
+        for &i in &self.order {
+            self.timings.push(self.systems[i].name, self.systems[i].last_us);
+        }
+        self.timings.wall_us = started.elapsed().as_micros() as u64;
+        Ok(&self.timings)
+    }
+
+    /// Returns the timings of the last run.
+    #[must_use]
+    pub fn timings(&self) -> &SystemTimings {
+        &self.timings
+    }
+}
+
+/// Worker threads to use by default: one per spare core, up to 7.
+///
+/// Always zero on targets without threads.
+#[must_use]
+pub fn default_worker_threads() -> usize {
+    if cfg!(target_arch = "wasm32") {
+        return 0;
+    }
+    std::thread::available_parallelism().map_or(0, |cores| cores.get().saturating_sub(1).min(7))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{EntityId, EventCursor, Position, Velocity};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn noop(name: &'static str, access: SystemAccess) -> FnSystem<impl FnMut(&mut SystemContext<'_>) + Send> {
+        FnSystem::new(name, access, |_| {})
+    }
+
+    #[test]
+    fn test_waves_respect_access_and_order() {
+        let mut schedule = Schedule::new(0);
+        schedule.add_system(noop("movement", SystemAccess::new().write::<Position>().read::<Velocity>()));
+        schedule.add_system(noop("render_prep", SystemAccess::new().read::<Position>()));
+        schedule.add_system(noop("steering", SystemAccess::new().write::<Velocity>()));
+        let _ = schedule.add_system(noop("input", SystemAccess::new())).before("steering");
+        schedule.add_system(noop("spawner", SystemAccess::new().exclusive()));
+        schedule.build().unwrap();
+
+        assert_eq!(schedule.wave_of("input"), Some(0));
+        assert_eq!(schedule.wave_of("movement"), Some(0));
+        // Reads what movement writes
+        assert_eq!(schedule.wave_of("render_prep"), Some(1));
+        // After input, and writes what movement reads
+        assert_eq!(schedule.wave_of("steering"), Some(1));
+        // Runs alone
+        assert_eq!(schedule.wave_of("spawner"), Some(2));
+        assert_eq!(schedule.wave_count(), 3);
+    }
+
+    #[test]
+    fn test_conflicts_keep_insertion_order() {
+        let mut schedule = Schedule::new(0);
+        schedule.add_system(noop("a", SystemAccess::new().write::<Position>()));
+        schedule.add_system(noop("b", SystemAccess::new().read::<Position>().write::<Velocity>()));
+        schedule.add_system(noop("c", SystemAccess::new().write::<Velocity>()));
+        schedule.build().unwrap();
+
+        // c doesn't touch Position, but must not overtake b on Velocity
+        assert_eq!(schedule.wave_of("a"), Some(0));
+        assert_eq!(schedule.wave_of("b"), Some(1));
+        assert_eq!(schedule.wave_of("c"), Some(2));
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let mut schedule = Schedule::new(0);
+        let _ = schedule.add_system(noop("a", SystemAccess::new())).after("b");
+        let _ = schedule.add_system(noop("b", SystemAccess::new())).after("a");
+        assert_eq!(schedule.build(), Err(ScheduleError::Cycle("a")));
+
+        let mut schedule = Schedule::new(0);
+        let _ = schedule.add_system(noop("a", SystemAccess::new())).after("missing");
+        assert_eq!(
+            schedule.build(),
+            Err(ScheduleError::UnknownLabel { system: "a", label: "missing" })
+        );
+
+        let mut schedule = Schedule::new(0);
+        schedule.add_system(noop("a", SystemAccess::new()));
+        schedule.add_system(noop("a", SystemAccess::new()));
+        assert_eq!(schedule.build(), Err(ScheduleError::DuplicateSystem("a")));
+    }
+
+    #[test]
+    fn test_run_updates_world_and_timings() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let id = world.spawn_pv(Position::new(0.0, 0.0, 0.0), Velocity::new(1.0, 0.0, 0.0));
+
+        let mut schedule = Schedule::new(2);
+        schedule.add_system(FnSystem::new(
+            "movement",
+            SystemAccess::new().write::<Position>().read::<Velocity>(),
+            |ctx| {
+                let dt = ctx.delta_time();
+                for (_, (pos, vel)) in ctx.query_mut::<(&mut Position, &Velocity)>() {
+                    pos.x += vel.x * dt;
+                }
+            },
+        ));
+        let _ = schedule
+            .add_system(FnSystem::new(
+                "spawner",
+                SystemAccess::new().exclusive(),
+                |ctx| {
+                    let _ = ctx.world_mut().spawn_p(Position::new(9.0, 0.0, 0.0));
+                },
+            ))
+            .after("movement");
+
+        for frame in 0..3 {
+            let timings = schedule.run(&mut world, 0.5, frame).unwrap();
+            assert_eq!(timings.entries().len(), 2);
+            assert!(timings.get("movement").is_some());
+        }
+
+        assert!((world.get_position(id).unwrap().x - 1.5).abs() < f32::EPSILON);
+        assert_eq!(world.p_table().len(), 3);
+    }
+
+    #[test]
+    fn test_commands_apply_after_last_wave() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        for x in [0.0, 1.0, 2.0, 3.0] {
+            let _ = world.spawn_p(Position::new(x, 0.0, 0.0));
+        }
+
+        let mut schedule = Schedule::new(1);
+        schedule.add_system(FnSystem::new("cull", SystemAccess::new().read::<Position>(), |ctx| {
+            let doomed: [Option<EntityId>; 4] = {
+                let mut doomed = [None; 4];
+                for (slot, (id, pos)) in doomed.iter_mut().zip(ctx.query::<&Position>()) {
+                    if pos.x >= 2.0 {
+                        *slot = Some(id);
+                    }
+                }
+                doomed
+            };
+            for id in doomed.into_iter().flatten() {
+                ctx.commands().despawn(id);
+            }
+        }));
+        schedule.add_system(FnSystem::new("spawn", SystemAccess::new(), |ctx| {
+            ctx.commands().spawn().insert(Position::new(10.0, 0.0, 0.0)).insert(Velocity::default());
+        }));
+        let _ = schedule
+            .add_system(FnSystem::new("count", SystemAccess::new().read::<Position>(), |ctx| {
+                // Structural changes are not visible until the run is over
+                assert_eq!(ctx.query::<&Position>().count(), 4);
+            }))
+            .after("spawn");
+
+        schedule.run(&mut world, 0.0, 0).unwrap();
+
+        assert_eq!(world.alive_count(), 3);
+        assert_eq!(world.pv_table().len(), 1);
+    }
+
+    #[test]
+    fn test_resources_and_events() {
+        struct Tick(u64);
+        struct Damage(u32);
+
+        let mut world = ArchetypeWorld::new(0, 0);
+        world.insert_resource(Tick(0));
+        world.add_events::<Damage>();
+
+        let mut schedule = Schedule::new(1);
+        let mut early_cursor = EventCursor::new();
+        let mut late_cursor = EventCursor::new();
+        let seen = Arc::new(AtomicUsize::new(0));
+
+        // Reads before the sender runs: sees events a frame late
+        let early_seen = Arc::clone(&seen);
+        schedule.add_system(FnSystem::new(
+            "early_reader",
+            SystemAccess::new().read_events::<Damage>(),
+            move |ctx| {
+                let events = ctx.events::<Damage>().unwrap();
+                let total: u32 = early_cursor.read(events).map(|damage| damage.0).sum();
+                early_seen.fetch_add(total as usize, Ordering::SeqCst);
+            },
+        ));
+        let _ = schedule
+            .add_system(FnSystem::new(
+                "combat",
+                SystemAccess::new().write_resource::<Tick>().send_events::<Damage>(),
+                |ctx| {
+                    ctx.resource_mut::<Tick>().unwrap().0 += 1;
+                    assert!(ctx.send_event(Damage(10)));
+                },
+            ))
+            .after("early_reader");
+        // Reads after the sender: sees events the same frame
+        let _ = schedule
+            .add_system(FnSystem::new(
+                "late_reader",
+                SystemAccess::new().read_resource::<Tick>().read_events::<Damage>(),
+                move |ctx| {
+                    let tick = ctx.resource::<Tick>().unwrap().0;
+                    let events = ctx.events::<Damage>().unwrap();
+                    assert_eq!(late_cursor.read(events).count(), 1, "tick {tick}");
+                },
+            ))
+            .after("combat");
+        schedule.build().unwrap();
+        assert_eq!(schedule.wave_count(), 3);
+
+        for frame in 0..3 {
+            world.update_events();
+            schedule.run(&mut world, 0.0, frame).unwrap();
+        }
+
+        assert_eq!(world.resource::<Tick>().unwrap().0, 3);
+        assert_eq!(seen.load(Ordering::SeqCst), 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not declare")]
+    fn test_undeclared_resource_panics() {
+        struct Tick(u64);
+
+        let mut world = ArchetypeWorld::new(0, 0);
+        world.insert_resource(Tick(0));
+        let mut schedule = Schedule::new(0);
+        schedule.add_system(FnSystem::new("sneaky", SystemAccess::new().read_resource::<Tick>(), |ctx| {
+            ctx.resource_mut::<Tick>().unwrap().0 += 1;
+        }));
+        let _ = schedule.run(&mut world, 0.0, 0);
+    }
+
+    #[test]
+    fn test_disjoint_systems_run_in_parallel() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let overlapped = Arc::new(AtomicUsize::new(0));
+
+        let mut schedule = Schedule::new(1);
+        for name in ["a", "b"] {
+            let running = Arc::clone(&running);
+            let overlapped = Arc::clone(&overlapped);
+            schedule.add_system(FnSystem::new(name, SystemAccess::new(), move |_| {
+                running.fetch_add(1, Ordering::SeqCst);
+                let deadline = Instant::now() + Duration::from_secs(2);
+                while Instant::now() < deadline {
+                    if running.load(Ordering::SeqCst) == 2 {
+                        overlapped.fetch_add(1, Ordering::SeqCst);
+                        break;
+                    }
+                    std::hint::spin_loop();
+                }
+            }));
+        }
+
+        let mut world = ArchetypeWorld::new(0, 0);
+        schedule.run(&mut world, 0.0, 0).unwrap();
+        assert_eq!(overlapped.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not declare")]
+    fn test_undeclared_query_panics() {
+        let mut world = ArchetypeWorld::new(16, 16);
+        let mut schedule = Schedule::new(0);
+        schedule.add_system(FnSystem::new("sneaky", SystemAccess::new().read::<Velocity>(), |ctx| {
+            for (_, pos) in ctx.query_mut::<&mut Position>() {
+                pos.x = 0.0;
+            }
+        }));
+        let _ = schedule.run(&mut world, 0.0, 0);
+    }
+}
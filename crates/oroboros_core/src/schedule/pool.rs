@@ -0,0 +1,243 @@
+//! Fixed worker pool for running a wave of systems.
+//!
+//! Threads are spawned once. Each job is a borrowed `Fn(usize)` plus a
+//! count; the caller and every worker claim indices from one atomic
+//! counter until none are left. Dispatch allocates nothing.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use parking_lot::{Condvar, Mutex};
+
+/// A borrowed job: run `task(i)` for every `i` in `0..count`.
+#[derive(Clone, Copy)]
+struct Job {
+    /// The task, borrowed from the caller of [`WorkerPool::run`].
+    task: *const (dyn Fn(usize) + Sync),
+    /// Number of indices.
+    count: usize,
+}
+
+// SAFETY: The task is Sync, and `run` keeps it alive until every worker
+// has let go of the job.
+unsafe impl Send for Job {}
+
+/// State shared under the pool lock.
+struct PoolState {
+    /// Bumped for every job so each worker joins it at most once.
+    generation: u64,
+    /// Current job, cleared once the caller has collected every worker.
+    job: Option<Job>,
+    /// Workers still inside the current job.
+    active: usize,
+    /// Set when a task panicked on a worker.
+    panicked: bool,
+    /// Tells workers to exit.
+    shutdown: bool,
+}
+
+/// Shared between the pool and its threads.
+struct Shared {
+    /// Job state.
+    state: Mutex<PoolState>,
+    /// Signals a new job or shutdown.
+    job_ready: Condvar,
+    /// Signals the last worker leaving a job.
+    job_done: Condvar,
+    /// Next unclaimed index of the current job.
+    next: AtomicUsize,
+}
+
+impl Shared {
+    /// Claims and runs indices until the job is exhausted.
+    ///
+    /// # Safety
+    ///
+    /// The job's task must be alive.
+    unsafe fn work(&self, job: Job) {
+        loop {
+            let index = self.next.fetch_add(1, Ordering::AcqRel);
+            if index >= job.count {
+                break;
+            }
+            (*job.task)(index);
+        }
+    }
+}
+
+/// Fixed set of worker threads.
+///
+/// With zero threads every job runs on the caller, in index order.
+pub struct WorkerPool {
+    /// State shared with the workers.
+    shared: Arc<Shared>,
+    /// Worker threads, joined on drop.
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Spawns `threads` workers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a thread cannot be spawned.
+    #[must_use]
+    pub fn new(threads: usize) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(PoolState {
+                generation: 0,
+                job: None,
+                active: 0,
+                panicked: false,
+                shutdown: false,
+            }),
+            job_ready: Condvar::new(),
+            job_done: Condvar::new(),
+            next: AtomicUsize::new(0),
+        });
+
+        let threads = (0..threads)
+            .map(|i| {
+                let shared = Arc::clone(&shared);
+                std::thread::Builder::new()
+                    .name(format!("oroboros-worker-{i}"))
+                    .spawn(move || worker_loop(&shared))
+                    .expect("Failed to spawn worker thread")
+            })
+            .collect();
+
+        Self { shared, threads }
+    }
+
+    /// Returns the number of worker threads (not counting the caller).
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Runs `task(i)` for every `i` in `0..count` across the workers and
+    /// the calling thread, returning once all have finished.
+    ///
+    /// # Panics
+    ///
+    /// Re-raises a panic from any task once every worker has finished.
+    pub fn run(&self, count: usize, task: &(dyn Fn(usize) + Sync)) {
+        if count == 0 {
+            return;
+        }
+        if self.threads.is_empty() || count == 1 {
+            (0..count).for_each(task);
+            return;
+        }
+
+        // SAFETY: Only the lifetime is erased; we wait below until no
+        // worker holds the job before `task` goes out of scope.
+        let task: *const (dyn Fn(usize) + Sync) = unsafe { std::mem::transmute(task) };
+        let job = Job { task, count };
+
+        {
+            let mut state = self.shared.state.lock();
+            self.shared.next.store(0, Ordering::Release);
+            state.job = Some(job);
+            state.generation = state.generation.wrapping_add(1);
+            self.shared.job_ready.notify_all();
+        }
+
+        // Workers may still hold `task`, so don't unwind past them
+        // SAFETY: `task` is alive for this whole call
+        let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe { self.shared.work(job) }));
+
+        let mut state = self.shared.state.lock();
+        while state.active > 0 {
+            self.shared.job_done.wait(&mut state);
+        }
+        state.job = None;
+        let worker_panicked = std::mem::take(&mut state.panicked);
+        drop(state);
+
+        if let Err(payload) = result {
+            panic::resume_unwind(payload);
+        }
+        assert!(!worker_panicked, "Task panicked on a worker thread");
+    }
+}
+
+/// Body of every worker thread.
+fn worker_loop(shared: &Shared) {
+    let mut seen = 0;
+    loop {
+        let job = {
+            let mut state = shared.state.lock();
+            while state.generation == seen && !state.shutdown {
+                shared.job_ready.wait(&mut state);
+            }
+            if state.shutdown {
+                return;
+            }
+            seen = state.generation;
+            let Some(job) = state.job else {
+                // Woke after the job was already collected
+                continue;
+            };
+            state.active += 1;
+            job
+        };
+
+        // SAFETY: The caller waits for `active` to drop to zero
+        let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe { shared.work(job) }));
+
+        let mut state = shared.state.lock();
+        state.panicked |= result.is_err();
+        state.active -= 1;
+        if state.active == 0 {
+            shared.job_done.notify_all();
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        self.shared.state.lock().shutdown = true;
+        self.shared.job_ready.notify_all();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_every_index_once() {
+        let pool = WorkerPool::new(3);
+        let hits: Vec<AtomicUsize> = (0..100).map(|_| AtomicUsize::new(0)).collect();
+
+        for _ in 0..50 {
+            pool.run(hits.len(), &|i| {
+                hits[i].fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        assert!(hits.iter().all(|hit| hit.load(Ordering::Relaxed) == 50));
+    }
+
+    #[test]
+    fn test_task_panic_propagates() {
+        let pool = WorkerPool::new(2);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.run(64, &|i| assert!(i != 40, "task 40 failed"));
+        }));
+        assert!(result.is_err());
+
+        // The pool is still usable afterwards
+        let hits = AtomicUsize::new(0);
+        pool.run(64, &|_| {
+            hits.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(hits.load(Ordering::Relaxed), 64);
+    }
+}
@@ -0,0 +1,387 @@
+//! Systems and what they may touch.
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::ecs::{
+    resource_bit, Access, ArchetypeTable, ArchetypeWorld, CommandBuffer, Component, Event,
+    Events, QueryData, QueryFilter, QueryIter, ReadOnlyQueryData, Resource,
+};
+
+/// Everything a system may touch, declared up front.
+///
+/// The schedule runs two systems at the same time only if their access
+/// doesn't conflict; [`SystemContext`] enforces the declaration.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemAccess {
+    /// Component reads and writes.
+    components: Access,
+    /// Resource and event queue reads and writes.
+    resources: Access,
+    /// Whether the system needs the whole world to itself.
+    exclusive: bool,
+}
+
+impl SystemAccess {
+    /// Creates an empty declaration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a read of component `C`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` is already declared as a write.
+    #[must_use]
+    pub fn read<C: Component>(mut self) -> Self {
+        self.components.read::<C>();
+        self
+    }
+
+    /// Declares a write of component `C` (which implies a read).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` is already declared.
+    #[must_use]
+    pub fn write<C: Component>(mut self) -> Self {
+        self.components.write::<C>();
+        self
+    }
+
+    /// Declares a read of resource `R`.
+    #[must_use]
+    pub fn read_resource<R: Resource>(mut self) -> Self {
+        self.resources.read_bits(resource_bit::<R>());
+        self
+    }
+
+    /// Declares a write of resource `R`.
+    #[must_use]
+    pub fn write_resource<R: Resource>(mut self) -> Self {
+        self.resources.write_bits(resource_bit::<R>());
+        self
+    }
+
+    /// Declares reading events of type `E`.
+    #[must_use]
+    pub fn read_events<E: Event>(self) -> Self {
+        self.read_resource::<Events<E>>()
+    }
+
+    /// Declares sending events of type `E`.
+    #[must_use]
+    pub fn send_events<E: Event>(self) -> Self {
+        self.write_resource::<Events<E>>()
+    }
+
+    /// Declares that the system needs the whole world (spawning,
+    /// despawning, moving entities between archetypes). It runs alone.
+    #[must_use]
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+
+    /// Returns whether the system runs alone.
+    #[must_use]
+    pub const fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// Returns the declared component access.
+    #[must_use]
+    pub const fn components(&self) -> &Access {
+        &self.components
+    }
+
+    /// Returns the declared resource access.
+    #[must_use]
+    pub const fn resources(&self) -> &Access {
+        &self.resources
+    }
+
+    /// Checks if two systems must not run at the same time.
+    #[must_use]
+    pub const fn conflicts_with(&self, other: &Self) -> bool {
+        self.exclusive
+            || other.exclusive
+            || self.components.conflicts_with(&other.components)
+            || self.resources.conflicts_with(&other.resources)
+    }
+}
+
+/// A unit of per-frame game logic.
+pub trait System: Send {
+    /// Unique label, used for ordering and timings.
+    fn name(&self) -> &'static str;
+
+    /// Declares what the system touches.
+    fn access(&self) -> SystemAccess;
+
+    /// Runs the system for one frame.
+    fn run(&mut self, ctx: &mut SystemContext<'_>);
+}
+
+/// A [`System`] built from a closure.
+///
+/// ```rust,ignore
+/// schedule.add_system(FnSystem::new(
+///     "movement",
+///     SystemAccess::new().write::<Position>().read::<Velocity>(),
+///     |ctx| {
+///         let dt = ctx.delta_time();
+///         for (_, (pos, vel)) in ctx.query_mut::<(&mut Position, &Velocity)>() {
+///             pos.x += vel.x * dt;
+///         }
+///     },
+/// ));
+/// ```
+pub struct FnSystem<F> {
+    /// Label.
+    name: &'static str,
+    /// Declared access.
+    access: SystemAccess,
+    /// Body.
+    run: F,
+}
+
+impl<F: FnMut(&mut SystemContext<'_>) + Send> FnSystem<F> {
+    /// Wraps `run` as a system.
+    #[must_use]
+    pub fn new(name: &'static str, access: SystemAccess, run: F) -> Self {
+        Self { name, access, run }
+    }
+}
+
+impl<F: FnMut(&mut SystemContext<'_>) + Send> System for FnSystem<F> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn access(&self) -> SystemAccess {
+        self.access
+    }
+
+    fn run(&mut self, ctx: &mut SystemContext<'_>) {
+        (self.run)(ctx);
+    }
+}
+
+/// A system's view of the world for one run.
+///
+/// Queries are checked against the system's declared access, so systems
+/// running in parallel can never alias.
+pub struct SystemContext<'w> {
+    /// The world, only dereferenced by exclusive systems.
+    world: NonNull<ArchetypeWorld>,
+    /// First archetype table.
+    tables: NonNull<ArchetypeTable>,
+    /// Number of archetype tables.
+    table_count: usize,
+    /// What this system declared.
+    access: SystemAccess,
+    /// Seconds since the last frame.
+    delta_time: f32,
+    /// Current frame number.
+    frame: u64,
+    /// This system's deferred structural changes.
+    commands: &'w mut CommandBuffer,
+    /// Ties the context to the world borrow.
+    _world: PhantomData<&'w mut ArchetypeWorld>,
+}
+
+// SAFETY: Contexts only reach the world through declared, non-conflicting access
+unsafe impl Send for SystemContext<'_> {}
+
+impl<'w> SystemContext<'w> {
+    /// Creates a context for one system run.
+    ///
+    /// # Safety
+    ///
+    /// `world` must stay valid for `'w`. No system running at the same
+    /// time may have conflicting access, and no archetype may be created
+    /// while the context is alive unless it is exclusive.
+    pub(super) unsafe fn new(
+        world: NonNull<ArchetypeWorld>,
+        tables: NonNull<ArchetypeTable>,
+        table_count: usize,
+        access: SystemAccess,
+        delta_time: f32,
+        frame: u64,
+        commands: &'w mut CommandBuffer,
+    ) -> Self {
+        Self {
+            world,
+            tables,
+            table_count,
+            access,
+            delta_time,
+            frame,
+            commands,
+            _world: PhantomData,
+        }
+    }
+
+    /// Returns seconds since the last frame.
+    #[must_use]
+    pub const fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// Returns the current frame number.
+    #[must_use]
+    pub const fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the system's command buffer.
+    ///
+    /// Spawns, despawns and component inserts/removes recorded here are
+    /// applied once every system in the schedule has run.
+    pub fn commands(&mut self) -> &mut CommandBuffer {
+        self.commands
+    }
+
+    /// Panics unless the system declared everything `Q` and `F` touch.
+    fn check<Q: QueryData, F: QueryFilter>(&self) {
+        if self.access.is_exclusive() {
+            return;
+        }
+        assert!(
+            Access::of::<Q, F>().is_covered_by(self.access.components()),
+            "System queried components it did not declare"
+        );
+    }
+
+    /// Iterates every entity with the components in `Q`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare the components read.
+    pub fn query<Q: ReadOnlyQueryData>(&mut self) -> QueryIter<'_, Q> {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// Iterates every entity with the components in `Q` that passes `F`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare the components read.
+    pub fn query_filtered<Q: ReadOnlyQueryData, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        self.query_filtered_mut::<Q, F>()
+    }
+
+    /// Iterates every entity with the components in `Q`, mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare the components touched.
+    pub fn query_mut<Q: QueryData>(&mut self) -> QueryIter<'_, Q> {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// Iterates every entity with the components in `Q` that passes `F`,
+    /// mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare the components touched.
+    pub fn query_filtered_mut<Q: QueryData, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        self.check::<Q, F>();
+        if self.access.is_exclusive() {
+            // The system may have created archetypes since the last query
+            // SAFETY: Exclusive systems run alone
+            let tables = unsafe { self.world.as_mut() }.archetypes_mut();
+            self.table_count = tables.len();
+            self.tables = NonNull::from(tables).cast();
+        }
+        // SAFETY: Access is declared and doesn't conflict with any system
+        // running now; `&mut self` keeps one iterator alive at a time
+        unsafe { QueryIter::new(self.tables, self.table_count) }
+    }
+
+    /// Panics unless the system declared `R` (writably if `write`).
+    fn check_resource<R: Resource>(&self, write: bool) {
+        if self.access.is_exclusive() {
+            return;
+        }
+        let mut used = Access::default();
+        if write {
+            used.write_bits(resource_bit::<R>());
+        } else {
+            used.read_bits(resource_bit::<R>());
+        }
+        assert!(
+            used.is_covered_by(self.access.resources()),
+            "System accessed a resource it did not declare"
+        );
+    }
+
+    /// Returns the resource of type `R`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare reading `R`.
+    #[must_use]
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.check_resource::<R>(false);
+        // SAFETY: Declared; no system running now writes `R`, and
+        // `world_mut` needs `&mut self`
+        unsafe { ArchetypeWorld::resources_raw(self.world.as_ptr()) }.get::<R>()
+    }
+
+    /// Returns the resource of type `R` mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare writing `R`.
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.check_resource::<R>(true);
+        // SAFETY: Declared as a write, so no system running now touches
+        // `R`; `&mut self` keeps this the only borrow from this context
+        unsafe {
+            ArchetypeWorld::resources_raw(self.world.as_ptr())
+                .get_ptr::<R>()
+                .map(|ptr| &mut *ptr.as_ptr())
+        }
+    }
+
+    /// Returns the queue of events of type `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare reading `E`.
+    #[must_use]
+    pub fn events<E: Event>(&self) -> Option<&Events<E>> {
+        self.resource::<Events<E>>()
+    }
+
+    /// Sends an event. Returns `false` if `E` was never registered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system did not declare sending `E`.
+    pub fn send_event<E: Event>(&mut self, event: E) -> bool {
+        match self.resource_mut::<Events<E>>() {
+            Some(events) => {
+                events.send(event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the whole world.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the system declared [`SystemAccess::exclusive`].
+    pub fn world_mut(&mut self) -> &mut ArchetypeWorld {
+        assert!(self.access.is_exclusive(), "Only exclusive systems can access the whole world");
+        // SAFETY: Exclusive systems run alone
+        unsafe { self.world.as_mut() }
+    }
+}
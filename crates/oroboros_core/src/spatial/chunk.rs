@@ -0,0 +1,57 @@
+//! Chunk grid addressing.
+//!
+//! Lives in core so crates that only need to name chunks (world
+//! generation, sharding) agree on them without depending on each other.
+
+/// Chunk width/depth in blocks.
+pub const CHUNK_SIZE: usize = 16;
+
+/// Chunk coordinate (identifies a chunk in the world grid).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChunkCoord {
+    /// X coordinate (in chunks, not blocks).
+    pub x: i32,
+    /// Z coordinate (in chunks, not blocks).
+    pub z: i32,
+}
+
+impl ChunkCoord {
+    /// Creates a new chunk coordinate.
+    #[inline]
+    #[must_use]
+    pub const fn new(x: i32, z: i32) -> Self {
+        Self { x, z }
+    }
+
+    /// Converts world block coordinates to chunk coordinate.
+    #[inline]
+    #[must_use]
+    pub const fn from_block_pos(block_x: i32, block_z: i32) -> Self {
+        Self {
+            x: block_x.div_euclid(CHUNK_SIZE as i32),
+            z: block_z.div_euclid(CHUNK_SIZE as i32),
+        }
+    }
+
+    /// Returns the world X coordinate of the chunk's origin (corner).
+    #[inline]
+    #[must_use]
+    pub const fn world_x(self) -> i32 {
+        self.x * CHUNK_SIZE as i32
+    }
+
+    /// Returns the world Z coordinate of the chunk's origin.
+    #[inline]
+    #[must_use]
+    pub const fn world_z(self) -> i32 {
+        self.z * CHUNK_SIZE as i32
+    }
+
+    /// Converts world coordinates to chunk coordinate.
+    /// Alias for `from_block_pos` for API consistency.
+    #[inline]
+    #[must_use]
+    pub const fn from_world_pos(world_x: i32, world_z: i32) -> Self {
+        Self::from_block_pos(world_x, world_z)
+    }
+}
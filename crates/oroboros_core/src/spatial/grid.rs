@@ -0,0 +1,602 @@
+//! Uniform hash grid over entity positions.
+
+use std::collections::hash_map;
+use std::collections::HashMap;
+
+use crate::ecs::{ArchetypeWorld, EntityId, Position};
+
+/// Integer coordinates of a grid cell.
+pub type CellKey = [i32; 3];
+
+/// Entities and their positions in one cell.
+type Cell = Vec<(EntityId, Position)>;
+
+/// Hash grid answering radius, box, k-nearest and ray queries over
+/// entity positions.
+///
+/// ```text
+///  update(world)                         query_radius(p, r)
+/// ┌─────────────────────────┐           ┌───┬───┬───┐
+/// │ dirty rows per archetype│  ──────►  │   │ • │   │  only cells overlapping
+/// │ (row + Position column) │  re-bin   ├───┼───┼───┤  the query are visited
+/// └─────────────────────────┘           │ • │ p │ • │
+///                                       └───┴───┴───┘
+/// ```
+///
+/// Keep it current by calling [`update`](Self::update) on the write
+/// buffer once per frame, before the swap clears the dirty flags. Build a
+/// separate index from a read buffer with [`rebuild`](Self::rebuild).
+/// Entities outside the ECS can be tracked with [`insert`](Self::insert)
+/// and [`remove`](Self::remove).
+pub struct SpatialGrid {
+    /// Edge length of a cell.
+    cell_size: f32,
+    /// `1.0 / cell_size`.
+    inv_cell_size: f32,
+    /// Occupied cells.
+    cells: HashMap<CellKey, Cell>,
+    /// Cell of every indexed entity.
+    entries: HashMap<EntityId, CellKey>,
+    /// Entity seen at each row of each archetype at the last update.
+    rows: Vec<Vec<EntityId>>,
+    /// Entities that lost their row during an update (scratch).
+    displaced: Vec<EntityId>,
+}
+
+impl SpatialGrid {
+    /// Creates an empty grid.
+    ///
+    /// Pick a cell size near the typical query radius.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not positive.
+    #[must_use]
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "Cell size must be positive");
+        Self {
+            cell_size,
+            inv_cell_size: 1.0 / cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+            rows: Vec::new(),
+            displaced: Vec::new(),
+        }
+    }
+
+    /// Returns the cell edge length.
+    #[must_use]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns the number of indexed entities.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if nothing is indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the cell containing `pos`.
+    #[must_use]
+    pub fn cell_of(&self, pos: Position) -> CellKey {
+        [
+            (pos.x * self.inv_cell_size).floor() as i32,
+            (pos.y * self.inv_cell_size).floor() as i32,
+            (pos.z * self.inv_cell_size).floor() as i32,
+        ]
+    }
+
+    /// Returns the indexed position of an entity.
+    #[must_use]
+    pub fn position_of(&self, id: EntityId) -> Option<Position> {
+        let key = self.entries.get(&id)?;
+        self.cells[key].iter().find(|(entry, _)| *entry == id).map(|&(_, pos)| pos)
+    }
+
+    /// Indexes or moves an entity.
+    pub fn insert(&mut self, id: EntityId, pos: Position) {
+        let key = self.cell_of(pos);
+        match self.entries.insert(id, key) {
+            Some(old) if old == key => {
+                if let Some(entry) = self.cells.get_mut(&key).and_then(|cell| cell.iter_mut().find(|(e, _)| *e == id)) {
+                    entry.1 = pos;
+                }
+                return;
+            }
+            Some(old) => self.remove_from_cell(old, id),
+            None => {}
+        }
+        self.cells.entry(key).or_default().push((id, pos));
+    }
+
+    /// Removes an entity. Returns `false` if it wasn't indexed.
+    pub fn remove(&mut self, id: EntityId) -> bool {
+        match self.entries.remove(&id) {
+            Some(key) => {
+                self.remove_from_cell(key, id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes everything.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.rows.clear();
+    }
+
+    /// Drops `id` from cell `key`, freeing the cell once empty.
+    fn remove_from_cell(&mut self, key: CellKey, id: EntityId) {
+        if let hash_map::Entry::Occupied(mut cell) = self.cells.entry(key) {
+            if let Some(index) = cell.get().iter().position(|(entry, _)| *entry == id) {
+                cell.get_mut().swap_remove(index);
+            }
+            if cell.get().is_empty() {
+                cell.remove();
+            }
+        }
+    }
+
+    /// Re-indexes the entities in rows the world marked dirty since the
+    /// last swap, and drops despawned entities or ones that lost their
+    /// `Position`.
+    ///
+    /// Returns the number of rows visited.
+    pub fn update(&mut self, world: &ArchetypeWorld) -> usize {
+        let mut visited = 0;
+        self.displaced.clear();
+
+        for (archetype, table) in world.archetypes().iter().enumerate() {
+            if self.rows.len() <= archetype {
+                self.rows.push(Vec::new());
+            }
+            let (Some(positions), Some(position_dirty)) =
+                (table.column::<Position>(), table.column_dirty_tracker::<Position>())
+            else {
+                continue;
+            };
+
+            let len = table.len();
+            if self.rows[archetype].len() > len {
+                self.displaced.extend(self.rows[archetype].drain(len..));
+            }
+            self.rows[archetype].resize(len, EntityId::NULL);
+
+            // Rows that changed occupant, then rows whose Position changed
+            let row_dirty = table.dirty_tracker();
+            let dirty = row_dirty
+                .iter_dirty()
+                .chain(position_dirty.iter_dirty().filter(|&row| !row_dirty.is_dirty(row)));
+            for row in dirty.filter(|&row| row < len) {
+                let id = table.entities()[row];
+                let previous = std::mem::replace(&mut self.rows[archetype][row], id);
+                if previous != id && !previous.is_null() {
+                    self.displaced.push(previous);
+                }
+                self.insert(id, positions[row]);
+                visited += 1;
+            }
+        }
+
+        // A displaced entity either moved to a dirty row (already
+        // re-indexed above) or lost its Position
+        let displaced = std::mem::take(&mut self.displaced);
+        for &id in &displaced {
+            if !world.has::<Position>(id) {
+                self.remove(id);
+            }
+        }
+        self.displaced = displaced;
+
+        visited
+    }
+
+    /// Drops everything and indexes every entity with a `Position`.
+    pub fn rebuild(&mut self, world: &ArchetypeWorld) {
+        self.clear();
+        for table in world.archetypes() {
+            let mut rows = Vec::new();
+            if let Some(positions) = table.column::<Position>() {
+                rows.extend_from_slice(table.entities());
+                for (&id, &pos) in table.entities().iter().zip(positions) {
+                    self.insert(id, pos);
+                }
+            }
+            self.rows.push(rows);
+        }
+    }
+
+    /// Iterates entities inside the box `[min, max]`.
+    pub fn query_aabb(&self, min: Position, max: Position) -> impl Iterator<Item = (EntityId, Position)> + '_ {
+        CellScan::new(self, self.cell_of(min), self.cell_of(max))
+            .flat_map(|cell| cell.iter().copied())
+            .filter(move |(_, pos)| {
+                pos.x >= min.x && pos.x <= max.x && pos.y >= min.y && pos.y <= max.y && pos.z >= min.z && pos.z <= max.z
+            })
+    }
+
+    /// Iterates entities within `radius` of `center`.
+    pub fn query_radius(&self, center: Position, radius: f32) -> impl Iterator<Item = (EntityId, Position)> + '_ {
+        let min = Position::new(center.x - radius, center.y - radius, center.z - radius);
+        let max = Position::new(center.x + radius, center.y + radius, center.z + radius);
+        let radius_sq = radius * radius;
+        CellScan::new(self, self.cell_of(min), self.cell_of(max))
+            .flat_map(|cell| cell.iter().copied())
+            .filter(move |(_, pos)| pos.distance_squared(center) <= radius_sq)
+    }
+
+    /// Finds the `k` entities nearest to `center` within `max_radius`,
+    /// closest first, as `(entity, distance)` in `out` (cleared first).
+    pub fn nearest(&self, center: Position, k: usize, max_radius: f32, out: &mut Vec<(EntityId, f32)>) {
+        out.clear();
+        if k == 0 {
+            return;
+        }
+
+        let origin = self.cell_of(center);
+        let max_radius_sq = max_radius * max_radius;
+        let consider = |cell: &Cell, out: &mut Vec<(EntityId, f32)>| {
+            for &(id, pos) in cell {
+                let distance_sq = pos.distance_squared(center);
+                if distance_sq <= max_radius_sq {
+                    out.push((id, distance_sq));
+                }
+            }
+        };
+
+        // Grow shells of cells around the origin until the k-th hit is
+        // closer than any unvisited cell could be
+        let mut ring: i32 = 0;
+        loop {
+            let side = (2 * i64::from(ring) + 1) as usize;
+            if side.saturating_pow(3) > self.cells.len() * 2 {
+                // Shells are mostly empty cells now; scan what exists
+                out.clear();
+                for cell in self.cells.values() {
+                    consider(cell, out);
+                }
+                break;
+            }
+
+            for dx in -ring..=ring {
+                for dy in -ring..=ring {
+                    for dz in -ring..=ring {
+                        if dx.abs().max(dy.abs()).max(dz.abs()) != ring {
+                            continue;
+                        }
+                        let key = [origin[0] + dx, origin[1] + dy, origin[2] + dz];
+                        if let Some(cell) = self.cells.get(&key) {
+                            consider(cell, out);
+                        }
+                    }
+                }
+            }
+
+            let covered = ring as f32 * self.cell_size;
+            if covered >= max_radius {
+                break;
+            }
+            if out.len() >= k {
+                out.select_nth_unstable_by(k - 1, |a, b| a.1.total_cmp(&b.1));
+                if out[k - 1].1 <= covered * covered {
+                    break;
+                }
+            }
+            ring += 1;
+        }
+
+        out.sort_unstable_by(|a, b| a.1.total_cmp(&b.1));
+        out.truncate(k);
+        for entry in out.iter_mut() {
+            entry.1 = entry.1.sqrt();
+        }
+    }
+
+    /// Iterates entities in the cells a ray passes through, in the order
+    /// the ray reaches the cells.
+    ///
+    /// Candidates only: entities are indexed as points, so test each
+    /// against its real shape. A zero `direction` yields the origin cell.
+    pub fn raycast_candidates(
+        &self,
+        origin: Position,
+        direction: [f32; 3],
+        max_distance: f32,
+    ) -> impl Iterator<Item = (EntityId, Position)> + '_ {
+        RayCells::new(self, origin, direction, max_distance)
+            .filter_map(|key| self.cells.get(&key))
+            .flat_map(|cell| cell.iter().copied())
+    }
+}
+
+/// Iterates occupied cells with keys in `[min, max]`.
+enum CellScan<'a> {
+    /// Walks every key in the box; used when the box is small.
+    Keys {
+        /// The grid.
+        grid: &'a SpatialGrid,
+        /// Next key to look up.
+        next: CellKey,
+        /// Box lower corner.
+        min: CellKey,
+        /// Box upper corner.
+        max: CellKey,
+    },
+    /// Filters every occupied cell; used when the box has more keys than
+    /// the grid has cells.
+    All {
+        /// Remaining cells.
+        cells: hash_map::Iter<'a, CellKey, Cell>,
+        /// Box lower corner.
+        min: CellKey,
+        /// Box upper corner.
+        max: CellKey,
+    },
+}
+
+impl<'a> CellScan<'a> {
+    /// Picks the cheaper scan for the box.
+    fn new(grid: &'a SpatialGrid, min: CellKey, max: CellKey) -> Self {
+        let volume = (0..3)
+            .map(|axis| (i64::from(max[axis]) - i64::from(min[axis]) + 1).max(0) as u64)
+            .fold(1u64, u64::saturating_mul);
+        if volume > grid.cells.len() as u64 {
+            Self::All {
+                cells: grid.cells.iter(),
+                min,
+                max,
+            }
+        } else {
+            Self::Keys { grid, next: min, min, max }
+        }
+    }
+}
+
+impl<'a> Iterator for CellScan<'a> {
+    type Item = &'a Cell;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Keys { grid, next, min, max } => loop {
+                if next[0] > max[0] {
+                    return None;
+                }
+                let key = *next;
+
+                // Advance z, then y, then x
+                next[2] += 1;
+                if next[2] > max[2] {
+                    next[2] = min[2];
+                    next[1] += 1;
+                    if next[1] > max[1] {
+                        next[1] = min[1];
+                        next[0] += 1;
+                    }
+                }
+
+                if let Some(cell) = grid.cells.get(&key) {
+                    return Some(cell);
+                }
+            },
+            Self::All { cells, min, max } => cells
+                .find(|(key, _)| (0..3).all(|axis| key[axis] >= min[axis] && key[axis] <= max[axis]))
+                .map(|(_, cell)| cell),
+        }
+    }
+}
+
+/// Cells crossed by a ray (Amanatides & Woo grid traversal).
+struct RayCells {
+    /// Current cell.
+    cell: CellKey,
+    /// Cell step per axis (-1, 0 or 1).
+    step: [i32; 3],
+    /// Ray distance at which each axis next crosses a cell boundary.
+    t_max: [f32; 3],
+    /// Ray distance between boundary crossings per axis.
+    t_delta: [f32; 3],
+    /// Distance limit.
+    max_distance: f32,
+    /// Set once the ray has left the range.
+    done: bool,
+}
+
+impl RayCells {
+    /// Starts at the cell containing `origin`.
+    fn new(grid: &SpatialGrid, origin: Position, direction: [f32; 3], max_distance: f32) -> Self {
+        let length = direction.iter().map(|d| d * d).sum::<f32>().sqrt();
+        let start = [origin.x, origin.y, origin.z];
+        let cell = grid.cell_of(origin);
+
+        let mut step = [0; 3];
+        let mut t_max = [f32::INFINITY; 3];
+        let mut t_delta = [f32::INFINITY; 3];
+        if length > 0.0 {
+            for axis in 0..3 {
+                let d = direction[axis] / length;
+                if d > 0.0 {
+                    step[axis] = 1;
+                    let boundary = (cell[axis] + 1) as f32 * grid.cell_size;
+                    t_max[axis] = (boundary - start[axis]) / d;
+                    t_delta[axis] = grid.cell_size / d;
+                } else if d < 0.0 {
+                    step[axis] = -1;
+                    let boundary = cell[axis] as f32 * grid.cell_size;
+                    t_max[axis] = (boundary - start[axis]) / d;
+                    t_delta[axis] = -grid.cell_size / d;
+                }
+            }
+        }
+
+        Self {
+            cell,
+            step,
+            t_max,
+            t_delta,
+            max_distance,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for RayCells {
+    type Item = CellKey;
+
+    fn next(&mut self) -> Option<CellKey> {
+        if self.done {
+            return None;
+        }
+        let current = self.cell;
+
+        // Step along the axis whose boundary comes first
+        let axis = if self.t_max[0] < self.t_max[1] {
+            if self.t_max[0] < self.t_max[2] { 0 } else { 2 }
+        } else if self.t_max[1] < self.t_max[2] {
+            1
+        } else {
+            2
+        };
+        if self.t_max[axis] > self.max_distance {
+            self.done = true;
+        } else {
+            self.cell[axis] += self.step[axis];
+            self.t_max[axis] += self.t_delta[axis];
+        }
+
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Velocity;
+
+    fn sorted(mut ids: Vec<EntityId>) -> Vec<EntityId> {
+        ids.sort_by_key(|id| id.to_bits());
+        ids
+    }
+
+    fn scattered_world() -> (ArchetypeWorld, Vec<(EntityId, Position)>) {
+        let mut world = ArchetypeWorld::new(256, 256);
+        let mut spawned = Vec::new();
+        let mut seed = 12345u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 8) as f32 / (1 << 24) as f32 * 200.0 - 100.0
+        };
+        for i in 0..300 {
+            let pos = Position::new(next(), next(), next());
+            let id = if i % 2 == 0 {
+                world.spawn_pv(pos, Velocity::default())
+            } else {
+                world.spawn_p(pos)
+            };
+            spawned.push((id, pos));
+        }
+        (world, spawned)
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let (world, spawned) = scattered_world();
+        let mut grid = SpatialGrid::new(8.0);
+        assert_eq!(grid.update(&world), 300);
+        assert_eq!(grid.len(), 300);
+
+        let center = Position::new(5.0, -3.0, 10.0);
+        for radius in [0.5, 12.0, 40.0, 500.0] {
+            let expected: Vec<_> = spawned
+                .iter()
+                .filter(|(_, pos)| pos.distance_squared(center) <= radius * radius)
+                .map(|&(id, _)| id)
+                .collect();
+            let found: Vec<_> = grid.query_radius(center, radius).map(|(id, _)| id).collect();
+            assert_eq!(sorted(found), sorted(expected), "radius {radius}");
+        }
+
+        let (min, max) = (Position::new(-20.0, -50.0, 0.0), Position::new(30.0, 0.0, 25.0));
+        let expected: Vec<_> = spawned
+            .iter()
+            .filter(|(_, p)| p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y && p.z >= min.z && p.z <= max.z)
+            .map(|&(id, _)| id)
+            .collect();
+        let found: Vec<_> = grid.query_aabb(min, max).map(|(id, _)| id).collect();
+        assert_eq!(sorted(found), sorted(expected));
+
+        let mut by_distance: Vec<_> = spawned.iter().map(|&(id, pos)| (id, pos.distance_squared(center))).collect();
+        by_distance.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let mut nearest = Vec::new();
+        grid.nearest(center, 5, f32::INFINITY, &mut nearest);
+        let expected: Vec<_> = by_distance.iter().take(5).map(|&(id, _)| id).collect();
+        assert_eq!(nearest.iter().map(|&(id, _)| id).collect::<Vec<_>>(), expected);
+
+        grid.nearest(center, 5, 0.1, &mut nearest);
+        assert!(nearest.is_empty());
+    }
+
+    #[test]
+    fn test_update_tracks_moves_and_despawns() {
+        let (mut world, spawned) = scattered_world();
+        let mut grid = SpatialGrid::new(8.0);
+        grid.update(&world);
+        world.clear_dirty();
+
+        let (moved, _) = spawned[0];
+        let (despawned, _) = spawned[2];
+        let (stripped, _) = spawned[1];
+        *world.get_mut::<Position>(moved).unwrap() = Position::new(1000.0, 0.0, 0.0);
+        assert!(world.despawn(despawned));
+        assert!(world.remove::<Position>(stripped).is_some());
+
+        let visited = grid.update(&world);
+        assert!(visited < 10, "only dirty rows are visited, got {visited}");
+
+        assert_eq!(grid.len(), 298);
+        assert_eq!(grid.position_of(moved), Some(Position::new(1000.0, 0.0, 0.0)));
+        assert_eq!(grid.position_of(despawned), None);
+        assert_eq!(grid.position_of(stripped), None);
+
+        // Everything else is still where the world says
+        let mut rebuilt = SpatialGrid::new(8.0);
+        rebuilt.rebuild(&world);
+        for (id, _) in spawned {
+            assert_eq!(grid.position_of(id), rebuilt.position_of(id));
+        }
+    }
+
+    #[test]
+    fn test_raycast_visits_cells_in_order() {
+        let mut grid = SpatialGrid::new(1.0);
+        let near = EntityId::new(1, 0);
+        let far = EntityId::new(2, 0);
+        let off_ray = EntityId::new(3, 0);
+        grid.insert(far, Position::new(8.5, 0.5, 0.5));
+        grid.insert(near, Position::new(3.5, 0.5, 0.5));
+        grid.insert(off_ray, Position::new(3.5, 5.5, 0.5));
+
+        let hits: Vec<_> = grid
+            .raycast_candidates(Position::new(0.5, 0.5, 0.5), [1.0, 0.0, 0.0], 20.0)
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(hits, [near, far]);
+
+        let short: Vec<_> = grid
+            .raycast_candidates(Position::new(0.5, 0.5, 0.5), [1.0, 0.0, 0.0], 5.0)
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(short, [near]);
+
+        assert!(grid.remove(near));
+        assert!(!grid.remove(near));
+        assert_eq!(grid.len(), 2);
+    }
+}
@@ -0,0 +1,20 @@
+//! # Spatial Indexing
+//!
+//! "Which entities are near here?" without scanning every `Position`.
+//!
+//! ## Design Philosophy
+//!
+//! - Uniform hash grid: only occupied cells use memory
+//! - Incremental: each update touches only rows the archetype dirty
+//!   trackers flagged since the last swap
+//! - Queries are iterators over borrowed cells, so they don't allocate
+//! - `Send + Sync`: share a built index with other threads by reference
+//!
+//! [`ChunkCoord`] names the world's voxel chunks for every crate that
+//! streams, generates or shards them.
+
+mod chunk;
+mod grid;
+
+pub use chunk::{ChunkCoord, CHUNK_SIZE};
+pub use grid::{CellKey, SpatialGrid};
@@ -67,7 +67,7 @@ use crate::ecs::archetype::ArchetypeWorld;
 ///
 ///     // Render thread gets read access (can overlap with next frame's logic)
 ///     let read = db_world.read_handle();
-///     for pos in read.pv_table.iter_positions() {
+///     for pos in read.pv_table().iter_positions() {
 ///         // render...
 ///     }
 ///     drop(read);
@@ -248,6 +248,9 @@ impl DoubleBufferedWorld {
             // Note: We need mutable access to clear, but we're about to release it
             let new_read_mut = &mut *self.buffers[new_read_idx].get();
             new_read_mut.clear_dirty();
+
+            // Resources and events stay with the logic side
+            new_write.swap_resources(new_read_mut);
         }
 
         // Increment frame counter
@@ -310,10 +313,7 @@ impl DoubleBufferedWorld {
             let read_buf = &mut *self.buffers[read_idx].get();
 
             // Mark everything as dirty in write buffer for full sync
-            let pv_len = write_buf_mut.pv_table.len();
-            let p_len = write_buf_mut.p_table.len();
-            write_buf_mut.pv_table.dirty_tracker_mut().mark_all_dirty(pv_len);
-            write_buf_mut.p_table.dirty_tracker_mut().mark_all_dirty(p_len);
+            write_buf_mut.mark_all_dirty();
 
             // Sync to read buffer
             read_buf.sync_dirty_from(write_buf_mut);
@@ -417,7 +417,7 @@ impl Drop for WorldWriteHandle {
 /// let read = db_world.read_handle();
 ///
 /// // Iterate over all Position+Velocity entities
-/// for (pos, vel) in read.pv_table.iter_position_velocity() {
+/// for (pos, vel) in read.pv_table().iter_position_velocity() {
 ///     render_entity(pos, vel);
 /// }
 ///
@@ -591,7 +591,7 @@ mod tests {
         {
             let read = db.read_handle();
             // Read is from the buffer we just wrote to
-            assert_eq!(read.pv_table.len(), 1);
+            assert_eq!(read.pv_table().len(), 1);
         }
 
         // New writes go to the other buffer
@@ -602,6 +602,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resources_follow_write_buffer() {
+        struct Tick(u64);
+
+        let db = DoubleBufferedWorld::new(10, 10);
+        db.write_handle().insert_resource(Tick(0));
+
+        for _ in 0..3 {
+            db.write_handle().resource_mut::<Tick>().unwrap().0 += 1;
+            db.swap_buffers();
+        }
+
+        assert_eq!(db.write_handle().resource::<Tick>().unwrap().0, 3);
+        assert!(db.read_handle().resource::<Tick>().is_none());
+    }
+
+    #[test]
+    fn test_despawn_propagates_through_swap() {
+        let db = DoubleBufferedWorld::new(1000, 100);
+
+        let (a, b, c) = {
+            let mut write = db.write_handle();
+            let a = write.spawn_pv(Position::new(1.0, 0.0, 0.0), Velocity::new(0.0, 0.0, 0.0));
+            let b = write.spawn_pv(Position::new(2.0, 0.0, 0.0), Velocity::new(0.0, 0.0, 0.0));
+            let c = write.spawn_pv(Position::new(3.0, 0.0, 0.0), Velocity::new(0.0, 0.0, 0.0));
+            (a, b, c)
+        };
+        db.swap_buffers();
+
+        // Despawn in the synced write buffer
+        {
+            let mut write = db.write_handle();
+            assert_eq!(write.pv_table().len(), 3);
+            assert!(write.despawn(a));
+        }
+        db.swap_buffers();
+
+        {
+            let read = db.read_handle();
+            assert_eq!(read.alive_count(), 2);
+            assert_eq!(read.pv_table().len(), 2);
+            assert_eq!(read.get_position(a), None);
+            assert_eq!(read.get_position(b).unwrap().x, 2.0);
+            assert_eq!(read.get_position(c).unwrap().x, 3.0);
+        }
+
+        // The next write buffer caught up too, and recycles the slot
+        let mut write = db.write_handle();
+        assert_eq!(write.pv_table().len(), 2);
+        assert_eq!(write.get_position(c).unwrap().x, 3.0);
+        let reused = write.spawn_p(Position::new(9.0, 0.0, 0.0));
+        assert_eq!(reused.index(), a.index());
+        assert_eq!(write.get_position(a), None);
+    }
+
     #[test]
     fn test_frame_sync() {
         let db = DoubleBufferedWorld::new(1000, 100);
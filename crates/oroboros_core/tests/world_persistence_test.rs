@@ -0,0 +1,65 @@
+//! Integration test for world save/load at scale.
+
+use oroboros_core::{ArchetypeWorld, ComponentRegistry, Position, Velocity};
+use std::time::{Duration, Instant};
+
+const ENTITY_COUNT: usize = 1_000_000;
+
+/// Save + load budget; debug builds get more slack.
+fn budget() -> Duration {
+    if cfg!(debug_assertions) {
+        Duration::from_secs(10)
+    } else {
+        Duration::from_secs(1)
+    }
+}
+
+#[test]
+fn test_million_entity_round_trip() {
+    let registry = ComponentRegistry::with_builtin();
+
+    let mut world = ArchetypeWorld::new(ENTITY_COUNT / 2, ENTITY_COUNT / 2);
+    let spawned = world.spawn_batch_pv(ENTITY_COUNT / 2, |i| {
+        let f = i as f32;
+        (Position::new(f, f * 2.0, -f), Velocity::new(1.0, 0.0, f))
+    });
+    assert_eq!(spawned, ENTITY_COUNT / 2);
+    for i in 0..ENTITY_COUNT / 2 {
+        let _ = world.spawn_p(Position::new(0.0, i as f32, 0.0));
+    }
+
+    // Punch holes so the free list and generations matter
+    let victims: Vec<_> = world.pv_table().entities().iter().step_by(1000).copied().collect();
+    for &id in &victims {
+        assert!(world.despawn(id));
+    }
+
+    let start = Instant::now();
+    let mut bytes = Vec::new();
+    world.save(&registry, &mut bytes).unwrap();
+    let saved_in = start.elapsed();
+
+    let start = Instant::now();
+    let loaded = ArchetypeWorld::load(&registry, bytes.as_slice()).unwrap();
+    let loaded_in = start.elapsed();
+
+    println!(
+        "Saved {} entities ({:.1} MB) in {:?}, loaded in {:?}",
+        world.alive_count(),
+        bytes.len() as f64 / (1024.0 * 1024.0),
+        saved_in,
+        loaded_in
+    );
+    assert!(saved_in + loaded_in < budget(), "Round trip took {:?}", saved_in + loaded_in);
+
+    assert_eq!(loaded.alive_count(), world.alive_count());
+    for (table, loaded_table) in world.archetypes().iter().zip(loaded.archetypes()) {
+        assert_eq!(table.signature(), loaded_table.signature());
+        assert_eq!(table.entities(), loaded_table.entities());
+        assert_eq!(table.column::<Position>(), loaded_table.column::<Position>());
+        assert_eq!(table.column::<Velocity>(), loaded_table.column::<Velocity>());
+    }
+    for &id in &victims {
+        assert!(!loaded.is_alive(id));
+    }
+}
@@ -50,8 +50,7 @@ use std::time::Instant;
 /// Entity ID (matches Unit 1's entity system).
 pub type EntityId = u64;
 
-/// Block ID in the world.
-pub type BlockId = u32;
+pub use oroboros_core::blocks::BlockId;
 
 /// Result of breaking a block.
 #[derive(Clone, Debug)]
@@ -303,7 +302,7 @@ impl TheBank {
             }
 
             // Write to WAL (async, non-blocking)
-            let handle = self.wal.log_loot_drop(entity_id, block_id, item_id, quantity)?;
+            let handle = self.wal.log_loot_drop(entity_id, u32::from(block_id), item_id, quantity)?;
             wal_lsn = Some(handle.lsn);
 
             // Build drop info
@@ -28,13 +28,22 @@
 //! 5. `entropy` - Additional blockchain entropy (32-bit)
 //! 6. `blockchain_salt` - Dynamic 128-bit salt from latest block hash (for rare+)
 
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
+use oroboros_core::embedded::EmbeddedData;
 use serde::{Deserialize, Serialize};
 use siphasher::sip128::{Hasher128, SipHasher24};
 use std::collections::HashMap;
+use std::fs;
 use std::hash::Hasher;
+use std::path::Path;
+use std::sync::Arc;
 
+use crate::error::{EconomyError, EconomyResult};
 use crate::inventory::ItemId;
 
+/// The shipped loot tables.
+const SHIPPED_LOOT_TABLES: &str = include_str!("../../../data/schemas/economy/loot_tables.toml");
+
 /// Rarity tier for items and blocks.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[repr(u8)]
@@ -132,7 +141,8 @@ pub struct LootEntry {
 /// A complete loot table for a block type.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LootTable {
-    /// Block ID this table is for.
+    /// Loot table ID, which blocks name as their `loot_table`. By
+    /// convention the ID of the block it was written for.
     pub block_id: u32,
     /// Block rarity tier.
     pub block_rarity: Rarity,
@@ -150,6 +160,21 @@ impl LootTable {
     }
 }
 
+/// `loot_tables.toml` as written.
+#[derive(Deserialize)]
+struct LootTableFile {
+    /// `[[loot_table]]`.
+    #[serde(default)]
+    loot_table: Vec<LootTable>,
+}
+
+/// Parses a `loot_tables.toml` document.
+fn parse_loot_tables(text: &str) -> EconomyResult<Vec<LootTable>> {
+    let file: LootTableFile = toml::from_str(text)
+        .map_err(|e| EconomyError::InvalidConfig(format!("Invalid loot tables: {e}")))?;
+    Ok(file.loot_table)
+}
+
 /// Pre-computed lookup tables for O(1) calculations.
 ///
 /// These tables are computed once at startup and indexed directly.
@@ -377,8 +402,10 @@ impl std::fmt::Debug for SecureSeed {
 pub struct LootCalculator {
     /// Pre-computed lookup tables.
     tables: LookupTables,
-    /// Loot tables indexed by block ID.
+    /// Loot tables indexed by table ID.
     loot_tables: HashMap<u32, LootTable>,
+    /// Block definitions, if mined blocks are resolved through them.
+    blocks: Option<Arc<BlockRegistry>>,
     /// Current blockchain salt (public, changes every block).
     blockchain_salt: BlockchainSalt,
     /// Server-side secret seed (NEVER exposed to clients).
@@ -396,6 +423,7 @@ impl LootCalculator {
         Self {
             tables: LookupTables::new(),
             loot_tables: HashMap::new(),
+            blocks: None,
             blockchain_salt: BlockchainSalt::default(),
             server_seed: SecureSeed::test_seed(),
             action_nonce: 0,
@@ -420,6 +448,7 @@ impl LootCalculator {
         Self {
             tables: LookupTables::new(),
             loot_tables: HashMap::new(),
+            blocks: None,
             blockchain_salt: BlockchainSalt::default(),
             server_seed: SecureSeed::new(secret),
             action_nonce: 0,
@@ -453,6 +482,91 @@ impl LootCalculator {
         self.loot_tables.insert(table.block_id, table);
     }
 
+    /// Registers every table in a `loot_tables.toml` document.
+    ///
+    /// Returns how many tables were registered.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text isn't a valid loot table file; nothing is
+    /// registered then.
+    pub fn register_tables_toml(&mut self, text: &str) -> EconomyResult<usize> {
+        let tables = parse_loot_tables(text)?;
+        let count = tables.len();
+        for table in tables {
+            self.register_table(table);
+        }
+        Ok(count)
+    }
+
+    /// Registers the shipped `loot_tables.toml`.
+    ///
+    /// The file is parsed once per process; later calls register copies
+    /// of the same tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shipped loot tables don't deserialize.
+    pub fn register_shipped_tables(&mut self) -> usize {
+        static SHIPPED: EmbeddedData<Vec<LootTable>, EconomyError> =
+            EmbeddedData::new("loot_tables.toml", SHIPPED_LOOT_TABLES, parse_loot_tables);
+        let tables = SHIPPED.get();
+        for table in tables.iter() {
+            self.register_table(table.clone());
+        }
+        tables.len()
+    }
+
+    /// Loads and registers every table in a `loot_tables.toml` file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a valid loot table file.
+    pub fn load_tables(&mut self, path: impl AsRef<Path>) -> EconomyResult<usize> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| EconomyError::InvalidConfig(format!("Failed to read loot tables: {e}")))?;
+        self.register_tables_toml(&text)
+    }
+
+    /// Resolves mined blocks through block definitions.
+    ///
+    /// A block then drops from its `loot_table`, and drops nothing below its
+    /// `tool_tier`. Without definitions, tables are looked up by block ID.
+    pub fn set_blocks(&mut self, blocks: Arc<BlockRegistry>) {
+        self.blocks = Some(blocks);
+    }
+
+    /// Returns true if a loot table with this ID is registered.
+    #[must_use]
+    pub fn has_table(&self, table: u32) -> bool {
+        self.loot_tables.contains_key(&table)
+    }
+
+    /// Checks that every loot table a block names is registered.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first block whose loot table is missing.
+    pub fn check_blocks(&self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        blocks.check_loot_tables(|table| self.has_table(table))
+    }
+
+    /// Finds the table a mined block drops from (O(1)).
+    #[inline]
+    fn table_for(&self, block_id: BlockId, pickaxe_tier: u8) -> Option<&LootTable> {
+        let table_id = match &self.blocks {
+            Some(blocks) => {
+                let def = blocks.get(block_id)?;
+                if pickaxe_tier < def.tool_tier {
+                    return None;
+                }
+                def.loot_table?
+            }
+            None => u32::from(block_id),
+        };
+        self.loot_tables.get(&table_id)
+    }
+
     /// Calculates the drop for a mining action (fast mode for common items).
     ///
     /// **WARNING**: This uses FNV-1a which is NOT secure for valuable items.
@@ -460,14 +574,14 @@ impl LootCalculator {
     #[must_use]
     pub fn calculate_drop(
         &self,
-        block_id: u32,
+        block_id: BlockId,
         player_level: u8,
         pickaxe_tier: u8,
         weather_seed: u32,
         entropy: u32,
     ) -> DropResult {
         // Get the loot table for this block
-        let Some(table) = self.loot_tables.get(&block_id) else {
+        let Some(table) = self.table_for(block_id, pickaxe_tier) else {
             return DropResult::nothing();
         };
 
@@ -476,7 +590,7 @@ impl LootCalculator {
         }
 
         // Use fast hash for common items
-        let hash = self.compute_hash_fast(block_id, player_level, pickaxe_tier, weather_seed, entropy);
+        let hash = self.compute_hash_fast(u32::from(block_id), player_level, pickaxe_tier, weather_seed, entropy);
         self.apply_loot_roll(table, hash, player_level, pickaxe_tier)
     }
 
@@ -498,14 +612,14 @@ impl LootCalculator {
     /// * `entropy` - Additional entropy (timestamp, player-specific nonce)
     pub fn calculate_drop_secure(
         &mut self,
-        block_id: u32,
+        block_id: BlockId,
         player_level: u8,
         pickaxe_tier: u8,
         weather_seed: u32,
         entropy: u32,
     ) -> DropResult {
         // Get the loot table for this block
-        let Some(table) = self.loot_tables.get(&block_id) else {
+        let Some(table) = self.table_for(block_id, pickaxe_tier) else {
             return DropResult::nothing();
         };
 
@@ -517,7 +631,7 @@ impl LootCalculator {
         let table = table.clone();
 
         // Use secure hash with server secret
-        let hash = self.compute_hash_secure(block_id, player_level, pickaxe_tier, weather_seed, entropy);
+        let hash = self.compute_hash_secure(u32::from(block_id), player_level, pickaxe_tier, weather_seed, entropy);
         self.apply_loot_roll(&table, hash, player_level, pickaxe_tier)
     }
 
@@ -664,7 +778,7 @@ impl LootCalculator {
     #[must_use]
     pub fn run_statistics(
         &self,
-        block_id: u32,
+        block_id: BlockId,
         player_level: u8,
         pickaxe_tier: u8,
         iterations: u32,
@@ -961,4 +1075,40 @@ mod tests {
             "Server secret should not be visible in debug output"
         );
     }
+
+    #[test]
+    fn test_shipped_tables_cover_blocks() {
+        let mut calc = LootCalculator::new();
+        assert_eq!(calc.register_shipped_tables(), 5);
+        calc.check_blocks(&BlockRegistry::shipped()).unwrap();
+
+        let empty = LootCalculator::new();
+        assert!(matches!(
+            empty.check_blocks(&BlockRegistry::shipped()),
+            Err(BlockConfigError::UnknownLootTable { .. })
+        ));
+        assert!(matches!(
+            calc.register_tables_toml("[[loot_table]]\nblock_id = \"stone\""),
+            Err(EconomyError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_blocks_pick_table_and_tool_tier() {
+        let blocks = BlockRegistry::shipped();
+        let stone = blocks.id("stone").unwrap();
+        let iron_ore = blocks.id("iron_ore").unwrap();
+        let dirt = blocks.id("dirt").unwrap();
+
+        let mut calc = LootCalculator::new();
+        calc.register_shipped_tables();
+        calc.set_blocks(blocks);
+
+        assert!(calc.run_statistics(stone, 50, 0, 1000).total_drops > 0);
+        assert_eq!(calc.run_statistics(dirt, 50, 5, 1000).total_drops, 0, "dirt has no loot table");
+
+        // Iron ore needs a tier 1 pickaxe
+        assert_eq!(calc.run_statistics(iron_ore, 50, 0, 1000).total_drops, 0);
+        assert!(calc.run_statistics(iron_ore, 50, 1, 1000).total_drops > 0);
+    }
 }
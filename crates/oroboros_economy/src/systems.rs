@@ -19,8 +19,11 @@
 //! - `process_mining_hit`: ≤50 microseconds
 
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
+use oroboros_core::blocks::{BlockId, BlockRegistry};
+
 use crate::crafting::CraftingGraph;
 use crate::error::EconomyResult;
 use crate::inventory::{Inventory, ItemId, MAX_INVENTORY_SLOTS};
@@ -44,7 +47,7 @@ pub struct TransactionResult {
 #[derive(Clone, Debug)]
 pub struct LootDropInfo {
     /// Block that was mined.
-    pub block_id: u32,
+    pub block_id: BlockId,
     /// Item that dropped.
     pub item_id: ItemId,
     /// Quantity dropped.
@@ -84,15 +87,19 @@ impl EconomySystem {
     /// # Arguments
     ///
     /// * `wal_path` - Path to the WAL file
+    /// * `blocks` - Block definitions; mined blocks drop from their
+    ///   `loot_table` and need their `tool_tier`
     ///
     /// # Errors
     ///
     /// Returns error if WAL cannot be opened.
-    pub fn new(wal_path: impl AsRef<Path>) -> EconomyResult<Self> {
+    pub fn new(wal_path: impl AsRef<Path>, blocks: Arc<BlockRegistry>) -> EconomyResult<Self> {
         let wal = WriteAheadLog::open(wal_path)?;
+        let mut loot = LootCalculator::new();
+        loot.set_blocks(blocks);
 
         Ok(Self {
-            loot: LootCalculator::new(),
+            loot,
             crafting: CraftingGraph::new(),
             wal,
             inventories: std::collections::HashMap::new(),
@@ -156,7 +163,7 @@ impl EconomySystem {
     pub fn process_mining_hit(
         &mut self,
         entity_id: EntityId,
-        block_id: u32,
+        block_id: BlockId,
         player_level: u8,
         pickaxe_tier: u8,
         weather_seed: u32,
@@ -219,7 +226,7 @@ impl EconomySystem {
             // Log the operation
             txn.add_operation(WalOperation::LootDrop {
                 entity_id,
-                block_id,
+                block_id: u32::from(block_id),
                 item_id,
                 quantity,
             })?;
@@ -241,7 +248,7 @@ impl EconomySystem {
     /// Determines if a block should use secure RNG.
     ///
     /// Returns true if the block can potentially drop rare+ items.
-    fn should_use_secure_rng(&self, _block_id: u32) -> bool {
+    fn should_use_secure_rng(&self, _block_id: BlockId) -> bool {
         // TODO: Check loot table for this block's max rarity
         // For now, use secure RNG for all (safer default)
         true
@@ -328,6 +335,41 @@ mod tests {
         std::env::temp_dir().join(format!("test_economy_{id}.wal"))
     }
 
+    /// Blocks 1 and 9 drop from the test table; block 12 needs a tier 5
+    /// pickaxe.
+    fn test_blocks() -> Arc<BlockRegistry> {
+        let text = r#"
+            [[block]]
+            id = 0
+            name = "air"
+            solid = false
+            transparent = true
+
+            [[block]]
+            id = 1
+            name = "ore"
+            solid = true
+            transparent = false
+            loot_table = 1
+
+            [[block]]
+            id = 9
+            name = "crate"
+            solid = true
+            transparent = false
+            loot_table = 1
+
+            [[block]]
+            id = 12
+            name = "vault"
+            solid = true
+            transparent = false
+            tool_tier = 5
+            loot_table = 1
+        "#;
+        Arc::new(BlockRegistry::from_toml(text).unwrap())
+    }
+
     fn create_test_loot_table() -> LootTable {
         LootTable {
             block_id: 1,
@@ -359,7 +401,7 @@ mod tests {
     #[test]
     fn test_process_mining_hit_basic() {
         let path = temp_wal_path();
-        let mut system = EconomySystem::new(&path).unwrap();
+        let mut system = EconomySystem::new(&path, test_blocks()).unwrap();
 
         system.register_loot_table(create_test_loot_table());
         system.update_blockchain_salt(BlockchainSalt::test_salt());
@@ -377,7 +419,7 @@ mod tests {
     #[test]
     fn test_process_mining_hit_performance() {
         let path = temp_wal_path();
-        let mut system = EconomySystem::new(&path).unwrap();
+        let mut system = EconomySystem::new(&path, test_blocks()).unwrap();
 
         system.register_loot_table(create_test_loot_table());
         system.update_blockchain_salt(BlockchainSalt::test_salt());
@@ -460,10 +502,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blocks_drop_from_their_loot_table() {
+        let path = temp_wal_path();
+        let mut system = EconomySystem::new(&path, test_blocks()).unwrap();
+        system.register_loot_table(create_test_loot_table());
+        system.update_blockchain_salt(BlockchainSalt::test_salt());
+
+        // Block 9 has no table of its own ID, but names table 1
+        let drops = (0..20)
+            .filter(|&i| system.process_mining_hit(1, 9, 50, 3, i, i).unwrap().loot_drop.is_some())
+            .count();
+        assert!(drops > 0, "crate should drop from table 1");
+        let inventory = system.get_inventory(1).unwrap();
+        assert!(inventory.count_item(100) + inventory.count_item(101) > 0);
+
+        // Below its tool tier the vault drops nothing
+        for i in 0..20 {
+            assert!(system.process_mining_hit(2, 12, 50, 3, i, i).unwrap().loot_drop.is_none());
+        }
+        assert!(system.get_inventory(2).is_none());
+
+        std::fs::remove_file(&path).ok();
+    }
+
     #[test]
     fn test_inventory_persists_across_mining() {
         let path = temp_wal_path();
-        let mut system = EconomySystem::new(&path).unwrap();
+        let mut system = EconomySystem::new(&path, test_blocks()).unwrap();
 
         system.register_loot_table(create_test_loot_table());
         system.update_blockchain_salt(BlockchainSalt::test_salt());
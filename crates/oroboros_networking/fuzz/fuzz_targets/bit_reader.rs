@@ -0,0 +1,21 @@
+//! Reads arbitrary bit widths from arbitrary packed bytes.
+//!
+//! Input: the first quarter of the bytes are widths, the rest is the stream.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use oroboros_networking::protocol::{BitReader, DecodeError};
+
+fuzz_target!(|data: &[u8]| {
+    let (widths, bits) = data.split_at(data.len() / 4);
+    let mut reader = BitReader::new(bits);
+    for &width in widths {
+        match reader.read_bits(width) {
+            Ok(value) => assert!(width == 32 || value >> width == 0),
+            Err(DecodeError::InvalidBitWidth(_)) => assert!(width == 0 || width > 32),
+            Err(DecodeError::OutOfBits { .. }) => break,
+            Err(e) => panic!("unexpected bit reader error {e}"),
+        }
+    }
+});
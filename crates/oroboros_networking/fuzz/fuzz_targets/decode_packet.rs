@@ -0,0 +1,22 @@
+//! Decodes arbitrary datagrams as packets of every type.
+//!
+//! Anything accepted must re-encode to a form that decodes to itself.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use oroboros_networking::protocol::{Packet, PacketDeserializer, PacketSerializer};
+
+fn encode(packet: &Packet) -> Vec<u8> {
+    let mut serializer = PacketSerializer::new();
+    assert!(serializer.serialize_packet(packet));
+    serializer.as_slice().to_vec()
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(packet) = PacketDeserializer::new(data).deserialize() {
+        let encoded = encode(&packet);
+        let again = PacketDeserializer::new(&encoded).deserialize().expect("re-encoded packet decodes");
+        assert_eq!(encode(&again), encoded);
+    }
+});
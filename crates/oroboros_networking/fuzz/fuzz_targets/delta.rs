@@ -0,0 +1,26 @@
+//! Applies arbitrary delta packets to arbitrary base snapshots.
+//!
+//! Input: `[base len: u16 LE][base snapshot packet][delta packet]`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use oroboros_networking::protocol::{DeltaDecompressor, Packet, PacketDeserializer, WorldSnapshot};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+    let (len, rest) = data.split_at(2);
+    let (base, delta) = rest.split_at(usize::from(u16::from_le_bytes([len[0], len[1]])).min(rest.len()));
+
+    let mut decompressor = DeltaDecompressor::new();
+    if let Ok(Packet::Snapshot(_, snapshot)) = PacketDeserializer::new(base).deserialize() {
+        decompressor.set_base(&snapshot);
+    }
+    if let Ok(Packet::Delta(_, delta)) = PacketDeserializer::new(delta).deserialize() {
+        if let Ok(rebuilt) = decompressor.decompress(&delta) {
+            assert!(rebuilt.entity_count as usize <= WorldSnapshot::MAX_ENTITIES);
+        }
+    }
+});
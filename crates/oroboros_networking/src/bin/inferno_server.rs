@@ -87,6 +87,7 @@ fn main() {
         max_clients,
         port,
         bind_address: bind_addr.parse().expect("Valid bind address"),
+        ..ServerConfig::default()
     };
 
     let mut server = InfernoServer::new(config);
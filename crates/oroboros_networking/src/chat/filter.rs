@@ -0,0 +1,129 @@
+//! # Chat Filters
+//!
+//! Pluggable moderation filters run on every accepted chat message.
+//!
+//! Filters see the raw UTF-8 bytes and may rewrite them in place (for
+//! example to censor a word) or reject the message outright. Rewrites must
+//! keep the bytes valid UTF-8; replacing whole characters with ASCII does.
+
+use crate::protocol::ChatChannel;
+use crate::server::ConnectionId;
+
+/// Result of running a filter over a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterVerdict {
+    /// Deliver the (possibly rewritten) message.
+    Allow,
+    /// Drop the message. The reason is recorded in chat history.
+    Block(&'static str),
+}
+
+/// A moderation filter.
+///
+/// Implement this to plug external moderation (word lists, spam scoring,
+/// link blocking) into the chat pipeline. Filters run in registration order
+/// and the first `Block` wins.
+pub trait ChatFilter: Send {
+    /// Inspects and optionally rewrites `text`.
+    fn check(&mut self, sender: ConnectionId, channel: ChatChannel, text: &mut [u8]) -> FilterVerdict;
+}
+
+/// What a [`WordFilter`] does on a match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordAction {
+    /// Replace every byte of the matched word with `*`.
+    Censor,
+    /// Reject the whole message.
+    Block,
+}
+
+/// Case-insensitive word list filter.
+///
+/// Matching is ASCII case-insensitive and only counts whole words, so
+/// "class" does not trip a filter for "ass".
+#[derive(Clone, Debug)]
+pub struct WordFilter {
+    /// Lowercased words to match.
+    words: Vec<Vec<u8>>,
+    /// Action on match.
+    action: WordAction,
+}
+
+impl WordFilter {
+    /// Creates a filter from a word list.
+    #[must_use]
+    pub fn new<I, S>(words: I, action: WordAction) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|w| w.as_ref().to_ascii_lowercase().into_bytes())
+                .filter(|w| !w.is_empty())
+                .collect(),
+            action,
+        }
+    }
+
+    /// Returns the start of a whole-word match of `word` in `text`, if any.
+    fn find_word(text: &[u8], word: &[u8], from: usize) -> Option<usize> {
+        if word.len() > text.len() {
+            return None;
+        }
+        (from..=text.len() - word.len()).find(|&start| {
+            let end = start + word.len();
+            text[start..end].eq_ignore_ascii_case(word)
+                && (start == 0 || !text[start - 1].is_ascii_alphanumeric())
+                && (end == text.len() || !text[end].is_ascii_alphanumeric())
+        })
+    }
+}
+
+impl ChatFilter for WordFilter {
+    fn check(&mut self, _sender: ConnectionId, _channel: ChatChannel, text: &mut [u8]) -> FilterVerdict {
+        for word in &self.words {
+            let mut from = 0;
+            while let Some(start) = Self::find_word(text, word, from) {
+                match self.action {
+                    WordAction::Block => return FilterVerdict::Block("blocked word"),
+                    WordAction::Censor => {
+                        text[start..start + word.len()].fill(b'*');
+                        from = start + word.len();
+                    }
+                }
+            }
+        }
+        FilterVerdict::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_censor_whole_words_only() {
+        let mut filter = WordFilter::new(["rat"], WordAction::Censor);
+        let mut text = *b"Rat! the pirate is a rat";
+
+        let verdict = filter.check(ConnectionId(0), ChatChannel::Global, &mut text);
+
+        assert_eq!(verdict, FilterVerdict::Allow);
+        assert_eq!(&text, b"***! the pirate is a ***");
+    }
+
+    #[test]
+    fn test_block() {
+        let mut filter = WordFilter::new(["scam"], WordAction::Block);
+        let mut clean = *b"fair trade";
+        let mut dirty = *b"free SCAM here";
+
+        assert_eq!(filter.check(ConnectionId(0), ChatChannel::Global, &mut clean), FilterVerdict::Allow);
+        assert!(matches!(
+            filter.check(ConnectionId(0), ChatChannel::Global, &mut dirty),
+            FilterVerdict::Block(_)
+        ));
+    }
+}
@@ -0,0 +1,137 @@
+//! # Chat History
+//!
+//! Fixed-capacity ring buffer of recent chat traffic for admin review.
+//!
+//! Every submitted message is recorded, including rejected ones, so
+//! moderators can see what a muted or filtered player tried to say.
+
+use std::net::SocketAddr;
+use crate::protocol::{ChatChannel, ChatMessage};
+use crate::server::ConnectionId;
+
+/// What happened to a recorded message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatOutcome {
+    /// Delivered to `recipients` connections.
+    Delivered {
+        /// Number of connections the message was sent to.
+        recipients: u16,
+    },
+    /// Rejected before delivery.
+    Rejected(&'static str),
+}
+
+/// A single history entry.
+#[derive(Clone, Copy, Debug)]
+pub struct ChatRecord {
+    /// Server tick the message was submitted on.
+    pub tick: u32,
+    /// Sender connection.
+    pub sender: ConnectionId,
+    /// Sender network address.
+    pub sender_addr: SocketAddr,
+    /// The message as delivered (after filtering) or as received if rejected.
+    pub message: ChatMessage,
+    /// Delivery outcome.
+    pub outcome: ChatOutcome,
+}
+
+impl ChatRecord {
+    /// Returns the decoded channel.
+    #[must_use]
+    pub const fn channel(&self) -> Option<ChatChannel> {
+        self.message.channel()
+    }
+}
+
+/// Ring buffer of chat records.
+pub struct ChatHistory {
+    /// Record storage (pre-allocated).
+    records: Box<[Option<ChatRecord>]>,
+    /// Next write position.
+    head: usize,
+    /// Number of valid records.
+    len: usize,
+}
+
+impl ChatHistory {
+    /// Creates a history holding at most `capacity` records.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: vec![None; capacity.max(1)].into_boxed_slice(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Appends a record, overwriting the oldest one when full.
+    pub fn push(&mut self, record: ChatRecord) {
+        self.records[self.head] = Some(record);
+        self.head = (self.head + 1) % self.records.len();
+        self.len = (self.len + 1).min(self.records.len());
+    }
+
+    /// Returns the number of stored records.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no records are stored.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates records from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &ChatRecord> {
+        let cap = self.records.len();
+        let start = (self.head + cap - self.len) % cap;
+        (0..self.len).filter_map(move |i| self.records[(start + i) % cap].as_ref())
+    }
+
+    /// Iterates records sent from `ip` (any port, any session).
+    pub fn by_sender(&self, ip: std::net::IpAddr) -> impl Iterator<Item = &ChatRecord> {
+        self.iter().filter(move |r| r.sender_addr.ip() == ip)
+    }
+
+    /// Iterates records submitted on or after `tick`.
+    pub fn since(&self, tick: u32) -> impl Iterator<Item = &ChatRecord> {
+        self.iter().filter(move |r| r.tick >= tick)
+    }
+
+    /// Clears all records.
+    pub fn clear(&mut self) {
+        self.records.fill(None);
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(tick: u32) -> ChatRecord {
+        ChatRecord {
+            tick,
+            sender: ConnectionId(tick),
+            sender_addr: "127.0.0.1:1000".parse().unwrap(),
+            message: ChatMessage::new(tick, ChatChannel::Global, 0, "x"),
+            outcome: ChatOutcome::Delivered { recipients: 1 },
+        }
+    }
+
+    #[test]
+    fn test_ring_overwrites_oldest() {
+        let mut history = ChatHistory::new(3);
+        for tick in 0..5 {
+            history.push(record(tick));
+        }
+
+        let ticks: Vec<u32> = history.iter().map(|r| r.tick).collect();
+        assert_eq!(ticks, vec![2, 3, 4]);
+        assert_eq!(history.since(4).count(), 1);
+    }
+}
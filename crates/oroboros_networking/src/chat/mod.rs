@@ -0,0 +1,574 @@
+//! # In-Game Chat
+//!
+//! Server-side chat routing with rate limiting and moderation hooks.
+//!
+//! ## Pipeline
+//!
+//! ```text
+//! Chat packet ──► dedupe ──► validate ──► mute check ──► rate limit
+//!                                                            │
+//!       history ◄── route (global/proximity/party/whisper) ◄─ filters
+//! ```
+//!
+//! ## Design
+//!
+//! - Chat rides the reliable channel; resends are dropped by `message_id`
+//! - Per-connection state lives in fixed `MAX_CLIENTS` slots
+//! - Recipient lists are written into a pre-allocated buffer
+//! - Every submission (delivered or not) lands in [`ChatHistory`]
+
+mod filter;
+mod history;
+
+pub use filter::{ChatFilter, FilterVerdict, WordAction, WordFilter};
+pub use history::{ChatHistory, ChatOutcome, ChatRecord};
+
+use thiserror::Error;
+use crate::protocol::{ChatChannel, ChatMessage};
+use crate::server::{ConnectionId, Moderation, ServerState};
+use crate::MAX_CLIENTS;
+
+/// Chat configuration.
+#[derive(Clone, Debug)]
+pub struct ChatConfig {
+    /// Maximum UTF-8 bytes per message (capped at `ChatMessage::MAX_TEXT_BYTES`).
+    pub max_text_bytes: usize,
+    /// Messages a player may send in a burst.
+    pub burst: u32,
+    /// Ticks to regain one message of burst allowance.
+    pub refill_ticks: u32,
+    /// Hearing distance for the proximity channel (world units).
+    pub proximity_radius: f32,
+    /// Number of messages kept for admin review.
+    pub history_capacity: usize,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            max_text_bytes: 200,
+            burst: 5,
+            refill_ticks: 60, // 1 message/second at 60Hz
+            proximity_radius: 48.0,
+            history_capacity: 4096,
+        }
+    }
+}
+
+/// Why a chat message was not delivered.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatError {
+    /// Resend of a message already delivered.
+    #[error("duplicate message {0}")]
+    Duplicate(u32),
+    /// Sender is not an active connection.
+    #[error("unknown sender")]
+    UnknownSender,
+    /// Message has no text.
+    #[error("empty message")]
+    Empty,
+    /// Message exceeds the configured length.
+    #[error("message too long: {len} bytes, max {max}")]
+    TooLong {
+        /// Message length in bytes.
+        len: usize,
+        /// Configured maximum.
+        max: usize,
+    },
+    /// Text is not valid UTF-8.
+    #[error("message is not valid UTF-8")]
+    InvalidUtf8,
+    /// Clients may not post to the system channel.
+    #[error("channel not allowed")]
+    ChannelNotAllowed,
+    /// Sender is muted.
+    #[error("muted until tick {until_tick}")]
+    Muted {
+        /// Tick the mute expires.
+        until_tick: u32,
+    },
+    /// Sender exceeded the rate limit.
+    #[error("rate limited, retry in {retry_in_ticks} ticks")]
+    RateLimited {
+        /// Ticks until another message is allowed.
+        retry_in_ticks: u32,
+    },
+    /// Party chat without a party.
+    #[error("not in a party")]
+    NoParty,
+    /// Whisper target is not connected.
+    #[error("whisper target {0} is offline")]
+    TargetOffline(u32),
+    /// A moderation filter rejected the message.
+    #[error("blocked by filter: {0}")]
+    Blocked(&'static str),
+}
+
+impl ChatError {
+    /// Short player-facing notice for this rejection.
+    ///
+    /// Returns None for rejections the sender should not be told about.
+    #[must_use]
+    pub const fn notice(&self) -> Option<&'static str> {
+        match self {
+            Self::Duplicate(_) | Self::UnknownSender => None,
+            Self::Empty | Self::InvalidUtf8 | Self::ChannelNotAllowed => Some("Message not sent."),
+            Self::TooLong { .. } => Some("Message too long."),
+            Self::Muted { .. } => Some("You are muted."),
+            Self::RateLimited { .. } => Some("You are sending messages too quickly."),
+            Self::NoParty => Some("You are not in a party."),
+            Self::TargetOffline(_) => Some("That player is not online."),
+            Self::Blocked(_) => Some("Message blocked by moderation."),
+        }
+    }
+
+    /// Short reason recorded in chat history.
+    const fn reason(&self) -> &'static str {
+        match self {
+            Self::Duplicate(_) => "duplicate",
+            Self::UnknownSender => "unknown sender",
+            Self::Empty => "empty",
+            Self::TooLong { .. } => "too long",
+            Self::InvalidUtf8 => "invalid utf-8",
+            Self::ChannelNotAllowed => "channel not allowed",
+            Self::Muted { .. } => "muted",
+            Self::RateLimited { .. } => "rate limited",
+            Self::NoParty => "no party",
+            Self::TargetOffline(_) => "target offline",
+            Self::Blocked(reason) => reason,
+        }
+    }
+}
+
+/// Token-bucket rate limiter with one bucket per connection slot.
+struct RateLimiter {
+    /// Remaining tokens per slot.
+    tokens: Box<[u32]>,
+    /// Tick of the last refill per slot.
+    last_refill: Box<[u32]>,
+    /// Bucket capacity.
+    burst: u32,
+    /// Ticks per token.
+    refill_ticks: u32,
+}
+
+impl RateLimiter {
+    fn new(burst: u32, refill_ticks: u32) -> Self {
+        Self {
+            tokens: vec![burst; MAX_CLIENTS].into_boxed_slice(),
+            last_refill: vec![0; MAX_CLIENTS].into_boxed_slice(),
+            burst,
+            refill_ticks: refill_ticks.max(1),
+        }
+    }
+
+    fn reset(&mut self, slot: usize, tick: u32) {
+        self.tokens[slot] = self.burst;
+        self.last_refill[slot] = tick;
+    }
+
+    /// Takes a token, or returns the ticks until one is available.
+    fn try_acquire(&mut self, slot: usize, tick: u32) -> Result<(), u32> {
+        let elapsed = tick.saturating_sub(self.last_refill[slot]);
+        let refilled = elapsed / self.refill_ticks;
+        if refilled > 0 {
+            self.tokens[slot] = self.tokens[slot].saturating_add(refilled).min(self.burst);
+            self.last_refill[slot] += refilled * self.refill_ticks;
+        }
+
+        if self.tokens[slot] == 0 {
+            return Err(self.refill_ticks - elapsed % self.refill_ticks);
+        }
+        if self.tokens[slot] == self.burst {
+            // A full bucket restarts the refill clock.
+            self.last_refill[slot] = tick;
+        }
+        self.tokens[slot] -= 1;
+        Ok(())
+    }
+}
+
+/// A message ready to send, with its recipients.
+#[derive(Debug)]
+pub struct ChatDelivery<'a> {
+    /// The message stamped with the sender ID and filtered text.
+    pub message: ChatMessage,
+    /// Connections to deliver to (includes the sender as an echo).
+    pub recipients: &'a [ConnectionId],
+}
+
+/// Server-side chat service.
+pub struct ChatService {
+    /// Configuration.
+    config: ChatConfig,
+    /// Per-slot rate limiting.
+    limiter: RateLimiter,
+    /// Party ID per slot (0 = no party).
+    parties: Box<[u32]>,
+    /// Last delivered `message_id` per slot.
+    last_message_id: Box<[Option<u32>]>,
+    /// Moderation filters, run in order.
+    filters: Vec<Box<dyn ChatFilter>>,
+    /// Recent traffic for admins.
+    history: ChatHistory,
+    /// Recipient scratch buffer (pre-allocated to `MAX_CLIENTS`).
+    recipients: Vec<ConnectionId>,
+}
+
+impl ChatService {
+    /// Creates a chat service.
+    #[must_use]
+    pub fn new(config: ChatConfig) -> Self {
+        Self {
+            limiter: RateLimiter::new(config.burst, config.refill_ticks),
+            parties: vec![0; MAX_CLIENTS].into_boxed_slice(),
+            last_message_id: vec![None; MAX_CLIENTS].into_boxed_slice(),
+            filters: Vec::new(),
+            history: ChatHistory::new(config.history_capacity),
+            recipients: Vec::with_capacity(MAX_CLIENTS),
+            config,
+        }
+    }
+
+    /// Registers a moderation filter. Filters run in registration order.
+    pub fn add_filter(&mut self, filter: Box<dyn ChatFilter>) {
+        self.filters.push(filter);
+    }
+
+    /// Resets per-connection state for a newly connected slot.
+    pub fn on_connect(&mut self, id: ConnectionId, tick: u32) {
+        let Some(slot) = Self::slot(id) else { return };
+        self.limiter.reset(slot, tick);
+        self.parties[slot] = 0;
+        self.last_message_id[slot] = None;
+    }
+
+    /// Sets or clears the party a connection chats with.
+    pub fn set_party(&mut self, id: ConnectionId, party: Option<u32>) {
+        if let Some(slot) = Self::slot(id) {
+            self.parties[slot] = party.unwrap_or(0);
+        }
+    }
+
+    /// Returns the party a connection belongs to.
+    #[must_use]
+    pub fn party(&self, id: ConnectionId) -> Option<u32> {
+        Self::slot(id).map(|slot| self.parties[slot]).filter(|&p| p != 0)
+    }
+
+    /// Returns the chat history for admin review.
+    #[must_use]
+    pub const fn history(&self) -> &ChatHistory {
+        &self.history
+    }
+
+    /// Returns the chat history mutably (e.g. to clear it).
+    pub fn history_mut(&mut self) -> &mut ChatHistory {
+        &mut self.history
+    }
+
+    /// Validates, filters and routes a message from a client.
+    ///
+    /// Every outcome except [`ChatError::Duplicate`] and
+    /// [`ChatError::UnknownSender`] is recorded in the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the message was not delivered.
+    pub fn submit(
+        &mut self,
+        state: &ServerState,
+        moderation: &Moderation,
+        sender: ConnectionId,
+        mut message: ChatMessage,
+    ) -> Result<ChatDelivery<'_>, ChatError> {
+        let tick = state.current_tick();
+        let client = state.get_client(sender).ok_or(ChatError::UnknownSender)?;
+        let sender_addr = client.addr;
+        let slot = sender.0 as usize;
+
+        if let Some(last) = self.last_message_id[slot] {
+            if message.message_id.wrapping_sub(last) == 0
+                || message.message_id.wrapping_sub(last) >= 1 << 31
+            {
+                return Err(ChatError::Duplicate(message.message_id));
+            }
+        }
+        message.sender_id = sender.0;
+
+        let result = self.check_and_route(state, moderation, sender, &mut message);
+        let outcome = match &result {
+            Ok(count) => {
+                // Only delivered messages use up their ID, so rejected ones can be retried
+                self.last_message_id[slot] = Some(message.message_id);
+                ChatOutcome::Delivered { recipients: *count as u16 }
+            }
+            Err(e) => ChatOutcome::Rejected(e.reason()),
+        };
+        self.history.push(ChatRecord { tick, sender, sender_addr, message, outcome });
+
+        result.map(|count| ChatDelivery { message, recipients: &self.recipients[..count] })
+    }
+
+    /// Builds a system notice addressed to `target`.
+    #[must_use]
+    pub fn system_notice(target: ConnectionId, text: &str) -> ChatMessage {
+        let mut message = ChatMessage::new(0, ChatChannel::System, target.0, text);
+        message.sender_id = ConnectionId::NULL.0;
+        message
+    }
+
+    fn check_and_route(
+        &mut self,
+        state: &ServerState,
+        moderation: &Moderation,
+        sender: ConnectionId,
+        message: &mut ChatMessage,
+    ) -> Result<usize, ChatError> {
+        let tick = state.current_tick();
+        let slot = sender.0 as usize;
+        let channel = message.channel().ok_or(ChatError::ChannelNotAllowed)?;
+        if channel == ChatChannel::System {
+            return Err(ChatError::ChannelNotAllowed);
+        }
+
+        let max = self.config.max_text_bytes.min(ChatMessage::MAX_TEXT_BYTES);
+        let text = message.text().ok_or(ChatError::InvalidUtf8)?;
+        if text.trim().is_empty() {
+            return Err(ChatError::Empty);
+        }
+        if text.len() > max {
+            return Err(ChatError::TooLong { len: text.len(), max });
+        }
+
+        if let Some(client) = state.get_client(sender) {
+            if let Some(until_tick) = moderation.muted_until(client.addr.ip(), tick) {
+                return Err(ChatError::Muted { until_tick });
+            }
+        }
+
+        self.limiter
+            .try_acquire(slot, tick)
+            .map_err(|retry_in_ticks| ChatError::RateLimited { retry_in_ticks })?;
+
+        for filter in &mut self.filters {
+            if let FilterVerdict::Block(reason) = filter.check(sender, channel, message.text_bytes_mut()) {
+                return Err(ChatError::Blocked(reason));
+            }
+        }
+
+        self.route(state, sender, channel, message.target_id)
+    }
+
+    /// Fills the recipient buffer for a channel. Returns the recipient count.
+    fn route(
+        &mut self,
+        state: &ServerState,
+        sender: ConnectionId,
+        channel: ChatChannel,
+        target_id: u32,
+    ) -> Result<usize, ChatError> {
+        self.recipients.clear();
+
+        match channel {
+            ChatChannel::Global | ChatChannel::System => {
+                self.recipients.extend(state.iter_clients().map(|c| c.id));
+            }
+            ChatChannel::Proximity => {
+                let origin = state
+                    .get_client(sender)
+                    .and_then(|c| state.get_entity(c.entity_id))
+                    .map(|e| e.position);
+                let radius_sq = self.config.proximity_radius * self.config.proximity_radius;
+
+                if let Some(origin) = origin {
+                    self.recipients.extend(state.iter_clients().filter_map(|c| {
+                        let pos = state.get_entity(c.entity_id)?.position;
+                        let (dx, dy, dz) = (pos.x - origin.x, pos.y - origin.y, pos.z - origin.z);
+                        (dx * dx + dy * dy + dz * dz <= radius_sq).then_some(c.id)
+                    }));
+                } else {
+                    self.recipients.push(sender);
+                }
+            }
+            ChatChannel::Party => {
+                let party = self.party(sender).ok_or(ChatError::NoParty)?;
+                let parties = &self.parties;
+                self.recipients.extend(
+                    state
+                        .iter_clients()
+                        .filter(|c| parties[c.id.0 as usize] == party)
+                        .map(|c| c.id),
+                );
+            }
+            ChatChannel::Whisper => {
+                let target = ConnectionId(target_id);
+                if target == sender || state.get_client(target).is_none() {
+                    return Err(ChatError::TargetOffline(target_id));
+                }
+                self.recipients.push(target);
+                self.recipients.push(sender);
+            }
+        }
+
+        Ok(self.recipients.len())
+    }
+
+    fn slot(id: ConnectionId) -> Option<usize> {
+        (!id.is_null() && (id.0 as usize) < MAX_CLIENTS).then_some(id.0 as usize)
+    }
+}
+
+impl Default for ChatService {
+    fn default() -> Self {
+        Self::new(ChatConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use oroboros_core::Position;
+
+    fn addr(n: u8) -> SocketAddr {
+        format!("10.0.0.{n}:4000").parse().unwrap()
+    }
+
+    fn setup(players: u8) -> (ServerState, ChatService, Vec<ConnectionId>) {
+        let mut state = ServerState::new(MAX_CLIENTS);
+        let mut chat = ChatService::default();
+        let ids = (1..=players)
+            .map(|n| {
+                let id = state.add_client(addr(n)).unwrap();
+                chat.on_connect(id, 0);
+                id
+            })
+            .collect();
+        (state, chat, ids)
+    }
+
+    fn place(state: &mut ServerState, id: ConnectionId, x: f32) {
+        let entity = state.get_client(id).unwrap().entity_id;
+        state.get_entity_mut(entity).unwrap().position = Position::new(x, 0.0, 0.0);
+    }
+
+    #[test]
+    fn test_global_reaches_everyone() {
+        let (state, mut chat, ids) = setup(3);
+        let msg = ChatMessage::new(1, ChatChannel::Global, 0, "hello");
+
+        let delivery = chat.submit(&state, &Moderation::new(), ids[0], msg).unwrap();
+
+        assert_eq!(delivery.recipients.len(), 3);
+        assert_eq!(delivery.message.sender_id, ids[0].0);
+        assert_eq!(chat.history().len(), 1);
+    }
+
+    #[test]
+    fn test_proximity_uses_world_distance() {
+        let (mut state, mut chat, ids) = setup(3);
+        place(&mut state, ids[0], 0.0);
+        place(&mut state, ids[1], 10.0);
+        place(&mut state, ids[2], 500.0);
+
+        let msg = ChatMessage::new(1, ChatChannel::Proximity, 0, "psst");
+        let delivery = chat.submit(&state, &Moderation::new(), ids[0], msg).unwrap();
+
+        assert_eq!(delivery.recipients, &[ids[0], ids[1]]);
+    }
+
+    #[test]
+    fn test_party_and_whisper() {
+        let (state, mut chat, ids) = setup(3);
+        let moderation = Moderation::new();
+
+        let msg = ChatMessage::new(1, ChatChannel::Party, 0, "go");
+        assert_eq!(chat.submit(&state, &moderation, ids[0], msg).unwrap_err(), ChatError::NoParty);
+
+        chat.set_party(ids[0], Some(7));
+        chat.set_party(ids[2], Some(7));
+        let msg = ChatMessage::new(2, ChatChannel::Party, 0, "go");
+        let delivery = chat.submit(&state, &moderation, ids[0], msg).unwrap();
+        assert_eq!(delivery.recipients, &[ids[0], ids[2]]);
+
+        let msg = ChatMessage::new(3, ChatChannel::Whisper, ids[1].0, "hi");
+        let delivery = chat.submit(&state, &moderation, ids[0], msg).unwrap();
+        assert_eq!(delivery.recipients, &[ids[1], ids[0]]);
+
+        let msg = ChatMessage::new(4, ChatChannel::Whisper, 400, "hi");
+        assert_eq!(
+            chat.submit(&state, &moderation, ids[0], msg).unwrap_err(),
+            ChatError::TargetOffline(400)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_and_refill() {
+        let (mut state, mut chat, ids) = setup(1);
+        let moderation = Moderation::new();
+        let burst = ChatConfig::default().burst;
+
+        for id in 0..burst {
+            let msg = ChatMessage::new(id, ChatChannel::Global, 0, "spam");
+            assert!(chat.submit(&state, &moderation, ids[0], msg).is_ok());
+        }
+        let msg = ChatMessage::new(burst, ChatChannel::Global, 0, "spam");
+        assert!(matches!(
+            chat.submit(&state, &moderation, ids[0], msg),
+            Err(ChatError::RateLimited { .. })
+        ));
+
+        for _ in 0..ChatConfig::default().refill_ticks {
+            state.update();
+        }
+        // The retry keeps its ID
+        let msg = ChatMessage::new(burst, ChatChannel::Global, 0, "spam");
+        assert!(chat.submit(&state, &moderation, ids[0], msg).is_ok());
+    }
+
+    #[test]
+    fn test_duplicates_and_length() {
+        let (state, mut chat, ids) = setup(1);
+        let moderation = Moderation::new();
+
+        let msg = ChatMessage::new(5, ChatChannel::Global, 0, "once");
+        assert!(chat.submit(&state, &moderation, ids[0], msg).is_ok());
+        assert_eq!(chat.submit(&state, &moderation, ids[0], msg).unwrap_err(), ChatError::Duplicate(5));
+
+        let long = "a".repeat(ChatConfig::default().max_text_bytes + 1);
+        let msg = ChatMessage::new(6, ChatChannel::Global, 0, &long);
+        assert!(matches!(
+            chat.submit(&state, &moderation, ids[0], msg),
+            Err(ChatError::TooLong { .. })
+        ));
+
+        let msg = ChatMessage::new(7, ChatChannel::System, 0, "fake notice");
+        assert_eq!(chat.submit(&state, &moderation, ids[0], msg).unwrap_err(), ChatError::ChannelNotAllowed);
+
+        // Duplicates are not recorded, rejections are.
+        assert_eq!(chat.history().len(), 3);
+    }
+
+    #[test]
+    fn test_mute_and_filters() {
+        let (state, mut chat, ids) = setup(2);
+        let mut moderation = Moderation::new();
+        chat.add_filter(Box::new(WordFilter::new(["noob"], WordAction::Censor)));
+
+        let msg = ChatMessage::new(1, ChatChannel::Global, 0, "gg noob");
+        let delivery = chat.submit(&state, &moderation, ids[0], msg).unwrap();
+        assert_eq!(delivery.message.text(), Some("gg ****"));
+
+        moderation.mute(addr(1).ip(), 1_000);
+        let msg = ChatMessage::new(2, ChatChannel::Global, 0, "let me talk");
+        assert_eq!(
+            chat.submit(&state, &moderation, ids[0], msg).unwrap_err(),
+            ChatError::Muted { until_tick: 1_000 }
+        );
+
+        let rejected = chat.history().by_sender(addr(1).ip()).last().unwrap();
+        assert_eq!(rejected.outcome, ChatOutcome::Rejected("muted"));
+        assert_eq!(rejected.message.text(), Some("let me talk"));
+    }
+}
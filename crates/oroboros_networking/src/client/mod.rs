@@ -25,7 +25,8 @@
 use std::net::SocketAddr;
 use crate::protocol::{
     PacketHeader, PlayerInput, WorldSnapshot, DragonState,
-    PacketSerializer, PacketDeserializer, Packet,
+    PacketSerializer, PacketDeserializer, Packet, ChatChannel, ChatMessage, JoinToken, SpectateRequest,
+    DeltaDecompressor,
 };
 use crate::snapshot::SnapshotBuffer;
 use crate::prediction::PredictionBuffer;
@@ -78,7 +79,6 @@ impl Default for ClientConfig {
 /// Game client for OROBOROS.
 pub struct GameClient {
     /// Client configuration.
-    #[allow(dead_code)]
     config: ClientConfig,
     /// Current state.
     state: ClientState,
@@ -96,6 +96,8 @@ pub struct GameClient {
     input_sequence: u32,
     /// Snapshot buffer for interpolation.
     snapshots: SnapshotBuffer,
+    /// Rebuilds snapshots from deltas.
+    deltas: DeltaDecompressor,
     /// Prediction buffer for reconciliation.
     predictions: PredictionBuffer,
     /// Last server tick we received.
@@ -106,8 +108,19 @@ pub struct GameClient {
     dragon_state: DragonState,
     /// Packet serializer (reused).
     serializer: PacketSerializer,
+    /// Next chat message ID (lets the server drop reliable resends).
+    chat_message_id: u32,
+    /// Chat messages received since the last `drain_chat`.
+    chat_inbox: Vec<ChatMessage>,
+    /// Token to present to a new shard after a redirect.
+    resume_token: Option<u64>,
+    /// Whether this connection watches rather than plays.
+    spectating: bool,
 }
 
+/// Maximum chat messages buffered between `drain_chat` calls.
+const CHAT_INBOX_SIZE: usize = 64;
+
 impl GameClient {
     /// Creates a new client with the given configuration.
     #[must_use]
@@ -122,11 +135,16 @@ impl GameClient {
             ack_bits: 0,
             input_sequence: 0,
             snapshots: SnapshotBuffer::new(32),
+            deltas: DeltaDecompressor::new(),
             predictions: PredictionBuffer::new(64),
             last_server_tick: 0,
             rtt_ms: 100.0,
             dragon_state: DragonState::new(0, DragonState::STATE_SLEEP),
             serializer: PacketSerializer::new(),
+            chat_message_id: 0,
+            chat_inbox: Vec::with_capacity(CHAT_INBOX_SIZE),
+            resume_token: None,
+            spectating: false,
         }
     }
 
@@ -151,6 +169,30 @@ impl GameClient {
         self.entity_id
     }
 
+    /// Returns the address packets should be sent to.
+    ///
+    /// Changes when a zone shard hands the player off to a neighbour.
+    #[inline]
+    #[must_use]
+    pub const fn server_addr(&self) -> SocketAddr {
+        self.config.server_addr
+    }
+
+    /// Returns true if a redirect was received and the new shard has not
+    /// been sent a resume packet yet.
+    #[inline]
+    #[must_use]
+    pub const fn needs_resume(&self) -> bool {
+        self.resume_token.is_some()
+    }
+
+    /// Returns true if connected (or connecting) as a spectator.
+    #[inline]
+    #[must_use]
+    pub const fn is_spectating(&self) -> bool {
+        self.spectating
+    }
+
     /// Returns the estimated RTT in milliseconds.
     #[inline]
     #[must_use]
@@ -181,10 +223,74 @@ impl GameClient {
         }
     }
 
+    /// Creates a join packet for a matchmade session.
+    ///
+    /// Use instead of `create_connect_packet` when the lobby handed out a
+    /// `JoinTicket`; the server at the ticket's address validates the token.
+    #[must_use]
+    pub fn create_join_packet(&mut self, token: &JoinToken) -> Option<([u8; MAX_PACKET_SIZE], usize)> {
+        let header = PacketHeader::new(self.next_sequence(), self.recv_ack, self.ack_bits);
+
+        if self.serializer.serialize_join(&header, token) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            let len = self.serializer.len();
+            data[..len].copy_from_slice(self.serializer.as_slice());
+            self.state = ClientState::Connecting;
+            Some((data, len))
+        } else {
+            None
+        }
+    }
+
+    /// Creates a spectate packet.
+    ///
+    /// Connects as a spectator (no entity, no inputs) or, once connected,
+    /// moves the camera.
+    #[must_use]
+    pub fn create_spectate_packet(&mut self, request: &SpectateRequest) -> Option<([u8; MAX_PACKET_SIZE], usize)> {
+        if self.state == ClientState::Connected && !self.spectating {
+            return None;
+        }
+
+        let header = PacketHeader::new(self.next_sequence(), self.recv_ack, self.ack_bits);
+
+        if self.serializer.serialize_spectate(&header, request) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            let len = self.serializer.len();
+            data[..len].copy_from_slice(self.serializer.as_slice());
+            if !self.spectating {
+                self.spectating = true;
+                self.state = ClientState::Connecting;
+            }
+            Some((data, len))
+        } else {
+            None
+        }
+    }
+
+    /// Creates the resume packet for the shard named in the last redirect.
+    ///
+    /// Send it to `server_addr()`. The session carries on as before, so this
+    /// does not change the client state.
+    #[must_use]
+    pub fn create_resume_packet(&mut self) -> Option<([u8; MAX_PACKET_SIZE], usize)> {
+        let token = self.resume_token.take()?;
+        let header = PacketHeader::new(self.next_sequence(), self.recv_ack, self.ack_bits);
+
+        if self.serializer.serialize_resume(&header, token) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            let len = self.serializer.len();
+            data[..len].copy_from_slice(self.serializer.as_slice());
+            Some((data, len))
+        } else {
+            None
+        }
+    }
+
     /// Creates an input packet.
     #[must_use]
     pub fn create_input_packet(&mut self, input: &PlayerInput) -> Option<([u8; MAX_PACKET_SIZE], usize)> {
-        if self.state != ClientState::Connected {
+        if self.state != ClientState::Connected || self.spectating {
             return None;
         }
 
@@ -204,6 +310,40 @@ impl GameClient {
         }
     }
 
+    /// Creates a chat packet.
+    ///
+    /// The caller must send it through the reliability layer. For whispers,
+    /// `target_id` is the recipient's connection ID; it is ignored otherwise.
+    #[must_use]
+    pub fn create_chat_packet(
+        &mut self,
+        channel: ChatChannel,
+        target_id: u32,
+        text: &str,
+    ) -> Option<([u8; MAX_PACKET_SIZE], usize)> {
+        if self.state != ClientState::Connected {
+            return None;
+        }
+
+        let header = PacketHeader::new(self.next_sequence(), self.recv_ack, self.ack_bits);
+        let chat = ChatMessage::new(self.chat_message_id, channel, target_id, text);
+        self.chat_message_id = self.chat_message_id.wrapping_add(1);
+
+        if self.serializer.serialize_chat(&header, &chat) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            let len = self.serializer.len();
+            data[..len].copy_from_slice(self.serializer.as_slice());
+            Some((data, len))
+        } else {
+            None
+        }
+    }
+
+    /// Drains chat messages received since the last call, oldest first.
+    pub fn drain_chat(&mut self) -> impl Iterator<Item = ChatMessage> + '_ {
+        self.chat_inbox.drain(..)
+    }
+
     /// Creates a heartbeat packet.
     #[must_use]
     pub fn create_heartbeat_packet(&mut self) -> Option<([u8; MAX_PACKET_SIZE], usize)> {
@@ -223,34 +363,62 @@ impl GameClient {
     pub fn handle_packet(&mut self, data: &[u8]) {
         let mut deserializer = PacketDeserializer::new(data);
         
-        if let Some(packet) = deserializer.deserialize() {
-            // Update ack state
-            let header = packet.header();
-            self.update_ack(header.sequence);
-            
-            match packet {
-                Packet::ConnectAck(_, client_id) => {
-                    self.client_id = Some(client_id);
+        let packet = match deserializer.deserialize() {
+            Ok(packet) => packet,
+            Err(e) => {
+                tracing::debug!("Dropped packet from server: {}", e);
+                return;
+            }
+        };
+
+        // Update ack state
+        let header = packet.header();
+        self.update_ack(header.sequence);
+        
+        match packet {
+            Packet::ConnectAck(_, client_id) => {
+                self.client_id = Some(client_id);
+                if !self.spectating {
                     self.entity_id = Some(client_id); // Server assigns entity_id = client_id
-                    self.state = ClientState::Connected;
-                    tracing::info!("Connected with client_id: {}", client_id);
-                }
-                Packet::Snapshot(_, snapshot) => {
-                    self.handle_snapshot(snapshot);
-                }
-                Packet::Dragon(_, dragon) => {
-                    self.dragon_state = dragon;
                 }
-                Packet::Hit(_, hit) => {
-                    // Handle hit confirmation
-                    tracing::debug!("Hit confirmation: tick={}, hit={}", hit.shot_tick, hit.hit);
-                }
-                Packet::Disconnect(_) => {
-                    self.state = ClientState::Disconnected;
-                    self.client_id = None;
+                self.state = ClientState::Connected;
+                tracing::info!("Connected with client_id: {}", client_id);
+            }
+            Packet::Snapshot(_, snapshot) => {
+                self.deltas.set_base(&snapshot);
+                self.handle_snapshot(snapshot);
+            }
+            Packet::Delta(_, delta) => match self.deltas.decompress(&delta) {
+                Ok(&snapshot) => self.handle_snapshot(snapshot),
+                Err(e) => tracing::debug!("Dropped delta for tick {}: {}", delta.tick, e),
+            },
+            Packet::Dragon(_, dragon) => {
+                self.dragon_state = dragon;
+            }
+            Packet::Hit(_, hit) => {
+                // Handle hit confirmation
+                tracing::debug!("Hit confirmation: tick={}, hit={}", hit.shot_tick, hit.hit);
+            }
+            Packet::Disconnect(_) => {
+                self.state = ClientState::Disconnected;
+                self.client_id = None;
+                self.spectating = false;
+            }
+            Packet::Chat(_, chat) => {
+                if self.chat_inbox.len() == CHAT_INBOX_SIZE {
+                    // Oldest message falls off when the UI is not draining.
+                    self.chat_inbox.remove(0);
                 }
-                _ => {}
+                self.chat_inbox.push(chat);
             }
+            Packet::Redirect(_, redirect) => {
+                // Zone handoff: same session, different shard.
+                self.config.server_addr = redirect.addr();
+                self.entity_id = Some(redirect.entity_id);
+                self.resume_token = Some(redirect.token);
+                tracing::debug!("Redirected to shard {}", self.config.server_addr);
+            }
+            _ => {}
         }
     }
 
@@ -325,6 +493,7 @@ impl GameClient {
         self.state = ClientState::Disconnected;
         self.client_id = None;
         self.entity_id = None;
+        self.spectating = false;
     }
 }
 
@@ -369,4 +538,53 @@ mod tests {
         client.update_ack(1);
         assert_eq!(client.recv_ack, 2); // Shouldn't change
     }
+
+    #[test]
+    fn test_chat_roundtrip() {
+        let mut client = GameClient::new(ClientConfig::default());
+        assert!(client.create_chat_packet(ChatChannel::Global, 0, "hi").is_none());
+
+        client.handle_packet(&{
+            let mut s = PacketSerializer::new();
+            assert!(s.serialize_connect_ack(&PacketHeader::new(0, 0, 0), 3));
+            s.as_slice().to_vec()
+        });
+        let (data, len) = client.create_chat_packet(ChatChannel::Global, 0, "hi").unwrap();
+
+        // Server echoes the message back.
+        client.handle_packet(&data[..len]);
+        let received: Vec<ChatMessage> = client.drain_chat().collect();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].text(), Some("hi"));
+        assert_eq!(client.drain_chat().count(), 0);
+    }
+
+    #[test]
+    fn test_redirect_keeps_session() {
+        use crate::protocol::ShardRedirect;
+
+        let mut client = GameClient::new(ClientConfig::default());
+        client.handle_packet(&{
+            let mut s = PacketSerializer::new();
+            assert!(s.serialize_connect_ack(&PacketHeader::new(0, 0, 0), 3));
+            s.as_slice().to_vec()
+        });
+
+        let shard: SocketAddr = "127.0.0.1:7002".parse().unwrap();
+        client.handle_packet(&{
+            let mut s = PacketSerializer::new();
+            assert!(s.serialize_redirect(&PacketHeader::new(1, 0, 0), &ShardRedirect::new(77, shard, 9)));
+            s.as_slice().to_vec()
+        });
+
+        assert_eq!(client.state(), ClientState::Connected);
+        assert_eq!(client.server_addr(), shard);
+        assert_eq!(client.entity_id(), Some(9));
+        assert!(client.needs_resume());
+
+        let (data, len) = client.create_resume_packet().unwrap();
+        let mut deserializer = PacketDeserializer::new(&data[..len]);
+        assert!(matches!(deserializer.deserialize(), Ok(Packet::Resume(_, 77))));
+        assert!(!client.needs_resume());
+    }
 }
@@ -98,17 +98,37 @@ impl GoldenPathTest {
         let spawn_pos = Position::new(0.0, 0.0, 0.0);
         server.connect_player(player_id, spawn_pos);
         
-        // Place a diamond block in the world (using test-only method)
-        server.test_set_block((5, 5, 5), 1); // Block type 1 = diamond
+        // Place diamond ore in the world (using test-only method)
+        let diamond_ore = 22;
+        server.test_set_block((5, 5, 5), diamond_ore);
+        
+        // Dig it with a diamond pickaxe until the server agrees it's broken
+        let diamond_pickaxe = 1102;
+        server.equip_tool(player_id, Some(diamond_pickaxe));
+        let dig_ticks = server
+            .mining_rules()
+            .break_ticks(diamond_ore, Some(diamond_pickaxe), &[])
+            .unwrap_or(0);
+        server.queue_action(player_id, PlayerAction::StartBreaking {
+            sequence: 0,
+            block_pos: (5, 5, 5),
+        });
+        for sequence in 0..dig_ticks {
+            server.queue_action(player_id, PlayerAction::ContinueBreaking {
+                sequence,
+                block_pos: (5, 5, 5),
+            });
+            server.tick();
+        }
         
         let setup_time = start.elapsed();
         
         // === THE GOLDEN PATH ===
         let action_start = Instant::now();
         
-        // Step 1: Client sends BreakBlock action
+        // Step 1: Client sends BreakBlock action once digging is done
         server.queue_action(player_id, PlayerAction::BreakBlock {
-            sequence: 1,
+            sequence: dig_ticks,
             block_pos: (5, 5, 5),
         });
         
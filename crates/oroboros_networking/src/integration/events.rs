@@ -4,8 +4,8 @@
 //!
 //! ## Event Flow for Block Break:
 //! ```text
-//! 1. Client: PlayerAction::BreakBlock
-//! 2. Server: Validate hit (Raycast)
+//! 1. Client: PlayerAction::StartBreaking, ContinueBreaking…, BreakBlock
+//! 2. Server: Validate hit (Raycast) and mining progress
 //! 3. Server → Unit 3: BlockBreakRequest
 //! 4. Unit 3 → Server: BlockBreakResult (with loot)
 //! 5. Server → Unit 1: InventoryUpdate
@@ -54,7 +54,29 @@ pub enum PlayerAction {
         target: Option<EntityId>,
     },
     
-    /// Player wants to break a block.
+    /// Player started digging a block.
+    StartBreaking {
+        /// Input sequence.
+        sequence: u32,
+        /// Block position in world.
+        block_pos: (i32, i32, i32),
+    },
+    
+    /// Player is still digging a block (sent while the button is held).
+    ContinueBreaking {
+        /// Input sequence.
+        sequence: u32,
+        /// Block position in world.
+        block_pos: (i32, i32, i32),
+    },
+    
+    /// Player stopped digging before the block broke.
+    CancelBreaking {
+        /// Input sequence.
+        sequence: u32,
+    },
+    
+    /// Player finished digging and wants the block broken.
     BreakBlock {
         /// Input sequence.
         sequence: u32,
@@ -113,6 +135,36 @@ pub enum GameEvent {
         loot: Vec<LootDrop>,
     },
     
+    /// A player's dig reached a new crack stage.
+    MiningProgress {
+        /// Who is digging.
+        player_id: PlayerId,
+        /// Where.
+        position: (i32, i32, i32),
+        /// Crack stage (0 to `MINING_STAGES - 1`).
+        stage: u8,
+    },
+    
+    /// A player stopped digging (cancelled, timed out, or the block changed).
+    MiningStopped {
+        /// Who was digging.
+        player_id: PlayerId,
+        /// Where.
+        position: (i32, i32, i32),
+    },
+    
+    /// The server refused to break a block.
+    BlockBreakRejected {
+        /// Who tried to break it.
+        player_id: PlayerId,
+        /// Where.
+        position: (i32, i32, i32),
+        /// Input sequence of the rejected action.
+        sequence: u32,
+        /// Why.
+        reason: MiningRejection,
+    },
+    
     /// A block was placed.
     BlockPlaced {
         /// Who placed it.
@@ -162,6 +214,19 @@ pub enum GameEvent {
     },
 }
 
+/// Why the server refused to start or finish breaking a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MiningRejection {
+    /// The block isn't in the block registry.
+    UnknownBlock,
+    /// The block can never be broken.
+    Unbreakable,
+    /// The player isn't digging that block.
+    NotDigging,
+    /// Not enough mining work was done yet.
+    TooFast,
+}
+
 /// An item that dropped as loot.
 #[derive(Clone, Debug)]
 pub struct LootDrop {
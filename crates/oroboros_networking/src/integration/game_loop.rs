@@ -10,10 +10,11 @@
 //! 3. Process actions:
 //!    a. Movement → Update Unit 1
 //!    b. Attacks → Call Unit 3 → Update Unit 1
-//!    c. Block breaks → Call Unit 3 → Update Unit 1
-//! 4. Sync Dragon with market (Unit 3)
-//! 5. Broadcast events to clients (Unit 4)
-//! 6. Swap buffers (Unit 1)
+//!    c. Block breaks → Check mining progress → Call Unit 3 → Update Unit 1
+//! 4. Advance mining progress, expire status effects
+//! 5. Sync Dragon with market (Unit 3)
+//! 6. Broadcast events to clients (Unit 4)
+//! 7. Swap buffers (Unit 1)
 //! ```
 
 use std::time::{Duration, Instant};
@@ -22,6 +23,7 @@ use std::collections::HashMap;
 use oroboros_core::Position;
 
 use crate::integration::events::*;
+use crate::integration::mining::*;
 use crate::integration::traits::*;
 
 /// Server tick rate (60 Hz).
@@ -61,6 +63,10 @@ pub struct ConnectedClient {
     pub connected_at: Instant,
     /// Last activity time.
     pub last_activity: Instant,
+    /// Item in hand (decides pickaxe tier).
+    pub held_tool: Option<ItemId>,
+    /// Active status effects.
+    pub effects: Vec<StatusEffect>,
 }
 
 /// Pending action to process.
@@ -93,6 +99,10 @@ pub struct GameServer<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> {
     pending_actions: Vec<PendingAction>,
     /// Events to broadcast this tick.
     pending_events: Vec<(PlayerId, GameEvent)>,
+    /// Break-time rules.
+    mining: MiningRules,
+    /// Digs in progress by player.
+    digs: HashMap<PlayerId, MiningState>,
     /// Current tick number.
     tick: u64,
     /// Server start time.
@@ -117,6 +127,8 @@ pub struct ServerStats {
     pub max_tick_duration_us: u64,
     /// Total blocks broken.
     pub blocks_broken: u64,
+    /// Block breaks refused (too fast, not digging, unbreakable).
+    pub breaks_rejected: u64,
     /// Total damage dealt.
     pub damage_dealt: u64,
 }
@@ -132,12 +144,21 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
             clients: HashMap::new(),
             pending_actions: Vec::new(),
             pending_events: Vec::new(),
+            mining: MiningRules::default(),
+            digs: HashMap::new(),
             tick: 0,
             start_time: Instant::now(),
             stats: ServerStats::default(),
         }
     }
     
+    /// Replaces the break-time rules.
+    #[must_use]
+    pub fn with_mining_rules(mut self, mining: MiningRules) -> Self {
+        self.mining = mining;
+        self
+    }
+    
     /// Connects a new player.
     pub fn connect_player(&mut self, player_id: PlayerId, spawn_position: Position) -> EntityId {
         let entity_id = self.memory.spawn_entity(EntityType::Player, spawn_position);
@@ -147,6 +168,8 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
             last_input_seq: 0,
             connected_at: Instant::now(),
             last_activity: Instant::now(),
+            held_tool: None,
+            effects: Vec::new(),
         });
         
         entity_id
@@ -157,6 +180,21 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
         if let Some(client) = self.clients.remove(&player_id) {
             self.memory.despawn_entity(client.entity_id);
         }
+        self.digs.remove(&player_id);
+    }
+    
+    /// Sets the item a player holds (decides their pickaxe tier).
+    pub fn equip_tool(&mut self, player_id: PlayerId, tool: Option<ItemId>) {
+        if let Some(client) = self.clients.get_mut(&player_id) {
+            client.held_tool = tool;
+        }
+    }
+    
+    /// Applies a status effect to a player for its duration.
+    pub fn apply_status_effect(&mut self, player_id: PlayerId, effect: StatusEffect) {
+        if let Some(client) = self.clients.get_mut(&player_id) {
+            client.effects.push(effect);
+        }
     }
     
     // =========================================================================
@@ -179,6 +217,21 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
         self.memory.get_health(entity_id)
     }
     
+    /// Gets a player's dig in progress.
+    pub fn mining_state(&self, player_id: PlayerId) -> Option<&MiningState> {
+        self.digs.get(&player_id)
+    }
+    
+    /// Gets a player's active status effects.
+    pub fn status_effects(&self, player_id: PlayerId) -> Option<&[StatusEffect]> {
+        self.clients.get(&player_id).map(|client| client.effects.as_slice())
+    }
+    
+    /// Gets the break-time rules.
+    pub fn mining_rules(&self) -> &MiningRules {
+        &self.mining
+    }
+    
     // =========================================================================
     // TEST-ONLY METHODS - Marked with cfg(test) or #[doc(hidden)]
     // =========================================================================
@@ -217,18 +270,27 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
             self.process_action(pending);
         }
         
-        // Step 2: Tick the economy (sync with market)
+        // Step 2: Advance digs and expire status effects
+        self.advance_mining();
+        for client in self.clients.values_mut() {
+            client.effects.retain_mut(|effect| {
+                effect.duration_ticks = effect.duration_ticks.saturating_sub(1);
+                effect.duration_ticks > 0
+            });
+        }
+        
+        // Step 3: Tick the economy (sync with market)
         self.economy.tick();
         
-        // Step 3: Collect events to broadcast
+        // Step 4: Collect events to broadcast
         let events = std::mem::take(&mut self.pending_events);
         self.stats.events_this_tick = events.len() as u32;
         
-        // Step 4: Swap buffers (Unit 1)
+        // Step 5: Swap buffers (Unit 1)
         self.memory.swap_buffers();
         self.memory.clear_dirty();
         
-        // Step 5: Update stats
+        // Step 6: Update stats
         let tick_duration = tick_start.elapsed().as_micros() as u64;
         self.stats.ticks_processed += 1;
         self.stats.avg_tick_duration_us = 
@@ -261,6 +323,19 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
             PlayerAction::Attack { sequence, direction, target } => {
                 self.process_attack(pending.player_id, entity_id, sequence, direction, target);
             }
+            PlayerAction::StartBreaking { sequence, block_pos } => {
+                self.process_start_breaking(pending.player_id, sequence, block_pos);
+            }
+            PlayerAction::ContinueBreaking { sequence: _, block_pos } => {
+                if let Some(dig) = self.digs.get_mut(&pending.player_id) {
+                    if dig.position == block_pos {
+                        dig.last_heard = self.tick;
+                    }
+                }
+            }
+            PlayerAction::CancelBreaking { sequence: _ } => {
+                self.stop_digging(pending.player_id);
+            }
             PlayerAction::BreakBlock { sequence, block_pos } => {
                 self.process_block_break(pending.player_id, sequence, block_pos);
             }
@@ -374,10 +449,104 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
         }
     }
     
+    /// Processes a start breaking action.
+    fn process_start_breaking(
+        &mut self,
+        player_id: PlayerId,
+        sequence: u32,
+        block_pos: (i32, i32, i32),
+    ) {
+        let block_type = self.memory.get_block(block_pos);
+        if block_type == 0 {
+            // Air - nothing to dig
+            return;
+        }
+        
+        // A new dig replaces the old one
+        self.stop_digging(player_id);
+        
+        let required = match self.mining.required_work(block_type) {
+            Ok(required) => required,
+            Err(reason) => {
+                self.reject_break(player_id, sequence, block_pos, reason);
+                return;
+            }
+        };
+        
+        self.digs.insert(player_id, MiningState::new(block_pos, block_type, required, self.tick));
+        self.pending_events.push((player_id, GameEvent::MiningProgress {
+            player_id,
+            position: block_pos,
+            stage: 0,
+        }));
+    }
+    
+    /// Drops a player's dig, telling clients to clear its cracks.
+    fn stop_digging(&mut self, player_id: PlayerId) {
+        if let Some(dig) = self.digs.remove(&player_id) {
+            self.pending_events.push((player_id, GameEvent::MiningStopped {
+                player_id,
+                position: dig.position,
+            }));
+        }
+    }
+    
+    /// Refuses a block break.
+    fn reject_break(
+        &mut self,
+        player_id: PlayerId,
+        sequence: u32,
+        block_pos: (i32, i32, i32),
+        reason: MiningRejection,
+    ) {
+        self.stats.breaks_rejected += 1;
+        self.pending_events.push((player_id, GameEvent::BlockBreakRejected {
+            player_id,
+            position: block_pos,
+            sequence,
+            reason,
+        }));
+    }
+    
+    /// Adds one tick of work to every dig.
+    ///
+    /// Digs stop when the player goes quiet for
+    /// [`MINING_IDLE_TIMEOUT_TICKS`] or the block changes under them.
+    fn advance_mining(&mut self) {
+        let mut stopped = Vec::new();
+        
+        for (&player_id, dig) in &mut self.digs {
+            let Some(client) = self.clients.get(&player_id) else {
+                stopped.push(player_id);
+                continue;
+            };
+            if self.tick - dig.last_heard > MINING_IDLE_TIMEOUT_TICKS
+                || self.memory.get_block(dig.position) != dig.block_type
+            {
+                stopped.push(player_id);
+                continue;
+            }
+            
+            let tier = self.mining.tool_tier(client.held_tool);
+            let rate = self.mining.work_per_tick(dig.block_type, tier, &client.effects);
+            if dig.advance(rate) {
+                self.pending_events.push((player_id, GameEvent::MiningProgress {
+                    player_id,
+                    position: dig.position,
+                    stage: dig.stage,
+                }));
+            }
+        }
+        
+        for player_id in stopped {
+            self.stop_digging(player_id);
+        }
+    }
+    
     /// Processes a block break action.
     ///
     /// THIS IS THE GOLDEN PATH for block breaking:
-    /// 1. Validate block exists
+    /// 1. Validate block exists and enough mining work was done
     /// 2. Call Unit 3 for loot calculation
     /// 3. Update world (Unit 1)
     /// 4. Update inventory (Unit 1)
@@ -385,7 +554,7 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
     fn process_block_break(
         &mut self,
         player_id: PlayerId,
-        _sequence: u32,
+        sequence: u32,
         block_pos: (i32, i32, i32),
     ) {
         // Step 1: Validate block exists
@@ -395,9 +564,35 @@ impl<M: MemoryOwner, E: EconomyAuditor, V: VisualFeedback> GameServer<M, E, V> {
             return;
         }
         
+        // ...and that the player has been digging it long enough
+        let Some(client) = self.clients.get(&player_id) else {
+            return;
+        };
+        let tool_id = client.held_tool;
+        let required = match self.mining.required_work(block_type) {
+            Ok(required) => required,
+            Err(reason) => {
+                self.reject_break(player_id, sequence, block_pos, reason);
+                return;
+            }
+        };
+        if required > 0.0 {
+            let rate = self.mining.work_per_tick(block_type, self.mining.tool_tier(tool_id), &client.effects);
+            let rejection = match self.digs.get(&player_id) {
+                Some(dig) if dig.position == block_pos && dig.block_type == block_type => {
+                    (!dig.can_finish(rate)).then_some(MiningRejection::TooFast)
+                }
+                _ => Some(MiningRejection::NotDigging),
+            };
+            if let Some(reason) = rejection {
+                self.stop_digging(player_id);
+                self.reject_break(player_id, sequence, block_pos, reason);
+                return;
+            }
+        }
+        self.digs.remove(&player_id);
+        
         // Step 2: Call Unit 3 for loot calculation
-        // TODO: Get tool from player inventory
-        let tool_id = None;
         let response = self.economy.on_block_break(
             player_id,
             block_pos,
@@ -0,0 +1,401 @@
+//! # Block Mining
+//!
+//! Server-authoritative mining progress.
+//!
+//! A player starts digging a block, keeps the dig alive with continue
+//! actions while the button is held, and finally asks for the block to
+//! break. The server accrues mining work every tick from the block's
+//! hardness, the held pickaxe and the player's status effects, broadcasts
+//! crack stages so other clients can draw them, and rejects a break that
+//! arrives before enough work has been done.
+//!
+//! ## Break Time
+//!
+//! ```text
+//! required work = hardness × 90              (1.5 s per hardness point at 60 Hz)
+//! work per tick = pickaxe speed × effects    (0.3 if the pickaxe tier is too low)
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use oroboros_core::BlockRegistry;
+
+use crate::integration::events::*;
+
+/// Mining work needed per point of block hardness (1.5 seconds at 60 Hz).
+pub const WORK_PER_HARDNESS: f32 = 90.0;
+
+/// Work per tick when the pickaxe tier is below the block's `tool_tier`.
+pub const UNDER_TIER_SPEED: f32 = 0.3;
+
+/// Number of crack stages broadcast while a block is being dug.
+pub const MINING_STAGES: u8 = 10;
+
+/// Ticks without a continue action before a dig is dropped.
+pub const MINING_IDLE_TIMEOUT_TICKS: u64 = 10;
+
+/// Ticks of work a break may arrive early by, to absorb network jitter.
+pub const BREAK_TOLERANCE_TICKS: f32 = 3.0;
+
+/// Pickaxes from `economy/items.toml` and their tiers.
+///
+/// Every item there with a `tier` is a pickaxe; a test keeps this list in
+/// step with the file.
+pub const SHIPPED_PICKAXES: [(ItemId, u8); 3] = [
+    (1100, 2), // Iron Pickaxe
+    (1101, 3), // Steel Pickaxe
+    (1102, 4), // Diamond Pickaxe
+];
+
+/// Mining speed of a pickaxe tier (tier 0 is bare hands).
+#[must_use]
+pub fn pickaxe_speed(tier: u8) -> f32 {
+    match tier {
+        0 => 1.0,
+        1 => 2.0,
+        2 => 4.0,
+        3 => 6.0,
+        4 => 8.0,
+        _ => 12.0,
+    }
+}
+
+/// Speed multiplier from a player's status effects.
+///
+/// Haste speeds mining up and Slowed (mining fatigue) slows it down, each
+/// by `1 + magnitude`. Other effects don't affect mining.
+#[must_use]
+pub fn effect_multiplier(effects: &[StatusEffect]) -> f32 {
+    effects.iter().fold(1.0, |multiplier, effect| match effect.effect_type {
+        StatusEffectType::Haste => multiplier * (1.0 + effect.magnitude),
+        StatusEffectType::Slowed => multiplier / (1.0 + effect.magnitude),
+        _ => multiplier,
+    })
+}
+
+/// Break-time rules: block hardness from the block registry and pickaxe
+/// tiers by item.
+#[derive(Clone, Debug)]
+pub struct MiningRules {
+    /// Block definitions (hardness and required tool tier).
+    blocks: Arc<BlockRegistry>,
+    /// Pickaxe tier by item ID.
+    tool_tiers: HashMap<ItemId, u8>,
+}
+
+impl MiningRules {
+    /// Creates rules for the given blocks, with no pickaxes registered.
+    #[must_use]
+    pub fn new(blocks: Arc<BlockRegistry>) -> Self {
+        Self {
+            blocks,
+            tool_tiers: HashMap::new(),
+        }
+    }
+
+    /// Registers an item as a pickaxe of the given tier.
+    #[must_use]
+    pub fn with_tool(mut self, item_id: ItemId, tier: u8) -> Self {
+        self.tool_tiers.insert(item_id, tier);
+        self
+    }
+
+    /// Pickaxe tier of a held item (0 for bare hands or a non-pickaxe).
+    #[must_use]
+    pub fn tool_tier(&self, tool: Option<ItemId>) -> u8 {
+        tool.and_then(|item_id| self.tool_tiers.get(&item_id).copied())
+            .unwrap_or(0)
+    }
+
+    /// Total work needed to break a block.
+    ///
+    /// # Errors
+    ///
+    /// Fails for blocks that aren't registered or can never be broken.
+    pub fn required_work(&self, block_type: BlockId) -> Result<f32, MiningRejection> {
+        let def = self.blocks.get(block_type).ok_or(MiningRejection::UnknownBlock)?;
+        if !def.is_breakable() {
+            return Err(MiningRejection::Unbreakable);
+        }
+        Ok(def.hardness * WORK_PER_HARDNESS)
+    }
+
+    /// Work done per tick on a block with a pickaxe tier and effects.
+    #[must_use]
+    pub fn work_per_tick(&self, block_type: BlockId, tier: u8, effects: &[StatusEffect]) -> f32 {
+        let required_tier = self.blocks.get(block_type).map_or(0, |def| def.tool_tier);
+        let speed = if tier < required_tier {
+            UNDER_TIER_SPEED
+        } else {
+            pickaxe_speed(tier)
+        };
+        speed * effect_multiplier(effects)
+    }
+
+    /// Ticks needed to break a block, for client-side prediction.
+    ///
+    /// # Errors
+    ///
+    /// Fails for blocks that aren't registered or can never be broken.
+    pub fn break_ticks(
+        &self,
+        block_type: BlockId,
+        tool: Option<ItemId>,
+        effects: &[StatusEffect],
+    ) -> Result<u32, MiningRejection> {
+        let required = self.required_work(block_type)?;
+        let rate = self.work_per_tick(block_type, self.tool_tier(tool), effects);
+        Ok((required / rate).ceil() as u32)
+    }
+}
+
+impl Default for MiningRules {
+    /// Shipped blocks and pickaxes.
+    fn default() -> Self {
+        SHIPPED_PICKAXES
+            .iter()
+            .fold(Self::new(BlockRegistry::shipped()), |rules, &(item_id, tier)| {
+                rules.with_tool(item_id, tier)
+            })
+    }
+}
+
+/// A player's dig on one block.
+#[derive(Clone, Debug)]
+pub struct MiningState {
+    /// Block being dug.
+    pub position: (i32, i32, i32),
+    /// Block type when digging started.
+    pub block_type: BlockId,
+    /// Work done so far.
+    pub work: f32,
+    /// Work needed to break the block.
+    pub required: f32,
+    /// Last crack stage broadcast.
+    pub stage: u8,
+    /// Tick of the last start or continue action.
+    pub last_heard: u64,
+}
+
+impl MiningState {
+    /// Starts a dig with no work done.
+    #[must_use]
+    pub fn new(position: (i32, i32, i32), block_type: BlockId, required: f32, tick: u64) -> Self {
+        Self {
+            position,
+            block_type,
+            work: 0.0,
+            required,
+            stage: 0,
+            last_heard: tick,
+        }
+    }
+
+    /// Fraction of the block broken (0.0 - 1.0).
+    #[must_use]
+    pub fn progress(&self) -> f32 {
+        if self.required <= 0.0 {
+            1.0
+        } else {
+            (self.work / self.required).min(1.0)
+        }
+    }
+
+    /// Adds one tick of work. Returns true if the crack stage changed.
+    pub fn advance(&mut self, rate: f32) -> bool {
+        self.work = (self.work + rate).min(self.required);
+        let stage = ((self.progress() * f32::from(MINING_STAGES)) as u8).min(MINING_STAGES - 1);
+        let changed = stage != self.stage;
+        self.stage = stage;
+        changed
+    }
+
+    /// True if a break is plausible at the current work rate, allowing
+    /// [`BREAK_TOLERANCE_TICKS`] of jitter.
+    #[must_use]
+    pub fn can_finish(&self, rate: f32) -> bool {
+        self.work + rate * BREAK_TOLERANCE_TICKS >= self.required
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::game_loop::*;
+    use crate::integration::traits::*;
+    use oroboros_core::Position;
+
+    const STONE: BlockId = 2;
+    const DIAMOND_ORE: BlockId = 22;
+    const BEDROCK: BlockId = 7;
+
+    fn server() -> GameServer<MockMemoryOwner, MockEconomyAuditor, MockVisualFeedback> {
+        let mut server = GameServer::new(
+            ServerConfig::default(),
+            MockMemoryOwner::new(),
+            MockEconomyAuditor::new(),
+            MockVisualFeedback::new(),
+        );
+        server.connect_player(1, Position::new(0.0, 0.0, 0.0));
+        server.connect_player(2, Position::new(2.0, 0.0, 0.0));
+        server
+    }
+
+    fn dig(server: &mut GameServer<MockMemoryOwner, MockEconomyAuditor, MockVisualFeedback>, ticks: u32) -> Vec<(PlayerId, GameEvent)> {
+        let mut events = Vec::new();
+        for sequence in 0..ticks {
+            server.queue_action(1, PlayerAction::ContinueBreaking { sequence, block_pos: (0, 0, 0) });
+            events.extend(server.tick());
+        }
+        events
+    }
+
+    fn haste(magnitude: f32) -> StatusEffect {
+        StatusEffect {
+            effect_type: StatusEffectType::Haste,
+            duration_ticks: 600,
+            magnitude,
+        }
+    }
+
+    #[test]
+    fn test_shipped_pickaxes_match_item_data() {
+        let items: toml::Table =
+            include_str!("../../../../data/schemas/economy/items.toml").parse().unwrap();
+        let mut tiers: Vec<(ItemId, u8)> = items["item"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|item| {
+                let tier = item.get("tier")?.as_integer()?;
+                let id = item["id"].as_integer().unwrap();
+                Some((ItemId::try_from(id).unwrap(), u8::try_from(tier).unwrap()))
+            })
+            .collect();
+        tiers.sort_unstable();
+        assert_eq!(tiers, SHIPPED_PICKAXES);
+    }
+
+    #[test]
+    fn test_break_time_from_hardness_tool_and_effects() {
+        let rules = MiningRules::default();
+
+        // Stone: hardness 1.5 → 135 work; bare hands 1/tick, diamond pickaxe 8/tick
+        assert_eq!(rules.break_ticks(STONE, None, &[]), Ok(135));
+        assert_eq!(rules.break_ticks(STONE, Some(1102), &[]), Ok(17));
+        assert_eq!(rules.break_ticks(STONE, Some(1102), &[haste(1.0)]), Ok(9));
+
+        // Diamond ore needs tier 3: an iron pickaxe digs at the penalty speed
+        let under = rules.break_ticks(DIAMOND_ORE, Some(1100), &[]).unwrap();
+        let steel = rules.break_ticks(DIAMOND_ORE, Some(1101), &[]).unwrap();
+        assert!(under > steel * 10);
+
+        assert_eq!(rules.required_work(BEDROCK), Err(MiningRejection::Unbreakable));
+        assert_eq!(rules.required_work(6), Err(MiningRejection::UnknownBlock));
+    }
+
+    #[test]
+    fn test_instant_break_is_rejected() {
+        let mut server = server();
+        server.test_set_block((0, 0, 0), STONE);
+
+        // Breaking without digging first
+        server.queue_action(1, PlayerAction::BreakBlock { sequence: 1, block_pos: (0, 0, 0) });
+        let events = server.tick();
+        assert!(events.iter().any(|(_, e)| matches!(
+            e,
+            GameEvent::BlockBreakRejected { reason: MiningRejection::NotDigging, .. }
+        )));
+        assert_eq!(server.get_block((0, 0, 0)), STONE);
+
+        // Breaking half way through
+        server.queue_action(1, PlayerAction::StartBreaking { sequence: 2, block_pos: (0, 0, 0) });
+        server.tick();
+        dig(&mut server, 60);
+        server.queue_action(1, PlayerAction::BreakBlock { sequence: 3, block_pos: (0, 0, 0) });
+        let events = server.tick();
+        assert!(events.iter().any(|(_, e)| matches!(
+            e,
+            GameEvent::BlockBreakRejected { reason: MiningRejection::TooFast, .. }
+        )));
+        assert_eq!(server.get_block((0, 0, 0)), STONE);
+        assert_eq!(server.stats().breaks_rejected, 2);
+    }
+
+    #[test]
+    fn test_dig_breaks_after_required_ticks() {
+        let mut server = server();
+        server.test_set_block((0, 0, 0), STONE);
+        server.equip_tool(1, Some(1102));
+
+        server.queue_action(1, PlayerAction::StartBreaking { sequence: 1, block_pos: (0, 0, 0) });
+        let mut events = server.tick();
+        events.extend(dig(&mut server, 16));
+
+        // Every crack stage was broadcast once
+        let stages: Vec<u8> = events
+            .iter()
+            .filter_map(|(_, e)| match e {
+                GameEvent::MiningProgress { player_id: 1, stage, .. } => Some(*stage),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stages, (0..MINING_STAGES).collect::<Vec<_>>());
+        assert!((server.mining_state(1).unwrap().progress() - 1.0).abs() < f32::EPSILON);
+
+        server.queue_action(1, PlayerAction::BreakBlock { sequence: 2, block_pos: (0, 0, 0) });
+        let events = server.tick();
+        assert!(events.iter().any(|(_, e)| matches!(e, GameEvent::BlockBroken { .. })));
+        assert_eq!(server.get_block((0, 0, 0)), 0);
+        assert!(server.mining_state(1).is_none());
+    }
+
+    #[test]
+    fn test_cancel_and_idle_dig_stop() {
+        let mut server = server();
+        server.test_set_block((0, 0, 0), STONE);
+
+        server.queue_action(1, PlayerAction::StartBreaking { sequence: 1, block_pos: (0, 0, 0) });
+        server.tick();
+        server.queue_action(1, PlayerAction::CancelBreaking { sequence: 2 });
+        let events = server.tick();
+        assert!(events.iter().any(|(_, e)| matches!(e, GameEvent::MiningStopped { player_id: 1, .. })));
+        assert!(server.mining_state(1).is_none());
+
+        // Without continue actions the dig lapses
+        server.queue_action(1, PlayerAction::StartBreaking { sequence: 3, block_pos: (0, 0, 0) });
+        server.tick();
+        let mut stopped = false;
+        for _ in 0..=MINING_IDLE_TIMEOUT_TICKS {
+            stopped |= server
+                .tick()
+                .iter()
+                .any(|(_, e)| matches!(e, GameEvent::MiningStopped { .. }));
+        }
+        assert!(stopped);
+        assert!(server.mining_state(1).is_none());
+    }
+
+    #[test]
+    fn test_dig_stops_when_block_changes() {
+        let mut server = server();
+        server.test_set_block((0, 0, 0), STONE);
+
+        server.queue_action(1, PlayerAction::StartBreaking { sequence: 1, block_pos: (0, 0, 0) });
+        server.tick();
+        server.test_set_block((0, 0, 0), 0);
+        let events = server.tick();
+        assert!(events.iter().any(|(_, e)| matches!(e, GameEvent::MiningStopped { .. })));
+    }
+
+    #[test]
+    fn test_status_effects_expire() {
+        let mut server = server();
+        server.apply_status_effect(1, StatusEffect { duration_ticks: 2, ..haste(1.0) });
+        assert_eq!(server.status_effects(1).map(<[_]>::len), Some(1));
+        server.tick();
+        server.tick();
+        assert_eq!(server.status_effects(1).map(<[_]>::len), Some(0));
+    }
+}
@@ -28,8 +28,10 @@ pub mod events;
 pub mod traits;
 pub mod game_loop;
 pub mod actions;
+pub mod mining;
 
 pub use events::*;
 pub use traits::*;
 pub use game_loop::*;
 pub use actions::*;
+pub use mining::*;
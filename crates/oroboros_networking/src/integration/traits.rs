@@ -331,8 +331,8 @@ impl EconomyAuditor for MockEconomyAuditor {
     ) -> EconomyResponse {
         self.transaction_counter += 1;
         
-        // Mock loot: diamond block (type 1) drops diamond item (type 1)
-        let loot = if block_type == 1 {
+        // Mock loot: diamond ore (block 22) drops diamond item (type 1)
+        let loot = if block_type == 22 {
             vec![LootDrop {
                 item_id: 1, // Diamond
                 quantity: 1,
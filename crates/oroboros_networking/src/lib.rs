@@ -62,6 +62,10 @@ pub mod simulation;
 pub mod transport;
 pub mod interpolation;
 pub mod integration;
+pub mod chat;
+pub mod matchmaking;
+pub mod sharding;
+pub mod spectator;
 
 // Re-exports for convenience
 pub use protocol::{
@@ -73,6 +77,10 @@ pub use client::{GameClient, ClientConfig, ClientState};
 pub use snapshot::{SnapshotBuffer, InterpolationState, SnapshotCompressor};
 pub use prediction::{PredictionBuffer, InputBuffer, ReconciliationResult};
 pub use simulation::{BotSimulation, SimulationConfig, NetworkConditions};
+pub use matchmaking::{LobbyService, MatchmakingConfig, JoinTicket, LocalServerPool};
+pub use sharding::{ZoneServer, ShardMap, ShardConfig, ShardId};
+pub use spectator::{SpectatorService, SpectatorConfig, ReplayCaster, SnapshotSource};
+pub use chat::{ChatService, ChatConfig, ChatError, ChatFilter};
 pub use interpolation::{VisualInterpolator, SnapshotInterpolator, PlayerVisualState, InterpolationMode};
 
 /// Network tick rate for Inferno (updates per second).
@@ -0,0 +1,156 @@
+//! # Lobby Service
+//!
+//! Front door for players: queue, get matched, receive a join ticket.
+
+use std::net::SocketAddr;
+use std::time::Instant;
+use crate::integration::PlayerId;
+use crate::protocol::JoinToken;
+use super::matcher::{Matcher, SessionPlan};
+use super::queue::{MatchQueue, PartyId, QueuedPlayer};
+use super::servers::ServerProvider;
+use super::token::TokenSigner;
+use super::{MatchmakingConfig, MatchmakingError};
+
+/// Everything a client needs to join its match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JoinTicket {
+    /// Player this ticket belongs to.
+    pub player_id: PlayerId,
+    /// Session the player was placed in.
+    pub session_id: u64,
+    /// Game server to connect to.
+    pub server_addr: SocketAddr,
+    /// Token to present in the handshake (`PacketType::Join`).
+    pub token: JoinToken,
+}
+
+/// A session that was formed and assigned a server.
+#[derive(Clone, Debug)]
+pub struct MatchFound {
+    /// Session identifier.
+    pub session_id: u64,
+    /// Game server hosting the session.
+    pub server_addr: SocketAddr,
+    /// Parties in the session.
+    pub parties: Vec<PartyId>,
+    /// One ticket per player.
+    pub tickets: Vec<JoinTicket>,
+}
+
+/// Matchmaking lobby.
+///
+/// Call [`LobbyService::update`] periodically (e.g. once a second); each call
+/// forms as many sessions as the queue allows and assigns them servers.
+pub struct LobbyService<P: ServerProvider> {
+    /// Configuration.
+    config: MatchmakingConfig,
+    /// Waiting parties.
+    queue: MatchQueue,
+    /// Session former.
+    matcher: Matcher,
+    /// Server allocation.
+    servers: P,
+    /// Join token issuer.
+    signer: TokenSigner,
+}
+
+impl<P: ServerProvider> LobbyService<P> {
+    /// Creates a lobby. `key` must match the game servers' `join_key`.
+    #[must_use]
+    pub fn new(config: MatchmakingConfig, key: [u8; 16], servers: P) -> Self {
+        Self {
+            config,
+            queue: MatchQueue::new(),
+            matcher: Matcher::new(1),
+            servers,
+            signer: TokenSigner::new(key),
+        }
+    }
+
+    /// Queues a solo player.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the player is already queued.
+    pub fn enqueue_solo(&mut self, player: QueuedPlayer, now: Instant) -> Result<PartyId, MatchmakingError> {
+        self.enqueue_party(vec![player], now)
+    }
+
+    /// Queues a party. Members are always placed in the same session.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the party is empty, too large, or has a member already queued.
+    pub fn enqueue_party(&mut self, members: Vec<QueuedPlayer>, now: Instant) -> Result<PartyId, MatchmakingError> {
+        let max = self.config.max_party_size.min(self.config.session_size);
+        self.queue.enqueue(members, max, now)
+    }
+
+    /// Leaves the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatchmakingError::UnknownParty`] if the party is not queued.
+    pub fn cancel(&mut self, party_id: PartyId) -> Result<(), MatchmakingError> {
+        self.queue.cancel(party_id).map(|_| ())
+    }
+
+    /// Forms sessions, assigns servers and issues join tickets.
+    ///
+    /// `unix_now` is the current time in Unix seconds, used for token expiry.
+    pub fn update(&mut self, now: Instant, unix_now: u64) -> Vec<MatchFound> {
+        let plans = self.matcher.form_sessions(&self.config, &mut self.queue, now);
+        let mut found = Vec::with_capacity(plans.len());
+        let mut unplaced = Vec::new();
+
+        for plan in plans {
+            match self.servers.assign(&plan) {
+                Ok(endpoint) => found.push(self.issue(&plan, endpoint.addr, unix_now)),
+                Err(e) => {
+                    tracing::warn!("Session {} not placed: {}", plan.session_id, e);
+                    unplaced.extend(plan.tickets);
+                }
+            }
+        }
+
+        if !unplaced.is_empty() {
+            self.queue.restore(unplaced);
+        }
+        found
+    }
+
+    /// Releases a finished session's server.
+    pub fn end_session(&mut self, session_id: u64) {
+        self.servers.release(session_id);
+    }
+
+    /// Returns the queue.
+    #[must_use]
+    pub fn queue(&self) -> &MatchQueue {
+        &self.queue
+    }
+
+    /// Returns the server provider.
+    pub fn servers_mut(&mut self) -> &mut P {
+        &mut self.servers
+    }
+
+    fn issue(&self, plan: &SessionPlan, server_addr: SocketAddr, unix_now: u64) -> MatchFound {
+        let expires_at = unix_now + self.config.token_ttl.as_secs();
+        MatchFound {
+            session_id: plan.session_id,
+            server_addr,
+            parties: plan.tickets.iter().map(|t| t.party_id).collect(),
+            tickets: plan
+                .players()
+                .map(|p| JoinTicket {
+                    player_id: p.player_id,
+                    session_id: plan.session_id,
+                    server_addr,
+                    token: self.signer.issue(plan.session_id, p.player_id, expires_at),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! # Session Matcher
+//!
+//! Groups queued parties into sessions by rating and latency bands.
+//!
+//! ## Algorithm
+//!
+//! ```text
+//! for each ticket, oldest first (the "anchor"):
+//!     candidates = unmatched tickets within the anchor's rating window
+//!                  and latency band, nearest rating first
+//!     greedily add whole parties that still fit
+//!     if the session is exactly full -> emit it
+//! ```
+//!
+//! Rating windows widen the longer a ticket waits, and after
+//! `latency_relax_after` adjacent latency bands become acceptable, so
+//! nobody waits forever in a thin population.
+
+use std::time::Instant;
+use super::queue::{MatchQueue, QueueTicket};
+use super::MatchmakingConfig;
+
+/// A group of parties selected to play together.
+#[derive(Clone, Debug)]
+pub struct SessionPlan {
+    /// Session identifier.
+    pub session_id: u64,
+    /// Parties in the session, in queue order.
+    pub tickets: Vec<QueueTicket>,
+}
+
+impl SessionPlan {
+    /// Iterates all players in the session.
+    pub fn players(&self) -> impl Iterator<Item = &super::QueuedPlayer> {
+        self.tickets.iter().flat_map(|t| t.members.iter())
+    }
+
+    /// Number of players in the session.
+    #[must_use]
+    pub fn player_count(&self) -> usize {
+        self.tickets.iter().map(QueueTicket::size).sum()
+    }
+
+    /// Mean player rating.
+    #[must_use]
+    pub fn mean_rating(&self) -> u32 {
+        let count = self.player_count().max(1) as u64;
+        (self.players().map(|p| u64::from(p.rating)).sum::<u64>() / count) as u32
+    }
+
+    /// Worst player latency.
+    #[must_use]
+    pub fn max_latency_ms(&self) -> u32 {
+        self.tickets.iter().map(|t| t.latency_ms).max().unwrap_or(0)
+    }
+}
+
+/// Forms sessions from a [`MatchQueue`].
+#[derive(Debug)]
+pub struct Matcher {
+    /// Next session ID.
+    next_session_id: u64,
+    /// Candidate index scratch buffer.
+    candidates: Vec<usize>,
+    /// Chosen index scratch buffer.
+    chosen: Vec<usize>,
+}
+
+impl Matcher {
+    /// Creates a matcher. Session IDs start at `first_session_id`.
+    #[must_use]
+    pub fn new(first_session_id: u64) -> Self {
+        Self {
+            next_session_id: first_session_id,
+            candidates: Vec::new(),
+            chosen: Vec::new(),
+        }
+    }
+
+    /// Rating window for a ticket as of `now`.
+    #[must_use]
+    pub fn rating_window(config: &MatchmakingConfig, ticket: &QueueTicket, now: Instant) -> u32 {
+        let widened = config.rating_widen_per_sec as f32 * ticket.waited(now).as_secs_f32();
+        config
+            .rating_window
+            .saturating_add(widened as u32)
+            .min(config.max_rating_window)
+    }
+
+    /// Returns true if two tickets may share a session as of `now`.
+    #[must_use]
+    pub fn compatible(config: &MatchmakingConfig, a: &QueueTicket, b: &QueueTicket, now: Instant) -> bool {
+        let window = Self::rating_window(config, a, now).max(Self::rating_window(config, b, now));
+        if a.rating.abs_diff(b.rating) > window {
+            return false;
+        }
+
+        let band_ms = config.latency_band_ms.max(1);
+        let band_gap = (a.latency_ms / band_ms).abs_diff(b.latency_ms / band_ms);
+        let relaxed = a.waited(now).max(b.waited(now)) >= config.latency_relax_after;
+        band_gap == 0 || (relaxed && band_gap == 1)
+    }
+
+    /// Removes every session that can be formed from `queue`.
+    pub fn form_sessions(
+        &mut self,
+        config: &MatchmakingConfig,
+        queue: &mut MatchQueue,
+        now: Instant,
+    ) -> Vec<SessionPlan> {
+        let mut plans = Vec::new();
+        let mut anchor = 0;
+
+        while anchor < queue.len() {
+            if self.try_fill(config, queue.tickets(), anchor, now) {
+                let session_id = self.next_session_id;
+                self.next_session_id += 1;
+                let tickets = queue.take(&mut self.chosen);
+                plans.push(SessionPlan { session_id, tickets });
+                // Indices shifted; the next-oldest ticket now sits at `anchor`.
+            } else {
+                anchor += 1;
+            }
+        }
+
+        plans
+    }
+
+    /// Tries to fill a session around `anchor`. On success `self.chosen`
+    /// holds the selected ticket indices.
+    fn try_fill(
+        &mut self,
+        config: &MatchmakingConfig,
+        tickets: &[QueueTicket],
+        anchor: usize,
+        now: Instant,
+    ) -> bool {
+        let anchor_ticket = &tickets[anchor];
+        let target = config.session_size;
+
+        self.candidates.clear();
+        self.candidates.extend(
+            (0..tickets.len())
+                .filter(|&i| i != anchor && Self::compatible(config, anchor_ticket, &tickets[i], now)),
+        );
+        self.candidates
+            .sort_by_key(|&i| (tickets[i].rating.abs_diff(anchor_ticket.rating), i));
+
+        self.chosen.clear();
+        self.chosen.push(anchor);
+        let mut filled = anchor_ticket.size();
+
+        for &i in &self.candidates {
+            if filled == target {
+                break;
+            }
+            let ticket = &tickets[i];
+            let fits = filled + ticket.size() <= target;
+            let fits_everyone = self
+                .chosen
+                .iter()
+                .all(|&c| Self::compatible(config, &tickets[c], ticket, now));
+            if fits && fits_everyone {
+                self.chosen.push(i);
+                filled += ticket.size();
+            }
+        }
+
+        filled == target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::matchmaking::QueuedPlayer;
+
+    fn solo(queue: &mut MatchQueue, id: u32, rating: u32, latency_ms: u32, at: Instant) {
+        queue
+            .enqueue(vec![QueuedPlayer { player_id: id, rating, latency_ms }], 4, at)
+            .unwrap();
+    }
+
+    fn config(session_size: usize) -> MatchmakingConfig {
+        MatchmakingConfig { session_size, ..MatchmakingConfig::default() }
+    }
+
+    #[test]
+    fn test_groups_by_rating() {
+        let now = Instant::now();
+        let mut queue = MatchQueue::new();
+        solo(&mut queue, 1, 1000, 30, now);
+        solo(&mut queue, 2, 2000, 30, now);
+        solo(&mut queue, 3, 1020, 30, now);
+        solo(&mut queue, 4, 2030, 30, now);
+
+        let plans = Matcher::new(1).form_sessions(&config(2), &mut queue, now);
+
+        assert_eq!(plans.len(), 2);
+        let ids: Vec<Vec<u32>> = plans
+            .iter()
+            .map(|p| p.players().map(|m| m.player_id).collect())
+            .collect();
+        assert_eq!(ids, vec![vec![1, 3], vec![2, 4]]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_latency_bands_relax_over_time() {
+        let start = Instant::now();
+        let cfg = config(2);
+        let mut queue = MatchQueue::new();
+        solo(&mut queue, 1, 1500, 20, start);
+        solo(&mut queue, 2, 1500, 20 + cfg.latency_band_ms, start);
+
+        let mut matcher = Matcher::new(1);
+        assert!(matcher.form_sessions(&cfg, &mut queue, start).is_empty());
+
+        let later = start + cfg.latency_relax_after;
+        assert_eq!(matcher.form_sessions(&cfg, &mut queue, later).len(), 1);
+    }
+
+    #[test]
+    fn test_rating_window_widens() {
+        let start = Instant::now();
+        let cfg = config(2);
+        let mut queue = MatchQueue::new();
+        solo(&mut queue, 1, 1500, 20, start);
+        solo(&mut queue, 2, 1500 + cfg.rating_window + 50, 20, start);
+
+        let mut matcher = Matcher::new(1);
+        assert!(matcher.form_sessions(&cfg, &mut queue, start).is_empty());
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(matcher.form_sessions(&cfg, &mut queue, later).len(), 1);
+    }
+
+    #[test]
+    fn test_parties_stay_together() {
+        let now = Instant::now();
+        let mut queue = MatchQueue::new();
+        let party = vec![
+            QueuedPlayer { player_id: 10, rating: 1500, latency_ms: 30 },
+            QueuedPlayer { player_id: 11, rating: 1500, latency_ms: 30 },
+            QueuedPlayer { player_id: 12, rating: 1500, latency_ms: 30 },
+        ];
+        queue.enqueue(party, 4, now).unwrap();
+        solo(&mut queue, 1, 1500, 30, now);
+        solo(&mut queue, 2, 1500, 30, now);
+
+        let plans = Matcher::new(1).form_sessions(&config(4), &mut queue, now);
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].player_count(), 4);
+        assert_eq!(plans[0].tickets[0].size(), 3);
+        // The leftover solo player waits for the next round.
+        assert_eq!(queue.player_count(), 1);
+    }
+}
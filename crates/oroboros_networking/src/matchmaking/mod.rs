@@ -0,0 +1,230 @@
+//! # Matchmaking & Lobby
+//!
+//! Places queued players into arena sessions and hands them a server.
+//!
+//! ## Flow
+//!
+//! ```text
+//! CLIENT            LOBBY                         GAME SERVER
+//!   |--- queue ------>|                                |
+//!   |                 |-- match (rating/latency) --    |
+//!   |                 |-- assign server -------------->| host_session(id, players)
+//!   |<-- JoinTicket --|                                |
+//!   |    (addr + signed token)                         |
+//!   |--- Join(token) --------------------------------->| verify MAC, expiry, roster
+//!   |<-- ConnectAck -----------------------------------|
+//! ```
+//!
+//! ## Design
+//!
+//! - Parties are atomic: placed whole or not at all
+//! - Rating windows widen with wait time; latency bands relax after a delay
+//! - Tokens are verified offline with a shared key (no lobby round-trip)
+//! - Server allocation is behind [`ServerProvider`]; [`LocalServerPool`]
+//!   runs real `InfernoServer`s in-process for tests
+
+mod lobby;
+mod matcher;
+mod queue;
+mod servers;
+mod token;
+
+pub use lobby::{JoinTicket, LobbyService, MatchFound};
+pub use matcher::{Matcher, SessionPlan};
+pub use queue::{MatchQueue, PartyId, QueueTicket, QueuedPlayer};
+pub use servers::{LocalServerPool, ServerEndpoint, ServerProvider};
+pub use token::{unix_now, TokenSigner};
+
+use std::time::Duration;
+use thiserror::Error;
+use crate::integration::PlayerId;
+
+/// Matchmaking configuration.
+#[derive(Clone, Debug)]
+pub struct MatchmakingConfig {
+    /// Players per session.
+    pub session_size: usize,
+    /// Largest party accepted (capped at `session_size`).
+    pub max_party_size: usize,
+    /// Initial rating window (± rating points).
+    pub rating_window: u32,
+    /// Rating points the window grows per second of waiting.
+    pub rating_widen_per_sec: u32,
+    /// Upper bound for the rating window.
+    pub max_rating_window: u32,
+    /// Width of a latency band (milliseconds).
+    pub latency_band_ms: u32,
+    /// Wait after which adjacent latency bands may be mixed.
+    pub latency_relax_after: Duration,
+    /// How long a join token stays valid.
+    pub token_ttl: Duration,
+}
+
+impl Default for MatchmakingConfig {
+    fn default() -> Self {
+        Self {
+            session_size: 10,
+            max_party_size: 5,
+            rating_window: 100,
+            rating_widen_per_sec: 10,
+            max_rating_window: 600,
+            latency_band_ms: 50,
+            latency_relax_after: Duration::from_secs(30),
+            token_ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Matchmaking and join-token errors.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchmakingError {
+    /// Party has no members.
+    #[error("party has no members")]
+    EmptyParty,
+    /// Party exceeds the maximum size.
+    #[error("party too large: {size} players, max {max}")]
+    PartyTooLarge {
+        /// Party size.
+        size: usize,
+        /// Maximum allowed.
+        max: usize,
+    },
+    /// Player is already in the queue.
+    #[error("player {0} is already queued")]
+    AlreadyQueued(PlayerId),
+    /// Party is not in the queue.
+    #[error("party {0:?} is not queued")]
+    UnknownParty(PartyId),
+    /// No game server could take the session.
+    #[error("no game server capacity")]
+    NoServerCapacity,
+    /// Join token MAC does not match.
+    #[error("invalid join token")]
+    InvalidToken,
+    /// Join token is past its expiry.
+    #[error("join token expired")]
+    TokenExpired,
+    /// Join token names a session this server does not host.
+    #[error("session {0} is not hosted here")]
+    UnknownSession(u64),
+    /// Player is not on the session roster.
+    #[error("player {0} may not join this session")]
+    NotOnRoster(PlayerId),
+    /// Player's token was already used from another IP.
+    #[error("join token of player {0} is in use from another IP")]
+    TokenInUse(PlayerId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::time::Instant;
+    use crate::protocol::{PacketHeader, PacketSerializer};
+    use crate::server::ServerConfig;
+
+    const KEY: [u8; 16] = *b"lobby-test-key!!";
+
+    fn lobby(max_instances: usize) -> LobbyService<LocalServerPool> {
+        let template = ServerConfig {
+            bind_address: "127.0.0.1:0".parse().unwrap(),
+            join_key: Some(KEY),
+            ..ServerConfig::default()
+        };
+        let config = MatchmakingConfig { session_size: 2, ..MatchmakingConfig::default() };
+        LobbyService::new(config, KEY, LocalServerPool::new(template, 40_000, max_instances, 1))
+    }
+
+    fn join_bytes(ticket: &JoinTicket) -> Vec<u8> {
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_join(&PacketHeader::new(0, 0, 0), &ticket.token));
+        serializer.as_slice().to_vec()
+    }
+
+    #[test]
+    fn test_match_to_handshake() {
+        let mut lobby = lobby(4);
+        let now = Instant::now();
+        let unix = unix_now();
+
+        for id in 1..=2 {
+            lobby
+                .enqueue_solo(QueuedPlayer { player_id: id, rating: 1500, latency_ms: 30 }, now)
+                .unwrap();
+        }
+        let found = lobby.update(now, unix);
+        assert_eq!(found.len(), 1);
+        let matched = &found[0];
+        assert_eq!(matched.tickets.len(), 2);
+        assert_eq!(matched.server_addr.port(), 40_000);
+
+        let server = lobby.servers_mut().server_mut(matched.server_addr).unwrap();
+        let alice: SocketAddr = "10.0.0.1:1000".parse().unwrap();
+        let bob: SocketAddr = "10.0.0.2:1000".parse().unwrap();
+
+        server.receive(alice, &join_bytes(&matched.tickets[0]));
+        assert_eq!(server.client_count(), 1);
+
+        // Replaying Alice's token from another address is refused.
+        server.receive(bob, &join_bytes(&matched.tickets[0]));
+        assert_eq!(server.client_count(), 1);
+
+        server.receive(bob, &join_bytes(&matched.tickets[1]));
+        assert_eq!(server.client_count(), 2);
+
+        // After Alice leaves her token still only works from her IP.
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_disconnect(&PacketHeader::new(0, 0, 0)));
+        server.receive(alice, serializer.as_slice());
+        assert_eq!(server.client_count(), 1);
+        let mallory: SocketAddr = "10.0.0.9:1000".parse().unwrap();
+        server.receive(mallory, &join_bytes(&matched.tickets[0]));
+        assert_eq!(server.client_count(), 1);
+        server.receive(alice, &join_bytes(&matched.tickets[0]));
+        assert_eq!(server.client_count(), 2);
+
+        // Rejoining from a new port replaces her old connection.
+        let alice_new_port: SocketAddr = "10.0.0.1:2000".parse().unwrap();
+        server.receive(alice_new_port, &join_bytes(&matched.tickets[0]));
+        assert_eq!(server.client_count(), 2);
+        assert!(server.state().find_client_by_addr(alice).is_none());
+        assert!(server.state().find_client_by_addr(alice_new_port).is_some());
+    }
+
+    #[test]
+    fn test_plain_connect_refused_when_tokens_required() {
+        let mut lobby = lobby(1);
+        let now = Instant::now();
+        for id in 1..=2 {
+            lobby
+                .enqueue_solo(QueuedPlayer { player_id: id, rating: 1500, latency_ms: 30 }, now)
+                .unwrap();
+        }
+        let found = lobby.update(now, unix_now());
+        let server = lobby.servers_mut().server_mut(found[0].server_addr).unwrap();
+
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_connect(&PacketHeader::new(0, 0, 0)));
+        server.receive("10.0.0.3:1000".parse().unwrap(), serializer.as_slice());
+        assert_eq!(server.client_count(), 0);
+    }
+
+    #[test]
+    fn test_requeue_without_capacity() {
+        let mut lobby = lobby(1);
+        let now = Instant::now();
+        for id in 1..=4 {
+            lobby
+                .enqueue_solo(QueuedPlayer { player_id: id, rating: 1500, latency_ms: 30 }, now)
+                .unwrap();
+        }
+
+        // One instance hosting one session: the second match waits.
+        assert_eq!(lobby.update(now, unix_now()).len(), 1);
+        assert_eq!(lobby.queue().player_count(), 2);
+
+        lobby.end_session(1);
+        assert_eq!(lobby.update(now, unix_now()).len(), 1);
+        assert!(lobby.queue().is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! # Match Queue
+//!
+//! Tickets waiting to be placed into a session.
+//!
+//! A ticket is a party (a solo player is a party of one). Parties are never
+//! split: the matcher places or skips a ticket as a whole.
+
+use std::time::{Duration, Instant};
+use crate::integration::PlayerId;
+use super::MatchmakingError;
+
+/// Identifier for a queued party.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartyId(pub u64);
+
+/// A player entering the queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueuedPlayer {
+    /// Player identifier.
+    pub player_id: PlayerId,
+    /// Skill rating (Elo-style, ~1500 average).
+    pub rating: u32,
+    /// Measured round-trip latency to the server region (milliseconds).
+    pub latency_ms: u32,
+}
+
+/// A party waiting in the queue.
+#[derive(Clone, Debug)]
+pub struct QueueTicket {
+    /// Party identifier.
+    pub party_id: PartyId,
+    /// Party members (never split).
+    pub members: Vec<QueuedPlayer>,
+    /// Mean member rating.
+    pub rating: u32,
+    /// Worst member latency; the party plays at its slowest member's ping.
+    pub latency_ms: u32,
+    /// When the party entered the queue.
+    pub enqueued_at: Instant,
+}
+
+impl QueueTicket {
+    /// Number of players in the party.
+    #[inline]
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Time spent waiting as of `now`.
+    #[inline]
+    #[must_use]
+    pub fn waited(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.enqueued_at)
+    }
+}
+
+/// Queue of parties, ordered by arrival.
+#[derive(Debug, Default)]
+pub struct MatchQueue {
+    /// Tickets, oldest first.
+    tickets: Vec<QueueTicket>,
+    /// Next party ID.
+    next_party_id: u64,
+}
+
+impl MatchQueue {
+    /// Creates an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a party to the queue.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the party is empty, larger than `max_party_size`, or any
+    /// member is already queued.
+    pub fn enqueue(
+        &mut self,
+        members: Vec<QueuedPlayer>,
+        max_party_size: usize,
+        now: Instant,
+    ) -> Result<PartyId, MatchmakingError> {
+        if members.is_empty() {
+            return Err(MatchmakingError::EmptyParty);
+        }
+        if members.len() > max_party_size {
+            return Err(MatchmakingError::PartyTooLarge {
+                size: members.len(),
+                max: max_party_size,
+            });
+        }
+        if let Some(dup) = members.iter().find(|m| self.contains_player(m.player_id)) {
+            return Err(MatchmakingError::AlreadyQueued(dup.player_id));
+        }
+
+        let party_id = PartyId(self.next_party_id);
+        self.next_party_id += 1;
+
+        let rating_sum: u64 = members.iter().map(|m| u64::from(m.rating)).sum();
+        let rating = (rating_sum / members.len() as u64) as u32;
+        let latency_ms = members.iter().map(|m| m.latency_ms).max().unwrap_or(0);
+
+        self.tickets.push(QueueTicket {
+            party_id,
+            members,
+            rating,
+            latency_ms,
+            enqueued_at: now,
+        });
+
+        Ok(party_id)
+    }
+
+    /// Removes a party from the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatchmakingError::UnknownParty`] if the party is not queued.
+    pub fn cancel(&mut self, party_id: PartyId) -> Result<QueueTicket, MatchmakingError> {
+        let index = self
+            .tickets
+            .iter()
+            .position(|t| t.party_id == party_id)
+            .ok_or(MatchmakingError::UnknownParty(party_id))?;
+        Ok(self.tickets.remove(index))
+    }
+
+    /// Returns true if the player is in any queued party.
+    #[must_use]
+    pub fn contains_player(&self, player_id: PlayerId) -> bool {
+        self.tickets
+            .iter()
+            .any(|t| t.members.iter().any(|m| m.player_id == player_id))
+    }
+
+    /// Number of queued parties.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tickets.len()
+    }
+
+    /// Returns true if nothing is queued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tickets.is_empty()
+    }
+
+    /// Number of queued players.
+    #[must_use]
+    pub fn player_count(&self) -> usize {
+        self.tickets.iter().map(QueueTicket::size).sum()
+    }
+
+    /// Tickets, oldest first.
+    #[must_use]
+    pub fn tickets(&self) -> &[QueueTicket] {
+        &self.tickets
+    }
+
+    /// Removes and returns the tickets at `indices` (any order).
+    pub(crate) fn take(&mut self, indices: &mut [usize]) -> Vec<QueueTicket> {
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        let mut taken: Vec<QueueTicket> = indices.iter().map(|&i| self.tickets.remove(i)).collect();
+        taken.reverse();
+        taken
+    }
+
+    /// Puts tickets back, keeping arrival order (used when no server is free).
+    pub(crate) fn restore(&mut self, tickets: Vec<QueueTicket>) {
+        self.tickets.extend(tickets);
+        self.tickets.sort_by_key(|t| t.enqueued_at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(player_id: PlayerId, rating: u32, latency_ms: u32) -> QueuedPlayer {
+        QueuedPlayer { player_id, rating, latency_ms }
+    }
+
+    #[test]
+    fn test_party_ticket_aggregates() {
+        let mut queue = MatchQueue::new();
+        let now = Instant::now();
+
+        let party = queue
+            .enqueue(vec![player(1, 1400, 20), player(2, 1600, 80)], 4, now)
+            .unwrap();
+
+        let ticket = &queue.tickets()[0];
+        assert_eq!(ticket.party_id, party);
+        assert_eq!(ticket.rating, 1500);
+        assert_eq!(ticket.latency_ms, 80);
+        assert_eq!(queue.player_count(), 2);
+    }
+
+    #[test]
+    fn test_enqueue_validation() {
+        let mut queue = MatchQueue::new();
+        let now = Instant::now();
+
+        assert_eq!(queue.enqueue(vec![], 4, now), Err(MatchmakingError::EmptyParty));
+        assert!(matches!(
+            queue.enqueue(vec![player(1, 1500, 10); 5], 4, now),
+            Err(MatchmakingError::PartyTooLarge { size: 5, max: 4 })
+        ));
+
+        let party = queue.enqueue(vec![player(1, 1500, 10)], 4, now).unwrap();
+        assert_eq!(
+            queue.enqueue(vec![player(1, 1500, 10)], 4, now),
+            Err(MatchmakingError::AlreadyQueued(1))
+        );
+
+        queue.cancel(party).unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(queue.cancel(party).unwrap_err(), MatchmakingError::UnknownParty(party));
+    }
+}
@@ -0,0 +1,131 @@
+//! # Game Server Allocation
+//!
+//! How the lobby finds a game server for a freshly formed session.
+//!
+//! Production deployments implement [`ServerProvider`] against their fleet
+//! manager (spin up a container, pick a warm instance). [`LocalServerPool`]
+//! is an in-process stand-in that runs [`InfernoServer`]s directly, for
+//! tests and single-machine setups.
+
+use std::net::SocketAddr;
+use crate::server::{InfernoServer, ServerConfig};
+use super::matcher::SessionPlan;
+use super::MatchmakingError;
+
+/// Where a session is hosted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ServerEndpoint {
+    /// Address clients connect to.
+    pub addr: SocketAddr,
+}
+
+/// Assigns game servers to sessions.
+pub trait ServerProvider {
+    /// Reserves a server for `plan` and tells it which players to expect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatchmakingError::NoServerCapacity`] when nothing is free;
+    /// the lobby re-queues the session's parties and retries next round.
+    fn assign(&mut self, plan: &SessionPlan) -> Result<ServerEndpoint, MatchmakingError>;
+
+    /// Releases the server reserved for `session_id`.
+    fn release(&mut self, session_id: u64);
+}
+
+/// A locally running game server instance.
+struct LocalInstance {
+    /// The server.
+    server: InfernoServer,
+    /// Sessions currently hosted.
+    sessions: Vec<u64>,
+}
+
+/// In-process pool of [`InfernoServer`]s.
+///
+/// Servers are started lazily on consecutive ports as sessions need them
+/// and reused once their sessions are released.
+pub struct LocalServerPool {
+    /// Running instances.
+    instances: Vec<LocalInstance>,
+    /// Template config for new instances (port is overridden).
+    template: ServerConfig,
+    /// First port to hand out.
+    base_port: u16,
+    /// Maximum number of instances.
+    max_instances: usize,
+    /// Sessions each instance may host at once.
+    sessions_per_instance: usize,
+}
+
+impl LocalServerPool {
+    /// Creates a pool. `template.join_key` should match the lobby's key.
+    #[must_use]
+    pub fn new(template: ServerConfig, base_port: u16, max_instances: usize, sessions_per_instance: usize) -> Self {
+        Self {
+            instances: Vec::new(),
+            template,
+            base_port,
+            max_instances,
+            sessions_per_instance: sessions_per_instance.max(1),
+        }
+    }
+
+    /// Number of running instances.
+    #[must_use]
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns the server listening on `addr`.
+    pub fn server_mut(&mut self, addr: SocketAddr) -> Option<&mut InfernoServer> {
+        self.instances
+            .iter_mut()
+            .find(|i| i.server.config().bind_address == addr)
+            .map(|i| &mut i.server)
+    }
+
+    /// Spins up a new instance if the pool has room.
+    fn spawn(&mut self) -> Option<usize> {
+        if self.instances.len() >= self.max_instances {
+            return None;
+        }
+        let port = self.base_port.checked_add(self.instances.len() as u16)?;
+        let mut config = self.template.clone();
+        config.port = port;
+        config.bind_address = SocketAddr::new(self.template.bind_address.ip(), port);
+
+        self.instances.push(LocalInstance {
+            server: InfernoServer::new(config),
+            sessions: Vec::new(),
+        });
+        Some(self.instances.len() - 1)
+    }
+}
+
+impl ServerProvider for LocalServerPool {
+    fn assign(&mut self, plan: &SessionPlan) -> Result<ServerEndpoint, MatchmakingError> {
+        let index = self
+            .instances
+            .iter()
+            .position(|i| i.sessions.len() < self.sessions_per_instance)
+            .or_else(|| self.spawn())
+            .ok_or(MatchmakingError::NoServerCapacity)?;
+
+        let instance = &mut self.instances[index];
+        let players: Vec<_> = plan.players().map(|p| p.player_id).collect();
+        instance.server.host_session(plan.session_id, &players);
+        instance.sessions.push(plan.session_id);
+
+        Ok(ServerEndpoint { addr: instance.server.config().bind_address })
+    }
+
+    fn release(&mut self, session_id: u64) {
+        for instance in &mut self.instances {
+            if let Some(pos) = instance.sessions.iter().position(|&s| s == session_id) {
+                instance.sessions.swap_remove(pos);
+                instance.server.end_session(session_id);
+            }
+        }
+    }
+}
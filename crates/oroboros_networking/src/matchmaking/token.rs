@@ -0,0 +1,121 @@
+//! # Join Tokens
+//!
+//! Signed tickets proving the lobby placed a player into a session.
+//!
+//! The lobby and the game servers share a 128-bit key. Tokens carry a
+//! SipHash-2-4 (128-bit) MAC over their fields, so any server holding the
+//! key can validate a handshake offline.
+
+use siphasher::sip128::{Hasher128, SipHasher24};
+use std::hash::Hasher;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::integration::PlayerId;
+use crate::protocol::JoinToken;
+use super::MatchmakingError;
+
+/// Issues and verifies join tokens.
+#[derive(Clone)]
+pub struct TokenSigner {
+    /// First key half.
+    k0: u64,
+    /// Second key half.
+    k1: u64,
+}
+
+impl TokenSigner {
+    /// Creates a signer from a shared 128-bit key.
+    #[must_use]
+    pub fn new(key: [u8; 16]) -> Self {
+        let mut k0 = [0u8; 8];
+        let mut k1 = [0u8; 8];
+        k0.copy_from_slice(&key[..8]);
+        k1.copy_from_slice(&key[8..]);
+        Self {
+            k0: u64::from_le_bytes(k0),
+            k1: u64::from_le_bytes(k1),
+        }
+    }
+
+    /// Issues a token for `player_id` in `session_id`, valid until `expires_at`
+    /// (seconds since the Unix epoch).
+    #[must_use]
+    pub fn issue(&self, session_id: u64, player_id: PlayerId, expires_at: u64) -> JoinToken {
+        let mut token = JoinToken {
+            session_id,
+            expires_at,
+            player_id,
+            _padding: 0,
+            mac: [0; 16],
+        };
+        token.mac = self.mac(&token);
+        token
+    }
+
+    /// Checks the token's signature and expiry against `now` (Unix seconds).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatchmakingError::InvalidToken`] if the MAC does not match and
+    /// [`MatchmakingError::TokenExpired`] if `now` is past the expiry.
+    pub fn verify(&self, token: &JoinToken, now: u64) -> Result<(), MatchmakingError> {
+        let expected = self.mac(token);
+        // Compare without early exit so timing does not leak the MAC prefix.
+        let diff = expected.iter().zip(&token.mac).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff != 0 {
+            return Err(MatchmakingError::InvalidToken);
+        }
+        if now > token.expires_at {
+            return Err(MatchmakingError::TokenExpired);
+        }
+        Ok(())
+    }
+
+    fn mac(&self, token: &JoinToken) -> [u8; 16] {
+        let mut hasher = SipHasher24::new_with_keys(self.k0, self.k1);
+        hasher.write_u64(token.session_id);
+        hasher.write_u64(token.expires_at);
+        hasher.write_u32(token.player_id);
+        hasher.finish128().as_bytes()
+    }
+}
+
+impl std::fmt::Debug for TokenSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the key.
+        f.debug_struct("TokenSigner").finish_non_exhaustive()
+    }
+}
+
+/// Current time in seconds since the Unix epoch.
+#[must_use]
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_and_verify() {
+        let signer = TokenSigner::new([7; 16]);
+        let token = signer.issue(42, 9, 1_000);
+
+        assert!(signer.verify(&token, 999).is_ok());
+        assert_eq!(signer.verify(&token, 1_001), Err(MatchmakingError::TokenExpired));
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let signer = TokenSigner::new([7; 16]);
+        let mut token = signer.issue(42, 9, 1_000);
+        token.player_id = 10;
+        assert_eq!(signer.verify(&token, 0), Err(MatchmakingError::InvalidToken));
+
+        let other = TokenSigner::new([8; 16]);
+        let token = signer.issue(42, 9, 1_000);
+        assert_eq!(other.verify(&token, 0), Err(MatchmakingError::InvalidToken));
+    }
+}
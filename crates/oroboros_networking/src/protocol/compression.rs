@@ -7,7 +7,11 @@
 //! 1. **Delta Compression**: Only send what changed since last snapshot
 //! 2. **Bit Packing**: Pack values into minimum required bits
 //! 3. **Quantization**: Reduce precision where acceptable
+//!
+//! The decoding halves ([`DeltaDecompressor`], [`BitReader`]) take
+//! untrusted input and report problems as [`DecodeError`]s.
 
+use super::error::DecodeError;
 use super::packets::{EntityState, WorldSnapshot, DeltaSnapshot};
 
 /// Threshold for position change to be considered "changed" (squared distance).
@@ -166,6 +170,99 @@ impl Default for DeltaCompressor {
     }
 }
 
+/// Delta decompressor for world snapshots.
+///
+/// Holds the last full or rebuilt snapshot and applies deltas to it.
+pub struct DeltaDecompressor {
+    /// Snapshot deltas are applied to.
+    base: WorldSnapshot,
+    /// Whether `base` is valid.
+    has_base: bool,
+}
+
+impl DeltaDecompressor {
+    /// Creates a new decompressor.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            base: WorldSnapshot::empty(0),
+            has_base: false,
+        }
+    }
+
+    /// Resets the decompressor state.
+    pub fn reset(&mut self) {
+        self.has_base = false;
+    }
+
+    /// Stores a full snapshot as the new base.
+    pub fn set_base(&mut self, snapshot: &WorldSnapshot) {
+        self.base = *snapshot;
+        self.has_base = true;
+    }
+
+    /// Returns the current base snapshot.
+    #[must_use]
+    pub fn base(&self) -> Option<&WorldSnapshot> {
+        self.has_base.then_some(&self.base)
+    }
+
+    /// Applies a delta to the base and returns the rebuilt snapshot, which
+    /// becomes the new base.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::BaseMismatch`] if the delta is relative to a
+    /// different tick, and [`DecodeError::TooManyEntities`] if its counts
+    /// or the rebuilt snapshot overflow. The base is unchanged on error.
+    pub fn decompress(&mut self, delta: &DeltaSnapshot) -> Result<&WorldSnapshot, DecodeError> {
+        if !self.has_base || self.base.tick != delta.base_tick {
+            return Err(DecodeError::BaseMismatch {
+                expected: delta.base_tick,
+                actual: self.has_base.then_some(self.base.tick),
+            });
+        }
+        let changed_count = delta.changed_count as usize;
+        let removed_count = delta.removed_count as usize;
+        if changed_count > DeltaSnapshot::MAX_CHANGES {
+            return Err(DecodeError::TooManyEntities { count: changed_count, max: DeltaSnapshot::MAX_CHANGES });
+        }
+        if removed_count > DeltaSnapshot::MAX_REMOVED {
+            return Err(DecodeError::TooManyEntities { count: removed_count, max: DeltaSnapshot::MAX_REMOVED });
+        }
+
+        let removed = &delta.removed[..removed_count];
+        let mut next = WorldSnapshot::empty(delta.tick);
+        next.dragon = self.base.dragon;
+        for entity in self.base.entities() {
+            if !removed.contains(&entity.entity_id) {
+                next.add_entity(*entity);
+            }
+        }
+
+        for changed in &delta.changed[..changed_count] {
+            let count = next.entity_count as usize;
+            if let Some(slot) = next.entities[..count].iter_mut().find(|e| e.entity_id == changed.entity_id) {
+                *slot = *changed;
+            } else if !next.add_entity(*changed) {
+                return Err(DecodeError::TooManyEntities {
+                    count: count + 1,
+                    max: WorldSnapshot::MAX_ENTITIES,
+                });
+            }
+        }
+
+        self.base = next;
+        Ok(&self.base)
+    }
+}
+
+impl Default for DeltaDecompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Bit packer for maximum compression.
 ///
 /// Packs values using only the bits required.
@@ -204,20 +301,23 @@ impl BitPacker {
 
     /// Writes bits to the buffer.
     ///
+    /// Returns false if `bits` is outside 1-32 or the buffer is full.
+    ///
     /// # Arguments
     ///
     /// * `value` - The value to write
     /// * `bits` - Number of bits to write (1-32)
     pub fn write_bits(&mut self, value: u32, bits: u8) -> bool {
-        debug_assert!(bits <= 32 && bits > 0);
-        
+        if bits == 0 || bits > 32 {
+            return false;
+        }
+
         let required_bytes = (self.bit_position + bits as usize + 7) / 8;
         if required_bytes > self.buffer.len() {
             return false;
         }
 
-        let mask = if bits == 32 { u32::MAX } else { (1u32 << bits) - 1 };
-        let value = value & mask;
+        let value = value & bit_mask(bits);
 
         for i in 0..bits as usize {
             let bit = (value >> i) & 1;
@@ -249,15 +349,18 @@ impl BitPacker {
     /// * `max` - Maximum expected value
     /// * `bits` - Number of bits to use
     pub fn write_quantized_float(&mut self, value: f32, min: f32, max: f32, bits: u8) -> bool {
+        if bits == 0 || bits > 32 {
+            return false;
+        }
         let range = max - min;
-        if range <= 0.0 {
+        if range.is_nan() || range <= 0.0 {
             return self.write_bits(0, bits);
         }
 
+        // NaN clamps to NaN and then casts to 0.
         let normalized = ((value - min) / range).clamp(0.0, 1.0);
-        let max_int = (1u32 << bits) - 1;
-        let quantized = (normalized * max_int as f32) as u32;
-        
+        let quantized = (f64::from(normalized) * f64::from(bit_mask(bits))) as u32;
+
         self.write_bits(quantized, bits)
     }
 }
@@ -268,6 +371,85 @@ impl Default for BitPacker {
     }
 }
 
+/// Bit reader - the inverse of [`BitPacker`].
+///
+/// Reads from an untrusted buffer; running past the end is an error.
+pub struct BitReader<'a> {
+    buffer: &'a [u8],
+    bit_position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a reader over packed bytes.
+    #[must_use]
+    pub const fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer, bit_position: 0 }
+    }
+
+    /// Returns the number of unread bits.
+    #[must_use]
+    pub const fn remaining_bits(&self) -> usize {
+        self.buffer.len() * 8 - self.bit_position
+    }
+
+    /// Reads `bits` bits (1-32) as written by [`BitPacker::write_bits`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::InvalidBitWidth`] for widths outside 1-32 and
+    /// [`DecodeError::OutOfBits`] past the end of the buffer.
+    pub fn read_bits(&mut self, bits: u8) -> Result<u32, DecodeError> {
+        if bits == 0 || bits > 32 {
+            return Err(DecodeError::InvalidBitWidth(bits));
+        }
+        let remaining = self.remaining_bits();
+        if bits as usize > remaining {
+            return Err(DecodeError::OutOfBits { needed: bits, remaining });
+        }
+
+        let mut value = 0u32;
+        for i in 0..bits {
+            let byte = self.buffer[self.bit_position / 8];
+            let bit = (byte >> (self.bit_position % 8)) & 1;
+            value |= u32::from(bit) << i;
+            self.bit_position += 1;
+        }
+        Ok(value)
+    }
+
+    /// Reads a boolean (1 bit).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::OutOfBits`] past the end of the buffer.
+    #[inline]
+    pub fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        self.read_bits(1).map(|bit| bit == 1)
+    }
+
+    /// Reads a float written by [`BitPacker::write_quantized_float`] with
+    /// the same range and width.
+    ///
+    /// # Errors
+    ///
+    /// As for [`read_bits`](Self::read_bits).
+    pub fn read_quantized_float(&mut self, min: f32, max: f32, bits: u8) -> Result<f32, DecodeError> {
+        let quantized = self.read_bits(bits)?;
+        let range = max - min;
+        if range.is_nan() || range <= 0.0 {
+            return Ok(min);
+        }
+        let normalized = f64::from(quantized) / f64::from(bit_mask(bits));
+        Ok(min + (normalized * f64::from(range)) as f32)
+    }
+}
+
+/// Mask of the low `bits` bits (1-32).
+#[inline]
+const fn bit_mask(bits: u8) -> u32 {
+    u32::MAX >> (32 - bits as u32)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -316,6 +498,59 @@ mod tests {
         assert!(packer.byte_len() == 1);
     }
 
+    #[test]
+    fn test_bit_reader_roundtrip() {
+        let mut packer = BitPacker::new();
+        assert!(packer.write_bits(0b101, 3));
+        assert!(packer.write_bits(u32::MAX, 32));
+        assert!(packer.write_bool(true));
+        assert!(packer.write_quantized_float(42.5, -100.0, 100.0, 16));
+        assert!(!packer.write_bits(1, 0));
+        assert!(!packer.write_bits(1, 33));
+
+        let mut reader = BitReader::new(packer.as_slice());
+        assert_eq!(reader.read_bits(3), Ok(0b101));
+        assert_eq!(reader.read_bits(32), Ok(u32::MAX));
+        assert_eq!(reader.read_bool(), Ok(true));
+        let value = reader.read_quantized_float(-100.0, 100.0, 16).unwrap();
+        assert!((value - 42.5).abs() < 0.01);
+
+        assert_eq!(reader.read_bits(0), Err(DecodeError::InvalidBitWidth(0)));
+        assert_eq!(reader.read_bits(8), Err(DecodeError::OutOfBits { needed: 8, remaining: 4 }));
+    }
+
+    #[test]
+    fn test_delta_decompression() {
+        let mut compressor = DeltaCompressor::new();
+        let mut decompressor = DeltaDecompressor::new();
+
+        let mut snap1 = WorldSnapshot::empty(1);
+        for id in 1..=3 {
+            snap1.add_entity(EntityState { entity_id: id, ..Default::default() });
+        }
+        assert!(compressor.compress(&snap1).is_none());
+        decompressor.set_base(&snap1);
+
+        // Entity 2 moves, 3 leaves, 4 arrives.
+        let mut snap2 = WorldSnapshot::empty(2);
+        snap2.add_entity(EntityState { entity_id: 1, ..Default::default() });
+        snap2.add_entity(EntityState { entity_id: 2, pos_x: 5.0, ..Default::default() });
+        snap2.add_entity(EntityState { entity_id: 4, ..Default::default() });
+        let delta = compressor.compress(&snap2).unwrap();
+
+        let rebuilt = decompressor.decompress(&delta).unwrap();
+        assert_eq!(rebuilt.tick, 2);
+        let ids: Vec<u32> = rebuilt.entities().iter().map(|e| e.entity_id).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+        assert_eq!(rebuilt.entities()[1].pos_x, 5.0);
+
+        // Applying the same delta twice names a base we no longer hold.
+        assert_eq!(
+            decompressor.decompress(&delta).unwrap_err(),
+            DecodeError::BaseMismatch { expected: 1, actual: Some(2) }
+        );
+    }
+
     #[test]
     fn test_quantized_float() {
         let mut packer = BitPacker::new();
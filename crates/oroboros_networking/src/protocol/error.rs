@@ -0,0 +1,68 @@
+//! # Decode Errors
+//!
+//! Everything that can be wrong with bytes from the network.
+//!
+//! Decoders never panic or read out of bounds on malformed input; they
+//! return one of these instead so callers can log, count and drop.
+
+use thiserror::Error;
+
+/// Why a packet, delta or bit stream could not be decoded.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer ended before a field.
+    #[error("truncated: needed {needed} bytes, {remaining} left")]
+    Truncated {
+        /// Bytes the next field needs.
+        needed: usize,
+        /// Bytes left in the buffer.
+        remaining: usize,
+    },
+    /// The packet type byte is not a known [`PacketType`](super::PacketType).
+    #[error("unknown packet type {0}")]
+    UnknownPacketType(u8),
+    /// An entity list is longer than its fixed-size array.
+    #[error("{count} entities exceeds the limit of {max}")]
+    TooManyEntities {
+        /// Count claimed by the packet.
+        count: usize,
+        /// Capacity of the receiving array.
+        max: usize,
+    },
+    /// A chat message names an unknown channel.
+    #[error("unknown chat channel {0}")]
+    InvalidChatChannel(u8),
+    /// A chat message claims more text than it can hold.
+    #[error("chat text length {0} exceeds the limit")]
+    ChatTooLong(u16),
+    /// A spectate request names an unknown camera mode.
+    #[error("unknown camera mode {0}")]
+    InvalidCameraMode(u8),
+    /// A delta's base tick is after the tick it produces.
+    #[error("delta base tick {base_tick} is after its tick {tick}")]
+    InvalidDeltaBase {
+        /// Tick the delta produces.
+        tick: u32,
+        /// Tick the delta is relative to.
+        base_tick: u32,
+    },
+    /// A delta does not apply to the snapshot the decoder holds.
+    #[error("delta is relative to tick {expected}, decoder has {actual:?}")]
+    BaseMismatch {
+        /// Base tick named by the delta.
+        expected: u32,
+        /// Tick of the decoder's snapshot, if it has one.
+        actual: Option<u32>,
+    },
+    /// A bit width outside `1..=32`.
+    #[error("bit width {0} outside 1..=32")]
+    InvalidBitWidth(u8),
+    /// The bit stream ended before a value.
+    #[error("bit stream ended: needed {needed} bits, {remaining} left")]
+    OutOfBits {
+        /// Bits the next value needs.
+        needed: u8,
+        /// Bits left in the stream.
+        remaining: usize,
+    },
+}
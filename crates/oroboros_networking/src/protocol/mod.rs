@@ -21,15 +21,18 @@
 //! - Delta compression for world state
 //! - Reliable delivery for critical packets only
 
+mod error;
 mod packets;
 mod serialization;
 mod compression;
 
+pub use error::DecodeError;
 pub use packets::{
     Packet, PacketType, PacketHeader, PlayerInput, WorldSnapshot, 
     DeltaSnapshot, EntityState, DragonState, HitReport, ShotFired,
+    ChatChannel, ChatMessage, JoinToken, ShardRedirect, CameraMode, SpectateRequest,
 };
 pub use serialization::{
     SequenceNumber, AckBitfield, PacketSerializer, PacketDeserializer,
 };
-pub use compression::{DeltaCompressor, BitPacker};
+pub use compression::{DeltaCompressor, DeltaDecompressor, BitPacker, BitReader};
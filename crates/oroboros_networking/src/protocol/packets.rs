@@ -9,6 +9,7 @@
 //! - Zero-copy deserialization
 //! - Cache-friendly iteration
 
+use std::net::{IpAddr, SocketAddr};
 use bytemuck::{Pod, Zeroable};
 use oroboros_core::{Position, Velocity};
 
@@ -61,6 +62,16 @@ pub enum PacketType {
     Heartbeat = 7,
     /// Bidirectional: Disconnect notification.
     Disconnect = 8,
+    /// Bidirectional: Chat message (reliable channel).
+    Chat = 9,
+    /// Client -> Server: Connection request carrying a matchmaking join token.
+    Join = 10,
+    /// Server -> Client: Continue on another zone shard.
+    Redirect = 11,
+    /// Client -> Server: First packet to a shard after a redirect.
+    Resume = 12,
+    /// Client -> Server: Watch as a spectator, or move the spectator camera.
+    Spectate = 13,
 }
 
 /// Player input packet - Client -> Server.
@@ -469,6 +480,312 @@ impl HitReport {
     pub const SIZE: usize = 12;
 }
 
+/// Chat channel a message is routed through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ChatChannel {
+    /// Everyone on the server.
+    Global = 0,
+    /// Players within hearing distance of the sender.
+    Proximity = 1,
+    /// Members of the sender's party.
+    Party = 2,
+    /// A single recipient (`ChatMessage::target_id`).
+    Whisper = 3,
+    /// Server-originated notices (never accepted from clients).
+    System = 4,
+}
+
+impl ChatChannel {
+    /// Decodes a channel from its wire value.
+    #[inline]
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Global),
+            1 => Some(Self::Proximity),
+            2 => Some(Self::Party),
+            3 => Some(Self::Whisper),
+            4 => Some(Self::System),
+            _ => None,
+        }
+    }
+}
+
+/// Chat message - bidirectional.
+///
+/// Client -> Server: `sender_id` is ignored (the server stamps it from the
+/// connection), `target_id` is the whisper recipient's connection ID.
+/// Server -> Client: `sender_id` is the originating connection ID.
+///
+/// Text is UTF-8 in a fixed buffer so the packet stays `Pod`.
+///
+/// Size: 272 bytes
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct ChatMessage {
+    /// Client-assigned message counter (used to drop reliable resends).
+    pub message_id: u32,
+    /// Sender connection ID.
+    pub sender_id: u32,
+    /// Whisper target connection ID (0 for other channels).
+    pub target_id: u32,
+    /// Channel (`ChatChannel` as u8).
+    pub channel: u8,
+    /// Padding for alignment.
+    pub _padding: u8,
+    /// Number of valid bytes in `text`.
+    pub len: u16,
+    /// UTF-8 text bytes.
+    pub text: [u8; 256],
+}
+
+impl ChatMessage {
+    /// Size in bytes.
+    pub const SIZE: usize = 272;
+
+    /// Maximum UTF-8 bytes of text per message.
+    pub const MAX_TEXT_BYTES: usize = 256;
+
+    /// Creates a chat message, truncating `text` to the last full character
+    /// that fits in `MAX_TEXT_BYTES`.
+    #[must_use]
+    pub fn new(message_id: u32, channel: ChatChannel, target_id: u32, text: &str) -> Self {
+        let mut end = text.len().min(Self::MAX_TEXT_BYTES);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let mut buf = [0u8; Self::MAX_TEXT_BYTES];
+        buf[..end].copy_from_slice(&text.as_bytes()[..end]);
+
+        Self {
+            message_id,
+            sender_id: 0,
+            target_id,
+            channel: channel as u8,
+            _padding: 0,
+            len: end as u16,
+            text: buf,
+        }
+    }
+
+    /// Returns the decoded channel, or None for an unknown wire value.
+    #[inline]
+    #[must_use]
+    pub const fn channel(&self) -> Option<ChatChannel> {
+        ChatChannel::from_u8(self.channel)
+    }
+
+    /// Returns the valid text bytes.
+    #[inline]
+    #[must_use]
+    pub fn text_bytes(&self) -> &[u8] {
+        &self.text[..(self.len as usize).min(Self::MAX_TEXT_BYTES)]
+    }
+
+    /// Returns the valid text bytes mutably (for in-place filtering).
+    #[inline]
+    pub fn text_bytes_mut(&mut self) -> &mut [u8] {
+        let len = (self.len as usize).min(Self::MAX_TEXT_BYTES);
+        &mut self.text[..len]
+    }
+
+    /// Returns the text as a string, or None if it is not valid UTF-8.
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(self.text_bytes()).ok()
+    }
+}
+
+impl Default for ChatMessage {
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+/// Matchmaking join token - Client -> Server during handshake.
+///
+/// Issued by the lobby service and signed with a key shared with the game
+/// servers, so a server can admit a player without calling back to the lobby.
+///
+/// Size: 40 bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct JoinToken {
+    /// Session the player was matched into.
+    pub session_id: u64,
+    /// Expiry as seconds since the Unix epoch.
+    pub expires_at: u64,
+    /// Player the token was issued to.
+    pub player_id: u32,
+    /// Padding for alignment.
+    pub _padding: u32,
+    /// Message authentication code over the fields above.
+    pub mac: [u8; 16],
+}
+
+impl JoinToken {
+    /// Size in bytes.
+    pub const SIZE: usize = 40;
+}
+
+/// Shard redirect - Server -> Client after a zone handoff.
+///
+/// Tells the client which shard now simulates its player. The session
+/// carries on: the client keeps its state and sequence numbers, points its
+/// socket at `addr()` and sends `PacketType::Resume` with `token`.
+///
+/// Size: 32 bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ShardRedirect {
+    /// One-time token binding the client to its handed-off slot.
+    pub token: u64,
+    /// Target shard IP (IPv4 in the first 4 bytes unless `is_ipv6`).
+    pub ip: [u8; 16],
+    /// Target shard UDP port.
+    pub port: u16,
+    /// 1 if `ip` is an IPv6 address.
+    pub is_ipv6: u8,
+    /// Padding for alignment.
+    pub _padding: u8,
+    /// Player entity ID (unchanged across the handoff).
+    pub entity_id: u32,
+}
+
+impl ShardRedirect {
+    /// Size in bytes.
+    pub const SIZE: usize = 32;
+
+    /// Creates a redirect to `addr`.
+    #[must_use]
+    pub fn new(token: u64, addr: SocketAddr, entity_id: u32) -> Self {
+        let mut ip = [0u8; 16];
+        let is_ipv6 = match addr.ip() {
+            IpAddr::V4(v4) => {
+                ip[..4].copy_from_slice(&v4.octets());
+                0
+            }
+            IpAddr::V6(v6) => {
+                ip.copy_from_slice(&v6.octets());
+                1
+            }
+        };
+        Self {
+            token,
+            ip,
+            port: addr.port(),
+            is_ipv6,
+            _padding: 0,
+            entity_id,
+        }
+    }
+
+    /// Returns the target shard address.
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        let ip = if self.is_ipv6 == 0 {
+            IpAddr::from([self.ip[0], self.ip[1], self.ip[2], self.ip[3]])
+        } else {
+            IpAddr::from(self.ip)
+        };
+        SocketAddr::new(ip, self.port)
+    }
+}
+
+/// Spectator camera mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CameraMode {
+    /// Camera moved by the spectator.
+    FreeFly = 0,
+    /// Camera locked to an entity.
+    Follow = 1,
+}
+
+impl CameraMode {
+    /// Decodes a wire value.
+    #[inline]
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::FreeFly),
+            1 => Some(Self::Follow),
+            _ => None,
+        }
+    }
+}
+
+/// Spectate request - Client -> Server.
+///
+/// The first one connects as a spectator (no entity is spawned); later ones
+/// move the camera.
+///
+/// Size: 20 bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SpectateRequest {
+    /// Camera mode (`CameraMode` as u8).
+    pub mode: u8,
+    /// Padding for alignment.
+    pub _padding: [u8; 3],
+    /// Entity to follow (`CameraMode::Follow`).
+    pub target_entity: u32,
+    /// Camera position X (`CameraMode::FreeFly`).
+    pub camera_x: f32,
+    /// Camera position Y.
+    pub camera_y: f32,
+    /// Camera position Z.
+    pub camera_z: f32,
+}
+
+impl SpectateRequest {
+    /// Size in bytes.
+    pub const SIZE: usize = 20;
+
+    /// Follows an entity.
+    #[must_use]
+    pub const fn follow(entity_id: u32) -> Self {
+        Self {
+            mode: CameraMode::Follow as u8,
+            _padding: [0; 3],
+            target_entity: entity_id,
+            camera_x: 0.0,
+            camera_y: 0.0,
+            camera_z: 0.0,
+        }
+    }
+
+    /// Free camera at `position`.
+    #[must_use]
+    pub const fn free_fly(position: Position) -> Self {
+        Self {
+            mode: CameraMode::FreeFly as u8,
+            _padding: [0; 3],
+            target_entity: 0,
+            camera_x: position.x,
+            camera_y: position.y,
+            camera_z: position.z,
+        }
+    }
+
+    /// Returns the decoded camera mode, or None for an unknown wire value.
+    #[inline]
+    #[must_use]
+    pub const fn mode(&self) -> Option<CameraMode> {
+        CameraMode::from_u8(self.mode)
+    }
+
+    /// Returns the free camera position.
+    #[inline]
+    #[must_use]
+    pub const fn camera(&self) -> Position {
+        Position::new(self.camera_x, self.camera_y, self.camera_z)
+    }
+}
+
 /// Generic packet container.
 #[derive(Clone, Copy, Debug)]
 pub enum Packet {
@@ -490,6 +807,16 @@ pub enum Packet {
     Heartbeat(PacketHeader),
     /// Disconnect.
     Disconnect(PacketHeader),
+    /// Chat message.
+    Chat(PacketHeader, ChatMessage),
+    /// Connection request with a matchmaking join token.
+    Join(PacketHeader, JoinToken),
+    /// Handoff to another shard.
+    Redirect(PacketHeader, ShardRedirect),
+    /// Resume on a shard after a redirect.
+    Resume(PacketHeader, u64), // token
+    /// Spectate request.
+    Spectate(PacketHeader, SpectateRequest),
 }
 
 impl Packet {
@@ -506,6 +833,11 @@ impl Packet {
             Self::ConnectAck(..) => PacketType::ConnectAck,
             Self::Heartbeat(..) => PacketType::Heartbeat,
             Self::Disconnect(..) => PacketType::Disconnect,
+            Self::Chat(..) => PacketType::Chat,
+            Self::Join(..) => PacketType::Join,
+            Self::Redirect(..) => PacketType::Redirect,
+            Self::Resume(..) => PacketType::Resume,
+            Self::Spectate(..) => PacketType::Spectate,
         }
     }
 
@@ -521,7 +853,12 @@ impl Packet {
             | Self::Connect(h)
             | Self::ConnectAck(h, _)
             | Self::Heartbeat(h)
-            | Self::Disconnect(h) => h,
+            | Self::Disconnect(h)
+            | Self::Chat(h, _)
+            | Self::Join(h, _)
+            | Self::Redirect(h, _)
+            | Self::Resume(h, _)
+            | Self::Spectate(h, _) => h,
         }
     }
 }
@@ -538,6 +875,29 @@ mod tests {
         assert_eq!(std::mem::size_of::<DragonState>(), DragonState::SIZE);
         assert_eq!(std::mem::size_of::<ShotFired>(), ShotFired::SIZE);
         assert_eq!(std::mem::size_of::<HitReport>(), HitReport::SIZE);
+        assert_eq!(std::mem::size_of::<ChatMessage>(), ChatMessage::SIZE);
+        assert_eq!(std::mem::size_of::<JoinToken>(), JoinToken::SIZE);
+        assert_eq!(std::mem::size_of::<ShardRedirect>(), ShardRedirect::SIZE);
+        assert_eq!(std::mem::size_of::<SpectateRequest>(), SpectateRequest::SIZE);
+    }
+
+    #[test]
+    fn test_redirect_address_roundtrip() {
+        for addr in ["127.0.0.1:7001", "[::1]:7002"] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            assert_eq!(ShardRedirect::new(9, addr, 3).addr(), addr);
+        }
+    }
+
+    #[test]
+    fn test_chat_message_truncates_on_char_boundary() {
+        // 'a' then 'é' (two bytes) x128 is 257 bytes: the last 'é' straddles the limit.
+        let text: String = std::iter::once('a').chain(std::iter::repeat('é').take(128)).collect();
+        let msg = ChatMessage::new(1, ChatChannel::Global, 0, &text);
+
+        assert_eq!(msg.len as usize, 255);
+        assert_eq!(msg.text().unwrap().chars().count(), 128);
+        assert_eq!(msg.channel(), Some(ChatChannel::Global));
     }
 
     #[test]
@@ -9,6 +9,7 @@
 //! - Direct memory copies where safe (Pod types)
 
 use bytemuck::{bytes_of, Pod};
+use super::error::DecodeError;
 use super::packets::*;
 
 /// Sequence number type alias.
@@ -139,7 +140,10 @@ impl PacketSerializer {
     /// Serializes a world snapshot packet.
     pub fn serialize_snapshot(&mut self, header: &PacketHeader, snapshot: &WorldSnapshot) -> bool {
         self.reset();
-        
+
+        if snapshot.entity_count as usize > WorldSnapshot::MAX_ENTITIES {
+            return false;
+        }
         if !self.write_u8(PacketType::Snapshot as u8) {
             return false;
         }
@@ -165,6 +169,46 @@ impl PacketSerializer {
         true
     }
 
+    /// Serializes a delta snapshot packet.
+    pub fn serialize_delta(&mut self, header: &PacketHeader, delta: &DeltaSnapshot) -> bool {
+        self.reset();
+
+        let changed = delta.changed_count as usize;
+        let removed = delta.removed_count as usize;
+        if changed > DeltaSnapshot::MAX_CHANGES || removed > DeltaSnapshot::MAX_REMOVED {
+            return false;
+        }
+
+        self.write_u8(PacketType::DeltaSnapshot as u8)
+            && self.write_header(header)
+            && self.write_u32(delta.tick)
+            && self.write_u32(delta.base_tick)
+            && self.write_u16(delta.changed_count)
+            && self.write_u16(delta.removed_count)
+            && delta.changed[..changed].iter().all(|e| self.write_pod(e))
+            && delta.removed[..removed].iter().all(|&id| self.write_u32(id))
+    }
+
+    /// Serializes any packet.
+    pub fn serialize_packet(&mut self, packet: &Packet) -> bool {
+        match packet {
+            Packet::Input(h, input) => self.serialize_input(h, input),
+            Packet::Snapshot(h, snapshot) => self.serialize_snapshot(h, snapshot),
+            Packet::Delta(h, delta) => self.serialize_delta(h, delta),
+            Packet::Dragon(h, dragon) => self.serialize_dragon(h, dragon),
+            Packet::Hit(h, hit) => self.serialize_hit(h, hit),
+            Packet::Connect(h) => self.serialize_connect(h),
+            Packet::ConnectAck(h, client_id) => self.serialize_connect_ack(h, *client_id),
+            Packet::Heartbeat(h) => self.serialize_heartbeat(h),
+            Packet::Disconnect(h) => self.serialize_disconnect(h),
+            Packet::Chat(h, chat) => self.serialize_chat(h, chat),
+            Packet::Join(h, token) => self.serialize_join(h, token),
+            Packet::Redirect(h, redirect) => self.serialize_redirect(h, redirect),
+            Packet::Resume(h, token) => self.serialize_resume(h, *token),
+            Packet::Spectate(h, request) => self.serialize_spectate(h, request),
+        }
+    }
+
     /// Serializes a dragon broadcast packet.
     pub fn serialize_dragon(&mut self, header: &PacketHeader, dragon: &DragonState) -> bool {
         self.reset();
@@ -209,6 +253,46 @@ impl PacketSerializer {
         self.write_u8(PacketType::Disconnect as u8)
             && self.write_header(header)
     }
+
+    /// Serializes a join packet (connect with matchmaking token).
+    pub fn serialize_join(&mut self, header: &PacketHeader, token: &JoinToken) -> bool {
+        self.reset();
+        self.write_u8(PacketType::Join as u8)
+            && self.write_header(header)
+            && self.write_pod(token)
+    }
+
+    /// Serializes a shard redirect packet.
+    pub fn serialize_redirect(&mut self, header: &PacketHeader, redirect: &ShardRedirect) -> bool {
+        self.reset();
+        self.write_u8(PacketType::Redirect as u8)
+            && self.write_header(header)
+            && self.write_pod(redirect)
+    }
+
+    /// Serializes a resume packet (first packet to a shard after a redirect).
+    pub fn serialize_resume(&mut self, header: &PacketHeader, token: u64) -> bool {
+        self.reset();
+        self.write_u8(PacketType::Resume as u8)
+            && self.write_header(header)
+            && self.write_pod(&token)
+    }
+
+    /// Serializes a spectate packet.
+    pub fn serialize_spectate(&mut self, header: &PacketHeader, request: &SpectateRequest) -> bool {
+        self.reset();
+        self.write_u8(PacketType::Spectate as u8)
+            && self.write_header(header)
+            && self.write_pod(request)
+    }
+
+    /// Serializes a chat packet.
+    pub fn serialize_chat(&mut self, header: &PacketHeader, chat: &ChatMessage) -> bool {
+        self.reset();
+        self.write_u8(PacketType::Chat as u8)
+            && self.write_header(header)
+            && self.write_pod(chat)
+    }
 }
 
 impl Default for PacketSerializer {
@@ -218,6 +302,9 @@ impl Default for PacketSerializer {
 }
 
 /// Packet deserializer - reads packets from a buffer.
+///
+/// The buffer is untrusted: every read is bounds-checked and malformed
+/// input yields a [`DecodeError`], never a panic.
 pub struct PacketDeserializer<'a> {
     buffer: &'a [u8],
     position: usize,
@@ -237,123 +324,193 @@ impl<'a> PacketDeserializer<'a> {
         self.buffer.len().saturating_sub(self.position)
     }
 
-    /// Reads a single byte.
+    /// Takes the next `len` bytes.
     #[inline]
-    pub fn read_u8(&mut self) -> Option<u8> {
-        if self.position >= self.buffer.len() {
-            return None;
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let remaining = self.remaining();
+        if len > remaining {
+            return Err(DecodeError::Truncated { needed: len, remaining });
         }
-        let value = self.buffer[self.position];
-        self.position += 1;
-        Some(value)
+        let slice = &self.buffer[self.position..self.position + len];
+        self.position += len;
+        Ok(slice)
+    }
+
+    /// Reads a single byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Truncated`] at the end of the buffer.
+    #[inline]
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        self.take(1).map(|b| b[0])
     }
 
     /// Reads a u16 in little-endian format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Truncated`] at the end of the buffer.
     #[inline]
-    pub fn read_u16(&mut self) -> Option<u16> {
-        if self.position + 2 > self.buffer.len() {
-            return None;
-        }
-        let value = u16::from_le_bytes([
-            self.buffer[self.position],
-            self.buffer[self.position + 1],
-        ]);
-        self.position += 2;
-        Some(value)
+    pub fn read_u16(&mut self) -> Result<u16, DecodeError> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
     }
 
     /// Reads a u32 in little-endian format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Truncated`] at the end of the buffer.
     #[inline]
-    pub fn read_u32(&mut self) -> Option<u32> {
-        if self.position + 4 > self.buffer.len() {
-            return None;
-        }
-        let value = u32::from_le_bytes([
-            self.buffer[self.position],
-            self.buffer[self.position + 1],
-            self.buffer[self.position + 2],
-            self.buffer[self.position + 3],
-        ]);
-        self.position += 4;
-        Some(value)
+    pub fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        self.take(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
     }
 
     /// Reads a f32 in little-endian format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Truncated`] at the end of the buffer.
     #[inline]
-    pub fn read_f32(&mut self) -> Option<f32> {
+    pub fn read_f32(&mut self) -> Result<f32, DecodeError> {
         self.read_u32().map(f32::from_bits)
     }
 
-    /// Reads a Pod type directly.
+    /// Reads a Pod type directly (any alignment).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Truncated`] at the end of the buffer.
     #[inline]
-    pub fn read_pod<T: Pod + Copy>(&mut self) -> Option<T> {
-        let size = std::mem::size_of::<T>();
-        if self.position + size > self.buffer.len() {
-            return None;
-        }
-        let slice = &self.buffer[self.position..self.position + size];
-        self.position += size;
-        // Use try_pod_read_unaligned for safety
-        bytemuck::try_pod_read_unaligned(slice).ok()
+    pub fn read_pod<T: Pod>(&mut self) -> Result<T, DecodeError> {
+        self.take(std::mem::size_of::<T>()).map(bytemuck::pod_read_unaligned)
     }
 
     /// Reads a packet header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::Truncated`] at the end of the buffer.
     #[inline]
-    pub fn read_header(&mut self) -> Option<PacketHeader> {
+    pub fn read_header(&mut self) -> Result<PacketHeader, DecodeError> {
         self.read_pod()
     }
 
     /// Deserializes a packet from the buffer.
-    pub fn deserialize(&mut self) -> Option<Packet> {
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DecodeError`] describing the first malformed field.
+    pub fn deserialize(&mut self) -> Result<Packet, DecodeError> {
         let packet_type_byte = self.read_u8()?;
         let header = self.read_header()?;
 
         match packet_type_byte {
             x if x == PacketType::Input as u8 => {
                 let input = self.read_pod::<PlayerInput>()?;
-                Some(Packet::Input(header, input))
+                Ok(Packet::Input(header, input))
             }
             x if x == PacketType::Snapshot as u8 => {
                 let tick = self.read_u32()?;
                 let entity_count = self.read_u16()?;
                 let dragon = self.read_pod::<DragonState>()?;
-                
+                check_count(entity_count, WorldSnapshot::MAX_ENTITIES)?;
+
                 let mut snapshot = WorldSnapshot::empty(tick);
                 snapshot.dragon = dragon;
-                
-                for _ in 0..entity_count.min(WorldSnapshot::MAX_ENTITIES as u16) {
-                    let entity = self.read_pod::<EntityState>()?;
-                    snapshot.add_entity(entity);
+                for _ in 0..entity_count {
+                    snapshot.add_entity(self.read_pod::<EntityState>()?);
+                }
+
+                Ok(Packet::Snapshot(header, snapshot))
+            }
+            x if x == PacketType::DeltaSnapshot as u8 => {
+                let tick = self.read_u32()?;
+                let base_tick = self.read_u32()?;
+                let changed_count = self.read_u16()?;
+                let removed_count = self.read_u16()?;
+                if base_tick > tick {
+                    return Err(DecodeError::InvalidDeltaBase { tick, base_tick });
+                }
+                check_count(changed_count, DeltaSnapshot::MAX_CHANGES)?;
+                check_count(removed_count, DeltaSnapshot::MAX_REMOVED)?;
+
+                let mut delta = DeltaSnapshot::empty(tick, base_tick);
+                for i in 0..changed_count as usize {
+                    delta.changed[i] = self.read_pod::<EntityState>()?;
                 }
-                
-                Some(Packet::Snapshot(header, snapshot))
+                for i in 0..removed_count as usize {
+                    delta.removed[i] = self.read_u32()?;
+                }
+                delta.changed_count = changed_count;
+                delta.removed_count = removed_count;
+
+                Ok(Packet::Delta(header, delta))
             }
             x if x == PacketType::DragonBroadcast as u8 => {
                 let dragon = self.read_pod::<DragonState>()?;
-                Some(Packet::Dragon(header, dragon))
+                Ok(Packet::Dragon(header, dragon))
             }
             x if x == PacketType::HitConfirm as u8 => {
                 let hit = self.read_pod::<HitReport>()?;
-                Some(Packet::Hit(header, hit))
+                Ok(Packet::Hit(header, hit))
             }
             x if x == PacketType::Connect as u8 => {
-                Some(Packet::Connect(header))
+                Ok(Packet::Connect(header))
             }
             x if x == PacketType::ConnectAck as u8 => {
                 let client_id = self.read_u32()?;
-                Some(Packet::ConnectAck(header, client_id))
+                Ok(Packet::ConnectAck(header, client_id))
             }
             x if x == PacketType::Heartbeat as u8 => {
-                Some(Packet::Heartbeat(header))
+                Ok(Packet::Heartbeat(header))
             }
             x if x == PacketType::Disconnect as u8 => {
-                Some(Packet::Disconnect(header))
+                Ok(Packet::Disconnect(header))
+            }
+            x if x == PacketType::Chat as u8 => {
+                let chat = self.read_pod::<ChatMessage>()?;
+                if chat.channel().is_none() {
+                    return Err(DecodeError::InvalidChatChannel(chat.channel));
+                }
+                if chat.len as usize > ChatMessage::MAX_TEXT_BYTES {
+                    return Err(DecodeError::ChatTooLong(chat.len));
+                }
+                Ok(Packet::Chat(header, chat))
+            }
+            x if x == PacketType::Join as u8 => {
+                let token = self.read_pod::<JoinToken>()?;
+                Ok(Packet::Join(header, token))
+            }
+            x if x == PacketType::Redirect as u8 => {
+                let redirect = self.read_pod::<ShardRedirect>()?;
+                Ok(Packet::Redirect(header, redirect))
             }
-            _ => None,
+            x if x == PacketType::Resume as u8 => {
+                let token = self.read_pod::<u64>()?;
+                Ok(Packet::Resume(header, token))
+            }
+            x if x == PacketType::Spectate as u8 => {
+                let request = self.read_pod::<SpectateRequest>()?;
+                if request.mode().is_none() {
+                    return Err(DecodeError::InvalidCameraMode(request.mode));
+                }
+                Ok(Packet::Spectate(header, request))
+            }
+            x => Err(DecodeError::UnknownPacketType(x)),
         }
     }
 }
 
+/// Rejects a list longer than the array it decodes into.
+#[inline]
+fn check_count(count: u16, max: usize) -> Result<(), DecodeError> {
+    if count as usize > max {
+        return Err(DecodeError::TooManyEntities { count: count as usize, max });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -428,6 +585,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_chat() {
+        let header = PacketHeader::new(7, 0, 0);
+        let chat = ChatMessage::new(3, ChatChannel::Whisper, 12, "meet at the spire");
+
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_chat(&header, &chat));
+
+        let mut deserializer = PacketDeserializer::new(serializer.as_slice());
+        let packet = deserializer.deserialize().unwrap();
+
+        if let Packet::Chat(h, c) = packet {
+            assert_eq!(h.sequence, 7);
+            assert_eq!(c.message_id, 3);
+            assert_eq!(c.target_id, 12);
+            assert_eq!(c.channel(), Some(ChatChannel::Whisper));
+            assert_eq!(c.text(), Some("meet at the spire"));
+        } else {
+            panic!("Expected Chat packet");
+        }
+    }
+
+    #[test]
+    fn test_deserialize_chat_rejects_bad_length() {
+        let header = PacketHeader::new(0, 0, 0);
+        let mut chat = ChatMessage::new(0, ChatChannel::Global, 0, "hi");
+        chat.len = 300;
+
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_chat(&header, &chat));
+
+        let mut deserializer = PacketDeserializer::new(serializer.as_slice());
+        assert_eq!(deserializer.deserialize().unwrap_err(), DecodeError::ChatTooLong(300));
+    }
+
+    #[test]
+    fn test_serialize_deserialize_redirect() {
+        let header = PacketHeader::new(4, 0, 0);
+        let redirect = ShardRedirect::new(0xDEAD_BEEF, "127.0.0.1:7002".parse().unwrap(), 42);
+
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_redirect(&header, &redirect));
+
+        let mut deserializer = PacketDeserializer::new(serializer.as_slice());
+        match deserializer.deserialize() {
+            Ok(Packet::Redirect(_, r)) => assert_eq!(r, redirect),
+            other => panic!("Expected Redirect packet, got {other:?}"),
+        }
+
+        assert!(serializer.serialize_resume(&header, 0xDEAD_BEEF));
+        let mut deserializer = PacketDeserializer::new(serializer.as_slice());
+        assert!(matches!(deserializer.deserialize(), Ok(Packet::Resume(_, 0xDEAD_BEEF))));
+    }
+
+    #[test]
+    fn test_deserialize_spectate_rejects_unknown_mode() {
+        let header = PacketHeader::new(0, 0, 0);
+        let mut serializer = PacketSerializer::new();
+
+        assert!(serializer.serialize_spectate(&header, &SpectateRequest::follow(5)));
+        let mut deserializer = PacketDeserializer::new(serializer.as_slice());
+        assert!(matches!(deserializer.deserialize(), Ok(Packet::Spectate(_, r)) if r.target_entity == 5));
+
+        let mut request = SpectateRequest::follow(5);
+        request.mode = 9;
+        assert!(serializer.serialize_spectate(&header, &request));
+        let mut deserializer = PacketDeserializer::new(serializer.as_slice());
+        assert_eq!(deserializer.deserialize().unwrap_err(), DecodeError::InvalidCameraMode(9));
+    }
+
+    #[test]
+    fn test_serialize_deserialize_delta() {
+        let header = PacketHeader::new(2, 0, 0);
+        let mut delta = DeltaSnapshot::empty(10, 8);
+        delta.changed[0] = EntityState { entity_id: 4, pos_x: 3.5, ..Default::default() };
+        delta.changed_count = 1;
+        delta.removed[0] = 9;
+        delta.removed_count = 1;
+
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_delta(&header, &delta));
+
+        match PacketDeserializer::new(serializer.as_slice()).deserialize() {
+            Ok(Packet::Delta(_, d)) => {
+                assert_eq!((d.tick, d.base_tick), (10, 8));
+                assert_eq!(d.changed[..1][0].pos_x, 3.5);
+                assert_eq!(&d.removed[..d.removed_count as usize], &[9]);
+            }
+            other => panic!("Expected Delta packet, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_rejects_malformed() {
+        let header = PacketHeader::new(0, 0, 0);
+        let mut serializer = PacketSerializer::new();
+
+        // Cut short mid-field.
+        assert!(serializer.serialize_connect_ack(&header, 7));
+        let data = &serializer.as_slice()[..serializer.len() - 1];
+        assert_eq!(
+            PacketDeserializer::new(data).deserialize().unwrap_err(),
+            DecodeError::Truncated { needed: 4, remaining: 3 }
+        );
+
+        // Entity count larger than the snapshot can hold.
+        assert!(serializer.serialize_snapshot(&header, &WorldSnapshot::empty(1)));
+        let mut data = serializer.as_slice().to_vec();
+        data[13..15].copy_from_slice(&500u16.to_le_bytes());
+        assert_eq!(
+            PacketDeserializer::new(&data).deserialize().unwrap_err(),
+            DecodeError::TooManyEntities { count: 500, max: WorldSnapshot::MAX_ENTITIES }
+        );
+
+        // Delta from the future.
+        assert!(serializer.serialize_delta(&header, &DeltaSnapshot::empty(3, 5)));
+        assert_eq!(
+            PacketDeserializer::new(serializer.as_slice()).deserialize().unwrap_err(),
+            DecodeError::InvalidDeltaBase { tick: 3, base_tick: 5 }
+        );
+
+        assert_eq!(PacketDeserializer::new(&[]).deserialize().unwrap_err(), DecodeError::Truncated { needed: 1, remaining: 0 });
+        assert_eq!(PacketDeserializer::new(&[200; 9]).deserialize().unwrap_err(), DecodeError::UnknownPacketType(200));
+    }
+
     #[test]
     fn test_packet_size_under_mtu() {
         let mut serializer = PacketSerializer::new();
@@ -32,17 +32,24 @@
 //! - Zero allocations in tick loop
 
 mod connection;
+mod moderation;
 mod state;
 mod tick;
 
 pub use connection::{ClientConnection, ConnectionId, ConnectionState};
-pub use state::ServerState;
+pub use moderation::{Moderation, PERMANENT};
+pub use state::{EntityType, ServerState, WorldEntity};
 pub use tick::TickLoop;
 
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use crate::protocol::{PacketHeader, PlayerInput, WorldSnapshot};
+use crate::chat::{ChatConfig, ChatError, ChatHistory, ChatService};
+use crate::integration::PlayerId;
+use crate::matchmaking::{unix_now, MatchmakingError, TokenSigner};
+use crate::protocol::{ChatMessage, JoinToken, PacketHeader, PlayerInput, SpectateRequest, WorldSnapshot};
+use crate::spectator::{self, SpectatorConfig, SpectatorJoin, SpectatorService};
 use crate::{INFERNO_TICK_RATE, MAX_CLIENTS, MAX_PACKET_SIZE};
 
 /// Server configuration.
@@ -56,6 +63,13 @@ pub struct ServerConfig {
     pub port: u16,
     /// Address to bind to.
     pub bind_address: SocketAddr,
+    /// Chat limits and routing.
+    pub chat: ChatConfig,
+    /// Key shared with the lobby. When set, clients must join with a
+    /// matchmaking token (`PacketType::Join`) and plain `Connect` is refused.
+    pub join_key: Option<[u8; 16]>,
+    /// Spectator limit and broadcast delay.
+    pub spectators: SpectatorConfig,
 }
 
 impl Default for ServerConfig {
@@ -65,6 +79,9 @@ impl Default for ServerConfig {
             max_clients: MAX_CLIENTS,
             port: 7777,
             bind_address: "0.0.0.0:7777".parse().expect("valid address"),
+            chat: ChatConfig::default(),
+            join_key: None,
+            spectators: SpectatorConfig::default(),
         }
     }
 }
@@ -99,6 +116,15 @@ pub enum NetworkCommand {
         /// Actual length.
         len: usize,
     },
+    /// Send packet to client through the reliability layer.
+    SendReliable {
+        /// Target address.
+        addr: SocketAddr,
+        /// Packet data.
+        data: [u8; MAX_PACKET_SIZE],
+        /// Actual length.
+        len: usize,
+    },
     /// Broadcast packet to all clients.
     Broadcast {
         /// Packet data.
@@ -110,19 +136,39 @@ pub enum NetworkCommand {
     Shutdown,
 }
 
+/// A player expected in a hosted session.
+#[derive(Clone, Copy, Debug)]
+struct RosterEntry {
+    /// Player the lobby placed in the session.
+    player_id: PlayerId,
+    /// Address the player last joined from; the token is bound to its IP.
+    joined: Option<SocketAddr>,
+}
+
 /// The Inferno game server.
 ///
 /// This is the main entry point for running the server.
 pub struct InfernoServer {
     /// Server configuration.
-    #[allow(dead_code)]
     config: ServerConfig,
     /// Server state.
     state: ServerState,
+    /// Chat routing, rate limiting and history.
+    chat: ChatService,
+    /// Mute and ban lists.
+    moderation: Moderation,
+    /// Join token verifier (present when `config.join_key` is set).
+    token_signer: Option<TokenSigner>,
+    /// Hosted sessions and their rosters.
+    sessions: HashMap<u64, Vec<RosterEntry>>,
+    /// Spectator connections and the delayed feed.
+    spectators: SpectatorService,
     /// Channel for receiving network events.
     event_rx: Receiver<NetworkEvent>,
     /// Channel for sending network commands.
     command_tx: Sender<NetworkCommand>,
+    /// Receiving end of the command channel, drained by the I/O thread.
+    command_rx: Receiver<NetworkCommand>,
     /// Running flag.
     running: AtomicBool,
     /// Current tick number.
@@ -140,19 +186,32 @@ impl InfernoServer {
         let (command_tx, command_rx) = bounded(10000);
         
         // Store for I/O thread to use
-        let _ = (event_tx, command_rx); // Will be used by I/O thread
+        let _ = event_tx; // Will be used by I/O thread
         
         Self {
             config: config.clone(),
             state: ServerState::new(config.max_clients),
+            chat: ChatService::new(config.chat.clone()),
+            moderation: Moderation::new(),
+            token_signer: config.join_key.map(TokenSigner::new),
+            sessions: HashMap::new(),
+            spectators: SpectatorService::new(config.spectators.clone(), config.tick_rate),
             event_rx,
             command_tx,
+            command_rx,
             running: AtomicBool::new(false),
             tick: AtomicU64::new(0),
             client_count: AtomicU32::new(0),
         }
     }
 
+    /// Returns the server configuration.
+    #[inline]
+    #[must_use]
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
     /// Returns the current tick number.
     #[inline]
     #[must_use]
@@ -167,6 +226,20 @@ impl InfernoServer {
         self.client_count.load(Ordering::Relaxed)
     }
 
+    /// Returns the number of connected spectators.
+    #[inline]
+    #[must_use]
+    pub fn spectator_count(&self) -> usize {
+        self.spectators.count()
+    }
+
+    /// Returns the spectator connections.
+    #[inline]
+    #[must_use]
+    pub fn spectators(&self) -> &SpectatorService {
+        &self.spectators
+    }
+
     /// Returns whether the server is running.
     #[inline]
     #[must_use]
@@ -187,6 +260,95 @@ impl InfernoServer {
         &mut self.state
     }
 
+    /// Returns the chat service (filters, parties).
+    #[inline]
+    pub fn chat_mut(&mut self) -> &mut ChatService {
+        &mut self.chat
+    }
+
+    /// Returns recent chat traffic for admin review.
+    #[inline]
+    #[must_use]
+    pub fn chat_history(&self) -> &ChatHistory {
+        self.chat.history()
+    }
+
+    /// Returns the mute and ban lists.
+    #[inline]
+    #[must_use]
+    pub fn moderation(&self) -> &Moderation {
+        &self.moderation
+    }
+
+    /// Returns the mute and ban lists mutably.
+    #[inline]
+    pub fn moderation_mut(&mut self) -> &mut Moderation {
+        &mut self.moderation
+    }
+
+    /// Bans an address and drops every connection from it.
+    pub fn ban(&mut self, ip: IpAddr) {
+        self.moderation.ban(ip);
+
+        loop {
+            let Some((id, addr)) = self
+                .state
+                .iter_clients()
+                .find(|c| c.addr.ip() == ip)
+                .map(|c| (c.id, c.addr))
+            else {
+                break;
+            };
+            self.send_disconnect(addr);
+            self.state.remove_client(id);
+            self.client_count.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Registers a matchmade session and the players allowed to join it.
+    pub fn host_session(&mut self, session_id: u64, players: &[PlayerId]) {
+        self.sessions
+            .insert(session_id, players.iter().map(|&player_id| RosterEntry { player_id, joined: None }).collect());
+    }
+
+    /// Stops accepting joins for a session.
+    pub fn end_session(&mut self, session_id: u64) {
+        self.sessions.remove(&session_id);
+    }
+
+    /// Handles a datagram delivered outside the I/O thread
+    /// (in-process transports, tests).
+    pub fn receive(&mut self, addr: SocketAddr, data: &[u8]) {
+        self.handle_packet(addr, data);
+    }
+
+    /// Takes the next outgoing command
+    /// (in-process transports, tests).
+    pub fn poll_command(&self) -> Option<NetworkCommand> {
+        self.command_rx.try_recv().ok()
+    }
+
+    /// Admits a client without a handshake (zone handoff).
+    ///
+    /// The caller restores the player's entity and connection state.
+    pub fn admit(&mut self, addr: SocketAddr) -> Option<ConnectionId> {
+        if self.moderation.is_banned(addr.ip()) || self.state.find_client_by_addr(addr).is_some() {
+            return None;
+        }
+        let id = self.state.add_client(addr)?;
+        self.chat.on_connect(id, self.state.current_tick());
+        self.client_count.fetch_add(1, Ordering::Relaxed);
+        Some(id)
+    }
+
+    /// Drops a client without notifying it (zone handoff).
+    pub fn release(&mut self, id: ConnectionId) {
+        if self.state.get_client(id).is_some() {
+            self.state.remove_client(id);
+            self.client_count.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
     /// Processes a single tick.
     ///
     /// This is the hot path - ZERO ALLOCATIONS allowed.
@@ -203,7 +365,12 @@ impl InfernoServer {
         let snapshot = self.state.generate_snapshot(self.current_tick() as u32);
         self.broadcast_snapshot(&snapshot);
 
-        // 4. Increment tick
+        // 4. Feed spectators their (delayed) views
+        self.spectators.record(&snapshot);
+        self.spectators.expire(self.current_tick() as u32);
+        self.spectators.deliver(&self.command_tx);
+
+        // 5. Increment tick
         self.tick.fetch_add(1, Ordering::Relaxed);
     }
 
@@ -214,7 +381,16 @@ impl InfernoServer {
                 self.handle_packet(addr, &data[..len]);
             }
             NetworkEvent::ClientConnected(addr) => {
+                if self.moderation.is_banned(addr.ip()) {
+                    return;
+                }
+                if self.token_signer.is_some() {
+                    // Matchmade servers only admit players through a verified Join.
+                    self.send_disconnect(addr);
+                    return;
+                }
                 if let Some(id) = self.state.add_client(addr) {
+                    self.chat.on_connect(id, self.state.current_tick());
                     self.client_count.fetch_add(1, Ordering::Relaxed);
                     tracing::info!("Client connected: {} (id: {})", addr, id.0);
                 }
@@ -233,29 +409,53 @@ impl InfernoServer {
         
         let mut deserializer = PacketDeserializer::new(data);
         
-        if let Some(packet) = deserializer.deserialize() {
-            match packet {
-                Packet::Input(header, input) => {
-                    self.handle_input(addr, &header, &input);
-                }
-                Packet::Connect(_) => {
+        let packet = match deserializer.deserialize() {
+            Ok(packet) => packet,
+            Err(e) => {
+                tracing::debug!("Dropped packet from {}: {}", addr, e);
+                return;
+            }
+        };
+
+        match packet {
+            Packet::Input(header, input) => {
+                self.handle_input(addr, &header, &input);
+            }
+            Packet::Connect(_) => {
+                if self.token_signer.is_some() {
+                    // Matchmade servers only admit players with a token.
+                    self.send_disconnect(addr);
+                } else {
                     self.handle_connect(addr);
                 }
-                Packet::Disconnect(_) => {
-                    if let Some(id) = self.state.find_client_by_addr(addr) {
-                        self.state.remove_client(id);
-                        self.client_count.fetch_sub(1, Ordering::Relaxed);
-                    }
-                }
-                Packet::Heartbeat(header) => {
-                    if let Some(client) = self.state.find_client_by_addr_mut(addr) {
-                        client.update_ack(header.ack, header.ack_bits);
-                    }
+            }
+            Packet::Join(_, token) => {
+                self.handle_join(addr, &token);
+            }
+            Packet::Disconnect(_) => {
+                if let Some(id) = self.state.find_client_by_addr(addr) {
+                    self.state.remove_client(id);
+                    self.client_count.fetch_sub(1, Ordering::Relaxed);
+                } else {
+                    self.spectators.leave(addr);
                 }
-                _ => {
-                    // Server doesn't handle other packet types from clients
+            }
+            Packet::Heartbeat(header) => {
+                if let Some(client) = self.state.find_client_by_addr_mut(addr) {
+                    client.update_ack(header.ack, header.ack_bits);
+                } else {
+                    self.spectators.touch(addr, self.current_tick() as u32);
                 }
             }
+            Packet::Spectate(_, request) => {
+                self.handle_spectate(addr, request);
+            }
+            Packet::Chat(_, chat) => {
+                self.handle_chat(addr, &chat);
+            }
+            _ => {
+                // Server doesn't handle other packet types from clients
+            }
         }
     }
 
@@ -266,32 +466,190 @@ impl InfernoServer {
         }
     }
 
-    /// Handles connection request.
-    fn handle_connect(&mut self, addr: SocketAddr) {
+    /// Handles a chat message.
+    fn handle_chat(&mut self, addr: SocketAddr, chat: &ChatMessage) {
+        let Some(sender) = self.state.find_client_by_addr(addr) else {
+            return;
+        };
+
+        match self.chat.submit(&self.state, &self.moderation, sender, *chat) {
+            Ok(delivery) => {
+                for &recipient in delivery.recipients {
+                    if let Some(client) = self.state.get_client_mut(recipient) {
+                        let header = PacketHeader::new(client.next_sequence(), 0, 0);
+                        Self::send_chat(&self.command_tx, client.addr, &header, &delivery.message);
+                    }
+                }
+            }
+            Err(ChatError::Duplicate(_)) => {}
+            Err(e) => {
+                tracing::debug!("Chat from {} rejected: {}", addr, e);
+                if let (Some(notice), Some(client)) = (e.notice(), self.state.get_client_mut(sender)) {
+                    let header = PacketHeader::new(client.next_sequence(), 0, 0);
+                    let message = ChatService::system_notice(sender, notice);
+                    Self::send_chat(&self.command_tx, client.addr, &header, &message);
+                }
+            }
+        }
+    }
+
+    /// Queues a chat packet on the reliable channel.
+    fn send_chat(
+        command_tx: &Sender<NetworkCommand>,
+        addr: SocketAddr,
+        header: &PacketHeader,
+        chat: &ChatMessage,
+    ) {
+        let mut serializer = crate::protocol::PacketSerializer::new();
+        if serializer.serialize_chat(header, chat) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            data[..serializer.len()].copy_from_slice(serializer.as_slice());
+
+            let _ = command_tx.try_send(NetworkCommand::SendReliable {
+                addr,
+                data,
+                len: serializer.len(),
+            });
+        }
+    }
+
+    /// Sends a disconnect notification.
+    fn send_disconnect(&self, addr: SocketAddr) {
+        let mut serializer = crate::protocol::PacketSerializer::new();
+        if serializer.serialize_disconnect(&PacketHeader::new(0, 0, 0)) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            data[..serializer.len()].copy_from_slice(serializer.as_slice());
+
+            let _ = self.command_tx.try_send(NetworkCommand::Send {
+                addr,
+                data,
+                len: serializer.len(),
+            });
+        }
+    }
+
+    /// Handles a spectate request: admits a new spectator or moves its camera.
+    fn handle_spectate(&mut self, addr: SocketAddr, request: SpectateRequest) {
         if self.state.find_client_by_addr(addr).is_some() {
-            // Already connected
+            // Players cannot spectate from their own connection.
             return;
         }
 
-        if let Some(id) = self.state.add_client(addr) {
-            self.client_count.fetch_add(1, Ordering::Relaxed);
-            
-            // Send connect ack
-            let mut serializer = crate::protocol::PacketSerializer::new();
-            let header = PacketHeader::new(0, 0, 0);
-            if serializer.serialize_connect_ack(&header, id.0) {
-                let mut data = [0u8; MAX_PACKET_SIZE];
-                data[..serializer.len()].copy_from_slice(serializer.as_slice());
-                
-                let _ = self.command_tx.try_send(NetworkCommand::Send {
-                    addr,
-                    data,
-                    len: serializer.len(),
-                });
+        if self.moderation.is_banned(addr.ip()) {
+            self.send_disconnect(addr);
+            return;
+        }
+
+        match self.spectators.join(addr, request, self.current_tick() as u32) {
+            Ok(SpectatorJoin::Joined(id)) => {
+                tracing::info!("Spectator connected: {} (id: {})", addr, id.0);
+                spectator::send_ack(&self.command_tx, addr, id);
+            }
+            Ok(SpectatorJoin::Updated(_)) => {}
+            Err(e) => {
+                tracing::info!("Spectator {} refused: {}", addr, e);
+                self.send_disconnect(addr);
+            }
+        }
+    }
+
+    /// Handles a join request carrying a matchmaking token.
+    fn handle_join(&mut self, addr: SocketAddr, token: &JoinToken) {
+        if self.state.find_client_by_addr(addr).is_some() {
+            return;
+        }
+
+        if let Err(e) = self.check_join_token(addr, token) {
+            tracing::info!("Join from {} refused: {}", addr, e);
+            self.send_disconnect(addr);
+            return;
+        }
+
+        let Some(entry) = self
+            .sessions
+            .get_mut(&token.session_id)
+            .and_then(|roster| roster.iter_mut().find(|e| e.player_id == token.player_id))
+        else {
+            return;
+        };
+        let previous = entry.joined;
+
+        // A rejoin from a new port replaces the player's stale connection.
+        if let Some(old_addr) = previous.filter(|&old| old != addr) {
+            if let Some(id) = self.state.find_client_by_addr(old_addr) {
+                self.send_disconnect(old_addr);
+                self.state.remove_client(id);
+                self.client_count.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+
+        if self.handle_connect(addr).is_some() {
+            if let Some(roster) = self.sessions.get_mut(&token.session_id) {
+                if let Some(entry) = roster.iter_mut().find(|e| e.player_id == token.player_id) {
+                    entry.joined = Some(addr);
+                }
             }
         }
     }
 
+    /// Validates a join token against the key, the clock and the roster.
+    fn check_join_token(&self, addr: SocketAddr, token: &JoinToken) -> Result<(), MatchmakingError> {
+        let signer = self.token_signer.as_ref().ok_or(MatchmakingError::InvalidToken)?;
+        signer.verify(token, unix_now())?;
+
+        let roster = self
+            .sessions
+            .get(&token.session_id)
+            .ok_or(MatchmakingError::UnknownSession(token.session_id))?;
+        let entry = roster
+            .iter()
+            .find(|e| e.player_id == token.player_id)
+            .ok_or(MatchmakingError::NotOnRoster(token.player_id))?;
+
+        // Once used, a token only lets its holder back in from the same IP.
+        match entry.joined {
+            Some(joined_addr) if joined_addr.ip() != addr.ip() => {
+                Err(MatchmakingError::TokenInUse(token.player_id))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Handles connection request.
+    ///
+    /// Returns the new connection ID if a slot was allocated.
+    fn handle_connect(&mut self, addr: SocketAddr) -> Option<ConnectionId> {
+        if self.state.find_client_by_addr(addr).is_some() {
+            // Already connected
+            return None;
+        }
+
+        if self.moderation.is_banned(addr.ip()) {
+            self.send_disconnect(addr);
+            return None;
+        }
+
+        let id = self.state.add_client(addr)?;
+        self.chat.on_connect(id, self.state.current_tick());
+        self.client_count.fetch_add(1, Ordering::Relaxed);
+
+        // Send connect ack
+        let mut serializer = crate::protocol::PacketSerializer::new();
+        let header = PacketHeader::new(0, 0, 0);
+        if serializer.serialize_connect_ack(&header, id.0) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            data[..serializer.len()].copy_from_slice(serializer.as_slice());
+
+            let _ = self.command_tx.try_send(NetworkCommand::Send {
+                addr,
+                data,
+                len: serializer.len(),
+            });
+        }
+
+        Some(id)
+    }
+
     /// Broadcasts a snapshot to all clients.
     fn broadcast_snapshot(&self, snapshot: &WorldSnapshot) {
         let mut serializer = crate::protocol::PacketSerializer::new();
@@ -342,10 +700,138 @@ mod tests {
             max_clients: 100,
             port: 8888,
             bind_address: "127.0.0.1:8888".parse().unwrap(),
+            chat: ChatConfig::default(),
+            join_key: None,
+            spectators: SpectatorConfig::default(),
         };
         
         assert_eq!(config.tick_rate, 120);
         assert_eq!(config.max_clients, 100);
         assert_eq!(config.port, 8888);
     }
+
+    fn packet_bytes(write: impl FnOnce(&mut crate::protocol::PacketSerializer) -> bool) -> Vec<u8> {
+        let mut serializer = crate::protocol::PacketSerializer::new();
+        assert!(write(&mut serializer));
+        serializer.as_slice().to_vec()
+    }
+
+    #[test]
+    fn test_chat_packet_recorded() {
+        use crate::protocol::ChatChannel;
+
+        let mut server = InfernoServer::new(ServerConfig::default());
+        let addr: SocketAddr = "10.1.1.1:5000".parse().unwrap();
+        let header = PacketHeader::new(0, 0, 0);
+
+        server.handle_packet(addr, &packet_bytes(|s| s.serialize_connect(&header)));
+        let chat = ChatMessage::new(1, ChatChannel::Global, 0, "hello inferno");
+        let data = packet_bytes(|s| s.serialize_chat(&header, &chat));
+        server.handle_packet(addr, &data);
+        // Reliable resend of the same message is ignored.
+        server.handle_packet(addr, &data);
+
+        assert_eq!(server.chat_history().len(), 1);
+        let record = server.chat_history().iter().next().unwrap();
+        assert_eq!(record.message.text(), Some("hello inferno"));
+    }
+
+    #[test]
+    fn test_ban_kicks_and_blocks_reconnect() {
+        let mut server = InfernoServer::new(ServerConfig::default());
+        let addr: SocketAddr = "10.1.1.2:5000".parse().unwrap();
+        let connect = packet_bytes(|s| s.serialize_connect(&PacketHeader::new(0, 0, 0)));
+
+        server.handle_packet(addr, &connect);
+        assert_eq!(server.client_count(), 1);
+
+        server.ban(addr.ip());
+        assert_eq!(server.client_count(), 0);
+
+        server.handle_packet(addr, &connect);
+        assert_eq!(server.client_count(), 0);
+    }
+
+    #[test]
+    fn test_connected_event_needs_token_when_required() {
+        let addr: SocketAddr = "10.1.1.3:5000".parse().unwrap();
+
+        let mut open = InfernoServer::new(ServerConfig::default());
+        open.handle_event(NetworkEvent::ClientConnected(addr));
+        assert_eq!(open.client_count(), 1);
+
+        let config = ServerConfig {
+            join_key: Some(*b"server-test-key!"),
+            ..ServerConfig::default()
+        };
+        let mut matchmade = InfernoServer::new(config);
+        matchmade.handle_event(NetworkEvent::ClientConnected(addr));
+        assert_eq!(matchmade.client_count(), 0);
+        assert!(matchmade.state().find_client_by_addr(addr).is_none());
+    }
+
+    /// Snapshot ticks queued for `addr`, draining the command channel.
+    fn snapshots_for(server: &InfernoServer, addr: SocketAddr) -> Vec<u32> {
+        use crate::protocol::{Packet, PacketDeserializer};
+
+        let mut ticks = Vec::new();
+        while let Some(command) = server.poll_command() {
+            if let NetworkCommand::Send { addr: to, data, len } = command {
+                if let (true, Ok(Packet::Snapshot(_, snapshot))) = (to == addr, PacketDeserializer::new(&data[..len]).deserialize()) {
+                    ticks.push(snapshot.tick);
+                }
+            }
+        }
+        ticks
+    }
+
+    #[test]
+    fn test_spectators_watch_delayed_without_entity() {
+        use crate::client::{ClientConfig, ClientState, GameClient};
+
+        let config = ServerConfig {
+            spectators: SpectatorConfig {
+                max_spectators: 1,
+                delay: std::time::Duration::from_millis(34),
+                ..SpectatorConfig::default()
+            },
+            ..ServerConfig::default()
+        };
+        let mut server = InfernoServer::new(config);
+        let player: SocketAddr = "10.1.1.3:5000".parse().unwrap();
+        server.handle_packet(player, &packet_bytes(|s| s.serialize_connect(&PacketHeader::new(0, 0, 0))));
+        let entities = server.state().active_entities();
+        while server.poll_command().is_some() {}
+
+        let addr: SocketAddr = "10.1.1.4:5000".parse().unwrap();
+        let mut client = GameClient::new(ClientConfig::default());
+        let camera = SpectateRequest::free_fly(oroboros_core::Position::new(0.0, 50.0, 0.0));
+        let (data, len) = client.create_spectate_packet(&camera).unwrap();
+        server.handle_packet(addr, &data[..len]);
+        while let Some(NetworkCommand::Send { data, len, .. }) = server.poll_command() {
+            client.handle_packet(&data[..len]);
+        }
+        assert_eq!(client.state(), ClientState::Connected);
+        assert!(client.is_spectating());
+        assert_eq!(client.entity_id(), None);
+        assert!(client.create_input_packet(&PlayerInput::default()).is_none());
+
+        // No entity, no player slot; the spectator limit is separate.
+        assert_eq!(server.state().active_entities(), entities);
+        assert_eq!(server.client_count(), 1);
+        assert_eq!(server.spectator_count(), 1);
+        let other: SocketAddr = "10.1.1.5:5000".parse().unwrap();
+        server.handle_packet(other, &packet_bytes(|s| s.serialize_spectate(&PacketHeader::new(0, 0, 0), &camera)));
+        assert_eq!(server.spectator_count(), 1);
+
+        // Two ticks (34 ms at 60 Hz) behind live play.
+        for _ in 0..5 {
+            server.tick();
+        }
+        assert_eq!(snapshots_for(&server, addr), vec![0, 1, 2]);
+
+        server.handle_packet(addr, &packet_bytes(|s| s.serialize_disconnect(&PacketHeader::new(0, 0, 0))));
+        assert_eq!(server.spectator_count(), 0);
+        assert_eq!(server.client_count(), 1);
+    }
 }
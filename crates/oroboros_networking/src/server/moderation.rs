@@ -0,0 +1,105 @@
+//! # Moderation
+//!
+//! Server-side mute and ban lists.
+//!
+//! ## Design
+//!
+//! - Keyed by IP address: connection IDs are slot indices and get reused,
+//!   so they cannot carry a sanction across reconnects
+//! - Mutes expire at a server tick; bans last until lifted
+//! - Touched only on connect and chat, never in the simulation hot path
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Mute that never expires.
+pub const PERMANENT: u32 = u32::MAX;
+
+/// Mute and ban lists consulted by the server.
+#[derive(Clone, Debug, Default)]
+pub struct Moderation {
+    /// Muted addresses and the tick their mute expires.
+    mutes: HashMap<IpAddr, u32>,
+    /// Banned addresses.
+    bans: HashSet<IpAddr>,
+}
+
+impl Moderation {
+    /// Creates empty mute and ban lists.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mutes an address until `until_tick` (use [`PERMANENT`] for no expiry).
+    pub fn mute(&mut self, ip: IpAddr, until_tick: u32) {
+        self.mutes.insert(ip, until_tick);
+    }
+
+    /// Lifts a mute.
+    pub fn unmute(&mut self, ip: IpAddr) {
+        self.mutes.remove(&ip);
+    }
+
+    /// Returns the tick the mute expires at, if the address is muted at `tick`.
+    #[must_use]
+    pub fn muted_until(&self, ip: IpAddr, tick: u32) -> Option<u32> {
+        self.mutes.get(&ip).copied().filter(|&until| tick < until)
+    }
+
+    /// Bans an address. Existing connections must be dropped by the caller.
+    pub fn ban(&mut self, ip: IpAddr) {
+        self.bans.insert(ip);
+    }
+
+    /// Lifts a ban.
+    pub fn unban(&mut self, ip: IpAddr) {
+        self.bans.remove(&ip);
+    }
+
+    /// Returns true if the address is banned.
+    #[must_use]
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        self.bans.contains(&ip)
+    }
+
+    /// Drops mutes that have expired by `tick`.
+    pub fn prune_expired(&mut self, tick: u32) {
+        self.mutes.retain(|_, until| tick < *until);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mute_expires() {
+        let mut moderation = Moderation::new();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        moderation.mute(ip, 100);
+        assert_eq!(moderation.muted_until(ip, 50), Some(100));
+        assert_eq!(moderation.muted_until(ip, 100), None);
+
+        moderation.prune_expired(100);
+        moderation.mute(ip, PERMANENT);
+        assert!(moderation.muted_until(ip, u32::MAX - 1).is_some());
+
+        moderation.unmute(ip);
+        assert!(moderation.muted_until(ip, 0).is_none());
+    }
+
+    #[test]
+    fn test_ban() {
+        let mut moderation = Moderation::new();
+        let ip: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(!moderation.is_banned(ip));
+        moderation.ban(ip);
+        assert!(moderation.is_banned(ip));
+        moderation.unban(ip);
+        assert!(!moderation.is_banned(ip));
+    }
+}
@@ -34,6 +34,8 @@ pub struct WorldEntity {
     pub owner: ConnectionId,
     /// Entity type.
     pub entity_type: EntityType,
+    /// Read-only copy of an entity simulated by a neighbouring shard.
+    pub mirrored: bool,
 }
 
 /// Type of entity.
@@ -209,6 +211,7 @@ impl ServerState {
             health: 100,
             owner: ConnectionId::NULL,
             entity_type,
+            mirrored: false,
         };
         
         self.active_entities += 1;
@@ -216,6 +219,24 @@ impl ServerState {
         Some(slot as u32)
     }
 
+    /// Frees an entity slot.
+    pub fn despawn_entity(&mut self, index: u32) {
+        if let Some(entity) = self.entities.get_mut(index as usize) {
+            if entity.active {
+                entity.active = false;
+                self.active_entities = self.active_entities.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Sets the ID given to the next spawned entity.
+    ///
+    /// Zone shards give each server a disjoint ID range so entities keep
+    /// their ID when mirrored or handed off.
+    pub fn set_next_entity_id(&mut self, id: u32) {
+        self.next_entity_id = id;
+    }
+
     /// Gets an entity by slot index.
     #[must_use]
     pub fn get_entity(&self, index: u32) -> Option<&WorldEntity> {
@@ -287,7 +308,8 @@ impl ServerState {
         const GRAVITY: f32 = -20.0;
         
         for entity in &mut self.entities {
-            if !entity.active {
+            // Mirrored entities are moved by their owning shard.
+            if !entity.active || entity.mirrored {
                 continue;
             }
             
@@ -472,4 +494,22 @@ mod tests {
         // Gravity should have affected y velocity
         assert!(entity.velocity.y < 5.0);
     }
+
+    #[test]
+    fn test_mirrored_entities_not_simulated() {
+        let mut state = ServerState::new(500);
+
+        let id = state.spawn_entity(EntityType::Player).unwrap();
+        {
+            let entity = state.get_entity_mut(id).unwrap();
+            entity.velocity = Velocity::new(10.0, 0.0, 0.0);
+            entity.mirrored = true;
+        }
+        state.update_physics();
+        assert!(state.get_entity(id).unwrap().position.x.abs() < f32::EPSILON);
+
+        state.despawn_entity(id);
+        assert!(state.get_entity(id).is_none());
+        assert_eq!(state.active_entities(), 0);
+    }
 }
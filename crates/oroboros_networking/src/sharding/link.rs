@@ -0,0 +1,112 @@
+//! # Shard Links
+//!
+//! Transport between neighbouring zone shards.
+//!
+//! Delivery is best effort; the zone server retries handoffs until they are
+//! acknowledged and mirrors are refreshed every tick, so lost datagrams only
+//! cost latency.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use crate::MAX_PACKET_SIZE;
+use super::map::ShardMap;
+use super::message::ShardMessage;
+use super::ShardId;
+
+/// Message transport between shards.
+pub trait ShardLink {
+    /// Sends a message to a shard. Unknown shards are ignored.
+    fn send(&mut self, to: ShardId, message: &ShardMessage);
+
+    /// Returns the next received message and its sender, if any.
+    fn recv(&mut self) -> Option<(ShardId, ShardMessage)>;
+}
+
+/// [`ShardLink`] over a non-blocking UDP socket.
+///
+/// Only datagrams from registered peers, claiming that peer's shard ID, are
+/// accepted.
+pub struct UdpShardLink {
+    /// This shard.
+    id: ShardId,
+    /// Bound socket.
+    socket: UdpSocket,
+    /// Link address of every peer.
+    peers: HashMap<ShardId, SocketAddr>,
+    /// Encode/receive buffer.
+    buf: [u8; MAX_PACKET_SIZE],
+}
+
+impl UdpShardLink {
+    /// Binds a link for shard `id` on `addr`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket cannot be bound or made non-blocking.
+    pub fn bind(id: ShardId, addr: SocketAddr) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            id,
+            socket,
+            peers: HashMap::new(),
+            buf: [0; MAX_PACKET_SIZE],
+        })
+    }
+
+    /// Returns the bound address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket address cannot be queried.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Registers a peer shard.
+    pub fn add_peer(&mut self, shard: ShardId, addr: SocketAddr) {
+        if shard != self.id {
+            self.peers.insert(shard, addr);
+        }
+    }
+
+    /// Registers every other shard in `map` as a peer.
+    pub fn add_peers_from(&mut self, map: &ShardMap) {
+        for (shard, endpoint) in map.endpoints() {
+            self.add_peer(shard, endpoint.link_addr);
+        }
+    }
+}
+
+impl ShardLink for UdpShardLink {
+    fn send(&mut self, to: ShardId, message: &ShardMessage) {
+        let Some(&addr) = self.peers.get(&to) else {
+            return;
+        };
+        if let Some(len) = message.encode(self.id, &mut self.buf) {
+            if let Err(e) = self.socket.send_to(&self.buf[..len], addr) {
+                tracing::debug!("Shard link send to {:?} failed: {}", to, e);
+            }
+        }
+    }
+
+    fn recv(&mut self) -> Option<(ShardId, ShardMessage)> {
+        loop {
+            let (len, addr) = match self.socket.recv_from(&mut self.buf) {
+                Ok(received) => received,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return None,
+                Err(e) => {
+                    tracing::debug!("Shard link receive failed: {}", e);
+                    return None;
+                }
+            };
+            match ShardMessage::decode(&self.buf[..len]) {
+                Some((from, message)) if self.peers.get(&from) == Some(&addr) => {
+                    return Some((from, message));
+                }
+                _ => tracing::debug!("Dropped shard datagram from {}", addr),
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! # Shard Map
+//!
+//! Which shard owns which part of the world.
+//!
+//! The world is cut into square regions of `region_chunks` x `region_chunks`
+//! chunks. Each region is assigned to one shard; unassigned regions belong
+//! to nobody and players cannot be handed into them.
+//!
+//! ```text
+//!            region x = -1    region x = 0
+//!          ┌──────────────┬──────────────┐
+//!  z = 0   │   shard 1    │   shard 2    │
+//!          │          ░░░░│░░░░          │  ░ = mirror margin
+//!          └──────────────┴──────────────┘
+//! ```
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use oroboros_core::{ChunkCoord, Position};
+use super::ShardId;
+
+/// Addresses of one shard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShardEndpoint {
+    /// Address game clients send to.
+    pub client_addr: SocketAddr,
+    /// Address neighbouring shards send to.
+    pub link_addr: SocketAddr,
+}
+
+/// Assignment of world regions to shards.
+///
+/// Every shard in a cluster is configured with the same map.
+#[derive(Clone, Debug)]
+pub struct ShardMap {
+    /// Region edge length in chunks.
+    region_chunks: i32,
+    /// Owner of each region.
+    regions: HashMap<(i32, i32), ShardId>,
+    /// Where each shard listens.
+    endpoints: HashMap<ShardId, ShardEndpoint>,
+}
+
+impl ShardMap {
+    /// Creates an empty map with regions of `region_chunks` chunks per side.
+    #[must_use]
+    pub fn new(region_chunks: i32) -> Self {
+        Self {
+            region_chunks: region_chunks.max(1),
+            regions: HashMap::new(),
+            endpoints: HashMap::new(),
+        }
+    }
+
+    /// Assigns a region to a shard.
+    pub fn assign(&mut self, region_x: i32, region_z: i32, shard: ShardId) {
+        self.regions.insert((region_x, region_z), shard);
+    }
+
+    /// Registers a shard's addresses.
+    pub fn set_endpoint(&mut self, shard: ShardId, endpoint: ShardEndpoint) {
+        self.endpoints.insert(shard, endpoint);
+    }
+
+    /// Returns a shard's addresses.
+    #[must_use]
+    pub fn endpoint(&self, shard: ShardId) -> Option<&ShardEndpoint> {
+        self.endpoints.get(&shard)
+    }
+
+    /// Iterates all registered shards and their addresses.
+    pub fn endpoints(&self) -> impl Iterator<Item = (ShardId, &ShardEndpoint)> {
+        self.endpoints.iter().map(|(&id, endpoint)| (id, endpoint))
+    }
+
+    /// Region edge length in chunks.
+    #[must_use]
+    pub const fn region_chunks(&self) -> i32 {
+        self.region_chunks
+    }
+
+    /// Region containing a chunk.
+    #[must_use]
+    pub const fn region_of(&self, chunk: ChunkCoord) -> (i32, i32) {
+        (
+            chunk.x.div_euclid(self.region_chunks),
+            chunk.z.div_euclid(self.region_chunks),
+        )
+    }
+
+    /// Shard owning a chunk.
+    #[must_use]
+    pub fn owner(&self, chunk: ChunkCoord) -> Option<ShardId> {
+        self.regions.get(&self.region_of(chunk)).copied()
+    }
+
+    /// Shard owning the chunk under a world position.
+    #[must_use]
+    pub fn owner_at(&self, x: f32, z: f32) -> Option<ShardId> {
+        let chunk = ChunkCoord::from_block_pos(x.floor() as i32, z.floor() as i32);
+        self.owner(chunk)
+    }
+
+    /// Shard owning a position, if it owns everything within `margin` of it
+    /// along both axes.
+    ///
+    /// Used as handoff hysteresis: a player walking along a border is not
+    /// bounced back and forth.
+    #[must_use]
+    pub fn owner_inside(&self, pos: &Position, margin: f32) -> Option<ShardId> {
+        let owner = self.owner_at(pos.x, pos.z)?;
+        let samples = [
+            (pos.x - margin, pos.z),
+            (pos.x + margin, pos.z),
+            (pos.x, pos.z - margin),
+            (pos.x, pos.z + margin),
+        ];
+        samples
+            .iter()
+            .all(|&(x, z)| self.owner_at(x, z) == Some(owner))
+            .then_some(owner)
+    }
+
+    /// Collects shards other than `owner` that own ground within `margin` of
+    /// `pos` (the shards that should receive a mirror of an entity there).
+    ///
+    /// `out` is cleared first.
+    pub fn neighbours_near(&self, pos: &Position, margin: f32, owner: ShardId, out: &mut Vec<ShardId>) {
+        out.clear();
+        for dx in [-margin, 0.0, margin] {
+            for dz in [-margin, 0.0, margin] {
+                if let Some(shard) = self.owner_at(pos.x + dx, pos.z + dz) {
+                    if shard != owner && !out.contains(&shard) {
+                        out.push(shard);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oroboros_core::spatial::CHUNK_SIZE;
+
+    fn two_shards() -> ShardMap {
+        let mut map = ShardMap::new(4);
+        map.assign(-1, 0, ShardId(1));
+        map.assign(0, 0, ShardId(2));
+        map
+    }
+
+    #[test]
+    fn test_region_ownership() {
+        let map = two_shards();
+        let edge = (4 * CHUNK_SIZE) as f32;
+
+        assert_eq!(map.owner_at(-0.5, 1.0), Some(ShardId(1)));
+        assert_eq!(map.owner_at(0.0, 1.0), Some(ShardId(2)));
+        assert_eq!(map.owner_at(edge - 0.5, 1.0), Some(ShardId(2)));
+        assert_eq!(map.owner_at(edge, 1.0), None);
+        assert_eq!(map.owner_at(1.0, -1.0), None);
+    }
+
+    #[test]
+    fn test_hysteresis_and_neighbours() {
+        let map = two_shards();
+        let mut out = Vec::new();
+
+        let near_border = Position::new(1.0, 0.0, 10.0);
+        assert_eq!(map.owner_inside(&near_border, 2.0), None);
+        map.neighbours_near(&near_border, 8.0, ShardId(2), &mut out);
+        assert_eq!(out, vec![ShardId(1)]);
+
+        let inside = Position::new(20.0, 0.0, 10.0);
+        assert_eq!(map.owner_inside(&inside, 2.0), Some(ShardId(2)));
+        map.neighbours_near(&inside, 8.0, ShardId(2), &mut out);
+        assert!(out.is_empty());
+    }
+}
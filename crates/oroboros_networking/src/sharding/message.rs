@@ -0,0 +1,381 @@
+//! # Shard-to-Shard Messages
+//!
+//! Wire format for traffic between neighbouring zone shards.
+//!
+//! ```text
+//! ┌──────────┬────────────┬──────────────────────────────┐
+//! │ tag (u8) │ from (u16) │ payload (Pod, little-endian) │
+//! └──────────┴────────────┴──────────────────────────────┘
+//! ```
+//!
+//! Every message fits in one datagram under `MAX_PACKET_SIZE`.
+
+use std::net::{IpAddr, SocketAddr};
+use bytemuck::{bytes_of, Pod, Zeroable};
+use crate::protocol::{EntityState, PlayerInput};
+use super::ShardId;
+
+/// Entities per mirror message.
+pub const MIRROR_BATCH: usize = 32;
+
+/// Most recent inputs carried over in a handoff.
+pub const HANDOFF_INPUTS: usize = 8;
+
+/// Header bytes before the payload (tag + sender).
+const HEADER_SIZE: usize = 3;
+
+// A full mirror batch must fit in one datagram.
+const _: () = assert!(HEADER_SIZE + 1 + MIRROR_BATCH * MirroredEntity::SIZE <= crate::MAX_PACKET_SIZE);
+
+/// Message tags.
+const TAG_MIRROR: u8 = 1;
+const TAG_HANDOFF: u8 = 2;
+const TAG_HANDOFF_ACK: u8 = 3;
+const TAG_HANDOFF_REFUSED: u8 = 4;
+const TAG_INPUT: u8 = 5;
+
+/// Read-only copy of an entity near a shard border.
+///
+/// Size: 36 bytes
+#[derive(Clone, Copy, Debug, Default, Pod, Zeroable)]
+#[repr(C)]
+pub struct MirroredEntity {
+    /// Network state (ID, position, velocity, health).
+    pub state: EntityState,
+    /// `EntityType` as u8.
+    pub entity_type: u8,
+    /// Padding for alignment.
+    pub _padding: [u8; 3],
+}
+
+impl MirroredEntity {
+    /// Size in bytes.
+    pub const SIZE: usize = 36;
+}
+
+/// Up to [`MIRROR_BATCH`] mirrored entities.
+#[derive(Clone, Copy, Debug)]
+pub struct MirrorBatch {
+    /// Number of valid entries.
+    len: usize,
+    /// Entries.
+    entities: [MirroredEntity; MIRROR_BATCH],
+}
+
+impl MirrorBatch {
+    /// Creates an empty batch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            entities: [MirroredEntity::default(); MIRROR_BATCH],
+        }
+    }
+
+    /// Adds an entity. Returns false if the batch is full.
+    pub fn push(&mut self, entity: MirroredEntity) -> bool {
+        if self.len == MIRROR_BATCH {
+            return false;
+        }
+        self.entities[self.len] = entity;
+        self.len += 1;
+        true
+    }
+
+    /// Returns the valid entries.
+    #[must_use]
+    pub fn as_slice(&self) -> &[MirroredEntity] {
+        &self.entities[..self.len]
+    }
+
+    /// Number of entries.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the batch holds nothing.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if no more entries fit.
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.len == MIRROR_BATCH
+    }
+
+    /// Empties the batch.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for MirrorBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Everything needed to continue a player's session on another shard.
+///
+/// Size: 272 bytes
+#[derive(Clone, Copy, Debug, Default, Pod, Zeroable)]
+#[repr(C)]
+pub struct PlayerHandoff {
+    /// One-time token the client presents to the target (`Resume`).
+    pub token: u64,
+    /// Reference to the player's inventory in the persistence layer.
+    /// Shards never copy inventories; they pass the handle along.
+    pub inventory_ref: u64,
+    /// Account-level player ID.
+    pub player_id: u32,
+    /// Player entity (ID is kept across the handoff).
+    pub entity: EntityState,
+    /// Client IP (IPv4 in the first 4 bytes unless `is_ipv6`).
+    pub client_ip: [u8; 16],
+    /// Client UDP port.
+    pub client_port: u16,
+    /// 1 if `client_ip` is an IPv6 address.
+    pub is_ipv6: u8,
+    /// Padding for alignment.
+    pub _padding: u8,
+    /// Next sequence number the server sends to the client.
+    pub next_send_sequence: u16,
+    /// Last sequence number received from the client.
+    pub last_recv_sequence: u16,
+    /// Number of valid entries in `inputs`.
+    pub input_count: u16,
+    /// Padding for alignment.
+    pub _padding2: u16,
+    /// Most recent inputs, oldest first.
+    pub inputs: [PlayerInput; HANDOFF_INPUTS],
+}
+
+impl PlayerHandoff {
+    /// Size in bytes.
+    pub const SIZE: usize = 272;
+
+    /// Stores the client's address.
+    pub fn set_client_addr(&mut self, addr: SocketAddr) {
+        self.client_ip = [0; 16];
+        match addr.ip() {
+            IpAddr::V4(v4) => {
+                self.client_ip[..4].copy_from_slice(&v4.octets());
+                self.is_ipv6 = 0;
+            }
+            IpAddr::V6(v6) => {
+                self.client_ip.copy_from_slice(&v6.octets());
+                self.is_ipv6 = 1;
+            }
+        }
+        self.client_port = addr.port();
+    }
+
+    /// Returns the client's address.
+    #[must_use]
+    pub fn client_addr(&self) -> SocketAddr {
+        let ip = if self.is_ipv6 == 0 {
+            let [a, b, c, d, ..] = self.client_ip;
+            IpAddr::from([a, b, c, d])
+        } else {
+            IpAddr::from(self.client_ip)
+        };
+        SocketAddr::new(ip, self.client_port)
+    }
+
+    /// Returns the carried-over inputs, oldest first.
+    #[must_use]
+    pub fn inputs(&self) -> &[PlayerInput] {
+        &self.inputs[..(self.input_count as usize).min(HANDOFF_INPUTS)]
+    }
+}
+
+/// Message between shards.
+///
+/// Payloads are stored inline: messages are built on the tick path and
+/// passed by reference, so boxing them would allocate every tick.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum ShardMessage {
+    /// Entities of the sender that are near the receiver's regions.
+    Mirror(MirrorBatch),
+    /// A player crossed into the receiver's regions.
+    Handoff(PlayerHandoff),
+    /// The receiver admitted the player.
+    HandoffAck {
+        /// Handoff token.
+        token: u64,
+    },
+    /// The receiver could not admit the player (full, banned).
+    HandoffRefused {
+        /// Handoff token.
+        token: u64,
+    },
+    /// Input that reached the old shard after the player left it.
+    Input {
+        /// Handoff token.
+        token: u64,
+        /// The input.
+        input: PlayerInput,
+    },
+}
+
+impl ShardMessage {
+    /// Encodes the message into `buf`. Returns the length written, or None
+    /// if `buf` is too small.
+    #[must_use]
+    pub fn encode(&self, from: ShardId, buf: &mut [u8]) -> Option<usize> {
+        let mut writer = Writer { buf, pos: 0 };
+        let tag = match self {
+            Self::Mirror(_) => TAG_MIRROR,
+            Self::Handoff(_) => TAG_HANDOFF,
+            Self::HandoffAck { .. } => TAG_HANDOFF_ACK,
+            Self::HandoffRefused { .. } => TAG_HANDOFF_REFUSED,
+            Self::Input { .. } => TAG_INPUT,
+        };
+        writer.write(&[tag])?;
+        writer.write(&from.0.to_le_bytes())?;
+
+        match self {
+            Self::Mirror(batch) => {
+                writer.write(&[batch.len() as u8])?;
+                writer.write(bytemuck::cast_slice(batch.as_slice()))?;
+            }
+            Self::Handoff(handoff) => writer.write(bytes_of(handoff))?,
+            Self::HandoffAck { token } | Self::HandoffRefused { token } => {
+                writer.write(&token.to_le_bytes())?;
+            }
+            Self::Input { token, input } => {
+                writer.write(&token.to_le_bytes())?;
+                writer.write(bytes_of(input))?;
+            }
+        }
+        Some(writer.pos)
+    }
+
+    /// Decodes a message. Returns the sender and the message, or None if the
+    /// datagram is malformed.
+    #[must_use]
+    pub fn decode(data: &[u8]) -> Option<(ShardId, Self)> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+        let from = ShardId(u16::from_le_bytes([data[1], data[2]]));
+        let payload = &data[HEADER_SIZE..];
+
+        let message = match data[0] {
+            TAG_MIRROR => {
+                let (&count, rest) = payload.split_first()?;
+                let count = count as usize;
+                if count > MIRROR_BATCH || rest.len() != count * MirroredEntity::SIZE {
+                    return None;
+                }
+                let mut batch = MirrorBatch::new();
+                for chunk in rest.chunks_exact(MirroredEntity::SIZE) {
+                    batch.push(bytemuck::try_pod_read_unaligned(chunk).ok()?);
+                }
+                Self::Mirror(batch)
+            }
+            TAG_HANDOFF => {
+                let handoff: PlayerHandoff = bytemuck::try_pod_read_unaligned(payload).ok()?;
+                if handoff.input_count as usize > HANDOFF_INPUTS {
+                    return None;
+                }
+                Self::Handoff(handoff)
+            }
+            TAG_HANDOFF_ACK => Self::HandoffAck { token: read_u64(payload)? },
+            TAG_HANDOFF_REFUSED => Self::HandoffRefused { token: read_u64(payload)? },
+            TAG_INPUT => {
+                if payload.len() != 8 + PlayerInput::SIZE {
+                    return None;
+                }
+                Self::Input {
+                    token: read_u64(&payload[..8])?,
+                    input: bytemuck::try_pod_read_unaligned(&payload[8..]).ok()?,
+                }
+            }
+            _ => return None,
+        };
+        Some((from, message))
+    }
+}
+
+/// Bounds-checked cursor over an output buffer.
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl Writer<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Option<()> {
+        let end = self.pos + bytes.len();
+        self.buf.get_mut(self.pos..end)?.copy_from_slice(bytes);
+        self.pos = end;
+        Some(())
+    }
+}
+
+fn read_u64(bytes: &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MAX_PACKET_SIZE;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(std::mem::size_of::<MirroredEntity>(), MirroredEntity::SIZE);
+        assert_eq!(std::mem::size_of::<PlayerHandoff>(), PlayerHandoff::SIZE);
+    }
+
+    #[test]
+    fn test_handoff_roundtrip() {
+        let mut handoff = PlayerHandoff {
+            token: 99,
+            inventory_ref: 0xABCD,
+            player_id: 7,
+            input_count: 2,
+            ..PlayerHandoff::default()
+        };
+        handoff.set_client_addr("[::1]:4000".parse().unwrap());
+        handoff.inputs[1].tick = 5;
+
+        let mut buf = [0u8; MAX_PACKET_SIZE];
+        let len = ShardMessage::Handoff(handoff).encode(ShardId(3), &mut buf).unwrap();
+
+        match ShardMessage::decode(&buf[..len]) {
+            Some((ShardId(3), ShardMessage::Handoff(decoded))) => {
+                assert_eq!(decoded.token, 99);
+                assert_eq!(decoded.inventory_ref, 0xABCD);
+                assert_eq!(decoded.client_addr(), "[::1]:4000".parse().unwrap());
+                assert_eq!(decoded.inputs().len(), 2);
+                assert_eq!(decoded.inputs()[1].tick, 5);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_full_mirror_batch_roundtrip() {
+        let mut batch = MirrorBatch::new();
+        for id in 0..MIRROR_BATCH as u32 {
+            assert!(batch.push(MirroredEntity { state: EntityState { entity_id: id, ..EntityState::default() }, ..MirroredEntity::default() }));
+        }
+        assert!(!batch.push(MirroredEntity::default()));
+
+        let mut buf = [0u8; MAX_PACKET_SIZE];
+        let len = ShardMessage::Mirror(batch).encode(ShardId(1), &mut buf).unwrap();
+        let Some((_, ShardMessage::Mirror(decoded))) = ShardMessage::decode(&buf[..len]) else {
+            panic!("expected mirror");
+        };
+        assert_eq!(decoded.len(), MIRROR_BATCH);
+        assert_eq!(decoded.as_slice()[31].state.entity_id, 31);
+
+        // Truncated datagrams are rejected.
+        assert!(ShardMessage::decode(&buf[..len - 1]).is_none());
+    }
+}
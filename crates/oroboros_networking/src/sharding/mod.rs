@@ -0,0 +1,263 @@
+//! # World Sharding
+//!
+//! Splits the infinite world across several server processes.
+//!
+//! ## Architecture
+//!
+//! ```text
+//! ┌──────────────────┬──────────────────┬──────────────────┐
+//! │  SHARD 1         │  SHARD 2         │  SHARD 3         │
+//! │  regions x < 0   │  regions x = 0   │  regions x > 0   │
+//! │              ░░░░│░░░░          ░░░░│░░░░              │
+//! └──────────────────┴──────────────────┴──────────────────┘
+//!          ░ mirrored to the neighbour    ──▶ handoff on crossing
+//! ```
+//!
+//! ## Design
+//!
+//! - Ownership is by [`ChunkCoord`](oroboros_core::ChunkCoord) region;
+//!   every shard runs with the same [`ShardMap`]
+//! - Entities within `mirror_margin` of a border are mirrored every tick as
+//!   read-only ghosts, so players see across it
+//! - Players well past a border are handed off: entity state, inventory
+//!   reference and recent inputs move to the new shard, and the client is
+//!   redirected without a new handshake
+//! - Entity IDs are unique per shard and kept across mirrors and handoffs
+//! - Shards talk over a [`ShardLink`]; [`UdpShardLink`] runs several
+//!   shards on one machine
+
+mod link;
+mod map;
+mod message;
+mod zone;
+
+pub use link::{ShardLink, UdpShardLink};
+pub use map::{ShardEndpoint, ShardMap};
+pub use message::{
+    MirrorBatch, MirroredEntity, PlayerHandoff, ShardMessage, HANDOFF_INPUTS, MIRROR_BATCH,
+};
+pub use zone::{PlayerMeta, ZoneServer};
+
+use crate::server::ServerConfig;
+
+/// Identifies a zone shard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ShardId(pub u16);
+
+impl ShardId {
+    /// Low bits of an entity ID allocated by a shard; the shard ID fills
+    /// the rest, so shard IDs must stay below `1 << (32 - ENTITY_ID_BITS)`.
+    pub const ENTITY_ID_BITS: u32 = 20;
+}
+
+/// Zone shard configuration.
+#[derive(Clone, Debug)]
+pub struct ShardConfig {
+    /// This shard.
+    pub shard_id: ShardId,
+    /// Game server settings (client-facing address, limits).
+    pub server: ServerConfig,
+    /// Distance from a border (blocks) within which entities are mirrored.
+    pub mirror_margin: f32,
+    /// How far past a border (blocks) a player must be to be handed off.
+    pub handoff_hysteresis: f32,
+    /// Ticks a ghost survives without a mirror update.
+    pub mirror_ttl_ticks: u64,
+    /// Ticks between handoff resends.
+    pub handoff_retry_ticks: u64,
+    /// Sends before a handoff is abandoned and the player restored.
+    pub handoff_max_attempts: u32,
+    /// Ticks a departed client's packets are still forwarded.
+    pub forward_ticks: u64,
+}
+
+impl ShardConfig {
+    /// Creates a configuration with default margins and timeouts.
+    #[must_use]
+    pub fn new(shard_id: ShardId, server: ServerConfig) -> Self {
+        Self {
+            shard_id,
+            server,
+            mirror_margin: 16.0,
+            handoff_hysteresis: 2.0,
+            mirror_ttl_ticks: 30,
+            handoff_retry_ticks: 10,
+            handoff_max_attempts: 5,
+            forward_ticks: 120,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+    use oroboros_core::Position;
+    use crate::client::{ClientConfig, ClientState, GameClient};
+    use crate::protocol::PlayerInput;
+    use crate::server::{EntityType, NetworkCommand};
+
+    /// Three shards side by side along x, one 16-block region each, linked
+    /// over UDP on localhost.
+    fn cluster() -> Vec<ZoneServer<UdpShardLink>> {
+        let ids = [ShardId(1), ShardId(2), ShardId(3)];
+        let mut map = ShardMap::new(1);
+        let mut links = Vec::new();
+
+        for (region_x, &id) in (-1..).zip(&ids) {
+            let link = UdpShardLink::bind(id, "127.0.0.1:0".parse().unwrap()).unwrap();
+            let client_addr: SocketAddr = format!("127.0.0.1:{}", 7100 + id.0).parse().unwrap();
+            map.assign(region_x, 0, id);
+            map.set_endpoint(id, ShardEndpoint { client_addr, link_addr: link.local_addr().unwrap() });
+            links.push(link);
+        }
+
+        ids.iter()
+            .zip(links)
+            .map(|(&id, mut link)| {
+                link.add_peers_from(&map);
+                let server = ServerConfig {
+                    bind_address: map.endpoint(id).unwrap().client_addr,
+                    ..ServerConfig::default()
+                };
+                ZoneServer::new(ShardConfig::new(id, server), map.clone(), link)
+            })
+            .collect()
+    }
+
+    fn tick_all(shards: &mut [ZoneServer<UdpShardLink>], until: impl Fn(&[ZoneServer<UdpShardLink>]) -> bool) {
+        for _ in 0..200 {
+            for shard in shards.iter_mut() {
+                shard.tick();
+            }
+            if until(shards) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!("cluster did not converge");
+    }
+
+    /// Feeds every packet the shard queued for `addr` to the client.
+    fn deliver(shard: &ZoneServer<UdpShardLink>, addr: SocketAddr, client: &mut GameClient) {
+        while let Some(command) = shard.server().poll_command() {
+            match command {
+                NetworkCommand::Send { addr: to, data, len } | NetworkCommand::SendReliable { addr: to, data, len }
+                    if to == addr =>
+                {
+                    client.handle_packet(&data[..len]);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn entity_ids(shard: &ZoneServer<UdpShardLink>) -> Vec<u32> {
+        shard.server().state().iter_entities().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_border_entities_mirrored() {
+        let mut shards = cluster();
+
+        // An enemy on shard 2 two blocks from the shard 3 border.
+        let slot = shards[1].server_mut().state_mut().spawn_entity(EntityType::Enemy).unwrap();
+        shards[1].server_mut().state_mut().get_entity_mut(slot).unwrap().position = Position::new(14.0, 0.0, 8.0);
+        let id = shards[1].server().state().get_entity(slot).unwrap().id;
+
+        tick_all(&mut shards, |s| s[2].ghost_count() == 1);
+        assert!(entity_ids(&shards[2]).contains(&id));
+        // Shard 1 is more than a margin away.
+        assert_eq!(shards[0].ghost_count(), 0);
+
+        // Ghosts vanish once the source stops mirroring them.
+        shards[1].server_mut().state_mut().despawn_entity(slot);
+        tick_all(&mut shards, |s| s[2].ghost_count() == 0);
+        assert!(!entity_ids(&shards[2]).contains(&id));
+    }
+
+    #[test]
+    fn test_player_handoff_without_reconnect() {
+        let mut shards = cluster();
+        let client_addr: SocketAddr = "127.0.0.1:50001".parse().unwrap();
+        let mut client = GameClient::new(ClientConfig {
+            server_addr: shards[1].server().config().bind_address,
+            ..ClientConfig::default()
+        });
+
+        // Connect to shard 2 and walk east.
+        let (data, len) = client.create_connect_packet().unwrap();
+        shards[1].receive(client_addr, &data[..len]);
+        deliver(&shards[1], client_addr, &mut client);
+        assert_eq!(client.state(), ClientState::Connected);
+
+        let conn = shards[1].server().state().find_client_by_addr(client_addr).unwrap();
+        shards[1].attach_player(conn, PlayerMeta { player_id: 77, inventory_ref: 0xFEED });
+        let slot = shards[1].server().state().get_client(conn).unwrap().entity_id;
+        let entity_id = shards[1].server().state().get_entity(slot).unwrap().id;
+
+        let input = PlayerInput { move_x: 127, flags: PlayerInput::FLAG_SPRINT, ..PlayerInput::default() };
+        let (data, len) = client.create_input_packet(&input).unwrap();
+        shards[1].receive(client_addr, &data[..len]);
+        shards[1].server_mut().state_mut().get_entity_mut(slot).unwrap().position = Position::new(15.0, 0.0, 8.0);
+
+        tick_all(&mut shards, |s| s[2].server().client_count() == 1 && s[1].pending_handoffs() == 0);
+        assert_eq!(shards[1].server().client_count(), 0);
+
+        // Same entity, inventory and input stream on the new shard.
+        let new_conn = shards[2].server().state().find_client_by_addr(client_addr).unwrap();
+        assert_eq!(shards[2].player_meta(new_conn), Some(&PlayerMeta { player_id: 77, inventory_ref: 0xFEED }));
+        let new_client = shards[2].server().state().get_client(new_conn).unwrap();
+        assert_eq!(new_client.latest_input().unwrap().move_x, 127);
+        let entity = shards[2].server().state().get_entity(new_client.entity_id).unwrap();
+        assert_eq!(entity.id, entity_id);
+        assert!(entity.position.x > 16.0);
+
+        // The client follows the redirect and keeps its session.
+        deliver(&shards[1], client_addr, &mut client);
+        assert_eq!(client.state(), ClientState::Connected);
+        assert_eq!(client.server_addr(), shards[2].server().config().bind_address);
+        assert_eq!(client.entity_id(), Some(entity_id));
+
+        // Resume from a new NAT mapping; the token cannot be replayed.
+        let nat_addr: SocketAddr = "127.0.0.1:50002".parse().unwrap();
+        let (data, len) = client.create_resume_packet().unwrap();
+        shards[2].receive(nat_addr, &data[..len]);
+        shards[2].receive("127.0.0.1:50003".parse().unwrap(), &data[..len]);
+        assert_eq!(shards[2].server().state().find_client_by_addr(nat_addr), Some(new_conn));
+    }
+
+    #[test]
+    fn test_unanswered_handoff_restores_player() {
+        let mut map = ShardMap::new(1);
+        map.assign(0, 0, ShardId(1));
+        map.assign(1, 0, ShardId(2));
+        let silent: SocketAddr = "127.0.0.1:9".parse().unwrap();
+        map.set_endpoint(ShardId(2), ShardEndpoint { client_addr: silent, link_addr: silent });
+
+        let mut link = UdpShardLink::bind(ShardId(1), "127.0.0.1:0".parse().unwrap()).unwrap();
+        link.add_peers_from(&map);
+        let mut config = ShardConfig::new(ShardId(1), ServerConfig::default());
+        config.handoff_retry_ticks = 1;
+        let mut shard = ZoneServer::new(config, map, link);
+
+        let client_addr: SocketAddr = "127.0.0.1:50010".parse().unwrap();
+        let conn = shard.server_mut().admit(client_addr).unwrap();
+        let slot = shard.server().state().get_client(conn).unwrap().entity_id;
+        shard.server_mut().state_mut().get_entity_mut(slot).unwrap().position = Position::new(20.0, 0.0, 8.0);
+
+        shard.tick();
+        assert_eq!(shard.server().client_count(), 0);
+        assert_eq!(shard.pending_handoffs(), 1);
+
+        // Five sends, then the player is restored and left alone for a while.
+        for _ in 0..6 {
+            shard.tick();
+        }
+        assert_eq!(shard.pending_handoffs(), 0);
+        let restored = shard.server().state().find_client_by_addr(client_addr).unwrap();
+        let slot = shard.server().state().get_client(restored).unwrap().entity_id;
+        assert!(shard.server().state().get_entity(slot).unwrap().position.x > 16.0);
+    }
+}
@@ -0,0 +1,594 @@
+//! # Zone Server
+//!
+//! An [`InfernoServer`] that owns part of the world and cooperates with the
+//! shards around it.
+//!
+//! ## Handoff
+//!
+//! ```text
+//! CLIENT                 SHARD A (old)                 SHARD B (new)
+//!   |                       |-- Handoff(state, token) -->| admit slot, same entity ID
+//!   |--- Input ------------>|-- Input(token) ----------->| (forwarded)
+//!   |                       |<-------- HandoffAck -------|
+//!   |<-- Redirect(B, token)-|                            |
+//!   |--- Resume(token) ------------------------------------>| bind address
+//!   |<-- Snapshot -------------------------------------------|
+//! ```
+//!
+//! The old shard drops the player as soon as the handoff is sent and keeps
+//! forwarding their inputs until they resume on the new shard. If the new
+//! shard refuses or never answers, the player is restored where they were.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::net::SocketAddr;
+use crate::integration::PlayerId;
+use crate::protocol::{
+    EntityState, Packet, PacketDeserializer, PacketHeader, PacketSerializer, PacketType, ShardRedirect,
+};
+use crate::server::{ConnectionId, EntityType, InfernoServer, NetworkCommand};
+use crate::MAX_PACKET_SIZE;
+use super::link::ShardLink;
+use super::map::ShardMap;
+use super::message::{MirrorBatch, MirroredEntity, PlayerHandoff, ShardMessage, HANDOFF_INPUTS};
+use super::{ShardConfig, ShardId};
+
+/// Account data that follows a player across shards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayerMeta {
+    /// Account-level player ID.
+    pub player_id: PlayerId,
+    /// Handle of the player's inventory in the persistence layer.
+    pub inventory_ref: u64,
+}
+
+/// A handoff waiting for the target's answer.
+#[derive(Clone, Copy, Debug)]
+struct OutgoingHandoff {
+    /// Target shard.
+    to: ShardId,
+    /// State sent (resent on retry, restored on failure).
+    handoff: PlayerHandoff,
+    /// Tick of the last send.
+    sent_at: u64,
+    /// Sends so far.
+    attempts: u32,
+}
+
+/// Where packets from a departed client go.
+#[derive(Clone, Copy, Debug)]
+struct Forward {
+    /// New shard.
+    to: ShardId,
+    /// Handoff token.
+    token: u64,
+    /// Redirect to repeat, once the handoff was acknowledged.
+    redirect: Option<ShardRedirect>,
+    /// Tick the redirect was last sent.
+    redirected_at: u64,
+    /// Tick after which the entry is dropped.
+    until: u64,
+}
+
+/// A player received from another shard.
+#[derive(Clone, Copy, Debug)]
+struct Incoming {
+    /// Local connection.
+    id: ConnectionId,
+    /// Whether the client has presented the token yet.
+    resumed: bool,
+}
+
+/// Local copy of a neighbour's entity.
+#[derive(Clone, Copy, Debug)]
+struct Ghost {
+    /// Entity slot in the local state.
+    slot: u32,
+    /// Tick of the last mirror update.
+    last_seen: u64,
+}
+
+/// A zone shard: one game server plus its links to neighbouring shards.
+pub struct ZoneServer<L: ShardLink> {
+    /// Configuration.
+    config: ShardConfig,
+    /// Region ownership, shared by the whole cluster.
+    map: ShardMap,
+    /// The game server simulating this shard's regions.
+    server: InfernoServer,
+    /// Transport to other shards.
+    link: L,
+    /// Account data of local players.
+    players: HashMap<ConnectionId, PlayerMeta>,
+    /// Handoffs awaiting an answer, by token.
+    outgoing: HashMap<u64, OutgoingHandoff>,
+    /// Departed clients, by address.
+    forwarding: HashMap<SocketAddr, Forward>,
+    /// Players received from other shards, by token.
+    incoming: HashMap<u64, Incoming>,
+    /// Players whose handoff failed, and the tick until which they stay.
+    backoff: HashMap<ConnectionId, u64>,
+    /// Mirrored entities, by entity ID.
+    ghosts: HashMap<u32, Ghost>,
+    /// Outgoing mirror batch per neighbour (reused every tick).
+    mirror_batches: HashMap<ShardId, MirrorBatch>,
+    /// Neighbour scratch buffer.
+    neighbours: Vec<ShardId>,
+    /// Handoff scratch buffer.
+    crossings: Vec<(ConnectionId, ShardId)>,
+    /// Random keys for handoff tokens.
+    token_keys: RandomState,
+    /// Tokens issued so far.
+    token_counter: u64,
+    /// Ticks run so far.
+    ticks: u64,
+}
+
+impl<L: ShardLink> ZoneServer<L> {
+    /// Creates a shard. Every shard in the cluster must use the same `map`.
+    #[must_use]
+    pub fn new(config: ShardConfig, map: ShardMap, link: L) -> Self {
+        let mut server = InfernoServer::new(config.server.clone());
+        // Disjoint entity ID ranges keep IDs stable across mirrors and handoffs.
+        server
+            .state_mut()
+            .set_next_entity_id((u32::from(config.shard_id.0) << ShardId::ENTITY_ID_BITS) | 1);
+
+        Self {
+            config,
+            map,
+            server,
+            link,
+            players: HashMap::new(),
+            outgoing: HashMap::new(),
+            forwarding: HashMap::new(),
+            incoming: HashMap::new(),
+            backoff: HashMap::new(),
+            ghosts: HashMap::new(),
+            mirror_batches: HashMap::new(),
+            neighbours: Vec::new(),
+            crossings: Vec::new(),
+            token_keys: RandomState::new(),
+            token_counter: 0,
+            ticks: 0,
+        }
+    }
+
+    /// Returns this shard's ID.
+    #[inline]
+    #[must_use]
+    pub fn shard_id(&self) -> ShardId {
+        self.config.shard_id
+    }
+
+    /// Returns the region map.
+    #[inline]
+    #[must_use]
+    pub fn map(&self) -> &ShardMap {
+        &self.map
+    }
+
+    /// Returns the game server.
+    #[inline]
+    #[must_use]
+    pub fn server(&self) -> &InfernoServer {
+        &self.server
+    }
+
+    /// Returns the game server mutably.
+    #[inline]
+    pub fn server_mut(&mut self) -> &mut InfernoServer {
+        &mut self.server
+    }
+
+    /// Records account data for a connected player, so it travels with
+    /// them on handoff.
+    pub fn attach_player(&mut self, id: ConnectionId, meta: PlayerMeta) {
+        self.players.insert(id, meta);
+    }
+
+    /// Returns account data for a local player.
+    #[must_use]
+    pub fn player_meta(&self, id: ConnectionId) -> Option<&PlayerMeta> {
+        self.players.get(&id)
+    }
+
+    /// Number of entities mirrored from neighbours.
+    #[must_use]
+    pub fn ghost_count(&self) -> usize {
+        self.ghosts.len()
+    }
+
+    /// Number of handoffs awaiting an answer.
+    #[must_use]
+    pub fn pending_handoffs(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Handles a datagram from a game client.
+    pub fn receive(&mut self, addr: SocketAddr, data: &[u8]) {
+        if self.forwarding.contains_key(&addr) {
+            self.forward(addr, data);
+        } else if data.first() == Some(&(PacketType::Resume as u8)) {
+            if let Ok(Packet::Resume(_, token)) = PacketDeserializer::new(data).deserialize() {
+                self.resume(addr, token);
+            }
+        } else {
+            self.server.receive(addr, data);
+        }
+    }
+
+    /// Runs one tick: shard traffic, simulation, handoffs, mirrors.
+    pub fn tick(&mut self) {
+        self.poll_link();
+        self.server.tick();
+        self.ticks += 1;
+
+        self.start_handoffs();
+        self.retry_handoffs();
+        self.send_mirrors();
+        self.expire();
+    }
+
+    /// Processes messages from other shards.
+    fn poll_link(&mut self) {
+        while let Some((from, message)) = self.link.recv() {
+            match message {
+                ShardMessage::Mirror(batch) => self.apply_mirror(&batch),
+                ShardMessage::Handoff(handoff) => self.accept_handoff(from, &handoff),
+                ShardMessage::HandoffAck { token } => self.complete_handoff(token),
+                ShardMessage::HandoffRefused { token } => {
+                    if let Some(outgoing) = self.outgoing.remove(&token) {
+                        tracing::info!("Shard {:?} refused handoff, restoring player", from);
+                        self.abort_handoff(&outgoing);
+                    }
+                }
+                ShardMessage::Input { token, input } => {
+                    let id = self.incoming.get(&token).map(|i| i.id);
+                    if let Some(client) = id.and_then(|id| self.server.state_mut().get_client_mut(id)) {
+                        client.add_input(input);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hands off every player standing well inside another shard's regions.
+    fn start_handoffs(&mut self) {
+        self.crossings.clear();
+        let state = self.server.state();
+        for client in state.iter_clients() {
+            let Some(entity) = state.get_entity(client.entity_id) else {
+                continue;
+            };
+            let target = self.map.owner_inside(&entity.position, self.config.handoff_hysteresis);
+            if let Some(target) = target.filter(|&t| t != self.config.shard_id) {
+                let backing_off = self.backoff.get(&client.id).is_some_and(|&until| self.ticks < until);
+                if !backing_off && self.map.endpoint(target).is_some() {
+                    self.crossings.push((client.id, target));
+                }
+            }
+        }
+
+        for i in 0..self.crossings.len() {
+            let (id, target) = self.crossings[i];
+            self.begin_handoff(id, target);
+        }
+    }
+
+    /// Sends a player's state to `to` and drops them locally.
+    fn begin_handoff(&mut self, id: ConnectionId, to: ShardId) {
+        let state = self.server.state();
+        let Some(client) = state.get_client(id) else {
+            return;
+        };
+        let Some(entity) = state.get_entity(client.entity_id) else {
+            return;
+        };
+        let meta = self.players.remove(&id).unwrap_or_default();
+
+        let mut handoff = PlayerHandoff {
+            token: self.token_keys.hash_one((self.config.shard_id.0, self.token_counter)),
+            inventory_ref: meta.inventory_ref,
+            player_id: meta.player_id,
+            entity: EntityState::from_components(entity.id, entity.position, entity.velocity, entity.health),
+            next_send_sequence: client.next_send_sequence,
+            last_recv_sequence: client.last_recv_sequence,
+            ..PlayerHandoff::default()
+        };
+        self.token_counter += 1;
+        handoff.set_client_addr(client.addr);
+
+        // Most recent inputs, oldest first.
+        let count = client.input_count.min(HANDOFF_INPUTS);
+        let history = client.input_history.len();
+        for (i, input) in handoff.inputs[..count].iter_mut().enumerate() {
+            *input = client.input_history[(client.input_write_index + history - count + i) % history];
+        }
+        handoff.input_count = count as u16;
+
+        let addr = client.addr;
+        self.link.send(to, &ShardMessage::Handoff(handoff));
+        self.server.release(id);
+
+        self.outgoing.insert(handoff.token, OutgoingHandoff {
+            to,
+            handoff,
+            sent_at: self.ticks,
+            attempts: 1,
+        });
+        self.forwarding.insert(addr, Forward {
+            to,
+            token: handoff.token,
+            redirect: None,
+            redirected_at: 0,
+            until: u64::MAX,
+        });
+        tracing::debug!("Handing off {} to shard {:?}", addr, to);
+    }
+
+    /// Resends unanswered handoffs; gives up after the configured attempts.
+    fn retry_handoffs(&mut self) {
+        let now = self.ticks;
+        let retry = self.config.handoff_retry_ticks;
+        let max_attempts = self.config.handoff_max_attempts;
+
+        let mut failed = Vec::new();
+        for outgoing in self.outgoing.values_mut() {
+            if now - outgoing.sent_at < retry {
+                continue;
+            }
+            if outgoing.attempts >= max_attempts {
+                failed.push(outgoing.handoff.token);
+                continue;
+            }
+            outgoing.attempts += 1;
+            outgoing.sent_at = now;
+            self.link.send(outgoing.to, &ShardMessage::Handoff(outgoing.handoff));
+        }
+
+        for token in failed {
+            if let Some(outgoing) = self.outgoing.remove(&token) {
+                tracing::warn!("Handoff to shard {:?} timed out, restoring player", outgoing.to);
+                self.abort_handoff(&outgoing);
+            }
+        }
+    }
+
+    /// The target admitted the player: point the client at it.
+    fn complete_handoff(&mut self, token: u64) {
+        let Some(outgoing) = self.outgoing.remove(&token) else {
+            return; // Duplicate ack for a retried handoff.
+        };
+        let Some(endpoint) = self.map.endpoint(outgoing.to) else {
+            return;
+        };
+        let redirect = ShardRedirect::new(token, endpoint.client_addr, outgoing.handoff.entity.entity_id);
+        let addr = outgoing.handoff.client_addr();
+
+        if let Some(forward) = self.forwarding.get_mut(&addr) {
+            forward.redirect = Some(redirect);
+            forward.redirected_at = self.ticks;
+            forward.until = self.ticks + self.config.forward_ticks;
+        }
+        self.send_redirect(addr, outgoing.handoff.next_send_sequence, &redirect);
+    }
+
+    /// The handoff failed: put the player back.
+    fn abort_handoff(&mut self, outgoing: &OutgoingHandoff) {
+        let addr = outgoing.handoff.client_addr();
+        self.forwarding.remove(&addr);
+        if let Some(id) = self.restore(&outgoing.handoff) {
+            // Stay put for a while rather than retrying straight away.
+            let wait = self.config.handoff_retry_ticks * u64::from(self.config.handoff_max_attempts);
+            self.backoff.insert(id, self.ticks + wait);
+        } else {
+            tracing::warn!("Could not restore {} after failed handoff", addr);
+        }
+    }
+
+    /// Admits a player handed over by `from`.
+    fn accept_handoff(&mut self, from: ShardId, handoff: &PlayerHandoff) {
+        if self.incoming.contains_key(&handoff.token) {
+            // Our ack was lost and the sender retried.
+            self.link.send(from, &ShardMessage::HandoffAck { token: handoff.token });
+            return;
+        }
+
+        // The player replaces the ghost we were showing for them.
+        if let Some(ghost) = self.ghosts.remove(&handoff.entity.entity_id) {
+            self.server.state_mut().despawn_entity(ghost.slot);
+        }
+
+        match self.restore(handoff) {
+            Some(id) => {
+                self.incoming.insert(handoff.token, Incoming { id, resumed: false });
+                self.link.send(from, &ShardMessage::HandoffAck { token: handoff.token });
+            }
+            None => self.link.send(from, &ShardMessage::HandoffRefused { token: handoff.token }),
+        }
+    }
+
+    /// Creates a local player from handoff state.
+    fn restore(&mut self, handoff: &PlayerHandoff) -> Option<ConnectionId> {
+        let id = self.server.admit(handoff.client_addr())?;
+        let state = self.server.state_mut();
+
+        let client = state.get_client_mut(id)?;
+        client.next_send_sequence = handoff.next_send_sequence;
+        client.last_recv_sequence = handoff.last_recv_sequence;
+        for input in handoff.inputs() {
+            client.add_input(*input);
+        }
+        let slot = client.entity_id;
+
+        if let Some(entity) = state.get_entity_mut(slot) {
+            entity.id = handoff.entity.entity_id;
+            entity.position = handoff.entity.position();
+            entity.velocity = handoff.entity.velocity();
+            entity.health = handoff.entity.health;
+        }
+
+        self.players.insert(id, PlayerMeta {
+            player_id: handoff.player_id,
+            inventory_ref: handoff.inventory_ref,
+        });
+        Some(id)
+    }
+
+    /// Binds a redirected client to its handed-off slot.
+    fn resume(&mut self, addr: SocketAddr, token: u64) {
+        let Some(incoming) = self.incoming.get_mut(&token) else {
+            return;
+        };
+        if incoming.resumed {
+            return; // Tokens are single-use.
+        }
+
+        let state = self.server.state_mut();
+        if state.find_client_by_addr(addr).is_some_and(|other| other != incoming.id) {
+            return;
+        }
+        let tick = state.current_tick();
+        if let Some(client) = state.get_client_mut(incoming.id) {
+            // The client may reach us through a different NAT mapping.
+            client.addr = addr;
+            client.last_recv_tick = tick;
+            incoming.resumed = true;
+        }
+    }
+
+    /// Handles a datagram from a client that was handed off.
+    fn forward(&mut self, addr: SocketAddr, data: &[u8]) {
+        let Some(forward) = self.forwarding.get(&addr).copied() else {
+            return;
+        };
+
+        if let Ok(Packet::Input(_, input)) = PacketDeserializer::new(data).deserialize() {
+            self.link.send(forward.to, &ShardMessage::Input { token: forward.token, input });
+        }
+
+        // The client is still talking to us: repeat the redirect, at most once a tick.
+        if let Some(redirect) = forward.redirect {
+            if forward.redirected_at != self.ticks {
+                if let Some(f) = self.forwarding.get_mut(&addr) {
+                    f.redirected_at = self.ticks;
+                }
+                self.send_redirect(addr, 0, &redirect);
+            }
+        }
+    }
+
+    /// Queues a redirect on the reliable channel.
+    fn send_redirect(&self, addr: SocketAddr, sequence: u16, redirect: &ShardRedirect) {
+        let mut serializer = PacketSerializer::new();
+        if serializer.serialize_redirect(&PacketHeader::new(sequence, 0, 0), redirect) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            data[..serializer.len()].copy_from_slice(serializer.as_slice());
+            self.server.send_command(NetworkCommand::SendReliable {
+                addr,
+                data,
+                len: serializer.len(),
+            });
+        }
+    }
+
+    /// Mirrors local entities near a border to the shards across it.
+    fn send_mirrors(&mut self) {
+        for batch in self.mirror_batches.values_mut() {
+            batch.clear();
+        }
+
+        for entity in self.server.state().iter_entities() {
+            if entity.mirrored {
+                continue;
+            }
+            self.map.neighbours_near(
+                &entity.position,
+                self.config.mirror_margin,
+                self.config.shard_id,
+                &mut self.neighbours,
+            );
+            for &neighbour in &self.neighbours {
+                let batch = self.mirror_batches.entry(neighbour).or_default();
+                if batch.is_full() {
+                    self.link.send(neighbour, &ShardMessage::Mirror(*batch));
+                    batch.clear();
+                }
+                batch.push(MirroredEntity {
+                    state: EntityState::from_components(entity.id, entity.position, entity.velocity, entity.health),
+                    entity_type: entity.entity_type as u8,
+                    _padding: [0; 3],
+                });
+            }
+        }
+
+        for (&neighbour, batch) in &self.mirror_batches {
+            if !batch.is_empty() {
+                self.link.send(neighbour, &ShardMessage::Mirror(*batch));
+            }
+        }
+    }
+
+    /// Creates or refreshes ghosts from a neighbour's mirror.
+    fn apply_mirror(&mut self, batch: &MirrorBatch) {
+        for mirrored in batch.as_slice() {
+            let id = mirrored.state.entity_id;
+            let state = self.server.state_mut();
+
+            let slot = if let Some(ghost) = self.ghosts.get_mut(&id) {
+                ghost.last_seen = self.ticks;
+                ghost.slot
+            } else {
+                // A late mirror of a player already handed to us.
+                if state.iter_entities().any(|e| e.id == id && !e.mirrored) {
+                    continue;
+                }
+                let Some(slot) = state.spawn_entity(entity_type_from_u8(mirrored.entity_type)) else {
+                    continue;
+                };
+                self.ghosts.insert(id, Ghost { slot, last_seen: self.ticks });
+                slot
+            };
+
+            if let Some(entity) = state.get_entity_mut(slot) {
+                entity.id = id;
+                entity.mirrored = true;
+                entity.position = mirrored.state.position();
+                entity.velocity = mirrored.state.velocity();
+                entity.health = mirrored.state.health;
+            }
+        }
+    }
+
+    /// Drops stale ghosts, finished forwards and departed players.
+    fn expire(&mut self) {
+        let now = self.ticks;
+        let ttl = self.config.mirror_ttl_ticks;
+        let state = self.server.state_mut();
+
+        self.ghosts.retain(|_, ghost| {
+            let alive = now - ghost.last_seen <= ttl;
+            if !alive {
+                state.despawn_entity(ghost.slot);
+            }
+            alive
+        });
+        self.forwarding.retain(|_, forward| now <= forward.until);
+        self.incoming.retain(|_, incoming| state.get_client(incoming.id).is_some());
+        self.players.retain(|&id, _| state.get_client(id).is_some());
+        self.backoff.retain(|&id, &mut until| now < until && state.get_client(id).is_some());
+    }
+}
+
+/// Decodes a mirrored entity type.
+const fn entity_type_from_u8(value: u8) -> EntityType {
+    match value {
+        1 => EntityType::Player,
+        2 => EntityType::Enemy,
+        3 => EntityType::Projectile,
+        4 => EntityType::Boss,
+        _ => EntityType::None,
+    }
+}
@@ -0,0 +1,126 @@
+//! Casting recorded matches to spectators.
+
+use std::net::SocketAddr;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use crate::protocol::{Packet, PacketDeserializer, PacketHeader, PacketSerializer, WorldSnapshot};
+use crate::server::NetworkCommand;
+use crate::MAX_PACKET_SIZE;
+use super::{send_ack, SpectatorConfig, SpectatorJoin, SpectatorService};
+
+/// A stream of world snapshots, one per tick (a replay file, a relay).
+pub trait SnapshotSource {
+    /// Returns the next tick's snapshot, or `None` once the stream ends.
+    fn next_snapshot(&mut self) -> Option<WorldSnapshot>;
+}
+
+/// Serves spectators from a [`SnapshotSource`] instead of a live server.
+///
+/// Speaks the same spectator protocol as [`InfernoServer`](crate::server::InfernoServer),
+/// so clients cannot tell a cast from a live match.
+pub struct ReplayCaster<S> {
+    /// Snapshot stream.
+    source: S,
+    /// Connected spectators.
+    spectators: SpectatorService,
+    /// Channel for sending network commands.
+    command_tx: Sender<NetworkCommand>,
+    /// Receiving end of the command channel, drained by the I/O layer.
+    command_rx: Receiver<NetworkCommand>,
+    /// Ticks run so far.
+    tick: u32,
+    /// Ticks held since the source ended, or `None` while it is running.
+    held: Option<usize>,
+}
+
+impl<S: SnapshotSource> ReplayCaster<S> {
+    /// Creates a caster playing `source` at `tick_rate` Hz.
+    #[must_use]
+    pub fn new(source: S, config: SpectatorConfig, tick_rate: u32) -> Self {
+        let (command_tx, command_rx) = bounded(10000);
+        Self {
+            source,
+            spectators: SpectatorService::new(config, tick_rate),
+            command_tx,
+            command_rx,
+            tick: 0,
+            held: None,
+        }
+    }
+
+    /// Returns the connected spectators.
+    #[must_use]
+    pub fn spectators(&self) -> &SpectatorService {
+        &self.spectators
+    }
+
+    /// Returns whether the source has run out of snapshots and the
+    /// delayed tail has been delivered.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.held.is_some_and(|held| held >= self.spectators.delay_ticks())
+    }
+
+    /// Handles a datagram from a spectator.
+    pub fn receive(&mut self, addr: SocketAddr, data: &[u8]) {
+        let Ok(packet) = PacketDeserializer::new(data).deserialize() else {
+            return;
+        };
+
+        match packet {
+            Packet::Spectate(_, request) => match self.spectators.join(addr, request, self.tick) {
+                Ok(SpectatorJoin::Joined(id)) => send_ack(&self.command_tx, addr, id),
+                Ok(SpectatorJoin::Updated(_)) => {}
+                Err(e) => {
+                    tracing::debug!("Spectator {} refused: {}", addr, e);
+                    self.send_disconnect(addr);
+                }
+            },
+            Packet::Heartbeat(_) => {
+                self.spectators.touch(addr, self.tick);
+            }
+            Packet::Disconnect(_) => {
+                self.spectators.leave(addr);
+            }
+            _ => {}
+        }
+    }
+
+    /// Advances the cast by one tick and queues every spectator's view.
+    ///
+    /// Once the source ends, the final snapshot is held for the delay so
+    /// the delayed tail still plays out, each snapshot once. After that
+    /// ticking does nothing.
+    pub fn tick(&mut self) {
+        if self.held.is_none() {
+            match self.source.next_snapshot() {
+                Some(snapshot) => self.spectators.record(&snapshot),
+                None => self.held = Some(0),
+            }
+        }
+        if let Some(held) = self.held {
+            if held >= self.spectators.delay_ticks() {
+                return;
+            }
+            self.spectators.hold();
+            self.held = Some(held + 1);
+        }
+
+        self.spectators.expire(self.tick);
+        self.spectators.deliver(&self.command_tx);
+        self.tick = self.tick.wrapping_add(1);
+    }
+
+    /// Takes the next outgoing command.
+    pub fn poll_command(&self) -> Option<NetworkCommand> {
+        self.command_rx.try_recv().ok()
+    }
+
+    fn send_disconnect(&self, addr: SocketAddr) {
+        let mut serializer = PacketSerializer::new();
+        if serializer.serialize_disconnect(&PacketHeader::new(0, 0, 0)) {
+            let mut data = [0u8; MAX_PACKET_SIZE];
+            data[..serializer.len()].copy_from_slice(serializer.as_slice());
+            let _ = self.command_tx.try_send(NetworkCommand::Send { addr, data, len: serializer.len() });
+        }
+    }
+}
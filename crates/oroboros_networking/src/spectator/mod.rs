@@ -0,0 +1,401 @@
+//! # Spectators
+//!
+//! Watch a match without playing in it.
+//!
+//! ## Design
+//!
+//! - Spectators have no entity in `ServerState` and their own connection
+//!   limit, so a full audience never locks players out
+//! - Snapshots can be delayed by a fixed time so spectators cannot feed
+//!   live positions to players ("ghosting")
+//! - Each spectator gets a view around its camera: a followed entity or a
+//!   free-fly position
+//! - [`ReplayCaster`] serves the same protocol from a recorded match
+//!
+//! ```text
+//! tick N snapshot ──▶ [ring of delay_ticks + 1] ──▶ snapshot N - delay
+//!                                                    │
+//!                                  per spectator:    ▼
+//!                                  entities near the camera ──▶ Send
+//! ```
+
+mod caster;
+
+pub use caster::{ReplayCaster, SnapshotSource};
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use thiserror::Error;
+use oroboros_core::Position;
+use crate::protocol::{CameraMode, PacketHeader, PacketSerializer, SpectateRequest, WorldSnapshot};
+use crate::server::NetworkCommand;
+use crate::MAX_PACKET_SIZE;
+
+/// Longest supported spectator delay.
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// Spectator configuration.
+#[derive(Clone, Debug)]
+pub struct SpectatorConfig {
+    /// Maximum concurrent spectators (separate from the player limit).
+    pub max_spectators: usize,
+    /// How far behind live play spectators see (zero for live).
+    pub delay: Duration,
+    /// Radius around the camera included in a spectator's view
+    /// (0 sends the whole snapshot).
+    pub view_radius: f32,
+    /// Ticks without a packet before a spectator is dropped.
+    pub timeout_ticks: u32,
+}
+
+impl Default for SpectatorConfig {
+    fn default() -> Self {
+        Self {
+            max_spectators: 64,
+            delay: Duration::ZERO,
+            view_radius: 0.0,
+            timeout_ticks: 300,
+        }
+    }
+}
+
+/// Spectator errors.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectatorError {
+    /// The spectator limit is reached.
+    #[error("spectator slots full ({max})")]
+    Full {
+        /// Configured limit.
+        max: usize,
+    },
+    /// The request names an unknown camera mode.
+    #[error("unknown camera mode {0}")]
+    InvalidMode(u8),
+}
+
+/// Unique identifier for a spectator connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpectatorId(pub u32);
+
+/// Outcome of a spectate request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpectatorJoin {
+    /// A new spectator was admitted.
+    Joined(SpectatorId),
+    /// An existing spectator moved its camera.
+    Updated(SpectatorId),
+}
+
+/// A spectator connection.
+#[derive(Clone, Copy, Debug)]
+pub struct Spectator {
+    /// Connection identifier.
+    pub id: SpectatorId,
+    /// Spectator's network address.
+    pub addr: SocketAddr,
+    /// Current camera.
+    pub camera: SpectateRequest,
+    /// Tick of the last packet received.
+    pub last_seen: u32,
+    /// Next sequence number to send.
+    pub next_sequence: u16,
+}
+
+/// Spectator connections and the delayed snapshot feed.
+pub struct SpectatorService {
+    /// Configuration.
+    config: SpectatorConfig,
+    /// Spectator slots (pre-allocated).
+    slots: Box<[Option<Spectator>]>,
+    /// Number of occupied slots.
+    count: usize,
+    /// Recent snapshots (ring buffer, `delay_ticks + 1` entries).
+    history: Box<[WorldSnapshot]>,
+    /// Next write index in `history`.
+    head: usize,
+    /// Snapshots recorded so far (saturating at `history.len()`).
+    recorded: usize,
+    /// View scratch buffer.
+    view: WorldSnapshot,
+    /// Serializer (reused).
+    serializer: PacketSerializer,
+}
+
+impl SpectatorService {
+    /// Creates the service for a server ticking at `tick_rate` Hz.
+    #[must_use]
+    pub fn new(config: SpectatorConfig, tick_rate: u32) -> Self {
+        let delay = config.delay.min(MAX_DELAY);
+        let delay_ticks = (delay.as_secs_f64() * f64::from(tick_rate)).round() as usize;
+
+        Self {
+            slots: vec![None; config.max_spectators].into_boxed_slice(),
+            count: 0,
+            history: vec![WorldSnapshot::default(); delay_ticks + 1].into_boxed_slice(),
+            head: 0,
+            recorded: 0,
+            view: WorldSnapshot::default(),
+            serializer: PacketSerializer::new(),
+            config,
+        }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub fn config(&self) -> &SpectatorConfig {
+        &self.config
+    }
+
+    /// Number of connected spectators.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Delay between live play and the spectator feed, in ticks.
+    #[must_use]
+    pub fn delay_ticks(&self) -> usize {
+        self.history.len() - 1
+    }
+
+    /// Admits a spectator, or updates its camera if already connected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpectatorError::InvalidMode`] for an unknown camera mode
+    /// and [`SpectatorError::Full`] when every slot is taken.
+    pub fn join(&mut self, addr: SocketAddr, camera: SpectateRequest, tick: u32) -> Result<SpectatorJoin, SpectatorError> {
+        if camera.mode().is_none() {
+            return Err(SpectatorError::InvalidMode(camera.mode));
+        }
+
+        if let Some(spectator) = self.find_mut(addr) {
+            spectator.camera = camera;
+            spectator.last_seen = tick;
+            return Ok(SpectatorJoin::Updated(spectator.id));
+        }
+
+        let max = self.config.max_spectators;
+        let index = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or(SpectatorError::Full { max })?;
+        let id = SpectatorId(index as u32);
+        self.slots[index] = Some(Spectator {
+            id,
+            addr,
+            camera,
+            last_seen: tick,
+            next_sequence: 0,
+        });
+        self.count += 1;
+        Ok(SpectatorJoin::Joined(id))
+    }
+
+    /// Removes a spectator. Returns false if `addr` was not spectating.
+    pub fn leave(&mut self, addr: SocketAddr) -> bool {
+        let Some(slot) = self.slots.iter_mut().find(|s| s.is_some_and(|s| s.addr == addr)) else {
+            return false;
+        };
+        *slot = None;
+        self.count -= 1;
+        true
+    }
+
+    /// Records that a packet arrived from `addr`. Returns false if `addr`
+    /// is not spectating.
+    pub fn touch(&mut self, addr: SocketAddr, tick: u32) -> bool {
+        self.find_mut(addr).map(|s| s.last_seen = tick).is_some()
+    }
+
+    /// Finds a spectator by address.
+    #[must_use]
+    pub fn find(&self, addr: SocketAddr) -> Option<&Spectator> {
+        self.slots.iter().flatten().find(|s| s.addr == addr)
+    }
+
+    /// Iterates connected spectators.
+    pub fn iter(&self) -> impl Iterator<Item = &Spectator> {
+        self.slots.iter().flatten()
+    }
+
+    /// Drops spectators silent for longer than the timeout.
+    pub fn expire(&mut self, tick: u32) {
+        let timeout = self.config.timeout_ticks;
+        for slot in self.slots.iter_mut() {
+            if slot.is_some_and(|s| tick.saturating_sub(s.last_seen) > timeout) {
+                *slot = None;
+                self.count -= 1;
+            }
+        }
+    }
+
+    /// Records a live snapshot.
+    pub fn record(&mut self, snapshot: &WorldSnapshot) {
+        self.history[self.head] = *snapshot;
+        self.head = (self.head + 1) % self.history.len();
+        self.recorded = (self.recorded + 1).min(self.history.len());
+    }
+
+    /// Repeats the newest snapshot (the source paused or ended).
+    pub fn hold(&mut self) {
+        if self.recorded > 0 {
+            let len = self.history.len();
+            let newest = self.history[(self.head + len - 1) % len];
+            self.record(&newest);
+        }
+    }
+
+    /// Returns the snapshot spectators should see now, once enough history
+    /// has been recorded to honour the delay.
+    #[must_use]
+    pub fn delayed(&self) -> Option<&WorldSnapshot> {
+        // With a full ring, the oldest entry sits at the write head.
+        (self.recorded == self.history.len()).then(|| &self.history[self.head])
+    }
+
+    /// Builds every spectator's view of the delayed snapshot and queues it
+    /// on `command_tx`.
+    ///
+    /// This is the hot path - ZERO ALLOCATIONS.
+    pub fn deliver(&mut self, command_tx: &crossbeam_channel::Sender<NetworkCommand>) {
+        if self.count == 0 {
+            return;
+        }
+        let Some(&snapshot) = self.delayed() else {
+            return;
+        };
+
+        for spectator in self.slots.iter_mut().flatten() {
+            build_view(&spectator.camera, self.config.view_radius, &snapshot, &mut self.view);
+
+            let header = PacketHeader::new(spectator.next_sequence, 0, 0);
+            spectator.next_sequence = spectator.next_sequence.wrapping_add(1);
+            if self.serializer.serialize_snapshot(&header, &self.view) {
+                let mut data = [0u8; MAX_PACKET_SIZE];
+                data[..self.serializer.len()].copy_from_slice(self.serializer.as_slice());
+                let _ = command_tx.try_send(NetworkCommand::Send {
+                    addr: spectator.addr,
+                    data,
+                    len: self.serializer.len(),
+                });
+            }
+        }
+    }
+
+    fn find_mut(&mut self, addr: SocketAddr) -> Option<&mut Spectator> {
+        self.slots.iter_mut().flatten().find(|s| s.addr == addr)
+    }
+}
+
+/// Fills `out` with the part of `snapshot` around the camera.
+///
+/// A followed entity is listed first so clients can lock onto it. If the
+/// target is not in the snapshot the camera stays where it was asked to be.
+fn build_view(camera: &SpectateRequest, radius: f32, snapshot: &WorldSnapshot, out: &mut WorldSnapshot) {
+    *out = WorldSnapshot::empty(snapshot.tick);
+    out.dragon = snapshot.dragon;
+
+    let mut center = camera.camera();
+    let mut followed = None;
+    if camera.mode() == Some(CameraMode::Follow) {
+        if let Some(target) = snapshot.entities().iter().find(|e| e.entity_id == camera.target_entity) {
+            center = target.position();
+            followed = Some(target.entity_id);
+            out.add_entity(*target);
+        }
+    }
+
+    let radius_sq = radius * radius;
+    for entity in snapshot.entities() {
+        if followed == Some(entity.entity_id) {
+            continue;
+        }
+        if radius <= 0.0 || distance_sq(&center, &entity.position()) <= radius_sq {
+            out.add_entity(*entity);
+        }
+    }
+}
+
+/// Queues a spectator's connect acknowledgement.
+pub(crate) fn send_ack(command_tx: &crossbeam_channel::Sender<NetworkCommand>, addr: SocketAddr, id: SpectatorId) {
+    let mut serializer = PacketSerializer::new();
+    if serializer.serialize_connect_ack(&PacketHeader::new(0, 0, 0), id.0) {
+        let mut data = [0u8; MAX_PACKET_SIZE];
+        data[..serializer.len()].copy_from_slice(serializer.as_slice());
+        let _ = command_tx.try_send(NetworkCommand::Send { addr, data, len: serializer.len() });
+    }
+}
+
+fn distance_sq(a: &Position, b: &Position) -> f32 {
+    let (dx, dy, dz) = (a.x - b.x, a.y - b.y, a.z - b.z);
+    dx * dx + dy * dy + dz * dz
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::EntityState;
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([10, 0, 0, 1], port))
+    }
+
+    fn snapshot(tick: u32, xs: &[f32]) -> WorldSnapshot {
+        let mut snapshot = WorldSnapshot::empty(tick);
+        for (i, &x) in xs.iter().enumerate() {
+            snapshot.add_entity(EntityState { entity_id: i as u32 + 1, pos_x: x, ..EntityState::default() });
+        }
+        snapshot
+    }
+
+    #[test]
+    fn test_separate_limit() {
+        let config = SpectatorConfig { max_spectators: 2, ..SpectatorConfig::default() };
+        let mut service = SpectatorService::new(config, 60);
+        let camera = SpectateRequest::free_fly(Position::new(0.0, 10.0, 0.0));
+
+        assert!(matches!(service.join(addr(1), camera, 0), Ok(SpectatorJoin::Joined(_))));
+        assert!(matches!(service.join(addr(1), camera, 0), Ok(SpectatorJoin::Updated(_))));
+        assert!(service.join(addr(2), camera, 0).is_ok());
+        assert_eq!(service.join(addr(3), camera, 0), Err(SpectatorError::Full { max: 2 }));
+
+        assert!(service.leave(addr(1)));
+        assert!(service.join(addr(3), camera, 0).is_ok());
+        assert_eq!(service.count(), 2);
+
+        service.expire(1_000);
+        assert_eq!(service.count(), 0);
+    }
+
+    #[test]
+    fn test_delay() {
+        let config = SpectatorConfig { delay: Duration::from_millis(50), ..SpectatorConfig::default() };
+        let mut service = SpectatorService::new(config, 60);
+        assert_eq!(service.delay_ticks(), 3);
+
+        for tick in 0..3 {
+            service.record(&snapshot(tick, &[]));
+            assert!(service.delayed().is_none());
+        }
+        for tick in 3..10 {
+            service.record(&snapshot(tick, &[]));
+            assert_eq!(service.delayed().unwrap().tick, tick - 3);
+        }
+    }
+
+    #[test]
+    fn test_follow_view() {
+        let world = snapshot(1, &[0.0, 100.0, 104.0, 300.0]);
+        let mut view = WorldSnapshot::default();
+
+        build_view(&SpectateRequest::follow(2), 10.0, &world, &mut view);
+        let ids: Vec<u32> = view.entities().iter().map(|e| e.entity_id).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        // Free-fly with radius 0 sees everything.
+        build_view(&SpectateRequest::free_fly(Position::default()), 0.0, &world, &mut view);
+        assert_eq!(view.entity_count, 4);
+    }
+}
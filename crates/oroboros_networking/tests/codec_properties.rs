@@ -0,0 +1,264 @@
+//! # Codec Property Tests
+//!
+//! The packet codec parses untrusted bytes from the internet. These
+//! properties check that every packet survives a round trip and that
+//! arbitrary, truncated or delta-encoded input decodes to a value or a
+//! `DecodeError` - never a panic.
+//!
+//! The fuzz corpus in `fuzz/corpus/` is replayed here as a regression test.
+
+use std::path::Path;
+
+use bytemuck::Pod;
+use oroboros_networking::protocol::{
+    BitPacker, BitReader, ChatMessage, DecodeError, DeltaCompressor, DeltaDecompressor, DeltaSnapshot, EntityState,
+    Packet, PacketDeserializer, PacketHeader, PacketSerializer, SpectateRequest, WorldSnapshot,
+};
+use proptest::prelude::*;
+
+/// Any value of a Pod type.
+fn pod<T: Pod + std::fmt::Debug>() -> impl Strategy<Value = T> {
+    prop::collection::vec(any::<u8>(), std::mem::size_of::<T>()).prop_map(|bytes| bytemuck::pod_read_unaligned(&bytes))
+}
+
+fn snapshot() -> impl Strategy<Value = WorldSnapshot> {
+    (any::<u32>(), pod(), prop::collection::vec(pod::<EntityState>(), 0..=WorldSnapshot::MAX_ENTITIES)).prop_map(
+        |(tick, dragon, entities)| {
+            let mut snapshot = WorldSnapshot::empty(tick);
+            snapshot.dragon = dragon;
+            for entity in entities {
+                snapshot.add_entity(entity);
+            }
+            snapshot
+        },
+    )
+}
+
+fn delta() -> impl Strategy<Value = DeltaSnapshot> {
+    (
+        any::<u32>(),
+        any::<u32>(),
+        prop::collection::vec(pod::<EntityState>(), 0..=DeltaSnapshot::MAX_CHANGES),
+        prop::collection::vec(any::<u32>(), 0..=DeltaSnapshot::MAX_REMOVED),
+    )
+        .prop_map(|(a, b, changed, removed)| {
+            let mut delta = DeltaSnapshot::empty(a.max(b), a.min(b));
+            delta.changed[..changed.len()].copy_from_slice(&changed);
+            delta.removed[..removed.len()].copy_from_slice(&removed);
+            delta.changed_count = changed.len() as u16;
+            delta.removed_count = removed.len() as u16;
+            delta
+        })
+}
+
+fn chat() -> impl Strategy<Value = ChatMessage> {
+    (pod::<ChatMessage>(), 0u8..=4, 0..=ChatMessage::MAX_TEXT_BYTES as u16).prop_map(|(mut chat, channel, len)| {
+        chat.channel = channel;
+        chat.len = len;
+        chat
+    })
+}
+
+fn spectate() -> impl Strategy<Value = SpectateRequest> {
+    (pod::<SpectateRequest>(), 0u8..=1).prop_map(|(mut request, mode)| {
+        request.mode = mode;
+        request
+    })
+}
+
+/// Any well-formed packet.
+fn packet() -> impl Strategy<Value = Packet> {
+    let header = pod::<PacketHeader>;
+    prop_oneof![
+        (header(), pod()).prop_map(|(h, input)| Packet::Input(h, input)),
+        (header(), snapshot()).prop_map(|(h, s)| Packet::Snapshot(h, s)),
+        (header(), delta()).prop_map(|(h, d)| Packet::Delta(h, d)),
+        (header(), pod()).prop_map(|(h, dragon)| Packet::Dragon(h, dragon)),
+        (header(), pod()).prop_map(|(h, hit)| Packet::Hit(h, hit)),
+        header().prop_map(Packet::Connect),
+        (header(), any::<u32>()).prop_map(|(h, id)| Packet::ConnectAck(h, id)),
+        header().prop_map(Packet::Heartbeat),
+        header().prop_map(Packet::Disconnect),
+        (header(), chat()).prop_map(|(h, chat)| Packet::Chat(h, chat)),
+        (header(), pod()).prop_map(|(h, token)| Packet::Join(h, token)),
+        (header(), pod()).prop_map(|(h, redirect)| Packet::Redirect(h, redirect)),
+        (header(), any::<u64>()).prop_map(|(h, token)| Packet::Resume(h, token)),
+        (header(), spectate()).prop_map(|(h, request)| Packet::Spectate(h, request)),
+    ]
+}
+
+fn encode(packet: &Packet) -> Vec<u8> {
+    let mut serializer = PacketSerializer::new();
+    assert!(serializer.serialize_packet(packet), "failed to encode {:?}", packet.packet_type());
+    serializer.as_slice().to_vec()
+}
+
+/// Decodes arbitrary bytes; anything accepted must re-encode to a stable form.
+fn check_decode(data: &[u8]) {
+    if let Ok(packet) = PacketDeserializer::new(data).deserialize() {
+        let encoded = encode(&packet);
+        let again = PacketDeserializer::new(&encoded).deserialize().expect("re-encoded packet decodes");
+        assert_eq!(encode(&again), encoded);
+    }
+}
+
+/// Decodes a `[base len: u16][base snapshot packet][delta packet]` input and
+/// applies the delta to the base.
+fn check_delta(data: &[u8]) {
+    if data.len() < 2 {
+        return;
+    }
+    let (len, rest) = data.split_at(2);
+    let (base, delta) = rest.split_at(usize::from(u16::from_le_bytes([len[0], len[1]])).min(rest.len()));
+
+    let mut decompressor = DeltaDecompressor::new();
+    if let Ok(Packet::Snapshot(_, snapshot)) = PacketDeserializer::new(base).deserialize() {
+        decompressor.set_base(&snapshot);
+    }
+    if let Ok(Packet::Delta(_, delta)) = PacketDeserializer::new(delta).deserialize() {
+        if let Ok(rebuilt) = decompressor.decompress(&delta) {
+            assert!(rebuilt.entity_count as usize <= WorldSnapshot::MAX_ENTITIES);
+        }
+    }
+}
+
+/// Reads values of the widths named by the first bytes from the rest.
+fn check_bits(data: &[u8]) {
+    let (widths, bits) = data.split_at(data.len() / 4);
+    let mut reader = BitReader::new(bits);
+    for &bits in widths {
+        match reader.read_bits(bits) {
+            Ok(value) => assert!(bits == 32 || value >> bits == 0),
+            Err(DecodeError::InvalidBitWidth(_)) => assert!(bits == 0 || bits > 32),
+            Err(DecodeError::OutOfBits { .. }) => break,
+            Err(e) => panic!("unexpected bit reader error {e}"),
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_packet_roundtrip(packet in packet()) {
+        let encoded = encode(&packet);
+        let decoded = PacketDeserializer::new(&encoded).deserialize().expect("well-formed packet decodes");
+        prop_assert_eq!(decoded.packet_type(), packet.packet_type());
+        prop_assert_eq!(encode(&decoded), encoded);
+    }
+
+    #[test]
+    fn prop_truncated_packet_rejected(packet in packet(), cut in any::<prop::sample::Index>()) {
+        let encoded = encode(&packet);
+        let len = cut.index(encoded.len());
+        let result = PacketDeserializer::new(&encoded[..len]).deserialize();
+        prop_assert!(matches!(result, Err(DecodeError::Truncated { .. })), "{:?}", result);
+    }
+
+    #[test]
+    fn prop_arbitrary_bytes_never_panic(data in prop::collection::vec(any::<u8>(), 0..1300)) {
+        check_decode(&data);
+    }
+
+    #[test]
+    fn prop_arbitrary_payload_never_panics(kind in 0u8..16, data in prop::collection::vec(any::<u8>(), 0..1300)) {
+        // Random bytes rarely hit a valid type byte; pin it instead.
+        let mut data = data;
+        data.insert(0, kind);
+        check_decode(&data);
+    }
+
+    #[test]
+    fn prop_bits_roundtrip(values in prop::collection::vec((any::<u32>(), 1u8..=32), 0..200)) {
+        let mut packer = BitPacker::new();
+        for &(value, bits) in &values {
+            prop_assert!(packer.write_bits(value, bits));
+        }
+
+        let mut reader = BitReader::new(packer.as_slice());
+        for &(value, bits) in &values {
+            let mask = u32::MAX >> (32 - u32::from(bits));
+            prop_assert_eq!(reader.read_bits(bits), Ok(value & mask));
+        }
+        prop_assert!(reader.remaining_bits() < 8);
+    }
+
+    #[test]
+    fn prop_arbitrary_bits_never_panic(data in prop::collection::vec(any::<u8>(), 0..512)) {
+        check_bits(&data);
+    }
+
+    #[test]
+    fn prop_delta_rebuilds_entity_set(
+        ids in prop::collection::btree_set(0u32..64, 0..=WorldSnapshot::MAX_ENTITIES),
+        next_ids in prop::collection::btree_set(0u32..64, 0..=WorldSnapshot::MAX_ENTITIES),
+        moved in any::<f32>().prop_filter("finite", |x| x.is_finite()),
+    ) {
+        let build = |tick: u32, ids: &std::collections::BTreeSet<u32>, x: f32| {
+            let mut snapshot = WorldSnapshot::empty(tick);
+            for &entity_id in ids {
+                snapshot.add_entity(EntityState { entity_id, pos_x: x, ..EntityState::default() });
+            }
+            snapshot
+        };
+        let prev = build(1, &ids, 0.0);
+        let next = build(2, &next_ids, moved);
+
+        let mut compressor = DeltaCompressor::new();
+        prop_assert!(compressor.compress(&prev).is_none());
+        let Some(delta) = compressor.compress(&next) else {
+            // Full snapshot chosen instead.
+            return Ok(());
+        };
+
+        // Through the wire and back.
+        let mut serializer = PacketSerializer::new();
+        prop_assert!(serializer.serialize_delta(&PacketHeader::new(0, 0, 0), &delta));
+        let Ok(Packet::Delta(_, delta)) = PacketDeserializer::new(serializer.as_slice()).deserialize() else {
+            return Err(TestCaseError::fail("delta did not decode"));
+        };
+
+        let mut decompressor = DeltaDecompressor::new();
+        decompressor.set_base(&prev);
+        let rebuilt = decompressor.decompress(&delta).expect("delta applies to its base");
+        let mut rebuilt_ids: Vec<u32> = rebuilt.entities().iter().map(|e| e.entity_id).collect();
+        rebuilt_ids.sort_unstable();
+        prop_assert_eq!(rebuilt_ids, next_ids.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn prop_arbitrary_delta_never_panics(data in prop::collection::vec(any::<u8>(), 0..2400)) {
+        check_delta(&data);
+    }
+}
+
+/// Runs every checked-in corpus file for a fuzz target through `check`.
+fn replay_corpus(target: &str, check: fn(&[u8])) -> usize {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus").join(target);
+    let mut count = 0;
+    for entry in std::fs::read_dir(&dir).unwrap_or_else(|e| panic!("{}: {e}", dir.display())) {
+        let data = std::fs::read(entry.unwrap().path()).unwrap();
+        check(&data);
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn test_fuzz_corpus_regressions() {
+    assert!(replay_corpus("decode_packet", check_decode) > 0);
+    assert!(replay_corpus("delta", check_delta) > 0);
+    assert!(replay_corpus("bit_reader", check_bits) > 0);
+}
+
+#[test]
+fn test_fuzz_corpus_covers_every_packet_type() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/decode_packet");
+    let mut types: Vec<u8> = std::fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| std::fs::read(entry.unwrap().path()).ok())
+        .filter_map(|data| PacketDeserializer::new(&data).deserialize().ok())
+        .map(|packet| packet.packet_type() as u8)
+        .collect();
+    types.sort_unstable();
+    types.dedup();
+    assert_eq!(types, (0..=13).collect::<Vec<u8>>());
+}
@@ -6,108 +6,587 @@
 //! - Temperature (from latitude and elevation)
 //! - Humidity (from a separate noise channel)
 //! - Elevation (from terrain noise)
+//!
+//! Which biome owns which climate is data: a [`BiomeRegistry`] loaded from
+//! `data/schemas/world/biomes.toml` maps climate boxes to biomes, so new
+//! biomes need no recompile. The shipped file is embedded in the binary
+//! and used unless another registry is given.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use oroboros_core::blocks::{BlockConfigError, BlockRegistry};
+use oroboros_core::embedded::EmbeddedData;
+use serde::Deserialize;
+use thiserror::Error;
 
 use crate::noise::{SimplexNoise, WorldSeed};
 
-/// Biome types in the world.
+/// The shipped biome definitions.
+const SHIPPED_BIOMES: &str = include_str!("../../../data/schemas/world/biomes.toml");
+
+/// Biome ID. Built-in biomes have constants; others come from the
+/// [`BiomeRegistry`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-#[repr(u8)]
-pub enum Biome {
+#[repr(transparent)]
+pub struct Biome(pub u8);
+
+impl Biome {
     /// Deep ocean (elevation < -0.5)
-    DeepOcean = 0,
+    pub const DEEP_OCEAN: Self = Self(0);
     /// Shallow ocean (elevation < -0.2)
-    Ocean = 1,
+    pub const OCEAN: Self = Self(1);
     /// Beach/coastline
-    Beach = 2,
+    pub const BEACH: Self = Self(2);
     /// Plains/grassland
-    Plains = 3,
+    pub const PLAINS: Self = Self(3);
     /// Forest
-    Forest = 4,
+    pub const FOREST: Self = Self(4);
     /// Dense jungle
-    Jungle = 5,
+    pub const JUNGLE: Self = Self(5);
     /// Arid desert
-    Desert = 6,
+    pub const DESERT: Self = Self(6);
     /// Cold tundra
-    Tundra = 7,
+    pub const TUNDRA: Self = Self(7);
     /// Snowy taiga forest
-    Taiga = 8,
+    pub const TAIGA: Self = Self(8);
     /// High mountains
-    Mountains = 9,
+    pub const MOUNTAINS: Self = Self(9);
     /// Snowy peaks
-    SnowyPeaks = 10,
+    pub const SNOWY_PEAKS: Self = Self(10);
     /// Swamp/wetland
-    Swamp = 11,
+    pub const SWAMP: Self = Self(11);
     /// Savanna grassland
-    Savanna = 12,
+    pub const SAVANNA: Self = Self(12);
     /// Volcanic/badlands
-    Badlands = 13,
+    pub const BADLANDS: Self = Self(13);
 }
 
-impl Biome {
-    /// Returns the block ID for the surface of this biome.
+/// Why biome definitions could not be loaded.
+#[derive(Error, Debug)]
+pub enum BiomeConfigError {
+    /// Reading the file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid TOML or is missing fields.
+    #[error("invalid biome config: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// Sea level is not below the maximum height.
+    #[error("sea level {sea_level} must be below max height {max_height}")]
+    Heights {
+        /// Configured sea level.
+        sea_level: i32,
+        /// Configured maximum height.
+        max_height: i32,
+    },
+    /// Two biomes share an ID.
+    #[error("biome ID {0} is defined twice")]
+    DuplicateId(u8),
+    /// A region gives two lower or two upper bounds on one axis.
+    #[error("biome {biome:?} bounds {axis} twice on one side")]
+    ConflictingBounds {
+        /// Biome name.
+        biome: String,
+        /// `"elevation"`, `"temperature"` or `"moisture"`.
+        axis: &'static str,
+    },
+    /// A region's range on one axis contains no value.
+    #[error("biome {biome:?} has an empty {axis} range")]
+    EmptyRange {
+        /// Biome name.
+        biome: String,
+        /// `"elevation"`, `"temperature"` or `"moisture"`.
+        axis: &'static str,
+    },
+    /// A climate falls in regions of two biomes, or twice in one.
+    #[error("biomes {first:?} and {second:?} overlap at {climate:?}")]
+    Overlap {
+        /// Earlier biome in the file.
+        first: String,
+        /// Later biome in the file.
+        second: String,
+        /// A point in both, as `[elevation, temperature, moisture]`.
+        climate: [f64; 3],
+    },
+    /// A climate falls in no region.
+    #[error("no biome covers {0:?}")]
+    Uncovered([f64; 3]),
+}
+
+/// A range of one climate value. Each bound either includes or excludes
+/// its own value, so `x < 0.5` and `x > 0.5` thresholds both have a range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClimateRange {
+    /// Lower bound.
+    pub min: f64,
+    /// Whether `min` itself is in the range.
+    pub min_inclusive: bool,
+    /// Upper bound.
+    pub max: f64,
+    /// Whether `max` itself is in the range.
+    pub max_inclusive: bool,
+}
+
+impl ClimateRange {
+    /// The whole axis.
+    pub const ALL: Self = Self {
+        min: f64::NEG_INFINITY,
+        min_inclusive: true,
+        max: f64::INFINITY,
+        max_inclusive: true,
+    };
+
+    /// Creates a half-open range `[min, max)` from optional bounds; `None`
+    /// is unbounded (and includes the infinity).
     #[must_use]
-    pub const fn surface_block(self) -> u32 {
-        match self {
-            Self::DeepOcean | Self::Ocean => 10, // Water
-            Self::Beach => 11,                   // Sand
-            Self::Plains | Self::Savanna => 1,   // Grass
-            Self::Forest => 1,                   // Grass
-            Self::Jungle => 12,                  // Jungle grass
-            Self::Desert => 11,                  // Sand
-            Self::Tundra => 13,                  // Frozen dirt
-            Self::Taiga => 13,                   // Frozen dirt
-            Self::Mountains => 2,                // Stone
-            Self::SnowyPeaks => 14,              // Snow
-            Self::Swamp => 15,                   // Mud
-            Self::Badlands => 16,                // Red sand
+    pub fn new(min: Option<f64>, max: Option<f64>) -> Self {
+        Self {
+            min: min.unwrap_or(f64::NEG_INFINITY),
+            min_inclusive: true,
+            max: max.unwrap_or(f64::INFINITY),
+            max_inclusive: max.is_none(),
         }
     }
 
-    /// Returns whether this biome can have trees.
+    /// Checks if `value` is in the range.
+    #[inline]
     #[must_use]
-    pub const fn has_trees(self) -> bool {
-        matches!(
-            self,
-            Self::Forest | Self::Jungle | Self::Taiga | Self::Swamp
-        )
+    pub fn contains(self, value: f64) -> bool {
+        let above_min = if self.min_inclusive { self.min <= value } else { self.min < value };
+        let below_max = if self.max_inclusive { value <= self.max } else { value < self.max };
+        above_min && below_max
+    }
+
+    /// Checks if no value is in the range.
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        !(self.min < self.max || (self.min <= self.max && self.min_inclusive && self.max_inclusive))
+    }
+}
+
+/// A box of climate owned by one biome.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClimateRegion {
+    /// Elevation range.
+    pub elevation: ClimateRange,
+    /// Temperature range.
+    pub temperature: ClimateRange,
+    /// Moisture (humidity) range.
+    pub moisture: ClimateRange,
+}
+
+impl ClimateRegion {
+    /// Checks if a climate is in the region.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, elevation: f64, temperature: f64, moisture: f64) -> bool {
+        self.elevation.contains(elevation)
+            && self.temperature.contains(temperature)
+            && self.moisture.contains(moisture)
+    }
+
+    /// The three ranges with their config names.
+    fn axes(&self) -> [(&'static str, ClimateRange); 3] {
+        [
+            ("elevation", self.elevation),
+            ("temperature", self.temperature),
+            ("moisture", self.moisture),
+        ]
     }
+}
+
+/// One biome's definition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BiomeDef {
+    /// Biome ID.
+    pub id: Biome,
+    /// Display name.
+    pub name: String,
+    /// Block ID of the surface layer.
+    pub surface_block: u16,
+    /// Average tree density (0-100).
+    pub tree_density: u8,
+    /// Ore abundance relative to the default.
+    pub ore_multiplier: f32,
+    /// Climates classified as this biome.
+    pub regions: Vec<ClimateRegion>,
+}
+
+/// `biomes.toml` as written.
+#[derive(Deserialize)]
+struct BiomeFile {
+    /// `[generation]`.
+    generation: GenerationTable,
+    /// `[[biome]]`.
+    #[serde(default)]
+    biome: Vec<BiomeTable>,
+}
+
+/// `[generation]`.
+#[derive(Deserialize)]
+struct GenerationTable {
+    /// Sea level.
+    sea_level: i32,
+    /// Maximum terrain height.
+    max_height: i32,
+}
+
+/// One `[[biome]]`.
+#[derive(Deserialize)]
+struct BiomeTable {
+    /// Biome ID.
+    id: u8,
+    /// Display name.
+    name: String,
+    /// Surface block ID.
+    surface_block: u16,
+    /// Tree density.
+    #[serde(default)]
+    tree_density: u8,
+    /// Ore multiplier.
+    #[serde(default)]
+    ore_multiplier: f32,
+    /// `[[biome.region]]`.
+    #[serde(default)]
+    region: Vec<RegionTable>,
+}
+
+/// One `[[biome.region]]`. `min_*` and `at_most_*` include their value,
+/// `above_*` and `max_*` exclude it.
+#[derive(Deserialize)]
+struct RegionTable {
+    /// Inclusive lower elevation bound.
+    min_elevation: Option<f64>,
+    /// Exclusive lower elevation bound.
+    above_elevation: Option<f64>,
+    /// Exclusive upper elevation bound.
+    max_elevation: Option<f64>,
+    /// Inclusive upper elevation bound.
+    at_most_elevation: Option<f64>,
+    /// Inclusive lower temperature bound.
+    min_temperature: Option<f64>,
+    /// Exclusive lower temperature bound.
+    above_temperature: Option<f64>,
+    /// Exclusive upper temperature bound.
+    max_temperature: Option<f64>,
+    /// Inclusive upper temperature bound.
+    at_most_temperature: Option<f64>,
+    /// Inclusive lower moisture bound.
+    min_moisture: Option<f64>,
+    /// Exclusive lower moisture bound.
+    above_moisture: Option<f64>,
+    /// Exclusive upper moisture bound.
+    max_moisture: Option<f64>,
+    /// Inclusive upper moisture bound.
+    at_most_moisture: Option<f64>,
+}
+
+impl RegionTable {
+    /// Builds the region, rejecting an axis bounded twice on one side.
+    fn region(&self, biome: &str) -> Result<ClimateRegion, BiomeConfigError> {
+        let range = |axis, min, above, max, at_most| {
+            climate_range(min, above, max, at_most).ok_or_else(|| BiomeConfigError::ConflictingBounds {
+                biome: biome.to_owned(),
+                axis,
+            })
+        };
+        Ok(ClimateRegion {
+            elevation: range("elevation", self.min_elevation, self.above_elevation, self.max_elevation, self.at_most_elevation)?,
+            temperature: range(
+                "temperature",
+                self.min_temperature,
+                self.above_temperature,
+                self.max_temperature,
+                self.at_most_temperature,
+            )?,
+            moisture: range("moisture", self.min_moisture, self.above_moisture, self.max_moisture, self.at_most_moisture)?,
+        })
+    }
+}
+
+/// Range from one axis's config bounds, or `None` if a side has two.
+fn climate_range(min: Option<f64>, above: Option<f64>, max: Option<f64>, at_most: Option<f64>) -> Option<ClimateRange> {
+    let (min, min_inclusive) = match (min, above) {
+        (Some(_), Some(_)) => return None,
+        (Some(min), None) => (min, true),
+        (None, Some(above)) => (above, false),
+        (None, None) => (f64::NEG_INFINITY, true),
+    };
+    let (max, max_inclusive) = match (max, at_most) {
+        (Some(_), Some(_)) => return None,
+        (Some(max), None) => (max, false),
+        (None, Some(at_most)) => (at_most, true),
+        (None, None) => (f64::INFINITY, true),
+    };
+    Some(ClimateRange {
+        min,
+        min_inclusive,
+        max,
+        max_inclusive,
+    })
+}
+
+/// Validated biome definitions and the terrain settings they go with.
+///
+/// Every climate maps to exactly one biome: construction fails on
+/// overlapping or missing climate ranges.
+///
+/// ```rust,ignore
+/// let biomes = Arc::new(BiomeRegistry::load("mods/biomes.toml")?);
+/// let generator = ChunkGenerator::from_preset(seed, GeneratorPreset::Terrain).with_biomes(biomes);
+/// ```
+#[derive(Debug)]
+pub struct BiomeRegistry {
+    /// Sea level.
+    sea_level: i32,
+    /// Maximum terrain height.
+    max_height: i32,
+    /// Definitions in file order.
+    biomes: Vec<BiomeDef>,
+    /// Index into `biomes` by ID.
+    lookup: [Option<u8>; 256],
+    /// Every region with its biome, flattened for classification.
+    regions: Vec<(Biome, ClimateRegion)>,
+}
 
-    /// Returns the average tree density (0-100).
+impl BiomeRegistry {
+    /// Returns the registry built from the shipped `biomes.toml`.
+    ///
+    /// The climate coverage check runs once, on the first call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shipped biomes overlap or leave part of the climate
+    /// space uncovered.
     #[must_use]
-    pub const fn tree_density(self) -> u8 {
-        match self {
-            Self::Forest => 50,
-            Self::Jungle => 80,
-            Self::Taiga => 40,
-            Self::Swamp => 30,
-            Self::Plains => 5,
-            Self::Savanna => 10,
-            _ => 0,
+    pub fn shipped() -> Arc<Self> {
+        static SHIPPED: EmbeddedData<BiomeRegistry, BiomeConfigError> =
+            EmbeddedData::new("biomes.toml", SHIPPED_BIOMES, BiomeRegistry::from_toml);
+        SHIPPED.get()
+    }
+
+    /// Loads definitions from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or the definitions are
+    /// invalid (see [`from_defs`](Self::from_defs)).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BiomeConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses definitions in the `biomes.toml` format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text can't be parsed or the definitions are invalid.
+    pub fn from_toml(text: &str) -> Result<Self, BiomeConfigError> {
+        let file: BiomeFile = toml::from_str(text)?;
+        let biomes = file
+            .biome
+            .into_iter()
+            .map(|table| {
+                Ok(BiomeDef {
+                    regions: table.region.iter().map(|region| region.region(&table.name)).collect::<Result<_, _>>()?,
+                    id: Biome(table.id),
+                    name: table.name,
+                    surface_block: table.surface_block,
+                    tree_density: table.tree_density,
+                    ore_multiplier: table.ore_multiplier,
+                })
+            })
+            .collect::<Result<_, BiomeConfigError>>()?;
+        Self::from_defs(file.generation.sea_level, file.generation.max_height, biomes)
+    }
+
+    /// Builds a registry from definitions.
+    ///
+    /// # Errors
+    ///
+    /// Fails if sea level isn't below `max_height`, an ID repeats, a range
+    /// is empty, or the regions overlap or leave a climate uncovered.
+    pub fn from_defs(sea_level: i32, max_height: i32, biomes: Vec<BiomeDef>) -> Result<Self, BiomeConfigError> {
+        if sea_level >= max_height {
+            return Err(BiomeConfigError::Heights { sea_level, max_height });
+        }
+
+        let mut lookup = [None; 256];
+        let mut regions = Vec::new();
+        for (index, def) in biomes.iter().enumerate() {
+            let slot = &mut lookup[usize::from(def.id.0)];
+            if slot.is_some() {
+                return Err(BiomeConfigError::DuplicateId(def.id.0));
+            }
+            // At most 256 unique IDs, so the index fits
+            *slot = Some(index as u8);
+
+            for region in &def.regions {
+                if let Some((axis, _)) = region.axes().into_iter().find(|(_, range)| range.is_empty()) {
+                    return Err(BiomeConfigError::EmptyRange {
+                        biome: def.name.clone(),
+                        axis,
+                    });
+                }
+                regions.push((def.id, *region));
+            }
+        }
+
+        let registry = Self {
+            sea_level,
+            max_height,
+            biomes,
+            lookup,
+            regions,
+        };
+        registry.check_partition()?;
+        Ok(registry)
+    }
+
+    /// Checks that every climate is in exactly one region.
+    ///
+    /// Region bounds split each axis into the bounds themselves and the
+    /// open intervals between them. Every region is a union of the
+    /// resulting cells, so testing one point per cell is exact.
+    fn check_partition(&self) -> Result<(), BiomeConfigError> {
+        let samples: Vec<Vec<f64>> = (0..3)
+            .map(|axis| {
+                let mut bounds: Vec<f64> = self
+                    .regions
+                    .iter()
+                    .flat_map(|(_, region)| {
+                        let range = region.axes()[axis].1;
+                        [range.min, range.max]
+                    })
+                    .filter(|bound| bound.is_finite())
+                    .collect();
+                bounds.sort_by(f64::total_cmp);
+                bounds.dedup();
+                cell_samples(&bounds)
+            })
+            .collect();
+
+        for &elevation in &samples[0] {
+            for &temperature in &samples[1] {
+                for &moisture in &samples[2] {
+                    let climate = [elevation, temperature, moisture];
+                    let mut owners = self
+                        .regions
+                        .iter()
+                        .filter(|(_, region)| region.contains(elevation, temperature, moisture))
+                        .map(|&(biome, _)| biome);
+
+                    let Some(first) = owners.next() else {
+                        return Err(BiomeConfigError::Uncovered(climate));
+                    };
+                    if let Some(second) = owners.next() {
+                        return Err(BiomeConfigError::Overlap {
+                            first: self.name(first).to_owned(),
+                            second: self.name(second).to_owned(),
+                            climate,
+                        });
+                    }
+                }
+            }
         }
+        Ok(())
+    }
+
+    /// Returns the sea level.
+    #[must_use]
+    pub const fn sea_level(&self) -> i32 {
+        self.sea_level
+    }
+
+    /// Returns the maximum terrain height.
+    #[must_use]
+    pub const fn max_height(&self) -> i32 {
+        self.max_height
     }
 
-    /// Converts from u8.
+    /// Returns the definitions in file order.
     #[must_use]
-    pub const fn from_u8(value: u8) -> Self {
-        match value {
-            0 => Self::DeepOcean,
-            1 => Self::Ocean,
-            2 => Self::Beach,
-            3 => Self::Plains,
-            4 => Self::Forest,
-            5 => Self::Jungle,
-            6 => Self::Desert,
-            7 => Self::Tundra,
-            8 => Self::Taiga,
-            9 => Self::Mountains,
-            10 => Self::SnowyPeaks,
-            11 => Self::Swamp,
-            12 => Self::Savanna,
-            _ => Self::Badlands,
+    pub fn biomes(&self) -> &[BiomeDef] {
+        &self.biomes
+    }
+
+    /// Returns a biome's definition.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, biome: Biome) -> Option<&BiomeDef> {
+        self.lookup[usize::from(biome.0)].map(|index| &self.biomes[usize::from(index)])
+    }
+
+    /// Returns a biome's name, or `"unknown"`.
+    #[must_use]
+    pub fn name(&self, biome: Biome) -> &str {
+        self.get(biome).map_or("unknown", |def| def.name.as_str())
+    }
+
+    /// Checks that every surface block is defined.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first biome whose surface block isn't in `blocks`.
+    pub fn check_blocks(&self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        for def in &self.biomes {
+            blocks.check_ids(&format!("biome {:?}", def.name), [def.surface_block])?;
         }
+        Ok(())
+    }
+
+    /// Returns the surface block ID of a biome (stone if unknown).
+    #[inline]
+    #[must_use]
+    pub fn surface_block(&self, biome: Biome) -> u16 {
+        self.get(biome).map_or(2, |def| def.surface_block)
+    }
+
+    /// Returns the tree density of a biome (0 if unknown).
+    #[inline]
+    #[must_use]
+    pub fn tree_density(&self, biome: Biome) -> u8 {
+        self.get(biome).map_or(0, |def| def.tree_density)
+    }
+
+    /// Returns the ore abundance of a biome (1.0 if unknown).
+    #[inline]
+    #[must_use]
+    pub fn ore_multiplier(&self, biome: Biome) -> f32 {
+        self.get(biome).map_or(1.0, |def| def.ore_multiplier)
+    }
+
+    /// Returns the biome owning a climate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a climate value is NaN; construction checked that every
+    /// other climate has an owner.
+    #[must_use]
+    pub fn classify(&self, elevation: f64, temperature: f64, moisture: f64) -> Biome {
+        self.regions
+            .iter()
+            .find(|(_, region)| region.contains(elevation, temperature, moisture))
+            .map_or_else(|| panic!("no biome covers climate {:?}", [elevation, temperature, moisture]), |&(biome, _)| biome)
     }
 }
 
+/// One point in each cell the sorted `bounds` split an axis into: the
+/// bounds, and a point inside each interval around them.
+fn cell_samples(bounds: &[f64]) -> Vec<f64> {
+    let (Some(&first), Some(&last)) = (bounds.first(), bounds.last()) else {
+        return vec![0.0];
+    };
+    let mut samples = vec![first - 1.0];
+    for pair in bounds.windows(2) {
+        samples.push(pair[0]);
+        samples.push((pair[0] + pair[1]) * 0.5);
+    }
+    samples.push(last);
+    samples.push(last + 1.0);
+    samples
+}
+
 /// Biome classifier that determines biome from world coordinates.
 ///
 /// Uses multiple noise channels to simulate climate.
@@ -121,6 +600,8 @@ pub struct BiomeClassifier {
     /// Detail noise for variation (reserved for future use)
     #[allow(dead_code)]
     detail_noise: SimplexNoise,
+    /// Biome definitions.
+    registry: Arc<BiomeRegistry>,
 }
 
 impl BiomeClassifier {
@@ -131,17 +612,31 @@ impl BiomeClassifier {
     /// Scale for elevation noise (reduced 50% for larger landmasses).
     const ELEVATION_SCALE: f64 = 0.0025;
 
-    /// Creates a new biome classifier from a world seed.
+    /// Creates a new biome classifier from a world seed, using the
+    /// shipped biome definitions.
     #[must_use]
     pub fn new(seed: WorldSeed) -> Self {
+        Self::with_registry(seed, BiomeRegistry::shipped())
+    }
+
+    /// Creates a biome classifier using custom biome definitions.
+    #[must_use]
+    pub fn with_registry(seed: WorldSeed, registry: Arc<BiomeRegistry>) -> Self {
         Self {
             temperature_noise: SimplexNoise::new(seed.derive(1)),
             humidity_noise: SimplexNoise::new(seed.derive(2)),
             elevation_noise: SimplexNoise::new(seed.derive(3)),
             detail_noise: SimplexNoise::new(seed.derive(4)),
+            registry,
         }
     }
 
+    /// Returns the biome definitions.
+    #[must_use]
+    pub fn registry(&self) -> &Arc<BiomeRegistry> {
+        &self.registry
+    }
+
     /// Classifies the biome at world coordinates.
     ///
     /// # Arguments
@@ -271,46 +766,7 @@ impl BiomeClassifier {
 
     /// Classifies biome from climate values.
     fn classify_from_climate(&self, elevation: f64, temperature: f64, humidity: f64) -> Biome {
-        // Water biomes
-        if elevation < -0.5 {
-            return Biome::DeepOcean;
-        }
-        if elevation < -0.2 {
-            return Biome::Ocean;
-        }
-        if elevation < -0.1 {
-            return Biome::Beach;
-        }
-
-        // Mountain biomes
-        if elevation > 0.7 {
-            if temperature < -0.2 {
-                return Biome::SnowyPeaks;
-            }
-            return Biome::Mountains;
-        }
-
-        // Land biomes based on temperature and humidity
-        match (temperature, humidity) {
-            // Cold biomes
-            (t, _) if t < -0.5 => Biome::Tundra,
-            (t, h) if t < -0.2 && h > 0.0 => Biome::Taiga,
-            (t, _) if t < -0.2 => Biome::Tundra,
-
-            // Hot biomes
-            (t, h) if t > 0.5 && h < -0.3 => Biome::Desert,
-            (t, h) if t > 0.5 && h > 0.5 => Biome::Jungle,
-            (t, h) if t > 0.3 && h < 0.0 => Biome::Savanna,
-            (t, _) if t > 0.6 => Biome::Badlands,
-
-            // Temperate biomes
-            (_, h) if h > 0.5 && elevation < 0.1 => Biome::Swamp,
-            (_, h) if h > 0.2 => Biome::Forest,
-            (_, h) if h < -0.2 => Biome::Plains,
-
-            // Default
-            _ => Biome::Plains,
-        }
+        self.registry.classify(elevation, temperature, humidity)
     }
 
     /// Gets the terrain height at world coordinates.
@@ -422,10 +878,10 @@ mod tests {
 
         // Manually check that low elevation gives water biomes
         let biome = classifier.classify_from_climate(-0.6, 0.0, 0.0);
-        assert_eq!(biome, Biome::DeepOcean);
+        assert_eq!(biome, Biome::DEEP_OCEAN);
 
         let biome = classifier.classify_from_climate(-0.3, 0.0, 0.0);
-        assert_eq!(biome, Biome::Ocean);
+        assert_eq!(biome, Biome::OCEAN);
     }
 
     #[test]
@@ -447,4 +903,172 @@ mod tests {
             );
         }
     }
+
+    /// The classifier as hardcoded before biomes moved to `biomes.toml`.
+    fn hardcoded_biome(elevation: f64, temperature: f64, humidity: f64) -> Biome {
+        if elevation < -0.5 {
+            return Biome::DEEP_OCEAN;
+        }
+        if elevation < -0.2 {
+            return Biome::OCEAN;
+        }
+        if elevation < -0.1 {
+            return Biome::BEACH;
+        }
+        if elevation > 0.7 {
+            if temperature < -0.2 {
+                return Biome::SNOWY_PEAKS;
+            }
+            return Biome::MOUNTAINS;
+        }
+        match (temperature, humidity) {
+            (t, _) if t < -0.5 => Biome::TUNDRA,
+            (t, h) if t < -0.2 && h > 0.0 => Biome::TAIGA,
+            (t, _) if t < -0.2 => Biome::TUNDRA,
+            (t, h) if t > 0.5 && h < -0.3 => Biome::DESERT,
+            (t, h) if t > 0.5 && h > 0.5 => Biome::JUNGLE,
+            (t, h) if t > 0.3 && h < 0.0 => Biome::SAVANNA,
+            (t, _) if t > 0.6 => Biome::BADLANDS,
+            (_, h) if h > 0.5 && elevation < 0.1 => Biome::SWAMP,
+            (_, h) if h > 0.2 => Biome::FOREST,
+            _ => Biome::PLAINS,
+        }
+    }
+
+    #[test]
+    fn test_shipped_config_matches_hardcoded() {
+        let registry = BiomeRegistry::shipped();
+        assert_eq!((registry.sea_level(), registry.max_height()), (64, 256));
+
+        // (surface block, tree density) as hardcoded, by ID
+        let hardcoded = [
+            (10, 0), (10, 0), (11, 0), (1, 5), (1, 50), (12, 80), (11, 0),
+            (13, 0), (13, 40), (2, 0), (14, 0), (15, 30), (1, 10), (16, 0),
+        ];
+        assert_eq!(registry.biomes().len(), hardcoded.len());
+        for (id, &(surface, trees)) in hardcoded.iter().enumerate() {
+            let biome = Biome(id as u8);
+            assert_eq!(registry.surface_block(biome), surface, "{}", registry.name(biome));
+            assert_eq!(registry.tree_density(biome), trees, "{}", registry.name(biome));
+        }
+
+        // Every threshold the old code compared against, and a point
+        // between each pair: the thresholds are where `<` and `>` differ
+        let thresholds = [-0.5, -0.3, -0.2, -0.1, 0.0, 0.1, 0.2, 0.3, 0.5, 0.6, 0.7];
+        let axis = cell_samples(&thresholds);
+        for &e in &axis {
+            for &t in &axis {
+                for &h in &axis {
+                    let climate = (e, t, h);
+                    assert_eq!(
+                        registry.classify(climate.0, climate.1, climate.2),
+                        hardcoded_biome(climate.0, climate.1, climate.2),
+                        "climate {climate:?}"
+                    );
+                }
+            }
+        }
+
+        // And on real terrain, for several seeds
+        for seed in [1, 42, 12345, 0xDEAD_BEEF] {
+            let classifier = BiomeClassifier::new(WorldSeed::new(seed));
+            for x in (-4000..4000).step_by(37) {
+                for y in (-4000..4000).step_by(41) {
+                    let (x, y) = (f64::from(x), f64::from(y));
+                    let elevation = classifier.get_elevation(x, y);
+                    let temperature = classifier.get_temperature(x, y, elevation);
+                    let humidity = classifier.get_humidity(x, y);
+                    assert_eq!(classifier.classify(x, y), hardcoded_biome(elevation, temperature, humidity));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_overlaps_and_gaps() {
+        // Dropping the beach leaves its elevation band uncovered
+        let no_beach = SHIPPED_BIOMES.replace("min_elevation = -0.2\nmax_elevation = -0.1\n", "min_elevation = -0.2\nmax_elevation = -0.15\n");
+        assert!(matches!(
+            BiomeRegistry::from_toml(&no_beach),
+            Err(BiomeConfigError::Uncovered([elevation, _, _])) if (-0.15..-0.1).contains(&elevation)
+        ));
+
+        // Stretching the ocean up into the beach
+        let wide_ocean = SHIPPED_BIOMES.replace("min_elevation = -0.5\nmax_elevation = -0.2\n", "min_elevation = -0.5\nmax_elevation = -0.15\n");
+        match BiomeRegistry::from_toml(&wide_ocean) {
+            Err(BiomeConfigError::Overlap { first, second, .. }) => assert_eq!((first.as_str(), second.as_str()), ("Ocean", "Beach")),
+            other => panic!("expected overlap, got {other:?}"),
+        }
+
+        let duplicate = SHIPPED_BIOMES.replace("id = 13", "id = 12");
+        assert!(matches!(BiomeRegistry::from_toml(&duplicate), Err(BiomeConfigError::DuplicateId(12))));
+
+        let empty = SHIPPED_BIOMES.replace("max_elevation = -0.5\n", "min_elevation = -0.5\nmax_elevation = -0.5\n");
+        assert!(matches!(BiomeRegistry::from_toml(&empty), Err(BiomeConfigError::EmptyRange { axis: "elevation", .. })));
+
+        // Land stopping short of 0.7 while peaks start above it leaves
+        // exactly 0.7 uncovered
+        let open_land = SHIPPED_BIOMES.replace("at_most_elevation = 0.7", "max_elevation = 0.7");
+        assert!(matches!(BiomeRegistry::from_toml(&open_land), Err(BiomeConfigError::Uncovered([elevation, _, _])) if elevation == 0.7));
+
+        // And peaks starting at 0.7 as well as land ending there overlap
+        let closed_peaks = SHIPPED_BIOMES.replace("above_elevation = 0.7", "min_elevation = 0.7");
+        assert!(matches!(BiomeRegistry::from_toml(&closed_peaks), Err(BiomeConfigError::Overlap { climate: [elevation, _, _], .. }) if elevation == 0.7));
+
+        let twice = SHIPPED_BIOMES.replace("above_elevation = 0.7", "above_elevation = 0.7\nmin_elevation = 0.8");
+        assert!(matches!(BiomeRegistry::from_toml(&twice), Err(BiomeConfigError::ConflictingBounds { axis: "elevation", .. })));
+    }
+
+    #[test]
+    fn test_custom_biomes() {
+        let registry = Arc::new(
+            BiomeRegistry::from_toml(
+                r#"
+                [generation]
+                sea_level = 40
+                max_height = 200
+
+                [[biome]]
+                id = 1
+                name = "Ocean"
+                surface_block = 10
+
+                [[biome.region]]
+                max_elevation = 0.0
+
+                [[biome]]
+                id = 42
+                name = "Salt Flats"
+                surface_block = 17
+                tree_density = 3
+
+                [[biome.region]]
+                min_elevation = 0.0
+                "#,
+            )
+            .unwrap(),
+        );
+
+        let salt_flats = Biome(42);
+        assert_eq!(registry.classify(0.5, 0.9, -0.9), salt_flats);
+        assert_eq!(registry.classify(-0.5, 0.0, 0.0), Biome::OCEAN);
+        assert_eq!(registry.surface_block(salt_flats), 17);
+        assert_eq!(registry.name(salt_flats), "Salt Flats");
+        assert!(registry.get(Biome::PLAINS).is_none());
+
+        let classifier = BiomeClassifier::with_registry(WorldSeed::new(7), registry);
+        for i in 0..100 {
+            let biome = classifier.classify(f64::from(i) * 97.0, f64::from(i) * 61.0);
+            assert!(biome == salt_flats || biome == Biome::OCEAN);
+        }
+
+        // Block 17 isn't shipped
+        let err = classifier.registry().check_blocks(&BlockRegistry::shipped()).unwrap_err();
+        assert!(matches!(err, BlockConfigError::UnknownBlock { id: 17, ref referrer } if referrer.contains("Salt Flats")));
+    }
+
+    #[test]
+    fn test_shipped_surface_blocks_exist() {
+        BiomeRegistry::shipped().check_blocks(&BlockRegistry::shipped()).unwrap();
+    }
 }
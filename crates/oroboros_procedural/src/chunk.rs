@@ -17,15 +17,18 @@
 
 use std::io::{Read, Write};
 use std::path::Path;
+use std::sync::Arc;
 
 use bytemuck::{Pod, Zeroable};
 use lz4_flex::{compress_prepend_size, decompress_size_prepended};
+use oroboros_core::blocks::{BlockConfigError, BlockRegistry};
+pub use oroboros_core::spatial::{ChunkCoord, CHUNK_SIZE};
 
-use crate::biome::{Biome, BiomeClassifier};
+use crate::biome::{Biome, BiomeClassifier, BiomeRegistry};
+use crate::generator::{GenerationPass, GeneratorPreset, WorldGenerator};
 use crate::noise::{SimplexNoise, WorldSeed};
-
-/// Chunk width/depth in blocks.
-pub const CHUNK_SIZE: usize = 16;
+use crate::structures::{self, PlacedStructure, StructureCache};
+use crate::underground::{self, UndergroundConfig};
 
 /// Chunk height in blocks.
 pub const CHUNK_HEIGHT: usize = 256;
@@ -33,56 +36,6 @@ pub const CHUNK_HEIGHT: usize = 256;
 /// Total blocks per chunk.
 pub const BLOCKS_PER_CHUNK: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_HEIGHT;
 
-/// Chunk coordinate (identifies a chunk in the world grid).
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
-pub struct ChunkCoord {
-    /// X coordinate (in chunks, not blocks).
-    pub x: i32,
-    /// Z coordinate (in chunks, not blocks).
-    pub z: i32,
-}
-
-impl ChunkCoord {
-    /// Creates a new chunk coordinate.
-    #[inline]
-    #[must_use]
-    pub const fn new(x: i32, z: i32) -> Self {
-        Self { x, z }
-    }
-
-    /// Converts world block coordinates to chunk coordinate.
-    #[inline]
-    #[must_use]
-    pub const fn from_block_pos(block_x: i32, block_z: i32) -> Self {
-        Self {
-            x: block_x.div_euclid(CHUNK_SIZE as i32),
-            z: block_z.div_euclid(CHUNK_SIZE as i32),
-        }
-    }
-
-    /// Returns the world X coordinate of the chunk's origin (corner).
-    #[inline]
-    #[must_use]
-    pub const fn world_x(self) -> i32 {
-        self.x * CHUNK_SIZE as i32
-    }
-
-    /// Returns the world Z coordinate of the chunk's origin.
-    #[inline]
-    #[must_use]
-    pub const fn world_z(self) -> i32 {
-        self.z * CHUNK_SIZE as i32
-    }
-
-    /// Converts world coordinates to chunk coordinate.
-    /// Alias for `from_block_pos` for API consistency.
-    #[inline]
-    #[must_use]
-    pub const fn from_world_pos(world_x: i32, world_z: i32) -> Self {
-        Self::from_block_pos(world_x, world_z)
-    }
-}
-
 /// A single block in the world.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
 #[repr(C)]
@@ -112,6 +65,61 @@ impl Block {
     pub const WATER: Self = Self { id: 10, meta: 0 };
     /// Sand block.
     pub const SAND: Self = Self { id: 11, meta: 0 };
+    /// Jungle grass block.
+    pub const JUNGLE_GRASS: Self = Self { id: 12, meta: 0 };
+    /// Frozen dirt block.
+    pub const FROZEN_DIRT: Self = Self { id: 13, meta: 0 };
+    /// Snow block.
+    pub const SNOW: Self = Self { id: 14, meta: 0 };
+    /// Mud block.
+    pub const MUD: Self = Self { id: 15, meta: 0 };
+    /// Red sand block.
+    pub const RED_SAND: Self = Self { id: 16, meta: 0 };
+    /// Iron ore (placed by ore veins).
+    pub const IRON_ORE: Self = Self { id: 20, meta: 0 };
+    /// Gold ore (placed by ore veins).
+    pub const GOLD_ORE: Self = Self { id: 21, meta: 0 };
+    /// Diamond ore (placed by ore veins).
+    pub const DIAMOND_ORE: Self = Self { id: 22, meta: 0 };
+    /// Oroboros crystal (placed by ore veins).
+    pub const OROBOROS_CRYSTAL: Self = Self { id: 99, meta: 0 };
+    /// Undercity concrete floor.
+    pub const CONCRETE_FLOOR: Self = Self { id: 32, meta: 0 };
+    /// Undercity concrete wall.
+    pub const CONCRETE_WALL: Self = Self { id: 33, meta: 0 };
+    /// Undercity red neon hazard (kills on contact).
+    pub const HAZARD_NEON: Self = Self { id: 34, meta: 0 };
+    /// Undercity extraction goal floor.
+    pub const GOAL_ZONE: Self = Self { id: 35, meta: 0 };
+    /// Undercity gold loot pickup.
+    pub const GOLD_LOOT: Self = Self { id: 36, meta: 0 };
+    /// Undercity metal bridge, catwalk and platform.
+    pub const METAL_BRIDGE: Self = Self { id: 37, meta: 0 };
+
+    /// Every block the built-in generators place, apart from ores, which
+    /// come from [`UndergroundConfig`].
+    pub const BUILTIN: [Self; 20] = [
+        Self::AIR,
+        Self::GRASS,
+        Self::STONE,
+        Self::DIRT,
+        Self::WOOD,
+        Self::LEAVES,
+        Self::BEDROCK,
+        Self::WATER,
+        Self::SAND,
+        Self::JUNGLE_GRASS,
+        Self::FROZEN_DIRT,
+        Self::SNOW,
+        Self::MUD,
+        Self::RED_SAND,
+        Self::CONCRETE_FLOOR,
+        Self::CONCRETE_WALL,
+        Self::HAZARD_NEON,
+        Self::GOAL_ZONE,
+        Self::GOLD_LOOT,
+        Self::METAL_BRIDGE,
+    ];
 
     /// Creates a new block with given ID.
     #[inline]
@@ -159,7 +167,7 @@ impl Chunk {
         Self {
             coord,
             blocks: Box::new([[[Block::AIR; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_HEIGHT]),
-            biomes: [[Biome::Plains; CHUNK_SIZE]; CHUNK_SIZE],
+            biomes: [[Biome::PLAINS; CHUNK_SIZE]; CHUNK_SIZE],
             height_map: [[0; CHUNK_SIZE]; CHUNK_SIZE],
             modified: false,
         }
@@ -210,7 +218,7 @@ impl Chunk {
         if x < CHUNK_SIZE && z < CHUNK_SIZE {
             self.biomes[z][x]
         } else {
-            Biome::Plains
+            Biome::PLAINS
         }
     }
 
@@ -293,19 +301,22 @@ impl Chunk {
             }
         }
 
-        // Recalculate height map
+        chunk.rebuild_height_map();
+        Ok(chunk)
+    }
+
+    /// Recalculates the height map from the blocks.
+    pub(crate) fn rebuild_height_map(&mut self) {
         for z in 0..CHUNK_SIZE {
             for x in 0..CHUNK_SIZE {
                 for y in (0..CHUNK_HEIGHT).rev() {
-                    if !chunk.blocks[y][z][x].is_air() {
-                        chunk.height_map[z][x] = y as u8;
+                    if !self.blocks[y][z][x].is_air() {
+                        self.height_map[z][x] = y as u8;
                         break;
                     }
                 }
             }
         }
-
-        Ok(chunk)
     }
 
     /// Returns the raw block data size in bytes (uncompressed).
@@ -313,83 +324,198 @@ impl Chunk {
     pub const fn data_size() -> usize {
         BLOCKS_PER_CHUNK * std::mem::size_of::<Block>()
     }
+
+    /// Size of [`to_raw`](Self::to_raw) output: blocks, then one byte
+    /// per column biome.
+    pub(crate) const RAW_SIZE: usize = Self::data_size() + CHUNK_SIZE * CHUNK_SIZE;
+
+    /// Encodes blocks and biomes, uncompressed.
+    pub(crate) fn to_raw(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::RAW_SIZE);
+        bytes.extend_from_slice(bytemuck::cast_slice::<Block, u8>(
+            self.blocks.as_ref().as_flattened().as_flattened(),
+        ));
+        bytes.extend(self.biomes.as_flattened().iter().map(|biome| biome.0));
+        bytes
+    }
+
+    /// Decodes [`to_raw`](Self::to_raw) output; `None` if the size is wrong.
+    pub(crate) fn from_raw(coord: ChunkCoord, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::RAW_SIZE {
+            return None;
+        }
+
+        let (blocks, biomes) = bytes.split_at(Self::data_size());
+        let mut chunk = Self::new(coord);
+        bytemuck::cast_slice_mut::<Block, u8>(chunk.blocks.as_mut().as_flattened_mut().as_flattened_mut())
+            .copy_from_slice(blocks);
+        for (slot, &id) in chunk.biomes.as_flattened_mut().iter_mut().zip(biomes) {
+            *slot = Biome(id);
+        }
+        chunk.rebuild_height_map();
+        Some(chunk)
+    }
 }
 
 /// Chunk generator using procedural noise.
-/// GLITCH WARS: Arena mode uses flat terrain, forest features unused
-#[allow(dead_code)]
+///
+/// Holds the seed and noise sources, and runs a list of
+/// [`GenerationPass`]es over each chunk. [`new`](Self::new) builds the
+/// GLITCH WARS Undercity; see [`GeneratorPreset`] for the others.
 pub struct ChunkGenerator {
-    /// Biome classifier for terrain generation (unused in Arena mode).
+    /// Biome classifier for terrain generation.
     classifier: BiomeClassifier,
+    /// Block definitions every placed block must exist in.
+    blocks: Arc<BlockRegistry>,
+    /// Cave and ore vein settings.
+    underground: Arc<UndergroundConfig>,
     /// Detail noise for block variation and cover placement.
     detail_noise: SimplexNoise,
-    /// Cave noise (reserved for cave generation).
+    /// Cave noise (Undercity pits and 3D caverns).
     cave_noise: SimplexNoise,
-    /// Tree placement noise (unused in Arena mode).
-    tree_noise: SimplexNoise,
-    /// Sea level (Y coordinate, unused in Arena mode).
+    /// Loot placement noise.
+    loot_noise: SimplexNoise,
+    /// Structure sites of recently used regions.
+    structures: StructureCache,
+    /// Sea level (Y coordinate, biome terrain only).
     sea_level: i32,
     /// World seed for deterministic RNG.
     seed: WorldSeed,
+    /// Name recorded in world metadata.
+    name: String,
+    /// Version recorded in world metadata.
+    version: u32,
+    /// Passes in run order.
+    passes: Vec<Box<dyn GenerationPass>>,
 }
 
 impl ChunkGenerator {
     /// Default sea level.
     pub const DEFAULT_SEA_LEVEL: i32 = 64;
-    
-    /// Minimum tree height (unused in Arena mode).
-    #[allow(dead_code)]
-    const TREE_MIN_HEIGHT: usize = 4;
-    
-    /// Maximum tree height (unused in Arena mode).
-    #[allow(dead_code)]
-    const TREE_MAX_HEIGHT: usize = 6;
 
-    /// Creates a new chunk generator.
+    /// Creates a new Undercity chunk generator.
     #[must_use]
     pub fn new(seed: WorldSeed) -> Self {
+        Self::from_preset(seed, GeneratorPreset::Undercity)
+    }
+
+    /// Creates a generator for a built-in preset.
+    #[must_use]
+    pub fn from_preset(seed: WorldSeed, preset: GeneratorPreset) -> Self {
+        Self::with_passes(seed, preset.name(), preset.passes()).with_version(preset.version())
+    }
+
+    /// Creates a generator from custom passes.
+    ///
+    /// Passes are sorted by [`Stage`](crate::generator::Stage), keeping
+    /// their given order within a stage.
+    #[must_use]
+    pub fn with_passes(seed: WorldSeed, name: impl Into<String>, mut passes: Vec<Box<dyn GenerationPass>>) -> Self {
+        passes.sort_by_key(|pass| pass.stage());
         Self {
             classifier: BiomeClassifier::new(seed),
+            blocks: BlockRegistry::shipped(),
+            underground: UndergroundConfig::shipped(),
             detail_noise: SimplexNoise::new(seed.derive(100)),
             cave_noise: SimplexNoise::new(seed.derive(101)),
-            tree_noise: SimplexNoise::new(seed.derive(102)),
+            loot_noise: SimplexNoise::new(seed.derive(102)),
+            structures: StructureCache::default(),
             sea_level: Self::DEFAULT_SEA_LEVEL,
             seed,
+            name: name.into(),
+            version: 1,
+            passes,
         }
     }
 
+    /// Sets the version recorded in world metadata (1 by default).
+    #[must_use]
+    pub const fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
     /// Sets the sea level.
     #[must_use]
-    pub const fn with_sea_level(mut self, level: i32) -> Self {
+    pub fn with_sea_level(mut self, level: i32) -> Self {
         self.sea_level = level;
         self
     }
 
-    /// Generates a chunk at the given coordinates.
-    /// BRUTALIST MEGA-STRUCTURE: Grid Architecture Arena
+    /// Uses custom biome definitions, including their sea level.
     #[must_use]
-    pub fn generate(&self, coord: ChunkCoord) -> Chunk {
-        let mut chunk = Chunk::new(coord);
+    pub fn with_biomes(mut self, registry: Arc<BiomeRegistry>) -> Self {
+        self.sea_level = registry.sea_level();
+        self.classifier = BiomeClassifier::with_registry(self.seed, registry);
+        self
+    }
 
-        let world_x = coord.world_x();
-        let world_z = coord.world_z();
+    /// Uses custom block definitions.
+    ///
+    /// Call [`check_blocks`](Self::check_blocks) afterwards to make sure
+    /// they cover everything the generator places.
+    #[must_use]
+    pub fn with_blocks(mut self, blocks: Arc<BlockRegistry>) -> Self {
+        self.blocks = blocks;
+        self
+    }
 
-        // MEGA-STRUCTURE: Generate grid architecture
-        for local_z in 0..CHUNK_SIZE {
-            for local_x in 0..CHUNK_SIZE {
-                let block_x = world_x + local_x as i32;
-                let block_z = world_z + local_z as i32;
+    /// Uses custom cave and ore settings.
+    #[must_use]
+    pub fn with_underground(mut self, underground: Arc<UndergroundConfig>) -> Self {
+        self.underground = underground;
+        self
+    }
 
-                self.generate_undercity_column(&mut chunk, local_x, local_z, block_x, block_z);
-            }
-        }
-        
-        // Carve the Extraction Beam (goal at origin)
-        self.carve_extraction_beam(&mut chunk, world_x, world_z);
-        
-        // Spawn gold loot on bridges and towers
-        self.generate_gold_loot(&mut chunk, world_x, world_z);
+    /// Returns the cave and ore settings.
+    #[must_use]
+    pub fn underground(&self) -> &Arc<UndergroundConfig> {
+        &self.underground
+    }
+
+    /// Returns the block definitions.
+    #[must_use]
+    pub fn blocks(&self) -> &Arc<BlockRegistry> {
+        &self.blocks
+    }
 
+    /// Checks that every block the built-in passes, biomes and ore veins
+    /// place is defined, and that every ore drops loot.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first undefined block ID or ore without a loot table.
+    pub fn check_blocks(&self) -> Result<(), BlockConfigError> {
+        self.blocks.check_ids("chunk generator", Block::BUILTIN.map(|block| block.id))?;
+        self.classifier.registry().check_blocks(&self.blocks)?;
+        self.underground.check_blocks(&self.blocks)
+    }
+
+    /// Returns the world seed.
+    #[must_use]
+    pub const fn seed(&self) -> WorldSeed {
+        self.seed
+    }
+
+    /// Returns the sea level.
+    #[must_use]
+    pub const fn sea_level(&self) -> i32 {
+        self.sea_level
+    }
+
+    /// Returns the biome classifier.
+    #[must_use]
+    pub const fn classifier(&self) -> &BiomeClassifier {
+        &self.classifier
+    }
+
+    /// Generates a chunk at the given coordinates.
+    #[must_use]
+    pub fn generate(&self, coord: ChunkCoord) -> Chunk {
+        let mut chunk = Chunk::new(coord);
+        for pass in &self.passes {
+            pass.apply(self, &mut chunk);
+        }
         chunk
     }
     
@@ -401,7 +527,7 @@ impl ChunkGenerator {
     /// - Strategic hazard placement (not everywhere)
     /// - Clear pathways with interesting obstacles
     /// - Production-grade deterministic generation
-    fn generate_undercity_column(
+    pub(crate) fn generate_undercity_column(
         &self,
         chunk: &mut Chunk,
         local_x: usize,
@@ -560,30 +686,30 @@ impl ChunkGenerator {
         for y in 0..CHUNK_HEIGHT {
             let block = if y < HAZARD_LAYER {
                 // Bedrock foundation
-                Block::new(5)
+                Block::BEDROCK
                 
             } else if y == HAZARD_LAYER {
                 // Hazard layer - red neon death
                 if is_spawn_area || is_goal_zone {
-                    Block::new(5) // Safe bedrock under spawn
+                    Block::BEDROCK // Safe bedrock under spawn
                 } else if is_hazard_pit || has_random_pit {
-                    Block::new(3) // RED NEON - visible death
+                    Block::HAZARD_NEON // RED NEON - visible death
                 } else {
-                    Block::new(5) // Bedrock under walkable areas
+                    Block::BEDROCK // Bedrock under walkable areas
                 }
                 
             } else if y == BASE_FLOOR_Y {
                 // Main floor
                 if is_goal_zone {
-                    Block::new(4) // Goal zone - ice white
+                    Block::GOAL_ZONE // Goal zone - ice white
                 } else if is_hazard_pit || has_random_pit {
                     Block::AIR // Pit opening
                 } else if is_outer_wall && !is_door {
-                    Block::new(2) // Wall base
+                    Block::CONCRETE_WALL // Wall base
                 } else if is_internal_pillar || is_internal_maze_wall {
-                    Block::new(2) // Internal structure base
+                    Block::CONCRETE_WALL // Internal structure base
             } else {
-                    Block::new(1) // Concrete floor
+                    Block::CONCRETE_FLOOR // Concrete floor
                 }
                 
             } else if y > BASE_FLOOR_Y && y <= BASE_FLOOR_Y + 3 {
@@ -591,11 +717,11 @@ impl ChunkGenerator {
                 if is_hazard_pit || has_random_pit {
                     Block::AIR // Open pit
                 } else if is_outer_wall && !is_door {
-                    Block::new(2) // Wall
+                    Block::CONCRETE_WALL // Wall
                 } else if is_internal_pillar || is_internal_maze_wall {
-                    Block::new(2) // Internal walls/pillars
+                    Block::CONCRETE_WALL // Internal walls/pillars
                 } else if is_raised_platform && y == BASE_FLOOR_Y + 3 {
-                    Block::new(7) // Platform surface
+                    Block::METAL_BRIDGE // Platform surface
                 } else {
                     Block::AIR // Walking space
                 }
@@ -603,7 +729,7 @@ impl ChunkGenerator {
             } else if y > BASE_FLOOR_Y + 3 && y < WALL_HEIGHT {
                 // Wall and structure height
                 if is_corner && y < tower_height {
-                    Block::new(2) // Corner tower
+                    Block::CONCRETE_WALL // Corner tower
                 } else if is_outer_wall && !is_door && !is_corner {
                     // Walls with window cutouts
                     let window_band = y > BASE_FLOOR_Y + 6 && y < WALL_HEIGHT - 3;
@@ -611,14 +737,14 @@ impl ChunkGenerator {
                     if window_band && window_pattern {
                         Block::AIR // Window
                     } else {
-                        Block::new(2) // Wall
+                        Block::CONCRETE_WALL // Wall
                     }
                 } else if is_internal_pillar && y < BASE_FLOOR_Y + 10 {
-                    Block::new(2) // Short pillars
+                    Block::CONCRETE_WALL // Short pillars
                 } else if is_internal_maze_wall && y < BASE_FLOOR_Y + 8 {
-                    Block::new(2) // Low maze walls
+                    Block::CONCRETE_WALL // Low maze walls
                 } else if is_raised_platform && y < BASE_FLOOR_Y + 6 {
-                    Block::new(2) // Platform support pillars (corners only)
+                    Block::CONCRETE_WALL // Platform support pillars (corners only)
                         } else {
                             Block::AIR
                         }
@@ -626,11 +752,11 @@ impl ChunkGenerator {
             } else if y == CATWALK_Y {
                 // Catwalk level
                 if is_catwalk && !is_corner {
-                    Block::new(7) // Metal catwalk
+                    Block::METAL_BRIDGE // Metal catwalk
                 } else if is_outer_wall && !is_door {
-                    Block::new(2) // Wall continues
+                    Block::CONCRETE_WALL // Wall continues
                 } else if is_corner {
-                    Block::new(2) // Tower continues
+                    Block::CONCRETE_WALL // Tower continues
                 } else {
                     Block::AIR
                 }
@@ -643,18 +769,18 @@ impl ChunkGenerator {
                                   local_rx == ROOM_SIZE - WALL_THICKNESS - 3 ||
                                   local_rz == WALL_THICKNESS + 2 ||
                                   local_rz == ROOM_SIZE - WALL_THICKNESS - 3;
-                    if is_edge { Block::new(7) } else { Block::AIR }
+                    if is_edge { Block::METAL_BRIDGE } else { Block::AIR }
                 } else if is_outer_wall && !is_door {
-                    Block::new(2)
+                    Block::CONCRETE_WALL
                 } else if is_corner && y < tower_height {
-                    Block::new(2)
+                    Block::CONCRETE_WALL
                 } else {
                     Block::AIR
                 }
                 
             } else if y > CATWALK_Y + 1 && y < tower_height && is_corner {
                 // Tower extends above catwalk
-                Block::new(2)
+                Block::CONCRETE_WALL
                 
             } else {
                 Block::AIR
@@ -669,24 +795,54 @@ impl ChunkGenerator {
                            else if is_raised_platform { BASE_FLOOR_Y + 6 }
                            else { BASE_FLOOR_Y };
         chunk.height_map[local_z][local_x] = surface_height.min(255) as u8;
-        chunk.set_biome(local_x, local_z, Biome::Desert);
+        chunk.set_biome(local_x, local_z, Biome::DESERT);
     }
     
-    /// 3D Simplex Noise approximation using 2D layers (legacy, kept for future use)
-    #[allow(dead_code)]
-    fn sample_3d_noise(&self, x: f64, y: f64, z: f64) -> f64 {
-        // Combine multiple 2D noise samples to simulate 3D
-        let n1 = self.cave_noise.sample(x, z + y * 0.7);
-        let n2 = self.cave_noise.sample(x + y * 0.5, z);
-        let n3 = self.detail_noise.sample(x * 1.5 + y * 0.3, z * 1.5);
-        
-        // Normalize to 0..1
-        ((n1 + n2 + n3) / 3.0 + 1.0) / 2.0
+    /// Carves worm tunnels and noise caverns below the surface.
+    pub(crate) fn carve_caves(&self, chunk: &mut Chunk) {
+        underground::carve_worms(&self.underground, self.seed, chunk);
+        underground::carve_caverns(&self.underground, &self.cave_noise, chunk);
+    }
+    
+    /// Places ore veins in stone.
+    pub(crate) fn place_ores(&self, chunk: &mut Chunk) {
+        underground::place_ores(&self.underground, self.seed, &self.classifier, chunk);
+    }
+    
+    /// Writes the slices of trees, ruins and towers overlapping the chunk.
+    pub(crate) fn place_structures(&self, chunk: &mut Chunk) {
+        let nearby = structures::structures_near(self, &self.structures, chunk.coord);
+        for placed in &nearby {
+            placed.write(chunk);
+        }
+        // Buildings may clear blocks below the old surface
+        if !nearby.is_empty() {
+            chunk.rebuild_height_map();
+        }
+    }
+    
+    /// Returns the structures whose sites lie in a structure region.
+    ///
+    /// Regions are [`STRUCTURE_REGION_SIZE`](crate::structures::STRUCTURE_REGION_SIZE)
+    /// blocks wide; region `(0, 0)` starts at block `(0, 0)`.
+    #[must_use]
+    pub fn structures_in_region(&self, region_x: i32, region_z: i32) -> Arc<[PlacedStructure]> {
+        self.structures.region(self, region_x, region_z)
+    }
+    
+    /// Returns the biome terrain surface height of a column, before caves
+    /// and structures.
+    #[must_use]
+    pub fn terrain_height(&self, block_x: i32, block_z: i32) -> i32 {
+        self.classifier
+            .get_terrain_height(f64::from(block_x), f64::from(block_z), self.sea_level, self.classifier.registry().max_height())
+            .max(0)
+            .min(CHUNK_HEIGHT as i32 - 1)
     }
     
     /// Carve the Extraction Beam - clear cylinder at origin
     /// This is the GOAL - reach it to escape the arena
-    fn carve_extraction_beam(&self, chunk: &mut Chunk, world_x: i32, world_z: i32) {
+    pub(crate) fn carve_extraction_beam(&self, chunk: &mut Chunk, world_x: i32, world_z: i32) {
         const BEAM_RADIUS: i32 = 4;
         const FLOOR_Y: usize = 4; // Match BASE_FLOOR_Y from structure gen
         
@@ -702,8 +858,8 @@ impl ChunkGenerator {
                     for y in (FLOOR_Y + 1)..CHUNK_HEIGHT {
                         chunk.set_block(local_x, y, local_z, Block::AIR);
                     }
-                    // Glowing Goal Zone floor (ID 4 = Ice White)
-                    chunk.set_block(local_x, FLOOR_Y, local_z, Block::new(4));
+                    // Glowing Goal Zone floor (Ice White)
+                    chunk.set_block(local_x, FLOOR_Y, local_z, Block::GOAL_ZONE);
                 }
             }
         }
@@ -711,7 +867,7 @@ impl ChunkGenerator {
     
     /// Generate valuable GOLD LOOT on platforms and catwalks
     /// Risk vs Reward: Further from spawn = more gold
-    fn generate_gold_loot(&self, chunk: &mut Chunk, world_x: i32, world_z: i32) {
+    pub(crate) fn generate_gold_loot(&self, chunk: &mut Chunk, world_x: i32, world_z: i32) {
         // Match the new structure heights
         const FLOOR_Y: usize = 4;
         const CATWALK_Y: usize = 12;
@@ -729,7 +885,7 @@ impl ChunkGenerator {
                 }
                 
                 // Use noise for gold placement
-                let gold_noise = self.tree_noise.sample(
+                let gold_noise = self.loot_noise.sample(
                     block_x as f64 * 0.15,
                     block_z as f64 * 0.15,
                 );
@@ -749,8 +905,8 @@ impl ChunkGenerator {
                         let current = chunk.get_block(local_x, y, local_z);
                         
                         // Place on solid surfaces
-                        if (below.id == 7 || below.id == 1 || below.id == 2) && current.id == 0 {
-                            chunk.set_block(local_x, y, local_z, Block::new(6));
+                        if [Block::METAL_BRIDGE.id, Block::CONCRETE_FLOOR.id, Block::CONCRETE_WALL.id].contains(&below.id) && current.is_air() {
+                            chunk.set_block(local_x, y, local_z, Block::GOLD_LOOT);
                             break;
                         }
                     }
@@ -760,8 +916,8 @@ impl ChunkGenerator {
                         let below = chunk.get_block(local_x, y - 1, local_z);
                         let current = chunk.get_block(local_x, y, local_z);
                         
-                        if below.id == 2 && current.id == 0 {
-                            chunk.set_block(local_x, y, local_z, Block::new(6));
+                        if below.id == Block::CONCRETE_WALL.id && current.is_air() {
+                            chunk.set_block(local_x, y, local_z, Block::GOLD_LOOT);
                             break;
                         }
                     }
@@ -771,12 +927,11 @@ impl ChunkGenerator {
     }
     
     // =========================================================================
-    // LEGACY ARENA MODE (kept for reference)
+    // FLAT ARENA MODE
     // =========================================================================
     
-    /// Generates a FLAT arena column (LEGACY - replaced by Undercity)
-    #[allow(dead_code)]
-    fn generate_arena_column_legacy(
+    /// Generates a FLAT arena column
+    pub(crate) fn generate_flat_column(
         &self,
         chunk: &mut Chunk,
         local_x: usize,
@@ -788,10 +943,10 @@ impl ChunkGenerator {
         
         for y in 0..CHUNK_HEIGHT {
             let block = if y == 0 {
-                Block::new(5)
+                Block::BEDROCK
             } else if y == FLOOR_Y {
                 let is_grid_line = (block_x % 8 == 0) || (block_z % 8 == 0);
-                if is_grid_line { Block::new(3) } else { Block::new(2) }
+                if is_grid_line { Block::HAZARD_NEON } else { Block::CONCRETE_WALL }
             } else {
                 Block::AIR
             };
@@ -800,130 +955,8 @@ impl ChunkGenerator {
         chunk.height_map[local_z][local_x] = FLOOR_Y as u8;
     }
     
-    /// Generates vegetation on the chunk (trees, plants).
-    /// UNUSED in Arena mode - kept for future terrain modes.
-    ///
-    /// Must be called after terrain generation.
-    #[allow(dead_code)]
-    fn generate_vegetation(&self, chunk: &mut Chunk, world_x: i32, world_z: i32) {
-        // Iterate over all columns
-        for local_z in 0..CHUNK_SIZE {
-            for local_x in 0..CHUNK_SIZE {
-                let block_x = world_x + local_x as i32;
-                let block_z = world_z + local_z as i32;
-                
-                // Get terrain height and biome
-                let height = chunk.get_height(local_x, local_z) as usize;
-                let biome = chunk.get_biome(local_x, local_z);
-                
-                // Check if biome can have trees
-                let tree_density = biome.tree_density();
-                if tree_density == 0 {
-                    continue;
-                }
-                
-                // Check if surface is grass (can grow trees)
-                let surface_block = chunk.get_block(local_x, height, local_z);
-                if surface_block.id != Block::GRASS.id && surface_block.id != 12 {
-                    // Not grass or jungle grass
-                    continue;
-                }
-                
-                // Use noise for deterministic tree placement
-                let tree_value = self.tree_noise.sample(
-                    block_x as f64 * 0.3, // Lower frequency for clumped forests
-                    block_z as f64 * 0.3,
-                );
-                
-                // Scale chance by biome tree density (2% base for plains, up to 8% for jungles)
-                // tree_density: 5 (plains) to 80 (jungle)
-                // threshold: 0.96 (plains/2%) to 0.6 (jungle/20%)
-                let base_chance = 0.02 + (tree_density as f64 / 100.0) * 0.18;
-                let threshold = 1.0 - base_chance;
-                
-                if tree_value > threshold {
-                    // Spawn tree at this position
-                    self.generate_tree(chunk, local_x, height + 1, local_z, block_x, block_z);
-                }
-            }
-        }
-    }
-    
-    /// Generates a single tree at the given position.
-    /// UNUSED in Arena mode - kept for future terrain modes.
-    #[allow(dead_code)]
-    fn generate_tree(
-        &self,
-        chunk: &mut Chunk,
-        local_x: usize,
-        base_y: usize,
-        local_z: usize,
-        world_x: i32,
-        world_z: i32,
-    ) {
-        // Determine tree height (4-6 blocks) based on detail noise
-        let height_noise = self.detail_noise.sample(world_x as f64 * 0.1, world_z as f64 * 0.1);
-        let tree_height = Self::TREE_MIN_HEIGHT 
-            + ((height_noise + 1.0) * 0.5 * (Self::TREE_MAX_HEIGHT - Self::TREE_MIN_HEIGHT) as f64) as usize;
-        
-        // Check if tree fits in chunk height
-        if base_y + tree_height + 3 >= CHUNK_HEIGHT {
-            return;
-        }
-        
-        // Check if there's space for the trunk (no overlap with other blocks)
-        for y in base_y..base_y + tree_height {
-            if !chunk.get_block(local_x, y, local_z).is_air() {
-                return; // Blocked
-            }
-        }
-        
-        // Generate trunk
-        for y in base_y..base_y + tree_height {
-            chunk.set_block(local_x, y, local_z, Block::WOOD);
-        }
-        
-        // Generate leaves (5x5x3 sphere-ish shape at top)
-        let leaf_base = base_y + tree_height - 2;
-        let leaf_top = base_y + tree_height + 2;
-        
-        for y in leaf_base..leaf_top.min(CHUNK_HEIGHT) {
-            let radius = if y == leaf_base || y == leaf_top - 1 { 2 } else { 2 };
-            
-            for dz in -(radius as i32)..=(radius as i32) {
-                for dx in -(radius as i32)..=(radius as i32) {
-                    let lx = local_x as i32 + dx;
-                    let lz = local_z as i32 + dz;
-                    
-                    // Skip if outside chunk bounds
-                    if lx < 0 || lx >= CHUNK_SIZE as i32 || lz < 0 || lz >= CHUNK_SIZE as i32 {
-                        continue;
-                    }
-                    
-                    // Skip corners for more natural shape
-                    let dist_sq = dx * dx + dz * dz;
-                    if dist_sq > radius * radius + 1 {
-                        continue;
-                    }
-                    
-                    // Don't overwrite trunk
-                    if dx == 0 && dz == 0 && y < base_y + tree_height {
-                        continue;
-                    }
-                    
-                    // Only place leaves in air
-                    if chunk.get_block(lx as usize, y, lz as usize).is_air() {
-                        chunk.set_block(lx as usize, y, lz as usize, Block::LEAVES);
-                    }
-                }
-            }
-        }
-    }
-
-    /// Generates a single column of the chunk (Forest mode).
-    /// UNUSED in Arena mode - kept for future terrain modes.
-    #[allow(dead_code)]
-    fn generate_column(
+    /// Generates a single column of the chunk (biome terrain mode).
+    pub(crate) fn generate_column(
         &self,
         chunk: &mut Chunk,
         local_x: usize,
@@ -939,31 +972,28 @@ impl ChunkGenerator {
         chunk.set_biome(local_x, local_z, biome);
 
         // Get terrain height
-        let terrain_height = self.classifier
-            .get_terrain_height(fx, fz, self.sea_level, CHUNK_HEIGHT as i32)
-            .max(0)
-            .min(CHUNK_HEIGHT as i32 - 1) as usize;
+        let terrain_height = self.terrain_height(block_x, block_z) as usize;
 
         // Get surface block for this biome
-        let surface_block = Block::new(biome.surface_block() as u16);
+        let surface_block = Block::new(self.classifier.registry().surface_block(biome));
 
         // Generate blocks from bottom to top
         for y in 0..CHUNK_HEIGHT {
             let block = if y == 0 {
                 // Bedrock at bottom
-                Block::new(7) // Bedrock ID
+                Block::BEDROCK
             } else if y < terrain_height.saturating_sub(4) {
                 // Deep stone
-                Block::new(2) // Stone ID
+                Block::STONE
             } else if y < terrain_height {
                 // Dirt/subsurface
-                Block::new(3) // Dirt ID
+                Block::DIRT
             } else if y == terrain_height {
                 // Surface
                 surface_block
             } else if y < self.sea_level as usize && terrain_height < self.sea_level as usize {
                 // Water
-                Block::new(10) // Water ID
+                Block::WATER
             } else {
                 // Air
                 Block::AIR
@@ -1025,6 +1055,28 @@ impl ChunkGenerator {
     }
 }
 
+impl WorldGenerator for ChunkGenerator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn seed(&self) -> WorldSeed {
+        self.seed
+    }
+
+    fn sea_level(&self) -> i32 {
+        self.sea_level
+    }
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn generate(&self, coord: ChunkCoord) -> Chunk {
+        ChunkGenerator::generate(self, coord)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1068,8 +1120,8 @@ mod tests {
         let gen = ChunkGenerator::new(WorldSeed::new(42));
         let chunk = gen.generate(ChunkCoord::new(0, 0));
 
-        // Should have bedrock at bottom (ID 5 in new palette)
-        assert_eq!(chunk.get_block(0, 0, 0).id, 5, "Should have bedrock at y=0");
+        // Should have bedrock at bottom
+        assert_eq!(chunk.get_block(0, 0, 0), Block::BEDROCK, "Should have bedrock at y=0");
 
         // Should have some non-air blocks
         let mut solid_count = 0;
@@ -1222,4 +1274,41 @@ mod tests {
         // Cleanup
         std::fs::remove_file(&temp_path).ok();
     }
+
+    #[test]
+    fn test_generated_blocks_are_registered() {
+        for preset in [GeneratorPreset::Undercity, GeneratorPreset::Terrain, GeneratorPreset::Flat] {
+            let gen = ChunkGenerator::from_preset(WorldSeed::new(7), preset);
+            gen.check_blocks().unwrap();
+            for coord in [ChunkCoord::new(0, 0), ChunkCoord::new(5, -3), ChunkCoord::new(-40, 12)] {
+                let chunk = gen.generate(coord);
+                for y in 0..CHUNK_HEIGHT {
+                    for z in 0..CHUNK_SIZE {
+                        for x in 0..CHUNK_SIZE {
+                            let id = chunk.get_block(x, y, z).id;
+                            assert!(gen.blocks().get(id).is_some(), "{preset:?} placed unknown block {id}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_blocks_rejects_missing_block() {
+        let blocks = BlockRegistry::from_defs(
+            BlockRegistry::shipped()
+                .blocks()
+                .iter()
+                .filter(|def| def.id != Block::SAND.id)
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+        let gen = ChunkGenerator::new(WorldSeed::new(7)).with_blocks(Arc::new(blocks));
+        assert!(matches!(
+            gen.check_blocks(),
+            Err(BlockConfigError::UnknownBlock { id, .. }) if id == Block::SAND.id
+        ));
+    }
 }
@@ -7,7 +7,8 @@
 //! When a player modifies a block (mining, placing), we:
 //! 1. Record the modification in the WorldManager's in-memory log
 //! 2. Write to the batched WAL for durability
-//! 3. On chunk reload, check WAL for modifications before generating
+//! 3. On chunk unload, flush the modifications to region files under `db_path`
+//! 4. On chunk reload, replay them from the region file before use
 //!
 //! ## Performance
 //!
@@ -16,9 +17,10 @@
 //! - Fresh terrain uses procedural generation (no I/O)
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::chunk::{Block, ChunkCoord, CHUNK_SIZE};
+use crate::region::{RegionError, RegionStore, StorageMode};
 use crate::world_manager::{ChunkModification, ModificationEntry, WorldManager};
 use crate::noise::WorldSeed;
 
@@ -109,8 +111,7 @@ impl BlockModifyPayload {
 pub struct ChunkPersistence {
     /// World manager for chunk generation.
     world: WorldManager,
-    /// Path to modification database.
-    #[allow(dead_code)]
+    /// Directory of the region files.
     db_path: PathBuf,
     /// Cached modifications loaded from disk.
     cached_mods: HashMap<ChunkCoord, Vec<ChunkModification>>,
@@ -124,17 +125,24 @@ impl ChunkPersistence {
     /// # Arguments
     ///
     /// * `seed` - World seed for procedural generation
-    /// * `db_path` - Path to store modification data
+    /// * `db_path` - Directory for region files (created on first save)
     #[must_use]
     pub fn new(seed: WorldSeed, db_path: PathBuf) -> Self {
         Self {
-            world: WorldManager::with_seed(seed),
+            world: WorldManager::with_seed(seed)
+                .with_storage(RegionStore::new(&db_path), StorageMode::Overlay),
             db_path,
             cached_mods: HashMap::new(),
             current_tick: 0,
         }
     }
 
+    /// Returns the directory of the region files.
+    #[must_use]
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
     /// Returns reference to the world manager.
     #[must_use]
     pub fn world(&self) -> &WorldManager {
@@ -234,6 +242,15 @@ impl ChunkPersistence {
         self.world.has_ground(world_x, world_y, world_z)
     }
 
+    /// Writes every modified chunk to the region files and waits for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a region can't be written.
+    pub fn save(&mut self) -> Result<usize, RegionError> {
+        self.world.save_all()
+    }
+
     /// Exports all modifications for checkpoint saving.
     #[must_use]
     pub fn export_all_modifications(&self) -> Vec<ModificationEntry> {
@@ -0,0 +1,388 @@
+//! # City Settings
+//!
+//! Loaded from `data/schemas/world/cities.toml`; the shipped file is
+//! embedded in the binary and used unless another is given.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
+use oroboros_core::embedded::EmbeddedData;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::chunk::CHUNK_HEIGHT;
+
+/// The shipped city settings.
+const SHIPPED_CITIES: &str = include_str!("../../../../data/schemas/world/cities.toml");
+
+/// Why city settings could not be loaded.
+#[derive(Error, Debug)]
+pub enum CityConfigError {
+    /// Reading the file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid TOML or is missing fields.
+    #[error("invalid city config: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// A minimum is above its maximum, or a size is too small to build on.
+    #[error("city {0} is out of range")]
+    Range(String),
+    /// There isn't exactly one core district.
+    #[error("city needs exactly one core district, found {0}")]
+    Core(usize),
+    /// Two districts or two neon entries share a name.
+    #[error("duplicate name: {0}")]
+    DuplicateName(String),
+    /// A landmark names a district that isn't defined.
+    #[error("landmark {landmark} is in unknown district {district}")]
+    UnknownDistrict {
+        /// The landmark.
+        landmark: String,
+        /// The district it names.
+        district: String,
+    },
+    /// Something names a neon colour that isn't defined.
+    #[error("{referrer} uses unknown neon {neon}")]
+    UnknownNeon {
+        /// What names it.
+        referrer: String,
+        /// The neon it names.
+        neon: String,
+    },
+    /// A building or landmark would reach above the chunk.
+    #[error("{0} reaches above the top of the world")]
+    TooTall(String),
+}
+
+/// `[layout]`: the tile grid.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct CityLayout {
+    /// Blocks per tile side.
+    pub tile_size: i32,
+    /// Tiles per city side.
+    pub size: usize,
+    /// First block above the road surface.
+    pub street_level: i32,
+    /// Blocks per building storey.
+    pub floor_height: i32,
+    /// District count; each is one Voronoi site.
+    pub districts: usize,
+}
+
+/// `[roads]`: how the road network grows.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct RoadSettings {
+    /// Highway width, in tiles.
+    pub highway_width: usize,
+    /// Street width, in tiles.
+    pub street_width: usize,
+    /// Chance a street keeps going past a junction (0-1).
+    pub continue_chance: f64,
+    /// Chance of a side street at a junction, per side (0-1).
+    pub branch_chance: f64,
+    /// Growth steps highways stay ahead of the streets they spawn.
+    pub branch_delay: u32,
+    /// Most road segments in a city.
+    pub max_segments: usize,
+    /// Tiles between lamps along highways.
+    pub lamp_spacing: usize,
+    /// Neon the lamps glow with.
+    pub lamp_neon: String,
+}
+
+/// `[blocks]`: what the city is built from.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct CityBlocks {
+    /// Road surface.
+    pub road: BlockId,
+    /// Ground between roads, and building floors.
+    pub sidewalk: BlockId,
+    /// Building walls.
+    pub wall: BlockId,
+    /// Building windows.
+    pub window: BlockId,
+    /// Roofs, building corners and lamp posts.
+    pub trim: BlockId,
+}
+
+/// `[lights]`: how signs behave.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct LightSettings {
+    /// Chance a sign flickers (0-1).
+    pub flicker_chance: f64,
+}
+
+/// One `[[district]]`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct DistrictDef {
+    /// Unique name.
+    pub name: String,
+    /// True for the one district at the city centre.
+    #[serde(default)]
+    pub core: bool,
+    /// Relative share of the other Voronoi sites.
+    pub weight: f64,
+    /// Tiles between junctions along a road.
+    pub block_size: usize,
+    /// Narrowest building, in tiles.
+    pub lot_min: usize,
+    /// Widest building, in tiles.
+    pub lot_max: usize,
+    /// Lowest building, in blocks.
+    pub height_min: i32,
+    /// Tallest building, in blocks.
+    pub height_max: i32,
+    /// Chance a building has a sign (0-1).
+    pub sign_chance: f64,
+    /// Neon its signs use.
+    pub neon: Vec<String>,
+}
+
+/// Landmark shapes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LandmarkShape {
+    /// The lot's largest building, raised to the landmark's height.
+    Spire,
+    /// The whole lot cleared into a square with a beacon.
+    Plaza,
+}
+
+/// One `[[landmark]]`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct LandmarkDef {
+    /// Display name.
+    pub name: String,
+    /// District it stands in.
+    pub district: String,
+    /// What it looks like.
+    pub shape: LandmarkShape,
+    /// Spire height, in blocks; plazas ignore it.
+    #[serde(default)]
+    pub height: i32,
+    /// Neon it glows with.
+    pub neon: String,
+}
+
+/// One `[[neon]]`: a sign block and the light it casts.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct NeonDef {
+    /// Unique name.
+    pub name: String,
+    /// Sign block.
+    pub block: BlockId,
+    /// Light colour (HDR, may exceed 1).
+    pub color: [f32; 3],
+    /// Light radius, in blocks.
+    pub radius: f32,
+    /// Light intensity.
+    pub intensity: f32,
+}
+
+/// Validated city settings.
+///
+/// ```rust,ignore
+/// let cities = Arc::new(CityConfig::load("mods/cities.toml")?);
+/// cities.check_blocks(generator.blocks())?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CityConfig {
+    /// `[layout]`.
+    pub layout: CityLayout,
+    /// `[roads]`.
+    pub roads: RoadSettings,
+    /// `[blocks]`.
+    pub blocks: CityBlocks,
+    /// `[lights]`.
+    pub lights: LightSettings,
+    /// `[[district]]`, in file order.
+    #[serde(rename = "district")]
+    pub districts: Vec<DistrictDef>,
+    /// `[[landmark]]`, in placement order.
+    #[serde(rename = "landmark", default)]
+    pub landmarks: Vec<LandmarkDef>,
+    /// `[[neon]]`.
+    pub neon: Vec<NeonDef>,
+}
+
+impl CityConfig {
+    /// Returns the settings from the shipped `cities.toml`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shipped city lacks exactly one core district, places a
+    /// landmark in an unknown district or builds above the top of the
+    /// world.
+    #[must_use]
+    pub fn shipped() -> Arc<Self> {
+        static SHIPPED: EmbeddedData<CityConfig, CityConfigError> =
+            EmbeddedData::new("cities.toml", SHIPPED_CITIES, CityConfig::from_toml);
+        SHIPPED.get()
+    }
+
+    /// Loads settings from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or the settings are
+    /// invalid (see [`validate`](Self::validate)).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CityConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses settings in the `cities.toml` format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text can't be parsed or the settings are invalid.
+    pub fn from_toml(text: &str) -> Result<Self, CityConfigError> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks sizes and ranges, that there is one core district, that
+    /// names resolve and that nothing is taller than the world.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first invalid setting.
+    pub fn validate(&self) -> Result<(), CityConfigError> {
+        let layout = &self.layout;
+        let range = |what: &str| Err(CityConfigError::Range(what.to_owned()));
+        // Buildings are inset one block from their tiles, so need 2 inside
+        if layout.tile_size < 4 {
+            return range("tile_size");
+        }
+        if layout.size < 8 || layout.districts == 0 || layout.floor_height < 2 {
+            return range("layout");
+        }
+        if self.roads.highway_width == 0 || self.roads.street_width == 0 || self.roads.lamp_spacing == 0 {
+            return range("road width");
+        }
+
+        let mut names = HashSet::new();
+        for neon in &self.neon {
+            if !names.insert(neon.name.as_str()) {
+                return Err(CityConfigError::DuplicateName(neon.name.clone()));
+            }
+        }
+        self.neon(&self.roads.lamp_neon, "lamps")?;
+
+        let top = CHUNK_HEIGHT as i32 - layout.street_level;
+        let mut districts = HashSet::new();
+        for district in &self.districts {
+            if !districts.insert(district.name.as_str()) {
+                return Err(CityConfigError::DuplicateName(district.name.clone()));
+            }
+            if district.lot_min == 0 || district.lot_min > district.lot_max || district.block_size < 2 {
+                return range(&format!("{} lot size", district.name));
+            }
+            if district.height_min < layout.floor_height || district.height_min > district.height_max {
+                return range(&format!("{} height", district.name));
+            }
+            if district.height_max >= top {
+                return Err(CityConfigError::TooTall(district.name.clone()));
+            }
+            if district.neon.is_empty() {
+                return range(&format!("{} neon", district.name));
+            }
+            for neon in &district.neon {
+                self.neon(neon, &district.name)?;
+            }
+        }
+        let cores = self.districts.iter().filter(|district| district.core).count();
+        if cores != 1 {
+            return Err(CityConfigError::Core(cores));
+        }
+        if layout.districts < self.districts.len() {
+            return range("district count");
+        }
+
+        for landmark in &self.landmarks {
+            if !districts.contains(landmark.district.as_str()) {
+                return Err(CityConfigError::UnknownDistrict {
+                    landmark: landmark.name.clone(),
+                    district: landmark.district.clone(),
+                });
+            }
+            self.neon(&landmark.neon, &landmark.name)?;
+            if landmark.shape == LandmarkShape::Spire && landmark.height < layout.floor_height {
+                return range(&format!("{} height", landmark.name));
+            }
+            if landmark.height >= top {
+                return Err(CityConfigError::TooTall(landmark.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks up a neon by name.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no neon has that name; `referrer` is named in the error.
+    pub fn neon(&self, name: &str, referrer: &str) -> Result<&NeonDef, CityConfigError> {
+        self.neon
+            .iter()
+            .find(|neon| neon.name == name)
+            .ok_or_else(|| CityConfigError::UnknownNeon {
+                referrer: referrer.to_owned(),
+                neon: name.to_owned(),
+            })
+    }
+
+    /// Returns the core district's index.
+    #[must_use]
+    pub fn core(&self) -> usize {
+        self.districts.iter().position(|district| district.core).unwrap_or(0)
+    }
+
+    /// Checks that every block the city places is defined.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first undefined block ID.
+    pub fn check_blocks(&self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        let CityBlocks {
+            road,
+            sidewalk,
+            wall,
+            window,
+            trim,
+        } = self.blocks;
+        blocks.check_ids("city", [road, sidewalk, wall, window, trim])?;
+        blocks.check_ids("city neon", self.neon.iter().map(|neon| neon.block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_config() {
+        let config = CityConfig::shipped();
+        config.check_blocks(&BlockRegistry::shipped()).unwrap();
+        assert_eq!(config.districts[config.core()].name, "downtown");
+
+        let mut tall = (*config).clone();
+        tall.landmarks[0].height = 240;
+        assert!(matches!(tall.validate(), Err(CityConfigError::TooTall(name)) if name == "Oroboros Spire"));
+
+        let mut lost = (*config).clone();
+        lost.landmarks[1].district = "harbour".to_owned();
+        assert!(matches!(lost.validate(), Err(CityConfigError::UnknownDistrict { .. })));
+
+        let mut dark = (*config).clone();
+        dark.districts[2].neon.push("ultraviolet".to_owned());
+        assert!(matches!(dark.validate(), Err(CityConfigError::UnknownNeon { neon, .. }) if neon == "ultraviolet"));
+
+        let mut headless = (*config).clone();
+        headless.districts[0].core = false;
+        assert!(matches!(headless.validate(), Err(CityConfigError::Core(0))));
+    }
+}
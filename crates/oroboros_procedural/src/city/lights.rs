@@ -0,0 +1,237 @@
+//! # City Lights
+//!
+//! Neon signs, street lamps and landmark beacons: the blocks that glow and
+//! the lights they cast.
+//!
+//! Lights are plain data so this crate doesn't depend on the renderer.
+//! Each [`CityLight`] carries what `NeonLight::neon_sign` (or
+//! `NeonLight::spot` when it has a [`Spot`]) takes; the renderer keeps
+//! only the ones near the camera, since a frame holds 256.
+
+use super::config::{CityConfig, LandmarkShape, NeonDef};
+use super::lots::Parcels;
+use super::roads::RoadNetwork;
+use crate::chunk::Block;
+use crate::noise::ChunkRng;
+
+/// What casts a light.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LightSource {
+    /// A neon sign on a building.
+    Sign,
+    /// A lamp in a highway median.
+    StreetLamp,
+    /// A landmark's glow or beacon.
+    Landmark,
+}
+
+/// Cone of a spotlight.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spot {
+    /// Unit direction it shines.
+    pub direction: [f32; 3],
+    /// Half-angle, in radians.
+    pub angle: f32,
+}
+
+/// A light to hand to the renderer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CityLight {
+    /// Position, in world units.
+    pub position: [f32; 3],
+    /// Colour (HDR, may exceed 1).
+    pub color: [f32; 3],
+    /// Radius, in world units.
+    pub radius: f32,
+    /// Intensity.
+    pub intensity: f32,
+    /// Flicker speed; 0 for a steady light.
+    pub flicker_speed: f32,
+    /// Cone, for spotlights.
+    pub spot: Option<Spot>,
+    /// What casts it.
+    pub source: LightSource,
+}
+
+impl CityLight {
+    fn point(position: [f32; 3], neon: &NeonDef, source: LightSource) -> Self {
+        Self {
+            position,
+            color: neon.color,
+            radius: neon.radius,
+            intensity: neon.intensity,
+            flicker_speed: 0.0,
+            spot: None,
+            source,
+        }
+    }
+}
+
+/// Glowing blocks and their lights.
+#[derive(Default)]
+pub(crate) struct Lighting {
+    pub(crate) lights: Vec<CityLight>,
+    /// Blocks to place over the buildings, in world blocks.
+    pub(crate) features: Vec<([i32; 3], Block)>,
+}
+
+/// Centre of a block, in world units.
+fn centre([x, y, z]: [i32; 3]) -> [f32; 3] {
+    [x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5]
+}
+
+/// Where a vertical strip down the middle of a face stands, and the
+/// face's outward normal. Faces are -x, +x, -z, +z.
+fn face_column(min: [i32; 3], max: [i32; 3], face: usize) -> ([i32; 2], [f32; 2]) {
+    let middle = [(min[0] + max[0]) / 2, (min[2] + max[2]) / 2];
+    match face {
+        0 => ([min[0], middle[1]], [-1.0, 0.0]),
+        1 => ([max[0] - 1, middle[1]], [1.0, 0.0]),
+        2 => ([middle[0], min[2]], [0.0, -1.0]),
+        _ => ([middle[0], max[2] - 1], [0.0, 1.0]),
+    }
+}
+
+impl Lighting {
+    /// Hangs signs, stands lamps and lights the landmarks.
+    ///
+    /// Names were checked by [`CityConfig::validate`], so lookups that
+    /// fail just leave things dark.
+    pub(crate) fn build(
+        config: &CityConfig,
+        parcels: &Parcels,
+        roads: &RoadNetwork,
+        origin: [i32; 2],
+        rng: &mut ChunkRng,
+    ) -> Self {
+        let mut lighting = Self::default();
+        let street = config.layout.street_level;
+
+        // Signs: a vertical strip on one face, lit from just outside it
+        for building in parcels.buildings.iter().filter(|building| building.landmark.is_none()) {
+            let district = &config.districts[parcels.lots[building.lot].district];
+            if building.height() < 6 || rng.next_f64() >= district.sign_chance {
+                continue;
+            }
+            let name = &district.neon[rng.range_i32(0, district.neon.len() as i32 - 1) as usize];
+            let Ok(neon) = config.neon(name, &district.name) else {
+                continue;
+            };
+            let face = rng.range_i32(0, 3) as usize;
+            let length = rng.range_i32(3, (building.height() - 3).min(10));
+            let ([x, z], normal) = face_column(building.min, building.max, face);
+            let bottom = street + 2;
+            for y in bottom..bottom + length {
+                lighting.features.push(([x, y, z], Block::new(neon.block)));
+            }
+
+            let [cx, cy, cz] = centre([x, bottom, z]);
+            let mut light = CityLight::point(
+                [cx + normal[0] * 1.5, cy + length as f32 / 2.0, cz + normal[1] * 1.5],
+                neon,
+                LightSource::Sign,
+            );
+            if rng.next_f64() < config.lights.flicker_chance {
+                light.flicker_speed = rng.range_f64(1.0, 4.0) as f32;
+            }
+            lighting.lights.push(light);
+        }
+
+        // Lamps: a trim post with a glowing head
+        if let Ok(neon) = config.neon(&config.roads.lamp_neon, "lamps") {
+            for &[x, z] in roads.lamps() {
+                let [x, z] = [origin[0] + x, origin[1] + z];
+                for y in street..street + 4 {
+                    lighting.features.push(([x, y, z], Block::new(config.blocks.trim)));
+                }
+                lighting.features.push(([x, street + 4, z], Block::new(neon.block)));
+                lighting.lights.push(CityLight::point(centre([x, street + 4, z]), neon, LightSource::StreetLamp));
+            }
+        }
+
+        for landmark in &parcels.landmarks {
+            let Some(def) = config.landmarks.iter().find(|def| def.name == landmark.name) else {
+                continue;
+            };
+            let Ok(neon) = config.neon(&def.neon, &def.name) else {
+                continue;
+            };
+            match landmark.shape {
+                LandmarkShape::Spire => lighting.light_spire(parcels, landmark.lot, neon),
+                LandmarkShape::Plaza => lighting.light_plaza(config, parcels, landmark.lot, origin, neon),
+            }
+            // Beacon: a spotlight up into the fog
+            let [x, y, z] = centre(landmark.position);
+            lighting.lights.push(CityLight {
+                position: [x, y + 2.0, z],
+                radius: neon.radius * 6.0,
+                intensity: neon.intensity * 2.0,
+                spot: Some(Spot {
+                    direction: [0.0, 1.0, 0.0],
+                    angle: 0.35,
+                }),
+                ..CityLight::point([x, y + 2.0, z], neon, LightSource::Landmark)
+            });
+        }
+        lighting
+    }
+
+    /// Runs neon up the middle of every face of the spire, with a light
+    /// halfway up each.
+    fn light_spire(&mut self, parcels: &Parcels, lot: usize, neon: &NeonDef) {
+        let Some(spire) = parcels.buildings.iter().find(|building| building.lot == lot && building.landmark.is_some()) else {
+            return;
+        };
+        for face in 0..4 {
+            let ([x, z], normal) = face_column(spire.min, spire.max, face);
+            for y in spire.min[1] + 1..spire.max[1] - 1 {
+                self.features.push(([x, y, z], Block::new(neon.block)));
+            }
+            let [cx, cy, cz] = centre([x, (spire.min[1] + spire.max[1]) / 2, z]);
+            self.lights.push(CityLight {
+                radius: neon.radius * 2.0,
+                ..CityLight::point([cx + normal[0] * 1.5, cy, cz + normal[1] * 1.5], neon, LightSource::Landmark)
+            });
+        }
+    }
+
+    /// Paves the plaza in trim, rings it in neon and raises an obelisk in
+    /// the middle.
+    fn light_plaza(&mut self, config: &CityConfig, parcels: &Parcels, lot: usize, origin: [i32; 2], neon: &NeonDef) {
+        let layout = &config.layout;
+        let tile_size = layout.tile_size;
+        let floor = layout.street_level - 1;
+        let in_lot = |[x, z]: [i32; 2]| {
+            let tile = [(x - origin[0]).div_euclid(tile_size), (z - origin[1]).div_euclid(tile_size)];
+            tile.iter().all(|&t| (0..layout.size as i32).contains(&t))
+                && parcels.lot_of[tile[1] as usize * layout.size + tile[0] as usize] == Some(lot)
+        };
+
+        let bounds = &parcels.lots[lot];
+        let min = [origin[0] + bounds.min[0] as i32 * tile_size, origin[1] + bounds.min[1] as i32 * tile_size];
+        let max = [origin[0] + bounds.max[0] as i32 * tile_size, origin[1] + bounds.max[1] as i32 * tile_size];
+        for z in min[1]..max[1] {
+            for x in min[0]..max[0] {
+                if !in_lot([x, z]) {
+                    continue;
+                }
+                // Neon where the plaza meets anything that isn't plaza
+                let edge = [[x - 1, z], [x + 1, z], [x, z - 1], [x, z + 1]].iter().any(|&next| !in_lot(next));
+                let block = if edge { neon.block } else { config.blocks.trim };
+                self.features.push(([x, floor, z], Block::new(block)));
+            }
+        }
+
+        if let Some(landmark) = parcels.landmarks.iter().find(|landmark| landmark.lot == lot) {
+            let [x, y, z] = landmark.position;
+            for height in 0..6 {
+                self.features.push(([x, y + height, z], Block::new(config.blocks.trim)));
+            }
+            self.features.push(([x, y + 6, z], Block::new(neon.block)));
+            self.lights.push(CityLight {
+                radius: neon.radius * 3.0,
+                ..CityLight::point(centre([x, y + 6, z]), neon, LightSource::Landmark)
+            });
+        }
+    }
+}
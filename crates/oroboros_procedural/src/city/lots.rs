@@ -0,0 +1,274 @@
+//! # Lots and Buildings
+//!
+//! The land between roads, cut into building footprints.
+//!
+//! ```text
+//! ┌──────────────────┐        ┌──────┬─────┬────┐
+//! │                  │  split │ ▓▓▓▓ │ ▓▓▓ │ ▓▓ │   ▓ = footprint, inset one
+//! │       lot        │ ─────► ├──────┴─┬───┴────┤       block for the sidewalk
+//! │                  │        │ ▓▓▓▓▓▓ │ ▓▓▓▓▓▓ │
+//! └──────────────────┘        └────────┴────────┘
+//! ```
+//!
+//! A lot is one connected patch of non-road tiles. Its bounding box is
+//! split along the longer side until pieces are at most the district's
+//! `lot_max`; pieces that fall outside the lot (lots aren't always
+//! rectangles) are split again or left open. Heights are rolled from the
+//! district's range. Landmarks then take the largest free lot in their
+//! district.
+
+use std::cmp::Reverse;
+
+use super::config::{CityConfig, LandmarkShape};
+use super::roads::{DistrictMap, RoadNetwork};
+use crate::noise::ChunkRng;
+
+/// One connected patch of land between roads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lot {
+    /// District most of its tiles are in.
+    pub district: usize,
+    /// Lowest tile of its bounding box.
+    pub min: [usize; 2],
+    /// One past the highest tile of its bounding box.
+    pub max: [usize; 2],
+    /// Tiles in the lot.
+    pub tiles: usize,
+    /// Landmark standing on it.
+    pub landmark: Option<usize>,
+}
+
+/// A building's box, in world blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Building {
+    /// Lot it stands on.
+    pub lot: usize,
+    /// Lowest block (at street level).
+    pub min: [i32; 3],
+    /// One past the highest block.
+    pub max: [i32; 3],
+    /// Landmark it is, if any.
+    pub landmark: Option<usize>,
+}
+
+impl Building {
+    /// Returns the height, in blocks.
+    #[must_use]
+    pub const fn height(&self) -> i32 {
+        self.max[1] - self.min[1]
+    }
+
+    /// Returns the footprint area, in blocks.
+    #[must_use]
+    pub const fn area(&self) -> i32 {
+        (self.max[0] - self.min[0]) * (self.max[2] - self.min[2])
+    }
+}
+
+/// A placed landmark.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Landmark {
+    /// Name from the config.
+    pub name: String,
+    /// Spire or plaza.
+    pub shape: LandmarkShape,
+    /// Lot it stands on.
+    pub lot: usize,
+    /// Centre of its base, in world blocks.
+    pub position: [i32; 3],
+}
+
+/// Lots, buildings and landmarks of one city.
+pub(crate) struct Parcels {
+    pub(crate) lots: Vec<Lot>,
+    /// Lot index per tile, row by row; `None` on roads.
+    pub(crate) lot_of: Vec<Option<usize>>,
+    pub(crate) buildings: Vec<Building>,
+    pub(crate) landmarks: Vec<Landmark>,
+}
+
+impl Parcels {
+    /// Finds the lots between `roads` and fills them with buildings.
+    pub(crate) fn build(
+        config: &CityConfig,
+        districts: &DistrictMap,
+        roads: &RoadNetwork,
+        origin: [i32; 2],
+        rng: &mut ChunkRng,
+    ) -> Self {
+        let size = config.layout.size;
+        let mut parcels = Self {
+            lots: Vec::new(),
+            lot_of: vec![None; size * size],
+            buildings: Vec::new(),
+            landmarks: Vec::new(),
+        };
+
+        // Lots: flood fill the land between roads
+        for first in 0..size * size {
+            let tile = [first % size, first / size];
+            if parcels.lot_of[first].is_some() || roads.at(tile).is_some() {
+                continue;
+            }
+            let id = parcels.lots.len();
+            let mut lot = Lot {
+                district: districts.at(tile),
+                min: tile,
+                max: [tile[0] + 1, tile[1] + 1],
+                tiles: 0,
+                landmark: None,
+            };
+            parcels.lot_of[first] = Some(id);
+            let mut counts = vec![0; config.districts.len()];
+            let mut pending = vec![tile];
+            while let Some([x, z]) = pending.pop() {
+                lot.tiles += 1;
+                counts[districts.at([x, z])] += 1;
+                lot.min = [lot.min[0].min(x), lot.min[1].min(z)];
+                lot.max = [lot.max[0].max(x + 1), lot.max[1].max(z + 1)];
+                let neighbours = [[x.wrapping_sub(1), z], [x + 1, z], [x, z.wrapping_sub(1)], [x, z + 1]];
+                for next in neighbours {
+                    if next[0] >= size || next[1] >= size {
+                        continue;
+                    }
+                    let index = next[1] * size + next[0];
+                    if parcels.lot_of[index].is_none() && roads.at(next).is_none() {
+                        parcels.lot_of[index] = Some(id);
+                        pending.push(next);
+                    }
+                }
+            }
+            // Ties go to the lower index, so the result doesn't depend on fill order
+            lot.district = (0..counts.len()).max_by_key(|&district| (counts[district], Reverse(district))).unwrap_or(lot.district);
+            parcels.lots.push(lot);
+        }
+
+        // Buildings: split each lot's bounding box into footprints
+        for id in 0..parcels.lots.len() {
+            let Lot { min, max, district, .. } = parcels.lots[id];
+            let def = &config.districts[district];
+            let mut pieces = Vec::new();
+            parcels.split(size, id, [min, max], [def.lot_min, def.lot_max], rng, &mut pieces);
+            for [piece_min, piece_max] in pieces {
+                let height = rng.range_i32(def.height_min, def.height_max);
+                parcels.buildings.push(footprint(config, origin, id, piece_min, piece_max, height));
+            }
+        }
+
+        parcels.place_landmarks(config, origin);
+        parcels
+    }
+
+    /// Returns true if every tile of the box is in lot `id`.
+    fn covers(&self, size: usize, id: usize, [min, max]: [[usize; 2]; 2]) -> bool {
+        (min[1]..max[1]).all(|z| (min[0]..max[0]).all(|x| self.lot_of[z * size + x] == Some(id)))
+    }
+
+    /// Splits a box until pieces fit `lot_max` and lie inside the lot.
+    fn split(
+        &self,
+        size: usize,
+        id: usize,
+        piece: [[usize; 2]; 2],
+        [lot_min, lot_max]: [usize; 2],
+        rng: &mut ChunkRng,
+        out: &mut Vec<[[usize; 2]; 2]>,
+    ) {
+        let [min, max] = piece;
+        let extent = [max[0] - min[0], max[1] - min[1]];
+        let axis = usize::from(extent[1] > extent[0]);
+        let long = extent[axis];
+        let inside = self.covers(size, id, piece);
+        if inside && long <= lot_max {
+            out.push(piece);
+            return;
+        }
+        if long < 2 {
+            // A single tile outside the lot stays open ground
+            if inside {
+                out.push(piece);
+            }
+            return;
+        }
+
+        // Keep both halves at least lot_min wide when there's room
+        let cut = if long >= 2 * lot_min {
+            rng.range_i32(lot_min as i32, (long - lot_min) as i32) as usize
+        } else {
+            long / 2
+        };
+        let (mut first_max, mut second_min) = (max, min);
+        first_max[axis] = min[axis] + cut;
+        second_min[axis] = min[axis] + cut;
+        self.split(size, id, [min, first_max], [lot_min, lot_max], rng, out);
+        self.split(size, id, [second_min, max], [lot_min, lot_max], rng, out);
+    }
+
+    /// Puts each landmark on the largest free lot of its district: a
+    /// spire raises the lot's largest building, a plaza clears the lot.
+    fn place_landmarks(&mut self, config: &CityConfig, origin: [i32; 2]) {
+        for def in &config.landmarks {
+            let Some(district) = config.districts.iter().position(|district| district.name == def.district) else {
+                continue;
+            };
+            let lot = self
+                .lots
+                .iter()
+                .enumerate()
+                .filter(|(id, lot)| {
+                    lot.district == district
+                        && lot.landmark.is_none()
+                        && self.buildings.iter().any(|building| building.lot == *id)
+                })
+                .max_by_key(|(id, lot)| (lot.tiles, Reverse(*id)))
+                .map(|(id, _)| id);
+            let Some(lot) = lot else {
+                continue;
+            };
+
+            let index = self.landmarks.len();
+            let position = match def.shape {
+                LandmarkShape::Spire => {
+                    let Some(building) = self
+                        .buildings
+                        .iter_mut()
+                        .filter(|building| building.lot == lot)
+                        .max_by_key(|building| building.area())
+                    else {
+                        continue;
+                    };
+                    building.max[1] = building.min[1] + def.height;
+                    building.landmark = Some(index);
+                    [(building.min[0] + building.max[0]) / 2, building.max[1], (building.min[2] + building.max[2]) / 2]
+                }
+                LandmarkShape::Plaza => {
+                    self.buildings.retain(|building| building.lot != lot);
+                    let Lot { min, max, .. } = self.lots[lot];
+                    let tile_size = config.layout.tile_size;
+                    let centre = |axis: usize| origin[axis] + (min[axis] + max[axis]) as i32 * tile_size / 2;
+                    [centre(0), config.layout.street_level, centre(1)]
+                }
+            };
+            self.lots[lot].landmark = Some(index);
+            self.landmarks.push(Landmark {
+                name: def.name.clone(),
+                shape: def.shape,
+                lot,
+                position,
+            });
+        }
+    }
+}
+
+/// A building on the tiles `min..max`, inset one block for the sidewalk.
+fn footprint(config: &CityConfig, origin: [i32; 2], lot: usize, min: [usize; 2], max: [usize; 2], height: i32) -> Building {
+    let tile_size = config.layout.tile_size;
+    let street = config.layout.street_level;
+    let block = |tile: usize, axis: usize| origin[axis] + tile as i32 * tile_size;
+    Building {
+        lot,
+        min: [block(min[0], 0) + 1, street, block(min[1], 1) + 1],
+        max: [block(max[0], 0) - 1, street + height, block(max[1], 1) - 1],
+        landmark: None,
+    }
+}
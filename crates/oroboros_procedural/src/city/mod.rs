@@ -0,0 +1,466 @@
+//! # Cities
+//!
+//! Neon Prime: districts, a grown road network, lots of towers and the
+//! neon that lights them.
+//!
+//! ```text
+//! cities.toml ──► CityConfig
+//!                      │
+//!      seed ──► DistrictMap (Voronoi, core at the centre)
+//!                      │
+//!                      ▼
+//!               RoadNetwork (L-system: highways, then streets)
+//!                      │
+//!                      ▼
+//!               Parcels (lots, buildings, landmarks)
+//!                      │
+//!                      ▼
+//!               Lighting (signs, lamps, beacons) ──► City
+//!                                                     │  lights for NeonLighting
+//!                                                     ▼
+//!                         CityPass builds each chunk's slice
+//! ```
+//!
+//! - [`config`]: layout, road, block, district, landmark and neon settings
+//! - [`roads`]: the district map and the road network
+//! - [`lots`]: lots, building footprints and landmarks
+//! - [`lights`]: neon signs, street lamps and the lights they cast
+//!
+//! Everything above street level inside the city is cleared, and the
+//! ground below is filled, so the city stands on whatever terrain it's
+//! placed over.
+
+pub mod config;
+pub mod lights;
+pub mod lots;
+pub mod roads;
+
+pub use config::{
+    CityBlocks, CityConfig, CityConfigError, CityLayout, DistrictDef, LandmarkDef, LandmarkShape, LightSettings,
+    NeonDef, RoadSettings,
+};
+pub use lights::{CityLight, LightSource, Spot};
+pub use lots::{Building, Landmark, Lot};
+pub use roads::{DistrictMap, RoadKind, RoadNetwork};
+
+use std::sync::Arc;
+
+use crate::chunk::{Block, Chunk, ChunkCoord, ChunkGenerator, CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::generator::{GenerationPass, PerSeed, Stage};
+use crate::noise::{ChunkRng, WorldSeed};
+use lights::Lighting;
+use lots::Parcels;
+
+/// Seed purpose for cities.
+const CITY_SEED: u64 = 108;
+
+/// A generated city: its roads, buildings, landmarks and lights.
+#[derive(Clone, Debug)]
+pub struct City {
+    origin: [i32; 2],
+    layout: CityLayout,
+    blocks: CityBlocks,
+    districts: DistrictMap,
+    roads: RoadNetwork,
+    lots: Vec<Lot>,
+    buildings: Vec<Building>,
+    landmarks: Vec<Landmark>,
+    lights: Vec<CityLight>,
+    /// Signs, lamps and landmark dressing, written over the buildings.
+    features: Vec<([i32; 3], Block)>,
+}
+
+impl City {
+    /// Generates the city whose lowest corner is `origin` (`[x, z]`).
+    #[must_use]
+    pub fn generate(config: &CityConfig, seed: WorldSeed, origin: [i32; 2]) -> Self {
+        let mut rng = ChunkRng::new(seed, 0, 0);
+        let districts = DistrictMap::build(config, &mut rng);
+        let roads = RoadNetwork::grow(config, &districts, &mut rng);
+        let parcels = Parcels::build(config, &districts, &roads, origin, &mut rng);
+        let Lighting { lights, features } = Lighting::build(config, &parcels, &roads, origin, &mut rng);
+        Self {
+            origin,
+            layout: config.layout.clone(),
+            blocks: config.blocks.clone(),
+            districts,
+            roads,
+            lots: parcels.lots,
+            buildings: parcels.buildings,
+            landmarks: parcels.landmarks,
+            lights,
+            features,
+        }
+    }
+
+    /// Returns the lowest corner, in world blocks.
+    #[must_use]
+    pub const fn origin(&self) -> [i32; 2] {
+        self.origin
+    }
+
+    /// Returns the side length, in blocks.
+    #[must_use]
+    pub const fn extent(&self) -> i32 {
+        self.layout.size as i32 * self.layout.tile_size
+    }
+
+    /// Returns the district map.
+    #[must_use]
+    pub const fn districts(&self) -> &DistrictMap {
+        &self.districts
+    }
+
+    /// Returns the road network.
+    #[must_use]
+    pub const fn roads(&self) -> &RoadNetwork {
+        &self.roads
+    }
+
+    /// Returns the lots between roads.
+    #[must_use]
+    pub fn lots(&self) -> &[Lot] {
+        &self.lots
+    }
+
+    /// Returns the buildings, landmark spires included.
+    #[must_use]
+    pub fn buildings(&self) -> &[Building] {
+        &self.buildings
+    }
+
+    /// Returns the placed landmarks. One whose district has no free lot
+    /// left is skipped.
+    #[must_use]
+    pub fn landmarks(&self) -> &[Landmark] {
+        &self.landmarks
+    }
+
+    /// Returns every light in the city.
+    #[must_use]
+    pub fn lights(&self) -> &[CityLight] {
+        &self.lights
+    }
+
+    /// Returns the lights whose position falls in a chunk's columns.
+    pub fn lights_in_chunk(&self, coord: ChunkCoord) -> impl Iterator<Item = &CityLight> {
+        self.lights.iter().filter(move |light| {
+            ChunkCoord::from_world_pos(light.position[0].floor() as i32, light.position[2].floor() as i32) == coord
+        })
+    }
+
+    /// Returns the tile under a world column, if it's in the city.
+    fn tile(&self, x: i32, z: i32) -> Option<[usize; 2]> {
+        let extent = self.extent();
+        let [dx, dz] = [x - self.origin[0], z - self.origin[1]];
+        ((0..extent).contains(&dx) && (0..extent).contains(&dz))
+            .then(|| [(dx / self.layout.tile_size) as usize, (dz / self.layout.tile_size) as usize])
+    }
+
+    /// Returns the district index of a world column, if it's in the city.
+    #[must_use]
+    pub fn district_at(&self, x: i32, z: i32) -> Option<usize> {
+        self.tile(x, z).map(|tile| self.districts.at(tile))
+    }
+
+    /// Returns the road on a world column, if any.
+    #[must_use]
+    pub fn road_at(&self, x: i32, z: i32) -> Option<RoadKind> {
+        self.tile(x, z).and_then(|tile| self.roads.at(tile))
+    }
+
+    /// Block of `building` at a world position inside it: trim corners
+    /// and roof, a band of windows on each storey, floors inside.
+    fn building_block(&self, building: &Building, [x, y, z]: [i32; 3]) -> Block {
+        let storey = (y - building.min[1]) % self.layout.floor_height;
+        let edge_x = x == building.min[0] || x == building.max[0] - 1;
+        let edge_z = z == building.min[2] || z == building.max[2] - 1;
+        let id = if y == building.max[1] - 1 || (edge_x && edge_z) {
+            self.blocks.trim
+        } else if edge_x || edge_z {
+            if (1..=2).contains(&storey) {
+                self.blocks.window
+            } else {
+                self.blocks.wall
+            }
+        } else if storey == 0 {
+            self.blocks.sidewalk
+        } else {
+            return Block::AIR;
+        };
+        Block::new(id)
+    }
+
+    /// Writes the part of the city inside `chunk`. Returns true if any
+    /// block was written.
+    pub fn write(&self, chunk: &mut Chunk) -> bool {
+        let (world_x, world_z) = (chunk.coord.world_x(), chunk.coord.world_z());
+        let extent = self.extent();
+        let min = [world_x.max(self.origin[0]), world_z.max(self.origin[1])];
+        let max = [
+            (world_x + CHUNK_SIZE as i32).min(self.origin[0] + extent),
+            (world_z + CHUNK_SIZE as i32).min(self.origin[1] + extent),
+        ];
+        if min[0] >= max[0] || min[1] >= max[1] {
+            return false;
+        }
+
+        // Ground: fill down to the terrain, pave, and clear the sky
+        let street = self.layout.street_level;
+        for z in min[1]..max[1] {
+            for x in min[0]..max[0] {
+                let (local_x, local_z) = ((x - world_x) as usize, (z - world_z) as usize);
+                for y in (0..street - 1).rev() {
+                    if !chunk.get_block(local_x, y as usize, local_z).is_air() {
+                        break;
+                    }
+                    chunk.set_block(local_x, y as usize, local_z, Block::new(self.blocks.sidewalk));
+                }
+                let surface = if self.road_at(x, z).is_some() { self.blocks.road } else { self.blocks.sidewalk };
+                chunk.set_block(local_x, (street - 1) as usize, local_z, Block::new(surface));
+                for y in street as usize..CHUNK_HEIGHT {
+                    chunk.set_block(local_x, y, local_z, Block::AIR);
+                }
+            }
+        }
+
+        for building in &self.buildings {
+            let from = [building.min[0].max(min[0]), building.min[2].max(min[1])];
+            let to = [building.max[0].min(max[0]), building.max[2].min(max[1])];
+            for z in from[1]..to[1] {
+                for x in from[0]..to[0] {
+                    for y in building.min[1]..building.max[1] {
+                        let block = self.building_block(building, [x, y, z]);
+                        chunk.set_block((x - world_x) as usize, y as usize, (z - world_z) as usize, block);
+                    }
+                }
+            }
+        }
+
+        for &([x, y, z], block) in &self.features {
+            if (min[0]..max[0]).contains(&x) && (min[1]..max[1]).contains(&z) && (0..CHUNK_HEIGHT as i32).contains(&y) {
+                chunk.set_block((x - world_x) as usize, y as usize, (z - world_z) as usize, block);
+            }
+        }
+        true
+    }
+}
+
+/// Builds a city into the world.
+///
+/// Runs in [`Stage::Decoration`], after caves and dungeons, so the city's
+/// foundations fill whatever was carved beneath its streets. Lights are
+/// metadata only; fetch them with [`city`](Self::city) and hand them to the
+/// renderer.
+///
+/// ```rust,ignore
+/// let city = CityPass::new(CityConfig::shipped(), [-128, -128]);
+/// let generator = ChunkGenerator::with_passes(seed, "neon_prime", vec![Box::new(FlatTerrain), Box::new(city)]);
+/// ```
+pub struct CityPass {
+    config: Arc<CityConfig>,
+    origin: [i32; 2],
+    generated: PerSeed<Arc<City>>,
+}
+
+impl CityPass {
+    /// Creates a pass for the city whose lowest corner is `origin`
+    /// (`[x, z]`).
+    #[must_use]
+    pub fn new(config: Arc<CityConfig>, origin: [i32; 2]) -> Self {
+        Self {
+            config,
+            origin,
+            generated: PerSeed::new(),
+        }
+    }
+
+    /// Returns the lowest corner, in world blocks.
+    #[must_use]
+    pub const fn origin(&self) -> [i32; 2] {
+        self.origin
+    }
+
+    /// Generates the city for a world, reusing the last result if the
+    /// seed is unchanged.
+    pub fn city(&self, world_seed: WorldSeed) -> Arc<City> {
+        self.generated.get(world_seed, CITY_SEED, &self.origin, |seed| {
+            Arc::new(City::generate(&self.config, seed, self.origin))
+        })
+    }
+}
+
+impl GenerationPass for CityPass {
+    fn stage(&self) -> Stage {
+        Stage::Decoration
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        let layout = &self.config.layout;
+        let extent = layout.size as i32 * layout.tile_size;
+        let [origin_x, origin_z] = self.origin;
+        let (world_x, world_z) = (chunk.coord.world_x(), chunk.coord.world_z());
+        if origin_x >= world_x + CHUNK_SIZE as i32
+            || origin_x + extent <= world_x
+            || origin_z >= world_z + CHUNK_SIZE as i32
+            || origin_z + extent <= world_z
+        {
+            return;
+        }
+        if self.city(generator.seed()).write(chunk) {
+            chunk.rebuild_height_map();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::generator::FlatTerrain;
+
+    const ORIGIN: [i32; 2] = [-128, -128];
+
+    fn city(seed: u64) -> City {
+        City::generate(&CityConfig::shipped(), WorldSeed::new(seed), ORIGIN)
+    }
+
+    #[test]
+    fn test_roads_are_connected() {
+        for seed in 0..8 {
+            let city = city(seed);
+            let roads = city.roads();
+            let size = CityConfig::shipped().layout.size;
+            let all: HashSet<[usize; 2]> =
+                (0..size * size).map(|index| [index % size, index / size]).filter(|&tile| roads.at(tile).is_some()).collect();
+            assert!(roads.segments() > 20, "seed {seed}: only {} segments", roads.segments());
+
+            let start = [size / 2, size / 2];
+            let mut seen = HashSet::from([start]);
+            let mut pending = vec![start];
+            while let Some([x, z]) = pending.pop() {
+                for next in [[x.wrapping_sub(1), z], [x + 1, z], [x, z.wrapping_sub(1)], [x, z + 1]] {
+                    if all.contains(&next) && seen.insert(next) {
+                        pending.push(next);
+                    }
+                }
+            }
+            assert_eq!(seen.len(), all.len(), "seed {seed}: roads cut off");
+        }
+    }
+
+    #[test]
+    fn test_buildings_follow_their_districts() {
+        let config = CityConfig::shipped();
+        for seed in 0..8 {
+            let city = city(seed);
+            assert!(city.buildings().len() > 50, "seed {seed}");
+            assert_eq!(city.landmarks().len(), config.landmarks.len(), "seed {seed}");
+
+            let mut taken = HashSet::new();
+            for building in city.buildings() {
+                let district = &config.districts[city.lots()[building.lot].district];
+                match building.landmark {
+                    Some(landmark) => assert_eq!(building.height(), config.landmarks[landmark].height),
+                    None => assert!((district.height_min..=district.height_max).contains(&building.height())),
+                }
+                assert!(building.max[1] <= CHUNK_HEIGHT as i32);
+                for z in building.min[2]..building.max[2] {
+                    for x in building.min[0]..building.max[0] {
+                        assert_eq!(city.road_at(x, z), None, "seed {seed}: building on a road");
+                        assert!(taken.insert([x, z]), "seed {seed}: buildings overlap");
+                    }
+                }
+            }
+
+            for landmark in city.landmarks() {
+                let def = config.landmarks.iter().find(|def| def.name == landmark.name).unwrap();
+                assert_eq!(config.districts[city.lots()[landmark.lot].district].name, def.district);
+                assert_eq!(city.lots()[landmark.lot].landmark.map(|index| &city.landmarks()[index]), Some(landmark));
+                if landmark.shape == LandmarkShape::Plaza {
+                    assert!(city.buildings().iter().all(|building| building.lot != landmark.lot));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lights_glow_from_neon() {
+        let config = CityConfig::shipped();
+        let city = city(4);
+        let sources: HashSet<LightSource> = city.lights().iter().map(|light| light.source).collect();
+        assert_eq!(sources.len(), 3);
+
+        // Every point light sits by a neon block of its own colour
+        for light in city.lights().iter().filter(|light| light.spot.is_none()) {
+            let lit = city.features.iter().any(|&([x, y, z], block)| {
+                let distance = [x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5]
+                    .iter()
+                    .zip(light.position)
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum::<f32>()
+                    .sqrt();
+                distance <= 2.0 && config.neon.iter().any(|neon| neon.block == block.id && neon.color == light.color)
+            });
+            assert!(lit, "{light:?} has no neon beside it");
+        }
+        let beacons = city.lights().iter().filter(|light| light.spot.is_some()).count();
+        assert_eq!(beacons, city.landmarks().len());
+
+        // Signs on the edge shine just past it
+        let in_chunks: usize = (-9..9)
+            .flat_map(|z| (-9..9).map(move |x| ChunkCoord::new(x, z)))
+            .map(|coord| city.lights_in_chunk(coord).count())
+            .sum();
+        assert_eq!(in_chunks, city.lights().len());
+    }
+
+    #[test]
+    fn test_pass_builds_same_blocks_in_any_order() {
+        let config = CityConfig::shipped();
+        let pass = || CityPass::new(CityConfig::shipped(), ORIGIN);
+        let city = pass().city(WorldSeed::new(5));
+
+        let generator = || ChunkGenerator::with_passes(WorldSeed::new(5), "city_test", vec![Box::new(FlatTerrain), Box::new(pass())]);
+        let (forward, backward) = (generator(), generator());
+        let coords: Vec<ChunkCoord> = (-2..2).flat_map(|z| (-2..2).map(move |x| ChunkCoord::new(x, z))).collect();
+        let first: Vec<Chunk> = coords.iter().map(|&coord| forward.generate(coord)).collect();
+        let second: Vec<Chunk> = coords.iter().rev().map(|&coord| backward.generate(coord)).collect();
+
+        let street = config.layout.street_level as usize;
+        let mut roads = 0;
+        for (chunk, other) in first.iter().zip(second.iter().rev()) {
+            assert_eq!(chunk.coord, other.coord);
+            for y in 0..CHUNK_HEIGHT {
+                for z in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        assert_eq!(chunk.get_block(x, y, z), other.get_block(x, y, z));
+                    }
+                }
+            }
+            for z in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    let (world_x, world_z) = (chunk.coord.world_x() + x as i32, chunk.coord.world_z() + z as i32);
+                    let surface = chunk.get_block(x, street - 1, z);
+                    if city.road_at(world_x, world_z).is_some() {
+                        assert_eq!(surface.id, config.blocks.road);
+                        roads += 1;
+                    }
+                }
+            }
+        }
+        assert!(roads > 0);
+
+        // The spire is standing in the world
+        let spire = city.buildings().iter().find(|building| building.landmark == Some(0)).unwrap();
+        let coord = ChunkCoord::from_world_pos(spire.min[0], spire.min[2]);
+        let chunk = forward.generate(coord);
+        let (x, z) = ((spire.min[0] - coord.world_x()) as usize, (spire.min[2] - coord.world_z()) as usize);
+        assert_eq!(chunk.get_block(x, (spire.max[1] - 1) as usize, z).id, config.blocks.trim);
+
+        // Same seed, same city; another seed, another one
+        assert_eq!(pass().city(WorldSeed::new(5)).buildings(), city.buildings());
+        assert_ne!(pass().city(WorldSeed::new(6)).buildings(), city.buildings());
+    }
+}
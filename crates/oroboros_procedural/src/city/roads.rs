@@ -0,0 +1,314 @@
+//! # Road Network
+//!
+//! Districts, then roads grown across them.
+//!
+//! Roads grow like the extended L-system of Parish and Müller: each road
+//! segment is a module that, once placed, produces the segments after it.
+//! Segments wait in a queue ordered by delay, so highways run ahead of
+//! the streets they spawn and streets fill in behind them.
+//!
+//! ```text
+//!            │         │
+//!  ──────────┼─────────┼──────   ◄─ side streets branch at junctions
+//!            │         │
+//!  ══════════╪═════════╪══════   ◄─ highways grow first from the centre
+//!            │    ·    │
+//! ```
+//!
+//! Each new segment is checked against the roads already on the grid
+//! (local constraints): it stops where it meets a road, at the city edge,
+//! or when it would run right beside another road. What grows next
+//! (global goals) depends on the district: its `block_size` is the
+//! distance between junctions.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::config::CityConfig;
+use crate::noise::ChunkRng;
+
+/// Kind of road on a tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoadKind {
+    /// Wide road grown from the centre; lamps stand in its median.
+    Highway,
+    /// Side street.
+    Street,
+}
+
+/// East, south, west, north, as tile steps.
+const DIRECTIONS: [[i32; 2]; 4] = [[1, 0], [0, 1], [-1, 0], [0, -1]];
+
+/// Tries to place a district site away from the others before settling.
+const SITE_ATTEMPTS: u32 = 16;
+
+/// Which district each tile belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistrictMap {
+    size: usize,
+    /// District index per tile, row by row.
+    tiles: Vec<usize>,
+    /// Voronoi sites: tile and district index.
+    sites: Vec<([i32; 2], usize)>,
+}
+
+impl DistrictMap {
+    /// Places the core district at the centre and the others around it.
+    pub(crate) fn build(config: &CityConfig, rng: &mut ChunkRng) -> Self {
+        let size = config.layout.size;
+        let core = config.core();
+        let centre = (size / 2) as i32;
+        let total: f64 = config.districts.iter().filter(|district| !district.core).map(|district| district.weight).sum();
+
+        // Every district gets one site so its landmarks have somewhere to
+        // go; the rest are rolled by weight
+        let mut sites = vec![([centre, centre], core)];
+        let mut unplaced = (0..config.districts.len()).filter(|&district| district != core);
+        for _ in 1..config.layout.districts {
+            // Keep sites apart so no district is squeezed out by its neighbours
+            let spacing = (size / 6) as i32;
+            let mut tile = [0, 0];
+            for _ in 0..SITE_ATTEMPTS {
+                tile = [rng.range_i32(0, size as i32 - 1), rng.range_i32(0, size as i32 - 1)];
+                let near = |&(site, _): &([i32; 2], usize)| {
+                    let [dx, dz] = [tile[0] - site[0], tile[1] - site[1]];
+                    dx * dx + dz * dz < spacing * spacing
+                };
+                if !sites.iter().any(near) {
+                    break;
+                }
+            }
+            let mut roll = rng.next_f64() * total;
+            let district = unplaced.next().unwrap_or_else(|| {
+                config
+                    .districts
+                    .iter()
+                    .position(|district| {
+                        if district.core {
+                            return false;
+                        }
+                        roll -= district.weight;
+                        roll < 0.0
+                    })
+                    .unwrap_or(core)
+            });
+            sites.push((tile, district));
+        }
+
+        let tiles = (0..size * size)
+            .map(|index| {
+                let tile = [(index % size) as i32, (index / size) as i32];
+                let nearest = sites.iter().min_by_key(|(site, _)| {
+                    let [dx, dz] = [tile[0] - site[0], tile[1] - site[1]];
+                    dx * dx + dz * dz
+                });
+                nearest.map_or(core, |&(_, district)| district)
+            })
+            .collect();
+        Self { size, tiles, sites }
+    }
+
+    /// Returns the district index of a tile inside the city.
+    #[must_use]
+    pub fn at(&self, [x, z]: [usize; 2]) -> usize {
+        self.tiles[z * self.size + x]
+    }
+
+    /// Returns the Voronoi sites: tile and district index.
+    #[must_use]
+    pub fn sites(&self) -> &[([i32; 2], usize)] {
+        &self.sites
+    }
+}
+
+/// A road module waiting to be placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Segment {
+    /// Growth step it is placed at; lower first.
+    delay: u32,
+    /// Creation order, so equal delays place in a fixed order.
+    order: u32,
+    start: [i32; 2],
+    direction: usize,
+    highway: bool,
+}
+
+/// The roads of a city, tile by tile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoadNetwork {
+    size: usize,
+    tiles: Vec<Option<RoadKind>>,
+    /// Lamp posts, as `[x, z]` block offsets from the city corner.
+    lamps: Vec<[i32; 2]>,
+    segments: usize,
+}
+
+impl RoadNetwork {
+    /// Grows roads from the centre until no segments remain or the
+    /// segment limit is reached.
+    pub(crate) fn grow(config: &CityConfig, districts: &DistrictMap, rng: &mut ChunkRng) -> Self {
+        let size = config.layout.size;
+        let mut network = Self {
+            size,
+            tiles: vec![None; size * size],
+            lamps: Vec::new(),
+            segments: 0,
+        };
+        let mut queue = BinaryHeap::new();
+        let mut order = 0;
+        let mut push = |queue: &mut BinaryHeap<Reverse<Segment>>, delay, start, direction, highway| {
+            queue.push(Reverse(Segment {
+                delay,
+                order,
+                start,
+                direction,
+                highway,
+            }));
+            order += 1;
+        };
+
+        // Axiom: four highways leaving a square at the centre
+        let centre = (size / 2) as i32;
+        let width = config.roads.highway_width as i32;
+        for offset in 0..width {
+            network.mark([centre + offset, centre], 0, true, config);
+        }
+        for (direction, step) in DIRECTIONS.iter().enumerate() {
+            let start = step.map(|step| centre + step.max(0) * (width - 1));
+            push(&mut queue, 0, start, direction, true);
+        }
+
+        let roads = &config.roads;
+        while let Some(Reverse(segment)) = queue.pop() {
+            if network.segments >= roads.max_segments {
+                break;
+            }
+            let Some(end) = network.place(config, districts, segment) else {
+                continue;
+            };
+            network.segments += 1;
+
+            // Global goals: carry on, and branch at the junction
+            let delay = segment.delay + 1;
+            if segment.highway || rng.next_f64() < roads.continue_chance {
+                push(&mut queue, delay, end, segment.direction, segment.highway);
+            }
+            let branch_delay = if segment.highway { delay + roads.branch_delay } else { delay };
+            for turn in [1, 3] {
+                if rng.next_f64() < roads.branch_chance {
+                    let direction = (segment.direction + turn) % 4;
+                    let start = Self::edge(config, end, segment.highway, direction);
+                    push(&mut queue, branch_delay, start, direction, false);
+                }
+            }
+        }
+        network
+    }
+
+    /// Returns the road on a tile inside the city.
+    #[must_use]
+    pub fn at(&self, [x, z]: [usize; 2]) -> Option<RoadKind> {
+        self.tiles[z * self.size + x]
+    }
+
+    /// Returns lamp posts, as `[x, z]` block offsets from the city corner.
+    #[must_use]
+    pub fn lamps(&self) -> &[[i32; 2]] {
+        &self.lamps
+    }
+
+    /// Returns how many segments were placed.
+    #[must_use]
+    pub const fn segments(&self) -> usize {
+        self.segments
+    }
+
+    fn inside(&self, [x, z]: [i32; 2]) -> bool {
+        (0..self.size as i32).contains(&x) && (0..self.size as i32).contains(&z)
+    }
+
+    fn get(&self, tile: [i32; 2]) -> Option<RoadKind> {
+        self.inside(tile).then(|| self.tiles[tile[1] as usize * self.size + tile[0] as usize]).flatten()
+    }
+
+    /// Tiles across a road at `tile`; extra lanes go towards +x or +z so
+    /// both halves of a highway line up.
+    fn cross_section(config: &CityConfig, tile: [i32; 2], highway: bool, direction: usize) -> Vec<[i32; 2]> {
+        let width = if highway { config.roads.highway_width } else { config.roads.street_width };
+        let across = if DIRECTIONS[direction][0] == 0 { [1, 0] } else { [0, 1] };
+        (0..width as i32).map(|lane| [tile[0] + across[0] * lane, tile[1] + across[1] * lane]).collect()
+    }
+
+    /// The tile of a road's cross-section furthest towards `direction`,
+    /// where a branch that way starts.
+    fn edge(config: &CityConfig, tile: [i32; 2], highway: bool, direction: usize) -> [i32; 2] {
+        let step = DIRECTIONS[direction];
+        Self::cross_section(config, tile, highway, (direction + 1) % 4)
+            .into_iter()
+            .max_by_key(|lane| lane[0] * step[0] + lane[1] * step[1])
+            .unwrap_or(tile)
+    }
+
+    fn mark(&mut self, tile: [i32; 2], direction: usize, highway: bool, config: &CityConfig) {
+        for lane in Self::cross_section(config, tile, highway, direction) {
+            if self.inside(lane) {
+                let index = lane[1] as usize * self.size + lane[0] as usize;
+                // Highways keep their kind where streets cross them
+                if self.tiles[index] != Some(RoadKind::Highway) {
+                    self.tiles[index] = Some(if highway { RoadKind::Highway } else { RoadKind::Street });
+                }
+            }
+        }
+    }
+
+    /// Places a segment under the local constraints. Returns where it
+    /// ends if it reached a junction that can grow further.
+    fn place(&mut self, config: &CityConfig, districts: &DistrictMap, segment: Segment) -> Option<[i32; 2]> {
+        let Segment {
+            start,
+            direction,
+            highway,
+            ..
+        } = segment;
+        if !self.inside(start) {
+            return None;
+        }
+        let step = DIRECTIONS[direction];
+        let length = config.districts[districts.at([start[0] as usize, start[1] as usize])].block_size as i32;
+        let width = if highway { config.roads.highway_width } else { config.roads.street_width } as i32;
+        let across = if step[0] == 0 { [1, 0] } else { [0, 1] };
+
+        for i in 1..=length {
+            let tile = [start[0] + step[0] * i, start[1] + step[1] * i];
+            let lanes = Self::cross_section(config, tile, highway, direction);
+            // City edge: stop
+            if !lanes.iter().all(|&lane| self.inside(lane)) {
+                return None;
+            }
+            // Met a road: join it and stop
+            if lanes.iter().any(|&lane| self.get(lane).is_some()) {
+                return None;
+            }
+            // A road right alongside: stop rather than leave a sliver
+            let beside = [-2, -1, width, width + 1]
+                .map(|offset| [tile[0] + across[0] * offset, tile[1] + across[1] * offset]);
+            if i > 1 && beside.iter().any(|&tile| self.get(tile).is_some()) {
+                return None;
+            }
+
+            self.mark(tile, direction, highway, config);
+            let spacing = config.roads.lamp_spacing as i32;
+            if highway && width > 1 && (tile[0] + tile[1]).rem_euclid(spacing) == 0 {
+                // In the median, between the first two lanes
+                let tile_size = config.layout.tile_size;
+                let middle = tile_size / 2;
+                self.lamps.push(if step[0] == 0 {
+                    [(tile[0] + 1) * tile_size, tile[1] * tile_size + middle]
+                } else {
+                    [tile[0] * tile_size + middle, (tile[1] + 1) * tile_size]
+                });
+            }
+        }
+        Some([start[0] + step[0] * length, start[1] + step[1] * length])
+    }
+}
@@ -0,0 +1,267 @@
+//! # Dungeon Settings
+//!
+//! Loaded from `data/schemas/world/dungeons.toml`; the shipped file is
+//! embedded in the binary and used unless another is given.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
+use oroboros_core::embedded::EmbeddedData;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// The shipped dungeon settings.
+const SHIPPED_DUNGEONS: &str = include_str!("../../../../data/schemas/world/dungeons.toml");
+
+/// Why dungeon settings could not be loaded.
+#[derive(Error, Debug)]
+pub enum DungeonConfigError {
+    /// Reading the file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid TOML or is missing fields.
+    #[error("invalid dungeon config: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// A minimum is above its maximum.
+    #[error("dungeon {0} range is empty")]
+    EmptyRange(&'static str),
+    /// Rooms would overlap stairs or the floor above.
+    #[error("rooms don't fit their cells: {0}")]
+    RoomTooLarge(&'static str),
+    /// The grid can't hold the rooms, or the rooms can't hold the locks.
+    #[error("dungeon needs {needed} {what} but has {available}")]
+    TooFew {
+        /// `"cells"` or `"rooms"`.
+        what: &'static str,
+        /// Count required.
+        needed: usize,
+        /// Count configured.
+        available: usize,
+    },
+}
+
+/// `[layout]`: the cell grid and room sizes, in blocks.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct LayoutSettings {
+    /// Width and depth of a cell.
+    pub cell_size: i32,
+    /// Height of a floor.
+    pub floor_height: i32,
+    /// Cells along X.
+    pub width: usize,
+    /// Cells along Z.
+    pub depth: usize,
+    /// Floors, counted from the bottom.
+    pub floors: usize,
+    /// Narrowest room edge (air inside the walls).
+    pub room_min: i32,
+    /// Widest room edge.
+    pub room_max: i32,
+    /// Air above a room's floor.
+    pub room_height: i32,
+}
+
+/// `[rooms]`: how many rooms, locks and loops.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct RoomSettings {
+    /// Fewest rooms, counting start, boss and treasure.
+    pub min_count: usize,
+    /// Most rooms.
+    pub max_count: usize,
+    /// Key and locked corridor pairs.
+    pub locks: usize,
+    /// Extra corridors between rooms behind the same locks.
+    pub loops: usize,
+}
+
+/// `[blocks]`: what the dungeon is built from.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct DungeonBlocks {
+    /// Walls and ceilings.
+    pub wall: BlockId,
+    /// Floors and stair steps.
+    pub floor: BlockId,
+    /// Loot containers.
+    pub chest: BlockId,
+    /// Locked doors; the block's meta is the key ID.
+    pub door: BlockId,
+}
+
+/// `[loot]`: loot tables for containers.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct LootSettings {
+    /// Chests in ordinary and key rooms.
+    pub room: u32,
+    /// The treasure room chest.
+    pub treasure: u32,
+    /// The chest behind the boss.
+    pub boss: u32,
+    /// Chance an ordinary room has a chest (0-1).
+    pub room_chest_chance: f64,
+}
+
+/// `[spawns]`: NPCs per room.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct SpawnSettings {
+    /// Fewest guards in an ordinary room.
+    pub min_guards: u32,
+    /// Most guards in an ordinary room.
+    pub max_guards: u32,
+    /// Guards beside the boss.
+    pub boss_guards: u32,
+}
+
+/// Validated dungeon settings.
+///
+/// ```rust,ignore
+/// let dungeons = Arc::new(DungeonConfig::load("mods/dungeons.toml")?);
+/// dungeons.check_blocks(generator.blocks())?;
+/// dungeons.check_loot_tables(|table| loot.has_table(table))?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct DungeonConfig {
+    /// `[layout]`.
+    pub layout: LayoutSettings,
+    /// `[rooms]`.
+    pub rooms: RoomSettings,
+    /// `[blocks]`.
+    pub blocks: DungeonBlocks,
+    /// `[loot]`.
+    pub loot: LootSettings,
+    /// `[spawns]`.
+    pub spawns: SpawnSettings,
+}
+
+impl DungeonConfig {
+    /// Returns the settings from the shipped `dungeons.toml`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shipped rooms don't fit their grid cells, or the room
+    /// count or grid is too small for the configured locks.
+    #[must_use]
+    pub fn shipped() -> Arc<Self> {
+        static SHIPPED: EmbeddedData<DungeonConfig, DungeonConfigError> =
+            EmbeddedData::new("dungeons.toml", SHIPPED_DUNGEONS, DungeonConfig::from_toml);
+        SHIPPED.get()
+    }
+
+    /// Loads settings from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or the settings are
+    /// invalid (see [`validate`](Self::validate)).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DungeonConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses settings in the `dungeons.toml` format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text can't be parsed or the settings are invalid.
+    pub fn from_toml(text: &str) -> Result<Self, DungeonConfigError> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that ranges are non-empty, rooms fit their cells and the
+    /// grid and room count leave space for every lock.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first invalid setting.
+    pub fn validate(&self) -> Result<(), DungeonConfigError> {
+        let layout = &self.layout;
+        let rooms = &self.rooms;
+        if layout.room_min > layout.room_max || layout.room_min < 3 {
+            return Err(DungeonConfigError::EmptyRange("room size"));
+        }
+        if rooms.min_count > rooms.max_count {
+            return Err(DungeonConfigError::EmptyRange("room count"));
+        }
+        if self.spawns.min_guards > self.spawns.max_guards {
+            return Err(DungeonConfigError::EmptyRange("guard count"));
+        }
+        if layout.room_max / 2 + 1 > (layout.cell_size - layout.floor_height) / 2 {
+            return Err(DungeonConfigError::RoomTooLarge("room_max reaches the stairs"));
+        }
+        if layout.room_height + 2 > layout.floor_height {
+            return Err(DungeonConfigError::RoomTooLarge("room_height reaches the floor above"));
+        }
+
+        // Start, boss, treasure, plus a key room and the room behind each lock
+        let needed = 3 + 2 * rooms.locks;
+        if rooms.min_count < needed {
+            return Err(DungeonConfigError::TooFew {
+                what: "rooms",
+                needed,
+                available: rooms.min_count,
+            });
+        }
+        // Leave half the grid free so rooms can always branch
+        let cells = layout.width * layout.depth * layout.floors;
+        if cells < 2 * rooms.max_count {
+            return Err(DungeonConfigError::TooFew {
+                what: "cells",
+                needed: 2 * rooms.max_count,
+                available: cells,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that every block the dungeon places is defined.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first undefined block ID.
+    pub fn check_blocks(&self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        let DungeonBlocks { wall, floor, chest, door } = self.blocks;
+        blocks.check_ids("dungeon", [wall, floor, chest, door])
+    }
+
+    /// Checks that every loot table containers name exists.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first loot table `exists` rejects.
+    pub fn check_loot_tables(&self, exists: impl Fn(u32) -> bool) -> Result<(), BlockConfigError> {
+        let LootSettings { room, treasure, boss, .. } = self.loot;
+        match [room, treasure, boss].into_iter().find(|&table| !exists(table)) {
+            Some(table) => Err(BlockConfigError::UnknownLootTable {
+                block: "dungeon chest".to_owned(),
+                table,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_config() {
+        let config = DungeonConfig::shipped();
+        config.check_blocks(&BlockRegistry::shipped()).unwrap();
+        assert!(config.check_loot_tables(|table| [20, 22, 99].contains(&table)).is_ok());
+        assert!(matches!(
+            config.check_loot_tables(|table| table != 99),
+            Err(BlockConfigError::UnknownLootTable { table: 99, .. })
+        ));
+
+        let mut cramped = (*config).clone();
+        cramped.layout.room_max = 17;
+        assert!(matches!(cramped.validate(), Err(DungeonConfigError::RoomTooLarge(_))));
+
+        let mut locked = (*config).clone();
+        locked.rooms.locks = 6;
+        assert!(matches!(locked.validate(), Err(DungeonConfigError::TooFew { what: "rooms", .. })));
+    }
+}
@@ -0,0 +1,379 @@
+//! # Room Graph
+//!
+//! Rooms and the corridors between them, built so the dungeon can always
+//! be finished.
+//!
+//! ```text
+//! level 0            level 1            level 2
+//! Start ── A ── Key0 ═lock 0═ B ── Key1 ═lock 1═ C ── Boss
+//!          └─ D ─┘            └─ E                └─ Treasure
+//! ```
+//!
+//! Rooms are added one at a time next to a room of the current *level*
+//! (number of locks between it and the start). A level's key room is
+//! placed before its lock, so every key is reachable with the keys before
+//! it. Loops only join rooms of the same level, so they never skip a lock.
+//! Each room takes one cell of the grid and corridors only join
+//! neighbouring cells, at most one per side of a room, so the graph is
+//! also the physical layout.
+
+use super::config::DungeonConfig;
+use crate::noise::ChunkRng;
+
+/// Index of a room in its [`RoomGraph`].
+pub type RoomId = usize;
+
+/// A key and the lock it opens.
+pub type KeyId = u16;
+
+/// What a room is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoomKind {
+    /// Entrance.
+    Start,
+    /// Ordinary room.
+    Normal,
+    /// Holds a key in a chest.
+    Key(KeyId),
+    /// Extra loot off the main path.
+    Treasure,
+    /// Final room, behind the last lock.
+    Boss,
+}
+
+impl RoomKind {
+    /// Returns true if corridors may branch from this room.
+    const fn branches(self) -> bool {
+        matches!(self, Self::Start | Self::Normal)
+    }
+}
+
+/// A room and its cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomNode {
+    /// What the room is for.
+    pub kind: RoomKind,
+    /// Grid cell `[x, floor, z]`.
+    pub cell: [usize; 3],
+    /// Locks between the start and this room.
+    pub level: usize,
+}
+
+/// A corridor (or stairs) between two rooms in neighbouring cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Connection {
+    /// The joined rooms; the first is the one nearer the start.
+    pub rooms: [RoomId; 2],
+    /// Key needed to pass, if locked.
+    pub lock: Option<KeyId>,
+}
+
+/// Rooms and corridors of one dungeon.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomGraph {
+    /// Rooms; the start is room 0.
+    pub rooms: Vec<RoomNode>,
+    /// Corridors.
+    pub connections: Vec<Connection>,
+}
+
+impl RoomGraph {
+    /// Builds a graph, or `None` if rooms boxed themselves in.
+    pub(crate) fn build(config: &DungeonConfig, rng: &mut ChunkRng) -> Option<Self> {
+        let layout = &config.layout;
+        let settings = &config.rooms;
+        let total = rng.range_i32(settings.min_count as i32, settings.max_count as i32) as usize;
+        let locks = settings.locks;
+
+        let mut builder = Builder {
+            size: [layout.width, layout.floors, layout.depth],
+            cells: vec![None; layout.width * layout.floors * layout.depth],
+            graph: Self {
+                rooms: Vec::with_capacity(total),
+                connections: Vec::new(),
+            },
+        };
+
+        // Enter on the top floor and work down
+        let start = [
+            rng.range_i32(0, layout.width as i32 - 1) as usize,
+            layout.floors - 1,
+            rng.range_i32(0, layout.depth as i32 - 1) as usize,
+        ];
+        builder.add_room(RoomKind::Start, start, 0);
+
+        // Ordinary rooms, shared out between levels
+        let normal = total - 3 - 2 * locks;
+        for level in 0..=locks {
+            let share = normal / (locks + 1) + usize::from(level < normal % (locks + 1));
+            for _ in 0..share {
+                builder.attach(rng, level, RoomKind::Normal, None)?;
+            }
+            if level < locks {
+                let key = level as KeyId;
+                builder.attach(rng, level, RoomKind::Key(key), None)?;
+                builder.attach(rng, level, RoomKind::Normal, Some(key))?;
+            }
+        }
+        builder.attach(rng, locks, RoomKind::Boss, None)?;
+        let treasure_level = rng.range_i32(locks.min(1) as i32, locks as i32) as usize;
+        builder.attach(rng, treasure_level, RoomKind::Treasure, None)?;
+
+        builder.add_loops(rng, settings.loops);
+        Some(builder.graph)
+    }
+
+    /// Returns the start room.
+    #[must_use]
+    pub const fn start(&self) -> RoomId {
+        0
+    }
+
+    /// Returns the boss room.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph has no boss room, which built graphs always do.
+    #[must_use]
+    pub fn boss(&self) -> RoomId {
+        self.rooms
+            .iter()
+            .position(|room| room.kind == RoomKind::Boss)
+            .expect("dungeons always have a boss room")
+    }
+
+    /// Returns the rooms reachable from the start holding only the keys
+    /// `has_key` accepts (keys found on the way are not picked up).
+    #[must_use]
+    pub fn reachable(&self, has_key: impl Fn(KeyId) -> bool) -> Vec<bool> {
+        let mut reached = vec![false; self.rooms.len()];
+        reached[self.start()] = true;
+        let mut pending = vec![self.start()];
+        while let Some(room) = pending.pop() {
+            for connection in &self.connections {
+                if connection.lock.is_some_and(|key| !has_key(key)) {
+                    continue;
+                }
+                let other = match connection.rooms {
+                    [a, b] if a == room => b,
+                    [a, b] if b == room => a,
+                    _ => continue,
+                };
+                if !reached[other] {
+                    reached[other] = true;
+                    pending.push(other);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Plays the dungeon: picks up every reachable key until nothing new
+    /// opens. Returns the keys in pickup order if the boss was reached.
+    #[must_use]
+    pub fn solve(&self) -> Option<Vec<KeyId>> {
+        let mut keys: Vec<KeyId> = Vec::new();
+        loop {
+            let reached = self.reachable(|key| keys.contains(&key));
+            if reached[self.boss()] {
+                return Some(keys);
+            }
+            let found: Vec<KeyId> = self
+                .rooms
+                .iter()
+                .zip(&reached)
+                .filter_map(|(room, &reached)| match room.kind {
+                    RoomKind::Key(key) if reached && !keys.contains(&key) => Some(key),
+                    _ => None,
+                })
+                .collect();
+            if found.is_empty() {
+                return None;
+            }
+            keys.extend(found);
+        }
+    }
+}
+
+/// Grows a graph room by room, one room per cell.
+struct Builder {
+    size: [usize; 3],
+    cells: Vec<Option<RoomId>>,
+    graph: RoomGraph,
+}
+
+impl Builder {
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        (y * self.size[2] + z) * self.size[0] + x
+    }
+
+    fn add_room(&mut self, kind: RoomKind, cell: [usize; 3], level: usize) -> RoomId {
+        let id = self.graph.rooms.len();
+        let index = self.index(cell);
+        self.cells[index] = Some(id);
+        self.graph.rooms.push(RoomNode { kind, cell, level });
+        id
+    }
+
+    /// Cells a corridor from `cell` may reach: the four sides on the same
+    /// floor and one floor up or down.
+    fn neighbours(&self, [x, y, z]: [usize; 3]) -> Vec<[usize; 3]> {
+        let mut out = Vec::new();
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            for dy in [0, 1, -1] {
+                let next = [x as i64 + dx, y as i64 + dy, z as i64 + dz];
+                if next.iter().zip(self.size).all(|(&value, size)| (0..size as i64).contains(&value)) {
+                    out.push(next.map(|value| value as usize));
+                }
+            }
+        }
+        out
+    }
+
+    fn room_at(&self, cell: [usize; 3]) -> Option<RoomId> {
+        self.cells[self.index(cell)]
+    }
+
+    fn connected(&self, a: RoomId, b: RoomId) -> bool {
+        self.graph
+            .connections
+            .iter()
+            .any(|connection| connection.rooms == [a, b] || connection.rooms == [b, a])
+    }
+
+    /// Returns true if stairs between `a` and `b` would cross stairs
+    /// already running the other way between the same two columns.
+    fn crosses_stairs(&self, a: [usize; 3], b: [usize; 3]) -> bool {
+        if a[1] == b[1] {
+            return false;
+        }
+        let (other_a, other_b) = ([a[0], b[1], a[2]], [b[0], a[1], b[2]]);
+        match (self.room_at(other_a), self.room_at(other_b)) {
+            (Some(first), Some(second)) => self.connected(first, second),
+            _ => false,
+        }
+    }
+
+    /// Returns true if `a` already has a corridor or stairs leaving
+    /// towards the column of `b`; stairs climb through anything else
+    /// leaving that side.
+    fn side_taken(&self, a: [usize; 3], b: [usize; 3]) -> bool {
+        let Some(room) = self.room_at(a) else {
+            return false;
+        };
+        (a[1].saturating_sub(1)..=(a[1] + 1).min(self.size[1] - 1))
+            .filter_map(|y| self.room_at([b[0], y, b[2]]))
+            .any(|other| self.connected(room, other))
+    }
+
+    /// Returns true if a corridor between `a` and `b` would run into one
+    /// already built.
+    fn blocked(&self, a: [usize; 3], b: [usize; 3]) -> bool {
+        self.crosses_stairs(a, b) || self.side_taken(a, b) || self.side_taken(b, a)
+    }
+
+    /// Adds a room next to a random branching room of `level`. A locked
+    /// corridor puts the new room one level deeper.
+    fn attach(&mut self, rng: &mut ChunkRng, level: usize, kind: RoomKind, lock: Option<KeyId>) -> Option<RoomId> {
+        let mut options: Vec<(RoomId, [usize; 3])> = Vec::new();
+        for (id, room) in self.graph.rooms.iter().enumerate() {
+            if room.level != level || !room.kind.branches() {
+                continue;
+            }
+            for cell in self.neighbours(room.cell) {
+                if self.room_at(cell).is_none() && !self.blocked(room.cell, cell) {
+                    options.push((id, cell));
+                }
+            }
+        }
+        if options.is_empty() {
+            return None;
+        }
+
+        let (parent, cell) = options[rng.range_i32(0, options.len() as i32 - 1) as usize];
+        let room = self.add_room(kind, cell, level + usize::from(lock.is_some()));
+        self.graph.connections.push(Connection {
+            rooms: [parent, room],
+            lock,
+        });
+        Some(room)
+    }
+
+    /// Joins up to `count` pairs of neighbouring branching rooms of the
+    /// same level.
+    fn add_loops(&mut self, rng: &mut ChunkRng, count: usize) {
+        let mut options: Vec<(RoomId, RoomId)> = Vec::new();
+        for (id, room) in self.graph.rooms.iter().enumerate() {
+            for cell in self.neighbours(room.cell) {
+                let Some(other) = self.room_at(cell) else {
+                    continue;
+                };
+                let neighbour = &self.graph.rooms[other];
+                if id < other
+                    && room.kind.branches()
+                    && neighbour.kind.branches()
+                    && room.level == neighbour.level
+                    && !self.connected(id, other)
+                {
+                    options.push((id, other));
+                }
+            }
+        }
+
+        for _ in 0..count {
+            // Earlier loops may have taken the sides the rest need
+            options.retain(|&(a, b)| {
+                !self.connected(a, b) && !self.blocked(self.graph.rooms[a].cell, self.graph.rooms[b].cell)
+            });
+            if options.is_empty() {
+                return;
+            }
+            let (a, b) = options.swap_remove(rng.range_i32(0, options.len() as i32 - 1) as usize);
+            self.graph.connections.push(Connection { rooms: [a, b], lock: None });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::WorldSeed;
+
+    #[test]
+    fn test_graphs_are_connected_and_solvable() {
+        let config = DungeonConfig::shipped();
+        let locks = config.rooms.locks;
+
+        for seed in 0..200 {
+            let mut rng = ChunkRng::new(WorldSeed::new(seed), 0, 0);
+            let Some(graph) = RoomGraph::build(&config, &mut rng) else {
+                continue;
+            };
+            let count = graph.rooms.len();
+            assert!((config.rooms.min_count..=config.rooms.max_count).contains(&count));
+
+            // Every room is reachable once every lock is open
+            assert!(graph.reachable(|_| true).iter().all(|&reached| reached), "seed {seed}: disconnected");
+            // Keys are found in lock order, and the boss needs all of them
+            let keys = graph.solve().unwrap_or_else(|| panic!("seed {seed}: unsolvable"));
+            assert_eq!(keys, (0..locks as KeyId).collect::<Vec<_>>());
+            for missing in 0..locks as KeyId {
+                assert!(!graph.reachable(|key| key != missing)[graph.boss()], "seed {seed}: lock {missing} skipped");
+            }
+
+            let kinds = |kind: RoomKind| graph.rooms.iter().filter(|room| room.kind == kind).count();
+            assert_eq!(kinds(RoomKind::Start), 1);
+            assert_eq!(kinds(RoomKind::Boss), 1);
+            assert_eq!(kinds(RoomKind::Treasure), 1);
+            assert_eq!(graph.connections.iter().filter(|connection| connection.lock.is_some()).count(), locks);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_graph() {
+        let config = DungeonConfig::shipped();
+        let build = |seed| RoomGraph::build(&config, &mut ChunkRng::new(WorldSeed::new(seed), 0, 0));
+        assert_eq!(build(17), build(17));
+        assert_ne!(build(17), build(18));
+    }
+}
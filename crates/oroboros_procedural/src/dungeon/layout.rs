@@ -0,0 +1,302 @@
+//! # Dungeon Layout
+//!
+//! Turns a [`RoomGraph`] into boxes of blocks, plus where chests, doors
+//! and NPCs go.
+//!
+//! ```text
+//!   cell_size
+//! ├──────────────────────┤
+//! ┌─────────┐              ┌───
+//! │  room   ├─── corridor ─┤    same floor: 3 wide, 3 tall
+//! │    ·    │      ▓       │    ▓ = locked door at the midpoint
+//! └─────────┘              └───
+//!             ┌─┘ stairs    one floor_height rise in the middle of the
+//!          ┌─┘              cell pair, 4 tall
+//! ```
+//!
+//! Boxes are filled in phases (walls, then air, then floors, then doors
+//! and chests), so a corridor's shell never seals a room it opens into.
+
+use super::config::DungeonConfig;
+use super::graph::{KeyId, RoomGraph, RoomId, RoomKind};
+use crate::chunk::Block;
+use crate::noise::ChunkRng;
+
+/// Air inside one room, in world blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoomBounds {
+    /// Lowest air block.
+    pub min: [i32; 3],
+    /// One past the highest air block.
+    pub max: [i32; 3],
+}
+
+impl RoomBounds {
+    /// Returns true if the block is inside the room.
+    #[must_use]
+    pub fn contains(&self, position: [i32; 3]) -> bool {
+        (0..3).all(|axis| (self.min[axis]..self.max[axis]).contains(&position[axis]))
+    }
+
+    /// Returns the centre of the floor.
+    #[must_use]
+    pub fn centre(&self) -> [i32; 3] {
+        [
+            (self.min[0] + self.max[0]).div_euclid(2),
+            self.min[1],
+            (self.min[2] + self.max[2]).div_euclid(2),
+        ]
+    }
+}
+
+/// A chest on a room floor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LootContainer {
+    /// Chest block, in world blocks.
+    pub position: [i32; 3],
+    /// Room holding it.
+    pub room: RoomId,
+    /// Loot table rolled when opened.
+    pub loot_table: u32,
+    /// Key handed out with the loot.
+    pub key: Option<KeyId>,
+}
+
+/// A locked door across a corridor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockedDoor {
+    /// Key that opens it; also the door blocks' meta.
+    pub key: KeyId,
+    /// Rooms on either side, start side first.
+    pub rooms: [RoomId; 2],
+    /// Lowest door block.
+    pub min: [i32; 3],
+    /// One past the highest door block.
+    pub max: [i32; 3],
+}
+
+/// Who stands at a spawn point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpawnRole {
+    /// Room guard.
+    Guard,
+    /// The boss.
+    Boss,
+}
+
+/// Where an NPC appears.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnPoint {
+    /// Feet position, in world units.
+    pub position: [f32; 3],
+    /// Room it guards.
+    pub room: RoomId,
+    /// Who spawns.
+    pub role: SpawnRole,
+}
+
+/// A box filled with one block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Fill {
+    pub(crate) min: [i32; 3],
+    pub(crate) max: [i32; 3],
+    pub(crate) block: Block,
+}
+
+/// Everything placed for one graph.
+pub(crate) struct Layout {
+    pub(crate) rooms: Vec<RoomBounds>,
+    pub(crate) containers: Vec<LootContainer>,
+    pub(crate) doors: Vec<LockedDoor>,
+    pub(crate) spawns: Vec<SpawnPoint>,
+    /// In fill order.
+    pub(crate) fills: Vec<Fill>,
+}
+
+/// Fill phases; later phases overwrite earlier ones.
+#[derive(Default)]
+struct Phases {
+    shells: Vec<Fill>,
+    air: Vec<Fill>,
+    floors: Vec<Fill>,
+    features: Vec<Fill>,
+}
+
+impl Layout {
+    /// Places the rooms of `graph` in the grid starting at `origin`.
+    pub(crate) fn build(config: &DungeonConfig, graph: &RoomGraph, origin: [i32; 3], rng: &mut ChunkRng) -> Self {
+        let settings = &config.layout;
+        let blocks = &config.blocks;
+        let wall = Block::new(blocks.wall);
+        let floor = Block::new(blocks.floor);
+        let mut phases = Phases::default();
+
+        // Rooms, centred in their cells
+        let rooms: Vec<RoomBounds> = graph
+            .rooms
+            .iter()
+            .map(|room| {
+                let [cx, cy, cz] = room.cell.map(|cell| cell as i32);
+                let centre = [
+                    origin[0] + cx * settings.cell_size + settings.cell_size / 2,
+                    origin[2] + cz * settings.cell_size + settings.cell_size / 2,
+                ];
+                let [width, depth] = match room.kind {
+                    RoomKind::Boss => [settings.room_max; 2],
+                    _ => [(); 2].map(|()| rng.range_i32(settings.room_min, settings.room_max)),
+                };
+                let floor_y = origin[1] + cy * settings.floor_height + 1;
+                let min = [centre[0] - width / 2, floor_y, centre[1] - depth / 2];
+                let max = [min[0] + width, floor_y + settings.room_height, min[2] + depth];
+
+                phases.shells.push(Fill {
+                    min: [min[0] - 1, min[1] - 1, min[2] - 1],
+                    max: [max[0] + 1, max[1] + 1, max[2] + 1],
+                    block: wall,
+                });
+                phases.air.push(Fill { min, max, block: Block::AIR });
+                phases.floors.push(Fill {
+                    min: [min[0], floor_y - 1, min[2]],
+                    max: [max[0], floor_y, max[2]],
+                    block: floor,
+                });
+                RoomBounds { min, max }
+            })
+            .collect();
+
+        // Corridors and stairs, from the lower room's centre to the other's
+        let mut doors = Vec::new();
+        for connection in &graph.connections {
+            let [first, second] = connection.rooms;
+            let (low, high) = if rooms[first].min[1] <= rooms[second].min[1] {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            let (from, to) = (rooms[low].centre(), rooms[high].centre());
+            let axis = if from[0] == to[0] { 2 } else { 0 };
+            let cross = 2 - axis;
+            let step = (to[axis] - from[axis]).signum();
+            let rise = to[1] - from[1];
+            let height = if rise == 0 { 3 } else { 4 };
+            let climb_start = (settings.cell_size - settings.floor_height) / 2;
+
+            for t in 0..=settings.cell_size {
+                let floor_y = from[1] + (t - climb_start).clamp(0, rise);
+                let along = from[axis] + step * t;
+                let column = |below: i32, above: i32, half_width: i32| {
+                    let mut min = [0; 3];
+                    let mut max = [0; 3];
+                    (min[axis], max[axis]) = (along, along + 1);
+                    (min[cross], max[cross]) = (from[cross] - half_width, from[cross] + half_width + 1);
+                    (min[1], max[1]) = (below, above);
+                    (min, max)
+                };
+
+                let (min, max) = column(floor_y - 1, floor_y + height + 1, 2);
+                phases.shells.push(Fill { min, max, block: wall });
+                let (min, max) = column(floor_y, floor_y + height, 1);
+                phases.air.push(Fill { min, max, block: Block::AIR });
+                let (min, max) = column(floor_y - 1, floor_y, 1);
+                phases.floors.push(Fill { min, max, block: floor });
+
+                if let (Some(key), true) = (connection.lock, t == settings.cell_size / 2) {
+                    let (min, max) = column(floor_y, floor_y + height, 1);
+                    phases.features.push(Fill {
+                        min,
+                        max,
+                        block: Block::with_meta(blocks.door, key),
+                    });
+                    doors.push(LockedDoor {
+                        key,
+                        rooms: connection.rooms,
+                        min,
+                        max,
+                    });
+                }
+            }
+        }
+
+        let (containers, spawns) = furnish(config, graph, &rooms, rng, &mut phases.features);
+
+        let Phases {
+            mut shells,
+            air,
+            floors,
+            features,
+        } = phases;
+        shells.extend(air);
+        shells.extend(floors);
+        shells.extend(features);
+        Self {
+            rooms,
+            containers,
+            doors,
+            spawns,
+            fills: shells,
+        }
+    }
+}
+
+/// Puts chests in room corners and guards anywhere else on the floor.
+fn furnish(
+    config: &DungeonConfig,
+    graph: &RoomGraph,
+    rooms: &[RoomBounds],
+    rng: &mut ChunkRng,
+    features: &mut Vec<Fill>,
+) -> (Vec<LootContainer>, Vec<SpawnPoint>) {
+    let loot = &config.loot;
+    let spawns_settings = &config.spawns;
+    let mut containers = Vec::new();
+    let mut spawns = Vec::new();
+    for (id, (room, bounds)) in graph.rooms.iter().zip(rooms).enumerate() {
+        let (table, key) = match room.kind {
+            RoomKind::Start => (None, None),
+            RoomKind::Normal => ((rng.next_f64() < loot.room_chest_chance).then_some(loot.room), None),
+            RoomKind::Key(key) => (Some(loot.room), Some(key)),
+            RoomKind::Treasure => (Some(loot.treasure), None),
+            RoomKind::Boss => (Some(loot.boss), None),
+        };
+        if let Some(loot_table) = table {
+            let x = if rng.next_u64() & 1 == 0 { bounds.min[0] } else { bounds.max[0] - 1 };
+            let z = if rng.next_u64() & 1 == 0 { bounds.min[2] } else { bounds.max[2] - 1 };
+            let position = [x, bounds.min[1], z];
+            features.push(Fill {
+                min: position,
+                max: position.map(|value| value + 1),
+                block: Block::new(config.blocks.chest),
+            });
+            containers.push(LootContainer {
+                position,
+                room: id,
+                loot_table,
+                key,
+            });
+        }
+
+        let guards = match room.kind {
+            RoomKind::Start => 0,
+            RoomKind::Boss => {
+                let [x, y, z] = bounds.centre();
+                spawns.push(SpawnPoint {
+                    position: [x as f32 + 0.5, y as f32, z as f32 + 0.5],
+                    room: id,
+                    role: SpawnRole::Boss,
+                });
+                spawns_settings.boss_guards
+            }
+            _ => rng.range_i32(spawns_settings.min_guards as i32, spawns_settings.max_guards as i32) as u32,
+        };
+        for _ in 0..guards {
+            let x = rng.range_i32(bounds.min[0] + 1, bounds.max[0] - 2);
+            let z = rng.range_i32(bounds.min[2] + 1, bounds.max[2] - 2);
+            spawns.push(SpawnPoint {
+                position: [x as f32 + 0.5, bounds.min[1] as f32, z as f32 + 0.5],
+                room: id,
+                role: SpawnRole::Guard,
+            });
+        }
+    }
+    (containers, spawns)
+}
@@ -0,0 +1,398 @@
+//! # Dungeons
+//!
+//! Multi-floor dungeons of rooms joined by corridors and stairs, with keys
+//! and locked doors laid out so every dungeon can be finished.
+//!
+//! ```text
+//! dungeons.toml ──► DungeonConfig
+//!                        │
+//!        seed ──► RoomGraph (rooms, keys, locks, loops)
+//!                        │
+//!                        ▼
+//!                 Layout (boxes of blocks) ──► Dungeon
+//!                                               │  containers, doors, spawns
+//!                                               ▼
+//!                        DungeonPass carves each chunk's slice
+//! ```
+//!
+//! - [`config`]: grid, room, block, loot and spawn settings
+//! - [`graph`]: the key/lock room graph and its solvability check
+//! - [`layout`]: room and corridor geometry, chests, doors and spawns
+
+pub mod config;
+pub mod graph;
+pub mod layout;
+
+pub use config::{
+    DungeonBlocks, DungeonConfig, DungeonConfigError, LayoutSettings, LootSettings, RoomSettings, SpawnSettings,
+};
+pub use graph::{Connection, KeyId, RoomGraph, RoomId, RoomKind, RoomNode};
+pub use layout::{LockedDoor, LootContainer, RoomBounds, SpawnPoint, SpawnRole};
+
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::chunk::{Chunk, ChunkGenerator, CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::generator::{GenerationPass, PerSeed, Stage};
+use crate::noise::{ChunkRng, WorldSeed};
+use layout::{Fill, Layout};
+
+/// Seed purpose for dungeons.
+const DUNGEON_SEED: u64 = 107;
+
+/// Room graphs tried before giving up.
+const MAX_ATTEMPTS: u32 = 8;
+
+/// Why a dungeon could not be generated.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DungeonError {
+    /// Every attempt boxed its rooms in before placing them all.
+    #[error("no room graph fit the grid after {0} attempts")]
+    NoSpace(u32),
+}
+
+/// A generated dungeon: its rooms, what's in them and the blocks to carve.
+#[derive(Clone, Debug)]
+pub struct Dungeon {
+    origin: [i32; 3],
+    size: [i32; 3],
+    graph: RoomGraph,
+    rooms: Vec<RoomBounds>,
+    containers: Vec<LootContainer>,
+    doors: Vec<LockedDoor>,
+    spawns: Vec<SpawnPoint>,
+    fills: Vec<Fill>,
+}
+
+impl Dungeon {
+    /// Generates the dungeon whose lowest corner is `origin`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no room graph fits the grid; only very crowded settings
+    /// do.
+    pub fn generate(config: &DungeonConfig, seed: WorldSeed, origin: [i32; 3]) -> Result<Self, DungeonError> {
+        for attempt in 0..MAX_ATTEMPTS {
+            let mut rng = ChunkRng::new(seed, attempt as i32, 0);
+            let Some(graph) = RoomGraph::build(config, &mut rng) else {
+                continue;
+            };
+            debug_assert!(graph.solve().is_some(), "room graph can't be finished");
+
+            let Layout {
+                rooms,
+                containers,
+                doors,
+                spawns,
+                fills,
+            } = Layout::build(config, &graph, origin, &mut rng);
+            let settings = &config.layout;
+            return Ok(Self {
+                origin,
+                size: [
+                    settings.width as i32 * settings.cell_size,
+                    settings.floors as i32 * settings.floor_height,
+                    settings.depth as i32 * settings.cell_size,
+                ],
+                graph,
+                rooms,
+                containers,
+                doors,
+                spawns,
+                fills,
+            });
+        }
+        Err(DungeonError::NoSpace(MAX_ATTEMPTS))
+    }
+
+    /// Returns the lowest corner, in world blocks.
+    #[must_use]
+    pub const fn origin(&self) -> [i32; 3] {
+        self.origin
+    }
+
+    /// Returns the grid size, in blocks.
+    #[must_use]
+    pub const fn size(&self) -> [i32; 3] {
+        self.size
+    }
+
+    /// Returns the room graph.
+    #[must_use]
+    pub const fn graph(&self) -> &RoomGraph {
+        &self.graph
+    }
+
+    /// Returns each room's air, indexed like the graph's rooms.
+    #[must_use]
+    pub fn rooms(&self) -> &[RoomBounds] {
+        &self.rooms
+    }
+
+    /// Returns the chests.
+    #[must_use]
+    pub fn containers(&self) -> &[LootContainer] {
+        &self.containers
+    }
+
+    /// Returns the locked doors.
+    #[must_use]
+    pub fn doors(&self) -> &[LockedDoor] {
+        &self.doors
+    }
+
+    /// Returns where NPCs appear.
+    #[must_use]
+    pub fn spawns(&self) -> &[SpawnPoint] {
+        &self.spawns
+    }
+
+    /// Returns the player's entry point, in world units.
+    #[must_use]
+    pub fn entrance(&self) -> [f32; 3] {
+        let [x, y, z] = self.rooms[self.graph.start()].centre();
+        [x as f32 + 0.5, y as f32, z as f32 + 0.5]
+    }
+
+    /// Writes the part of the dungeon inside `chunk`. Returns true if any
+    /// block was written.
+    pub fn write(&self, chunk: &mut Chunk) -> bool {
+        let (world_x, world_z) = (chunk.coord.world_x(), chunk.coord.world_z());
+        let chunk_min = [world_x, 0, world_z];
+        let chunk_max = [world_x + CHUNK_SIZE as i32, CHUNK_HEIGHT as i32, world_z + CHUNK_SIZE as i32];
+
+        let mut written = false;
+        for fill in &self.fills {
+            let min: [i32; 3] = std::array::from_fn(|axis| fill.min[axis].max(chunk_min[axis]));
+            let max: [i32; 3] = std::array::from_fn(|axis| fill.max[axis].min(chunk_max[axis]));
+            if (0..3).any(|axis| min[axis] >= max[axis]) {
+                continue;
+            }
+            for y in min[1]..max[1] {
+                for z in min[2]..max[2] {
+                    for x in min[0]..max[0] {
+                        chunk.set_block((x - world_x) as usize, y as usize, (z - world_z) as usize, fill.block);
+                    }
+                }
+            }
+            written = true;
+        }
+        written
+    }
+}
+
+/// Carves a dungeon into the world.
+///
+/// Runs in [`Stage::Carving`], after caves, so tunnels never break into
+/// rooms through their walls. If the dungeon can't be generated the chunk
+/// is left as it is; call [`dungeon`](Self::dungeon) to see why.
+///
+/// ```rust,ignore
+/// let dungeon = DungeonPass::new(DungeonConfig::shipped(), [-72, 20, -72]);
+/// let generator = ChunkGenerator::with_passes(seed, "crypt", vec![Box::new(BiomeTerrain), Box::new(dungeon)]);
+/// ```
+pub struct DungeonPass {
+    config: Arc<DungeonConfig>,
+    origin: [i32; 3],
+    generated: PerSeed<Result<Arc<Dungeon>, DungeonError>>,
+}
+
+impl DungeonPass {
+    /// Creates a pass for the dungeon whose lowest corner is `origin`.
+    #[must_use]
+    pub fn new(config: Arc<DungeonConfig>, origin: [i32; 3]) -> Self {
+        Self {
+            config,
+            origin,
+            generated: PerSeed::new(),
+        }
+    }
+
+    /// Returns the lowest corner, in world blocks.
+    #[must_use]
+    pub const fn origin(&self) -> [i32; 3] {
+        self.origin
+    }
+
+    /// Generates the dungeon for a world, reusing the last result if the
+    /// seed is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`Dungeon::generate`] does.
+    pub fn dungeon(&self, world_seed: WorldSeed) -> Result<Arc<Dungeon>, DungeonError> {
+        self.generated.get(world_seed, DUNGEON_SEED, &self.origin, |seed| {
+            Dungeon::generate(&self.config, seed, self.origin).map(Arc::new)
+        })
+    }
+
+    /// Grid size in blocks, known without generating.
+    fn config_size(&self) -> [i32; 3] {
+        let layout = &self.config.layout;
+        [
+            layout.width as i32 * layout.cell_size,
+            layout.floors as i32 * layout.floor_height,
+            layout.depth as i32 * layout.cell_size,
+        ]
+    }
+}
+
+impl GenerationPass for DungeonPass {
+    fn stage(&self) -> Stage {
+        Stage::Carving
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        let [size_x, _, size_z] = self.config_size();
+        let [origin_x, _, origin_z] = self.origin;
+        let (world_x, world_z) = (chunk.coord.world_x(), chunk.coord.world_z());
+        if origin_x >= world_x + CHUNK_SIZE as i32
+            || origin_x + size_x <= world_x
+            || origin_z >= world_z + CHUNK_SIZE as i32
+            || origin_z + size_z <= world_z
+        {
+            return;
+        }
+        let Ok(dungeon) = self.dungeon(generator.seed()) else {
+            return;
+        };
+        if dungeon.write(chunk) {
+            chunk.rebuild_height_map();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::chunk::{Block, ChunkCoord};
+    use crate::generator::FlatTerrain;
+
+    /// Blocks the dungeon sets, by world position.
+    fn blocks(dungeon: &Dungeon) -> HashMap<[i32; 3], Block> {
+        let mut blocks = HashMap::new();
+        for fill in &dungeon.fills {
+            for y in fill.min[1]..fill.max[1] {
+                for z in fill.min[2]..fill.max[2] {
+                    for x in fill.min[0]..fill.max[0] {
+                        blocks.insert([x, y, z], fill.block);
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+    /// Rooms a player reaches from the start walking through air, opening
+    /// the doors `has_key` accepts. Anything outside the dungeon is solid.
+    fn walk(dungeon: &Dungeon, blocks: &HashMap<[i32; 3], Block>, has_key: impl Fn(KeyId) -> bool) -> Vec<bool> {
+        let door = DungeonConfig::shipped().blocks.door;
+        let passable = |position: &[i32; 3]| match blocks.get(position) {
+            Some(block) if block.id == door => has_key(block.meta),
+            Some(block) => *block == Block::AIR,
+            None => false,
+        };
+
+        let start = dungeon.rooms()[dungeon.graph().start()].centre();
+        let mut seen = HashSet::from([start]);
+        let mut pending = vec![start];
+        while let Some([x, y, z]) = pending.pop() {
+            for next in [[x + 1, y, z], [x - 1, y, z], [x, y + 1, z], [x, y - 1, z], [x, y, z + 1], [x, y, z - 1]] {
+                if passable(&next) && seen.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+        dungeon.rooms().iter().map(|room| seen.contains(&room.centre())).collect()
+    }
+
+    #[test]
+    fn test_locks_hold_in_the_blocks() {
+        let config = DungeonConfig::shipped();
+        for seed in 0..12 {
+            let dungeon = Dungeon::generate(&config, WorldSeed::new(seed), [-70, 20, 30]).unwrap();
+            let blocks = blocks(&dungeon);
+            let graph = dungeon.graph();
+
+            // With every key all rooms are open; with none, only level 0
+            assert!(walk(&dungeon, &blocks, |_| true).iter().all(|&reached| reached), "seed {seed}");
+            assert_eq!(walk(&dungeon, &blocks, |_| false), graph.reachable(|_| false), "seed {seed}: leak");
+            for missing in 0..config.rooms.locks as KeyId {
+                assert!(!walk(&dungeon, &blocks, |key| key != missing)[graph.boss()], "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_contents_follow_the_config() {
+        let config = DungeonConfig::shipped();
+        let dungeon = Dungeon::generate(&config, WorldSeed::new(3), [0, 10, 0]).unwrap();
+        let blocks = blocks(&dungeon);
+        let kind = |room: RoomId| dungeon.graph().rooms[room].kind;
+
+        for container in dungeon.containers() {
+            let room = &dungeon.rooms()[container.room];
+            assert!(room.contains(container.position));
+            assert_eq!(blocks[&container.position].id, config.blocks.chest);
+            let expected = match kind(container.room) {
+                RoomKind::Treasure => config.loot.treasure,
+                RoomKind::Boss => config.loot.boss,
+                RoomKind::Key(key) => {
+                    assert_eq!(container.key, Some(key));
+                    config.loot.room
+                }
+                _ => config.loot.room,
+            };
+            assert_eq!(container.loot_table, expected);
+        }
+        let has_chest = |kind: RoomKind| dungeon.containers().iter().any(|container| dungeon.graph().rooms[container.room].kind == kind);
+        assert!(has_chest(RoomKind::Boss) && has_chest(RoomKind::Treasure));
+
+        let bosses: Vec<&SpawnPoint> = dungeon.spawns().iter().filter(|spawn| spawn.role == SpawnRole::Boss).collect();
+        assert_eq!(bosses.len(), 1);
+        assert_eq!(kind(bosses[0].room), RoomKind::Boss);
+        assert!(dungeon.spawns().iter().all(|spawn| kind(spawn.room) != RoomKind::Start));
+
+        for door in dungeon.doors() {
+            assert_eq!(blocks[&door.min], Block::with_meta(config.blocks.door, door.key));
+        }
+        assert_eq!(dungeon.doors().len(), config.rooms.locks);
+    }
+
+    #[test]
+    fn test_pass_carves_same_blocks_in_any_order() {
+        let origin = [-40, 4, -40];
+        let pass = || DungeonPass::new(DungeonConfig::shipped(), origin);
+        let dungeon = pass().dungeon(WorldSeed::new(9)).unwrap();
+        let expected = blocks(&dungeon);
+
+        let generator = || ChunkGenerator::with_passes(WorldSeed::new(9), "dungeon_test", vec![Box::new(FlatTerrain), Box::new(pass())]);
+        let (forward, backward) = (generator(), generator());
+        let coords: Vec<ChunkCoord> = (-3..3).flat_map(|z| (-3..3).map(move |x| ChunkCoord::new(x, z))).collect();
+        let first: Vec<Chunk> = coords.iter().map(|&coord| forward.generate(coord)).collect();
+        let second: Vec<Chunk> = coords.iter().rev().map(|&coord| backward.generate(coord)).collect();
+
+        for (chunk, other) in first.iter().zip(second.iter().rev()) {
+            assert_eq!(chunk.coord, other.coord);
+            for y in 0..40 {
+                for z in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        let world = [chunk.coord.world_x() + x as i32, y as i32, chunk.coord.world_z() + z as i32];
+                        let block = chunk.get_block(x, y, z);
+                        assert_eq!(block, other.get_block(x, y, z));
+                        if let Some(&expected) = expected.get(&world) {
+                            assert_eq!(block, expected, "{world:?}");
+                        }
+                    }
+                }
+            }
+        }
+
+        // Same seed, same dungeon; another seed, another one
+        assert_eq!(pass().dungeon(WorldSeed::new(9)).unwrap().graph(), dungeon.graph());
+        assert_ne!(pass().dungeon(WorldSeed::new(10)).unwrap().graph(), dungeon.graph());
+    }
+}
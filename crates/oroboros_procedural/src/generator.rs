@@ -0,0 +1,516 @@
+//! # World Generators
+//!
+//! Pluggable chunk generation built from ordered passes.
+//!
+//! ## Design
+//!
+//! ```text
+//! ChunkGenerator (seed + shared noise)
+//!   │
+//!   ├─ Terrain     fills columns        UndercityTerrain │ BiomeTerrain │ FlatTerrain, OreVeins
+//!   ├─ Carving     removes blocks       ExtractionBeam │ Caves, DungeonPass
+//!   ├─ Decoration  adds features        Structures │ WfcPass, CityPass
+//!   └─ Loot        places pickups       GoldLoot
+//! ```
+//!
+//! A [`GeneratorPreset`] names a fixed list of passes. The preset name and
+//! version, seed and sea level are stored in [`WorldMetadata`] next to the
+//! world's chunks, so reopening a saved world always regenerates the same
+//! blocks, or refuses to if the preset has changed since.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use parking_lot::Mutex;
+
+use crate::chunk::{Chunk, ChunkCoord, ChunkGenerator, CHUNK_SIZE};
+use crate::noise::WorldSeed;
+
+/// Produces the chunks of a world.
+///
+/// Implementations must be deterministic: the same coordinate always
+/// yields the same chunk.
+pub trait WorldGenerator: Send + Sync {
+    /// Name recorded in world metadata.
+    fn name(&self) -> &str;
+
+    /// Seed recorded in world metadata.
+    fn seed(&self) -> WorldSeed;
+
+    /// Sea level recorded in world metadata.
+    fn sea_level(&self) -> i32 {
+        ChunkGenerator::DEFAULT_SEA_LEVEL
+    }
+
+    /// Version recorded in world metadata. Bump it whenever the same seed
+    /// starts producing different blocks.
+    fn version(&self) -> u32 {
+        1
+    }
+
+    /// Generates the chunk at `coord`.
+    fn generate(&self, coord: ChunkCoord) -> Chunk;
+}
+
+/// When a pass runs. Passes run in stage order, and in insertion order
+/// within a stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Fills every column with base blocks.
+    Terrain,
+    /// Removes blocks (caves, shafts, goal areas).
+    Carving,
+    /// Adds features on top of the terrain (trees, props).
+    Decoration,
+    /// Places collectible blocks.
+    Loot,
+}
+
+/// One step of chunk generation.
+pub trait GenerationPass: Send + Sync {
+    /// Stage this pass belongs to.
+    fn stage(&self) -> Stage;
+
+    /// Modifies `chunk`, using `generator`'s seed and noise sources.
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk);
+}
+
+/// Built-in generator configurations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GeneratorPreset {
+    /// GLITCH WARS maze of rooms, towers and catwalks with gold loot.
+    #[default]
+    Undercity,
+    /// Biome-based terrain with caves, ore veins and trees.
+    Terrain,
+    /// Flat gridded arena floor.
+    Flat,
+}
+
+impl GeneratorPreset {
+    /// Every preset.
+    pub const ALL: [Self; 3] = [Self::Undercity, Self::Terrain, Self::Flat];
+
+    /// Returns the name stored in world metadata.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Undercity => "undercity",
+            Self::Terrain => "terrain",
+            Self::Flat => "flat",
+        }
+    }
+
+    /// Looks up a preset by [`name`](Self::name).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    /// Returns the version stored in world metadata.
+    ///
+    /// Bump it whenever the preset's passes or their output change, so
+    /// saved worlds aren't reopened onto different terrain.
+    #[must_use]
+    pub const fn version(self) -> u32 {
+        match self {
+            // 2: floor, wall, bridge and gold blocks use registry IDs
+            Self::Undercity => 2,
+            // 2: ore veins and caves; 3: structures replace vegetation
+            Self::Terrain => 3,
+            Self::Flat => 1,
+        }
+    }
+
+    /// Returns this preset's passes.
+    #[must_use]
+    pub fn passes(self) -> Vec<Box<dyn GenerationPass>> {
+        match self {
+            Self::Undercity => vec![Box::new(UndercityTerrain), Box::new(ExtractionBeam), Box::new(GoldLoot)],
+            Self::Terrain => vec![Box::new(BiomeTerrain), Box::new(OreVeins), Box::new(Caves), Box::new(Structures)],
+            Self::Flat => vec![Box::new(FlatTerrain)],
+        }
+    }
+}
+
+/// Something a pass builds whole, once per world seed, rather than chunk
+/// by chunk, so chunks that share it agree no matter which is generated
+/// first. The last result is reused while the seed is unchanged.
+pub(crate) struct PerSeed<T> {
+    /// The seed last built for and what came out.
+    last: Mutex<Option<(WorldSeed, T)>>,
+}
+
+impl<T: Clone> PerSeed<T> {
+    /// Creates an empty cache.
+    pub(crate) fn new() -> Self {
+        Self { last: Mutex::new(None) }
+    }
+
+    /// Returns the value for `world_seed`, calling `build` if it changed.
+    ///
+    /// `build` gets a stream derived from `purpose` and the feature's
+    /// `origin`, so moving one feature doesn't reshuffle others.
+    pub(crate) fn get(&self, world_seed: WorldSeed, purpose: u64, origin: &[i32], build: impl FnOnce(WorldSeed) -> T) -> T {
+        let mut last = self.last.lock();
+        if let Some((seed, value)) = last.as_ref() {
+            if *seed == world_seed {
+                return value.clone();
+            }
+        }
+
+        let seed = origin.iter().fold(world_seed.derive(purpose), |seed, &axis| seed.derive(axis as u32 as u64));
+        let value = build(seed);
+        *last = Some((world_seed, value.clone()));
+        value
+    }
+}
+
+/// Calls `column` for every column of `chunk` with local and world
+/// coordinates.
+fn for_each_column(chunk: &mut Chunk, mut column: impl FnMut(&mut Chunk, usize, usize, i32, i32)) {
+    let world_x = chunk.coord.world_x();
+    let world_z = chunk.coord.world_z();
+    for local_z in 0..CHUNK_SIZE {
+        for local_x in 0..CHUNK_SIZE {
+            column(chunk, local_x, local_z, world_x + local_x as i32, world_z + local_z as i32);
+        }
+    }
+}
+
+/// Undercity rooms, walls, towers, catwalks and hazard pits.
+pub struct UndercityTerrain;
+
+impl GenerationPass for UndercityTerrain {
+    fn stage(&self) -> Stage {
+        Stage::Terrain
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        for_each_column(chunk, |chunk, local_x, local_z, block_x, block_z| {
+            generator.generate_undercity_column(chunk, local_x, local_z, block_x, block_z);
+        });
+    }
+}
+
+/// Biome-classified heightmap terrain with water below sea level.
+pub struct BiomeTerrain;
+
+impl GenerationPass for BiomeTerrain {
+    fn stage(&self) -> Stage {
+        Stage::Terrain
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        for_each_column(chunk, |chunk, local_x, local_z, block_x, block_z| {
+            generator.generate_column(chunk, local_x, local_z, block_x, block_z);
+        });
+    }
+}
+
+/// Flat arena floor with grid lines.
+pub struct FlatTerrain;
+
+impl GenerationPass for FlatTerrain {
+    fn stage(&self) -> Stage {
+        Stage::Terrain
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        for_each_column(chunk, |chunk, local_x, local_z, block_x, block_z| {
+            generator.generate_flat_column(chunk, local_x, local_z, block_x, block_z);
+        });
+    }
+}
+
+/// Ore veins in stone, by depth and biome ore multiplier.
+///
+/// Runs after the terrain fill so caves carved later expose ore walls.
+pub struct OreVeins;
+
+impl GenerationPass for OreVeins {
+    fn stage(&self) -> Stage {
+        Stage::Terrain
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        generator.place_ores(chunk);
+    }
+}
+
+/// Worm tunnels and noise caverns below the surface.
+pub struct Caves;
+
+impl GenerationPass for Caves {
+    fn stage(&self) -> Stage {
+        Stage::Carving
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        generator.carve_caves(chunk);
+    }
+}
+
+/// Clears the Undercity goal shaft at the origin.
+pub struct ExtractionBeam;
+
+impl GenerationPass for ExtractionBeam {
+    fn stage(&self) -> Stage {
+        Stage::Carving
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        generator.carve_extraction_beam(chunk, chunk.coord.world_x(), chunk.coord.world_z());
+    }
+}
+
+/// Trees, ruins and towers, placed per region so they cross chunk borders.
+pub struct Structures;
+
+impl GenerationPass for Structures {
+    fn stage(&self) -> Stage {
+        Stage::Decoration
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        generator.place_structures(chunk);
+    }
+}
+
+/// Gold on Undercity floors, platforms and tower tops.
+pub struct GoldLoot;
+
+impl GenerationPass for GoldLoot {
+    fn stage(&self) -> Stage {
+        Stage::Loot
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        generator.generate_gold_loot(chunk, chunk.coord.world_x(), chunk.coord.world_z());
+    }
+}
+
+/// Generator settings saved with a world.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorldMetadata {
+    /// World seed.
+    pub seed: WorldSeed,
+    /// Generator name (a [`GeneratorPreset`] name for built-in worlds).
+    pub generator: String,
+    /// Generator version the world was created with.
+    pub generator_version: u32,
+    /// Sea level.
+    pub sea_level: i32,
+}
+
+impl WorldMetadata {
+    /// File name inside the world's save directory.
+    pub const FILE_NAME: &'static str = "world.meta";
+
+    /// Current format version.
+    const FORMAT_VERSION: u32 = 1;
+
+    /// Metadata for a preset world with the default sea level.
+    #[must_use]
+    pub fn new(seed: WorldSeed, preset: GeneratorPreset) -> Self {
+        Self {
+            seed,
+            generator: preset.name().to_string(),
+            generator_version: preset.version(),
+            sea_level: ChunkGenerator::DEFAULT_SEA_LEVEL,
+        }
+    }
+
+    /// Metadata describing `generator`.
+    #[must_use]
+    pub fn of(generator: &dyn WorldGenerator) -> Self {
+        Self {
+            seed: generator.seed(),
+            generator: generator.name().to_string(),
+            generator_version: generator.version(),
+            sea_level: generator.sea_level(),
+        }
+    }
+
+    /// Returns the preset named by [`generator`](Self::generator).
+    #[must_use]
+    pub fn preset(&self) -> Option<GeneratorPreset> {
+        GeneratorPreset::from_name(&self.generator)
+    }
+
+    /// Builds the generator this metadata describes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the generator isn't a built-in preset, or
+    /// the preset's version differs from the one the world was made with.
+    pub fn build_generator(&self) -> io::Result<ChunkGenerator> {
+        let preset = self.preset().ok_or_else(|| {
+            invalid_data(format!("World uses unknown generator '{}'", self.generator))
+        })?;
+        if preset.version() != self.generator_version {
+            return Err(invalid_data(format!(
+                "World uses generator '{}' version {}, but this build has version {}",
+                self.generator,
+                self.generator_version,
+                preset.version()
+            )));
+        }
+        Ok(ChunkGenerator::from_preset(self.seed, preset).with_sea_level(self.sea_level))
+    }
+
+    /// Writes the metadata as `key=value` lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = format!(
+            "format={}\nseed={}\ngenerator={}\ngenerator_version={}\nsea_level={}\n",
+            Self::FORMAT_VERSION,
+            self.seed.value(),
+            self.generator,
+            self.generator_version,
+            self.sea_level
+        );
+        fs::write(path, text)
+    }
+
+    /// Reads metadata written by [`save`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, `InvalidData` if it is
+    /// malformed or from a newer format.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut format = None;
+        let mut seed = None;
+        let mut generator = None;
+        let mut generator_version = None;
+        let mut sea_level = None;
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid_data(format!("Malformed metadata line '{line}'")))?;
+            let bad_value = || invalid_data(format!("Bad value for '{key}': '{value}'"));
+            match key {
+                "format" => format = Some(value.parse::<u32>().map_err(|_| bad_value())?),
+                "seed" => seed = Some(WorldSeed::new(value.parse().map_err(|_| bad_value())?)),
+                "generator" => generator = Some(value.to_string()),
+                "generator_version" => generator_version = Some(value.parse().map_err(|_| bad_value())?),
+                "sea_level" => sea_level = Some(value.parse().map_err(|_| bad_value())?),
+                // Unknown keys are from newer writers of the same format
+                _ => {}
+            }
+        }
+
+        match format {
+            Some(version) if version <= Self::FORMAT_VERSION => {}
+            Some(version) => return Err(invalid_data(format!("Unsupported metadata format {version}"))),
+            None => return Err(invalid_data("Metadata has no format version".to_string())),
+        }
+
+        Ok(Self {
+            seed: seed.ok_or_else(|| invalid_data("Metadata has no seed".to_string()))?,
+            generator: generator.ok_or_else(|| invalid_data("Metadata has no generator".to_string()))?,
+            generator_version: generator_version
+                .ok_or_else(|| invalid_data("Metadata has no generator version".to_string()))?,
+            sea_level: sea_level.unwrap_or(ChunkGenerator::DEFAULT_SEA_LEVEL),
+        })
+    }
+}
+
+/// Builds an `InvalidData` error.
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::{Block, CHUNK_HEIGHT};
+
+    fn blocks(chunk: &Chunk) -> Vec<Block> {
+        let mut out = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE * CHUNK_HEIGHT);
+        for y in 0..CHUNK_HEIGHT {
+            for z in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    out.push(chunk.get_block(x, y, z));
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_presets_produce_distinct_worlds() {
+        let seed = WorldSeed::new(42);
+        let coord = ChunkCoord::new(3, -2);
+
+        let undercity = ChunkGenerator::from_preset(seed, GeneratorPreset::Undercity);
+        assert_eq!(blocks(&undercity.generate(coord)), blocks(&ChunkGenerator::new(seed).generate(coord)));
+
+        let flat = ChunkGenerator::from_preset(seed, GeneratorPreset::Flat).generate(coord);
+        assert_eq!(flat.get_height(0, 0), 1);
+        assert!(flat.get_block(5, 2, 5).is_air());
+
+        let terrain = ChunkGenerator::from_preset(seed, GeneratorPreset::Terrain);
+        let chunk = terrain.generate(coord);
+        assert_eq!(chunk.get_block(0, 0, 0).id, 7, "terrain preset has bedrock 7");
+        assert_eq!(blocks(&chunk), blocks(&terrain.generate(coord)));
+        assert_ne!(blocks(&chunk), blocks(&undercity.generate(coord)));
+    }
+
+    #[test]
+    fn test_custom_passes_run_in_stage_order() {
+        struct Pillar(Stage, u16);
+
+        impl GenerationPass for Pillar {
+            fn stage(&self) -> Stage {
+                self.0
+            }
+
+            fn apply(&self, _generator: &ChunkGenerator, chunk: &mut Chunk) {
+                chunk.set_block(0, 10, 0, Block::new(self.1));
+            }
+        }
+
+        // Loot is listed first but must run last
+        let generator = ChunkGenerator::with_passes(
+            WorldSeed::new(1),
+            "pillars",
+            vec![Box::new(Pillar(Stage::Loot, 9)), Box::new(FlatTerrain), Box::new(Pillar(Stage::Decoration, 8))],
+        );
+        let chunk = generator.generate(ChunkCoord::new(0, 0));
+        assert_eq!(chunk.get_block(0, 10, 0).id, 9);
+        assert_eq!(WorldGenerator::name(&generator), "pillars");
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let path = std::env::temp_dir().join(format!("oroboros_meta_{}.meta", std::process::id()));
+        let mut metadata = WorldMetadata::new(WorldSeed::new(u64::MAX), GeneratorPreset::Terrain);
+        metadata.sea_level = 40;
+        metadata.save(&path).unwrap();
+
+        let loaded = WorldMetadata::load(&path).unwrap();
+        assert_eq!(loaded, metadata);
+        let generator = loaded.build_generator().unwrap();
+        assert_eq!(WorldMetadata::of(&generator), metadata);
+
+        std::fs::write(&path, "format=99\nseed=1\ngenerator=flat\n").unwrap();
+        assert_eq!(WorldMetadata::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        std::fs::write(&path, "format=1\nseed=1\ngenerator=nope\ngenerator_version=1\n").unwrap();
+        assert!(WorldMetadata::load(&path).unwrap().build_generator().is_err());
+
+        // A world from another version of its preset would regenerate differently
+        std::fs::write(&path, "format=1\nseed=1\ngenerator=flat\ngenerator_version=99\n").unwrap();
+        let other = WorldMetadata::load(&path).unwrap();
+        assert!(matches!(other.build_generator(), Err(e) if e.kind() == io::ErrorKind::InvalidData));
+        std::fs::remove_file(&path).ok();
+    }
+}
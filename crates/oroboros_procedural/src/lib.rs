@@ -13,9 +13,17 @@
 //!
 //! - `SimplexNoise`: 2D/3D noise generation
 //! - `ChunkGenerator`: Produces world chunks from noise
+//! - `WorldGenerator`: Pluggable generators built from generation passes
 //! - `BiomeClassifier`: Determines terrain types from noise values
+//! - `BiomeRegistry`: Biome definitions loaded from `biomes.toml`
 //! - `WorldManager`: Dynamic chunk loading/unloading
 //! - `ChunkPersistence`: WAL integration for block modifications
+//! - `RegionStore`: Region files holding saved chunks
+//! - `UndergroundConfig`: Caves and ore veins from `underground.toml`
+//! - `PlacedStructure`: Trees and buildings placed per region across chunks
+//! - `WfcSolver`: Wave Function Collapse over modular voxel tilesets
+//! - `Dungeon`: Multi-floor key/lock dungeons from `dungeons.toml`
+//! - `City`: Neon Prime streets, towers and neon lights from `cities.toml`
 //!
 //! ## Example
 //!
@@ -42,13 +50,27 @@
 pub mod biome;
 pub mod chunk;
 pub mod chunk_persistence;
+pub mod city;
+pub mod dungeon;
+pub mod generator;
 pub mod noise;
+pub mod region;
+pub mod structures;
+pub mod underground;
+pub mod wfc;
 pub mod world_manager;
 
-pub use biome::{Biome, BiomeClassifier};
+pub use biome::{Biome, BiomeClassifier, BiomeConfigError, BiomeDef, BiomeRegistry, ClimateRange, ClimateRegion};
 pub use chunk::{Block, Chunk, ChunkCoord, ChunkGenerator, CHUNK_SIZE};
 pub use chunk_persistence::{BlockModifyPayload, ChunkOpType, ChunkPersistence, WorldChunkSystem};
-pub use noise::{SimplexNoise, WorldSeed};
+pub use city::{City, CityConfig, CityConfigError, CityLight, CityPass, LightSource};
+pub use dungeon::{Dungeon, DungeonConfig, DungeonConfigError, DungeonError, DungeonPass, LootContainer, SpawnPoint};
+pub use generator::{GenerationPass, GeneratorPreset, Stage, WorldGenerator, WorldMetadata};
+pub use noise::{ChunkRng, SimplexNoise, WorldSeed};
+pub use region::{RegionCoord, RegionError, RegionFlusher, RegionStore, StorageMode, StoredChunk, REGION_SIZE};
+pub use structures::{PlacedStructure, StructureKind, MAX_STRUCTURE_RADIUS, STRUCTURE_REGION_SIZE};
+pub use underground::{CavernSettings, CaveSettings, OreDef, UndergroundConfig, UndergroundConfigError, WormSettings};
+pub use wfc::{Constraints, Tileset, TilesetError, WfcError, WfcGrid, WfcPass, WfcSolver};
 pub use world_manager::{
     ChunkModification, ChunkState, ModificationEntry, WorldManager, WorldManagerConfig, WorldStats,
 };
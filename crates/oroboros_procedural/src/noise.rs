@@ -115,9 +115,22 @@ impl PermutationTable {
     fn gradient(&self, hash: u8) -> [i8; 2] {
         self.grad[(hash % 12) as usize]
     }
+
+    /// Gets a 3D gradient for a given hash.
+    #[inline]
+    fn gradient_3d(hash: u8) -> [i8; 3] {
+        GRAD3[(hash % 12) as usize]
+    }
 }
 
-/// 2D Simplex noise generator.
+/// 12 gradient vectors for 3D simplex: the edge midpoints of a cube.
+const GRAD3: [[i8; 3]; 12] = [
+    [1, 1, 0], [-1, 1, 0], [1, -1, 0], [-1, -1, 0],
+    [1, 0, 1], [-1, 0, 1], [1, 0, -1], [-1, 0, -1],
+    [0, 1, 1], [0, -1, 1], [0, 1, -1], [0, -1, -1],
+];
+
+/// 2D and 3D Simplex noise generator.
 ///
 /// Produces smooth, continuous noise values in the range [-1, 1].
 ///
@@ -149,6 +162,10 @@ impl SimplexNoise {
     const F2: f64 = 0.366025403784439; // (sqrt(3) - 1) / 2
     /// Unskewing factor for 2D simplex grid.
     const G2: f64 = 0.211324865405187; // (3 - sqrt(3)) / 6
+    /// Skewing factor for 3D simplex grid.
+    const F3: f64 = 1.0 / 3.0;
+    /// Unskewing factor for 3D simplex grid.
+    const G3: f64 = 1.0 / 6.0;
 
     /// Creates a new simplex noise generator from a seed.
     #[must_use]
@@ -215,6 +232,89 @@ impl SimplexNoise {
         }
     }
 
+    /// Samples 3D simplex noise at the given coordinates.
+    ///
+    /// Used for volumes such as caves, where 2D layers would leave
+    /// vertical streaks.
+    ///
+    /// # Returns
+    ///
+    /// A value in the range [-1, 1].
+    #[must_use]
+    pub fn sample_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        // Skew input coordinates to simplex grid
+        let skew = (x + y + z) * Self::F3;
+        let i = fast_floor(x + skew);
+        let j = fast_floor(y + skew);
+        let k = fast_floor(z + skew);
+
+        // Unskew to get first corner in simplex
+        let unskew = f64::from(i + j + k) * Self::G3;
+        let x0 = x - (f64::from(i) - unskew);
+        let y0 = y - (f64::from(j) - unskew);
+        let z0 = z - (f64::from(k) - unskew);
+
+        // Determine which of the six tetrahedra we're in
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        // Offsets for the other three corners
+        let corner = |di: usize, dj: usize, dk: usize, n: f64| {
+            (
+                x0 - di as f64 + n * Self::G3,
+                y0 - dj as f64 + n * Self::G3,
+                z0 - dk as f64 + n * Self::G3,
+            )
+        };
+        let c1 = corner(i1, j1, k1, 1.0);
+        let c2 = corner(i2, j2, k2, 2.0);
+        let c3 = corner(1, 1, 1, 3.0);
+
+        // Hash coordinates to get gradient indices
+        let ii = (i & 255) as usize;
+        let jj = (j & 255) as usize;
+        let kk = (k & 255) as usize;
+        let perm = &self.perm_table;
+        let hash = |di: usize, dj: usize, dk: usize| {
+            perm.get(ii + di + perm.get(jj + dj + perm.get(kk + dk) as usize) as usize)
+        };
+
+        // Calculate contribution from four corners
+        let n0 = Self::contribution_3d(x0, y0, z0, hash(0, 0, 0));
+        let n1 = Self::contribution_3d(c1.0, c1.1, c1.2, hash(i1, j1, k1));
+        let n2 = Self::contribution_3d(c2.0, c2.1, c2.2, hash(i2, j2, k2));
+        let n3 = Self::contribution_3d(c3.0, c3.1, c3.2, hash(1, 1, 1));
+
+        // Scale to [-1, 1] range
+        32.0 * (n0 + n1 + n2 + n3)
+    }
+
+    /// Calculates the contribution from one corner of a 3D simplex.
+    #[inline]
+    fn contribution_3d(x: f64, y: f64, z: f64, gradient_index: u8) -> f64 {
+        let t = 0.6 - x * x - y * y - z * z;
+        if t < 0.0 {
+            0.0
+        } else {
+            let grad = PermutationTable::gradient_3d(gradient_index);
+            let t2 = t * t;
+            t2 * t2 * (x * f64::from(grad[0]) + y * f64::from(grad[1]) + z * f64::from(grad[2]))
+        }
+    }
+
     /// Generates octaved (fractal) noise.
     ///
     /// Combines multiple layers of noise at different frequencies
@@ -289,6 +389,62 @@ impl SimplexNoise {
     }
 }
 
+/// Deterministic random numbers for features placed per chunk.
+///
+/// Seeded from the world seed and a chunk coordinate, so a feature that
+/// starts in one chunk can be replayed exactly by its neighbours: each
+/// chunk regenerates the caves and veins of the chunks around it and keeps
+/// the blocks that fall inside itself.
+#[derive(Clone, Debug)]
+pub struct ChunkRng(u64);
+
+impl ChunkRng {
+    /// Creates the generator for a chunk.
+    #[must_use]
+    pub const fn new(seed: WorldSeed, chunk_x: i32, chunk_z: i32) -> Self {
+        let seed = seed
+            .derive(chunk_x as u32 as u64)
+            .derive((chunk_z as u32 as u64) << 32 | 0x5EED);
+        Self(seed.value())
+    }
+
+    /// Returns the next 64 random bits (`SplitMix64`).
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `[0, 1)`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a value in `[min, max)`.
+    #[inline]
+    pub fn range_f64(&mut self, min: f64, max: f64) -> f64 {
+        min + self.next_f64() * (max - min)
+    }
+
+    /// Returns a value in `[min, max]`.
+    #[inline]
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        let span = (i64::from(max) - i64::from(min) + 1).max(1) as u64;
+        (i64::from(min) + (self.next_u64() % span) as i64) as i32
+    }
+
+    /// Rounds a fractional count up or down at random, keeping its mean.
+    #[inline]
+    pub fn round_count(&mut self, mean: f64) -> u32 {
+        let whole = mean.max(0.0).floor();
+        whole as u32 + u32::from(self.next_f64() < mean - whole)
+    }
+}
+
 /// Fast floor function.
 ///
 /// Faster than `f64::floor()` for our use case.
@@ -369,6 +525,49 @@ mod tests {
         assert!(diff2 < 0.01, "Noise should be continuous: diff = {diff2}");
     }
 
+    #[test]
+    fn test_3d_range_and_continuity() {
+        let noise = SimplexNoise::new(WorldSeed::new(42));
+
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+        for i in 0..10000 {
+            let x = (i as f64 * 0.1) - 500.0;
+            let y = (i as f64 * 0.07) - 300.0;
+            let z = (i as f64 * 0.13) - 650.0;
+            let value = noise.sample_3d(x, y, z);
+            assert!((-1.0..=1.0).contains(&value), "Value {value} out of range at ({x}, {y}, {z})");
+            min = min.min(value);
+            max = max.max(value);
+        }
+        assert!(min < -0.5 && max > 0.5, "3D noise should use its range: [{min}, {max}]");
+
+        let v = noise.sample_3d(10.0, 20.0, 30.0);
+        assert!((v - noise.sample_3d(10.0, 20.001, 30.0)).abs() < 0.01);
+        assert_eq!(v, SimplexNoise::new(WorldSeed::new(42)).sample_3d(10.0, 20.0, 30.0));
+        assert_ne!(v, noise.sample_3d(10.0, 21.5, 30.0), "Y must matter");
+    }
+
+    #[test]
+    fn test_chunk_rng() {
+        let seed = WorldSeed::new(42);
+        let mut a = ChunkRng::new(seed, 3, -7);
+        let mut b = ChunkRng::new(seed, 3, -7);
+        let mut c = ChunkRng::new(seed, -7, 3);
+        let first = a.next_u64();
+        assert_eq!(first, b.next_u64());
+        assert_ne!(first, c.next_u64(), "Swapped coordinates must differ");
+
+        for _ in 0..1000 {
+            let value = a.range_i32(-2, 5);
+            assert!((-2..=5).contains(&value));
+            assert!((0.0..1.0).contains(&a.next_f64()));
+        }
+
+        let total: u32 = (0..10_000).map(|_| a.round_count(0.25)).sum();
+        assert!((2000..3000).contains(&total), "mean of 0.25 gave {total}");
+    }
+
     #[test]
     fn test_octaved_noise() {
         let noise = SimplexNoise::new(WorldSeed::new(42));
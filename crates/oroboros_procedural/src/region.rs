@@ -0,0 +1,726 @@
+//! # Region Files
+//!
+//! Saved chunks grouped into files of 32x32 chunks.
+//!
+//! ## Format
+//!
+//! ```text
+//! r.<x>.<z>.region (all integers little-endian)
+//! ┌──────────────────────────────────────────────────────────┐
+//! │ magic "ORRG" │ version u16 │ reserved u16                │
+//! │ index: 1024 × { offset u32, length u32 }  (0 = empty)    │
+//! ├──────────────────────────────────────────────────────────┤
+//! │ record: chunk_x i32 │ chunk_z i32 │ kind u8 │ version u8 │
+//! │         reserved u16 │ crc32 u32 │ LZ4 payload            │
+//! │ record: ...                                              │
+//! └──────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A record holds either a full chunk or only the block modifications
+//! to replay on top of the generated terrain (see [`StorageMode`]). Index
+//! slot `local_z * 32 + local_x` points at the chunk's record; the CRC
+//! covers the compressed payload.
+//!
+//! ## Crash Safety
+//!
+//! Saving rewrites the region into a temporary file, syncs it and renames
+//! it over the old one, so a crash leaves the old or the new region on
+//! disk, never a mix. [`RegionFlusher`] runs saves on a background thread.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread::{self, JoinHandle};
+
+use lz4_flex::{compress_prepend_size, decompress_size_prepended};
+use parking_lot::Mutex;
+use thiserror::Error;
+
+use crate::chunk::{Chunk, ChunkCoord};
+use crate::world_manager::ChunkModification;
+
+/// Chunks per region side.
+pub const REGION_SIZE: i32 = 32;
+
+/// Chunks per region.
+const SLOTS: usize = (REGION_SIZE * REGION_SIZE) as usize;
+
+/// File magic.
+const MAGIC: [u8; 4] = *b"ORRG";
+
+/// Region file format version.
+const FORMAT_VERSION: u16 = 1;
+
+/// Bytes before the first record.
+const HEADER_SIZE: usize = 8 + SLOTS * 8;
+
+/// Bytes of a record before its payload.
+const RECORD_HEADER_SIZE: usize = 16;
+
+/// Record payload version.
+const RECORD_VERSION: u8 = 1;
+
+/// Record kind: [`StoredChunk::Full`].
+const KIND_FULL: u8 = 1;
+
+/// Record kind: [`StoredChunk::Overlay`].
+const KIND_OVERLAY: u8 = 2;
+
+/// Bytes per encoded modification.
+const MODIFICATION_SIZE: usize = 13;
+
+/// Why a region could not be read or written.
+#[derive(Error, Debug)]
+pub enum RegionError {
+    /// Reading or writing failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// The file is not a region file.
+    #[error("not a region file (bad magic)")]
+    BadMagic,
+    /// The file or a record is from a newer format.
+    #[error("unsupported region format version {0}")]
+    UnsupportedVersion(u16),
+    /// A record's payload doesn't match its checksum.
+    #[error("chunk {0:?} failed its checksum")]
+    Checksum(ChunkCoord),
+    /// The file is structurally invalid.
+    #[error("corrupt region: {0}")]
+    Corrupt(&'static str),
+}
+
+/// How much of a chunk is saved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StorageMode {
+    /// Only player modifications; terrain is regenerated from the seed.
+    #[default]
+    Overlay,
+    /// Every block, independent of the generator.
+    Full,
+}
+
+/// A chunk as saved in a region.
+#[derive(Clone)]
+pub enum StoredChunk {
+    /// Complete chunk.
+    Full(Box<Chunk>),
+    /// Modifications to replay on the generated chunk, oldest first.
+    Overlay(Vec<ChunkModification>),
+}
+
+impl StoredChunk {
+    /// Record kind byte.
+    fn kind(&self) -> u8 {
+        match self {
+            Self::Full(_) => KIND_FULL,
+            Self::Overlay(_) => KIND_OVERLAY,
+        }
+    }
+
+    /// Uncompressed payload.
+    fn payload(&self) -> Vec<u8> {
+        match self {
+            Self::Full(chunk) => chunk.to_raw(),
+            Self::Overlay(modifications) => {
+                let mut bytes = Vec::with_capacity(4 + modifications.len() * MODIFICATION_SIZE);
+                bytes.extend_from_slice(&(modifications.len() as u32).to_le_bytes());
+                for m in modifications {
+                    bytes.extend_from_slice(&[m.local_x, m.y, m.local_z]);
+                    bytes.extend_from_slice(&m.block_id.to_le_bytes());
+                    bytes.extend_from_slice(&m.tick.to_le_bytes());
+                }
+                bytes
+            }
+        }
+    }
+
+    /// Decodes a payload of the given kind.
+    fn from_payload(coord: ChunkCoord, kind: u8, bytes: &[u8]) -> Result<Self, RegionError> {
+        match kind {
+            KIND_FULL => Chunk::from_raw(coord, bytes)
+                .map(|chunk| Self::Full(Box::new(chunk)))
+                .ok_or(RegionError::Corrupt("full chunk has the wrong size")),
+            KIND_OVERLAY => {
+                if bytes.len() < 4 {
+                    return Err(RegionError::Corrupt("truncated overlay"));
+                }
+                let (count, rest) = bytes.split_at(4);
+                let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize;
+                if rest.len() != count * MODIFICATION_SIZE {
+                    return Err(RegionError::Corrupt("overlay length mismatch"));
+                }
+
+                let modifications = rest
+                    .chunks_exact(MODIFICATION_SIZE)
+                    .map(|m| ChunkModification {
+                        local_x: m[0],
+                        y: m[1],
+                        local_z: m[2],
+                        block_id: u16::from_le_bytes([m[3], m[4]]),
+                        tick: u64::from_le_bytes(m[5..13].try_into().expect("8-byte slice")),
+                    })
+                    .collect();
+                Ok(Self::Overlay(modifications))
+            }
+            _ => Err(RegionError::Corrupt("unknown record kind")),
+        }
+    }
+
+    /// Encodes a complete record for `coord`.
+    fn encode(&self, coord: ChunkCoord) -> Vec<u8> {
+        let compressed = compress_prepend_size(&self.payload());
+        let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + compressed.len());
+        record.extend_from_slice(&coord.x.to_le_bytes());
+        record.extend_from_slice(&coord.z.to_le_bytes());
+        record.extend_from_slice(&[self.kind(), RECORD_VERSION, 0, 0]);
+        record.extend_from_slice(&crc32fast::hash(&compressed).to_le_bytes());
+        record.extend_from_slice(&compressed);
+        record
+    }
+
+    /// Decodes and verifies a record expected to hold `coord`.
+    fn decode(coord: ChunkCoord, record: &[u8]) -> Result<Self, RegionError> {
+        if record.len() < RECORD_HEADER_SIZE {
+            return Err(RegionError::Corrupt("truncated record"));
+        }
+
+        let (header, compressed) = record.split_at(RECORD_HEADER_SIZE);
+        let field = |at: usize| [header[at], header[at + 1], header[at + 2], header[at + 3]];
+        if ChunkCoord::new(i32::from_le_bytes(field(0)), i32::from_le_bytes(field(4))) != coord {
+            return Err(RegionError::Corrupt("record belongs to another chunk"));
+        }
+        if header[9] > RECORD_VERSION {
+            return Err(RegionError::UnsupportedVersion(u16::from(header[9])));
+        }
+        if crc32fast::hash(compressed) != u32::from_le_bytes(field(12)) {
+            return Err(RegionError::Checksum(coord));
+        }
+
+        let payload = decompress_size_prepended(compressed).map_err(|_| RegionError::Corrupt("bad LZ4 payload"))?;
+        Self::from_payload(coord, header[8], &payload)
+    }
+}
+
+/// Position of a region in the region grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RegionCoord {
+    /// X coordinate (in regions).
+    pub x: i32,
+    /// Z coordinate (in regions).
+    pub z: i32,
+}
+
+impl RegionCoord {
+    /// Returns the region containing a chunk.
+    #[inline]
+    #[must_use]
+    pub const fn of(chunk: ChunkCoord) -> Self {
+        Self {
+            x: chunk.x.div_euclid(REGION_SIZE),
+            z: chunk.z.div_euclid(REGION_SIZE),
+        }
+    }
+
+    /// Index slot of a chunk within its region.
+    #[inline]
+    const fn slot(chunk: ChunkCoord) -> usize {
+        (chunk.z.rem_euclid(REGION_SIZE) * REGION_SIZE + chunk.x.rem_euclid(REGION_SIZE)) as usize
+    }
+
+    /// File name of the region.
+    #[must_use]
+    pub fn file_name(self) -> String {
+        format!("r.{}.{}.region", self.x, self.z)
+    }
+}
+
+/// Reads the header and returns the `(offset, length)` index.
+fn read_index(header: &[u8]) -> Result<Vec<(u32, u32)>, RegionError> {
+    if header.len() < HEADER_SIZE {
+        return Err(RegionError::Corrupt("truncated header"));
+    }
+    if header[..4] != MAGIC {
+        return Err(RegionError::BadMagic);
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version > FORMAT_VERSION {
+        return Err(RegionError::UnsupportedVersion(version));
+    }
+
+    Ok(header[8..HEADER_SIZE]
+        .chunks_exact(8)
+        .map(|entry| {
+            (
+                u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+            )
+        })
+        .collect())
+}
+
+/// Region files in one directory.
+///
+/// Loads may run from any thread alongside saves; saves are serialized
+/// internally.
+///
+/// ```rust,ignore
+/// let store = RegionStore::new("world/chunks");
+/// store.save([(coord, &StoredChunk::Overlay(modifications))])?;
+/// let restored = store.load(coord)?;
+/// ```
+pub struct RegionStore {
+    /// Directory holding the region files.
+    dir: PathBuf,
+    /// Held while a region is rewritten.
+    write_lock: Mutex<()>,
+}
+
+impl RegionStore {
+    /// Creates a store for `dir`. Nothing is touched until the first save.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns the directory holding the region files.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of a region file.
+    #[must_use]
+    pub fn region_path(&self, region: RegionCoord) -> PathBuf {
+        self.dir.join(region.file_name())
+    }
+
+    /// Loads a chunk, or `None` if it was never saved.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the region can't be read or the record is corrupt.
+    pub fn load(&self, coord: ChunkCoord) -> Result<Option<StoredChunk>, RegionError> {
+        let mut file = match File::open(self.region_path(RegionCoord::of(coord))) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut header = vec![0; HEADER_SIZE];
+        file.read_exact(&mut header)?;
+        let (offset, length) = read_index(&header)?[RegionCoord::slot(coord)];
+        if length == 0 {
+            return Ok(None);
+        }
+
+        // Check the index against the file before trusting it with an allocation
+        if u64::from(offset) + u64::from(length) > file.metadata()?.len() {
+            return Err(RegionError::Corrupt("record out of bounds"));
+        }
+        let mut record = vec![0; length as usize];
+        file.seek(SeekFrom::Start(u64::from(offset)))?;
+        file.read_exact(&mut record)?;
+        StoredChunk::decode(coord, &record).map(Some)
+    }
+
+    /// Saves chunks, rewriting each affected region once.
+    ///
+    /// Returns the number of chunks written.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a region can't be written, or an existing region is
+    /// corrupt (it is left untouched rather than overwritten).
+    pub fn save<'a>(&self, chunks: impl IntoIterator<Item = (ChunkCoord, &'a StoredChunk)>) -> Result<usize, RegionError> {
+        let mut regions: HashMap<RegionCoord, Vec<(usize, Vec<u8>)>> = HashMap::new();
+        let mut count = 0;
+        for (coord, stored) in chunks {
+            regions
+                .entry(RegionCoord::of(coord))
+                .or_default()
+                .push((RegionCoord::slot(coord), stored.encode(coord)));
+            count += 1;
+        }
+
+        let _guard = self.write_lock.lock();
+        if !regions.is_empty() {
+            fs::create_dir_all(&self.dir)?;
+        }
+        for (region, records) in regions {
+            self.rewrite(region, &records)?;
+        }
+        Ok(count)
+    }
+
+    /// Replaces records in a region file, keeping the others.
+    fn rewrite(&self, region: RegionCoord, updates: &[(usize, Vec<u8>)]) -> Result<(), RegionError> {
+        let path = self.region_path(region);
+        let old = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut records: Vec<&[u8]> = vec![&[]; SLOTS];
+        if !old.is_empty() {
+            for (slot, (offset, length)) in read_index(&old)?.into_iter().enumerate() {
+                let range = offset as usize..offset as usize + length as usize;
+                records[slot] = old.get(range).ok_or(RegionError::Corrupt("record out of bounds"))?;
+            }
+        }
+        for (slot, record) in updates {
+            records[*slot] = record;
+        }
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + records.iter().map(|r| r.len()).sum::<usize>());
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+        let mut offset = HEADER_SIZE;
+        for record in &records {
+            let start = if record.is_empty() { 0 } else { offset };
+            bytes.extend_from_slice(&(start as u32).to_le_bytes());
+            bytes.extend_from_slice(&(record.len() as u32).to_le_bytes());
+            offset += record.len();
+        }
+        for record in &records {
+            bytes.extend_from_slice(record);
+        }
+
+        let temp = path.with_extension("region.tmp");
+        {
+            let mut file = File::create(&temp)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+        }
+        fs::rename(&temp, &path)?;
+        // Make the rename itself durable
+        #[cfg(unix)]
+        File::open(&self.dir)?.sync_all()?;
+        Ok(())
+    }
+}
+
+/// Chunks sent to the flush thread, with their tickets.
+type FlushBatch = Vec<(ChunkCoord, u64, Arc<StoredChunk>)>;
+
+/// Outcome of a batch.
+struct FlushAck {
+    /// Chunks in the batch.
+    tickets: Vec<(ChunkCoord, u64)>,
+    /// Whether they were written.
+    result: Result<usize, RegionError>,
+}
+
+/// Writes chunks to a [`RegionStore`] on a background thread.
+///
+/// Submitted chunks stay readable through [`pending`](Self::pending)
+/// until they are on disk, so a chunk reloaded mid-flush is never read
+/// stale. Failed writes stay pending and are retried by
+/// [`flush`](Self::flush).
+pub struct RegionFlusher {
+    /// Destination.
+    store: Arc<RegionStore>,
+    /// Chunks not yet on disk, with the ticket of their latest submit.
+    pending: HashMap<ChunkCoord, (u64, Arc<StoredChunk>)>,
+    /// Next submit ticket.
+    next_ticket: u64,
+    /// Batches submitted but not acknowledged.
+    in_flight: usize,
+    /// Work for the flush thread.
+    jobs: Sender<FlushBatch>,
+    /// Results from the flush thread.
+    acks: Receiver<FlushAck>,
+    /// Result sender, for writing inline when there is no thread.
+    ack_sender: Sender<FlushAck>,
+    /// The flush thread (Native only).
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: Option<JoinHandle<()>>,
+}
+
+impl RegionFlusher {
+    /// Starts a flush thread writing to `store`. On WASM writes run
+    /// inline.
+    #[must_use]
+    pub fn new(store: Arc<RegionStore>) -> Self {
+        let (jobs, job_receiver) = channel::<FlushBatch>();
+        let (ack_sender, acks) = channel();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let worker = {
+            let store = Arc::clone(&store);
+            let ack_sender = ack_sender.clone();
+            Some(thread::spawn(move || {
+                while let Ok(batch) = job_receiver.recv() {
+                    let _ = ack_sender.send(Self::write(&store, &batch));
+                }
+            }))
+        };
+        #[cfg(target_arch = "wasm32")]
+        drop(job_receiver);
+
+        Self {
+            store,
+            pending: HashMap::new(),
+            next_ticket: 0,
+            in_flight: 0,
+            jobs,
+            acks,
+            ack_sender,
+            #[cfg(not(target_arch = "wasm32"))]
+            worker,
+        }
+    }
+
+    /// Writes a batch and reports the outcome.
+    fn write(store: &RegionStore, batch: &FlushBatch) -> FlushAck {
+        FlushAck {
+            tickets: batch.iter().map(|(coord, ticket, _)| (*coord, *ticket)).collect(),
+            result: store.save(batch.iter().map(|(coord, _, stored)| (*coord, stored.as_ref()))),
+        }
+    }
+
+    /// Returns the store being written to.
+    #[must_use]
+    pub fn store(&self) -> &Arc<RegionStore> {
+        &self.store
+    }
+
+    /// Returns the latest submitted state of a chunk not yet on disk.
+    #[must_use]
+    pub fn pending(&self, coord: ChunkCoord) -> Option<&Arc<StoredChunk>> {
+        self.pending.get(&coord).map(|(_, stored)| stored)
+    }
+
+    /// Returns the number of chunks not yet on disk.
+    #[must_use]
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Queues chunks for writing.
+    pub fn submit(&mut self, chunks: impl IntoIterator<Item = (ChunkCoord, StoredChunk)>) {
+        let batch: FlushBatch = chunks
+            .into_iter()
+            .map(|(coord, stored)| {
+                let ticket = self.next_ticket;
+                self.next_ticket += 1;
+                let stored = Arc::new(stored);
+                self.pending.insert(coord, (ticket, Arc::clone(&stored)));
+                (coord, ticket, stored)
+            })
+            .collect();
+        self.dispatch(batch);
+    }
+
+    /// Sends a batch to the thread, or writes it inline without one.
+    fn dispatch(&mut self, batch: FlushBatch) {
+        if batch.is_empty() {
+            return;
+        }
+        self.in_flight += 1;
+        if let Err(rejected) = self.jobs.send(batch) {
+            let _ = self.ack_sender.send(Self::write(&self.store, &rejected.0));
+        }
+    }
+
+    /// Handles an acknowledgement, returning the chunks written.
+    fn complete(&mut self, ack: FlushAck) -> Result<usize, RegionError> {
+        self.in_flight -= 1;
+        let written = ack.result?;
+        for (coord, ticket) in ack.tickets {
+            // A newer submit of the same chunk stays pending
+            if self.pending.get(&coord).is_some_and(|(latest, _)| *latest == ticket) {
+                self.pending.remove(&coord);
+            }
+        }
+        Ok(written)
+    }
+
+    /// Processes finished writes without blocking.
+    ///
+    /// Returns the number of chunks written since the last call. Failures
+    /// are logged and left pending.
+    pub fn poll(&mut self) -> usize {
+        let mut written = 0;
+        while let Ok(ack) = self.acks.try_recv() {
+            match self.complete(ack) {
+                Ok(count) => written += count,
+                Err(err) => eprintln!("[REGION] Flush failed, will retry: {err}"),
+            }
+        }
+        written
+    }
+
+    /// Blocks until every pending chunk is on disk, retrying failed writes
+    /// once.
+    ///
+    /// Returns the number of chunks written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the retry; those chunks stay pending.
+    pub fn flush(&mut self) -> Result<usize, RegionError> {
+        let mut written = 0;
+        while self.in_flight > 0 {
+            let Ok(ack) = self.acks.recv() else {
+                break;
+            };
+            written += self.complete(ack).unwrap_or(0);
+        }
+
+        let retry: FlushBatch = self
+            .pending
+            .iter()
+            .map(|(coord, (ticket, stored))| (*coord, *ticket, Arc::clone(stored)))
+            .collect();
+        if !retry.is_empty() {
+            let ack = Self::write(&self.store, &retry);
+            self.in_flight += 1;
+            written += self.complete(ack)?;
+        }
+        Ok(written)
+    }
+}
+
+impl Drop for RegionFlusher {
+    /// Finishes queued writes before returning.
+    fn drop(&mut self) {
+        // Closing the job channel ends the thread after its queue drains
+        let (closed, _) = channel();
+        self.jobs = closed;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::Block;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("oroboros_region_{name}_{}", std::process::id()))
+    }
+
+    fn modification(local_x: u8, y: u8, block_id: u16) -> ChunkModification {
+        ChunkModification {
+            local_x,
+            y,
+            local_z: 3,
+            block_id,
+            tick: 77,
+        }
+    }
+
+    #[test]
+    fn test_region_roundtrip() {
+        let dir = temp_dir("roundtrip");
+        let store = RegionStore::new(&dir);
+
+        let mut chunk = Chunk::new(ChunkCoord::new(-1, 40));
+        chunk.set_block(1, 2, 3, Block::STONE);
+        let overlay = ChunkCoord::new(-32, 40);
+        let full = chunk.coord;
+
+        // Both land in region (-1, 1)
+        assert_eq!(RegionCoord::of(full), RegionCoord::of(overlay));
+        assert_eq!(
+            store
+                .save([
+                    (full, &StoredChunk::Full(Box::new(chunk))),
+                    (overlay, &StoredChunk::Overlay(vec![modification(1, 64, 9)])),
+                ])
+                .unwrap(),
+            2
+        );
+
+        // A later save keeps the other record
+        store
+            .save([(overlay, &StoredChunk::Overlay(vec![modification(1, 64, 9), modification(2, 65, 4)]))])
+            .unwrap();
+
+        match store.load(full).unwrap() {
+            Some(StoredChunk::Full(loaded)) => {
+                assert_eq!(loaded.get_block(1, 2, 3), Block::STONE);
+                assert_eq!(loaded.get_height(1, 3), 2);
+            }
+            _ => panic!("expected a full chunk"),
+        }
+        match store.load(overlay).unwrap() {
+            Some(StoredChunk::Overlay(mods)) => {
+                assert_eq!(mods.len(), 2);
+                assert_eq!((mods[1].local_x, mods[1].y, mods[1].block_id, mods[1].tick), (2, 65, 4, 77));
+            }
+            _ => panic!("expected an overlay"),
+        }
+        assert!(store.load(ChunkCoord::new(-2, 40)).unwrap().is_none());
+        assert!(store.load(ChunkCoord::new(500, 500)).unwrap().is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_corruption_is_detected() {
+        let dir = temp_dir("corrupt");
+        let store = RegionStore::new(&dir);
+        let coord = ChunkCoord::new(0, 0);
+        store.save([(coord, &StoredChunk::Overlay(vec![modification(0, 1, 2)]))]).unwrap();
+
+        let path = store.region_path(RegionCoord::of(coord));
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(store.load(coord), Err(RegionError::Checksum(c)) if c == coord));
+
+        // A length past the end of the file is refused, not allocated
+        let mut huge = bytes.clone();
+        huge[8 + 4..8 + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path, &huge).unwrap();
+        assert!(matches!(store.load(coord), Err(RegionError::Corrupt("record out of bounds"))));
+
+        // Refuses to rewrite a region it can't read
+        bytes[0] = b'X';
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            store.save([(ChunkCoord::new(1, 0), &StoredChunk::Overlay(Vec::new()))]),
+            Err(RegionError::BadMagic)
+        ));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_flusher_keeps_latest_pending() {
+        let dir = temp_dir("flusher");
+        let store = Arc::new(RegionStore::new(&dir));
+        let coord = ChunkCoord::new(3, 3);
+
+        let mut flusher = RegionFlusher::new(Arc::clone(&store));
+        flusher.submit([(coord, StoredChunk::Overlay(vec![modification(0, 1, 1)]))]);
+        flusher.submit([(coord, StoredChunk::Overlay(vec![modification(0, 1, 2)]))]);
+        assert!(matches!(flusher.pending(coord).map(AsRef::as_ref), Some(StoredChunk::Overlay(m)) if m[0].block_id == 2));
+
+        flusher.flush().unwrap();
+        assert_eq!(flusher.pending_count(), 0);
+        assert!(matches!(store.load(coord).unwrap(), Some(StoredChunk::Overlay(m)) if m[0].block_id == 2));
+
+        // Dropping waits for queued writes
+        flusher.submit([(coord, StoredChunk::Overlay(vec![modification(0, 1, 3)]))]);
+        drop(flusher);
+        assert!(matches!(store.load(coord).unwrap(), Some(StoredChunk::Overlay(m)) if m[0].block_id == 3));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
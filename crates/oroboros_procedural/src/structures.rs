@@ -0,0 +1,489 @@
+//! # Structures
+//!
+//! Trees, ruins and towers that may span several chunks.
+//!
+//! ## Placement
+//!
+//! ```text
+//! ┌──────── region (32×32) ────────┐
+//! │  sites chosen from the seed,    │   each chunk asks every region within
+//! │  the region coordinate and the  │   MAX_STRUCTURE_RADIUS which
+//! │  terrain height/biome functions │   structures overlap it, and writes
+//! └─────────────────────────────────┘   only its own slice of each
+//! ```
+//!
+//! Sites depend only on the seed and the terrain *functions*, never on
+//! generated chunk contents, so every chunk agrees on where a structure
+//! stands and how tall it is. Structures are written in a fixed global
+//! order, so overlaps resolve the same way in every chunk: a tree
+//! straddling a border is whole no matter which side is generated first.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::chunk::{Block, Chunk, ChunkCoord, ChunkGenerator, CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::noise::{ChunkRng, WorldSeed};
+
+/// Width and depth of a structure region, in blocks.
+pub const STRUCTURE_REGION_SIZE: i32 = 32;
+
+/// Furthest any structure reaches from its origin column, in blocks.
+pub const MAX_STRUCTURE_RADIUS: i32 = 6;
+
+/// Seed purpose for structure sites.
+const STRUCTURE_SEED: u64 = 105;
+
+/// Trees are tried once per cell of this size.
+const TREE_CELL: i32 = 3;
+
+/// Tree density (0-100) from which a biome counts as forest.
+const FOREST_DENSITY: u8 = 30;
+
+/// Regions kept in a [`StructureCache`] before it is cleared.
+const CACHE_REGIONS: usize = 256;
+
+/// Kinds of structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StructureKind {
+    /// 4-6 block tree on grass.
+    Tree,
+    /// 2×2 trunk, 10-16 blocks tall with a wide canopy, in forests.
+    GiantTree,
+    /// Broken stone walls on open land.
+    Ruin,
+    /// Hollow stone tower with wooden floors on open land.
+    Tower,
+}
+
+impl StructureKind {
+    /// Furthest this kind reaches from its origin column.
+    #[must_use]
+    pub const fn radius(self) -> i32 {
+        match self {
+            Self::Tree => 2,
+            Self::GiantTree | Self::Ruin => 6,
+            Self::Tower => 3,
+        }
+    }
+
+    /// Blocks this kind rises above its origin at most.
+    #[must_use]
+    pub const fn max_height(self) -> i32 {
+        match self {
+            Self::Tree => 9,
+            Self::GiantTree => 19,
+            Self::Ruin => 4,
+            Self::Tower => 22,
+        }
+    }
+}
+
+/// A structure at a site.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlacedStructure {
+    /// What is built.
+    pub kind: StructureKind,
+    /// World position of the lowest block above ground at the centre.
+    pub origin: [i32; 3],
+    /// Picks sizes and details.
+    pub variant: u64,
+}
+
+impl PlacedStructure {
+    /// Returns true if any of the structure's columns lie in `coord`.
+    #[must_use]
+    pub fn overlaps(&self, coord: ChunkCoord) -> bool {
+        let radius = self.kind.radius();
+        let (min_x, min_z) = (coord.world_x(), coord.world_z());
+        let size = CHUNK_SIZE as i32;
+        self.origin[0] + radius >= min_x
+            && self.origin[0] - radius < min_x + size
+            && self.origin[2] + radius >= min_z
+            && self.origin[2] - radius < min_z + size
+    }
+
+    /// Writes the part of the structure inside `chunk`.
+    pub fn write(&self, chunk: &mut Chunk) {
+        let mut slice = Slice::new(chunk);
+        match self.kind {
+            StructureKind::Tree => self.write_tree(&mut slice),
+            StructureKind::GiantTree => self.write_giant_tree(&mut slice),
+            StructureKind::Ruin => self.write_ruin(&mut slice),
+            StructureKind::Tower => self.write_tower(&mut slice),
+        }
+    }
+
+    /// Deterministic bits for one block of the structure.
+    fn hash(&self, x: i32, y: i32, z: i32) -> u64 {
+        ChunkRng::new(WorldSeed::new(self.variant).derive(y as u32 as u64), x, z).next_u64()
+    }
+
+    fn write_tree(&self, slice: &mut Slice<'_>) {
+        let [x, y, z] = self.origin;
+        let height = 4 + (self.variant % 3) as i32;
+
+        for dy in 0..height {
+            slice.set(x, y + dy, z, Block::WOOD, Fill::AirOrLeaves);
+        }
+
+        // 5×5×4 canopy with the corners cut off
+        for leaf_y in y + height - 2..y + height + 2 {
+            for dz in -2..=2 {
+                for dx in -2..=2 {
+                    if dx * dx + dz * dz <= 5 {
+                        slice.set(x + dx, leaf_y, z + dz, Block::LEAVES, Fill::Air);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_giant_tree(&self, slice: &mut Slice<'_>) {
+        let [x, y, z] = self.origin;
+        let height = 10 + (self.variant % 7) as i32;
+
+        for dy in 0..height {
+            for (dx, dz) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                slice.set(x + dx, y + dy, z + dz, Block::WOOD, Fill::AirOrLeaves);
+            }
+        }
+
+        // Layered canopy around the trunk top, widest just below it
+        let top = y + height;
+        for dy in -4..=2 {
+            let radius: i32 = if dy <= 0 { 5 } else { 5 - 2 * dy };
+            for dz in -radius..=radius + 1 {
+                for dx in -radius..=radius + 1 {
+                    // Distance from the centre of the 2×2 trunk, doubled
+                    let (cx, cz) = (2 * dx - 1, 2 * dz - 1);
+                    let ragged = (self.hash(x + dx, top + dy, z + dz) % 3) as i32;
+                    if cx * cx + cz * cz <= 4 * radius * radius - ragged * 4 * radius {
+                        slice.set(x + dx, top + dy, z + dz, Block::LEAVES, Fill::Air);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_ruin(&self, slice: &mut Slice<'_>) {
+        let [x, y, z] = self.origin;
+        let half = 3 + (self.variant % 4) as i32;
+
+        for dz in -half..=half {
+            for dx in -half..=half {
+                let (bx, bz) = (x + dx, z + dz);
+                slice.set(bx, y - 1, bz, Block::STONE, Fill::Any);
+                slice.foundation(bx, y - 2, bz);
+
+                if dx.abs() == half || dz.abs() == half {
+                    // Crumbling walls: 0-3 blocks high
+                    let wall = (self.hash(bx, y, bz) % 4) as i32;
+                    for dy in 0..wall {
+                        slice.set(bx, y + dy, bz, Block::STONE, Fill::Any);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_tower(&self, slice: &mut Slice<'_>) {
+        let [x, y, z] = self.origin;
+        let height = 12 + (self.variant % 9) as i32;
+
+        for dz in -3..=3 {
+            for dx in -3..=3 {
+                let distance = dx * dx + dz * dz;
+                if distance > 10 {
+                    continue;
+                }
+                let (bx, bz) = (x + dx, z + dz);
+                slice.set(bx, y - 1, bz, Block::STONE, Fill::Any);
+                slice.foundation(bx, y - 2, bz);
+
+                let is_wall = distance > 4;
+                let is_door = dx == 0 && dz == -3;
+                for dy in 0..height {
+                    let block = if is_wall {
+                        if is_door && dy < 2 {
+                            Block::AIR
+                        } else {
+                            Block::STONE
+                        }
+                    } else if dy > 0 && dy % 5 == 0 {
+                        Block::WOOD
+                    } else {
+                        Block::AIR
+                    };
+                    slice.set(bx, y + dy, bz, block, Fill::Any);
+                }
+
+                // Crenellations
+                if is_wall && (dx + dz) % 2 == 0 {
+                    slice.set(bx, y + height, bz, Block::STONE, Fill::Any);
+                }
+            }
+        }
+    }
+}
+
+/// Which existing blocks a structure block may replace.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Fill {
+    /// Only air (leaves don't eat terrain or trunks).
+    Air,
+    /// Air or leaves (trunks grow through neighbouring canopies).
+    AirOrLeaves,
+    /// Anything (buildings clear their own space).
+    Any,
+}
+
+/// A chunk seen through world coordinates; writes outside it are dropped.
+struct Slice<'a> {
+    chunk: &'a mut Chunk,
+    world_x: i32,
+    world_z: i32,
+}
+
+impl<'a> Slice<'a> {
+    fn new(chunk: &'a mut Chunk) -> Self {
+        let (world_x, world_z) = (chunk.coord.world_x(), chunk.coord.world_z());
+        Self { chunk, world_x, world_z }
+    }
+
+    /// Local coordinates of a world position, if it is in the chunk.
+    fn local(&self, x: i32, y: i32, z: i32) -> Option<(usize, usize, usize)> {
+        let size = CHUNK_SIZE as i32;
+        let (lx, lz) = (x - self.world_x, z - self.world_z);
+        ((0..size).contains(&lx) && (0..size).contains(&lz) && (1..CHUNK_HEIGHT as i32).contains(&y))
+            .then_some((lx as usize, y as usize, lz as usize))
+    }
+
+    fn set(&mut self, x: i32, y: i32, z: i32, block: Block, fill: Fill) {
+        let Some((lx, ly, lz)) = self.local(x, y, z) else {
+            return;
+        };
+        let existing = self.chunk.get_block(lx, ly, lz);
+        let allowed = match fill {
+            Fill::Air => existing.is_air(),
+            Fill::AirOrLeaves => existing.is_air() || existing.id == Block::LEAVES.id,
+            Fill::Any => true,
+        };
+        if allowed {
+            self.chunk.set_block(lx, ly, lz, block);
+        }
+    }
+
+    /// Fills stone down from `y` until solid ground, so buildings on slopes
+    /// don't float.
+    fn foundation(&mut self, x: i32, y: i32, z: i32) {
+        for below in (1..=y).rev() {
+            let Some((lx, ly, lz)) = self.local(x, below, z) else {
+                return;
+            };
+            let existing = self.chunk.get_block(lx, ly, lz);
+            if !existing.is_air() && existing.id != Block::WATER.id {
+                return;
+            }
+            self.chunk.set_block(lx, ly, lz, Block::STONE);
+        }
+    }
+}
+
+/// Structures of one region, in write order.
+type RegionStructures = Arc<[PlacedStructure]>;
+
+/// Structure sites by region, so neighbouring chunks don't redo the
+/// terrain sampling behind them.
+#[derive(Default)]
+pub(crate) struct StructureCache {
+    regions: Mutex<HashMap<(i32, i32), RegionStructures>>,
+}
+
+impl StructureCache {
+    /// Returns a region's structures, choosing their sites on first use.
+    pub(crate) fn region(&self, generator: &ChunkGenerator, region_x: i32, region_z: i32) -> RegionStructures {
+        if let Some(placed) = self.regions.lock().get(&(region_x, region_z)) {
+            return placed.clone();
+        }
+        let placed: RegionStructures = choose_sites(generator, region_x, region_z).into();
+        let mut regions = self.regions.lock();
+        if regions.len() >= CACHE_REGIONS {
+            regions.clear();
+        }
+        regions.insert((region_x, region_z), placed.clone());
+        placed
+    }
+}
+
+/// Chooses the structures of one region.
+fn choose_sites(generator: &ChunkGenerator, region_x: i32, region_z: i32) -> Vec<PlacedStructure> {
+    let registry = generator.classifier().registry();
+    let mut rng = ChunkRng::new(generator.seed().derive(STRUCTURE_SEED), region_x, region_z);
+    let base_x = region_x * STRUCTURE_REGION_SIZE;
+    let base_z = region_z * STRUCTURE_REGION_SIZE;
+    let mut placed = Vec::new();
+
+    // Dry ground at a column: (surface height, biome)
+    let ground = |x: i32, z: i32| {
+        let height = generator.terrain_height(x, z);
+        let biome = generator.classifier().classify(f64::from(x), f64::from(z));
+        (height >= generator.sea_level()).then_some((height, biome))
+    };
+    let fits = |kind: StructureKind, height: i32| height + 1 + kind.max_height() < CHUNK_HEIGHT as i32;
+
+    // One ruin or tower try per region, on open land
+    let building = if rng.next_f64() < 0.5 { StructureKind::Ruin } else { StructureKind::Tower };
+    let (chance, x, z, variant) = (rng.next_f64(), rng.range_i32(0, 31), rng.range_i32(0, 31), rng.next_u64());
+    if chance < 0.12 {
+        let (x, z) = (base_x + x, base_z + z);
+        if let Some((height, biome)) = ground(x, z) {
+            if registry.tree_density(biome) < FOREST_DENSITY && fits(building, height) {
+                placed.push(PlacedStructure { kind: building, origin: [x, height + 1, z], variant });
+            }
+        }
+    }
+
+    // One giant tree try per region, in forests
+    let (chance, x, z, variant) = (rng.next_f64(), rng.range_i32(0, 31), rng.range_i32(0, 31), rng.next_u64());
+    if chance < 0.35 {
+        let (x, z) = (base_x + x, base_z + z);
+        if let Some((height, biome)) = ground(x, z) {
+            let surface = registry.surface_block(biome);
+            let on_grass = surface == Block::GRASS.id || surface == Block::JUNGLE_GRASS.id;
+            if on_grass && registry.tree_density(biome) >= FOREST_DENSITY && fits(StructureKind::GiantTree, height) {
+                placed.push(PlacedStructure { kind: StructureKind::GiantTree, origin: [x, height + 1, z], variant });
+            }
+        }
+    }
+
+    // Trees: one try per cell, kept by biome density
+    // (2% of columns for plains, up to ~16% for jungle)
+    for cell_z in (0..STRUCTURE_REGION_SIZE).step_by(TREE_CELL as usize) {
+        for cell_x in (0..STRUCTURE_REGION_SIZE).step_by(TREE_CELL as usize) {
+            let x = base_x + cell_x + rng.range_i32(0, TREE_CELL - 1);
+            let z = base_z + cell_z + rng.range_i32(0, TREE_CELL - 1);
+            let (roll, variant) = (rng.next_f64(), rng.next_u64());
+            let Some((height, biome)) = ground(x, z) else {
+                continue;
+            };
+            let surface = registry.surface_block(biome);
+            if surface != Block::GRASS.id && surface != Block::JUNGLE_GRASS.id {
+                continue;
+            }
+            let clear_of_buildings = placed.iter().all(|building| {
+                let reach = building.kind.radius() + StructureKind::Tree.radius();
+                building.kind == StructureKind::Tree
+                    || (building.origin[0] - x).abs() > reach
+                    || (building.origin[2] - z).abs() > reach
+            });
+            if !clear_of_buildings {
+                continue;
+            }
+            let density = f64::from(registry.tree_density(biome));
+            let per_column = 0.02 + density / 100.0 * 0.18;
+            let per_cell = per_column * f64::from(TREE_CELL * TREE_CELL);
+            if density > 0.0 && roll < per_cell && fits(StructureKind::Tree, height) {
+                placed.push(PlacedStructure { kind: StructureKind::Tree, origin: [x, height + 1, z], variant });
+            }
+        }
+    }
+
+    placed
+}
+
+/// Returns every structure overlapping `coord`, in write order.
+pub(crate) fn structures_near(generator: &ChunkGenerator, cache: &StructureCache, coord: ChunkCoord) -> Vec<PlacedStructure> {
+    let size = CHUNK_SIZE as i32;
+    let region = |block: i32| block.div_euclid(STRUCTURE_REGION_SIZE);
+    let (world_x, world_z) = (coord.world_x(), coord.world_z());
+
+    let mut out = Vec::new();
+    for region_z in region(world_z - MAX_STRUCTURE_RADIUS)..=region(world_z + size - 1 + MAX_STRUCTURE_RADIUS) {
+        for region_x in region(world_x - MAX_STRUCTURE_RADIUS)..=region(world_x + size - 1 + MAX_STRUCTURE_RADIUS) {
+            out.extend(cache.region(generator, region_x, region_z).iter().filter(|placed| placed.overlaps(coord)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::GeneratorPreset;
+
+    fn same_blocks(a: &Chunk, b: &Chunk) -> bool {
+        (0..CHUNK_HEIGHT).all(|y| {
+            (0..CHUNK_SIZE).all(|z| (0..CHUNK_SIZE).all(|x| a.get_block(x, y, z) == b.get_block(x, y, z)))
+        })
+    }
+
+    fn terrain(seed: u64) -> ChunkGenerator {
+        ChunkGenerator::from_preset(WorldSeed::new(seed), GeneratorPreset::Terrain)
+    }
+
+    #[test]
+    fn test_generation_order_does_not_matter() {
+        let forward = terrain(5);
+        let backward = terrain(5);
+        let coords: Vec<ChunkCoord> = (-2..2).flat_map(|z| (-2..2).map(move |x| ChunkCoord::new(x, z))).collect();
+
+        let first: Vec<Chunk> = coords.iter().map(|&coord| forward.generate(coord)).collect();
+        let mut second: Vec<Chunk> = coords.iter().rev().map(|&coord| backward.generate(coord)).collect();
+        second.reverse();
+        for (a, b) in first.iter().zip(&second) {
+            assert!(same_blocks(a, b), "chunk {:?} depends on generation order", a.coord);
+        }
+    }
+
+    #[test]
+    fn test_trees_continue_across_borders() {
+        let generator = terrain(9);
+        let mut crossing = 0;
+
+        for region_z in -2..2 {
+            for region_x in -2..2 {
+                for tree in generator.structures_in_region(region_x, region_z).iter() {
+                    let [x, y, z] = tree.origin;
+                    if tree.kind != StructureKind::Tree || x.rem_euclid(CHUNK_SIZE as i32) != 15 {
+                        continue;
+                    }
+                    // Trunk on the east edge, so half the canopy is in the next chunk
+                    let east = generator.generate(ChunkCoord::from_block_pos(x + 1, z));
+                    let local_z = z.rem_euclid(CHUNK_SIZE as i32) as usize;
+                    let height = 4 + (tree.variant % 3) as i32;
+                    if east.get_block(0, (y + height) as usize, local_z).id == Block::LEAVES.id {
+                        crossing += 1;
+                    }
+                }
+            }
+        }
+        assert!(crossing > 0, "no tree canopy crossed a chunk border");
+    }
+
+    #[test]
+    fn test_buildings_stand_on_foundations() {
+        let generator = terrain(21);
+        let towers: Vec<PlacedStructure> = (-10..10)
+            .flat_map(|z| (-10..10).map(move |x| (x, z)))
+            .flat_map(|(x, z)| generator.structures_in_region(x, z).to_vec())
+            .filter(|placed| placed.kind == StructureKind::Tower)
+            .collect();
+        assert!(!towers.is_empty(), "no towers in 400 regions");
+
+        for tower in towers {
+            let [x, y, z] = tower.origin;
+            // The wall 3 blocks east may be in the next chunk
+            let wall_x = x + 3;
+            let chunk = generator.generate(ChunkCoord::from_block_pos(wall_x, z));
+            let (lx, lz) = (wall_x.rem_euclid(16) as usize, z.rem_euclid(16) as usize);
+            for dy in -1..12 {
+                assert_eq!(chunk.get_block(lx, (y + dy) as usize, lz).id, Block::STONE.id, "tower wall at {:?}", tower.origin);
+            }
+            // Nothing hangs over air or water below the floor
+            let below = chunk.get_block(lx, (y - 2) as usize, lz);
+            assert!(!below.is_air() && below.id != Block::WATER.id, "tower floats at {:?}", tower.origin);
+        }
+    }
+}
@@ -0,0 +1,547 @@
+//! # Caves and Ore Veins
+//!
+//! Carves 3D caves and places ore veins below biome terrain.
+//!
+//! - **Worm tunnels**: random walks that start in a chunk and wander up to
+//!   [`UndergroundConfig::worm_reach_chunks`] chunks away. Each chunk
+//!   replays the worms of every chunk within reach and keeps the part that
+//!   falls inside itself, so tunnels continue across borders no matter
+//!   which chunk is generated first.
+//! - **Caverns**: open chambers where 3D noise sampled at world
+//!   coordinates passes a threshold, continuous by construction.
+//! - **Ore veins**: short random walks replacing stone, each ore in its own
+//!   depth range. Valuable ores sit deeper, and every ore block names a
+//!   loot table in `blocks.toml`, so mining yields grow with depth.
+//!
+//! Settings come from `data/schemas/world/underground.toml`; the shipped
+//! file is embedded in the binary and used unless another is given.
+
+use std::f64::consts::{PI, TAU};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
+use oroboros_core::embedded::EmbeddedData;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::biome::BiomeClassifier;
+use crate::chunk::{Block, Chunk, CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::noise::{ChunkRng, SimplexNoise, WorldSeed};
+
+/// The shipped underground settings.
+const SHIPPED_UNDERGROUND: &str = include_str!("../../../data/schemas/world/underground.toml");
+
+/// Seed purpose for worm tunnels.
+const WORM_SEED: u64 = 103;
+
+/// Seed purpose for ore veins.
+const ORE_SEED: u64 = 104;
+
+/// Why underground settings could not be loaded.
+#[derive(Error, Debug)]
+pub enum UndergroundConfigError {
+    /// Reading the file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid TOML or is missing fields.
+    #[error("invalid underground config: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// A minimum is above its maximum.
+    #[error("{feature} has an empty {field} range")]
+    EmptyRange {
+        /// `"worms"`, `"caverns"` or `"ore <block>"`.
+        feature: String,
+        /// `"y"`, `"length"` or `"radius"`.
+        field: &'static str,
+    },
+    /// A vein size is zero or wider than a chunk.
+    #[error("ore {block} has vein size {size}; must be 1 to 16")]
+    VeinSize {
+        /// Ore block ID.
+        block: BlockId,
+        /// Configured size.
+        size: u32,
+    },
+}
+
+/// Worm tunnel settings.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct WormSettings {
+    /// Average tunnels starting in each chunk.
+    pub per_chunk: f64,
+    /// Shortest tunnel, in steps of one block.
+    pub min_length: u32,
+    /// Longest tunnel, in steps of one block.
+    pub max_length: u32,
+    /// Narrowest tunnel radius.
+    pub min_radius: f64,
+    /// Widest tunnel radius.
+    pub max_radius: f64,
+    /// Lowest Y a tunnel runs at.
+    pub min_y: i32,
+    /// Highest Y a tunnel runs at.
+    pub max_y: i32,
+}
+
+/// Noise cavern settings.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CavernSettings {
+    /// Noise frequency (smaller is larger caverns).
+    pub frequency: f64,
+    /// Extra vertical frequency, flattening caverns into chambers.
+    pub vertical_scale: f64,
+    /// Noise value above which blocks are carved (-1 to 1).
+    pub threshold: f64,
+    /// Lowest carved Y.
+    pub min_y: i32,
+    /// Highest carved Y.
+    pub max_y: i32,
+}
+
+/// Cave settings.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CaveSettings {
+    /// Solid blocks left between caves and the surface.
+    pub surface_clearance: i32,
+    /// `[caves.worms]`.
+    pub worms: WormSettings,
+    /// `[caves.caverns]`.
+    pub caverns: CavernSettings,
+}
+
+/// One ore and where its veins go.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct OreDef {
+    /// Ore block ID.
+    pub block: BlockId,
+    /// Lowest Y a vein block is placed at.
+    pub min_y: i32,
+    /// Highest Y a vein block is placed at.
+    pub max_y: i32,
+    /// Blocks each vein tries to place (1-16).
+    pub vein_size: u32,
+    /// Average veins per chunk before the biome ore multiplier.
+    pub veins_per_chunk: f64,
+}
+
+/// Validated cave and ore settings.
+///
+/// ```rust,ignore
+/// let underground = Arc::new(UndergroundConfig::load("mods/underground.toml")?);
+/// let generator = ChunkGenerator::from_preset(seed, GeneratorPreset::Terrain).with_underground(underground);
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct UndergroundConfig {
+    /// `[caves]`.
+    pub caves: CaveSettings,
+    /// `[[ore]]`, in placement order.
+    #[serde(default, rename = "ore")]
+    pub ores: Vec<OreDef>,
+}
+
+impl UndergroundConfig {
+    /// Returns the settings from the shipped `underground.toml`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a shipped cave or ore has a min above its max (height,
+    /// worm length or radius) or an ore's vein size is outside 1 to 16.
+    #[must_use]
+    pub fn shipped() -> Arc<Self> {
+        static SHIPPED: EmbeddedData<UndergroundConfig, UndergroundConfigError> =
+            EmbeddedData::new("underground.toml", SHIPPED_UNDERGROUND, UndergroundConfig::from_toml);
+        SHIPPED.get()
+    }
+
+    /// Loads settings from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or the settings are
+    /// invalid (see [`validate`](Self::validate)).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, UndergroundConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses settings in the `underground.toml` format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text can't be parsed or the settings are invalid.
+    pub fn from_toml(text: &str) -> Result<Self, UndergroundConfigError> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that every range is non-empty and vein sizes fit in a chunk.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first invalid setting.
+    pub fn validate(&self) -> Result<(), UndergroundConfigError> {
+        let empty = |feature: &str, field| UndergroundConfigError::EmptyRange {
+            feature: feature.to_owned(),
+            field,
+        };
+        let worms = &self.caves.worms;
+        if worms.min_y > worms.max_y {
+            return Err(empty("worms", "y"));
+        }
+        if worms.min_length > worms.max_length {
+            return Err(empty("worms", "length"));
+        }
+        if worms.min_radius > worms.max_radius {
+            return Err(empty("worms", "radius"));
+        }
+        if self.caves.caverns.min_y > self.caves.caverns.max_y {
+            return Err(empty("caverns", "y"));
+        }
+        for ore in &self.ores {
+            if ore.min_y > ore.max_y {
+                return Err(empty(&format!("ore {}", ore.block), "y"));
+            }
+            if ore.vein_size == 0 || ore.vein_size > CHUNK_SIZE as u32 {
+                return Err(UndergroundConfigError::VeinSize {
+                    block: ore.block,
+                    size: ore.vein_size,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every ore block is defined and drops loot.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first ore that isn't in `blocks` or has no loot
+    /// table.
+    pub fn check_blocks(&self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        blocks.check_ids("underground ores", self.ores.iter().map(|ore| ore.block))?;
+        match self.ores.iter().filter_map(|ore| blocks.get(ore.block)).find(|def| def.loot_table.is_none()) {
+            Some(def) => Err(BlockConfigError::NoLootTable {
+                referrer: "underground ores".to_owned(),
+                block: def.name.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// How many chunks away from its start a worm tunnel can carve.
+    #[must_use]
+    pub fn worm_reach_chunks(&self) -> i32 {
+        let worms = &self.caves.worms;
+        ((f64::from(worms.max_length) + worms.max_radius) / CHUNK_SIZE as f64).ceil() as i32
+    }
+}
+
+/// Carves a block if it is stone-like and deep enough below the surface.
+#[inline]
+fn carve(chunk: &mut Chunk, local_x: usize, y: i32, local_z: usize, clearance: i32) {
+    if y < 1 || y > i32::from(chunk.get_height(local_x, local_z)) - clearance {
+        return;
+    }
+    let y = y as usize;
+    let block = chunk.get_block(local_x, y, local_z);
+    if block.is_air() || block.id == Block::WATER.id || block.id == Block::BEDROCK.id {
+        return;
+    }
+    chunk.set_block(local_x, y, local_z, Block::AIR);
+}
+
+/// Carves the part of a sphere (in world coordinates) inside `chunk`.
+fn carve_sphere(chunk: &mut Chunk, center: [f64; 3], radius: f64, clearance: i32) {
+    let world_x = chunk.coord.world_x();
+    let world_z = chunk.coord.world_z();
+    let size = CHUNK_SIZE as i32;
+
+    let min_x = ((center[0] - radius).floor() as i32 - world_x).max(0);
+    let max_x = ((center[0] + radius).ceil() as i32 - world_x).min(size - 1);
+    let min_z = ((center[2] - radius).floor() as i32 - world_z).max(0);
+    let max_z = ((center[2] + radius).ceil() as i32 - world_z).min(size - 1);
+    if min_x > max_x || min_z > max_z {
+        return;
+    }
+    let min_y = ((center[1] - radius).floor() as i32).max(1);
+    let max_y = ((center[1] + radius).ceil() as i32).min(CHUNK_HEIGHT as i32 - 1);
+
+    let radius_sq = radius * radius;
+    for local_z in min_z..=max_z {
+        let dz = f64::from(world_z + local_z) + 0.5 - center[2];
+        for local_x in min_x..=max_x {
+            let dx = f64::from(world_x + local_x) + 0.5 - center[0];
+            for y in min_y..=max_y {
+                let dy = f64::from(y) + 0.5 - center[1];
+                if dx * dx + dy * dy + dz * dz <= radius_sq {
+                    carve(chunk, local_x as usize, y, local_z as usize, clearance);
+                }
+            }
+        }
+    }
+}
+
+/// Carves the worm tunnels that reach `chunk`.
+pub(crate) fn carve_worms(config: &UndergroundConfig, seed: WorldSeed, chunk: &mut Chunk) {
+    let worms = &config.caves.worms;
+    let clearance = config.caves.surface_clearance;
+    let reach = config.worm_reach_chunks();
+    let size = CHUNK_SIZE as f64;
+
+    for origin_z in chunk.coord.z - reach..=chunk.coord.z + reach {
+        for origin_x in chunk.coord.x - reach..=chunk.coord.x + reach {
+            let mut rng = ChunkRng::new(seed.derive(WORM_SEED), origin_x, origin_z);
+            for _ in 0..rng.round_count(worms.per_chunk) {
+                let mut position = [
+                    f64::from(origin_x) * size + rng.range_f64(0.0, size),
+                    rng.range_f64(f64::from(worms.min_y), f64::from(worms.max_y) + 1.0),
+                    f64::from(origin_z) * size + rng.range_f64(0.0, size),
+                ];
+                let length = rng.range_i32(worms.min_length as i32, worms.max_length as i32).max(1);
+                let width = rng.range_f64(worms.min_radius, worms.max_radius);
+                let mut yaw = rng.range_f64(0.0, TAU);
+                let mut pitch = rng.range_f64(-0.25, 0.25);
+                let mut yaw_turn = 0.0;
+                let mut pitch_turn = 0.0;
+
+                for step in 0..length {
+                    // Narrow at both ends, widest in the middle
+                    let along = f64::from(step) / f64::from(length);
+                    let radius = width * (0.6 + 0.4 * (PI * along).sin());
+                    carve_sphere(chunk, position, radius, clearance);
+
+                    position[0] += yaw.cos() * pitch.cos();
+                    position[1] = (position[1] + pitch.sin())
+                        .clamp(f64::from(worms.min_y), f64::from(worms.max_y));
+                    position[2] += yaw.sin() * pitch.cos();
+
+                    yaw += yaw_turn * 0.1;
+                    pitch = pitch * 0.9 + pitch_turn * 0.1;
+                    yaw_turn = yaw_turn * 0.9 + rng.range_f64(-1.0, 1.0);
+                    pitch_turn = pitch_turn * 0.75 + rng.range_f64(-1.0, 1.0);
+                }
+            }
+        }
+    }
+}
+
+/// Carves noise caverns in `chunk`.
+pub(crate) fn carve_caverns(config: &UndergroundConfig, noise: &SimplexNoise, chunk: &mut Chunk) {
+    let caverns = &config.caves.caverns;
+    let clearance = config.caves.surface_clearance;
+    let world_x = chunk.coord.world_x();
+    let world_z = chunk.coord.world_z();
+
+    for local_z in 0..CHUNK_SIZE {
+        for local_x in 0..CHUNK_SIZE {
+            let top = caverns.max_y.min(i32::from(chunk.get_height(local_x, local_z)) - clearance);
+            let x = f64::from(world_x + local_x as i32) * caverns.frequency;
+            let z = f64::from(world_z + local_z as i32) * caverns.frequency;
+            for y in caverns.min_y.max(1)..=top {
+                let value = noise.sample_3d(x, f64::from(y) * caverns.frequency * caverns.vertical_scale, z);
+                if value > caverns.threshold {
+                    carve(chunk, local_x, y, local_z, clearance);
+                }
+            }
+        }
+    }
+}
+
+/// Places the ore veins that reach `chunk`, replacing stone.
+///
+/// Vein counts are scaled by the ore multiplier of the biome at the centre
+/// of the chunk the vein starts in.
+pub(crate) fn place_ores(config: &UndergroundConfig, seed: WorldSeed, classifier: &BiomeClassifier, chunk: &mut Chunk) {
+    let world_x = chunk.coord.world_x();
+    let world_z = chunk.coord.world_z();
+    let size = CHUNK_SIZE as i32;
+
+    // Vein sizes are capped at a chunk, so neighbours are far enough
+    for origin_z in chunk.coord.z - 1..=chunk.coord.z + 1 {
+        for origin_x in chunk.coord.x - 1..=chunk.coord.x + 1 {
+            let mut rng = ChunkRng::new(seed.derive(ORE_SEED), origin_x, origin_z);
+            let center_x = origin_x * size + size / 2;
+            let center_z = origin_z * size + size / 2;
+            let biome = classifier.classify(f64::from(center_x), f64::from(center_z));
+            let multiplier = f64::from(classifier.registry().ore_multiplier(biome));
+
+            for ore in &config.ores {
+                let ore_block = Block::new(ore.block);
+                for _ in 0..rng.round_count(ore.veins_per_chunk * multiplier) {
+                    let mut x = origin_x * size + rng.range_i32(0, size - 1);
+                    let mut y = rng.range_i32(ore.min_y, ore.max_y);
+                    let mut z = origin_z * size + rng.range_i32(0, size - 1);
+
+                    for _ in 0..ore.vein_size {
+                        let (local_x, local_z) = (x - world_x, z - world_z);
+                        if (0..size).contains(&local_x)
+                            && (0..size).contains(&local_z)
+                            && (ore.min_y..=ore.max_y).contains(&y)
+                            && (0..CHUNK_HEIGHT as i32).contains(&y)
+                            && chunk.get_block(local_x as usize, y as usize, local_z as usize).id == Block::STONE.id
+                        {
+                            chunk.set_block(local_x as usize, y as usize, local_z as usize, ore_block);
+                        }
+
+                        let step = if rng.next_u64() & 1 == 0 { 1 } else { -1 };
+                        match rng.next_u64() % 3 {
+                            0 => x += step,
+                            1 => y += step,
+                            _ => z += step,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::{ChunkCoord, ChunkGenerator};
+    use crate::generator::GeneratorPreset;
+
+    /// Air blocks below the clearance line, which terrain alone never has.
+    fn cave_blocks(chunk: &Chunk, clearance: i32) -> Vec<(usize, usize, usize)> {
+        let mut out = Vec::new();
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let top = i32::from(chunk.get_height(x, z)) - clearance;
+                for y in 1..top.max(1) as usize {
+                    if chunk.get_block(x, y, z).is_air() {
+                        out.push((x, y, z));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn config_with(edit: impl FnOnce(&mut UndergroundConfig)) -> Arc<UndergroundConfig> {
+        let mut config = (*UndergroundConfig::shipped()).clone();
+        edit(&mut config);
+        config.validate().unwrap();
+        Arc::new(config)
+    }
+
+    #[test]
+    fn test_caves_stay_underground() {
+        let generator = ChunkGenerator::from_preset(WorldSeed::new(7), GeneratorPreset::Terrain);
+        let clearance = UndergroundConfig::shipped().caves.surface_clearance;
+
+        let mut caves = 0;
+        for cz in -2..2 {
+            for cx in -2..2 {
+                let chunk = generator.generate(ChunkCoord::new(cx, cz));
+                caves += cave_blocks(&chunk, clearance).len();
+                for z in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        assert_eq!(chunk.get_block(x, 0, z).id, Block::BEDROCK.id);
+                        let (block_x, block_z) = (chunk.coord.world_x() + x as i32, chunk.coord.world_z() + z as i32);
+                        let height = generator.terrain_height(block_x, block_z) as usize;
+                        for y in height + 1 - clearance as usize..=height {
+                            assert!(!chunk.get_block(x, y, z).is_air(), "cave within clearance at ({x}, {y}, {z})");
+                        }
+                    }
+                }
+            }
+        }
+        assert!(caves > 0, "terrain should have caves");
+    }
+
+    #[test]
+    fn test_worm_tunnels_cross_chunk_borders() {
+        let config = config_with(|config| {
+            config.caves.caverns.threshold = 2.0;
+            config.caves.worms.per_chunk = 3.0;
+        });
+        let generator = ChunkGenerator::from_preset(WorldSeed::new(3), GeneratorPreset::Terrain).with_underground(config);
+
+        let mut crossings = 0;
+        for cz in 0..4 {
+            let west = generator.generate(ChunkCoord::new(0, cz));
+            let east = generator.generate(ChunkCoord::new(1, cz));
+            for z in 0..CHUNK_SIZE {
+                let top = i32::from(west.get_height(15, z).min(east.get_height(0, z))) - 5;
+                for y in 1..top.max(1) as usize {
+                    if west.get_block(15, y, z).is_air() && east.get_block(0, y, z).is_air() {
+                        crossings += 1;
+                    }
+                }
+            }
+        }
+        assert!(crossings > 0, "tunnels should continue across the border");
+
+        // Generating a neighbour first changes nothing
+        let alone = generator.generate(ChunkCoord::new(1, 2));
+        let _ = generator.generate(ChunkCoord::new(2, 2));
+        assert_eq!(
+            cave_blocks(&alone, 5),
+            cave_blocks(&generator.generate(ChunkCoord::new(1, 2)), 5)
+        );
+    }
+
+    #[test]
+    fn test_ores_follow_depth_ranges() {
+        let generator = ChunkGenerator::from_preset(WorldSeed::new(11), GeneratorPreset::Terrain);
+        let config = UndergroundConfig::shipped();
+        let range = |id: u16| config.ores.iter().find(|ore| ore.block == id).map(|ore| ore.min_y..=ore.max_y);
+
+        let mut counts = std::collections::HashMap::<u16, usize>::new();
+        let mut deep_rare = 0;
+        let mut shallow_rare = 0;
+        for cz in -3..3 {
+            for cx in -3..3 {
+                let chunk = generator.generate(ChunkCoord::new(cx, cz));
+                for y in 0..80 {
+                    for z in 0..CHUNK_SIZE {
+                        for x in 0..CHUNK_SIZE {
+                            let id = chunk.get_block(x, y, z).id;
+                            if let Some(range) = range(id) {
+                                assert!(range.contains(&(y as i32)), "ore {id} at y={y}");
+                                *counts.entry(id).or_default() += 1;
+                                if id == Block::DIAMOND_ORE.id || id == Block::GOLD_ORE.id {
+                                    if y < 20 { deep_rare += 1 } else { shallow_rare += 1 }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(counts[&Block::IRON_ORE.id] > counts[&Block::GOLD_ORE.id]);
+        assert!(counts[&Block::GOLD_ORE.id] > counts.get(&Block::DIAMOND_ORE.id).copied().unwrap_or(0));
+        assert!(deep_rare > shallow_rare, "valuable ores should cluster deep: {deep_rare} vs {shallow_rare}");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let shipped = UndergroundConfig::shipped();
+        shipped.check_blocks(&BlockRegistry::shipped()).unwrap();
+
+        let mut config = (*shipped).clone();
+        config.ores[0].vein_size = 17;
+        assert!(matches!(config.validate(), Err(UndergroundConfigError::VeinSize { size: 17, .. })));
+
+        let mut config = (*shipped).clone();
+        config.caves.worms.min_radius = 9.0;
+        assert!(matches!(config.validate(), Err(UndergroundConfigError::EmptyRange { field: "radius", .. })));
+
+        let text = SHIPPED_UNDERGROUND.replace("min_y = 5\nmax_y = 36", "min_y = 36\nmax_y = 5");
+        assert!(matches!(
+            UndergroundConfig::from_toml(&text),
+            Err(UndergroundConfigError::EmptyRange { ref feature, field: "y" }) if feature == "ore 21"
+        ));
+
+        // Grass has no loot table; block 6 doesn't exist
+        let mut config = (*shipped).clone();
+        config.ores[0].block = Block::GRASS.id;
+        assert!(matches!(config.check_blocks(&BlockRegistry::shipped()), Err(BlockConfigError::NoLootTable { .. })));
+        config.ores[0].block = 6;
+        assert!(matches!(config.check_blocks(&BlockRegistry::shipped()), Err(BlockConfigError::UnknownBlock { id: 6, .. })));
+    }
+}
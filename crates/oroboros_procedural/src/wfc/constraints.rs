@@ -0,0 +1,158 @@
+//! # Constraints
+//!
+//! Restrictions on a volume before solving: sockets allowed at its edges,
+//! tiles pinned to cells and tiles banned everywhere.
+
+use serde::Deserialize;
+
+use super::rules::Direction;
+use super::solver::{Domains, WfcError};
+use super::tiles::{TileId, Tileset};
+
+/// Sockets allowed on tile faces at each edge of a volume.
+///
+/// An empty list allows any socket.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct Boundary {
+    /// East edge.
+    #[serde(rename = "+x", default)]
+    pub pos_x: Vec<String>,
+    /// West edge.
+    #[serde(rename = "-x", default)]
+    pub neg_x: Vec<String>,
+    /// Top edge.
+    #[serde(rename = "+y", default)]
+    pub pos_y: Vec<String>,
+    /// Bottom edge.
+    #[serde(rename = "-y", default)]
+    pub neg_y: Vec<String>,
+    /// South edge.
+    #[serde(rename = "+z", default)]
+    pub pos_z: Vec<String>,
+    /// North edge.
+    #[serde(rename = "-z", default)]
+    pub neg_z: Vec<String>,
+}
+
+impl Boundary {
+    /// Returns the sockets allowed at an edge.
+    #[must_use]
+    pub fn allowed(&self, direction: Direction) -> &[String] {
+        match direction {
+            Direction::PosX => &self.pos_x,
+            Direction::NegX => &self.neg_x,
+            Direction::PosY => &self.pos_y,
+            Direction::NegY => &self.neg_y,
+            Direction::PosZ => &self.pos_z,
+            Direction::NegZ => &self.neg_z,
+        }
+    }
+
+    fn allowed_mut(&mut self, direction: Direction) -> &mut Vec<String> {
+        match direction {
+            Direction::PosX => &mut self.pos_x,
+            Direction::NegX => &mut self.neg_x,
+            Direction::PosY => &mut self.pos_y,
+            Direction::NegY => &mut self.neg_y,
+            Direction::PosZ => &mut self.pos_z,
+            Direction::NegZ => &mut self.neg_z,
+        }
+    }
+}
+
+/// What a solved volume must satisfy besides adjacency.
+///
+/// ```rust,ignore
+/// // A dungeon room: entrance on the west wall, no loot tiles
+/// let constraints = Constraints::for_tileset(&tileset)
+///     .with_fixed([0, 0, 2], tileset.tile_id("door@180").unwrap())
+///     .with_forbidden(tileset.tile_id("loot").unwrap());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    boundary: Boundary,
+    fixed: Vec<([usize; 3], TileId)>,
+    forbidden: Vec<TileId>,
+}
+
+impl Constraints {
+    /// Creates constraints with only an edge rule.
+    #[must_use]
+    pub fn new(boundary: Boundary) -> Self {
+        Self {
+            boundary,
+            ..Self::default()
+        }
+    }
+
+    /// Creates constraints with the tileset's default edges.
+    #[must_use]
+    pub fn for_tileset(tileset: &Tileset) -> Self {
+        Self::new(tileset.boundary().clone())
+    }
+
+    /// Replaces the sockets allowed at one edge (empty allows any).
+    #[must_use]
+    pub fn with_boundary<S: Into<String>>(mut self, edge: Direction, sockets: impl IntoIterator<Item = S>) -> Self {
+        *self.boundary.allowed_mut(edge) = sockets.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Pins a tile to a cell.
+    #[must_use]
+    pub fn with_fixed(mut self, cell: [usize; 3], tile: TileId) -> Self {
+        self.fixed.push((cell, tile));
+        self
+    }
+
+    /// Bans a tile from every cell.
+    #[must_use]
+    pub fn with_forbidden(mut self, tile: TileId) -> Self {
+        self.forbidden.push(tile);
+        self
+    }
+
+    /// Returns the edge rule.
+    #[must_use]
+    pub const fn boundary(&self) -> &Boundary {
+        &self.boundary
+    }
+
+    /// Returns the pinned cells.
+    #[must_use]
+    pub fn fixed(&self) -> &[([usize; 3], TileId)] {
+        &self.fixed
+    }
+
+    /// Returns the banned tiles.
+    #[must_use]
+    pub fn forbidden(&self) -> &[TileId] {
+        &self.forbidden
+    }
+
+    /// Removes every tile the constraints rule out from `domains`.
+    pub(crate) fn apply(&self, tileset: &Tileset, domains: &mut Domains) -> Result<(), WfcError> {
+        let size = domains.size();
+        for cell in 0..domains.len() {
+            domains.retain(cell, |tile| !self.forbidden.contains(&tile));
+            for edge in Direction::ALL {
+                let allowed = self.boundary.allowed(edge);
+                if allowed.is_empty() || domains.neighbour(cell, edge).is_some() {
+                    continue;
+                }
+                domains.retain(cell, |tile| {
+                    let socket = tileset.tile(tile).sockets.get(edge);
+                    allowed.iter().any(|allowed| allowed == socket)
+                });
+            }
+        }
+
+        for &(cell, tile) in &self.fixed {
+            let Some(index) = domains.index(cell) else {
+                return Err(WfcError::OutOfBounds { cell, size });
+            };
+            domains.retain(index, |other| other == tile);
+        }
+        Ok(())
+    }
+}
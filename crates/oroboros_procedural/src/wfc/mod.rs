@@ -0,0 +1,194 @@
+//! # Wave Function Collapse
+//!
+//! Fills a bounding volume with modular voxel tiles so that every pair of
+//! touching faces matches.
+//!
+//! ```text
+//! tilesets/*.toml ──► Tileset (tiles + turned variants) ──► AdjacencyRules
+//!                                                              │
+//! Constraints (edges, pinned, banned) ──► WfcSolver::solve(seed) ──► WfcGrid
+//!                                                              │
+//!                              WfcPass writes each chunk's slice of the grid
+//! ```
+//!
+//! - [`tiles`]: tiles and tilesets loaded from data
+//! - [`rules`]: socket-based adjacency compiled to bitsets
+//! - [`constraints`]: edge sockets, pinned cells and banned tiles
+//! - [`solver`]: seeded collapse with bounded backtracking
+
+pub mod constraints;
+pub mod rules;
+pub mod solver;
+pub mod tiles;
+
+pub use constraints::{Boundary, Constraints};
+pub use rules::{AdjacencyRules, Direction, Sockets};
+pub use solver::{WfcError, WfcGrid, WfcSolver, DEFAULT_BACKTRACK_BUDGET};
+pub use tiles::{Tile, TileDef, TileId, Tileset, TilesetError, TilesetFile, TilesetHeader};
+
+use std::sync::Arc;
+
+use crate::chunk::{Block, Chunk, ChunkGenerator, CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::generator::{GenerationPass, PerSeed, Stage};
+use crate::noise::WorldSeed;
+
+/// Seed purpose for WFC volumes.
+const WFC_SEED: u64 = 106;
+
+/// Fills a world-space volume with a tileset.
+///
+/// Runs in [`Stage::Decoration`] and replaces every block in the volume,
+/// air included. If the volume can't be solved the chunk is left as it
+/// is; call [`solution`](Self::solution) to see why.
+///
+/// ```rust,ignore
+/// // One Undercity block, 32×8×32, on the street at the origin
+/// let block = WfcPass::new(Tileset::shipped_undercity(), [0, 4, 0], [8, 2, 8]);
+/// let generator = ChunkGenerator::with_passes(seed, "undercity_wfc", vec![Box::new(FlatTerrain), Box::new(block)]);
+/// ```
+pub struct WfcPass {
+    tileset: Arc<Tileset>,
+    origin: [i32; 3],
+    size: [usize; 3],
+    constraints: Constraints,
+    budget: u32,
+    solved: PerSeed<Result<Arc<WfcGrid>, WfcError>>,
+}
+
+impl WfcPass {
+    /// Creates a pass filling `size` cells from the block at `origin`.
+    #[must_use]
+    pub fn new(tileset: Arc<Tileset>, origin: [i32; 3], size: [usize; 3]) -> Self {
+        let constraints = Constraints::for_tileset(&tileset);
+        Self {
+            tileset,
+            origin,
+            size,
+            constraints,
+            budget: DEFAULT_BACKTRACK_BUDGET,
+            solved: PerSeed::new(),
+        }
+    }
+
+    /// Replaces the constraints.
+    #[must_use]
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Sets how many choices may be undone before giving up.
+    #[must_use]
+    pub const fn with_backtrack_budget(mut self, budget: u32) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Returns the lowest corner of the volume, in world blocks.
+    #[must_use]
+    pub const fn origin(&self) -> [i32; 3] {
+        self.origin
+    }
+
+    /// Returns the volume size, in blocks.
+    #[must_use]
+    pub fn block_size(&self) -> [usize; 3] {
+        self.size.map(|cells| cells * self.tileset.tile_size())
+    }
+
+    /// Solves the volume for a world, reusing the last result if the seed
+    /// is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`WfcSolver::solve`] does.
+    pub fn solution(&self, world_seed: WorldSeed) -> Result<Arc<WfcGrid>, WfcError> {
+        self.solved.get(world_seed, WFC_SEED, &self.origin, |seed| {
+            WfcSolver::new(&self.tileset, self.size)
+                .with_constraints(self.constraints.clone())
+                .with_backtrack_budget(self.budget)
+                .solve(seed)
+                .map(Arc::new)
+        })
+    }
+}
+
+impl GenerationPass for WfcPass {
+    fn stage(&self) -> Stage {
+        Stage::Decoration
+    }
+
+    fn apply(&self, generator: &ChunkGenerator, chunk: &mut Chunk) {
+        let [size_x, size_y, size_z] = self.block_size().map(|size| size as i32);
+        let [origin_x, origin_y, origin_z] = self.origin;
+        let (world_x, world_z) = (chunk.coord.world_x(), chunk.coord.world_z());
+
+        // Overlap of the volume with the chunk, in world blocks
+        let (min_x, max_x) = (origin_x.max(world_x), (origin_x + size_x).min(world_x + CHUNK_SIZE as i32));
+        let (min_z, max_z) = (origin_z.max(world_z), (origin_z + size_z).min(world_z + CHUNK_SIZE as i32));
+        let (min_y, max_y) = (origin_y.max(0), (origin_y + size_y).min(CHUNK_HEIGHT as i32));
+        if min_x >= max_x || min_z >= max_z || min_y >= max_y {
+            return;
+        }
+        let Ok(grid) = self.solution(generator.seed()) else {
+            return;
+        };
+
+        for y in min_y..max_y {
+            for z in min_z..max_z {
+                for x in min_x..max_x {
+                    let inside = [x - origin_x, y - origin_y, z - origin_z].map(|offset| offset as usize);
+                    let block = Block::new(grid.block(&self.tileset, inside));
+                    chunk.set_block((x - world_x) as usize, y as usize, (z - world_z) as usize, block);
+                }
+            }
+        }
+        chunk.rebuild_height_map();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkCoord;
+    use crate::generator::FlatTerrain;
+
+    fn generator(seed: u64, origin: [i32; 3]) -> ChunkGenerator {
+        let pass = WfcPass::new(Tileset::shipped_undercity(), origin, [8, 2, 8]);
+        ChunkGenerator::with_passes(WorldSeed::new(seed), "wfc_test", vec![Box::new(FlatTerrain), Box::new(pass)])
+    }
+
+    #[test]
+    fn test_pass_writes_solution_across_chunks() {
+        // 32×8×32 volume straddling the four chunks around the origin
+        let origin = [-16, 10, -16];
+        let tileset = Tileset::shipped_undercity();
+        let grid = WfcPass::new(tileset.clone(), origin, [8, 2, 8]).solution(WorldSeed::new(4)).unwrap();
+
+        let forward = generator(4, origin);
+        let backward = generator(4, origin);
+        let coords = [ChunkCoord::new(-1, -1), ChunkCoord::new(0, -1), ChunkCoord::new(-1, 0), ChunkCoord::new(0, 0)];
+        let first: Vec<Chunk> = coords.iter().map(|&coord| forward.generate(coord)).collect();
+        let second: Vec<Chunk> = coords.iter().rev().map(|&coord| backward.generate(coord)).collect();
+
+        for (chunk, other) in first.iter().zip(second.iter().rev()) {
+            for y in 0..8 {
+                for z in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        let world = [chunk.coord.world_x() + x as i32, chunk.coord.world_z() + z as i32];
+                        let inside = [world[0] - origin[0], y, world[1] - origin[2]].map(|offset| offset as usize);
+                        let expected = grid.block(&tileset, inside);
+                        assert_eq!(chunk.get_block(x, 10 + y as usize, z).id, expected);
+                        assert_eq!(other.get_block(x, 10 + y as usize, z).id, expected);
+                    }
+                }
+            }
+        }
+
+        // Outside the volume the floor is untouched
+        let flat = ChunkGenerator::from_preset(WorldSeed::new(4), crate::generator::GeneratorPreset::Flat);
+        let far = ChunkCoord::new(3, 3);
+        let (chunk, plain) = (forward.generate(far), flat.generate(far));
+        assert!((0..CHUNK_HEIGHT).all(|y| chunk.get_block(5, y, 5) == plain.get_block(5, y, 5)));
+    }
+}
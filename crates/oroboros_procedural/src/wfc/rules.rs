@@ -0,0 +1,212 @@
+//! # Adjacency Rules
+//!
+//! Which tiles may sit next to each other, compiled from face sockets into
+//! bitsets the solver intersects during propagation.
+
+use serde::Deserialize;
+
+use super::tiles::{Tile, TileId};
+
+/// A face of a tile, or the step from a cell to its neighbour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// East.
+    PosX,
+    /// West.
+    NegX,
+    /// Up.
+    PosY,
+    /// Down.
+    NegY,
+    /// South.
+    PosZ,
+    /// North.
+    NegZ,
+}
+
+impl Direction {
+    /// Every direction.
+    pub const ALL: [Self; 6] = [Self::PosX, Self::NegX, Self::PosY, Self::NegY, Self::PosZ, Self::NegZ];
+
+    /// Returns the direction pointing back.
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::PosX => Self::NegX,
+            Self::NegX => Self::PosX,
+            Self::PosY => Self::NegY,
+            Self::NegY => Self::PosY,
+            Self::PosZ => Self::NegZ,
+            Self::NegZ => Self::PosZ,
+        }
+    }
+
+    /// Returns the cell step in this direction.
+    #[must_use]
+    pub const fn offset(self) -> [i32; 3] {
+        match self {
+            Self::PosX => [1, 0, 0],
+            Self::NegX => [-1, 0, 0],
+            Self::PosY => [0, 1, 0],
+            Self::NegY => [0, -1, 0],
+            Self::PosZ => [0, 0, 1],
+            Self::NegZ => [0, 0, -1],
+        }
+    }
+
+    /// Returns the TOML key (`"+x"`, `"-y"`, ...).
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::PosX => "+x",
+            Self::NegX => "-x",
+            Self::PosY => "+y",
+            Self::NegY => "-y",
+            Self::PosZ => "+z",
+            Self::NegZ => "-z",
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Socket names on the six faces of a tile.
+///
+/// Two tiles may touch when the sockets on their touching faces are equal.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Sockets {
+    /// East face.
+    #[serde(rename = "+x")]
+    pub pos_x: String,
+    /// West face.
+    #[serde(rename = "-x")]
+    pub neg_x: String,
+    /// Top face.
+    #[serde(rename = "+y")]
+    pub pos_y: String,
+    /// Bottom face.
+    #[serde(rename = "-y")]
+    pub neg_y: String,
+    /// South face.
+    #[serde(rename = "+z")]
+    pub pos_z: String,
+    /// North face.
+    #[serde(rename = "-z")]
+    pub neg_z: String,
+}
+
+impl Sockets {
+    /// Returns the socket on a face.
+    #[must_use]
+    pub fn get(&self, direction: Direction) -> &str {
+        match direction {
+            Direction::PosX => &self.pos_x,
+            Direction::NegX => &self.neg_x,
+            Direction::PosY => &self.pos_y,
+            Direction::NegY => &self.neg_y,
+            Direction::PosZ => &self.pos_z,
+            Direction::NegZ => &self.neg_z,
+        }
+    }
+
+    /// Returns the sockets of the tile turned 90 degrees about Y
+    /// (east face to south).
+    #[must_use]
+    pub fn rotated(&self) -> Self {
+        Self {
+            pos_x: self.neg_z.clone(),
+            neg_x: self.pos_z.clone(),
+            pos_y: self.pos_y.clone(),
+            neg_y: self.neg_y.clone(),
+            pos_z: self.pos_x.clone(),
+            neg_z: self.neg_x.clone(),
+        }
+    }
+}
+
+/// Compiled adjacency: for each tile and direction, the set of tiles
+/// allowed in the neighbouring cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdjacencyRules {
+    tile_count: usize,
+    /// Bitset words per tile set.
+    words: usize,
+    /// `[tile][direction][word]`.
+    allowed: Vec<u64>,
+}
+
+impl AdjacencyRules {
+    /// Compiles rules from tile sockets.
+    #[must_use]
+    pub fn from_tiles(tiles: &[Tile]) -> Self {
+        let tile_count = tiles.len();
+        let words = tile_count.div_ceil(64);
+        let mut allowed = vec![0; tile_count * Direction::ALL.len() * words];
+
+        for (from, tile) in tiles.iter().enumerate() {
+            for direction in Direction::ALL {
+                let socket = tile.sockets.get(direction);
+                let base = (from * Direction::ALL.len() + direction.index()) * words;
+                for (to, other) in tiles.iter().enumerate() {
+                    if other.sockets.get(direction.opposite()) == socket {
+                        allowed[base + to / 64] |= 1 << (to % 64);
+                    }
+                }
+            }
+        }
+        Self { tile_count, words, allowed }
+    }
+
+    /// Returns the number of tiles.
+    #[must_use]
+    pub const fn tile_count(&self) -> usize {
+        self.tile_count
+    }
+
+    /// Returns true if `to` may sit in `direction` from `from`.
+    #[must_use]
+    pub fn allows(&self, from: TileId, direction: Direction, to: TileId) -> bool {
+        let to = usize::from(to);
+        self.allowed(from, direction)[to / 64] & (1 << (to % 64)) != 0
+    }
+
+    /// Bitset words per tile set.
+    pub(crate) const fn words(&self) -> usize {
+        self.words
+    }
+
+    /// Tiles allowed in `direction` from `from`, as bitset words.
+    pub(crate) fn allowed(&self, from: TileId, direction: Direction) -> &[u64] {
+        let base = (usize::from(from) * Direction::ALL.len() + direction.index()) * self.words;
+        &self.allowed[base..base + self.words]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wfc::Tileset;
+
+    #[test]
+    fn test_rules_are_symmetric() {
+        let tileset = Tileset::shipped_undercity();
+        let rules = tileset.rules();
+        let count = rules.tile_count() as TileId;
+
+        for from in 0..count {
+            for to in 0..count {
+                for direction in Direction::ALL {
+                    assert_eq!(rules.allows(from, direction, to), rules.allows(to, direction.opposite(), from));
+                }
+            }
+        }
+
+        let plaza = tileset.tile_id("plaza").unwrap();
+        let wall = tileset.tile_id("wall").unwrap();
+        assert!(rules.allows(plaza, Direction::PosX, plaza));
+        assert!(!rules.allows(plaza, Direction::PosX, wall), "wall ends must not open onto plazas");
+        assert!(rules.allows(wall, Direction::PosX, wall));
+    }
+}
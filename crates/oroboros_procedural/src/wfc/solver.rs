@@ -0,0 +1,496 @@
+//! # Solver
+//!
+//! Wave Function Collapse over a 3D grid of cells.
+//!
+//! ```text
+//! every cell starts with every tile ──► constraints remove tiles
+//!        ┌──────────────────────────────────────┘
+//!        ▼
+//! collapse the cell with the lowest entropy to one weighted-random tile
+//!        ▼
+//! propagate: shrink neighbours to tiles their neighbours allow (AC-3)
+//!        ▼
+//! a cell ran out of tiles? ── yes ──► undo the last choice, ban that tile
+//!        │ no                         there, propagate again (bounded)
+//!        ▼
+//! repeat until every cell has one tile
+//! ```
+//!
+//! All choices come from a [`ChunkRng`] seeded by the caller, so the same
+//! seed, tileset and constraints always give the same grid.
+
+use oroboros_core::blocks::BlockId;
+use thiserror::Error;
+
+use super::constraints::Constraints;
+use super::rules::{AdjacencyRules, Direction};
+use super::tiles::{TileId, Tileset};
+use crate::noise::{ChunkRng, WorldSeed};
+
+/// Backtracks allowed by default before the solver gives up.
+pub const DEFAULT_BACKTRACK_BUDGET: u32 = 1000;
+
+/// Why a volume could not be solved.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WfcError {
+    /// No grid satisfies the tileset and constraints.
+    #[error("constraints leave no valid layout")]
+    Unsatisfiable,
+    /// The search undid more choices than its budget allows.
+    #[error("gave up after {0} backtracks")]
+    BudgetExhausted(u32),
+    /// A pinned cell lies outside the volume.
+    #[error("cell {cell:?} is outside the {size:?} volume")]
+    OutOfBounds {
+        /// The pinned cell.
+        cell: [usize; 3],
+        /// Volume size in cells.
+        size: [usize; 3],
+    },
+}
+
+/// Tiles still possible in each cell, as bitsets.
+#[derive(Clone, Debug)]
+pub(crate) struct Domains {
+    size: [usize; 3],
+    words: usize,
+    bits: Vec<u64>,
+    /// Every word changed so far, with its old value, so choices can be
+    /// undone without copying the whole volume.
+    trail: Vec<(usize, u64)>,
+}
+
+impl Domains {
+    /// Every tile possible in every cell.
+    fn full(size: [usize; 3], tile_count: usize, words: usize) -> Self {
+        let cells = size[0] * size[1] * size[2];
+        let mut all = vec![0; words];
+        for tile in 0..tile_count {
+            all[tile / 64] |= 1 << (tile % 64);
+        }
+        Self {
+            size,
+            words,
+            bits: all.repeat(cells),
+            trail: Vec::new(),
+        }
+    }
+
+    /// Volume size in cells.
+    pub(crate) const fn size(&self) -> [usize; 3] {
+        self.size
+    }
+
+    /// Number of cells.
+    pub(crate) fn len(&self) -> usize {
+        self.bits.len() / self.words
+    }
+
+    /// Index of a cell, if it is inside the volume.
+    pub(crate) fn index(&self, [x, y, z]: [usize; 3]) -> Option<usize> {
+        let [size_x, size_y, size_z] = self.size;
+        (x < size_x && y < size_y && z < size_z).then_some((y * size_z + z) * size_x + x)
+    }
+
+    /// Index of the cell next to `cell` in `direction`, if inside.
+    pub(crate) fn neighbour(&self, cell: usize, direction: Direction) -> Option<usize> {
+        let [size_x, _, size_z] = self.size;
+        let position = [cell % size_x, cell / (size_x * size_z), cell / size_x % size_z];
+        let offset = direction.offset();
+        let mut next = [0; 3];
+        for axis in 0..3 {
+            next[axis] = position[axis].checked_add_signed(offset[axis] as isize)?;
+        }
+        self.index(next)
+    }
+
+    fn cell(&self, cell: usize) -> &[u64] {
+        &self.bits[cell * self.words..(cell + 1) * self.words]
+    }
+
+    /// Tiles possible in a cell.
+    fn tiles(&self, cell: usize) -> impl Iterator<Item = TileId> + '_ {
+        self.cell(cell).iter().enumerate().flat_map(|(word, &bits)| {
+            (0..64).filter(move |bit| bits & (1 << bit) != 0).map(move |bit| (word * 64 + bit) as TileId)
+        })
+    }
+
+    fn count(&self, cell: usize) -> u32 {
+        self.cell(cell).iter().map(|bits| bits.count_ones()).sum()
+    }
+
+    /// Clears bits of a word, logging its old value if any were set.
+    fn clear_bits(&mut self, word: usize, mask: u64) -> bool {
+        let old = self.bits[word];
+        if old & mask == 0 {
+            return false;
+        }
+        self.trail.push((word, old));
+        self.bits[word] = old & !mask;
+        true
+    }
+
+    /// Keeps only the tiles of a cell that pass `keep`.
+    pub(crate) fn retain(&mut self, cell: usize, keep: impl Fn(TileId) -> bool) {
+        let removed: Vec<TileId> = self.tiles(cell).filter(|&tile| !keep(tile)).collect();
+        for tile in removed {
+            let tile = usize::from(tile);
+            self.clear_bits(cell * self.words + tile / 64, 1 << (tile % 64));
+        }
+    }
+
+    /// Intersects a cell with `allowed`; returns whether it changed.
+    fn intersect(&mut self, cell: usize, allowed: &[u64]) -> bool {
+        let mut changed = false;
+        for (offset, &allowed) in allowed.iter().enumerate() {
+            changed |= self.clear_bits(cell * self.words + offset, !allowed);
+        }
+        changed
+    }
+
+    /// Undoes every change after the first `len` logged.
+    fn undo_to(&mut self, len: usize) {
+        for (word, old) in self.trail.drain(len..).rev() {
+            self.bits[word] = old;
+        }
+    }
+}
+
+/// One collapse, kept so it can be undone.
+struct Decision {
+    /// Length of the domains' undo trail before the collapse.
+    trail_len: usize,
+    cell: usize,
+    tile: TileId,
+}
+
+/// Fills a volume of cells with tiles.
+///
+/// ```rust,ignore
+/// let tileset = Tileset::shipped_undercity();
+/// let grid = WfcSolver::new(&tileset, [8, 2, 8]).solve(seed)?;
+/// let block = grid.block(&tileset, [5, 1, 9]);
+/// ```
+pub struct WfcSolver<'a> {
+    tileset: &'a Tileset,
+    size: [usize; 3],
+    constraints: Constraints,
+    budget: u32,
+}
+
+impl<'a> WfcSolver<'a> {
+    /// Creates a solver for `size` cells with the tileset's default edges.
+    #[must_use]
+    pub fn new(tileset: &'a Tileset, size: [usize; 3]) -> Self {
+        Self {
+            tileset,
+            size,
+            constraints: Constraints::for_tileset(tileset),
+            budget: DEFAULT_BACKTRACK_BUDGET,
+        }
+    }
+
+    /// Replaces the constraints.
+    #[must_use]
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Sets how many choices may be undone before giving up.
+    #[must_use]
+    pub const fn with_backtrack_budget(mut self, budget: u32) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Solves the volume.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a pinned cell is outside the volume, no layout exists, or
+    /// the backtrack budget runs out first.
+    pub fn solve(&self, seed: WorldSeed) -> Result<WfcGrid, WfcError> {
+        let rules = self.tileset.rules();
+        let mut domains = Domains::full(self.size, rules.tile_count(), rules.words());
+        self.constraints.apply(self.tileset, &mut domains)?;
+        let cells: Vec<usize> = (0..domains.len()).collect();
+        if cells.iter().any(|&cell| domains.count(cell) == 0) || !propagate(rules, &mut domains, cells) {
+            return Err(WfcError::Unsatisfiable);
+        }
+        // Nothing before the first choice is ever undone
+        domains.trail.clear();
+
+        let mut rng = ChunkRng::new(seed, 0, 0);
+        let mut stack: Vec<Decision> = Vec::new();
+        let mut backtracks = 0;
+
+        while let Some(cell) = self.lowest_entropy(&domains, &mut rng) {
+            let tile = self.pick(&domains, cell, &mut rng);
+            stack.push(Decision {
+                trail_len: domains.trail.len(),
+                cell,
+                tile,
+            });
+            domains.retain(cell, |other| other == tile);
+            if propagate(rules, &mut domains, vec![cell]) {
+                continue;
+            }
+
+            // Undo choices until one can be avoided
+            loop {
+                let Some(decision) = stack.pop() else {
+                    return Err(WfcError::Unsatisfiable);
+                };
+                backtracks += 1;
+                if backtracks > self.budget {
+                    return Err(WfcError::BudgetExhausted(self.budget));
+                }
+                domains.undo_to(decision.trail_len);
+                domains.retain(decision.cell, |other| other != decision.tile);
+                if domains.count(decision.cell) > 0 && propagate(rules, &mut domains, vec![decision.cell]) {
+                    break;
+                }
+            }
+        }
+
+        let tiles = (0..domains.len())
+            .map(|cell| domains.tiles(cell).next().expect("solved cells have one tile"))
+            .collect();
+        Ok(WfcGrid { size: self.size, tiles })
+    }
+
+    /// The undecided cell with the lowest weighted entropy; ties are broken
+    /// at random.
+    fn lowest_entropy(&self, domains: &Domains, rng: &mut ChunkRng) -> Option<usize> {
+        let mut best: Option<(f64, usize)> = None;
+        for cell in 0..domains.len() {
+            if domains.count(cell) < 2 {
+                continue;
+            }
+            let (mut total, mut weighted_log) = (0.0, 0.0);
+            for tile in domains.tiles(cell) {
+                let weight = self.tileset.tile(tile).weight;
+                total += weight;
+                weighted_log += weight * weight.ln();
+            }
+            let entropy = total.ln() - weighted_log / total + rng.next_f64() * 1e-6;
+            if best.map_or(true, |(lowest, _)| entropy < lowest) {
+                best = Some((entropy, cell));
+            }
+        }
+        best.map(|(_, cell)| cell)
+    }
+
+    /// A weighted-random tile from a cell.
+    fn pick(&self, domains: &Domains, cell: usize, rng: &mut ChunkRng) -> TileId {
+        let weight = |tile| self.tileset.tile(tile).weight;
+        let total: f64 = domains.tiles(cell).map(weight).sum();
+        let mut roll = rng.next_f64() * total;
+        let mut last = 0;
+        for tile in domains.tiles(cell) {
+            roll -= weight(tile);
+            if roll < 0.0 {
+                return tile;
+            }
+            last = tile;
+        }
+        last
+    }
+}
+
+/// Shrinks domains until every tile has a possible neighbour on every side.
+///
+/// Returns false if a cell runs out of tiles.
+fn propagate(rules: &AdjacencyRules, domains: &mut Domains, mut pending: Vec<usize>) -> bool {
+    let mut allowed = vec![0; rules.words()];
+    while let Some(cell) = pending.pop() {
+        for direction in Direction::ALL {
+            let Some(next) = domains.neighbour(cell, direction) else {
+                continue;
+            };
+            allowed.fill(0);
+            for tile in domains.tiles(cell) {
+                for (allowed, &bits) in allowed.iter_mut().zip(rules.allowed(tile, direction)) {
+                    *allowed |= bits;
+                }
+            }
+            if domains.intersect(next, &allowed) {
+                if domains.count(next) == 0 {
+                    return false;
+                }
+                pending.push(next);
+            }
+        }
+    }
+    true
+}
+
+/// A solved volume: one tile per cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WfcGrid {
+    size: [usize; 3],
+    tiles: Vec<TileId>,
+}
+
+impl WfcGrid {
+    /// Volume size in cells.
+    #[must_use]
+    pub const fn size(&self) -> [usize; 3] {
+        self.size
+    }
+
+    /// Returns the tile in a cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is outside the volume.
+    #[must_use]
+    pub fn tile(&self, [x, y, z]: [usize; 3]) -> TileId {
+        let [size_x, size_y, size_z] = self.size;
+        assert!(x < size_x && y < size_y && z < size_z, "cell ({x}, {y}, {z}) is outside the volume");
+        self.tiles[(y * size_z + z) * size_x + x]
+    }
+
+    /// Returns the block at a position inside the volume, in blocks from
+    /// its lowest corner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside the volume.
+    #[must_use]
+    pub fn block(&self, tileset: &Tileset, [x, y, z]: [usize; 3]) -> BlockId {
+        let size = tileset.tile_size();
+        let tile = self.tile([x / size, y / size, z / size]);
+        tileset.tile(tile).block(x % size, y % size, z % size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wfc::Boundary;
+
+    /// Checks every adjacency and edge of a solved grid.
+    fn assert_valid(tileset: &Tileset, grid: &WfcGrid, boundary: &Boundary) {
+        let [size_x, size_y, size_z] = grid.size();
+        let domains = Domains::full(grid.size(), 1, 1);
+        for y in 0..size_y {
+            for z in 0..size_z {
+                for x in 0..size_x {
+                    let tile = grid.tile([x, y, z]);
+                    let index = domains.index([x, y, z]).unwrap();
+                    for direction in Direction::ALL {
+                        match domains.neighbour(index, direction) {
+                            Some(next) => {
+                                let (nx, ny, nz) = (next % size_x, next / (size_x * size_z), next / size_x % size_z);
+                                let other = grid.tile([nx, ny, nz]);
+                                assert!(tileset.rules().allows(tile, direction, other), "bad neighbours at ({x}, {y}, {z})");
+                            }
+                            None => {
+                                let allowed = boundary.allowed(direction);
+                                let socket = tileset.tile(tile).sockets.get(direction);
+                                assert!(allowed.is_empty() || allowed.iter().any(|allowed| allowed == socket));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Three tiles whose x and z rules don't commute, so no 2×2 layer is
+    /// solvable even though every tile has neighbours on every side.
+    fn twisted() -> Tileset {
+        let mut text = String::from("[tileset]\nname = \"twisted\"\ntile_size = 1\n[palette]\n\".\" = 0\n");
+        // Along x: 0→1, 1→0, 2→2. Along z: 0→0, 1→2, 2→1.
+        for (value, east, south) in [(0, 1, 0), (1, 0, 2), (2, 2, 1)] {
+            text += &format!(
+                "[[tile]]\nname = \"t{value}\"\nlayers = [[\".\"]]\nsockets = {{ \"+x\" = \"x{east}\", \"-x\" = \"x{value}\", \
+                 \"+z\" = \"z{south}\", \"-z\" = \"z{value}\", \"+y\" = \"y\", \"-y\" = \"y\" }}\n"
+            );
+        }
+        Tileset::from_toml(&text).unwrap()
+    }
+
+    #[test]
+    fn test_solution_is_valid_and_deterministic() {
+        let tileset = Tileset::shipped_undercity();
+        let solver = WfcSolver::new(&tileset, [8, 2, 8]);
+
+        let grid = solver.solve(WorldSeed::new(1)).unwrap();
+        assert_valid(&tileset, &grid, tileset.boundary());
+        assert_eq!(grid, solver.solve(WorldSeed::new(1)).unwrap());
+        assert_ne!(grid, solver.solve(WorldSeed::new(2)).unwrap());
+
+        // Street level is floored everywhere
+        for z in 0..32 {
+            for x in 0..32 {
+                assert_ne!(grid.block(&tileset, [x, 0, z]), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constraints_are_respected() {
+        let tileset = Tileset::shipped_undercity();
+        let cross = tileset.tile_id("wall_cross").unwrap();
+        let plaza = tileset.tile_id("plaza").unwrap();
+        let constraints = Constraints::for_tileset(&tileset)
+            .with_fixed([3, 0, 3], cross)
+            .with_forbidden(plaza);
+
+        let grid = WfcSolver::new(&tileset, [6, 2, 6])
+            .with_constraints(constraints.clone())
+            .solve(WorldSeed::new(9))
+            .unwrap();
+        assert_valid(&tileset, &grid, constraints.boundary());
+        assert_eq!(grid.tile([3, 0, 3]), cross);
+        assert!(grid.tiles.iter().all(|&tile| tile != plaza));
+
+        // A wall can't run off the edge, so pinning one there fails
+        let wall = tileset.tile_id("wall").unwrap();
+        let edge = Constraints::for_tileset(&tileset).with_fixed([0, 0, 0], wall);
+        assert_eq!(
+            WfcSolver::new(&tileset, [4, 2, 4]).with_constraints(edge).solve(WorldSeed::new(9)),
+            Err(WfcError::Unsatisfiable)
+        );
+        let outside = Constraints::for_tileset(&tileset).with_fixed([4, 0, 0], wall);
+        assert!(matches!(
+            WfcSolver::new(&tileset, [4, 2, 4]).with_constraints(outside).solve(WorldSeed::new(9)),
+            Err(WfcError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_undo_restores_domains() {
+        let mut domains = Domains::full([3, 1, 1], 70, 2);
+        let before = domains.bits.clone();
+        domains.retain(0, |tile| tile == 65);
+        let mark = domains.trail.len();
+        assert!(domains.intersect(1, &[0b1, 0]));
+        assert!(!domains.intersect(1, &[0b11, 0]));
+        domains.retain(2, |tile| tile < 3);
+
+        domains.undo_to(mark);
+        assert_eq!(domains.tiles(0).collect::<Vec<_>>(), [65]);
+        assert_eq!(domains.count(1), 70);
+        domains.undo_to(0);
+        assert_eq!(domains.bits, before);
+    }
+
+    #[test]
+    fn test_backtracking_is_bounded() {
+        let tileset = twisted();
+        // A single row is always solvable
+        assert!(WfcSolver::new(&tileset, [4, 1, 1]).solve(WorldSeed::new(3)).is_ok());
+
+        // A 2×2 layer only fails once a choice is made and must be undone
+        let square = WfcSolver::new(&tileset, [2, 1, 2]);
+        assert_eq!(square.solve(WorldSeed::new(3)), Err(WfcError::Unsatisfiable));
+        assert_eq!(
+            square.with_backtrack_budget(0).solve(WorldSeed::new(3)),
+            Err(WfcError::BudgetExhausted(0))
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! # Tiles
+//!
+//! Voxel tiles and the tilesets that group them, loaded from TOML files in
+//! `data/schemas/world/tilesets/`. The shipped Undercity tileset is
+//! embedded in the binary.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
+use oroboros_core::embedded::EmbeddedData;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::constraints::Boundary;
+use super::rules::{AdjacencyRules, Sockets};
+
+/// The shipped Undercity block tileset.
+const SHIPPED_UNDERCITY: &str = include_str!("../../../../data/schemas/world/tilesets/undercity.toml");
+
+/// Largest allowed tile edge, in blocks.
+const MAX_TILE_SIZE: usize = 16;
+
+/// Index of a tile in its [`Tileset`].
+pub type TileId = u16;
+
+/// Why a tileset could not be loaded.
+#[derive(Error, Debug)]
+pub enum TilesetError {
+    /// Reading the file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid TOML or is missing fields.
+    #[error("invalid tileset: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// The tileset defines no tiles.
+    #[error("tileset {0} has no tiles")]
+    NoTiles(String),
+    /// The tileset has more tiles (after rotation) than a [`TileId`] holds.
+    #[error("tileset {0} has too many tiles")]
+    TooManyTiles(String),
+    /// The tile size is zero or too large.
+    #[error("tile size {0} must be 1 to 16")]
+    TileSize(usize),
+    /// A palette key is not a single character.
+    #[error("palette symbol {0:?} must be one character")]
+    Symbol(String),
+    /// Two tiles share a name.
+    #[error("tile name {0} is used twice")]
+    DuplicateName(String),
+    /// A tile's layers, rows or row lengths don't match the tile size.
+    #[error("tile {tile} has {found} {what}; expected {expected}")]
+    Shape {
+        /// Tile name.
+        tile: String,
+        /// `"layers"`, `"rows"` or `"columns"`.
+        what: &'static str,
+        /// Count found.
+        found: usize,
+        /// The tile size.
+        expected: usize,
+    },
+    /// A tile uses a symbol the palette doesn't define.
+    #[error("tile {tile} uses symbol {symbol:?} missing from the palette")]
+    UnknownSymbol {
+        /// Tile name.
+        tile: String,
+        /// The symbol.
+        symbol: char,
+    },
+    /// A tile weight is zero, negative or not finite.
+    #[error("tile {tile} has weight {weight}; must be positive")]
+    Weight {
+        /// Tile name.
+        tile: String,
+        /// Configured weight.
+        weight: f64,
+    },
+}
+
+/// `[tileset]`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TilesetHeader {
+    /// Tileset name.
+    pub name: String,
+    /// Tile edge length, in blocks.
+    pub tile_size: usize,
+}
+
+/// One `[[tile]]` as written in the file.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TileDef {
+    /// Unique tile name.
+    pub name: String,
+    /// Relative pick frequency.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Also add the tile turned 90, 180 and 270 degrees about Y.
+    #[serde(default)]
+    pub rotate: bool,
+    /// Face sockets.
+    pub sockets: Sockets,
+    /// Palette symbols: layers bottom to top, rows -z to +z, columns -x to +x.
+    pub layers: Vec<Vec<String>>,
+}
+
+const fn default_weight() -> f64 {
+    1.0
+}
+
+/// A tileset file as written.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TilesetFile {
+    /// `[tileset]`.
+    pub tileset: TilesetHeader,
+    /// `[palette]`: symbol to block ID.
+    pub palette: HashMap<String, BlockId>,
+    /// `[boundary]`.
+    #[serde(default)]
+    pub boundary: Boundary,
+    /// `[[tile]]`, in file order.
+    #[serde(rename = "tile")]
+    pub tiles: Vec<TileDef>,
+}
+
+/// A cube of blocks with sockets on its faces.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tile {
+    /// Tile name; turned variants add `@90`, `@180` or `@270`.
+    pub name: String,
+    /// Relative pick frequency.
+    pub weight: f64,
+    /// Face sockets.
+    pub sockets: Sockets,
+    size: usize,
+    /// Indexed `(y * size + z) * size + x`.
+    blocks: Vec<BlockId>,
+}
+
+impl Tile {
+    /// Returns the block at a position inside the tile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside the tile.
+    #[must_use]
+    pub fn block(&self, x: usize, y: usize, z: usize) -> BlockId {
+        assert!(x < self.size && y < self.size && z < self.size, "({x}, {y}, {z}) is outside the tile");
+        self.blocks[(y * self.size + z) * self.size + x]
+    }
+
+    /// Returns the tile turned 90 degrees about Y (east face to south).
+    fn rotated(&self, name: String) -> Self {
+        let size = self.size;
+        let mut blocks = vec![0; self.blocks.len()];
+        for y in 0..size {
+            for z in 0..size {
+                for x in 0..size {
+                    blocks[(y * size + z) * size + x] = self.block(z, y, size - 1 - x);
+                }
+            }
+        }
+        Self {
+            name,
+            weight: self.weight,
+            sockets: self.sockets.rotated(),
+            size,
+            blocks,
+        }
+    }
+}
+
+/// Validated tiles, their adjacency rules and the default boundary.
+///
+/// ```rust,ignore
+/// let tileset = Arc::new(Tileset::load("mods/tilesets/ruins.toml")?);
+/// tileset.check_blocks(generator.blocks())?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tileset {
+    name: String,
+    tile_size: usize,
+    tiles: Vec<Tile>,
+    boundary: Boundary,
+    rules: AdjacencyRules,
+}
+
+impl Tileset {
+    /// Returns the shipped Undercity block tileset.
+    ///
+    /// Adjacency rules are derived from the tile edges once, on the first
+    /// call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a shipped tile has the wrong size, a zero weight or a
+    /// symbol missing from the palette.
+    #[must_use]
+    pub fn shipped_undercity() -> Arc<Self> {
+        static SHIPPED: EmbeddedData<Tileset, TilesetError> =
+            EmbeddedData::new("tilesets/undercity.toml", SHIPPED_UNDERCITY, Tileset::from_toml);
+        SHIPPED.get()
+    }
+
+    /// Loads a tileset from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or the tiles are invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TilesetError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a tileset in the `tilesets/*.toml` format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text can't be parsed or the tiles are invalid.
+    pub fn from_toml(text: &str) -> Result<Self, TilesetError> {
+        Self::from_file(toml::from_str(text)?)
+    }
+
+    /// Builds a tileset, adding turned variants of rotating tiles.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first invalid tile or palette entry.
+    pub fn from_file(file: TilesetFile) -> Result<Self, TilesetError> {
+        let TilesetFile { tileset, palette, boundary, tiles: defs } = file;
+        let size = tileset.tile_size;
+        if size == 0 || size > MAX_TILE_SIZE {
+            return Err(TilesetError::TileSize(size));
+        }
+        if defs.is_empty() {
+            return Err(TilesetError::NoTiles(tileset.name));
+        }
+
+        let mut symbols = HashMap::new();
+        for (key, &block) in &palette {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(symbol), None) => symbols.insert(symbol, block),
+                _ => return Err(TilesetError::Symbol(key.clone())),
+            };
+        }
+
+        let mut tiles: Vec<Tile> = Vec::new();
+        for def in defs {
+            if tiles.iter().any(|tile| tile.name == def.name) {
+                return Err(TilesetError::DuplicateName(def.name));
+            }
+            let tile = parse_tile(def.clone(), size, &symbols)?;
+            let first = tiles.len();
+            let mut turned = tile.clone();
+            tiles.push(tile);
+            if def.rotate {
+                for degrees in [90, 180, 270] {
+                    turned = turned.rotated(format!("{}@{degrees}", def.name));
+                    let repeat = tiles[first..]
+                        .iter()
+                        .any(|tile| tile.blocks == turned.blocks && tile.sockets == turned.sockets);
+                    if !repeat {
+                        tiles.push(turned.clone());
+                    }
+                }
+            }
+        }
+        if tiles.len() > usize::from(TileId::MAX) {
+            return Err(TilesetError::TooManyTiles(tileset.name));
+        }
+
+        let rules = AdjacencyRules::from_tiles(&tiles);
+        Ok(Self {
+            name: tileset.name,
+            tile_size: size,
+            tiles,
+            boundary,
+            rules,
+        })
+    }
+
+    /// Checks that every block the tiles place is defined.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first undefined block ID.
+    pub fn check_blocks(&self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        let referrer = format!("tileset {}", self.name);
+        blocks.check_ids(&referrer, self.tiles.iter().flat_map(|tile| tile.blocks.iter().copied()))
+    }
+
+    /// Returns the tileset name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the tile edge length, in blocks.
+    #[must_use]
+    pub const fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    /// Returns every tile, including turned variants.
+    #[must_use]
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Returns a tile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a tile of this set.
+    #[must_use]
+    pub fn tile(&self, id: TileId) -> &Tile {
+        &self.tiles[usize::from(id)]
+    }
+
+    /// Looks up a tile by name (`"wall"`, `"wall@90"`).
+    #[must_use]
+    pub fn tile_id(&self, name: &str) -> Option<TileId> {
+        self.tiles.iter().position(|tile| tile.name == name).map(|index| index as TileId)
+    }
+
+    /// Returns the sockets allowed at the edges of a volume by default.
+    #[must_use]
+    pub const fn boundary(&self) -> &Boundary {
+        &self.boundary
+    }
+
+    /// Returns the compiled adjacency rules.
+    #[must_use]
+    pub const fn rules(&self) -> &AdjacencyRules {
+        &self.rules
+    }
+}
+
+/// Converts a tile's symbol layers into block IDs.
+fn parse_tile(def: TileDef, size: usize, symbols: &HashMap<char, BlockId>) -> Result<Tile, TilesetError> {
+    let shape = |what, found| TilesetError::Shape {
+        tile: def.name.clone(),
+        what,
+        found,
+        expected: size,
+    };
+    if !(def.weight.is_finite() && def.weight > 0.0) {
+        return Err(TilesetError::Weight {
+            tile: def.name,
+            weight: def.weight,
+        });
+    }
+    if def.layers.len() != size {
+        return Err(shape("layers", def.layers.len()));
+    }
+
+    let mut blocks = Vec::with_capacity(size * size * size);
+    for layer in &def.layers {
+        if layer.len() != size {
+            return Err(shape("rows", layer.len()));
+        }
+        for row in layer {
+            if row.chars().count() != size {
+                return Err(shape("columns", row.chars().count()));
+            }
+            for symbol in row.chars() {
+                let Some(&block) = symbols.get(&symbol) else {
+                    return Err(TilesetError::UnknownSymbol {
+                        tile: def.name.clone(),
+                        symbol,
+                    });
+                };
+                blocks.push(block);
+            }
+        }
+    }
+
+    Ok(Tile {
+        name: def.name,
+        weight: def.weight,
+        sockets: def.sockets,
+        size,
+        blocks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wfc::Direction;
+
+    #[test]
+    fn test_shipped_tileset() {
+        let tileset = Tileset::shipped_undercity();
+        assert_eq!(tileset.name(), "undercity");
+        assert_eq!(tileset.tile_size(), 4);
+        tileset.check_blocks(&BlockRegistry::shipped()).unwrap();
+
+        // Straight walls look the same turned 180 degrees; crosses at every turn
+        assert!(tileset.tile_id("wall@90").is_some());
+        assert!(tileset.tile_id("wall@180").is_none());
+        assert!(tileset.tile_id("wall_end@270").is_some());
+        assert!(tileset.tile_id("wall_cross@90").is_none());
+    }
+
+    #[test]
+    fn test_rotation_turns_blocks_with_sockets() {
+        let tileset = Tileset::shipped_undercity();
+        let end = tileset.tile(tileset.tile_id("wall_end").unwrap());
+        let turned = tileset.tile(tileset.tile_id("wall_end@90").unwrap());
+        let wall = 33;
+
+        assert_eq!(end.sockets.get(Direction::PosX), "wall");
+        assert_eq!(turned.sockets.get(Direction::PosZ), "wall");
+        assert_eq!(turned.sockets.get(Direction::PosX), "open");
+        // The wall reaches the east face before turning and the south face after
+        assert_eq!(end.block(3, 1, 1), wall);
+        assert_eq!(turned.block(1, 1, 3), wall);
+        assert_eq!(turned.block(3, 1, 1), 0);
+    }
+
+    #[test]
+    fn test_invalid_tilesets() {
+        let tileset = |tile: &str| {
+            format!(
+                "[tileset]\nname = \"test\"\ntile_size = 2\n[palette]\n\".\" = 0\n[[tile]]\nname = \"a\"\n\
+                 sockets = {{ \"+x\" = \"s\", \"-x\" = \"s\", \"+y\" = \"s\", \"-y\" = \"s\", \"+z\" = \"s\", \"-z\" = \"s\" }}\n{tile}"
+            )
+        };
+
+        assert!(Tileset::from_toml(&tileset("layers = [[\"..\", \"..\"], [\"..\", \"..\"]]")).is_ok());
+        assert!(matches!(
+            Tileset::from_toml(&tileset("layers = [[\"..\", \"..\"]]")),
+            Err(TilesetError::Shape { what: "layers", found: 1, .. })
+        ));
+        assert!(matches!(
+            Tileset::from_toml(&tileset("layers = [[\"..\", \"...\"], [\"..\", \"..\"]]")),
+            Err(TilesetError::Shape { what: "columns", found: 3, .. })
+        ));
+        assert!(matches!(
+            Tileset::from_toml(&tileset("layers = [[\"..\", \".#\"], [\"..\", \"..\"]]")),
+            Err(TilesetError::UnknownSymbol { symbol: '#', .. })
+        ));
+        assert!(matches!(
+            Tileset::from_toml(&tileset("weight = 0.0\nlayers = [[\"..\", \"..\"], [\"..\", \"..\"]]")),
+            Err(TilesetError::Weight { .. })
+        ));
+    }
+}
@@ -8,6 +8,7 @@
 //! - Loads chunks within 10-chunk radius
 //! - Unloads chunks beyond 12-chunk radius
 //! - Integrates with WAL for modified chunk persistence
+//! - Flushes modified chunks to region files in the background on unload
 //!
 //! ## Performance
 //!
@@ -16,6 +17,7 @@
 //! - Pre-allocated chunk pool to avoid allocations in hot path
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
 use std::path::PathBuf;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
@@ -27,8 +29,10 @@ use std::time::Instant;
 #[cfg(target_arch = "wasm32")]
 use web_time::Instant;
 
-use crate::chunk::{Chunk, ChunkCoord, ChunkGenerator, CHUNK_SIZE};
+use crate::chunk::{Chunk, ChunkCoord, ChunkGenerator, CHUNK_HEIGHT, CHUNK_SIZE};
+use crate::generator::{WorldGenerator, WorldMetadata};
 use crate::noise::WorldSeed;
+use crate::region::{RegionError, RegionFlusher, RegionStore, StorageMode, StoredChunk};
 
 // =============================================================================
 // ASYNC CHUNK GENERATION - SMOOTH ALPHA
@@ -108,6 +112,8 @@ pub struct WorldStats {
     pub generated_this_session: usize,
     /// Number of modified chunks saved.
     pub saved_modified_chunks: usize,
+    /// Number of unloaded chunks waiting to be written to disk.
+    pub pending_saves: usize,
 }
 
 /// Modification record for chunk persistence.
@@ -154,7 +160,7 @@ struct ChunkResult {
 /// Uses async thread pool for non-blocking generation.
 pub struct WorldManager {
     /// Chunk generator for procedural terrain (shared with workers).
-    generator: Arc<ChunkGenerator>,
+    generator: Arc<dyn WorldGenerator>,
     /// Configuration.
     config: WorldManagerConfig,
     /// Currently loaded chunks.
@@ -170,6 +176,10 @@ pub struct WorldManager {
     player_chunk: ChunkCoord,
     /// Modification log for persistence.
     modification_log: HashMap<ChunkCoord, Vec<ChunkModification>>,
+    /// Background writer for region files, if the world is saved.
+    storage: Option<RegionFlusher>,
+    /// What the region files keep of each chunk.
+    storage_mode: StorageMode,
     /// Statistics.
     stats: WorldStats,
     /// Current server tick (for modification timestamps).
@@ -183,7 +193,7 @@ pub struct WorldManager {
 }
 
 impl WorldManager {
-    /// Creates a new world manager with async worker thread.
+    /// Creates a new Undercity world manager with async worker thread.
     /// On WASM, runs synchronously on main thread (no threading support).
     ///
     /// # Arguments
@@ -192,13 +202,46 @@ impl WorldManager {
     /// * `config` - World manager configuration
     #[must_use]
     pub fn new(seed: WorldSeed, config: WorldManagerConfig) -> Self {
+        Self::with_generator(Arc::new(ChunkGenerator::new(seed)), config)
+    }
+
+    /// Opens the world saved under `config.world_save_path`, or creates it
+    /// with `default` if it has no metadata yet.
+    ///
+    /// A saved world always uses its recorded generator, seed and sea
+    /// level; `default` only applies to new worlds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metadata can't be read or written, or names
+    /// a generator that isn't a built-in preset or has changed version.
+    pub fn open_or_create(config: WorldManagerConfig, default: &WorldMetadata) -> io::Result<Self> {
+        let path = config.world_save_path.join(WorldMetadata::FILE_NAME);
+        let metadata = if path.exists() {
+            WorldMetadata::load(&path)?
+        } else {
+            std::fs::create_dir_all(&config.world_save_path)?;
+            default.save(&path)?;
+            default.clone()
+        };
+
+        let generator = metadata.build_generator()?;
+        let store = RegionStore::new(&config.world_save_path);
+        Ok(Self::with_generator(Arc::new(generator), config).with_storage(store, StorageMode::Overlay))
+    }
+
+    /// Creates a world manager using any generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `generator` - Chunk source, shared with the worker thread
+    /// * `config` - World manager configuration
+    #[must_use]
+    pub fn with_generator(generator: Arc<dyn WorldGenerator>, config: WorldManagerConfig) -> Self {
         // Create channels for async communication
         let (work_sender, work_receiver) = channel::<ChunkRequest>();
         let (result_sender, result_receiver) = channel::<ChunkResult>();
         
-        // Shared generator for worker thread
-        let generator = Arc::new(ChunkGenerator::new(seed));
-        
         // WASM: No threading support - chunks generated synchronously in flush_generation_queue
         // Native: Spawn dedicated worker thread for background generation
         #[cfg(not(target_arch = "wasm32"))]
@@ -253,6 +296,8 @@ impl WorldManager {
             in_flight: HashSet::with_capacity(64),
             player_chunk: ChunkCoord::new(i32::MAX, i32::MAX),
             modification_log: HashMap::new(),
+            storage: None,
+            storage_mode: StorageMode::default(),
             stats: WorldStats::default(),
             current_tick: 0,
             work_sender,
@@ -266,6 +311,23 @@ impl WorldManager {
         Self::new(seed, WorldManagerConfig::default())
     }
 
+    /// Saves modified chunks to region files in `store` when they unload,
+    /// and restores them from there when they load again.
+    ///
+    /// Without storage, modifications stay in memory for the session.
+    #[must_use]
+    pub fn with_storage(mut self, store: RegionStore, mode: StorageMode) -> Self {
+        self.storage = Some(RegionFlusher::new(Arc::new(store)));
+        self.storage_mode = mode;
+        self
+    }
+
+    /// Returns the generator settings to save with this world.
+    #[must_use]
+    pub fn metadata(&self) -> WorldMetadata {
+        WorldMetadata::of(self.generator.as_ref())
+    }
+
     /// Returns the current statistics.
     #[must_use]
     pub fn stats(&self) -> &WorldStats {
@@ -384,8 +446,12 @@ impl WorldManager {
             .copied()
             .collect();
         
-        for coord in to_unload {
-            self.unload_chunk(coord);
+        let saves: Vec<(ChunkCoord, StoredChunk)> = to_unload
+            .into_iter()
+            .filter_map(|coord| self.unload_chunk(coord))
+            .collect();
+        if let Some(storage) = &mut self.storage {
+            storage.submit(saves);
         }
     }
 
@@ -402,20 +468,12 @@ impl WorldManager {
             // 1. RECEIVE: Collect any completed chunks from worker (non-blocking)
             while let Ok(result) = self.result_receiver.try_recv() {
                 self.in_flight.remove(&result.coord);
-                
-                // Apply any saved modifications
-                let mut chunk = result.chunk;
-                if let Some(modifications) = self.modification_log.get(&result.coord) {
-                    for m in modifications {
-                        chunk.set_block(
-                            m.local_x as usize,
-                            m.y as usize,
-                            m.local_z as usize,
-                            crate::chunk::Block::new(m.block_id),
-                        );
-                    }
+                if self.loaded_chunks.contains_key(&result.coord) {
+                    continue; // Loaded synchronously meanwhile
                 }
                 
+                // Apply any saved modifications
+                let chunk = self.restore(result.coord, result.chunk);
                 self.loaded_chunks.insert(result.coord, chunk);
                 generated += 1;
                 self.stats.generated_this_session += 1;
@@ -464,20 +522,10 @@ impl WorldManager {
                 self.queued_set.remove(&coord);
                 
                 // Generate synchronously
-                let mut chunk = self.generator.generate(coord);
+                let chunk = self.generator.generate(coord);
                 
                 // Apply any saved modifications
-                if let Some(modifications) = self.modification_log.get(&coord) {
-                    for m in modifications {
-                        chunk.set_block(
-                            m.local_x as usize,
-                            m.y as usize,
-                            m.local_z as usize,
-                            crate::chunk::Block::new(m.block_id),
-                        );
-                    }
-                }
-                
+                let chunk = self.restore(coord, chunk);
                 self.loaded_chunks.insert(coord, chunk);
                 generated += 1;
                 processed += 1;
@@ -485,6 +533,9 @@ impl WorldManager {
             }
         }
         
+        // Collect finished region writes
+        self.poll_storage();
+
         // Update stats
         self.stats.loaded_chunks = self.loaded_chunks.len();
         self.stats.pending_chunks = self.generation_queue.len() + self.in_flight.len();
@@ -492,16 +543,107 @@ impl WorldManager {
         generated
     }
 
-    /// Unloads a chunk, saving if modified.
-    fn unload_chunk(&mut self, coord: ChunkCoord) {
-        if let Some(chunk) = self.loaded_chunks.remove(&coord) {
-            if chunk.modified {
-                // Chunk was modified - we would save to WAL here
-                self.stats.saved_modified_chunks += 1;
-                // Note: Actual WAL integration would be done via callback
+    /// Unloads a chunk, returning what to save if it was modified and the
+    /// world has storage.
+    fn unload_chunk(&mut self, coord: ChunkCoord) -> Option<(ChunkCoord, StoredChunk)> {
+        let chunk = self.loaded_chunks.remove(&coord)?;
+        self.stats.unloaded_this_session += 1;
+        self.storage.as_ref()?;
+
+        // Saved chunks are restored from disk, so the log can drop them
+        let modifications = self.modification_log.remove(&coord)?;
+        let stored = match self.storage_mode {
+            StorageMode::Overlay => StoredChunk::Overlay(compact(modifications)),
+            StorageMode::Full => StoredChunk::Full(Box::new(chunk)),
+        };
+        Some((coord, stored))
+    }
+
+    /// Applies a chunk's saved and logged modifications to its freshly
+    /// generated blocks.
+    ///
+    /// Saved data moves into the modification log, so the chunk is saved
+    /// again in full when it next unloads.
+    fn restore(&mut self, coord: ChunkCoord, mut chunk: Chunk) -> Chunk {
+        if let Some(storage) = &self.storage {
+            // Chunks still being flushed are newer than the disk
+            let stored = match storage.pending(coord) {
+                Some(stored) => Some(Arc::clone(stored)),
+                None => storage.store().load(coord).unwrap_or_else(|err| {
+                    eprintln!("[REGION] Failed to load chunk [{},{}], regenerating: {err}", coord.x, coord.z);
+                    None
+                }).map(Arc::new),
+            };
+
+            let saved = match stored.as_deref() {
+                Some(StoredChunk::Overlay(modifications)) => modifications.clone(),
+                Some(StoredChunk::Full(saved)) if self.storage_mode == StorageMode::Full => {
+                    chunk = saved.as_ref().clone();
+                    Vec::new()
+                }
+                // Keep only what differs from the terrain
+                Some(StoredChunk::Full(saved)) => diff(&chunk, saved),
+                None => Vec::new(),
+            };
+            if stored.is_some() {
+                let log = self.modification_log.entry(coord).or_default();
+                log.splice(0..0, saved);
+            }
+        }
+
+        if let Some(modifications) = self.modification_log.get(&coord) {
+            for m in modifications {
+                chunk.set_block(
+                    m.local_x as usize,
+                    m.y as usize,
+                    m.local_z as usize,
+                    crate::chunk::Block::new(m.block_id),
+                );
             }
-            self.stats.unloaded_this_session += 1;
         }
+        chunk
+    }
+
+    /// Collects finished region writes into the stats.
+    fn poll_storage(&mut self) {
+        if let Some(storage) = &mut self.storage {
+            self.stats.saved_modified_chunks += storage.poll();
+            self.stats.pending_saves = storage.pending_count();
+        }
+    }
+
+    /// Writes every modified chunk, loaded or not, to region files and
+    /// waits until they are on disk. Call before shutdown.
+    ///
+    /// Returns the number of chunks written; zero without storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a region can't be written. The chunks stay
+    /// queued, and the next call retries them.
+    pub fn save_all(&mut self) -> Result<usize, RegionError> {
+        let Some(storage) = &mut self.storage else {
+            return Ok(0);
+        };
+
+        let saves: Vec<(ChunkCoord, StoredChunk)> = self
+            .modification_log
+            .iter()
+            .filter_map(|(coord, modifications)| {
+                let stored = match self.storage_mode {
+                    StorageMode::Overlay => StoredChunk::Overlay(compact(modifications.clone())),
+                    StorageMode::Full => StoredChunk::Full(Box::new(self.loaded_chunks.get(coord)?.clone())),
+                };
+                Some((*coord, stored))
+            })
+            .collect();
+        storage.submit(saves);
+
+        let written = storage.flush();
+        self.stats.pending_saves = storage.pending_count();
+        let written = written?;
+        self.stats.saved_modified_chunks += written;
+        Ok(written)
     }
 
     /// Gets a loaded chunk at the given coordinate.
@@ -589,8 +731,11 @@ impl WorldManager {
         {
             while let Ok(result) = self.result_receiver.try_recv() {
                 self.in_flight.remove(&result.coord);
-                self.loaded_chunks.insert(result.coord, result.chunk);
-                self.stats.generated_this_session += 1;
+                if !self.loaded_chunks.contains_key(&result.coord) {
+                    let chunk = self.restore(result.coord, result.chunk);
+                    self.loaded_chunks.insert(result.coord, chunk);
+                    self.stats.generated_this_session += 1;
+                }
             }
         }
         
@@ -598,18 +743,8 @@ impl WorldManager {
         while let Some(coord) = self.generation_queue.pop_front() {
             self.queued_set.remove(&coord);
             
-            let mut chunk = self.generator.generate(coord);
-            if let Some(modifications) = self.modification_log.get(&coord) {
-                for m in modifications {
-                    chunk.set_block(
-                        m.local_x as usize,
-                        m.y as usize,
-                        m.local_z as usize,
-                        crate::chunk::Block::new(m.block_id),
-                    );
-                }
-            }
-            
+            let chunk = self.generator.generate(coord);
+            let chunk = self.restore(coord, chunk);
             self.loaded_chunks.insert(coord, chunk);
             self.stats.generated_this_session += 1;
         }
@@ -621,12 +756,16 @@ impl WorldManager {
             while !self.in_flight.is_empty() {
                 if let Ok(result) = self.result_receiver.recv() {
                     self.in_flight.remove(&result.coord);
-                    self.loaded_chunks.insert(result.coord, result.chunk);
-                    self.stats.generated_this_session += 1;
+                    if !self.loaded_chunks.contains_key(&result.coord) {
+                        let chunk = self.restore(result.coord, result.chunk);
+                        self.loaded_chunks.insert(result.coord, chunk);
+                        self.stats.generated_this_session += 1;
+                    }
                 }
             }
         }
         
+        self.poll_storage();
         self.stats.loaded_chunks = self.loaded_chunks.len();
         self.stats.pending_chunks = 0;
     }
@@ -695,20 +834,10 @@ impl WorldManager {
                 
                 if !self.loaded_chunks.contains_key(&coord) {
                     // Direct generation (blocking) for spawn area only
-                    let mut chunk = self.generator.generate(coord);
+                    let chunk = self.generator.generate(coord);
                     
                     // Apply any saved modifications
-                    if let Some(modifications) = self.modification_log.get(&coord) {
-                        for m in modifications {
-                            chunk.set_block(
-                                m.local_x as usize,
-                                m.y as usize,
-                                m.local_z as usize,
-                                crate::chunk::Block::new(m.block_id),
-                            );
-                        }
-                    }
-                    
+                    let chunk = self.restore(coord, chunk);
                     self.loaded_chunks.insert(coord, chunk);
                     self.stats.generated_this_session += 1;
                     loaded += 1;
@@ -721,6 +850,40 @@ impl WorldManager {
     }
 }
 
+/// Drops modifications overwritten later at the same block.
+fn compact(modifications: Vec<ChunkModification>) -> Vec<ChunkModification> {
+    let mut seen = HashSet::with_capacity(modifications.len());
+    let mut kept: Vec<ChunkModification> = modifications
+        .into_iter()
+        .rev()
+        .filter(|m| seen.insert((m.local_x, m.y, m.local_z)))
+        .collect();
+    kept.reverse();
+    kept
+}
+
+/// Modifications turning `generated` into `saved`.
+fn diff(generated: &Chunk, saved: &Chunk) -> Vec<ChunkModification> {
+    let mut modifications = Vec::new();
+    for y in 0..CHUNK_HEIGHT {
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let block = saved.get_block(x, y, z);
+                if block.id != generated.get_block(x, y, z).id {
+                    modifications.push(ChunkModification {
+                        local_x: x as u8,
+                        y: y as u8,
+                        local_z: z as u8,
+                        block_id: block.id,
+                        tick: 0,
+                    });
+                }
+            }
+        }
+    }
+    modifications
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -919,4 +1082,62 @@ mod tests {
         // Origin should still work
         assert!(manager.has_ground(0, 100, 0));
     }
+
+    #[test]
+    fn test_saved_world_keeps_its_generator() {
+        use crate::generator::GeneratorPreset;
+
+        let dir = std::env::temp_dir().join(format!("oroboros_world_meta_{}", std::process::id()));
+        let config = WorldManagerConfig {
+            world_save_path: dir.clone(),
+            ..WorldManagerConfig::test()
+        };
+
+        let created = WorldMetadata::new(WorldSeed::new(7), GeneratorPreset::Flat);
+        let mut manager = WorldManager::open_or_create(config.clone(), &created).unwrap();
+        assert_eq!(manager.metadata(), created);
+        manager.ensure_loaded_around(0.0, 0.0, 0);
+        assert_eq!(manager.get_block(3, 1, 3).map(|block| block.id), Some(crate::chunk::Block::CONCRETE_WALL.id));
+
+        // Reopening ignores the new default
+        let other = WorldMetadata::new(WorldSeed::new(8), GeneratorPreset::Undercity);
+        let reopened = WorldManager::open_or_create(config, &other).unwrap();
+        assert_eq!(reopened.metadata(), created);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unloaded_modifications_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("oroboros_world_regions_{}", std::process::id()));
+        let open = |mode| {
+            WorldManager::new(WorldSeed::new(5), WorldManagerConfig::test()).with_storage(RegionStore::new(&dir), mode)
+        };
+
+        for mode in [StorageMode::Overlay, StorageMode::Full] {
+            let mut manager = open(mode);
+            manager.ensure_loaded_around(0.0, 0.0, 1);
+            assert!(manager.set_block(5, 200, 5, 42));
+            assert!(manager.set_block(5, 200, 5, 43));
+
+            // Walking away unloads and flushes the chunk in the background
+            manager.update(0.0, 0.0);
+            manager.update(1000.0, 1000.0);
+            assert!(manager.get_chunk(ChunkCoord::new(0, 0)).is_none());
+            assert!(manager.export_modifications().iter().all(|entry| entry.coord != ChunkCoord::new(0, 0)));
+
+            // Coming back before the write lands still sees the change
+            manager.ensure_loaded_around(0.0, 0.0, 0);
+            assert_eq!(manager.get_block(5, 200, 5).map(|block| block.id), Some(43));
+            assert!(manager.save_all().unwrap() >= 1);
+            assert_eq!(manager.stats().pending_saves, 0);
+            drop(manager);
+
+            let mut restarted = open(mode);
+            restarted.ensure_loaded_around(0.0, 0.0, 0);
+            assert_eq!(restarted.get_block(5, 200, 5).map(|block| block.id), Some(43));
+            std::fs::remove_dir_all(&dir).ok();
+        }
+    }
+
 }
@@ -4,7 +4,7 @@
 //! Grid architecture with rooms, walls, bridges, and hazard zones.
 
 use oroboros_procedural::{
-    ChunkCoord, ChunkGenerator, WorldSeed, BiomeClassifier,
+    Block, ChunkCoord, ChunkGenerator, WorldSeed, BiomeClassifier,
 };
 
 /// Test: Verify terrain has flat walkable areas.
@@ -113,11 +113,11 @@ fn test_chunks_have_brutalist_structures() {
                     for x in 0..16 {
                         let block = chunk.get_block(x, y, z);
                         match block.id {
-                            1 => total_concrete_floor += 1,
-                            2 => { total_concrete_wall += 1; chunk_walls += 1; }
-                            3 => total_hazard_neon += 1,
-                            5 => total_bedrock += 1,
-                            7 => { total_metal_bridge += 1; chunk_bridges += 1; }
+                            id if id == Block::CONCRETE_FLOOR.id => total_concrete_floor += 1,
+                            id if id == Block::CONCRETE_WALL.id => { total_concrete_wall += 1; chunk_walls += 1; }
+                            id if id == Block::HAZARD_NEON.id => total_hazard_neon += 1,
+                            id if id == Block::BEDROCK.id => total_bedrock += 1,
+                            id if id == Block::METAL_BRIDGE.id => { total_metal_bridge += 1; chunk_bridges += 1; }
                             _ => {}
                         }
                     }
@@ -200,20 +200,20 @@ fn test_maze_structure() {
                     for x in 0..16 {
                     // Check floor level (Y=4)
                     let floor_block = chunk.get_block(x, 4, z);
-                    if floor_block.id == 1 { floor_found += 1; }
+                    if floor_block == Block::CONCRETE_FLOOR { floor_found += 1; }
                     
-                    // Check for any metal (ID 7) at any Y level
+                    // Check for any metal at any Y level
                     for y in 0..50 {
-                        if chunk.get_block(x, y, z).id == 7 { 
+                        if chunk.get_block(x, y, z) == Block::METAL_BRIDGE { 
                             metal_found += 1; 
                                             }
                                         }
                                         
                     // Measure wall heights
-                    if floor_block.id == 2 {
+                    if floor_block == Block::CONCRETE_WALL {
                         let mut height = 0;
                         for y in 4..50 {
-                            if chunk.get_block(x, y, z).id == 2 {
+                            if chunk.get_block(x, y, z) == Block::CONCRETE_WALL {
                                 height += 1;
                             } else {
                                 break;
@@ -50,7 +50,7 @@ impl<'a> WorldReader for CoreWorldReader<'a> {
         let world = &**self.handle;
 
         // Iterate over the PV (Position+Velocity) table
-        let pv_table = &world.pv_table;
+        let pv_table = world.pv_table();
 
         for idx in 0..pv_table.len() {
             if let (Some(pos), Some(vel)) = (
@@ -74,7 +74,7 @@ impl<'a> WorldReader for CoreWorldReader<'a> {
         let world = &**self.handle;
 
         // Iterate over P-only (Position only) table for static entities
-        let p_table = &world.p_table;
+        let p_table = world.p_table();
 
         for idx in 0..p_table.len() {
             if let Some(pos) = p_table.get_position(idx) {
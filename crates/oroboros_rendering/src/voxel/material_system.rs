@@ -16,6 +16,7 @@
 //! Total materials possible: 65,536 (enough for 3 worlds + expansion)
 
 use bytemuck::{Pod, Zeroable};
+use oroboros_core::blocks::{BlockConfigError, BlockId, BlockRegistry};
 use std::collections::HashMap;
 
 /// Maximum materials in global registry.
@@ -149,6 +150,37 @@ impl MaterialDef {
         self.flags = (self.flags & 0x0000FFFF) | world_mask;
         self
     }
+    
+    /// Returns the colour to draw with when only one colour fits (vertex
+    /// colours): the emission for emissive materials, else the base colour,
+    /// with the alpha of transparent materials.
+    #[must_use]
+    pub fn vertex_color(&self) -> [f32; 4] {
+        let [r, g, b, _] = if self.flags & Self::FLAG_EMISSIVE != 0 {
+            self.emission_metallic
+        } else {
+            self.color_roughness
+        };
+        let alpha = if self.flags & Self::FLAG_TRANSPARENT != 0 { self.emission_metallic[3] } else { 1.0 };
+        [r, g, b, alpha]
+    }
+    
+    /// Returns the metallic value (0 for transparent materials, which
+    /// store alpha there).
+    #[must_use]
+    pub fn metallic(&self) -> f32 {
+        if self.flags & Self::FLAG_TRANSPARENT != 0 {
+            0.0
+        } else {
+            self.emission_metallic[3]
+        }
+    }
+    
+    /// Returns the roughness.
+    #[must_use]
+    pub fn roughness(&self) -> f32 {
+        self.color_roughness[3]
+    }
 }
 
 /// Per-chunk local palette.
@@ -285,6 +317,8 @@ pub struct MaterialRegistry {
     materials: Vec<MaterialDef>,
     /// Name to ID mapping.
     name_to_id: HashMap<String, MaterialId>,
+    /// Material of each block ID, filled by `bind_blocks`.
+    block_materials: Vec<MaterialId>,
     /// Dirty flag for GPU sync.
     dirty: bool,
 }
@@ -296,6 +330,7 @@ impl MaterialRegistry {
         let mut registry = Self {
             materials: vec![MaterialDef::default(); MAX_GLOBAL_MATERIALS],
             name_to_id: HashMap::new(),
+            block_materials: Vec::new(),
             dirty: true,
         };
         
@@ -342,6 +377,24 @@ impl MaterialRegistry {
         registry.register_at(3004, "hellfire_crystal", MaterialDef::neon(1.0, 0.1, 0.0, 15.0, 4.0)
             .for_world(MaterialDef::WORLD_INFERNO));
         
+        // Block materials (IDs 4000-4999), named by data/schemas/world/blocks.toml
+        registry.register_at(4000, "leaves", MaterialDef::solid(0.18, 0.42, 0.15, 0.95));
+        registry.register_at(4001, "bedrock", MaterialDef::solid(0.06, 0.06, 0.06, 0.9));
+        registry.register_at(4002, "water", MaterialDef::transparent(0.15, 0.35, 0.7, 0.6));
+        registry.register_at(4003, "sand", MaterialDef::solid(0.85, 0.78, 0.55, 0.95));
+        registry.register_at(4004, "jungle_grass", MaterialDef::solid(0.1, 0.5, 0.1, 0.95));
+        registry.register_at(4005, "frozen_dirt", MaterialDef::solid(0.45, 0.4, 0.38, 0.9));
+        registry.register_at(4006, "snow", MaterialDef::solid(0.95, 0.96, 1.0, 0.7));
+        registry.register_at(4007, "mud", MaterialDef::solid(0.3, 0.22, 0.12, 0.6));
+        registry.register_at(4008, "red_sand", MaterialDef::solid(0.75, 0.35, 0.15, 0.95));
+        registry.register_at(4009, "iron_ore", MaterialDef::solid(0.55, 0.45, 0.4, 0.7));
+        registry.register_at(4010, "gold_ore", MaterialDef::metal(0.9, 0.75, 0.3, 0.4));
+        registry.register_at(4011, "diamond_ore", MaterialDef::solid(0.5, 0.85, 0.9, 0.3));
+        registry.register_at(4012, "concrete", MaterialDef::solid(0.314, 0.314, 0.314, 0.9));
+        registry.register_at(4013, "concrete_dark", MaterialDef::solid(0.188, 0.188, 0.188, 0.85));
+        registry.register_at(4014, "hazard_red", MaterialDef::neon(1.0, 0.0, 0.0, 5.0, 0.0));
+        registry.register_at(4015, "goal_white", MaterialDef::neon(0.8, 1.0, 1.0, 5.0, 0.0));
+        
         // =====================================================
         // PROCEDURAL MODEL MATERIALS (IDs 10-99)
         // Used by ProceduralModels for code-generated assets
@@ -393,6 +446,35 @@ impl MaterialRegistry {
         self.name_to_id.get(name).copied()
     }
     
+    /// Resolves every block's material.
+    ///
+    /// After this, [`block_material`](Self::block_material) maps block IDs
+    /// to materials.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a block names a material that isn't registered.
+    pub fn bind_blocks(&mut self, blocks: &BlockRegistry) -> Result<(), BlockConfigError> {
+        blocks.check_materials(|name| self.name_to_id.contains_key(name))?;
+
+        let len = blocks.blocks().iter().map(|def| usize::from(def.id) + 1).max().unwrap_or(0);
+        self.block_materials = vec![MaterialId::AIR; len];
+        for def in blocks.blocks() {
+            if let Some(material) = &def.material {
+                self.block_materials[usize::from(def.id)] = self.name_to_id[material];
+            }
+        }
+        Ok(())
+    }
+    
+    /// Gets the material a block is drawn with (air if it isn't drawn or
+    /// blocks aren't bound).
+    #[inline]
+    #[must_use]
+    pub fn block_material(&self, block: BlockId) -> MaterialId {
+        self.block_materials.get(usize::from(block)).copied().unwrap_or(MaterialId::AIR)
+    }
+    
     /// Gets material definition by ID.
     #[must_use]
     pub fn get(&self, id: MaterialId) -> &MaterialDef {
@@ -467,6 +549,43 @@ mod tests {
         assert!(stone.color_roughness[0] > 0.0);
     }
     
+    #[test]
+    fn test_vertex_color() {
+        let registry = MaterialRegistry::new();
+        let get = |name: &str| *registry.get(registry.get_id(name).unwrap());
+        
+        assert_eq!(get("concrete").vertex_color(), [0.314, 0.314, 0.314, 1.0]);
+        // Emissive materials draw with their emission
+        assert_eq!(get("hazard_red").vertex_color(), [5.0, 0.0, 0.0, 1.0]);
+        assert_eq!(get("water").vertex_color()[3], 0.6);
+        assert_eq!(get("water").metallic(), 0.0);
+        assert_eq!(get("chrome").metallic(), 1.0);
+        assert_eq!(get("chrome").roughness(), 0.1);
+    }
+    
+    #[test]
+    fn test_bind_blocks() {
+        let blocks = BlockRegistry::shipped();
+        let mut registry = MaterialRegistry::new();
+        assert_eq!(registry.block_material(2), MaterialId::AIR);
+        
+        registry.bind_blocks(&blocks).unwrap();
+        let stone = blocks.id("stone").unwrap();
+        assert_eq!(registry.block_material(stone), registry.get_id("stone").unwrap());
+        assert_eq!(registry.block_material(0), MaterialId::AIR);
+        assert_eq!(registry.block_material(u16::MAX), MaterialId::AIR);
+        for def in blocks.blocks().iter().filter(|def| def.material.is_some()) {
+            assert_ne!(registry.block_material(def.id), MaterialId::AIR, "{} has no material", def.name);
+        }
+        
+        let mut missing = MaterialRegistry::new();
+        missing.name_to_id.remove("stone");
+        assert!(matches!(
+            missing.bind_blocks(&blocks),
+            Err(BlockConfigError::UnknownMaterial { material, .. }) if material == "stone"
+        ));
+    }
+    
     #[test]
     fn test_memory_budget() {
         // Global registry: 65K materials × 64 bytes = 4MB
@@ -37,7 +37,8 @@ pub use compressed::{
 
 use bytemuck::{Pod, Zeroable};
 use oroboros_core::Position;
-use oroboros_networking::protocol::{PlayerInput, EntityState, DragonState};
+use oroboros_networking::protocol::{PlayerInput, EntityState, DragonState, WorldSnapshot};
+use oroboros_networking::spectator::SnapshotSource;
 use std::io::{self, Read, Write};
 
 /// Magic number for replay files.
@@ -233,7 +234,7 @@ impl ReplayFrame {
         if pos + 16 > data.len() {
             return None;
         }
-        let dragon: DragonState = *bytemuck::from_bytes(&data[pos..pos+16]);
+        let dragon: DragonState = bytemuck::pod_read_unaligned(&data[pos..pos+16]);
         pos += 16;
 
         // Inputs
@@ -250,7 +251,7 @@ impl ReplayFrame {
 
         let mut inputs = Vec::with_capacity(input_count);
         for _ in 0..input_count {
-            let record: InputRecord = *bytemuck::from_bytes(&data[pos..pos+input_size]);
+            let record: InputRecord = bytemuck::pod_read_unaligned(&data[pos..pos+input_size]);
             inputs.push(record);
             pos += input_size;
         }
@@ -269,7 +270,7 @@ impl ReplayFrame {
 
         let mut entities = Vec::with_capacity(entity_count);
         for _ in 0..entity_count {
-            let entity: EntityState = *bytemuck::from_bytes(&data[pos..pos+entity_size]);
+            let entity: EntityState = bytemuck::pod_read_unaligned(&data[pos..pos+entity_size]);
             entities.push(entity);
             pos += entity_size;
         }
@@ -556,6 +557,25 @@ impl ReplayPlayer {
     }
 }
 
+/// Casts a replay to spectators (see `ReplayCaster`).
+///
+/// Yields the current frame and advances; a paused replay repeats its
+/// frame. Entities past `WorldSnapshot::MAX_ENTITIES` are dropped.
+impl SnapshotSource for ReplayPlayer {
+    fn next_snapshot(&mut self) -> Option<WorldSnapshot> {
+        let frame = self.current_frame()?;
+        let mut snapshot = WorldSnapshot::empty(frame.tick);
+        snapshot.dragon = frame.dragon;
+        for entity in &frame.entities {
+            if !snapshot.add_entity(*entity) {
+                break;
+            }
+        }
+        self.next_frame();
+        Some(snapshot)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -635,4 +655,62 @@ mod tests {
         assert_eq!(player.total_frames(), 5);
         assert_eq!(player.header().tick_rate, 60);
     }
+
+    fn cast_positions(config: oroboros_networking::spectator::SpectatorConfig) -> Vec<f32> {
+        use oroboros_networking::protocol::{Packet, PacketDeserializer, PacketHeader, PacketSerializer, SpectateRequest};
+        use oroboros_networking::server::NetworkCommand;
+        use oroboros_networking::spectator::ReplayCaster;
+
+        let mut recorder = ReplayRecorder::new(60);
+        recorder.start();
+        for tick in 0..10 {
+            recorder.begin_frame(tick);
+            recorder.record_entity(EntityState { entity_id: 7, pos_x: tick as f32, ..EntityState::default() });
+        }
+        recorder.stop();
+        let mut buffer = Vec::new();
+        recorder.write(&mut buffer).unwrap();
+        let player = ReplayPlayer::load(&mut std::io::Cursor::new(buffer)).unwrap();
+
+        let mut caster = ReplayCaster::new(player, config, 60);
+        let addr = "10.2.0.1:6000".parse().unwrap();
+        let mut serializer = PacketSerializer::new();
+        assert!(serializer.serialize_spectate(&PacketHeader::new(0, 0, 0), &SpectateRequest::follow(7)));
+        caster.receive(addr, serializer.as_slice());
+
+        let mut positions = Vec::new();
+        while !caster.is_finished() {
+            caster.tick();
+            while let Some(command) = caster.poll_command() {
+                if let NetworkCommand::Send { data, len, .. } = command {
+                    if let Ok(Packet::Snapshot(_, snapshot)) = PacketDeserializer::new(&data[..len]).deserialize() {
+                        positions.push(snapshot.entities()[0].pos_x);
+                    }
+                }
+            }
+        }
+
+        // Nothing more once finished
+        caster.tick();
+        assert!(caster.poll_command().is_none());
+        positions
+    }
+
+    #[test]
+    fn test_cast_to_spectators() {
+        let positions = cast_positions(oroboros_networking::spectator::SpectatorConfig::default());
+        assert_eq!(positions, (0..10).map(|t| t as f32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_delayed_cast_plays_every_frame_once() {
+        use oroboros_networking::spectator::SpectatorConfig;
+
+        let config = SpectatorConfig {
+            delay: std::time::Duration::from_millis(50),
+            ..SpectatorConfig::default()
+        };
+        let positions = cast_positions(config);
+        assert_eq!(positions, (0..10).map(|t| t as f32).collect::<Vec<_>>());
+    }
 }
@@ -77,6 +77,16 @@ impl Vec3 {
     pub fn distance_squared(self, other: Self) -> f32 {
         (self - other).length_squared()
     }
+
+    /// Cross product
+    #[must_use]
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
 }
 
 impl std::ops::Add for Vec3 {
@@ -150,6 +160,35 @@ impl Quaternion {
 
     /// Identity rotation
     pub const IDENTITY: Self = Self::new(0.0, 0.0, 0.0, 1.0);
+
+    /// Rotation of `angle` radians around a unit `axis`
+    #[must_use]
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Rotates a vector
+    #[must_use]
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        // v + 2w(q × v) + 2(q × (q × v))
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+}
+
+impl std::ops::Mul for Quaternion {
+    type Output = Self;
+    /// Applies `rhs` first, then `self`
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        )
+    }
 }
 
 impl Default for Quaternion {
@@ -179,6 +218,23 @@ impl Transform {
 
     /// Identity transform
     pub const IDENTITY: Self = Self::new(Vec3::ZERO, Quaternion::IDENTITY, 1.0);
+
+    /// Maps a point from this transform's local space to its parent's
+    #[must_use]
+    pub fn transform_point(self, point: Vec3) -> Vec3 {
+        self.position + self.rotation.rotate(point * self.scale)
+    }
+
+    /// Composes `child`, expressed in this transform's space, into this
+    /// transform's parent space
+    #[must_use]
+    pub fn mul_transform(self, child: Self) -> Self {
+        Self {
+            position: self.transform_point(child.position),
+            scale: self.scale * child.scale,
+            rotation: self.rotation * child.rotation,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +255,20 @@ mod tests {
         assert_eq!(dot, 32.0); // 1*4 + 2*5 + 3*6
     }
 
+    #[test]
+    fn test_transform_composition() {
+        let quarter_turn = Quaternion::from_axis_angle(Vec3::Y, std::f32::consts::FRAC_PI_2);
+        let parent = Transform::new(Vec3::new(10.0, 0.0, 0.0), quarter_turn, 2.0);
+        let child = Transform::new(Vec3::new(1.0, 0.0, 0.0), quarter_turn, 0.5);
+
+        // +X rotated a quarter turn about +Y points to -Z
+        let global = parent.mul_transform(child);
+        assert!(global.position.distance(Vec3::new(10.0, 0.0, -2.0)) < 1e-5);
+        assert!((global.scale - 1.0).abs() < 1e-6);
+        assert!(global.rotation.rotate(Vec3::X).distance(Vec3::new(-1.0, 0.0, 0.0)) < 1e-5);
+        assert_eq!(Transform::IDENTITY.mul_transform(child), child);
+    }
+
     #[test]
     fn test_vec3_bytemuck() {
         let v = Vec3::new(1.0, 2.0, 3.0);